@@ -101,6 +101,65 @@ pub struct CompletionResponse {
     pub choice: rig::OneOrMany<rig::message::AssistantContent>,
     pub finish_reason: FinishReason,
     pub output_tokens: u64,
+    #[serde(default)]
+    pub input_tokens: u64,
+}
+
+/// Tokens spent on one completion
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Usage {
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Change of the LLM settings for one thread, on top of the handler's `LLMConfig`. Fields
+/// left unset keep their current value.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct LLMConfigUpdate {
+    pub provider: Option<LLMProvider>,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub max_tokens: Option<u64>,
+}
+
+impl LLMConfigUpdate {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply `update` over these settings
+    pub fn merge(&mut self, update: &LLMConfigUpdate) {
+        if update.provider.is_some() {
+            self.provider = update.provider;
+            // the previous model belongs to the previous provider
+            self.model = None;
+        }
+        if update.model.is_some() {
+            self.model = update.model.clone();
+        }
+        self.temperature = update.temperature.or(self.temperature);
+        self.max_tokens = update.max_tokens.or(self.max_tokens);
+    }
+}
+
+impl std::fmt::Display for LLMConfigUpdate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(provider) = self.provider {
+            parts.push(format!("provider {}", provider.name()));
+        }
+        if let Some(model) = &self.model {
+            parts.push(format!("model {}", model));
+        }
+        if let Some(temperature) = self.temperature {
+            parts.push(format!("temperature {}", temperature));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            parts.push(format!("max tokens {}", max_tokens));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
 }
 
 impl CompletionResponse {
@@ -269,7 +328,8 @@ fn jitter_ms(base_ms: u64) -> u64 {
 }
 
 /// Factory for LLM Clients
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LLMProvider {
     Anthropic,
     Gemini,
@@ -331,6 +391,22 @@ impl LLMProvider {
     }
 }
 
+impl std::str::FromStr for LLMProvider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "anthropic" => Ok(LLMProvider::Anthropic),
+            "gemini" => Ok(LLMProvider::Gemini),
+            "openrouter" => Ok(LLMProvider::OpenRouter),
+            _ => Err(format!(
+                "unknown provider {}, expected anthropic, gemini or openrouter",
+                s
+            )),
+        }
+    }
+}
+
 impl LLMClient for rig::providers::anthropic::Client {
     async fn completion(&self, completion: Completion) -> eyre::Result<CompletionResponse> {
         let model = self.completion_model(&completion.model);
@@ -347,6 +423,7 @@ impl LLMClient for rig::providers::anthropic::Client {
                 choice: response.choice,
                 finish_reason,
                 output_tokens: response.raw_response.usage.output_tokens,
+                input_tokens: response.usage.input_tokens,
             }
         });
         result.map_err(Into::into)
@@ -394,6 +471,7 @@ impl LLMClient for rig::providers::gemini::Client {
                 choice: response.choice,
                 finish_reason,
                 output_tokens,
+                input_tokens: response.usage.input_tokens,
             }
         });
         result.map_err(Into::into)
@@ -429,6 +507,7 @@ impl LLMClient for rig::providers::openrouter::Client {
                 choice: response.choice,
                 finish_reason,
                 output_tokens,
+                input_tokens: response.usage.input_tokens,
             }
         });
         result.map_err(Into::into)
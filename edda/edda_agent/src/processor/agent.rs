@@ -1,4 +1,5 @@
-use crate::llm::CompletionResponse;
+use super::plan::Plan;
+use crate::llm::{CompletionResponse, LLMConfigUpdate, Usage};
 use edda_mq::{Aggregate, Event as MQEvent};
 use eyre::Result;
 use rig::message::{ToolCall, ToolResult, UserContent};
@@ -10,6 +11,11 @@ pub enum Command<T> {
     PutUserMessage {
         content: rig::OneOrMany<rig::message::UserContent>,
     },
+    /// New instruction from the user while the agent is working, delivered before its
+    /// next LLM turn
+    Interrupt {
+        content: rig::OneOrMany<rig::message::UserContent>,
+    },
     PutToolCalls {
         calls: Vec<ToolCall>,
     },
@@ -19,6 +25,28 @@ pub enum Command<T> {
     PutToolResults {
         results: Vec<ToolResult>,
     },
+    /// Create the plan, or replace it if there is one
+    PutPlan {
+        tasks: Vec<String>,
+    },
+    CompleteTask {
+        index: usize,
+    },
+    /// Hold tool calls until the user decides on them
+    RequestApproval {
+        calls: Vec<ToolCall>,
+    },
+    ResolveApproval {
+        call_id: String,
+        decision: ApprovalDecision,
+    },
+    RecordUsage {
+        usage: Usage,
+    },
+    /// Switch provider, model or sampling settings for the following turns
+    UpdateLLMConfig {
+        update: LLMConfigUpdate,
+    },
     Shutdown,
     Agent(T),
 }
@@ -28,6 +56,10 @@ pub enum Event<T> {
     UserCompletion {
         content: rig::OneOrMany<rig::message::UserContent>,
     },
+    /// Held until the pending tool calls are done, then sent along with their results
+    UserInterrupted {
+        content: rig::OneOrMany<rig::message::UserContent>,
+    },
     ToolCalls {
         calls: Vec<ToolCall>,
     },
@@ -37,10 +69,49 @@ pub enum Event<T> {
     ToolResults {
         results: Vec<ToolResult>,
     },
+    PlanCreated {
+        tasks: Vec<String>,
+    },
+    PlanUpdated {
+        tasks: Vec<String>,
+    },
+    TaskCompleted {
+        index: usize,
+    },
+    ApprovalRequired {
+        call: ToolCall,
+    },
+    /// `call` carries the arguments to run with, edited ones included
+    ApprovalResolved {
+        call: ToolCall,
+        decision: ApprovalDecision,
+    },
+    UsageRecorded {
+        usage: Usage,
+    },
+    LLMConfigUpdated {
+        update: LLMConfigUpdate,
+    },
     Shutdown,
     Agent(T),
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ApprovalDecision {
+    Approve,
+    Reject,
+    /// Run the call with these arguments instead
+    Edit {
+        arguments: serde_json::Value,
+    },
+}
+
+impl ApprovalDecision {
+    pub fn is_approved(&self) -> bool {
+        !matches!(self, ApprovalDecision::Reject)
+    }
+}
+
 impl<T: MQEvent> MQEvent for Event<T> {
     fn event_version(&self) -> String {
         match self {
@@ -53,9 +124,17 @@ impl<T: MQEvent> MQEvent for Event<T> {
     fn event_type(&self) -> String {
         match self {
             Event::UserCompletion { .. } => "user.completion".to_owned(),
+            Event::UserInterrupted { .. } => "user.interrupted".to_owned(),
             Event::ToolCalls { .. } => "tool.calls".to_owned(),
             Event::AgentCompletion { .. } => "agent.completion".to_owned(),
             Event::ToolResults { .. } => "tool.results".to_owned(),
+            Event::PlanCreated { .. } => "plan.created".to_owned(),
+            Event::PlanUpdated { .. } => "plan.updated".to_owned(),
+            Event::TaskCompleted { .. } => "task.completed".to_owned(),
+            Event::ApprovalRequired { .. } => "approval.required".to_owned(),
+            Event::ApprovalResolved { .. } => "approval.resolved".to_owned(),
+            Event::UsageRecorded { .. } => "usage.recorded".to_owned(),
+            Event::LLMConfigUpdated { .. } => "llm_config.updated".to_owned(),
             Event::Shutdown => "shutdown".to_owned(),
             Event::Agent(inner) => inner.event_type(),
         }
@@ -88,6 +167,13 @@ pub struct AgentState<A: Agent> {
     pub agent: A,
     pub calls: HashMap<String, Option<ToolResult>>,
     pub messages: Vec<rig::message::Message>,
+    pub plan: Plan,
+    /// Tool calls waiting for the user's decision, by call id
+    pub approvals: HashMap<String, ToolCall>,
+    /// Instructions from interrupts not yet sent to the LLM
+    pub interrupt: Vec<UserContent>,
+    /// LLM settings of this thread that differ from the handler's
+    pub llm: LLMConfigUpdate,
 }
 
 impl<A: Agent> AgentState<A> {
@@ -112,11 +198,34 @@ impl<A: Agent> AgentState<A> {
 
     pub fn results_to_user(&self, incoming: &[ToolResult]) -> Event<A::AgentEvent> {
         let completed = self.merge_tool_results(incoming);
-        let content = completed.into_iter().map(UserContent::ToolResult);
+        let content = completed
+            .into_iter()
+            .map(UserContent::ToolResult)
+            .chain(self.interrupt.iter().cloned());
         let content = rig::OneOrMany::many(content).unwrap();
         Event::UserCompletion { content }
     }
 
+    /// The LLM is answering or tool calls are pending, so a user message has to wait
+    pub fn is_working(&self) -> bool {
+        let awaiting_llm = matches!(
+            self.messages.last(),
+            Some(rig::message::Message::User { .. })
+        );
+        awaiting_llm || !self.all_tools_ready()
+    }
+
+    pub fn shared_interrupt(
+        &self,
+        content: &rig::OneOrMany<UserContent>,
+    ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
+        let content = content.clone();
+        match self.is_working() {
+            true => Ok(vec![Event::UserInterrupted { content }]),
+            false => Ok(vec![Event::UserCompletion { content }]),
+        }
+    }
+
     pub fn shared_put_user(
         &self,
         content: &rig::OneOrMany<UserContent>,
@@ -136,8 +245,14 @@ impl<A: Agent> AgentState<A> {
         let mut events = vec![Event::AgentCompletion {
             response: response.clone(),
         }];
-        if let Some(calls) = response.tool_calls() {
-            events.push(Event::ToolCalls { calls });
+        match response.tool_calls() {
+            Some(calls) => events.push(Event::ToolCalls { calls }),
+            // the turn is over, an interrupt that came during it starts the next one
+            None => {
+                if let Ok(content) = rig::OneOrMany::many(self.interrupt.iter().cloned()) {
+                    events.push(Event::UserCompletion { content });
+                }
+            }
         }
         Ok(events)
     }
@@ -154,6 +269,70 @@ impl<A: Agent> AgentState<A> {
         }])
     }
 
+    pub fn shared_put_plan(
+        &self,
+        tasks: &[String],
+    ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
+        let tasks = tasks.to_vec();
+        match self.plan.is_empty() {
+            true => Ok(vec![Event::PlanCreated { tasks }]),
+            false => Ok(vec![Event::PlanUpdated { tasks }]),
+        }
+    }
+
+    pub fn shared_complete_task(
+        &self,
+        index: usize,
+    ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
+        if index >= self.plan.tasks.len() {
+            return Err(Error::UnknownTask(index).into());
+        }
+        Ok(vec![Event::TaskCompleted { index }])
+    }
+
+    pub fn shared_resolve_approval(
+        &self,
+        call_id: &str,
+        decision: ApprovalDecision,
+    ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
+        let Some(call) = self.approvals.get(call_id) else {
+            return Err(Error::NoPendingApproval(call_id.to_owned()).into());
+        };
+        let mut call = call.clone();
+        if let ApprovalDecision::Edit { arguments } = &decision {
+            call.function.arguments = arguments.clone();
+        }
+        Ok(vec![Event::ApprovalResolved { call, decision }])
+    }
+
+    pub fn shared_update_llm_config(
+        &self,
+        update: &LLMConfigUpdate,
+    ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
+        if update.is_empty() {
+            return Err(Error::InvalidLLMConfig("nothing to change".to_owned()).into());
+        }
+        if update
+            .model
+            .as_ref()
+            .is_some_and(|model| model.trim().is_empty())
+        {
+            return Err(Error::InvalidLLMConfig("empty model name".to_owned()).into());
+        }
+        if let Some(temperature) = update.temperature
+            && !(0.0..=2.0).contains(&temperature)
+        {
+            let reason = format!("temperature {} is outside 0 to 2", temperature);
+            return Err(Error::InvalidLLMConfig(reason).into());
+        }
+        if update.max_tokens == Some(0) {
+            return Err(Error::InvalidLLMConfig("max tokens must be positive".to_owned()).into());
+        }
+        Ok(vec![Event::LLMConfigUpdated {
+            update: update.clone(),
+        }])
+    }
+
     #[allow(unused)]
     pub async fn handle_shared(
         &self,
@@ -162,6 +341,7 @@ impl<A: Agent> AgentState<A> {
     ) -> Result<Vec<Event<A::AgentEvent>>, AgentError<A::AgentError>> {
         match cmd {
             Command::PutUserMessage { content } => self.shared_put_user(&content),
+            Command::Interrupt { content } => self.shared_interrupt(&content),
             Command::PutToolCalls { calls } => Ok(vec![Event::ToolCalls { calls }]),
             Command::PutCompletion { response } => self.shared_put_completion(&response),
             Command::PutToolResults { results } => {
@@ -171,6 +351,17 @@ impl<A: Agent> AgentState<A> {
                 }
                 Ok(events)
             }
+            Command::PutPlan { tasks } => self.shared_put_plan(&tasks),
+            Command::CompleteTask { index } => self.shared_complete_task(index),
+            Command::RequestApproval { calls } => Ok(calls
+                .into_iter()
+                .map(|call| Event::ApprovalRequired { call })
+                .collect()),
+            Command::ResolveApproval { call_id, decision } => {
+                self.shared_resolve_approval(&call_id, decision)
+            }
+            Command::RecordUsage { usage } => Ok(vec![Event::UsageRecorded { usage }]),
+            Command::UpdateLLMConfig { update } => self.shared_update_llm_config(&update),
             Command::Shutdown => Ok(vec![Event::Shutdown]),
             _ => Ok(vec![]),
         }
@@ -183,6 +374,10 @@ impl<A: Agent> AgentState<A> {
                     content: content.clone(),
                 });
                 self.calls.clear();
+                self.interrupt.clear();
+            }
+            Event::UserInterrupted { content } => {
+                self.interrupt.extend(content);
             }
             Event::ToolCalls { calls } => {
                 for call in calls {
@@ -197,6 +392,16 @@ impl<A: Agent> AgentState<A> {
                     self.calls.insert(result.id.clone(), Some(result));
                 }
             }
+            Event::PlanCreated { .. } | Event::PlanUpdated { .. } | Event::TaskCompleted { .. } => {
+                self.plan.apply(&event);
+            }
+            Event::ApprovalRequired { call } => {
+                self.approvals.insert(call.id.clone(), call);
+            }
+            Event::ApprovalResolved { call, .. } => {
+                self.approvals.remove(&call.id);
+            }
+            Event::LLMConfigUpdated { update } => self.llm.merge(&update),
             _ => {}
         }
     }
@@ -230,6 +435,12 @@ pub enum Error {
     NotReady,
     #[error("Unexpected tool result with id: {0}")]
     UnexpectedTool(String),
+    #[error("No task with index {0} in the plan")]
+    UnknownTask(usize),
+    #[error("No tool call with id {0} is waiting for approval")]
+    NoPendingApproval(String),
+    #[error("Invalid LLM config: {0}")]
+    InvalidLLMConfig(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -1,10 +1,12 @@
 use super::agent::{Agent, AgentState, Command, Event};
 use crate::toolbox::ToolCallExt;
 use edda_integrations::{
-    DatabricksDescribeTableArgs, DatabricksExecuteQueryArgs, DatabricksListCatalogsArgs,
-    DatabricksListSchemasArgs, DatabricksListTablesArgs, DatabricksRestClient,
-    DescribeTableRequest, ExecuteSqlRequest, ListSchemasRequest, ListTablesRequest,
-    ToolResultDisplay,
+    DatabricksDescribeTableArgs, DatabricksExecuteQueryArgs, DatabricksExportDashboardQueriesArgs,
+    DatabricksGenieAskArgs, DatabricksListCatalogsArgs, DatabricksListDashboardsArgs,
+    DatabricksListSchemasArgs, DatabricksListServingEndpointsArgs, DatabricksListTablesArgs,
+    DatabricksListWarehousesArgs, DatabricksQueryServingEndpointArgs, DatabricksRestClient,
+    DescribeTableRequest, ExecuteSqlOptions, ExecuteSqlRequest, GenieAskRequest,
+    ListSchemasRequest, ListTablesRequest, ToolResultDisplay,
 };
 use edda_mq::{Envelope, EventHandler, EventStore, Handler};
 use edda_sandbox::FutureBoxed;
@@ -13,6 +15,7 @@ use rig::message::{ToolCall, ToolResult};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FinishDelegationArgs {
@@ -348,6 +351,14 @@ impl DatabricksTool for DatabricksExecuteQuery {
                         "type": "string",
                         "description": "SQL SELECT query to execute",
                     },
+                    "max_rows": {
+                        "type": "integer",
+                        "description": "Maximum number of rows to fetch (default: 100). Larger results are fetched in chunks.",
+                    },
+                    "timeout": {
+                        "type": "integer",
+                        "description": "Timeout in seconds; the query is cancelled when exceeded",
+                    },
                 },
                 "required": ["query"],
             }),
@@ -367,13 +378,278 @@ impl DatabricksTool for DatabricksExecuteQuery {
         let request = ExecuteSqlRequest {
             query: args.query.clone(),
         };
-        match client.execute_sql(&request).await {
+        let options = ExecuteSqlOptions {
+            max_rows: args.max_rows,
+            timeout: args.timeout.map(Duration::from_secs),
+        };
+        let result = client.execute_sql_with_options(&request, &options).await;
+        match result {
             Ok(result) => Ok(Ok(result.display())),
             Err(e) => Ok(Err(format!("Failed to execute query: {}", e))),
         }
     }
 }
 
+pub struct DatabricksListWarehouses;
+
+impl DatabricksTool for DatabricksListWarehouses {
+    type Args = DatabricksListWarehousesArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_list_warehouses".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "List SQL warehouses with their state and size".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": [],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        _args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        match client.list_warehouses().await {
+            Ok(result) => Ok(Ok(result.display())),
+            Err(e) => Ok(Err(format!("Failed to list warehouses: {}", e))),
+        }
+    }
+}
+
+pub struct DatabricksGenieAsk;
+
+impl DatabricksTool for DatabricksGenieAsk {
+    type Args = DatabricksGenieAskArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_genie_ask".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "Ask a Genie space a natural-language question. Genie writes and runs the SQL and returns the query with its result. Useful to find the right tables and joins before writing queries yourself.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "question": {
+                        "type": "string",
+                        "description": "Natural-language question about the data",
+                    },
+                    "space_id": {
+                        "type": "string",
+                        "description": "Genie space ID (optional, defaults to DATABRICKS_GENIE_SPACE_ID)",
+                    },
+                    "conversation_id": {
+                        "type": "string",
+                        "description": "Conversation ID from a previous answer to ask a follow-up question (optional)",
+                    }
+                },
+                "required": ["question"],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        let request = GenieAskRequest {
+            question: args.question,
+            space_id: args.space_id,
+            conversation_id: args.conversation_id,
+        };
+        match client.genie_ask(&request).await {
+            Ok(result) => Ok(Ok(result.display())),
+            Err(e) => Ok(Err(format!("Genie query failed: {}", e))),
+        }
+    }
+}
+
+pub struct DatabricksListDashboards;
+
+impl DatabricksTool for DatabricksListDashboards {
+    type Args = DatabricksListDashboardsArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_list_dashboards".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "List Lakeview dashboards with their IDs and workspace paths".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": [],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        _args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        match client.list_dashboards().await {
+            Ok(result) => Ok(Ok(result.display())),
+            Err(e) => Ok(Err(format!("Failed to list dashboards: {}", e))),
+        }
+    }
+}
+
+pub struct DatabricksExportDashboardQueries;
+
+impl DatabricksTool for DatabricksExportDashboardQueries {
+    type Args = DatabricksExportDashboardQueriesArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_export_dashboard_queries".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "Export the SQL queries behind each dataset of a Lakeview dashboard"
+                .to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dashboard_id": {
+                        "type": "string",
+                        "description": "Dashboard ID from databricks_list_dashboards",
+                    }
+                },
+                "required": ["dashboard_id"],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        match client.export_dashboard_queries(&args.dashboard_id).await {
+            Ok(result) => Ok(Ok(result.display())),
+            Err(e) => Ok(Err(format!(
+                "Failed to export queries of dashboard '{}': {}",
+                args.dashboard_id, e
+            ))),
+        }
+    }
+}
+
+pub struct DatabricksListServingEndpoints;
+
+impl DatabricksTool for DatabricksListServingEndpoints {
+    type Args = DatabricksListServingEndpointsArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_list_serving_endpoints".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "List Model Serving endpoints with their state, task and served models"
+                .to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": [],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        _args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        match client.list_serving_endpoints().await {
+            Ok(result) => Ok(Ok(result.display())),
+            Err(e) => Ok(Err(format!("Failed to list serving endpoints: {}", e))),
+        }
+    }
+}
+
+pub struct DatabricksQueryServingEndpoint;
+
+impl DatabricksTool for DatabricksQueryServingEndpoint {
+    type Args = DatabricksQueryServingEndpointArgs;
+    type Output = String;
+    type Error = String;
+
+    fn name(&self) -> String {
+        "databricks_query_serving_endpoint".to_string()
+    }
+
+    fn definition(&self) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: DatabricksTool::name(self),
+            description: "Query a Model Serving endpoint. Call without payload to get the expected request schema first.".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the serving endpoint",
+                    },
+                    "payload": {
+                        "type": "object",
+                        "description": "JSON request payload. Omit to get the endpoint's request schema.",
+                    },
+                },
+                "required": ["name"],
+            }),
+        }
+    }
+
+    async fn call(
+        &self,
+        args: Self::Args,
+        client: &DatabricksRestClient,
+    ) -> Result<Result<Self::Output, Self::Error>> {
+        let result = match &args.payload {
+            Some(payload) => client
+                .query_serving_endpoint(&args.name, payload)
+                .await
+                .map(|r| r.display()),
+            None => client
+                .get_serving_endpoint_schema(&args.name)
+                .await
+                .map(|r| r.display()),
+        };
+        match result {
+            Ok(text) => Ok(Ok(text)),
+            Err(e) => Ok(Err(format!(
+                "Failed to query serving endpoint '{}': {}",
+                args.name, e
+            ))),
+        }
+    }
+}
+
 pub struct FinishDelegation;
 
 impl DatabricksTool for FinishDelegation {
@@ -485,6 +761,12 @@ pub fn toolbox() -> Vec<Box<dyn DatabricksToolDyn>> {
         Box::new(DatabricksListTables),
         Box::new(DatabricksDescribeTable),
         Box::new(DatabricksExecuteQuery),
+        Box::new(DatabricksGenieAsk),
+        Box::new(DatabricksListWarehouses),
+        Box::new(DatabricksListDashboards),
+        Box::new(DatabricksExportDashboardQueries),
+        Box::new(DatabricksListServingEndpoints),
+        Box::new(DatabricksQueryServingEndpoint),
         Box::new(FinishDelegation),
     ];
     tools
@@ -1,5 +1,5 @@
 use super::agent::{Agent, AgentState, Command, Event};
-use crate::llm::{Completion, CompletionResponse, LLMClientDyn};
+use crate::llm::{Completion, CompletionResponse, LLMClientDyn, LLMConfigUpdate, Usage};
 use edda_mq::{Envelope, EventHandler, EventStore, Handler};
 use eyre::{OptionExt, Result};
 use rig::completion::ToolDefinition;
@@ -35,22 +35,38 @@ impl LLMHandler {
         Self { llm, config }
     }
 
+    /// Model name used for a thread with the given overrides
+    fn model(&self, overrides: &LLMConfigUpdate) -> String {
+        match (&overrides.model, overrides.provider) {
+            (Some(model), _) => model.clone(),
+            (None, Some(provider)) => provider.default_model().to_string(),
+            (None, None) => self.config.model.clone(),
+        }
+    }
+
     async fn handle_completion(
         &self,
         mut history: Vec<rig::completion::Message>,
+        overrides: &LLMConfigUpdate,
     ) -> Result<CompletionResponse> {
         let message = history.pop().ok_or_eyre("No messages")?;
-        let mut completion = Completion::new(self.config.model.clone(), message)
+        let mut completion = Completion::new(self.model(overrides), message)
             .history(history)
-            .temperature(self.config.temperature)
-            .max_tokens(self.config.max_tokens);
+            .temperature(overrides.temperature.unwrap_or(self.config.temperature))
+            .max_tokens(overrides.max_tokens.unwrap_or(self.config.max_tokens));
         if let Some(preamble) = &self.config.preamble {
             completion = completion.preamble(preamble.clone());
         }
         if let Some(ref tools) = self.config.tools {
             completion = completion.tools(tools.clone());
         }
-        self.llm.completion(completion).await
+        match overrides.provider {
+            Some(provider) => {
+                let llm = provider.client_from_env().map_err(|e| eyre::eyre!(e))?;
+                llm.completion(completion).await
+            }
+            None => self.llm.completion(completion).await,
+        }
     }
 }
 
@@ -62,7 +78,14 @@ impl<A: Agent, ES: EventStore> EventHandler<AgentState<A>, ES> for LLMHandler {
     ) -> Result<()> {
         if let Event::UserCompletion { .. } = &event.data {
             let aggregate = handler.load_aggregate(&event.aggregate_id).await?;
-            let response = self.handle_completion(aggregate.messages).await?;
+            let response = self
+                .handle_completion(aggregate.messages, &aggregate.llm)
+                .await?;
+            let usage = Usage {
+                model: self.model(&aggregate.llm),
+                input_tokens: response.input_tokens,
+                output_tokens: response.output_tokens,
+            };
             handler
                 .execute_with_metadata(
                     &event.aggregate_id,
@@ -70,6 +93,13 @@ impl<A: Agent, ES: EventStore> EventHandler<AgentState<A>, ES> for LLMHandler {
                     event.metadata.clone(),
                 )
                 .await?;
+            handler
+                .execute_with_metadata(
+                    &event.aggregate_id,
+                    Command::RecordUsage { usage },
+                    event.metadata.clone(),
+                )
+                .await?;
         }
         Ok(())
     }
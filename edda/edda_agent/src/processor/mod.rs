@@ -3,6 +3,7 @@ pub mod databricks;
 pub mod finish;
 pub mod link;
 pub mod llm;
+pub mod plan;
 pub mod sandbox;
 pub mod tools;
 pub mod utils;
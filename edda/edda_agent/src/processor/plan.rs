@@ -0,0 +1,162 @@
+use super::agent::{Agent, AgentState, Command, Event};
+use crate::toolbox::ToolCallExt;
+use edda_mq::{Envelope, EventHandler, EventStore, Handler};
+use eyre::Result;
+use rig::completion::ToolDefinition;
+use serde::{Deserialize, Serialize};
+
+pub const PLAN_TOOL: &str = "update_plan";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanTask {
+    pub description: String,
+    pub done: bool,
+}
+
+/// Task checklist of an agent, folded from its plan events
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plan {
+    pub tasks: Vec<PlanTask>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Index of the task being worked on: the first one not done
+    pub fn current(&self) -> Option<usize> {
+        self.tasks.iter().position(|task| !task.done)
+    }
+
+    pub fn apply<T>(&mut self, event: &Event<T>) {
+        match event {
+            Event::PlanCreated { tasks } => {
+                self.tasks = tasks
+                    .iter()
+                    .map(|description| PlanTask {
+                        description: description.clone(),
+                        done: false,
+                    })
+                    .collect();
+            }
+            // tasks kept from the previous plan stay done
+            Event::PlanUpdated { tasks } => {
+                self.tasks = tasks
+                    .iter()
+                    .map(|description| PlanTask {
+                        description: description.clone(),
+                        done: self
+                            .tasks
+                            .iter()
+                            .any(|task| task.done && task.description == *description),
+                    })
+                    .collect();
+            }
+            Event::TaskCompleted { index } => {
+                if let Some(task) = self.tasks.get_mut(*index) {
+                    task.done = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePlanArgs {
+    /// Full list of tasks, replacing the current plan
+    #[serde(default)]
+    pub tasks: Option<Vec<String>>,
+    /// Indexes of tasks that are finished
+    #[serde(default)]
+    pub completed: Vec<usize>,
+}
+
+pub fn plan_tool_definition() -> ToolDefinition {
+    ToolDefinition {
+        name: PLAN_TOOL.to_string(),
+        description: "Record the plan for the current request as a list of tasks, and mark tasks as completed once they are done. Pass `tasks` to create or replace the plan, `completed` with zero-based task indexes to check them off.".to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "tasks": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "All tasks of the plan, in execution order"
+                },
+                "completed": {
+                    "type": "array",
+                    "items": {"type": "integer", "minimum": 0},
+                    "description": "Indexes of the tasks that are now done"
+                }
+            },
+            "required": []
+        }),
+    }
+}
+
+/// Turns `update_plan` tool calls into plan events, so plan progress is part of the
+/// event stream instead of only living in tool arguments.
+pub struct PlanHandler;
+
+impl PlanHandler {
+    async fn update<A: Agent, ES: EventStore>(
+        handler: &Handler<AgentState<A>, ES>,
+        envelope: &Envelope<AgentState<A>>,
+        args: UpdatePlanArgs,
+    ) -> Result<serde_json::Value> {
+        if let Some(tasks) = args.tasks {
+            handler
+                .execute_with_metadata(
+                    &envelope.aggregate_id,
+                    Command::PutPlan { tasks },
+                    envelope.metadata.clone(),
+                )
+                .await?;
+        }
+        for index in args.completed {
+            handler
+                .execute_with_metadata(
+                    &envelope.aggregate_id,
+                    Command::CompleteTask { index },
+                    envelope.metadata.clone(),
+                )
+                .await?;
+        }
+        let state = handler.load_aggregate(&envelope.aggregate_id).await?;
+        Ok(serde_json::to_value(&state.plan)?)
+    }
+}
+
+impl<A: Agent, ES: EventStore> EventHandler<AgentState<A>, ES> for PlanHandler {
+    async fn process(
+        &mut self,
+        handler: &Handler<AgentState<A>, ES>,
+        envelope: &Envelope<AgentState<A>>,
+    ) -> Result<()> {
+        let Event::ToolCalls { calls } = &envelope.data else {
+            return Ok(());
+        };
+        let mut results = Vec::new();
+        for call in calls.iter().filter(|call| call.function.name == PLAN_TOOL) {
+            let result = match serde_json::from_value(call.function.arguments.clone()) {
+                Ok(args) => Self::update(handler, envelope, args)
+                    .await
+                    .map_err(|e| serde_json::json!(e.to_string())),
+                Err(e) => Err(serde_json::json!(e.to_string())),
+            };
+            results.push(call.to_result(result));
+        }
+        if !results.is_empty() {
+            handler
+                .execute_with_metadata(
+                    &envelope.aggregate_id,
+                    Command::PutToolResults { results },
+                    envelope.metadata.clone(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -56,6 +56,8 @@ pub struct ToolHandler {
     tools: Vec<Box<dyn ToolDyn>>,
     dagger: SandboxHandle,
     config: TemplateConfig,
+    /// Tools whose calls wait for the user's approval
+    approval: Vec<String>,
 }
 
 impl ToolHandler {
@@ -68,9 +70,21 @@ impl ToolHandler {
             tools,
             dagger,
             config,
+            approval: Vec::new(),
         }
     }
 
+    /// Hold calls of `tools` (e.g. "bash", "write_file") as `ApprovalRequired` events
+    /// until the user approves, rejects or edits them.
+    pub fn with_approval<I: IntoIterator<Item = S>, S: Into<String>>(mut self, tools: I) -> Self {
+        self.approval = tools.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn needs_approval(&self, call: &ToolCall) -> bool {
+        self.approval.contains(&call.function.name)
+    }
+
     async fn run_tools(&self, aggregate_id: &str, calls: &[ToolCall]) -> Result<Vec<ToolResult>> {
         let mut sandbox = match self.dagger.get(aggregate_id).await? {
             Some(sandbox) => {
@@ -155,17 +169,46 @@ impl<A: Agent, ES: EventStore> EventHandler<AgentState<A>, ES> for ToolHandler {
         handler: &Handler<AgentState<A>, ES>,
         event: &Envelope<AgentState<A>>,
     ) -> Result<()> {
-        if let Event::ToolCalls { calls } = &event.data {
-            let results = self.run_tools(&event.aggregate_id, calls).await?;
-            if !results.is_empty() {
-                handler
-                    .execute_with_metadata(
-                        &event.aggregate_id,
-                        Command::PutToolResults { results },
-                        event.metadata.clone(),
-                    )
-                    .await?;
+        let results = match &event.data {
+            Event::ToolCalls { calls } => {
+                let (held, calls): (Vec<ToolCall>, Vec<ToolCall>) = calls
+                    .iter()
+                    .cloned()
+                    .partition(|call| self.needs_approval(call));
+                if !held.is_empty() {
+                    handler
+                        .execute_with_metadata(
+                            &event.aggregate_id,
+                            Command::RequestApproval { calls: held },
+                            event.metadata.clone(),
+                        )
+                        .await?;
+                }
+                self.run_tools(&event.aggregate_id, &calls).await?
+            }
+            Event::ApprovalResolved { call, decision }
+                if self.get_tool(&call.function.name).is_some() =>
+            {
+                match decision.is_approved() {
+                    true => {
+                        self.run_tools(&event.aggregate_id, std::slice::from_ref(call))
+                            .await?
+                    }
+                    false => vec![
+                        call.to_result(Err(serde_json::json!("The user rejected this tool call"))),
+                    ],
+                }
             }
+            _ => return Ok(()),
+        };
+        if !results.is_empty() {
+            handler
+                .execute_with_metadata(
+                    &event.aggregate_id,
+                    Command::PutToolResults { results },
+                    event.metadata.clone(),
+                )
+                .await?;
         }
         Ok(())
     }
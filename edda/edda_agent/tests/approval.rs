@@ -0,0 +1,100 @@
+use edda_agent::processor::agent::{Agent, AgentState, ApprovalDecision, Command, Event};
+use edda_mq::db::sqlite::SqliteStore;
+use edda_mq::{Event as MQEvent, Handler};
+use rig::message::{ToolCall, ToolFunction};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Reviewer;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReviewerEvent {}
+
+impl MQEvent for ReviewerEvent {
+    fn event_type(&self) -> String {
+        "reviewer".to_string()
+    }
+
+    fn event_version(&self) -> String {
+        "1.0".to_string()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ReviewerError {}
+
+impl Agent for Reviewer {
+    const TYPE: &'static str = "approval_test";
+    type AgentCommand = ();
+    type AgentEvent = ReviewerEvent;
+    type AgentError = ReviewerError;
+    type Services = ();
+}
+
+#[tokio::test]
+async fn test_approval_events() {
+    let pool = sqlx::SqlitePool::connect(":memory:")
+        .await
+        .expect("Failed to create in-memory SQLite pool");
+    let store = SqliteStore::new(pool, "agent");
+    store.migrate().await;
+    let handler = Handler::<AgentState<Reviewer>, _>::new(store, ());
+    let call = |id: &str, command: &str| ToolCall {
+        id: id.to_string(),
+        call_id: None,
+        function: ToolFunction {
+            name: "bash".to_string(),
+            arguments: serde_json::json!({ "command": command }),
+        },
+    };
+
+    handler
+        .execute(
+            "review",
+            Command::RequestApproval {
+                calls: vec![call("1", "rm -rf build"), call("2", "ls")],
+            },
+        )
+        .await
+        .unwrap();
+    let state = handler.load_aggregate("review").await.unwrap();
+    assert_eq!(state.approvals.len(), 2);
+
+    let edited = serde_json::json!({ "command": "rm -rf build/cache" });
+    handler
+        .execute(
+            "review",
+            Command::ResolveApproval {
+                call_id: "1".to_string(),
+                decision: ApprovalDecision::Edit {
+                    arguments: edited.clone(),
+                },
+            },
+        )
+        .await
+        .unwrap();
+    // a call can only be decided once
+    assert!(
+        handler
+            .execute(
+                "review",
+                Command::ResolveApproval {
+                    call_id: "1".to_string(),
+                    decision: ApprovalDecision::Approve,
+                },
+            )
+            .await
+            .is_err()
+    );
+
+    let state = handler.load_aggregate("review").await.unwrap();
+    assert_eq!(state.approvals.len(), 1);
+    let events = handler.load_events("review").await.unwrap();
+    match &events[2] {
+        Event::ApprovalResolved { call, decision } => {
+            assert_eq!(call.function.arguments, edited);
+            assert!(decision.is_approved());
+        }
+        other => panic!("expected ApprovalResolved, got {:?}", other),
+    }
+}
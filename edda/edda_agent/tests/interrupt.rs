@@ -0,0 +1,187 @@
+use edda_agent::llm::{CompletionResponse, FinishReason};
+use edda_agent::processor::agent::{Agent, AgentState, Command, Event};
+use edda_mq::db::sqlite::SqliteStore;
+use edda_mq::{Event as MQEvent, Handler};
+use rig::message::{AssistantContent, ToolCall, ToolFunction, ToolResult, UserContent};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Steered;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SteeredEvent {}
+
+impl MQEvent for SteeredEvent {
+    fn event_type(&self) -> String {
+        "steered".to_string()
+    }
+
+    fn event_version(&self) -> String {
+        "1.0".to_string()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SteeredError {}
+
+impl Agent for Steered {
+    const TYPE: &'static str = "interrupt_test";
+    type AgentCommand = ();
+    type AgentEvent = SteeredEvent;
+    type AgentError = SteeredError;
+    type Services = ();
+}
+
+fn text(value: &str) -> rig::OneOrMany<UserContent> {
+    rig::OneOrMany::one(UserContent::text(value))
+}
+
+fn is_text(content: &UserContent, value: &str) -> bool {
+    matches!(content, UserContent::Text(t) if t.text == value)
+}
+
+#[tokio::test]
+async fn test_interrupt_waits_for_tools() {
+    let pool = sqlx::SqlitePool::connect(":memory:")
+        .await
+        .expect("Failed to create in-memory SQLite pool");
+    let store = SqliteStore::new(pool, "agent");
+    store.migrate().await;
+    let handler = Handler::<AgentState<Steered>, _>::new(store, ());
+
+    handler
+        .execute(
+            "steer",
+            Command::PutUserMessage {
+                content: text("build the api"),
+            },
+        )
+        .await
+        .unwrap();
+    let call = ToolCall {
+        id: "1".to_string(),
+        call_id: None,
+        function: ToolFunction {
+            name: "bash".to_string(),
+            arguments: serde_json::json!({ "command": "cargo build" }),
+        },
+    };
+    let response = CompletionResponse {
+        choice: rig::OneOrMany::one(AssistantContent::ToolCall(call)),
+        finish_reason: FinishReason::ToolUse,
+        output_tokens: 10,
+        input_tokens: 0,
+    };
+    handler
+        .execute("steer", Command::PutCompletion { response })
+        .await
+        .unwrap();
+
+    // held while the tool call runs
+    handler
+        .execute(
+            "steer",
+            Command::Interrupt {
+                content: text("use axum instead"),
+            },
+        )
+        .await
+        .unwrap();
+    let events = handler.load_events("steer").await.unwrap();
+    assert!(matches!(events.last(), Some(Event::UserInterrupted { .. })));
+
+    let result = ToolResult {
+        id: "1".to_string(),
+        call_id: None,
+        content: rig::OneOrMany::one(rig::message::ToolResultContent::text("ok")),
+    };
+    handler
+        .execute(
+            "steer",
+            Command::PutToolResults {
+                results: vec![result],
+            },
+        )
+        .await
+        .unwrap();
+    let events = handler.load_events("steer").await.unwrap();
+    match events.last() {
+        Some(Event::UserCompletion { content }) => {
+            let content: Vec<_> = content.iter().collect();
+            assert!(matches!(content[0], UserContent::ToolResult(_)));
+            assert!(is_text(content[1], "use axum instead"));
+        }
+        other => panic!("expected UserCompletion, got {:?}", other),
+    }
+    let state = handler.load_aggregate("steer").await.unwrap();
+    assert!(state.interrupt.is_empty());
+}
+
+#[tokio::test]
+async fn test_interrupt_after_turn() {
+    let pool = sqlx::SqlitePool::connect(":memory:")
+        .await
+        .expect("Failed to create in-memory SQLite pool");
+    let store = SqliteStore::new(pool, "agent");
+    store.migrate().await;
+    let handler = Handler::<AgentState<Steered>, _>::new(store, ());
+
+    handler
+        .execute(
+            "steer",
+            Command::PutUserMessage {
+                content: text("hello"),
+            },
+        )
+        .await
+        .unwrap();
+    handler
+        .execute(
+            "steer",
+            Command::Interrupt {
+                content: text("answer in French"),
+            },
+        )
+        .await
+        .unwrap();
+    let response = CompletionResponse {
+        choice: rig::OneOrMany::one(AssistantContent::text("Hi!")),
+        finish_reason: FinishReason::Stop,
+        output_tokens: 2,
+        input_tokens: 0,
+    };
+    handler
+        .execute("steer", Command::PutCompletion { response })
+        .await
+        .unwrap();
+    let events = handler.load_events("steer").await.unwrap();
+    match events.last() {
+        Some(Event::UserCompletion { content }) => {
+            assert!(is_text(content.iter().next().unwrap(), "answer in French"));
+        }
+        other => panic!("expected UserCompletion, got {:?}", other),
+    }
+
+    // an idle agent gets the instruction right away
+    let response = CompletionResponse {
+        choice: rig::OneOrMany::one(AssistantContent::text("Bonjour !")),
+        finish_reason: FinishReason::Stop,
+        output_tokens: 3,
+        input_tokens: 0,
+    };
+    handler
+        .execute("steer", Command::PutCompletion { response })
+        .await
+        .unwrap();
+    handler
+        .execute(
+            "steer",
+            Command::Interrupt {
+                content: text("thanks"),
+            },
+        )
+        .await
+        .unwrap();
+    let events = handler.load_events("steer").await.unwrap();
+    assert!(matches!(events.last(), Some(Event::UserCompletion { .. })));
+}
@@ -0,0 +1,84 @@
+use edda_agent::processor::agent::{Agent, AgentState, Command, Event};
+use edda_mq::db::sqlite::SqliteStore;
+use edda_mq::{Event as MQEvent, Handler};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Planner;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PlannerEvent {}
+
+impl MQEvent for PlannerEvent {
+    fn event_type(&self) -> String {
+        "planner".to_string()
+    }
+
+    fn event_version(&self) -> String {
+        "1.0".to_string()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlannerError {}
+
+impl Agent for Planner {
+    const TYPE: &'static str = "plan_test";
+    type AgentCommand = ();
+    type AgentEvent = PlannerEvent;
+    type AgentError = PlannerError;
+    type Services = ();
+}
+
+#[tokio::test]
+async fn test_plan_events() {
+    let pool = sqlx::SqlitePool::connect(":memory:")
+        .await
+        .expect("Failed to create in-memory SQLite pool");
+    let store = SqliteStore::new(pool, "agent");
+    store.migrate().await;
+    let handler = Handler::<AgentState<Planner>, _>::new(store, ());
+    let tasks =
+        |names: &[&str]| -> Vec<String> { names.iter().map(|name| name.to_string()).collect() };
+
+    handler
+        .execute(
+            "plan",
+            Command::PutPlan {
+                tasks: tasks(&["schema", "api", "ui"]),
+            },
+        )
+        .await
+        .unwrap();
+    handler
+        .execute("plan", Command::CompleteTask { index: 0 })
+        .await
+        .unwrap();
+    let state = handler.load_aggregate("plan").await.unwrap();
+    assert_eq!(state.plan.current(), Some(1));
+
+    // completed tasks that are kept stay done after a replan
+    handler
+        .execute(
+            "plan",
+            Command::PutPlan {
+                tasks: tasks(&["schema", "ui"]),
+            },
+        )
+        .await
+        .unwrap();
+    let state = handler.load_aggregate("plan").await.unwrap();
+    assert!(state.plan.tasks[0].done);
+    assert_eq!(state.plan.current(), Some(1));
+    assert!(
+        handler
+            .execute("plan", Command::CompleteTask { index: 2 })
+            .await
+            .is_err()
+    );
+
+    let events = handler.load_events("plan").await.unwrap();
+    assert!(matches!(events[0], Event::PlanCreated { .. }));
+    assert!(matches!(events[1], Event::TaskCompleted { index: 0 }));
+    assert!(matches!(events[2], Event::PlanUpdated { .. }));
+}
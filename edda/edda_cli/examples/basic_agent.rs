@@ -1,12 +1,13 @@
+use clap::Parser;
 use edda_agent::processor::agent::{Agent, AgentState};
 use edda_agent::processor::link::Runtime;
 use edda_agent::processor::llm::{LLMConfig, LLMHandler};
+use edda_agent::processor::plan::{PlanHandler, plan_tool_definition};
 use edda_agent::processor::tools::{TemplateConfig, ToolHandler, get_dockerfile_dir_from_src_ws};
 use edda_agent::processor::utils::LogHandler;
 use edda_agent::toolbox::{self, basic::toolset};
-use edda_cli::App;
-use edda_mq::db::sqlite::SqliteStore;
-use edda_mq::{Event as MQEvent, PollingQueue};
+use edda_cli::{Cli, launch, session};
+use edda_mq::Event as MQEvent;
 use edda_sandbox::SandboxHandle;
 use eyre::Result;
 use rig::client::ProviderClient;
@@ -14,13 +15,13 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 const MODEL: &str = "claude-sonnet-4-5-20250929";
-const AGGREGATE_ID: &str = "agent";
 
 const SYSTEM_PROMPT: &str = "
 You are a python software engineer.
 Workspace is already set up using uv init.
 Use uv package manager if you need to add extra libraries.
 Program will be run using uv run main.py command in the current directory.
+Track your tasks with update_plan and mark each one completed when it is done.
 ";
 
 #[tokio::main]
@@ -30,7 +31,8 @@ async fn main() {
 
 pub async fn run_agent_with_cli() -> Result<()> {
     let _ = dotenvy::dotenv();
-    let store = store().await;
+    let cli = Cli::parse();
+    let store = session::open_store(&cli.data_dir()).await?;
 
     // Setup Runtime with all handlers
     let worker_tools = toolset(Validator);
@@ -39,7 +41,13 @@ pub async fn run_agent_with_cli() -> Result<()> {
         LLMConfig {
             model: MODEL.to_string(),
             preamble: Some(SYSTEM_PROMPT.to_string()),
-            tools: Some(worker_tools.iter().map(|tool| tool.definition()).collect()),
+            tools: Some(
+                worker_tools
+                    .iter()
+                    .map(|tool| tool.definition())
+                    .chain([plan_tool_definition()])
+                    .collect(),
+            ),
             ..Default::default()
         },
     );
@@ -47,19 +55,17 @@ pub async fn run_agent_with_cli() -> Result<()> {
         worker_tools,
         SandboxHandle::new(Default::default()),
         TemplateConfig::default_dir(get_dockerfile_dir_from_src_ws()),
-    );
-    let mut runtime = Runtime::<AgentState<Worker>, _>::new(store.clone(), ())
+    )
+    // shell commands and deletions wait for confirmation in the UI
+    .with_approval(["bash", "rm_file"]);
+    let runtime = Runtime::<AgentState<Worker>, _>::new(store.clone(), ())
         .with_handler(worker_llm)
         .with_handler(worker_tool_handler)
+        .with_handler(PlanHandler)
         .with_handler(LogHandler);
 
     // the single line required to set up the CLI
-    let app = App::new(&mut runtime, AGGREGATE_ID.to_string())?;
-
-    tokio::select! {
-        res = runtime.start() => res,
-        res = app.run(ratatui::init()) => res,
-    }
+    launch(&cli, runtime).await
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -89,15 +95,6 @@ impl Agent for Worker {
     type Services = ();
 }
 
-async fn store() -> PollingQueue<SqliteStore> {
-    let pool = sqlx::SqlitePool::connect(":memory:")
-        .await
-        .expect("Failed to create in-memory SQLite pool");
-    let store = SqliteStore::new(pool, "agent");
-    store.migrate().await;
-    PollingQueue::new(store)
-}
-
 pub struct Validator;
 
 impl toolbox::Validator for Validator {
@@ -1,19 +1,17 @@
+use clap::Parser;
 use edda_agent::processor::agent::{Agent, AgentState};
 use edda_agent::processor::databricks::*;
 use edda_agent::processor::link::Runtime;
 use edda_agent::processor::llm::{LLMConfig, LLMHandler};
 use edda_agent::processor::utils::LogHandler;
-use edda_cli::App;
+use edda_cli::{Cli, launch, session};
 use edda_integrations::databricks::DatabricksRestClient;
-use edda_mq::db::sqlite::SqliteStore;
-use edda_mq::{Event as MQEvent, PollingQueue};
+use edda_mq::Event as MQEvent;
 use eyre::Result;
 use rig::client::ProviderClient;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-const AGGREGATE_ID: &str = "databricks";
-
 const MODEL: &str = "claude-sonnet-4-5-20250929";
 
 const MAIN_PROMPT: &str = "
@@ -34,7 +32,8 @@ async fn main() {
 }
 
 pub async fn run_databricks_worker() -> Result<()> {
-    let store = store().await;
+    let cli = Cli::parse();
+    let store = session::open_store(&cli.data_dir()).await?;
 
     // Main agent setup
     let tools = toolbox();
@@ -51,17 +50,13 @@ pub async fn run_databricks_worker() -> Result<()> {
         Arc::new(DatabricksRestClient::new().map_err(|e| eyre::eyre!("{}", e))?),
         tools,
     );
-    let mut runtime = Runtime::<AgentState<MainAgent>, _>::new(store.clone(), ())
+    let runtime = Runtime::<AgentState<MainAgent>, _>::new(store.clone(), ())
         .with_handler(main_llm)
         .with_handler(tool_handler)
         .with_handler(LogHandler);
 
-    let app = App::new(&mut runtime, AGGREGATE_ID.to_string())?;
-
-    tokio::select! {
-        res = runtime.start() => res,
-        res = app.run(ratatui::init()) => res,
-    }
+    // the single line required to set up the CLI
+    launch(&cli, runtime).await
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -101,12 +96,3 @@ pub fn toolbox() -> Vec<Box<dyn DatabricksToolDyn>> {
     ];
     tools
 }
-
-async fn store() -> PollingQueue<SqliteStore> {
-    let pool = sqlx::SqlitePool::connect(":memory:")
-        .await
-        .expect("Failed to create in-memory SQLite pool");
-    let store = SqliteStore::new(pool, "agent");
-    store.migrate().await;
-    PollingQueue::new(store)
-}
@@ -1,19 +1,74 @@
+use crate::command::InputCommand;
+use crate::config::{Action, Config};
 use crate::events::{AppEvent, CliEvent, EventHandler};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use edda_agent::processor::agent::{Agent, AgentState, Command, Event};
+use crate::export;
+use crate::history::PromptHistory;
+use crate::search::{self, Search};
+use crate::session::new_session_id;
+use crate::tab::Tab;
+use crate::widgets::event_as_text;
+use crossterm::event::{KeyCode, KeyEvent};
+use edda_agent::llm::LLMConfigUpdate;
+use edda_agent::processor::agent::{Agent, AgentState, ApprovalDecision, Command};
 use edda_agent::processor::link::Runtime;
 use edda_mq::{EventQueue, EventStore, Handler};
-use ratatui::widgets::ListState;
+use rig::message::{ToolResult, ToolResultContent};
+
+const OUTPUT_SCROLL: u16 = 5;
+const INTERRUPTED_TOOL: &str =
+    "Interrupted before the tool finished, run it again if it is still needed";
 
 pub struct App<A: Agent, ES: EventStore> {
     pub handler: Handler<AgentState<A>, ES>,
-    pub aggregate_id: String,
-    pub history: Vec<Event<A::AgentEvent>>,
+    /// Sessions shown in the tab bar, all of them receive their events
+    pub tabs: Vec<Tab<A::AgentEvent>>,
+    /// Index of the tab on screen
+    pub active: usize,
+    /// The input box holds the arguments of the first pending approval
+    pub editing_approval: bool,
+    /// Feedback shown in the input box title, cleared on the next key
+    pub status: Option<String>,
+    pub output_open: bool,
+    /// The diff view replaces the event list
+    pub diff_open: bool,
+    /// Spending in USD from which the status bar warns
+    pub budget: Option<f64>,
+    /// Title and text shown over the event list until Esc
+    pub popup: Option<(String, String)>,
+    /// Search confirmed with Enter, kept highlighted until Esc
+    pub search: Option<Search>,
+    pub config: Config,
     pub input_buffer: String,
+    pub prompt_history: PromptHistory,
+    /// LLM settings from the command line, sent to the sessions started in the UI
+    pub llm: LLMConfigUpdate,
     pub running: bool,
+    /// Attached to a shared store: events are shown, nothing is written
+    pub read_only: bool,
     pub events: EventHandler<A::AgentEvent>,
-    pub list_state: ListState,
-    pub auto_scroll: bool,
+}
+
+impl<A: Agent, ES: EventStore> App<A, ES> {
+    pub fn tab(&self) -> &Tab<A::AgentEvent> {
+        &self.tabs[self.active]
+    }
+
+    pub fn tab_mut(&mut self) -> &mut Tab<A::AgentEvent> {
+        &mut self.tabs[self.active]
+    }
+
+    /// The input box holds a `/` search rather than a message
+    pub fn searching(&self) -> bool {
+        !self.editing_approval && self.input_buffer.starts_with('/')
+    }
+
+    /// Search being typed, or the last one confirmed
+    pub fn current_search(&self) -> Option<Search> {
+        match self.searching() {
+            true => Search::new(&self.input_buffer[1..]),
+            false => self.search.clone(),
+        }
+    }
 }
 
 impl<A: Agent + 'static, ES: EventQueue + 'static> App<A, ES>
@@ -25,28 +80,140 @@ where
         runtime: &mut Runtime<AgentState<A>, ES>,
         aggregate_id: String,
     ) -> color_eyre::Result<Self> {
-        Ok(Self {
-            aggregate_id,
+        Ok(Self::with_tabs(runtime, vec![Tab::new(aggregate_id)]))
+    }
+
+    fn with_tabs(runtime: &mut Runtime<AgentState<A>, ES>, tabs: Vec<Tab<A::AgentEvent>>) -> Self {
+        Self {
             handler: runtime.handler.clone(),
-            history: Vec::new(),
+            tabs,
+            active: 0,
+            editing_approval: false,
+            status: None,
+            output_open: true,
+            diff_open: false,
+            budget: None,
+            popup: None,
+            search: None,
+            config: Config::default(),
             input_buffer: String::new(),
+            prompt_history: PromptHistory::default(),
+            llm: LLMConfigUpdate::default(),
             running: true,
+            read_only: false,
             events: EventHandler::new(runtime),
-            list_state: ListState::default(),
-            auto_scroll: true,
-        })
+        }
+    }
+
+    /// Reopen stored sessions, one tab each. Their events are folded back into the history
+    /// and the runtime's handlers only see new ones; tool calls cut off by the interruption
+    /// get an error result so the agent continues from there.
+    pub async fn resume(
+        runtime: &mut Runtime<AgentState<A>, ES>,
+        aggregate_ids: &[String],
+    ) -> color_eyre::Result<Self> {
+        if aggregate_ids.is_empty() {
+            color_eyre::eyre::bail!("no session to resume");
+        }
+        runtime.listener.skip_existing().await?;
+        let mut app = Self::with_tabs(runtime, Vec::new());
+        for aggregate_id in aggregate_ids {
+            app.open(aggregate_id.clone()).await?;
+            app.finish_interrupted(aggregate_id).await?;
+        }
+        app.select_tab(0);
+        Ok(app)
+    }
+
+    /// Watch stored sessions, one tab each, without sending anything to them. `runtime`
+    /// should have no handlers, the agent runs elsewhere.
+    pub async fn attach(
+        runtime: &mut Runtime<AgentState<A>, ES>,
+        aggregate_ids: &[String],
+    ) -> color_eyre::Result<Self> {
+        if aggregate_ids.is_empty() {
+            color_eyre::eyre::bail!("no session to attach to");
+        }
+        runtime.listener.skip_existing().await?;
+        let mut app = Self::with_tabs(runtime, Vec::new());
+        app.read_only = true;
+        for aggregate_id in aggregate_ids {
+            app.open(aggregate_id.clone()).await?;
+        }
+        app.select_tab(0);
+        Ok(app)
+    }
+
+    /// Answer the tool calls of a session that never got a result. Calls held for approval
+    /// are still waiting on the user, not interrupted.
+    async fn finish_interrupted(&self, aggregate_id: &str) -> color_eyre::Result<()> {
+        let state = self.handler.load_aggregate(aggregate_id).await?;
+        let results: Vec<ToolResult> = state
+            .calls
+            .iter()
+            .filter(|(id, result)| result.is_none() && !state.approvals.contains_key(*id))
+            .map(|(id, _)| ToolResult {
+                id: id.clone(),
+                call_id: None,
+                content: rig::OneOrMany::one(ToolResultContent::text(INTERRUPTED_TOOL)),
+            })
+            .collect();
+        if !results.is_empty() {
+            let command = Command::PutToolResults { results };
+            self.handler.execute(aggregate_id, command).await?;
+        }
+        Ok(())
+    }
+
+    /// Show a stored session in a new tab, or switch to it if it is open already
+    pub async fn open(&mut self, aggregate_id: String) -> color_eyre::Result<()> {
+        if let Some(index) = self
+            .tabs
+            .iter()
+            .position(|t| t.aggregate_id == aggregate_id)
+        {
+            self.select_tab(index);
+            return Ok(());
+        }
+        let history = self
+            .handler
+            .store()
+            .load_events::<AgentState<A>>(&aggregate_id)
+            .await?;
+        if history.is_empty() {
+            color_eyre::eyre::bail!("no session with id {}", aggregate_id);
+        }
+        let mut tab = Tab::new(aggregate_id);
+        for envelope in history {
+            tab.push(envelope.sequence, envelope.data);
+        }
+        self.tabs.push(tab);
+        self.select_tab(self.tabs.len() - 1);
+        Ok(())
     }
 
-    pub async fn run(mut self, mut terminal: ratatui::DefaultTerminal) -> color_eyre::Result<()> {
+    pub async fn run(&mut self, mut terminal: ratatui::DefaultTerminal) -> color_eyre::Result<()> {
         while self.running {
-            terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
+            terminal.draw(|frame| frame.render_widget(&mut *self, frame.area()))?;
             match self.events.next().await? {
                 CliEvent::Tick => self.tick(),
-                CliEvent::Crossterm(event) => if let crossterm::event::Event::Key(key_event) = event { self.handle_key_events(key_event)? },
-                CliEvent::Agent(event) => {
-                    self.history.push(event);
-                    if self.auto_scroll && !self.history.is_empty() {
-                        self.list_state.select(Some(self.history.len() - 1));
+                CliEvent::Crossterm(event) => {
+                    if let crossterm::event::Event::Key(key_event) = event {
+                        self.handle_key_events(key_event)?
+                    }
+                }
+                CliEvent::Agent {
+                    aggregate_id,
+                    sequence,
+                    event,
+                } => {
+                    // other sessions of the store are not shown
+                    if let Some(tab) = self
+                        .tabs
+                        .iter_mut()
+                        .find(|t| t.aggregate_id == aggregate_id)
+                    {
+                        tab.push(sequence, event);
                     }
                 }
                 CliEvent::App(app_event) => match app_event {
@@ -54,85 +221,203 @@ where
                     AppEvent::Erase => self.erase(),
                     AppEvent::Input(input) => self.input(input),
                     AppEvent::Quit => self.quit(),
+                    AppEvent::Decide(decision) => self.decide(decision).await?,
+                    AppEvent::EditApproval => self.edit_approval(),
+                    AppEvent::Cancel => self.cancel(),
+                    AppEvent::OpenEditor => self.open_editor(&mut terminal).await?,
                 },
             }
         }
         Ok(())
     }
 
-    pub fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<()> {
-        match key.code {
-            KeyCode::Enter => self.events.send(CliEvent::App(AppEvent::Confirm)),
-            KeyCode::Char('c' | 'C') if key.modifiers == KeyModifiers::CONTROL => {
-                self.events.send(CliEvent::App(AppEvent::Quit))
-            }
-            KeyCode::Char(c) => self.events.send(CliEvent::App(AppEvent::Input(c))),
-            KeyCode::Backspace => self.events.send(CliEvent::App(AppEvent::Erase)),
-            KeyCode::Up => {
-                self.auto_scroll = false;
-                if let Some(selected) = self.list_state.selected() {
-                    if selected > 0 {
-                        self.list_state.select(Some(selected - 1));
-                    }
-                } else if !self.history.is_empty() {
-                    self.list_state.select(Some(self.history.len() - 1));
+    /// Switch tabs, dropping arguments being edited for the previous tab's approval
+    fn select_tab(&mut self, index: usize) {
+        if self.editing_approval {
+            self.cancel();
+        }
+        self.active = index.min(self.tabs.len().saturating_sub(1));
+    }
+
+    fn handle_action(&mut self, action: Action) -> color_eyre::Result<()> {
+        let diff_open = self.diff_open;
+        let tab = self.tab_mut();
+        match action {
+            Action::Quit => self.events.send(CliEvent::App(AppEvent::Quit)),
+            Action::ToggleOutput => self.output_open = !self.output_open,
+            Action::ToggleDiff => {
+                self.diff_open = !self.diff_open;
+                if self.diff_open {
+                    let tab = self.tab_mut();
+                    tab.select_change(tab.file_changes.changes.len().saturating_sub(1));
                 }
             }
-            KeyCode::Down => {
-                if let Some(selected) = self.list_state.selected() {
-                    if selected < self.history.len() - 1 {
-                        self.list_state.select(Some(selected + 1));
-                        // Re-enable auto-scroll if we reach the bottom
-                        if selected + 1 == self.history.len() - 1 {
-                            self.auto_scroll = true;
-                        }
-                    }
-                } else if !self.history.is_empty() {
-                    self.list_state.select(Some(0));
-                }
+            Action::NextChange if diff_open => tab.select_change(tab.diff_selected + 1),
+            Action::PreviousChange if diff_open => {
+                tab.select_change(tab.diff_selected.saturating_sub(1))
             }
-            KeyCode::PageUp => {
-                self.auto_scroll = false;
-                if !self.history.is_empty() {
-                    let current = self.list_state.selected().unwrap_or(self.history.len() - 1);
-                    let new_pos = current.saturating_sub(10);
-                    self.list_state.select(Some(new_pos));
-                }
+            Action::NextChange | Action::PreviousChange => {}
+            Action::ScrollUp if diff_open => {
+                tab.diff_scroll = tab.diff_scroll.saturating_sub(OUTPUT_SCROLL);
             }
-            KeyCode::PageDown => {
-                if !self.history.is_empty() {
-                    let current = self.list_state.selected().unwrap_or(0);
-                    let new_pos = (current + 10).min(self.history.len() - 1);
-                    self.list_state.select(Some(new_pos));
-                    // Re-enable auto-scroll if we reach the bottom
-                    if new_pos == self.history.len() - 1 {
-                        self.auto_scroll = true;
-                    }
+            Action::ScrollDown if diff_open => tab.diff_scroll += OUTPUT_SCROLL,
+            Action::ScrollUp => {
+                let current = tab.output_scroll.unwrap_or(tab.output_tail);
+                tab.output_scroll = Some(current.saturating_sub(OUTPUT_SCROLL));
+            }
+            Action::ScrollDown => {
+                if let Some(current) = tab.output_scroll {
+                    let next = current + OUTPUT_SCROLL;
+                    tab.output_scroll = (next < tab.output_tail).then_some(next);
                 }
             }
-            KeyCode::Home => {
-                self.auto_scroll = false;
-                if !self.history.is_empty() {
-                    self.list_state.select(Some(0));
+            Action::NextTab => self.select_tab((self.active + 1) % self.tabs.len()),
+            Action::PreviousTab => {
+                self.select_tab((self.active + self.tabs.len() - 1) % self.tabs.len())
+            }
+            Action::CopyOutput => self.copy_output()?,
+            // recalling would replace the arguments being edited
+            Action::HistoryPrevious | Action::HistoryNext if self.editing_approval => {}
+            Action::HistoryPrevious => {
+                if let Some(entry) = self.prompt_history.older(&self.input_buffer) {
+                    self.input_buffer = entry.to_string();
                 }
             }
-            KeyCode::End => {
-                self.auto_scroll = true;
-                if !self.history.is_empty() {
-                    self.list_state.select(Some(self.history.len() - 1));
+            Action::HistoryNext => {
+                if let Some(entry) = self.prompt_history.newer() {
+                    self.input_buffer = entry.to_string();
                 }
             }
-            _ => {}
+            Action::Newline => self.input('\n'),
+            Action::SearchNext => self.jump_to_match(search::next_match),
+            Action::SearchPrevious => self.jump_to_match(search::previous_match),
+            Action::OpenEditor => self.events.send(CliEvent::App(AppEvent::OpenEditor)),
+            Action::Approve => self
+                .events
+                .send(CliEvent::App(AppEvent::Decide(ApprovalDecision::Approve))),
+            Action::Reject => self
+                .events
+                .send(CliEvent::App(AppEvent::Decide(ApprovalDecision::Reject))),
+            Action::EditApproval => self.events.send(CliEvent::App(AppEvent::EditApproval)),
+        }
+        Ok(())
+    }
+
+    pub fn handle_key_events(&mut self, key: KeyEvent) -> color_eyre::Result<()> {
+        if let Some(action) = self.config.keys.action(&key, self.deciding()) {
+            return self.handle_action(action);
+        }
+        match key.code {
+            KeyCode::Enter => self.events.send(CliEvent::App(AppEvent::Confirm)),
+            KeyCode::Char(_) if self.deciding() => {}
+            KeyCode::Char(c) => self.events.send(CliEvent::App(AppEvent::Input(c))),
+            KeyCode::Esc => self.events.send(CliEvent::App(AppEvent::Cancel)),
+            KeyCode::Backspace => self.events.send(CliEvent::App(AppEvent::Erase)),
+            code => self.tab_mut().navigate(code),
         }
         Ok(())
     }
 
+    /// Put the tool output on the clipboard through the terminal (OSC 52)
+    fn copy_output(&self) -> color_eyre::Result<()> {
+        let text = self.tab().tool_runs.text();
+        if !text.is_empty() {
+            crossterm::execute!(
+                std::io::stdout(),
+                crossterm::clipboard::CopyToClipboard::to_clipboard_from(text)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// A tool call waits for y/n/e instead of the input box taking keys
+    fn deciding(&self) -> bool {
+        !self.read_only && !self.tab().approvals.is_empty() && !self.editing_approval
+    }
+
+    async fn decide(&mut self, decision: ApprovalDecision) -> color_eyre::Result<()> {
+        if self.read_only || self.tab().approvals.is_empty() {
+            return Ok(());
+        }
+        let tab = self.tab_mut();
+        // removed right away so a repeated key doesn't decide the same call twice
+        let call = tab.approvals.remove(0);
+        let command = Command::ResolveApproval {
+            call_id: call.id,
+            decision,
+        };
+        self.handler
+            .execute(&self.tab().aggregate_id, command)
+            .await?;
+        Ok(())
+    }
+
+    pub fn edit_approval(&mut self) {
+        if self.read_only {
+            return;
+        }
+        if let Some(call) = self.tab().approvals.first() {
+            self.input_buffer = call.function.arguments.to_string();
+            self.editing_approval = true;
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        if self.popup.is_some() {
+            self.popup = None;
+        } else if self.editing_approval {
+            self.editing_approval = false;
+            self.status = None;
+            self.input_buffer.clear();
+        } else if self.searching() {
+            self.input_buffer.clear();
+        } else if self.search.is_some() {
+            self.search = None;
+            self.status = None;
+        }
+    }
+
+    /// Indices in the event list of the items `search` matches
+    pub fn search_matches(&self, search: &Search) -> Vec<usize> {
+        self.tab()
+            .history
+            .iter()
+            .filter_map(|event| event_as_text(event, &self.config.theme))
+            .enumerate()
+            .filter(|(_, text)| search.matches(text))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Select the match `pick` chooses relative to the selected item
+    fn jump_to_match(&mut self, pick: fn(&[usize], Option<usize>) -> Option<usize>) {
+        let Some(search) = self.current_search() else {
+            return;
+        };
+        let matches = self.search_matches(&search);
+        let tab = self.tab_mut();
+        match pick(&matches, tab.list_state.selected()) {
+            Some(index) => {
+                tab.auto_scroll = false;
+                tab.list_state.select(Some(index));
+            }
+            None if !self.searching() => {
+                self.status = Some(format!("no match for {:?}", search.pattern()))
+            }
+            None => {}
+        }
+    }
+
+    /// Sent as an interrupt: an idle agent takes it as a new message, a working one gets
+    /// it after the running tool calls, before its next LLM turn
     async fn send_message(&mut self) -> color_eyre::Result<()> {
         let content = self.input_buffer.clone();
         let text = rig::message::UserContent::text(content);
         let message = rig::OneOrMany::one(text);
-        let command = Command::PutUserMessage { content: message };
-        self.handler.execute(&self.aggregate_id, command).await?;
+        let command = Command::Interrupt { content: message };
+        self.handler
+            .execute(&self.tab().aggregate_id, command)
+            .await?;
         Ok(())
     }
 
@@ -141,7 +426,11 @@ where
     }
 
     pub fn erase(&mut self) {
+        self.prompt_history.reset();
         self.input_buffer.pop();
+        if self.searching() {
+            self.jump_to_match(|matches, _| matches.last().copied());
+        }
     }
 
     pub fn quit(&mut self) {
@@ -149,14 +438,194 @@ where
     }
 
     pub async fn confirm(&mut self) -> color_eyre::Result<()> {
-        if !self.input_buffer.is_empty() {
+        if self.editing_approval {
+            match serde_json::from_str(&self.input_buffer) {
+                Ok(arguments) => {
+                    self.cancel();
+                    self.decide(ApprovalDecision::Edit { arguments }).await?;
+                }
+                Err(e) => self.status = Some(format!("invalid JSON: {}", e)),
+            }
+            return Ok(());
+        }
+        if self.searching() {
+            self.search = self.current_search();
+            self.status = self.search.as_ref().map(|search| {
+                let count = self.search_matches(search).len();
+                format!("{} matches for {:?}", count, search.pattern())
+            });
+            self.input_buffer.clear();
+            return Ok(());
+        }
+        if let Some(command) = InputCommand::parse(&self.input_buffer) {
+            self.remember_input();
+            self.input_buffer.clear();
+            self.status = Some(match command {
+                Ok(command) => self.run_command(command).await,
+                Err(e) => e,
+            });
+            return Ok(());
+        }
+        if self.read_only && !self.input_buffer.is_empty() {
+            self.status = Some("read-only, messages can't be sent while attached".to_string());
+            return Ok(());
+        }
+        // the agent can't take a message while a tool call is pending
+        if !self.input_buffer.is_empty() && self.tab().approvals.is_empty() {
             self.send_message().await?;
+            self.remember_input();
             self.input_buffer.clear();
         }
         Ok(())
     }
 
+    /// Add the input to the prompt history. Failing to save it is shown but doesn't stop
+    /// the prompt.
+    fn remember_input(&mut self) {
+        if let Err(e) = self.prompt_history.push(&self.input_buffer) {
+            self.status = Some(format!("can't save the prompt history: {}", e));
+        }
+    }
+
+    /// Switch the LLM of the session in tab `index`. Returns `false` without sending
+    /// anything if it already uses these settings.
+    pub async fn update_llm(
+        &self,
+        index: usize,
+        update: &LLMConfigUpdate,
+    ) -> color_eyre::Result<bool> {
+        let tab = &self.tabs[index];
+        let mut merged = tab.llm.clone();
+        merged.merge(update);
+        if update.is_empty() || merged == tab.llm {
+            return Ok(false);
+        }
+        if let Some(provider) = update.provider
+            && !provider.is_api_key_env_var_set()
+        {
+            color_eyre::eyre::bail!(
+                "{} is not set, {} can't be used",
+                provider.api_key_env_var(),
+                provider.name()
+            );
+        }
+        let command = Command::UpdateLLMConfig {
+            update: update.clone(),
+        };
+        self.handler.execute(&tab.aggregate_id, command).await?;
+        Ok(true)
+    }
+
+    /// Run a `:` command, returning what to tell the user
+    async fn run_command(&mut self, command: InputCommand) -> String {
+        match command {
+            InputCommand::Export { dir } => {
+                let tab = self.tab();
+                match export::export(&dir, &tab.aggregate_id, &tab.history) {
+                    Ok((transcript, trajectory)) => format!(
+                        "exported {} and {}",
+                        transcript.display(),
+                        trajectory.display()
+                    ),
+                    Err(e) => format!("export failed: {}", e),
+                }
+            }
+            InputCommand::Cost => {
+                let tab = self.tab();
+                let summary = tab.usage.summary(self.budget);
+                self.popup = Some(("Usage".to_string(), tab.usage.report()));
+                summary
+            }
+            InputCommand::New if self.read_only => {
+                "read-only, sessions can't be started while attached".to_string()
+            }
+            InputCommand::New => {
+                self.tabs.push(Tab::new(new_session_id()));
+                self.select_tab(self.tabs.len() - 1);
+                if let Err(e) = self.update_llm(self.active, &self.llm).await {
+                    return format!("new session, but its LLM wasn't switched: {}", e);
+                }
+                "new session, type its first message".to_string()
+            }
+            InputCommand::Open { id } => match self.open(id).await {
+                Ok(()) => format!("opened {}", self.tab().aggregate_id),
+                Err(e) => e.to_string(),
+            },
+            InputCommand::Close => {
+                if self.tabs.len() == 1 {
+                    return "the last tab can't be closed".to_string();
+                }
+                let tab = self.tabs.remove(self.active);
+                self.select_tab(self.active);
+                format!("closed {}, the session keeps running", tab.aggregate_id)
+            }
+            InputCommand::Model { update } if update.is_empty() => match &self.tab().llm {
+                llm if llm.is_empty() => "the session uses the agent's default LLM".to_string(),
+                llm => format!("the session uses {}", llm),
+            },
+            InputCommand::Model { .. } if self.read_only => {
+                "read-only, the LLM can't be switched while attached".to_string()
+            }
+            InputCommand::Model { update } => match self.update_llm(self.active, &update).await {
+                Ok(true) => format!("switched to {}", update),
+                Ok(false) => format!("already using {}", update),
+                Err(e) => e.to_string(),
+            },
+        }
+    }
+
     pub fn input(&mut self, input: char) {
+        if !self.editing_approval {
+            self.status = None;
+        }
+        self.prompt_history.reset();
         self.input_buffer.push(input);
+        // incremental, from the most recent match
+        if self.searching() {
+            self.jump_to_match(|matches, _| matches.last().copied());
+        }
+    }
+
+    /// Edit the input box in `$VISUAL` or `$EDITOR`, for prompts too long to type in one
+    /// line. The agent keeps running meanwhile.
+    async fn open_editor(
+        &mut self,
+        terminal: &mut ratatui::DefaultTerminal,
+    ) -> color_eyre::Result<()> {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        // the variable may carry arguments, as in `code --wait`
+        let mut args = editor.split_whitespace();
+        let Some(program) = args.next() else {
+            self.status = Some("$EDITOR is empty".to_string());
+            return Ok(());
+        };
+        let path = std::env::temp_dir().join(format!("edda-prompt-{}.md", std::process::id()));
+        std::fs::write(&path, &self.input_buffer)?;
+
+        self.events.suspend().await;
+        ratatui::restore();
+        let status = tokio::process::Command::new(program)
+            .args(args)
+            .arg(&path)
+            .status()
+            .await;
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(std::io::stdout(), crossterm::terminal::EnterAlternateScreen)?;
+        terminal.clear()?;
+        self.events.resume();
+
+        match status {
+            Ok(status) if status.success() => {
+                let content = std::fs::read_to_string(&path)?;
+                self.input_buffer = content.trim_end().to_string();
+                self.prompt_history.reset();
+            }
+            Ok(status) => self.status = Some(format!("{} exited with {}", program, status)),
+            Err(e) => self.status = Some(format!("can't run {}: {}", program, e)),
+        }
+        let _ = std::fs::remove_file(&path);
+        Ok(())
     }
 }
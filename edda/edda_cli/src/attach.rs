@@ -0,0 +1,55 @@
+use crate::App;
+use crate::session::{Cli, configure, list_sessions};
+use clap::Args;
+use edda_agent::processor::agent::{Agent, AgentState};
+use edda_agent::processor::link::Runtime;
+use edda_mq::PollingQueue;
+use edda_mq::db::postgres::PostgresStore;
+use sqlx::PgPool;
+
+#[derive(Debug, Args)]
+pub struct AttachArgs {
+    /// Postgres URL of the event store the agent writes to
+    #[arg(long, value_name = "POSTGRES_URL")]
+    pub database: String,
+    /// Stream id the agent was started with
+    #[arg(long)]
+    pub stream: String,
+    /// Sessions to watch, one tab each (default: the most recent one)
+    pub sessions: Vec<String>,
+}
+
+/// Watch sessions of a shared store as they run. The UI is read-only and no handlers are
+/// started, so the process never writes to the store and doesn't compete with the agent.
+pub async fn run<A>(args: &AttachArgs, cli: &Cli, services: A::Services) -> color_eyre::Result<()>
+where
+    A: Agent + 'static,
+    A::Services: Clone,
+{
+    let pool = PgPool::connect(&args.database).await?;
+    let store = PollingQueue::new(PostgresStore::new(pool, &args.stream));
+    let mut runtime = Runtime::<AgentState<A>, _>::new(store, services);
+
+    let sessions = match args.sessions.is_empty() {
+        true => list_sessions::<A, _>(runtime.handler.store())
+            .await?
+            .into_iter()
+            .take(1)
+            .map(|session| session.id)
+            .collect(),
+        false => args.sessions.clone(),
+    };
+    if sessions.is_empty() {
+        color_eyre::eyre::bail!("no sessions in stream {}", args.stream);
+    }
+    let mut app = App::attach(&mut runtime, &sessions).await?;
+    configure(&mut app, cli)?;
+
+    let terminal = ratatui::init();
+    let result = tokio::select! {
+        res = runtime.start() => res,
+        res = app.run(terminal) => res,
+    };
+    ratatui::restore();
+    result
+}
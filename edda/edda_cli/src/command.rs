@@ -0,0 +1,76 @@
+use edda_agent::llm::LLMConfigUpdate;
+use std::path::PathBuf;
+
+/// Commands typed into the input box, prefixed with `:`
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputCommand {
+    /// Write the session as Markdown and JSON into `dir`
+    Export { dir: PathBuf },
+    /// Show token usage and cost
+    Cost,
+    /// Start another session in a new tab
+    New,
+    /// Show a stored session in a new tab
+    Open { id: String },
+    /// Close the current tab, its session keeps running
+    Close,
+    /// Switch the LLM of the current session, or show its settings if `update` is empty
+    Model { update: LLMConfigUpdate },
+}
+
+impl InputCommand {
+    /// `None` if `line` is a message rather than a command
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let line = line.trim().strip_prefix(':')?;
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some("export") => Ok(InputCommand::Export {
+                dir: words
+                    .next()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| ".".into()),
+            }),
+            Some("cost") => Ok(InputCommand::Cost),
+            Some("new") => Ok(InputCommand::New),
+            Some("open") => match words.next() {
+                Some(id) => Ok(InputCommand::Open { id: id.to_string() }),
+                None => Err("usage: :open <session id>".to_string()),
+            },
+            Some("close") => Ok(InputCommand::Close),
+            Some("model") => parse_llm_update(words).map(|update| InputCommand::Model { update }),
+            Some(name) => Err(format!("unknown command :{}", name)),
+            None => Err("empty command".to_string()),
+        };
+        Some(command)
+    }
+}
+
+/// `<model> provider=<name> temperature=<t> max_tokens=<n>`, every part optional
+fn parse_llm_update<'a>(words: impl Iterator<Item = &'a str>) -> Result<LLMConfigUpdate, String> {
+    let mut update = LLMConfigUpdate::default();
+    for word in words {
+        match word.split_once('=') {
+            Some(("provider", value)) => update.provider = Some(value.parse()?),
+            Some(("temperature", value)) => {
+                let temperature = value
+                    .parse()
+                    .map_err(|_| format!("invalid temperature {}", value))?;
+                update.temperature = Some(temperature);
+            }
+            Some(("max_tokens", value)) => {
+                let max_tokens = value
+                    .parse()
+                    .map_err(|_| format!("invalid max_tokens {}", value))?;
+                update.max_tokens = Some(max_tokens);
+            }
+            Some((key, _)) => {
+                return Err(format!(
+                    "unknown setting {}, expected provider, temperature or max_tokens",
+                    key
+                ));
+            }
+            None => update.model = Some(word.to_string()),
+        }
+    }
+    Ok(update)
+}
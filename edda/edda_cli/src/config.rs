@@ -0,0 +1,418 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Overrides where the config file is read from
+pub const CONFIG_ENV: &str = "EDDA_CONFIG";
+
+/// Settings of the UI, from `~/.config/edda/config.toml`:
+///
+/// ```toml
+/// [theme]
+/// preset = "light"
+/// accent = "#005f87"
+///
+/// [keys]
+/// toggle_output = "ctrl+t"
+/// approve = "a"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub theme: Theme,
+    pub keys: Keymap,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    theme: ThemeFile,
+    keys: HashMap<Action, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeFile {
+    preset: Preset,
+    accent: Option<String>,
+    muted: Option<String>,
+    success: Option<String>,
+    error: Option<String>,
+    info: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Preset {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        if let Ok(path) = std::env::var(CONFIG_ENV) {
+            return PathBuf::from(path);
+        }
+        let config_home = std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".config")
+            });
+        config_home.join("edda").join("config.toml")
+    }
+
+    /// Config at `path`, the defaults if there is no file
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        Self::parse(&content).map_err(|e| eyre::eyre!("invalid config {}: {}", path.display(), e))
+    }
+
+    pub fn parse(content: &str) -> eyre::Result<Self> {
+        let file: ConfigFile = toml::from_str(content)?;
+        let mut theme = match file.theme.preset {
+            Preset::Dark => Theme::dark(),
+            Preset::Light => Theme::light(),
+        };
+        let overrides = [
+            (&mut theme.accent, file.theme.accent),
+            (&mut theme.muted, file.theme.muted),
+            (&mut theme.success, file.theme.success),
+            (&mut theme.error, file.theme.error),
+            (&mut theme.info, file.theme.info),
+        ];
+        for (color, value) in overrides {
+            if let Some(value) = value {
+                *color = Color::from_str(&value)
+                    .map_err(|_| eyre::eyre!("unknown color {:?}", value))?;
+            }
+        }
+        let mut keys = Keymap::default();
+        for (action, binding) in file.keys {
+            keys.bindings.insert(action, KeyBinding::parse(&binding)?);
+        }
+        Ok(Self { theme, keys })
+    }
+}
+
+/// Colors of the widgets, by role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Selection, the current task and anything waiting on the user
+    pub accent: Color,
+    /// Ids, hints and other secondary text
+    pub muted: Color,
+    /// Finished tasks and added lines
+    pub success: Color,
+    /// Removed lines and exceeded budgets
+    pub error: Color,
+    /// Paths and diff hunk headers
+    pub info: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Yellow,
+            muted: Color::Gray,
+            success: Color::Green,
+            error: Color::Red,
+            info: Color::Cyan,
+        }
+    }
+
+    /// Darker colors that stay readable on a light background
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Indexed(130),
+            muted: Color::Indexed(242),
+            success: Color::Indexed(28),
+            error: Color::Indexed(160),
+            info: Color::Indexed(25),
+        }
+    }
+}
+
+/// Keys that can be rebound. Typing, Enter, Esc and navigation keys are fixed; arrows not
+/// taken by a binding, such as Alt+Up, move through the event list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    ToggleOutput,
+    ScrollUp,
+    ScrollDown,
+    CopyOutput,
+    ToggleDiff,
+    NextChange,
+    PreviousChange,
+    NextTab,
+    PreviousTab,
+    /// Recall sent prompts into the input box
+    HistoryPrevious,
+    HistoryNext,
+    /// Break the line in the input box instead of sending it
+    Newline,
+    /// Compose the input in `$VISUAL` or `$EDITOR`
+    OpenEditor,
+    /// Jump between matches of a `/` search
+    SearchNext,
+    SearchPrevious,
+    /// Only while a tool call waits for approval, like `reject` and `edit_approval`
+    Approve,
+    Reject,
+    EditApproval,
+}
+
+impl Action {
+    pub fn is_approval(&self) -> bool {
+        matches!(
+            self,
+            Action::Approve | Action::Reject | Action::EditApproval
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    const fn ctrl(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    const fn key(c: char) -> Self {
+        Self::plain(KeyCode::Char(c))
+    }
+
+    const fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    const fn alt(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::ALT)
+    }
+
+    /// `"ctrl+o"`, `"alt+x"`, `"y"`, `"f2"`, `"tab"`, `"alt+right"`
+    pub fn parse(value: &str) -> eyre::Result<Self> {
+        let mut parts: Vec<&str> = value.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default().to_lowercase();
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                other => eyre::bail!("unknown modifier {:?} in {:?}", other, value),
+            };
+        }
+        let code = match key.as_str() {
+            "tab" => KeyCode::Tab,
+            "enter" => KeyCode::Enter,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            f if f.len() > 1 && f.starts_with('f') => match f[1..].parse() {
+                Ok(n) => KeyCode::F(n),
+                Err(_) => eyre::bail!("unknown key {:?}", value),
+            },
+            c if c.chars().count() == 1 => KeyCode::Char(c.chars().next().unwrap_or_default()),
+            _ => eyre::bail!("unknown key {:?}", value),
+        };
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Letters match in either case, so Caps Lock doesn't break bindings
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = key.modifiers - KeyModifiers::SHIFT;
+        let code = match key.code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            code => code,
+        };
+        code == self.code && modifiers == self.modifiers
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub bindings: HashMap<Action, KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = [
+            (Action::Quit, KeyBinding::ctrl('c')),
+            (Action::ToggleOutput, KeyBinding::ctrl('o')),
+            (Action::ScrollUp, KeyBinding::ctrl('u')),
+            (Action::ScrollDown, KeyBinding::ctrl('d')),
+            (Action::CopyOutput, KeyBinding::ctrl('y')),
+            (Action::ToggleDiff, KeyBinding::ctrl('f')),
+            (Action::NextChange, KeyBinding::ctrl('n')),
+            (Action::PreviousChange, KeyBinding::ctrl('p')),
+            (Action::NextTab, KeyBinding::alt(KeyCode::Right)),
+            (Action::PreviousTab, KeyBinding::alt(KeyCode::Left)),
+            (Action::HistoryPrevious, KeyBinding::plain(KeyCode::Up)),
+            (Action::HistoryNext, KeyBinding::plain(KeyCode::Down)),
+            (Action::Newline, KeyBinding::alt(KeyCode::Enter)),
+            (Action::OpenEditor, KeyBinding::ctrl('e')),
+            (Action::SearchNext, KeyBinding::ctrl('s')),
+            (Action::SearchPrevious, KeyBinding::ctrl('r')),
+            (Action::Approve, KeyBinding::key('y')),
+            (Action::Reject, KeyBinding::key('n')),
+            (Action::EditApproval, KeyBinding::key('e')),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Action bound to `key`. Approval keys are plain letters, so they only count while
+    /// `deciding`, otherwise they are typed.
+    pub fn action(&self, key: &KeyEvent, deciding: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(action, binding)| binding.matches(key) && (deciding || !action.is_approval()))
+            .map(|(action, _)| *action)
+    }
+
+    /// Display form of the key bound to `action`, for hints in titles
+    pub fn label(&self, action: Action) -> String {
+        let Some(binding) = self.bindings.get(&action) else {
+            return "unbound".to_string();
+        };
+        let mut label = String::new();
+        if binding.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if binding.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        match binding.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(c) if binding.modifiers.is_empty() => label.push(c),
+            KeyCode::Char(c) => label.push(c.to_ascii_uppercase()),
+            code => label.push_str(&code.to_string()),
+        }
+        label
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_key_binding() {
+        let parsed = KeyBinding::parse("Ctrl+T").unwrap();
+        assert_eq!(parsed, KeyBinding::ctrl('t'));
+        let parsed = KeyBinding::parse("alt + right").unwrap();
+        assert_eq!(parsed, KeyBinding::alt(KeyCode::Right));
+        assert_eq!(
+            KeyBinding::parse("f2").unwrap(),
+            KeyBinding::plain(KeyCode::F(2))
+        );
+        assert_eq!(KeyBinding::parse("f").unwrap(), KeyBinding::key('f'));
+        assert_eq!(KeyBinding::parse("space").unwrap(), KeyBinding::key(' '));
+        assert!(KeyBinding::parse("shift+x").is_err());
+        assert!(KeyBinding::parse("fx").is_err());
+        assert!(KeyBinding::parse("pageup").is_err());
+    }
+
+    #[test]
+    fn test_binding_matches_either_case() {
+        let binding = KeyBinding::ctrl('o');
+        assert!(binding.matches(&key(KeyCode::Char('o'), KeyModifiers::CONTROL)));
+        assert!(binding.matches(&key(
+            KeyCode::Char('O'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT
+        )));
+        assert!(!binding.matches(&key(KeyCode::Char('o'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn test_approval_keys_only_while_deciding() {
+        let keys = Keymap::default();
+        let y = key(KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!(keys.action(&y, true), Some(Action::Approve));
+        assert_eq!(keys.action(&y, false), None);
+        let ctrl_c = key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(keys.action(&ctrl_c, false), Some(Action::Quit));
+    }
+
+    #[test]
+    fn test_labels() {
+        let keys = Keymap::default();
+        assert_eq!(keys.label(Action::ToggleOutput), "Ctrl+O");
+        assert_eq!(keys.label(Action::NextTab), "Alt+Right");
+        assert_eq!(keys.label(Action::Approve), "y");
+        let keys = Keymap {
+            bindings: HashMap::new(),
+        };
+        assert_eq!(keys.label(Action::Quit), "unbound");
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r##"
+            [theme]
+            preset = "light"
+            accent = "#005f87"
+
+            [keys]
+            toggle_output = "ctrl+t"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.theme.accent, Color::Rgb(0x00, 0x5f, 0x87));
+        assert_eq!(config.theme.muted, Theme::light().muted);
+        assert_eq!(
+            config.keys.bindings[&Action::ToggleOutput],
+            KeyBinding::ctrl('t')
+        );
+        // unchanged bindings keep their defaults
+        assert_eq!(config.keys.bindings[&Action::Quit], KeyBinding::ctrl('c'));
+
+        assert_eq!(Config::parse("").unwrap().theme, Theme::dark());
+        assert!(Config::parse("[theme]\naccent = \"nope\"").is_err());
+        assert!(Config::parse("[keys]\nfly = \"x\"").is_err());
+        assert!(Config::parse("[colors]").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file_is_default() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = Config::load(&dir.path().join("config.toml")).unwrap();
+        assert_eq!(config.theme, Theme::dark());
+        assert_eq!(config.keys.bindings.len(), Keymap::default().bindings.len());
+    }
+}
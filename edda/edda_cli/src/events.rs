@@ -1,10 +1,10 @@
 use color_eyre::eyre::OptionExt;
-use crossterm::event::Event as CrosstermEvent;
-use edda_agent::processor::agent::{Agent, AgentState, Event};
+use crossterm::event::{Event as CrosstermEvent, EventStream};
+use edda_agent::processor::agent::{Agent, AgentState, ApprovalDecision, Event};
 use edda_agent::processor::link::Runtime;
 use edda_mq::{Callback, Envelope, EventQueue};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio_stream::StreamExt;
 
 const TICK_FPS: f64 = 30.0;
@@ -15,19 +15,37 @@ pub enum AppEvent {
     Erase,
     Input(char),
     Quit,
+    Decide(ApprovalDecision),
+    /// Edit the arguments of the pending tool call in the input box
+    EditApproval,
+    Cancel,
+    /// Compose the input in `$EDITOR`
+    OpenEditor,
 }
 
 #[derive(Debug, Clone)]
 pub enum CliEvent<T> {
     Tick,
     Crossterm(CrosstermEvent),
-    Agent(Event<T>),
+    Agent {
+        aggregate_id: String,
+        sequence: i64,
+        event: Event<T>,
+    },
     App(AppEvent),
 }
 
 pub struct EventHandler<T> {
     sender: mpsc::UnboundedSender<CliEvent<T>>,
     receiver: mpsc::UnboundedReceiver<CliEvent<T>>,
+    control: mpsc::UnboundedSender<Control>,
+}
+
+/// Hands the terminal to another program and back
+enum Control {
+    /// Stop reading terminal events, acknowledged once the reader is gone
+    Suspend(oneshot::Sender<()>),
+    Resume,
 }
 
 impl<T: Send + 'static> EventHandler<T> {
@@ -37,11 +55,28 @@ impl<T: Send + 'static> EventHandler<T> {
         ES: EventQueue,
     {
         let (sender, receiver) = mpsc::unbounded_channel();
-        let actor = EventTask::new(sender.clone());
+        let (control, control_receiver) = mpsc::unbounded_channel();
+        let actor = EventTask::new(sender.clone(), control_receiver);
         tokio::spawn(async { actor.run().await });
         let forwarder = CliForwarder::new(sender.clone());
         runtime.listener.push_callback(forwarder);
-        Self { sender, receiver }
+        Self {
+            sender,
+            receiver,
+            control,
+        }
+    }
+
+    /// Stop reading the terminal until `resume`, so a program run in it gets the keys
+    pub async fn suspend(&self) {
+        let (ack, done) = oneshot::channel();
+        if self.control.send(Control::Suspend(ack)).is_ok() {
+            let _ = done.await;
+        }
+    }
+
+    pub fn resume(&self) {
+        let _ = self.control.send(Control::Resume);
     }
 
     pub async fn next(&mut self) -> color_eyre::Result<CliEvent<T>> {
@@ -58,16 +93,20 @@ impl<T: Send + 'static> EventHandler<T> {
 
 pub struct EventTask<T> {
     sender: mpsc::UnboundedSender<CliEvent<T>>,
+    control: mpsc::UnboundedReceiver<Control>,
 }
 
 impl<T> EventTask<T> {
-    pub fn new(sender: mpsc::UnboundedSender<CliEvent<T>>) -> Self {
-        Self { sender }
+    fn new(
+        sender: mpsc::UnboundedSender<CliEvent<T>>,
+        control: mpsc::UnboundedReceiver<Control>,
+    ) -> Self {
+        Self { sender, control }
     }
 
-    pub async fn run(self) -> color_eyre::Result<()> {
+    pub async fn run(mut self) -> color_eyre::Result<()> {
         let tick_rate = Duration::from_secs_f64(1.0 / TICK_FPS);
-        let mut reader = crossterm::event::EventStream::new();
+        let mut reader = Some(EventStream::new());
         let mut tick = tokio::time::interval(tick_rate);
         loop {
             let tick_delay = tick.tick();
@@ -78,7 +117,14 @@ impl<T> EventTask<T> {
                 _ = tick_delay => {
                     self.send(CliEvent::Tick);
                 }
-                Some(Ok(evt)) = reader.next() => {
+                Some(control) = self.control.recv() => match control {
+                    Control::Suspend(ack) => {
+                        reader = None;
+                        let _ = ack.send(());
+                    }
+                    Control::Resume => reader = Some(EventStream::new()),
+                },
+                Some(Ok(evt)) = next_event(&mut reader) => {
                     self.send(CliEvent::Crossterm(evt));
                 }
             };
@@ -91,6 +137,14 @@ impl<T> EventTask<T> {
     }
 }
 
+/// Next terminal event, never while suspended
+async fn next_event(reader: &mut Option<EventStream>) -> Option<std::io::Result<CrosstermEvent>> {
+    match reader {
+        Some(reader) => reader.next().await,
+        None => std::future::pending().await,
+    }
+}
+
 struct CliForwarder<T> {
     sender: mpsc::UnboundedSender<CliEvent<T>>,
 }
@@ -103,7 +157,11 @@ impl<T> CliForwarder<T> {
 
 impl<A: Agent> Callback<AgentState<A>> for CliForwarder<A::AgentEvent> {
     async fn process(&mut self, envelope: &Envelope<AgentState<A>>) -> eyre::Result<()> {
-        let _ = self.sender.send(CliEvent::Agent(envelope.data.clone()));
+        let _ = self.sender.send(CliEvent::Agent {
+            aggregate_id: envelope.aggregate_id.clone(),
+            sequence: envelope.sequence,
+            event: envelope.data.clone(),
+        });
         Ok(())
     }
 }
@@ -0,0 +1,230 @@
+use crate::widgets::tool_result_text;
+use edda_agent::processor::agent::{ApprovalDecision, Event};
+use edda_mq::Event as MQEvent;
+use rig::message::{AssistantContent, ToolCall, UserContent};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Readable transcript of a session: messages, tool calls with their output, plan changes
+/// and approval decisions
+pub fn markdown<T>(session_id: &str, events: &[Event<T>]) -> String {
+    let mut out = format!("# Session {}\n", session_id);
+    for event in events {
+        out.push_str(&event_markdown(event));
+    }
+    out
+}
+
+/// Markdown of a single event. Tool calls and results are shown where they appear in the
+/// conversation, so the separate tool events render as nothing.
+pub fn event_markdown<T>(event: &Event<T>) -> String {
+    let mut out = String::new();
+    match event {
+        Event::UserCompletion { content } => {
+            let (results, texts): (Vec<_>, Vec<_>) = content
+                .iter()
+                .partition(|item| matches!(item, UserContent::ToolResult(_)));
+            if !texts.is_empty() {
+                out.push_str("\n## User\n");
+            }
+            for item in texts {
+                match item {
+                    UserContent::Text(text) => push_paragraph(&mut out, &text.text),
+                    UserContent::Image(_) => push_paragraph(&mut out, "_[image]_"),
+                    UserContent::Audio(_) => push_paragraph(&mut out, "_[audio]_"),
+                    UserContent::Video(_) => push_paragraph(&mut out, "_[video]_"),
+                    UserContent::Document(_) => push_paragraph(&mut out, "_[document]_"),
+                    UserContent::ToolResult(_) => {}
+                }
+            }
+            for item in results {
+                if let UserContent::ToolResult(result) = item {
+                    let _ = write!(out, "\n**Result** `{}`\n", result.id);
+                    push_code(&mut out, "", &tool_result_text(result));
+                }
+            }
+        }
+        Event::AgentCompletion { response } => {
+            out.push_str("\n## Assistant\n");
+            for item in response.choice.iter() {
+                match item {
+                    AssistantContent::Text(text) => push_paragraph(&mut out, &text.text),
+                    AssistantContent::Reasoning(reasoning) => {
+                        let text = reasoning.reasoning.join("\n");
+                        push_paragraph(&mut out, &quote(&text));
+                    }
+                    AssistantContent::ToolCall(call) => push_call(&mut out, "Tool call", call),
+                }
+            }
+        }
+        Event::PlanCreated { tasks } | Event::PlanUpdated { tasks } => {
+            let title = match event {
+                Event::PlanCreated { .. } => "Plan",
+                _ => "Plan updated",
+            };
+            let _ = writeln!(out, "\n**{}**\n", title);
+            for (index, task) in tasks.iter().enumerate() {
+                let _ = writeln!(out, "{}. {}", index + 1, task);
+            }
+        }
+        Event::TaskCompleted { index } => {
+            let _ = write!(out, "\n_Task {} completed_\n", index + 1);
+        }
+        Event::UserInterrupted { content } => {
+            out.push_str("\n## User (interrupt)\n");
+            for item in content.iter() {
+                if let UserContent::Text(text) = item {
+                    push_paragraph(&mut out, &text.text);
+                }
+            }
+        }
+        Event::ApprovalRequired { call } => push_call(&mut out, "Approval required", call),
+        Event::ApprovalResolved { call, decision } => {
+            let decision = match decision {
+                ApprovalDecision::Approve => "approved",
+                ApprovalDecision::Reject => "rejected",
+                ApprovalDecision::Edit { .. } => "approved with edited arguments",
+            };
+            let _ = write!(
+                out,
+                "\n_`{}` [{}] {} by the user_\n",
+                call.function.name, call.id, decision
+            );
+        }
+        Event::LLMConfigUpdated { update } => {
+            let _ = write!(out, "\n_LLM switched to {}_\n", update);
+        }
+        Event::Shutdown => out.push_str("\n_Session ended_\n"),
+        Event::ToolCalls { .. }
+        | Event::ToolResults { .. }
+        | Event::UsageRecorded { .. }
+        | Event::Agent(_) => {}
+    }
+    out
+}
+
+/// Every event of a session in order, with its type, for replaying or attaching to a bug
+pub fn trajectory<T: MQEvent>(session_id: &str, events: &[Event<T>]) -> serde_json::Value {
+    let events: Vec<serde_json::Value> = events
+        .iter()
+        .map(|event| {
+            serde_json::json!({
+                "type": event.event_type(),
+                "version": event.event_version(),
+                "data": event,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "session_id": session_id,
+        "events": events,
+    })
+}
+
+/// Write `<session_id>.md` and `<session_id>.json` into `dir`, returning both paths
+pub fn export<T: MQEvent>(
+    dir: &Path,
+    session_id: &str,
+    events: &[Event<T>],
+) -> eyre::Result<(PathBuf, PathBuf)> {
+    std::fs::create_dir_all(dir)?;
+    let transcript = dir.join(format!("{}.md", session_id));
+    std::fs::write(&transcript, markdown(session_id, events))?;
+    let trajectory_path = dir.join(format!("{}.json", session_id));
+    let json = serde_json::to_string_pretty(&trajectory(session_id, events))?;
+    std::fs::write(&trajectory_path, json)?;
+    Ok((transcript, trajectory_path))
+}
+
+fn push_paragraph(out: &mut String, text: &str) {
+    let _ = write!(out, "\n{}\n", text.trim_end());
+}
+
+fn push_call(out: &mut String, title: &str, call: &ToolCall) {
+    let _ = write!(
+        out,
+        "\n**{}** `{}` [{}]\n",
+        title, call.function.name, call.id
+    );
+    let args = serde_json::to_string_pretty(&call.function.arguments).unwrap_or_default();
+    push_code(out, "json", &args);
+}
+
+/// Fenced block, with a fence longer than any backtick run in `text`
+fn push_code(out: &mut String, lang: &str, text: &str) {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let _ = write!(out, "\n{}{}\n{}\n{}\n", fence, lang, text.trim_end(), fence);
+}
+
+fn quote(text: &str) -> String {
+    text.lines()
+        .map(|line| format!("> {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edda_agent::llm::{CompletionResponse, FinishReason};
+    use rig::message::ToolFunction;
+
+    fn call() -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            call_id: None,
+            function: ToolFunction {
+                name: "write_file".to_string(),
+                arguments: serde_json::json!({"path": "README.md"}),
+            },
+        }
+    }
+
+    #[test]
+    fn test_markdown_transcript() {
+        let events: Vec<Event<()>> = vec![
+            Event::UserCompletion {
+                content: rig::OneOrMany::one(UserContent::text("Add a README")),
+            },
+            Event::AgentCompletion {
+                response: CompletionResponse {
+                    choice: rig::OneOrMany::one(AssistantContent::ToolCall(call())),
+                    finish_reason: FinishReason::ToolUse,
+                    output_tokens: 0,
+                    input_tokens: 0,
+                },
+            },
+            Event::ToolCalls {
+                calls: vec![call()],
+            },
+            Event::ApprovalResolved {
+                call: call(),
+                decision: ApprovalDecision::Reject,
+            },
+            Event::TaskCompleted { index: 0 },
+            Event::Shutdown,
+        ];
+        let expected = "# Session s1\n\
+            \n## User\n\nAdd a README\n\
+            \n## Assistant\n\
+            \n**Tool call** `write_file` [call_1]\n\
+            \n```json\n{\n  \"path\": \"README.md\"\n}\n```\n\
+            \n_`write_file` [call_1] rejected by the user_\n\
+            \n_Task 1 completed_\n\
+            \n_Session ended_\n";
+        assert_eq!(markdown("s1", &events), expected);
+    }
+
+    #[test]
+    fn test_code_fence_outlasts_backticks() {
+        let mut out = String::new();
+        push_code(&mut out, "", "```rust\nfn main() {}\n```");
+        assert_eq!(out, "\n````\n```rust\nfn main() {}\n```\n````\n");
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("one\ntwo"), "> one\n> two");
+    }
+}
@@ -0,0 +1,319 @@
+use crate::export::event_markdown;
+use crate::session::new_session_id;
+use crate::widgets::{is_tool_error, tool_result_text};
+use clap::{Args, ValueEnum};
+use edda_agent::llm::{FinishReason, LLMConfigUpdate};
+use edda_agent::processor::agent::{Agent, AgentState, ApprovalDecision, Command, Event};
+use edda_agent::processor::link::Runtime;
+use edda_mq::{Callback, Envelope, Event as MQEvent, EventQueue};
+use rig::message::ToolResult;
+use serde::Serialize;
+use std::collections::HashSet;
+use tokio::sync::mpsc;
+
+/// Tool whose successful result means the task passed its checks
+const DONE_TOOL: &str = "done";
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Task for the agent
+    #[arg(long)]
+    pub prompt: String,
+    /// Stop after this many LLM completions
+    #[arg(long, default_value_t = 50)]
+    pub max_turns: usize,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+    /// Run tool calls that need confirmation instead of rejecting them
+    #[arg(long)]
+    pub approve: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Markdown transcript, as in `:export`
+    Text,
+    /// One JSON object per event and a final result line
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    /// The agent stopped after its last `done` call failed validation
+    ValidationFailed,
+    /// `--max-turns` was reached before the agent finished
+    MaxTurns,
+    /// The agent stopped without ever calling `done`
+    Unfinished,
+}
+
+impl RunStatus {
+    /// 0 on success, 2 on failed validation, 3 when out of turns, 4 when the agent stopped
+    /// without calling `done`. Errors of the run itself exit with 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RunStatus::Success => 0,
+            RunStatus::ValidationFailed => 2,
+            RunStatus::MaxTurns => 3,
+            RunStatus::Unfinished => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    pub session_id: String,
+    pub status: RunStatus,
+    pub turns: usize,
+    /// Output of the last failed `done` call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+}
+
+/// Progress of a headless run, folded from the session's events
+#[derive(Debug, Default)]
+struct RunTracker {
+    turns: usize,
+    done_calls: HashSet<String>,
+    validation_error: Option<String>,
+}
+
+impl RunTracker {
+    /// The status the run ends with, once `event` finishes it. An agent stopping without a
+    /// passing `done` call hasn't succeeded.
+    fn apply<T>(&mut self, event: &Event<T>, max_turns: usize) -> Option<RunStatus> {
+        match event {
+            Event::AgentCompletion { response } => {
+                self.turns += 1;
+                if response.finish_reason != FinishReason::ToolUse {
+                    return Some(self.stopped());
+                }
+                (self.turns >= max_turns).then_some(RunStatus::MaxTurns)
+            }
+            Event::ToolCalls { calls } => {
+                let done = calls.iter().filter(|c| c.function.name == DONE_TOOL);
+                self.done_calls.extend(done.map(|c| c.id.clone()));
+                None
+            }
+            Event::ToolResults { results } => {
+                let result = results.iter().find(|r| self.done_calls.contains(&r.id))?;
+                self.validation_error = tool_error(result);
+                self.validation_error
+                    .is_none()
+                    .then_some(RunStatus::Success)
+            }
+            // only sent once the agent reported it finished and its work was exported
+            Event::Shutdown => Some(RunStatus::Success),
+            _ => None,
+        }
+    }
+
+    fn stopped(&self) -> RunStatus {
+        match self.validation_error {
+            Some(_) => RunStatus::ValidationFailed,
+            None => RunStatus::Unfinished,
+        }
+    }
+}
+
+fn tool_error(result: &ToolResult) -> Option<String> {
+    is_tool_error(result).then(|| tool_result_text(result))
+}
+
+/// Send the session's events to the run loop
+struct Forwarder<A: Agent> {
+    sender: mpsc::UnboundedSender<Envelope<AgentState<A>>>,
+}
+
+impl<A: Agent> Callback<AgentState<A>> for Forwarder<A> {
+    async fn process(&mut self, envelope: &Envelope<AgentState<A>>) -> eyre::Result<()> {
+        let _ = self.sender.send(envelope.clone());
+        Ok(())
+    }
+}
+
+/// Run `args.prompt` in a new session without the UI, printing progress to stdout until
+/// the agent finishes or runs out of turns. `llm` is applied to the session first.
+pub async fn run<A, ES>(
+    args: &RunArgs,
+    llm: &LLMConfigUpdate,
+    mut runtime: Runtime<AgentState<A>, ES>,
+) -> color_eyre::Result<RunReport>
+where
+    A: Agent + 'static,
+    A::Services: Clone,
+    ES: EventQueue + 'static,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    runtime.listener.push_callback(Forwarder::<A> { sender });
+    runtime.listener.skip_existing().await?;
+    let handler = runtime.handler.clone();
+    let session_id = new_session_id();
+
+    let watch = async {
+        if !llm.is_empty() {
+            let command = Command::UpdateLLMConfig {
+                update: llm.clone(),
+            };
+            handler.execute(&session_id, command).await?;
+        }
+        let text = rig::message::UserContent::text(args.prompt.clone());
+        let command = Command::PutUserMessage {
+            content: rig::OneOrMany::one(text),
+        };
+        handler.execute(&session_id, command).await?;
+
+        let mut tracker = RunTracker::default();
+        while let Some(envelope) = receiver.recv().await {
+            if envelope.aggregate_id != session_id {
+                continue;
+            }
+            let event = envelope.data;
+            print_event(args.output, &event);
+            if let Event::ApprovalRequired { call } = &event {
+                let decision = match args.approve {
+                    true => ApprovalDecision::Approve,
+                    false => ApprovalDecision::Reject,
+                };
+                let command = Command::ResolveApproval {
+                    call_id: call.id.clone(),
+                    decision,
+                };
+                handler.execute(&session_id, command).await?;
+            }
+            if let Some(status) = tracker.apply(&event, args.max_turns) {
+                return Ok(RunReport {
+                    session_id: session_id.clone(),
+                    status,
+                    turns: tracker.turns,
+                    validation_error: tracker.validation_error,
+                });
+            }
+        }
+        Err(color_eyre::eyre::eyre!(
+            "event stream closed before the run finished"
+        ))
+    };
+
+    let report = tokio::select! {
+        res = runtime.start() => {
+            res?;
+            color_eyre::eyre::bail!("runtime stopped before the run finished")
+        }
+        res = watch => res?,
+    };
+    match args.output {
+        OutputFormat::Json => {
+            let mut line = serde_json::to_value(&report)?;
+            line["type"] = "result".into();
+            println!("{}", line);
+        }
+        OutputFormat::Text => println!(
+            "\n---\n{:?} after {} turns, session {}",
+            report.status, report.turns, report.session_id
+        ),
+    }
+    Ok(report)
+}
+
+fn print_event<T: MQEvent>(output: OutputFormat, event: &Event<T>) {
+    match output {
+        OutputFormat::Json => {
+            let line = serde_json::json!({
+                "type": event.event_type(),
+                "data": event,
+            });
+            println!("{}", line);
+        }
+        OutputFormat::Text => print!("{}", event_markdown(event)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use edda_agent::llm::CompletionResponse;
+    use rig::message::{AssistantContent, ToolCall, ToolFunction, ToolResultContent};
+
+    type TestEvent = Event<()>;
+
+    fn completion(finish_reason: FinishReason) -> TestEvent {
+        Event::AgentCompletion {
+            response: CompletionResponse {
+                choice: rig::OneOrMany::one(AssistantContent::text("ok")),
+                finish_reason,
+                output_tokens: 0,
+                input_tokens: 0,
+            },
+        }
+    }
+
+    fn calls(id: &str, name: &str) -> TestEvent {
+        Event::ToolCalls {
+            calls: vec![ToolCall {
+                id: id.to_string(),
+                call_id: None,
+                function: ToolFunction {
+                    name: name.to_string(),
+                    arguments: serde_json::json!({}),
+                },
+            }],
+        }
+    }
+
+    fn results(id: &str, output: serde_json::Value) -> TestEvent {
+        Event::ToolResults {
+            results: vec![ToolResult {
+                id: id.to_string(),
+                call_id: None,
+                content: rig::OneOrMany::one(ToolResultContent::Text(output.to_string().into())),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_passing_done_succeeds() {
+        let mut tracker = RunTracker::default();
+        assert_eq!(tracker.apply(&completion(FinishReason::ToolUse), 10), None);
+        assert_eq!(tracker.apply(&calls("1", DONE_TOOL), 10), None);
+        let status = tracker.apply(&results("1", serde_json::json!("passed")), 10);
+        assert_eq!(status, Some(RunStatus::Success));
+        assert_eq!(tracker.validation_error, None);
+    }
+
+    #[test]
+    fn test_failed_done_then_stop() {
+        let mut tracker = RunTracker::default();
+        tracker.apply(&completion(FinishReason::ToolUse), 10);
+        tracker.apply(&calls("1", DONE_TOOL), 10);
+        let failed = serde_json::json!({"error": "tests failed"});
+        assert_eq!(tracker.apply(&results("1", failed), 10), None);
+        assert_eq!(tracker.validation_error.as_deref(), Some("tests failed"));
+        let status = tracker.apply(&completion(FinishReason::Stop), 10);
+        assert_eq!(status, Some(RunStatus::ValidationFailed));
+    }
+
+    #[test]
+    fn test_stop_without_done_is_unfinished() {
+        let mut tracker = RunTracker::default();
+        tracker.apply(&calls("1", "read_file"), 10);
+        // results of other tools don't count
+        assert_eq!(
+            tracker.apply(&results("1", serde_json::json!("text")), 10),
+            None
+        );
+        let status = tracker.apply(&completion(FinishReason::Stop), 10);
+        assert_eq!(status, Some(RunStatus::Unfinished));
+    }
+
+    #[test]
+    fn test_max_turns() {
+        let mut tracker = RunTracker::default();
+        assert_eq!(tracker.apply(&completion(FinishReason::ToolUse), 2), None);
+        let status = tracker.apply(&completion(FinishReason::ToolUse), 2);
+        assert_eq!(status, Some(RunStatus::MaxTurns));
+        assert_eq!(tracker.turns, 2);
+    }
+}
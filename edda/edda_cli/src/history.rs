@@ -0,0 +1,156 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Entries kept when loading, older ones are dropped
+const MAX_ENTRIES: usize = 1000;
+
+/// Prompts sent from the input box, shared by all sessions and kept across runs as one
+/// JSON string per line
+#[derive(Debug, Default)]
+pub struct PromptHistory {
+    entries: Vec<String>,
+    /// File new entries are appended to, `None` to keep them in memory
+    path: Option<PathBuf>,
+    /// Entry being shown while browsing with the arrow keys
+    position: Option<usize>,
+    /// What was in the input box before browsing started
+    draft: String,
+}
+
+impl PromptHistory {
+    /// History stored at `path`, empty if there is no file yet. Lines that don't parse are
+    /// skipped.
+    pub fn load(path: PathBuf) -> eyre::Result<Self> {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries: Vec<String> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        let excess = entries.len().saturating_sub(MAX_ENTRIES);
+        entries.drain(..excess);
+        Ok(Self {
+            entries,
+            path: Some(path),
+            ..Default::default()
+        })
+    }
+
+    /// Record a sent prompt, unless it repeats the previous one
+    pub fn push(&mut self, entry: &str) -> eyre::Result<()> {
+        self.reset();
+        if entry.trim().is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return Ok(());
+        }
+        self.entries.push(entry.to_string());
+        if let Some(path) = &self.path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+        Ok(())
+    }
+
+    /// The entry before the one shown, starting from the latest. `current` is kept to come
+    /// back to once browsing goes past the latest entry.
+    pub fn older(&mut self, current: &str) -> Option<&str> {
+        let position = match self.position {
+            None if self.entries.is_empty() => return None,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len() - 1
+            }
+            Some(position) => position.saturating_sub(1),
+        };
+        self.position = Some(position);
+        Some(&self.entries[position])
+    }
+
+    /// The entry after the one shown, or the draft after the latest one
+    pub fn newer(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+        match position < self.entries.len() {
+            true => {
+                self.position = Some(position);
+                Some(&self.entries[position])
+            }
+            false => {
+                self.position = None;
+                Some(&self.draft)
+            }
+        }
+    }
+
+    /// Stop browsing, the input box was edited
+    pub fn reset(&mut self) {
+        self.position = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browsing_returns_to_draft() {
+        let mut history = PromptHistory::default();
+        assert_eq!(history.older("draft"), None);
+        history.push("first").unwrap();
+        history.push("second").unwrap();
+
+        assert_eq!(history.older("draft"), Some("second"));
+        assert_eq!(history.older("ignored"), Some("first"));
+        // stays at the oldest entry
+        assert_eq!(history.older("ignored"), Some("first"));
+        assert_eq!(history.newer(), Some("second"));
+        assert_eq!(history.newer(), Some("draft"));
+        assert_eq!(history.newer(), None);
+    }
+
+    #[test]
+    fn test_push_skips_blank_and_repeated() {
+        let mut history = PromptHistory::default();
+        history.push("same").unwrap();
+        history.push("same").unwrap();
+        history.push("  ").unwrap();
+        assert_eq!(history.entries, vec!["same"]);
+    }
+
+    #[test]
+    fn test_persisted_across_loads() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("edda").join("history.jsonl");
+        let mut history = PromptHistory::load(path.clone()).unwrap();
+        assert!(history.entries.is_empty());
+        history.push("build a dashboard\nwith two charts").unwrap();
+
+        std::fs::write(
+            &path,
+            format!("{}not json\n", std::fs::read_to_string(&path).unwrap()),
+        )
+        .unwrap();
+        let history = PromptHistory::load(path).unwrap();
+        assert_eq!(history.entries, vec!["build a dashboard\nwith two charts"]);
+    }
+
+    #[test]
+    fn test_load_keeps_latest_entries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let lines: String = (0..MAX_ENTRIES + 5)
+            .map(|n| format!("\"prompt {}\"\n", n))
+            .collect();
+        std::fs::write(&path, lines).unwrap();
+        let history = PromptHistory::load(path).unwrap();
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(history.entries[0], "prompt 5");
+    }
+}
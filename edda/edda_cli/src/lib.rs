@@ -1,6 +1,17 @@
 pub mod app;
+pub mod attach;
+pub mod command;
+pub mod config;
 pub mod events;
+pub mod export;
+pub mod headless;
+pub mod history;
+pub mod search;
+pub mod session;
+pub mod tab;
 pub mod ui;
+pub mod usage;
 pub mod widgets;
 pub use app::App;
 pub use events::{CliEvent, EventHandler};
+pub use session::{Cli, launch};
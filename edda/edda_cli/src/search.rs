@@ -0,0 +1,154 @@
+use ratatui::style::Style;
+use ratatui::text::{Line, Span, Text};
+
+/// Pattern searched in the rendered event list, ignoring ASCII case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Search {
+    pattern: String,
+}
+
+impl Search {
+    /// `None` for an empty pattern, which would match everything
+    pub fn new(pattern: &str) -> Option<Self> {
+        (!pattern.is_empty()).then(|| Self {
+            pattern: pattern.to_ascii_lowercase(),
+        })
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// A line of `text` contains the pattern
+    pub fn matches(&self, text: &Text) -> bool {
+        text.lines
+            .iter()
+            .any(|line| !self.ranges(&line_content(line)).is_empty())
+    }
+
+    /// Byte ranges of the pattern in `content`. Only ASCII is folded, so they hold in the
+    /// original as well.
+    fn ranges(&self, content: &str) -> Vec<(usize, usize)> {
+        content
+            .to_ascii_lowercase()
+            .match_indices(&self.pattern)
+            .map(|(start, found)| (start, start + found.len()))
+            .collect()
+    }
+
+    /// `text` with every match patched with `style`, also across span boundaries
+    pub fn highlight<'a>(&self, mut text: Text<'a>, style: Style) -> Text<'a> {
+        for line in text.lines.iter_mut() {
+            let ranges = self.ranges(&line_content(line));
+            if ranges.is_empty() {
+                continue;
+            }
+            let mut spans = Vec::new();
+            let mut offset = 0;
+            for span in line.spans.drain(..) {
+                let end = offset + span.content.len();
+                let mut cuts = vec![offset, end];
+                for (start, stop) in ranges.iter() {
+                    cuts.extend(
+                        [*start, *stop]
+                            .into_iter()
+                            .filter(|c| *c > offset && *c < end),
+                    );
+                }
+                cuts.sort_unstable();
+                cuts.dedup();
+                for part in cuts.windows(2) {
+                    let (from, to) = (part[0], part[1]);
+                    let matched = ranges
+                        .iter()
+                        .any(|(start, stop)| from >= *start && to <= *stop);
+                    let part_style = match matched {
+                        true => span.style.patch(style),
+                        false => span.style,
+                    };
+                    let content = span.content[from - offset..to - offset].to_string();
+                    spans.push(Span::styled(content, part_style));
+                }
+                offset = end;
+            }
+            line.spans = spans;
+        }
+        text
+    }
+}
+
+fn line_content(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// First match after `selected`, wrapping around to the first one
+pub fn next_match(matches: &[usize], selected: Option<usize>) -> Option<usize> {
+    let after = selected.and_then(|s| matches.iter().find(|m| **m > s));
+    after.or(matches.first()).copied()
+}
+
+/// Last match before `selected`, wrapping around to the last one
+pub fn previous_match(matches: &[usize], selected: Option<usize>) -> Option<usize> {
+    let before = selected.and_then(|s| matches.iter().rev().find(|m| **m < s));
+    before.or(matches.last()).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Modifier};
+
+    #[test]
+    fn test_empty_pattern() {
+        assert_eq!(Search::new(""), None);
+        assert_eq!(Search::new("Read").unwrap().pattern(), "read");
+    }
+
+    #[test]
+    fn test_matches_ignoring_case() {
+        let search = Search::new("FILE").unwrap();
+        assert!(search.matches(&Text::from("read_file src/main.rs")));
+        assert!(!search.matches(&Text::from("write_dir")));
+        // a match may span several spans
+        let line = Line::from(vec![Span::raw("read_fi"), Span::raw("le")]);
+        assert!(search.matches(&Text::from(line)));
+    }
+
+    #[test]
+    fn test_highlight_across_spans() {
+        let search = Search::new("ab").unwrap();
+        let base = Style::default().fg(Color::Red);
+        let line = Line::from(vec![Span::styled("xa", base), Span::raw("bab")]);
+        let marked = Style::default().add_modifier(Modifier::REVERSED);
+        let text = search.highlight(Text::from(line), marked);
+        let spans: Vec<(&str, Style)> = text.lines[0]
+            .spans
+            .iter()
+            .map(|span| (span.content.as_ref(), span.style))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("x", base),
+                ("a", base.patch(marked)),
+                ("b", marked),
+                ("ab", marked),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_navigation_wraps() {
+        let matches = [2, 5, 9];
+        assert_eq!(next_match(&matches, None), Some(2));
+        assert_eq!(next_match(&matches, Some(5)), Some(9));
+        assert_eq!(next_match(&matches, Some(9)), Some(2));
+        assert_eq!(previous_match(&matches, None), Some(9));
+        assert_eq!(previous_match(&matches, Some(5)), Some(2));
+        assert_eq!(previous_match(&matches, Some(2)), Some(9));
+        assert_eq!(next_match(&[], Some(1)), None);
+    }
+}
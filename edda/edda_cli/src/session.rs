@@ -0,0 +1,276 @@
+use crate::attach::{self, AttachArgs};
+use crate::config::Config;
+use crate::headless::{self, RunArgs};
+use crate::history::PromptHistory;
+use crate::{App, export};
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use edda_agent::llm::{LLMConfigUpdate, LLMProvider};
+use edda_agent::processor::agent::{Agent, AgentState, Event};
+use edda_agent::processor::link::Runtime;
+use edda_mq::db::sqlite::SqliteStore;
+use edda_mq::{EventQueue, EventStore, PollingQueue};
+use rig::message::UserContent;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::path::{Path, PathBuf};
+
+/// Overrides where sessions are stored
+pub const DATA_DIR_ENV: &str = "EDDA_DATA_DIR";
+const DATABASE_FILE: &str = "sessions.db";
+const HISTORY_FILE: &str = "prompts.jsonl";
+const STREAM_ID: &str = "agent";
+const PREVIEW_CHARS: usize = 60;
+
+#[derive(Debug, Parser)]
+pub struct Cli {
+    /// Directory holding the session store (default: $EDDA_DATA_DIR or ~/.local/share/edda)
+    #[arg(long, global = true)]
+    pub data_dir: Option<PathBuf>,
+    /// Write the session as Markdown and JSON when the UI exits, into DIR or the current directory
+    #[arg(long, global = true, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+    pub export_on_exit: Option<PathBuf>,
+    /// Spending in USD from which the status bar warns
+    #[arg(long, global = true, value_name = "USD")]
+    pub budget: Option<f64>,
+    /// Theme and key bindings of the UI (default: $EDDA_CONFIG or ~/.config/edda/config.toml)
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    #[command(flatten)]
+    pub llm: LLMArgs,
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+/// LLM of the sessions started or resumed, instead of the agent's default
+#[derive(Debug, Default, Args)]
+pub struct LLMArgs {
+    /// anthropic, gemini or openrouter, with its default model unless --model is given
+    #[arg(long, global = true)]
+    pub provider: Option<LLMProvider>,
+    #[arg(long, global = true)]
+    pub model: Option<String>,
+    #[arg(long, global = true)]
+    pub temperature: Option<f64>,
+    #[arg(long, global = true)]
+    pub max_tokens: Option<u64>,
+}
+
+impl LLMArgs {
+    pub fn update(&self) -> LLMConfigUpdate {
+        LLMConfigUpdate {
+            provider: self.provider,
+            model: self.model.clone(),
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Manage stored sessions
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    /// Continue stored sessions, one tab each
+    Resume {
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+    /// Run a single prompt without the UI, for scripts and CI
+    Run(RunArgs),
+    /// Watch sessions of a shared Postgres store without taking part in them
+    Attach(AttachArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionsCommand {
+    /// List stored sessions, most recent first
+    List,
+}
+
+impl Cli {
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.clone().unwrap_or_else(data_dir)
+    }
+}
+
+pub fn data_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var(DATA_DIR_ENV) {
+        return PathBuf::from(dir);
+    }
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(".local").join("share")
+        });
+    data_home.join("edda")
+}
+
+/// Run the command line against `runtime`: list its stored sessions, run a prompt
+/// headless, or open the UI on a resumed or new session.
+pub async fn launch<A, ES>(
+    cli: &Cli,
+    mut runtime: Runtime<AgentState<A>, ES>,
+) -> color_eyre::Result<()>
+where
+    A: Agent + 'static,
+    A::Services: Clone,
+    ES: EventQueue + 'static,
+{
+    let mut app = match &cli.command {
+        Some(CliCommand::Sessions {
+            command: SessionsCommand::List,
+        }) => {
+            print_sessions(&list_sessions::<A, _>(runtime.handler.store()).await?);
+            return Ok(());
+        }
+        Some(CliCommand::Run(args)) => {
+            let handler = runtime.handler.clone();
+            let report = headless::run(args, &cli.llm.update(), runtime).await?;
+            if let Some(dir) = &cli.export_on_exit {
+                let events = handler.load_events(&report.session_id).await?;
+                export::export(dir, &report.session_id, &events)?;
+            }
+            std::process::exit(report.status.exit_code());
+        }
+        Some(CliCommand::Attach(args)) => {
+            return attach::run::<A>(args, cli, runtime.services.clone()).await;
+        }
+        Some(CliCommand::Resume { ids }) => App::resume(&mut runtime, ids).await?,
+        None => {
+            // other stored sessions are history, not work for the handlers
+            runtime.listener.skip_existing().await?;
+            App::new(&mut runtime, new_session_id())?
+        }
+    };
+    configure(&mut app, cli)?;
+    app.llm = cli.llm.update();
+    for index in 0..app.tabs.len() {
+        app.update_llm(index, &app.llm).await?;
+    }
+    let handler = runtime.handler.clone();
+
+    let terminal = ratatui::init();
+    let result = tokio::select! {
+        res = runtime.start() => res,
+        res = app.run(terminal) => res,
+    };
+    ratatui::restore();
+    let session_ids: Vec<&str> = app.tabs.iter().map(|t| t.aggregate_id.as_str()).collect();
+    if let Some(dir) = &cli.export_on_exit {
+        for session_id in session_ids.iter() {
+            let events = handler.load_events(session_id).await?;
+            let (transcript, trajectory) = export::export(dir, session_id, &events)?;
+            println!(
+                "Exported {} and {}",
+                transcript.display(),
+                trajectory.display()
+            );
+        }
+    }
+    println!(
+        "Sessions are saved, continue them with `resume {}`",
+        session_ids.join(" ")
+    );
+    result
+}
+
+/// Apply the UI settings of the command line and the config file
+pub fn configure<A, ES>(app: &mut App<A, ES>, cli: &Cli) -> eyre::Result<()>
+where
+    A: Agent,
+    ES: EventStore,
+{
+    app.budget = cli.budget;
+    app.config = Config::load(&cli.config.clone().unwrap_or_else(Config::path))?;
+    app.prompt_history = PromptHistory::load(cli.data_dir().join(HISTORY_FILE))?;
+    Ok(())
+}
+
+/// File-backed event store under `data_dir`, created on first use
+pub async fn open_store(data_dir: &Path) -> eyre::Result<PollingQueue<SqliteStore>> {
+    std::fs::create_dir_all(data_dir)?;
+    let options = SqliteConnectOptions::new()
+        .filename(data_dir.join(DATABASE_FILE))
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await?;
+    let store = SqliteStore::new(pool, STREAM_ID);
+    store.migrate().await;
+    Ok(PollingQueue::new(store))
+}
+
+/// Ids are UUIDv7 so they sort by creation time
+pub fn new_session_id() -> String {
+    uuid::Uuid::now_v7().to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub events: i64,
+    /// Start of the first user message
+    pub preview: Option<String>,
+}
+
+pub async fn list_sessions<A: Agent, ES: EventStore>(store: &ES) -> eyre::Result<Vec<SessionInfo>> {
+    let mut sessions = Vec::new();
+    for (id, events) in store.load_sequence_nums::<AgentState<A>>().await? {
+        let history = store.load_events::<AgentState<A>>(&id).await?;
+        let preview = history.iter().find_map(|envelope| match &envelope.data {
+            Event::UserCompletion { content } => content.iter().find_map(|item| match item {
+                UserContent::Text(text) => Some(preview(&text.text)),
+                _ => None,
+            }),
+            _ => None,
+        });
+        sessions.push(SessionInfo {
+            started_at: started_at(&id),
+            id,
+            events,
+            preview,
+        });
+    }
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+    Ok(sessions)
+}
+
+pub fn print_sessions(sessions: &[SessionInfo]) {
+    if sessions.is_empty() {
+        println!("No sessions yet");
+        return;
+    }
+    for session in sessions {
+        let started_at = session
+            .started_at
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{}  {}  {:>4} events  {}",
+            session.id,
+            started_at,
+            session.events,
+            session.preview.as_deref().unwrap_or("")
+        );
+    }
+}
+
+fn started_at(id: &str) -> Option<DateTime<Utc>> {
+    let (secs, nanos) = uuid::Uuid::parse_str(id).ok()?.get_timestamp()?.to_unix();
+    DateTime::from_timestamp(secs as i64, nanos)
+}
+
+fn preview(text: &str) -> String {
+    let line = text
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    match line.chars().count() > PREVIEW_CHARS {
+        true => format!("{}…", line.chars().take(PREVIEW_CHARS).collect::<String>()),
+        false => line.to_string(),
+    }
+}
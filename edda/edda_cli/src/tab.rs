@@ -0,0 +1,181 @@
+use crate::usage::UsageTracker;
+use crate::widgets::{FileChanges, ToolRuns};
+use crossterm::event::KeyCode;
+use edda_agent::llm::{FinishReason, LLMConfigUpdate};
+use edda_agent::processor::agent::Event;
+use edda_agent::processor::plan::Plan;
+use ratatui::widgets::ListState;
+use rig::message::{ToolCall, UserContent};
+
+const TITLE_CHARS: usize = 20;
+
+/// One session in the UI: its history and the state folded from it
+pub struct Tab<T> {
+    pub aggregate_id: String,
+    pub history: Vec<Event<T>>,
+    /// Sequence of the last event in `history`, events up to it are already shown
+    pub last_sequence: i64,
+    /// Folded from the plan events in `history`
+    pub plan: Plan,
+    /// Tool calls waiting for the user's decision, oldest first
+    pub approvals: Vec<ToolCall>,
+    /// The agent is mid-turn, a message sent now steers it
+    pub working: bool,
+    pub tool_runs: ToolRuns,
+    /// Offset into the tool output, `None` to follow its end
+    pub output_scroll: Option<u16>,
+    /// Offset of the end of the tool output as last rendered
+    pub output_tail: u16,
+    pub file_changes: FileChanges,
+    pub diff_selected: usize,
+    pub diff_scroll: u16,
+    pub usage: UsageTracker,
+    /// LLM settings switched to in this session
+    pub llm: LLMConfigUpdate,
+    pub list_state: ListState,
+    pub auto_scroll: bool,
+}
+
+impl<T> Tab<T> {
+    pub fn new(aggregate_id: String) -> Self {
+        Self {
+            aggregate_id,
+            history: Vec::new(),
+            last_sequence: 0,
+            plan: Plan::default(),
+            approvals: Vec::new(),
+            working: false,
+            tool_runs: ToolRuns::default(),
+            output_scroll: None,
+            output_tail: 0,
+            file_changes: FileChanges::default(),
+            diff_selected: 0,
+            diff_scroll: 0,
+            usage: UsageTracker::default(),
+            llm: LLMConfigUpdate::default(),
+            list_state: ListState::default(),
+            auto_scroll: true,
+        }
+    }
+
+    /// Add an event of the session, skipping ones loaded with its stored history
+    pub fn push(&mut self, sequence: i64, event: Event<T>) {
+        if sequence <= self.last_sequence {
+            return;
+        }
+        self.last_sequence = sequence;
+        self.observe(&event);
+        self.history.push(event);
+        if self.auto_scroll {
+            self.list_state.select(Some(self.history.len() - 1));
+        }
+    }
+
+    /// Fold an agent event into the state shown next to the history
+    fn observe(&mut self, event: &Event<T>) {
+        self.plan.apply(event);
+        self.tool_runs.apply(event);
+        self.file_changes.apply(event);
+        self.usage.apply(event);
+        match event {
+            Event::ApprovalRequired { call } => self.approvals.push(call.clone()),
+            Event::ApprovalResolved { call, .. } => self.approvals.retain(|c| c.id != call.id),
+            Event::ToolCalls { .. } => self.output_scroll = None,
+            Event::UserCompletion { .. } => self.working = true,
+            Event::LLMConfigUpdated { update } => self.llm.merge(update),
+            Event::AgentCompletion { response } => {
+                self.working = response.finish_reason == FinishReason::ToolUse;
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the selection in the event list
+    pub fn navigate(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => {
+                self.auto_scroll = false;
+                if let Some(selected) = self.list_state.selected() {
+                    if selected > 0 {
+                        self.list_state.select(Some(selected - 1));
+                    }
+                } else if !self.history.is_empty() {
+                    self.list_state.select(Some(self.history.len() - 1));
+                }
+            }
+            KeyCode::Down => {
+                if let Some(selected) = self.list_state.selected() {
+                    if selected < self.history.len() - 1 {
+                        self.list_state.select(Some(selected + 1));
+                        // Re-enable auto-scroll if we reach the bottom
+                        if selected + 1 == self.history.len() - 1 {
+                            self.auto_scroll = true;
+                        }
+                    }
+                } else if !self.history.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+            }
+            KeyCode::PageUp => {
+                self.auto_scroll = false;
+                if !self.history.is_empty() {
+                    let current = self.list_state.selected().unwrap_or(self.history.len() - 1);
+                    let new_pos = current.saturating_sub(10);
+                    self.list_state.select(Some(new_pos));
+                }
+            }
+            KeyCode::PageDown if !self.history.is_empty() => {
+                let current = self.list_state.selected().unwrap_or(0);
+                let new_pos = (current + 10).min(self.history.len() - 1);
+                self.list_state.select(Some(new_pos));
+                // Re-enable auto-scroll if we reach the bottom
+                if new_pos == self.history.len() - 1 {
+                    self.auto_scroll = true;
+                }
+            }
+            KeyCode::Home => {
+                self.auto_scroll = false;
+                if !self.history.is_empty() {
+                    self.list_state.select(Some(0));
+                }
+            }
+            KeyCode::End => {
+                self.auto_scroll = true;
+                if !self.history.is_empty() {
+                    self.list_state.select(Some(self.history.len() - 1));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn select_change(&mut self, index: usize) {
+        let last = self.file_changes.changes.len().saturating_sub(1);
+        self.diff_selected = index.min(last);
+        self.diff_scroll = 0;
+    }
+
+    /// Start of the first user message, marked when the session needs attention
+    pub fn title(&self) -> String {
+        let message = self.history.iter().find_map(|event| match event {
+            Event::UserCompletion { content } => content.iter().find_map(|item| match item {
+                UserContent::Text(text) => text.text.lines().find(|l| !l.trim().is_empty()),
+                _ => None,
+            }),
+            _ => None,
+        });
+        let mut title = match message.map(str::trim) {
+            Some(message) if message.chars().count() > TITLE_CHARS => {
+                format!("{}…", message.chars().take(TITLE_CHARS).collect::<String>())
+            }
+            Some(message) => message.to_string(),
+            None => "new session".to_string(),
+        };
+        if !self.approvals.is_empty() {
+            title.push_str(" [approval]");
+        } else if self.working {
+            title.push_str(" [working]");
+        }
+        title
+    }
+}
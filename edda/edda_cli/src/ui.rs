@@ -1,5 +1,9 @@
 use crate::App;
-use crate::widgets::event_as_text;
+use crate::config::Action;
+use crate::usage::BudgetState;
+use crate::widgets::{
+    ApprovalModal, DiffView, OutputPane, PlanPanel, Popup, TabBar, event_as_text,
+};
 use edda_agent::processor::agent::Agent;
 use edda_mq::EventStore;
 use ratatui::{
@@ -9,57 +13,213 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph, StatefulWidget, Widget},
 };
 
+const MAX_INPUT_LINES: usize = 8;
+
 impl<A: Agent, ES: EventStore> Widget for &mut App<A, ES> {
     fn render(self, area: Rect, buf: &mut Buffer)
     where
         Self: Sized,
     {
+        // the tab bar only takes a line once there is more than one session
+        let tab_bar = match self.tabs.len() > 1 {
+            true => 1,
+            false => 0,
+        };
+        let screen = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(tab_bar),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(area);
+        if tab_bar > 0 {
+            TabBar::new(&self.tabs, self.active, &self.config.theme).render(screen[0], buf);
+        }
+        self.draw_status(screen[2], buf);
+
         let main_chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
-            .split(area);
+            .split(screen[1]);
 
         let content_chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(3), Constraint::Length(3)])
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(self.input_lines() + 2),
+            ])
             .split(main_chunks[0]);
 
-        self.draw_messages(content_chunks[0], buf);
+        let message_area = match self.output_open && !self.tab().tool_runs.runs.is_empty() {
+            true => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(3), Constraint::Percentage(40)])
+                    .split(content_chunks[0]);
+                self.draw_output(chunks[1], buf);
+                chunks[0]
+            }
+            false => content_chunks[0],
+        };
+
+        match self.diff_open {
+            true => DiffView::new(
+                &self.tab().file_changes,
+                self.tab().diff_selected,
+                self.tab().diff_scroll,
+                &self.config.theme,
+                &self.config.keys,
+            )
+            .render(message_area, buf),
+            false => self.draw_messages(message_area, buf),
+        }
         self.draw_input(content_chunks[1], buf);
+        PlanPanel::new(&self.tab().plan, &self.config.theme).render(main_chunks[1], buf);
+
+        if let Some((title, text)) = &self.popup {
+            Popup::new(title, text).render(centered(content_chunks[0]), buf);
+        }
+
+        // over the messages only, the input box is used to edit arguments
+        if let Some(call) = self.tab().approvals.first() {
+            let modal = centered(content_chunks[0]);
+            ApprovalModal::new(
+                call,
+                self.tab().approvals.len(),
+                self.editing_approval,
+                &self.config.theme,
+                &self.config.keys,
+            )
+            .render(modal, buf);
+        }
     }
 }
 
 impl<A: Agent, ES: EventStore> App<A, ES> {
     fn draw_messages(&mut self, area: Rect, buf: &mut Buffer) {
-        let items: Vec<ListItem> = self
+        let search = self.current_search();
+        let highlight = Style::new().fg(self.config.theme.accent).reversed();
+        let tab = &mut self.tabs[self.active];
+        let mut matches = Vec::new();
+        let items: Vec<ListItem> = tab
             .history
             .iter()
-            .filter_map(|event| event_as_text(event).map(ListItem::new))
+            .filter_map(|event| event_as_text(event, &self.config.theme))
+            .enumerate()
+            .map(|(index, text)| match &search {
+                Some(search) if search.matches(&text) => {
+                    matches.push(index);
+                    ListItem::new(search.highlight(text, highlight))
+                }
+                _ => ListItem::new(text),
+            })
             .collect();
 
-        let title = if self.auto_scroll {
-            "Event List (Auto-scroll ON | Use ↑↓ to navigate)"
-        } else {
-            "Event List (Auto-scroll OFF | Press End to re-enable)"
+        let title = match &search {
+            Some(search) => {
+                let selected = tab.list_state.selected();
+                let position = matches.iter().position(|m| Some(*m) == selected);
+                let count = match position {
+                    Some(position) => format!("match {}/{}", position + 1, matches.len()),
+                    None => format!("{} matches", matches.len()),
+                };
+                format!(
+                    "Event List ({} for {:?} | {}/{} next/previous, Esc to clear)",
+                    count,
+                    search.pattern(),
+                    self.config.keys.label(Action::SearchNext),
+                    self.config.keys.label(Action::SearchPrevious)
+                )
+            }
+            None if tab.auto_scroll => {
+                "Event List (Auto-scroll ON | Alt+↑↓ to navigate, /pattern to search)".to_string()
+            }
+            None => "Event List (Auto-scroll OFF | Press End to re-enable)".to_string(),
         };
 
         let messages_list = List::new(items)
             .block(Block::default().borders(Borders::ALL).title(title))
-            .highlight_style(Style::default().yellow())
+            .highlight_style(Style::default().fg(self.config.theme.accent))
             .highlight_symbol(">> ");
 
-        StatefulWidget::render(messages_list, area, buf, &mut self.list_state);
+        StatefulWidget::render(messages_list, area, buf, &mut tab.list_state);
+    }
+
+    fn draw_output(&mut self, area: Rect, buf: &mut Buffer) {
+        let tab = &mut self.tabs[self.active];
+        let pane = OutputPane::new(
+            &tab.tool_runs,
+            tab.output_scroll,
+            &self.config.theme,
+            &self.config.keys,
+        );
+        tab.output_tail = pane.tail(area);
+        pane.render(area, buf);
+    }
+
+    fn draw_status(&self, area: Rect, buf: &mut Buffer) {
+        let theme = &self.config.theme;
+        let usage = &self.tab().usage;
+        let style = match self.budget.map(|budget| usage.budget_state(budget)) {
+            Some(BudgetState::Over) => Style::default().fg(theme.error).bold(),
+            Some(BudgetState::Near) => Style::default().fg(theme.accent),
+            _ => Style::default().fg(theme.muted),
+        };
+        Paragraph::new(usage.summary(self.budget))
+            .style(style)
+            .render(area, buf);
+    }
+
+    /// Height of the input text, growing with its lines up to a limit
+    fn input_lines(&self) -> u16 {
+        let lines = self.input_buffer.split('\n').count();
+        lines.clamp(1, MAX_INPUT_LINES) as u16
     }
 
     fn draw_input(&self, area: Rect, buf: &mut Buffer) {
+        let tab = self.tab();
+        let title = match (self.editing_approval, tab.approvals.is_empty()) {
+            (true, _) => match &self.status {
+                Some(error) => format!("Tool arguments ({})", error),
+                None => "Tool arguments (JSON, Enter to run)".to_string(),
+            },
+            (false, _) if self.read_only && self.status.is_none() && !self.searching() => {
+                "Read-only (attached, /pattern to search, :export :cost :open <id>)".to_string()
+            }
+            (false, false) => "Input (paused until the tool call is decided)".to_string(),
+            (false, true) if self.searching() => {
+                "Search (Enter to keep the matches highlighted, Esc to cancel)".to_string()
+            }
+            (false, true) => match (&self.status, tab.working) {
+                (Some(status), _) => format!("Input ({})", status),
+                (None, true) => "Input (agent working, Enter to steer it)".to_string(),
+                (None, false) => format!(
+                    "Input (Enter to send, {} new line, {} editor, :export :cost :new :open :close)",
+                    self.config.keys.label(Action::Newline),
+                    self.config.keys.label(Action::OpenEditor)
+                ),
+            },
+        };
+        // the end of a long input stays in view
+        let lines = self.input_buffer.split('\n').count() as u16;
         let input = Paragraph::new(self.input_buffer.as_str())
             .style(Style::default())
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Input (Enter to send)"),
-            );
+            .scroll((lines.saturating_sub(self.input_lines()), 0))
+            .block(Block::default().borders(Borders::ALL).title(title));
 
         input.render(area, buf);
     }
 }
+
+/// Middle of `area`, 80% of its size
+fn centered(area: Rect) -> Rect {
+    let width = area.width * 4 / 5;
+    let height = area.height * 4 / 5;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
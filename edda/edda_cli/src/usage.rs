@@ -0,0 +1,252 @@
+use edda_agent::llm::Usage;
+use edda_agent::processor::agent::Event;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Share of the budget from which the footer warns
+const BUDGET_WARNING: f64 = 0.8;
+const HEAVIEST_TURNS: usize = 5;
+
+/// USD per million input and output tokens, by model name prefix. Provider prefixes such as
+/// `anthropic/` are ignored.
+const PRICES: &[(&str, f64, f64)] = &[
+    ("claude-opus-4", 15.0, 75.0),
+    ("claude-sonnet-4", 3.0, 15.0),
+    ("claude-haiku-4", 1.0, 5.0),
+    ("claude-3-5-haiku", 0.8, 4.0),
+    ("gemini-2.5-pro", 1.25, 10.0),
+    ("gemini-2.5-flash", 0.3, 2.5),
+];
+
+/// Cost of `usage` in USD, `None` for models without a known price
+pub fn cost(usage: &Usage) -> Option<f64> {
+    let model = usage.model.rsplit('/').next().unwrap_or(&usage.model);
+    let (_, input, output) = PRICES
+        .iter()
+        .find(|(prefix, _, _)| model.starts_with(prefix))?;
+    Some((usage.input_tokens as f64 * input + usage.output_tokens as f64 * output) / 1e6)
+}
+
+#[derive(Debug, Clone)]
+pub struct TurnUsage {
+    pub usage: Usage,
+    /// Tools the completion called
+    pub tools: Vec<String>,
+}
+
+/// Token usage of a session, folded from its `UsageRecorded` events
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    pub turns: Vec<TurnUsage>,
+    /// Tool calls of the completion whose usage comes next
+    last_tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetState {
+    Under,
+    Near,
+    Over,
+}
+
+impl UsageTracker {
+    pub fn apply<T>(&mut self, event: &Event<T>) {
+        match event {
+            Event::AgentCompletion { .. } => self.last_tools.clear(),
+            Event::ToolCalls { calls } => {
+                self.last_tools = calls.iter().map(|c| c.function.name.clone()).collect();
+            }
+            Event::UsageRecorded { usage } => self.turns.push(TurnUsage {
+                usage: usage.clone(),
+                tools: std::mem::take(&mut self.last_tools),
+            }),
+            _ => {}
+        }
+    }
+
+    pub fn input_tokens(&self) -> u64 {
+        self.turns.iter().map(|t| t.usage.input_tokens).sum()
+    }
+
+    pub fn output_tokens(&self) -> u64 {
+        self.turns.iter().map(|t| t.usage.output_tokens).sum()
+    }
+
+    /// Total cost, leaving out models without a known price
+    pub fn cost(&self) -> f64 {
+        self.turns.iter().filter_map(|t| cost(&t.usage)).sum()
+    }
+
+    pub fn budget_state(&self, budget: f64) -> BudgetState {
+        match self.cost() {
+            cost if cost >= budget => BudgetState::Over,
+            cost if cost >= budget * BUDGET_WARNING => BudgetState::Near,
+            _ => BudgetState::Under,
+        }
+    }
+
+    /// One line for the status bar
+    pub fn summary(&self, budget: Option<f64>) -> String {
+        let mut summary = format!(
+            "tokens in {} / out {} | ${:.2}",
+            tokens(self.input_tokens()),
+            tokens(self.output_tokens()),
+            self.cost()
+        );
+        if let Some(budget) = budget {
+            let _ = write!(summary, " of ${:.2}", budget);
+            match self.budget_state(budget) {
+                BudgetState::Over => summary.push_str(" (budget exceeded)"),
+                BudgetState::Near => summary.push_str(" (near budget)"),
+                BudgetState::Under => {}
+            }
+        }
+        summary
+    }
+
+    /// Usage per model and the turns that used the most tokens, for `:cost`
+    pub fn report(&self) -> String {
+        if self.turns.is_empty() {
+            return "No usage recorded yet".to_string();
+        }
+        let mut models: BTreeMap<&str, Usage> = BTreeMap::new();
+        for turn in self.turns.iter() {
+            let entry = models
+                .entry(turn.usage.model.as_str())
+                .or_insert_with(|| Usage {
+                    model: turn.usage.model.clone(),
+                    ..Default::default()
+                });
+            entry.input_tokens += turn.usage.input_tokens;
+            entry.output_tokens += turn.usage.output_tokens;
+        }
+        let mut report = String::from("By model\n");
+        for usage in models.values() {
+            let _ = writeln!(
+                report,
+                "  {}: in {} / out {}, {}",
+                usage.model,
+                tokens(usage.input_tokens),
+                tokens(usage.output_tokens),
+                price(cost(usage))
+            );
+        }
+
+        let mut turns: Vec<(usize, &TurnUsage)> = self.turns.iter().enumerate().collect();
+        turns.sort_by_key(|(_, t)| Reverse(t.usage.input_tokens + t.usage.output_tokens));
+        report.push_str("\nHeaviest turns\n");
+        for (index, turn) in turns.into_iter().take(HEAVIEST_TURNS) {
+            let tools = match turn.tools.is_empty() {
+                true => "no tools".to_string(),
+                false => turn.tools.join(", "),
+            };
+            let _ = writeln!(
+                report,
+                "  #{}: in {} / out {}, {} ({})",
+                index + 1,
+                tokens(turn.usage.input_tokens),
+                tokens(turn.usage.output_tokens),
+                price(cost(&turn.usage)),
+                tools
+            );
+        }
+        let _ = write!(report, "\nTotal: {}", self.summary(None));
+        report
+    }
+}
+
+fn tokens(count: u64) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..1_000_000 => format!("{:.1}k", count as f64 / 1e3),
+        _ => format!("{:.1}M", count as f64 / 1e6),
+    }
+}
+
+fn price(cost: Option<f64>) -> String {
+    match cost {
+        Some(cost) => format!("${:.2}", cost),
+        None => "unknown price".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(model: &str, input_tokens: u64, output_tokens: u64) -> Event<()> {
+        Event::UsageRecorded {
+            usage: Usage {
+                model: model.to_string(),
+                input_tokens,
+                output_tokens,
+            },
+        }
+    }
+
+    #[test]
+    fn test_cost() {
+        let usage = Usage {
+            model: "anthropic/claude-sonnet-4-20250514".to_string(),
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+        };
+        assert_eq!(cost(&usage), Some(4.5));
+        let usage = Usage {
+            model: "local-model".to_string(),
+            ..usage
+        };
+        assert_eq!(cost(&usage), None);
+    }
+
+    #[test]
+    fn test_tracker_totals_and_budget() {
+        let mut tracker = UsageTracker::default();
+        tracker.apply(&usage("claude-haiku-4-5", 500_000, 100_000));
+        tracker.apply(&usage("local-model", 2_000, 500));
+        assert_eq!(tracker.input_tokens(), 502_000);
+        assert_eq!(tracker.output_tokens(), 100_500);
+        // unknown prices are left out
+        assert_eq!(tracker.cost(), 1.0);
+
+        assert_eq!(tracker.budget_state(2.0), BudgetState::Under);
+        assert_eq!(tracker.budget_state(1.2), BudgetState::Near);
+        assert_eq!(tracker.budget_state(1.0), BudgetState::Over);
+        assert_eq!(
+            tracker.summary(Some(1.2)),
+            "tokens in 502.0k / out 100.5k | $1.00 of $1.20 (near budget)"
+        );
+        assert_eq!(
+            tracker.summary(None),
+            "tokens in 502.0k / out 100.5k | $1.00"
+        );
+    }
+
+    #[test]
+    fn test_report_names_tools_of_heaviest_turn() {
+        use rig::message::{ToolCall, ToolFunction};
+
+        let mut tracker = UsageTracker::default();
+        assert_eq!(tracker.report(), "No usage recorded yet");
+        tracker.apply(&usage("claude-opus-4-1", 900, 10));
+        tracker.apply(&Event::<()>::ToolCalls {
+            calls: vec![ToolCall {
+                id: "1".to_string(),
+                call_id: None,
+                function: ToolFunction {
+                    name: "read_file".to_string(),
+                    arguments: serde_json::json!({}),
+                },
+            }],
+        });
+        tracker.apply(&usage("claude-opus-4-1", 2_000_000, 0));
+        assert_eq!(tracker.turns[1].tools, vec!["read_file"]);
+
+        let report = tracker.report();
+        assert!(report.contains("claude-opus-4-1: in 2.0M / out 10, $30.01"));
+        let heaviest = report.split("Heaviest turns\n").nth(1).unwrap();
+        assert!(heaviest.starts_with("  #2: in 2.0M / out 0, $30.00 (read_file)"));
+        assert!(heaviest.contains("  #1: in 900 / out 10, $0.01 (no tools)"));
+    }
+}
@@ -1,23 +1,32 @@
+use crate::config::{Action, Keymap, Theme};
+use crate::tab::Tab;
 use edda_agent::llm::CompletionResponse;
 use edda_agent::processor::agent::Event;
+use edda_agent::processor::plan::Plan;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style, Stylize},
+    style::{Style, Stylize},
     text::{Line, Span, Text},
-    widgets::{Block, List, ListItem, ListState, StatefulWidget, Widget},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Paragraph, StatefulWidget, Tabs, Widget,
+        Wrap,
+    },
 };
 use rig::completion::message::{
     AssistantContent, ToolCall, ToolResult, ToolResultContent, UserContent,
 };
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 pub struct EventList<'a, T> {
     events: &'a [Event<T>],
+    theme: &'a Theme,
 }
 
 impl<'a, T> EventList<'a, T> {
-    pub fn new(events: &'a [Event<T>]) -> Self {
-        Self { events }
+    pub fn new(events: &'a [Event<T>], theme: &'a Theme) -> Self {
+        Self { events, theme }
     }
 }
 
@@ -28,27 +37,561 @@ impl<T> Widget for EventList<'_, T> {
         let items: Vec<ListItem> = self
             .events
             .iter()
-            .filter_map(|event| event_as_text(event).map(ListItem::new))
+            .filter_map(|event| event_as_text(event, self.theme).map(ListItem::new))
             .collect();
 
         let list = List::new(items)
             .block(Block::default().title("Event List"))
-            .highlight_style(Style::default().fg(Color::Yellow))
+            .highlight_style(Style::default().fg(self.theme.accent))
             .highlight_symbol(">> ");
 
         StatefulWidget::render(list, area, buf, &mut state);
     }
 }
 
-pub fn event_as_text<T>(event: &Event<T>) -> Option<Text<'_>> {
+/// One title per open session, the one on screen highlighted
+pub struct TabBar<'a, T> {
+    tabs: &'a [Tab<T>],
+    active: usize,
+    theme: &'a Theme,
+}
+
+impl<'a, T> TabBar<'a, T> {
+    pub fn new(tabs: &'a [Tab<T>], active: usize, theme: &'a Theme) -> Self {
+        Self {
+            tabs,
+            active,
+            theme,
+        }
+    }
+}
+
+impl<T> Widget for TabBar<'_, T> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let titles = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| format!("{} {}", index + 1, tab.title()));
+        Tabs::new(titles)
+            .select(self.active)
+            .style(Style::new().fg(self.theme.muted))
+            .highlight_style(Style::new().fg(self.theme.accent).bold())
+            .render(area, buf);
+    }
+}
+
+/// Checklist of the agent's plan with the task being worked on highlighted
+pub struct PlanPanel<'a> {
+    plan: &'a Plan,
+    theme: &'a Theme,
+}
+
+impl<'a> PlanPanel<'a> {
+    pub fn new(plan: &'a Plan, theme: &'a Theme) -> Self {
+        Self { plan, theme }
+    }
+}
+
+impl Widget for PlanPanel<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let done = self.plan.tasks.iter().filter(|task| task.done).count();
+        let title = match self.plan.is_empty() {
+            true => "Plan".to_string(),
+            false => format!("Plan ({}/{})", done, self.plan.tasks.len()),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if self.plan.is_empty() {
+            Paragraph::new(Span::styled(
+                "No plan yet",
+                Style::new().fg(self.theme.muted),
+            ))
+            .block(block)
+            .render(area, buf);
+            return;
+        }
+
+        let current = self.plan.current();
+        let lines: Vec<Line> = self
+            .plan
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| {
+                let (marker, style) = if task.done {
+                    ("[x] ", Style::new().fg(self.theme.success))
+                } else if Some(index) == current {
+                    ("[>] ", Style::new().fg(self.theme.accent).bold())
+                } else {
+                    ("[ ] ", Style::new())
+                };
+                Line::from(vec![
+                    Span::styled(marker, style),
+                    Span::styled(task.description.clone(), style),
+                ])
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+/// Pending tool call shown over the UI until the user decides on it
+pub struct ApprovalModal<'a> {
+    call: &'a ToolCall,
+    pending: usize,
+    editing: bool,
+    theme: &'a Theme,
+    keys: &'a Keymap,
+}
+
+impl<'a> ApprovalModal<'a> {
+    pub fn new(
+        call: &'a ToolCall,
+        pending: usize,
+        editing: bool,
+        theme: &'a Theme,
+        keys: &'a Keymap,
+    ) -> Self {
+        Self {
+            call,
+            pending,
+            editing,
+            theme,
+            keys,
+        }
+    }
+}
+
+impl Widget for ApprovalModal<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.pending {
+            1 => "Approval required".to_string(),
+            n => format!("Approval required (1 of {})", n),
+        };
+        let mut lines = vec![Line::from(Span::styled(
+            self.call.function.name.clone(),
+            Style::new().bold(),
+        ))];
+        let args = &self.call.function.arguments;
+        match (args["command"].as_str(), args["path"].as_str()) {
+            (Some(command), _) => lines.push(Line::from(format!("$ {}", command))),
+            (None, Some(path)) => {
+                lines.push(Line::from(Span::styled(
+                    path.to_owned(),
+                    Style::new().fg(self.theme.info),
+                )));
+                let body = args["contents"].as_str().or(args["replace"].as_str());
+                for line in body.unwrap_or_default().lines() {
+                    lines.push(Line::from(line.to_owned()));
+                }
+            }
+            (None, None) => {
+                let pretty = serde_json::to_string_pretty(args).unwrap_or_default();
+                for line in pretty.lines() {
+                    lines.push(Line::from(line.to_owned()));
+                }
+            }
+        }
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            match self.editing {
+                true => "Edit the arguments below, Enter to run them, Esc to go back".to_string(),
+                false => format!(
+                    "[{}] approve  [{}] reject  [{}] edit arguments",
+                    self.keys.label(Action::Approve),
+                    self.keys.label(Action::Reject),
+                    self.keys.label(Action::EditApproval)
+                ),
+            },
+            Style::new().fg(self.theme.accent),
+        )));
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::new().fg(self.theme.accent))
+                    .title(title),
+            )
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+/// Read-only text over the event list, closed with Esc
+pub struct Popup<'a> {
+    title: &'a str,
+    text: &'a str,
+}
+
+impl<'a> Popup<'a> {
+    pub fn new(title: &'a str, text: &'a str) -> Self {
+        Self { title, text }
+    }
+}
+
+impl Widget for Popup<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        Paragraph::new(self.text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} (Esc to close)", self.title)),
+            )
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+/// Latest batch of tool calls, with their output once they return
+#[derive(Debug, Default)]
+pub struct ToolRuns {
+    pub runs: Vec<ToolRun>,
+}
+
+#[derive(Debug)]
+pub struct ToolRun {
+    pub call: ToolCall,
+    pub started: Instant,
+    pub elapsed: Option<Duration>,
+    pub output: Option<String>,
+}
+
+impl ToolRuns {
+    pub fn apply<T>(&mut self, event: &Event<T>) {
+        match event {
+            Event::ToolCalls { calls } => {
+                self.runs = calls
+                    .iter()
+                    .map(|call| ToolRun {
+                        call: call.clone(),
+                        started: Instant::now(),
+                        elapsed: None,
+                        output: None,
+                    })
+                    .collect();
+            }
+            Event::ToolResults { results } => {
+                for result in results {
+                    if let Some(run) = self.runs.iter_mut().find(|run| run.call.id == result.id) {
+                        run.elapsed = Some(run.started.elapsed());
+                        run.output = Some(tool_result_text(result));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.runs.iter().any(|run| run.output.is_none())
+    }
+
+    /// Plain text of the pane, for copying
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for run in self.runs.iter() {
+            text.push_str(&run_header(&run.call));
+            text.push('\n');
+            if let Some(output) = &run.output {
+                text.push_str(output);
+                text.push('\n');
+            }
+        }
+        text
+    }
+}
+
+/// Output of the tool calls being run, following the end unless scrolled
+pub struct OutputPane<'a> {
+    runs: &'a ToolRuns,
+    scroll: Option<u16>,
+    theme: &'a Theme,
+    keys: &'a Keymap,
+}
+
+impl<'a> OutputPane<'a> {
+    pub fn new(
+        runs: &'a ToolRuns,
+        scroll: Option<u16>,
+        theme: &'a Theme,
+        keys: &'a Keymap,
+    ) -> Self {
+        Self {
+            runs,
+            scroll,
+            theme,
+            keys,
+        }
+    }
+
+    /// Scroll offset that shows the end of the output in `area`
+    pub fn tail(&self, area: Rect) -> u16 {
+        let lines: usize = self.lines().len();
+        lines.saturating_sub(area.height.saturating_sub(2) as usize) as u16
+    }
+
+    fn lines(&self) -> Vec<Line<'a>> {
+        let mut lines = Vec::new();
+        for run in self.runs.runs.iter() {
+            let status = match run.elapsed {
+                Some(elapsed) => format!(" ({}s)", elapsed.as_secs()),
+                None => format!(" running {}s", run.started.elapsed().as_secs()),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(run_header(&run.call), Style::new().bold()),
+                Span::styled(status, Style::new().fg(self.theme.muted)),
+            ]));
+            for line in run.output.as_deref().unwrap_or_default().lines() {
+                lines.push(Line::from(line.to_owned()));
+            }
+        }
+        lines
+    }
+}
+
+impl Widget for OutputPane<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let hint = format!(
+            "{} hide, {}/{} scroll, {} copy",
+            self.keys.label(Action::ToggleOutput),
+            self.keys.label(Action::ScrollUp),
+            self.keys.label(Action::ScrollDown),
+            self.keys.label(Action::CopyOutput)
+        );
+        let title = match (self.runs.is_running(), self.scroll) {
+            (true, _) => format!("Tool output (running | {})", hint),
+            (false, None) => format!("Tool output ({})", hint),
+            (false, Some(_)) => format!(
+                "Tool output (scrolled | {} to the end)",
+                self.keys.label(Action::ScrollDown)
+            ),
+        };
+        let scroll = self.scroll.unwrap_or_else(|| self.tail(area));
+        Paragraph::new(self.lines())
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((scroll, 0))
+            .render(area, buf);
+    }
+}
+
+/// File edits of the session as unified diffs, one per successful `write_file` or
+/// `edit_file` call. Contents are tracked from the calls themselves, so an edit to a file
+/// the agent hasn't written shows only the replaced text.
+#[derive(Debug, Default)]
+pub struct FileChanges {
+    pub changes: Vec<FileChange>,
+    /// Calls waiting for their result, with the arguments they run with
+    pending: HashMap<String, ToolCall>,
+    contents: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: String,
+    pub diff: String,
+}
+
+impl FileChanges {
+    pub fn apply<T>(&mut self, event: &Event<T>) {
+        match event {
+            Event::ToolCalls { calls } => {
+                for call in calls.iter().filter(|call| is_file_edit(call)) {
+                    self.pending.insert(call.id.clone(), call.clone());
+                }
+            }
+            // the user may have edited the arguments
+            Event::ApprovalResolved { call, .. } if self.pending.contains_key(&call.id) => {
+                self.pending.insert(call.id.clone(), call.clone());
+            }
+            Event::ToolResults { results } => {
+                for result in results {
+                    let Some(call) = self.pending.remove(&result.id) else {
+                        continue;
+                    };
+                    if !is_tool_error(result) {
+                        self.record(&call);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record(&mut self, call: &ToolCall) {
+        let args = &call.function.arguments;
+        let Some(path) = args["path"].as_str() else {
+            return;
+        };
+        let old = self.contents.get(path).cloned();
+        let (original, modified) = match (call.function.name.as_str(), old) {
+            ("write_file", old) => (
+                old.unwrap_or_default(),
+                args["contents"].as_str().unwrap_or_default().to_owned(),
+            ),
+            (_, Some(old)) => {
+                let find = args["find"].as_str().unwrap_or_default();
+                let replace = args["replace"].as_str().unwrap_or_default();
+                let new = old.replacen(find, replace, 1);
+                (old, new)
+            }
+            (_, None) => {
+                let find = args["find"].as_str().unwrap_or_default().to_owned();
+                let replace = args["replace"].as_str().unwrap_or_default().to_owned();
+                self.changes.push(FileChange {
+                    path: path.to_owned(),
+                    diff: unified_diff(path, &find, &replace),
+                });
+                return;
+            }
+        };
+        self.changes.push(FileChange {
+            path: path.to_owned(),
+            diff: unified_diff(path, &original, &modified),
+        });
+        self.contents.insert(path.to_owned(), modified);
+    }
+}
+
+fn is_file_edit(call: &ToolCall) -> bool {
+    matches!(call.function.name.as_str(), "write_file" | "edit_file")
+}
+
+fn unified_diff(path: &str, original: &str, modified: &str) -> String {
+    diffy::DiffOptions::new()
+        .set_original_filename(format!("a/{}", path))
+        .set_modified_filename(format!("b/{}", path))
+        .create_patch(original, modified)
+        .to_string()
+}
+
+/// One change of `FileChanges` at a time
+pub struct DiffView<'a> {
+    changes: &'a FileChanges,
+    selected: usize,
+    scroll: u16,
+    theme: &'a Theme,
+    keys: &'a Keymap,
+}
+
+impl<'a> DiffView<'a> {
+    pub fn new(
+        changes: &'a FileChanges,
+        selected: usize,
+        scroll: u16,
+        theme: &'a Theme,
+        keys: &'a Keymap,
+    ) -> Self {
+        Self {
+            changes,
+            selected,
+            scroll,
+            theme,
+            keys,
+        }
+    }
+}
+
+impl Widget for DiffView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let total = self.changes.changes.len();
+        let Some(change) = self.changes.changes.get(self.selected) else {
+            Paragraph::new("No file changes yet")
+                .block(Block::default().borders(Borders::ALL).title("Changes"))
+                .render(area, buf);
+            return;
+        };
+        let title = format!(
+            "Changes {}/{}: {} ({}/{} next/previous, {}/{} scroll, {} close)",
+            self.selected + 1,
+            total,
+            change.path,
+            self.keys.label(Action::NextChange),
+            self.keys.label(Action::PreviousChange),
+            self.keys.label(Action::ScrollUp),
+            self.keys.label(Action::ScrollDown),
+            self.keys.label(Action::ToggleDiff)
+        );
+        let lines: Vec<Line> = change
+            .diff
+            .lines()
+            .map(|line| {
+                let style = match line {
+                    l if l.starts_with("+++") || l.starts_with("---") => Style::new().bold(),
+                    l if l.starts_with('+') => Style::new().fg(self.theme.success),
+                    l if l.starts_with('-') => Style::new().fg(self.theme.error),
+                    l if l.starts_with("@@") => Style::new().fg(self.theme.info),
+                    _ => Style::new(),
+                };
+                Line::styled(line.to_owned(), style)
+            })
+            .collect();
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+}
+
+fn run_header(call: &ToolCall) -> String {
+    match call.function.arguments["command"].as_str() {
+        Some(command) => format!("$ {}", command),
+        None => format!("{} {}", call.function.name, call.function.arguments),
+    }
+}
+
+/// Result of a call that failed, see `ToolCallExt::to_result`
+pub fn is_tool_error(result: &ToolResult) -> bool {
+    result.content.iter().any(|item| match item {
+        ToolResultContent::Text(text) => serde_json::from_str::<serde_json::Value>(&text.text)
+            .is_ok_and(|value| value.get("error").is_some()),
+        _ => false,
+    })
+}
+
+/// Tool results hold JSON, show strings and errors unquoted
+pub fn tool_result_text(result: &ToolResult) -> String {
+    let mut text = Vec::new();
+    for item in result.content.iter() {
+        if let ToolResultContent::Text(item) = item {
+            let value = serde_json::from_str::<serde_json::Value>(&item.text);
+            text.push(match value {
+                Ok(serde_json::Value::String(output)) => output,
+                Ok(value) => match value["error"].as_str() {
+                    Some(error) => error.to_owned(),
+                    None => serde_json::to_string_pretty(&value).unwrap_or_default(),
+                },
+                Err(_) => item.text.clone(),
+            });
+        }
+    }
+    text.join("\n")
+}
+
+pub fn event_as_text<'a, T>(event: &'a Event<T>, theme: &Theme) -> Option<Text<'a>> {
     match event {
-        Event::UserCompletion { content } => Some(render_user_message(content)),
-        Event::AgentCompletion { response } => Some(render_agent_message(response)),
+        Event::UserCompletion { content } => Some(render_user_message(content, theme)),
+        Event::UserInterrupted { content } => {
+            let mut text = render_user_message(content, theme);
+            let note = Line::from("[interrupt, sent once the running tools finish]");
+            text.lines.insert(0, note.fg(theme.muted));
+            Some(text)
+        }
+        Event::AgentCompletion { response } => Some(render_agent_message(response, theme)),
+        Event::LLMConfigUpdated { update } => {
+            let line = Line::from(format!("[llm switched to {}]", update));
+            Some(Text::from(line.fg(theme.muted)))
+        }
         _ => None,
     }
 }
 
-pub fn render_agent_message(completion: &CompletionResponse) -> Text<'_> {
+pub fn render_agent_message<'a>(completion: &'a CompletionResponse, theme: &Theme) -> Text<'a> {
     let mut lines = Vec::new();
     for item in completion.choice.iter() {
         match item {
@@ -64,14 +607,17 @@ pub fn render_agent_message(completion: &CompletionResponse) -> Text<'_> {
                 }
             }
             AssistantContent::ToolCall(tool_call) => {
-                lines.append(&mut tool_call_lines(tool_call));
+                lines.append(&mut tool_call_lines(tool_call, theme));
             }
         }
     }
     Text::from(lines)
 }
 
-pub fn render_user_message(response: &rig::OneOrMany<UserContent>) -> Text<'_> {
+pub fn render_user_message<'a>(
+    response: &'a rig::OneOrMany<UserContent>,
+    theme: &Theme,
+) -> Text<'a> {
     let mut lines = Vec::new();
     for item in response.iter() {
         match item {
@@ -81,7 +627,7 @@ pub fn render_user_message(response: &rig::OneOrMany<UserContent>) -> Text<'_> {
                 }
             }
             UserContent::ToolResult(tool_result) => {
-                lines.append(&mut tool_result_lines(tool_result));
+                lines.append(&mut tool_result_lines(tool_result, theme));
             }
             UserContent::Image(_) => lines.push(Line::from("[image]")),
             UserContent::Audio(_) => lines.push(Line::from("[audio]")),
@@ -92,12 +638,12 @@ pub fn render_user_message(response: &rig::OneOrMany<UserContent>) -> Text<'_> {
     Text::from(lines)
 }
 
-pub fn tool_call_lines(value: &ToolCall) -> Vec<Line<'_>> {
+pub fn tool_call_lines<'a>(value: &'a ToolCall, theme: &Theme) -> Vec<Line<'a>> {
     let args = serde_json::to_string_pretty(&value.function.arguments).unwrap();
     let mut lines = vec![Line::from(vec![
         Span::styled(value.function.name.clone(), Style::new().bold()),
         Span::raw(" "),
-        Span::styled(format!("[{}]", value.id), Style::new().gray()),
+        Span::styled(format!("[{}]", value.id), Style::new().fg(theme.muted)),
     ])];
     for line in args.lines() {
         lines.push(Line::from(line.to_owned()));
@@ -105,10 +651,10 @@ pub fn tool_call_lines(value: &ToolCall) -> Vec<Line<'_>> {
     lines
 }
 
-pub fn tool_result_lines(value: &ToolResult) -> Vec<Line<'_>> {
+pub fn tool_result_lines<'a>(value: &'a ToolResult, theme: &Theme) -> Vec<Line<'a>> {
     let mut lines = vec![Line::from(vec![Span::styled(
         format!("[{}]", value.id),
-        Style::new().gray(),
+        Style::new().fg(theme.muted),
     )])];
     for item in value.content.iter() {
         match item {
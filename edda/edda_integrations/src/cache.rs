@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Small in-memory cache with a fixed time-to-live per entry.
+/// A zero TTL disables caching entirely.
+pub struct TtlCache<V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, V)>>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: impl Into<String>, value: V) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.into(), (Instant::now(), value));
+    }
+
+    /// Drop the entry `prefix` and every entry continuing it after a `.` or `:`,
+    /// so `schemas:main` drops `schemas:main` but keeps `schemas:main2`.
+    pub fn invalidate_prefix(&self, prefix: &str) {
+        self.entries.lock().unwrap().retain(|key, _| {
+            key.strip_prefix(prefix)
+                .is_none_or(|rest| !(rest.is_empty() || rest.starts_with(['.', ':'])))
+        });
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_cache() {
+        let cache = TtlCache::new(Duration::from_secs(60));
+        cache.insert("schemas:main", vec!["default".to_string()]);
+        cache.insert("schemas:main2", vec!["default".to_string()]);
+        cache.insert("schemas:dev", vec!["tmp".to_string()]);
+        assert_eq!(cache.get("schemas:main"), Some(vec!["default".to_string()]));
+
+        cache.invalidate_prefix("schemas:main");
+        assert_eq!(cache.get("schemas:main"), None);
+        assert!(cache.get("schemas:main2").is_some());
+        assert!(cache.get("schemas:dev").is_some());
+
+        cache.insert("describe:main.default.t:5", vec![]);
+        cache.insert("describe:main.default.t2:5", vec![]);
+        cache.invalidate_prefix("describe:main.default.t");
+        assert_eq!(cache.get("describe:main.default.t:5"), None);
+        assert!(cache.get("describe:main.default.t2:5").is_some());
+
+        cache.clear();
+        assert!(cache.get("schemas:dev").is_none());
+    }
+
+    #[test]
+    fn test_ttl_cache_expiry() {
+        let cache = TtlCache::new(Duration::from_millis(1));
+        cache.insert("catalogs", 1);
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(cache.get("catalogs"), None);
+
+        let disabled = TtlCache::new(Duration::ZERO);
+        disabled.insert("catalogs", 1);
+        assert_eq!(disabled.get("catalogs"), None);
+    }
+}
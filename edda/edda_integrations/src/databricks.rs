@@ -1,3 +1,6 @@
+use crate::cache::TtlCache;
+use crate::databricks_auth::{DatabricksAuthConfig, TokenProvider};
+use crate::retry::{CircuitBreaker, RetryPolicy};
 use anyhow::{Result, anyhow};
 use log::debug;
 use reqwest;
@@ -5,15 +8,30 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 const SQL_STATEMENTS_ENDPOINT: &str = "/api/2.0/sql/statements";
 const UNITY_CATALOG_TABLES_ENDPOINT: &str = "/api/2.1/unity-catalog/tables";
 const UNITY_CATALOG_CATALOGS_ENDPOINT: &str = "/api/2.1/unity-catalog/catalogs";
 const UNITY_CATALOG_SCHEMAS_ENDPOINT: &str = "/api/2.1/unity-catalog/schemas";
+const FILES_ENDPOINT: &str = "/api/2.0/fs/files";
+const DIRECTORIES_ENDPOINT: &str = "/api/2.0/fs/directories";
+const SERVING_ENDPOINTS_ENDPOINT: &str = "/api/2.0/serving-endpoints";
+const SERVING_INVOCATIONS_ENDPOINT: &str = "/serving-endpoints";
+const WAREHOUSES_ENDPOINT: &str = "/api/2.0/sql/warehouses";
+const LAKEVIEW_DASHBOARDS_ENDPOINT: &str = "/api/2.0/lakeview/dashboards";
+const GENIE_SPACES_ENDPOINT: &str = "/api/2.0/genie/spaces";
 const DEFAULT_WAIT_TIMEOUT: &str = "30s";
 const MAX_POLL_ATTEMPTS: usize = 30;
+const INLINE_ROW_LIMIT: usize = 100;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const METADATA_CACHE_TTL: Duration = Duration::from_secs(300);
+const WAREHOUSE_START_TIMEOUT: Duration = Duration::from_secs(600);
+const WAREHOUSE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const GENIE_TIMEOUT: Duration = Duration::from_secs(300);
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Deserialize)]
 struct TableResponse {
@@ -71,6 +89,189 @@ struct SchemaSummary {
     name: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct DirectoryContentsResponse {
+    contents: Option<Vec<DirectoryEntry>>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DirectoryEntry {
+    path: String,
+    name: Option<String>,
+    #[serde(default)]
+    is_directory: bool,
+    file_size: Option<i64>,
+    last_modified: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServingEndpointsListResponse {
+    endpoints: Option<Vec<ServingEndpoint>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarehousesListResponse {
+    warehouses: Option<Vec<SqlWarehouse>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieSpacesListResponse {
+    spaces: Option<Vec<GenieSpace>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GenieMessageRequest<'a> {
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieStartConversationResponse {
+    conversation_id: String,
+    message_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieMessage {
+    #[serde(alias = "message_id")]
+    id: String,
+    conversation_id: String,
+    status: Option<String>,
+    attachments: Option<Vec<GenieAttachment>>,
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieAttachment {
+    attachment_id: Option<String>,
+    text: Option<GenieTextAttachment>,
+    query: Option<GenieQueryAttachment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieTextAttachment {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieQueryAttachment {
+    query: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenieQueryResultResponse {
+    statement_response: Option<SqlStatementResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DashboardsListResponse {
+    dashboards: Option<Vec<LakeviewDashboard>>,
+    next_page_token: Option<String>,
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Failure of a Databricks REST call. Returned inside `anyhow::Error`, so callers can
+/// `downcast_ref::<DatabricksError>()` to tell transient failures from auth failures.
+#[derive(Debug, thiserror::Error)]
+pub enum DatabricksError {
+    #[error("Databricks authentication failed (status {status}): {message}")]
+    Auth { status: u16, message: String },
+    #[error("Databricks rate limit exceeded (status 429): {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    #[error("Databricks service unavailable (status {status}): {message}")]
+    Unavailable {
+        status: u16,
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    #[error("API request failed with status {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("HTTP request failed: {0}")]
+    Network(String),
+    #[error(
+        "Databricks API circuit open after repeated failures, retry in {}s",
+        .retry_in.as_secs().max(1)
+    )]
+    CircuitOpen { retry_in: Duration },
+}
+
+impl DatabricksError {
+    fn from_response(
+        status: reqwest::StatusCode,
+        headers: &reqwest::header::HeaderMap,
+        message: String,
+    ) -> Self {
+        let retry_after = headers
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let status_code = status.as_u16();
+        match status_code {
+            401 | 403 => Self::Auth {
+                status: status_code,
+                message,
+            },
+            429 => Self::RateLimited {
+                retry_after,
+                message,
+            },
+            500 | 502 | 503 | 504 => Self::Unavailable {
+                status: status_code,
+                retry_after,
+                message,
+            },
+            _ => Self::Api {
+                status: status_code,
+                message,
+            },
+        }
+    }
+
+    /// Worth retrying later: rate limits, 5xx, network errors and an open circuit.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. }
+                | Self::Unavailable { .. }
+                | Self::Network(_)
+                | Self::CircuitOpen { .. }
+        )
+    }
+
+    pub fn is_auth(&self) -> bool {
+        matches!(self, Self::Auth { .. })
+    }
+
+    /// Server-requested delay from the `Retry-After` header.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } | Self::Unavailable { retry_after, .. } => {
+                *retry_after
+            }
+            Self::CircuitOpen { retry_in } => Some(*retry_in),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Auth { status, .. } | Self::Unavailable { status, .. } | Self::Api { status, .. } => {
+                Some(*status)
+            }
+            Self::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -97,6 +298,62 @@ fn validate_identifier(id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Whether a statement may change catalog metadata, so cached listings must be dropped.
+fn is_ddl(sql: &str) -> bool {
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    matches!(
+        keyword.as_str(),
+        "CREATE" | "DROP" | "ALTER" | "RENAME" | "REPLACE" | "COMMENT" | "UNDROP" | "MSCK"
+    )
+}
+
+/// Whether the statement changes table data, which stale describe results (row counts, samples) would hide.
+fn is_dml(sql: &str) -> bool {
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    matches!(
+        keyword.as_str(),
+        "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "TRUNCATE" | "COPY"
+    )
+}
+
+/// Whether sending the request twice has the same effect as sending it once.
+fn is_idempotent(method: &reqwest::Method) -> bool {
+    matches!(
+        *method,
+        reqwest::Method::GET
+            | reqwest::Method::HEAD
+            | reqwest::Method::PUT
+            | reqwest::Method::DELETE
+            | reqwest::Method::OPTIONS
+    )
+}
+
+/// Validates a Unity Catalog volume path and encodes it for the Files API.
+fn encode_volume_path(path: &str) -> Result<String> {
+    if !path.starts_with("/Volumes/") {
+        return Err(anyhow!(
+            "Invalid volume path '{}': must start with /Volumes/<catalog>/<schema>/<volume>",
+            path
+        ));
+    }
+    if path.split('/').any(|segment| segment == "..") {
+        return Err(anyhow!("Invalid volume path '{}': '..' is not allowed", path));
+    }
+    Ok(path
+        .split('/')
+        .map(|segment| urlencoding::encode(segment).into_owned())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
 /// Escapes a user-provided pattern for safe use in SQL LIKE clauses.
 /// - Escapes the backslash escape character itself
 /// - Escapes SQL wildcards (%, _) to treat them as literals
@@ -159,6 +416,74 @@ pub struct DatabricksDescribeTableArgs {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DatabricksExecuteQueryArgs {
     pub query: String,
+    /// Maximum number of rows to fetch. Above 100 rows results are fetched in chunks via external links.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<usize>,
+    /// Timeout in seconds. The statement is cancelled on the warehouse when exceeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabricksListVolumeFilesArgs {
+    /// Volume directory path, e.g. /Volumes/main/default/my_volume/data
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabricksUploadFileArgs {
+    /// Absolute path of the local file to upload
+    pub local_path: String,
+    /// Destination file path in the volume, e.g. /Volumes/main/default/my_volume/export.csv
+    pub volume_path: String,
+    /// Overwrite the destination if it already exists
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct DatabricksListWarehousesArgs {
+    // no parameters needed - lists all SQL warehouses
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct DatabricksListServingEndpointsArgs {
+    // no parameters needed - lists all serving endpoints
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabricksQueryServingEndpointArgs {
+    /// Name of the serving endpoint
+    pub name: String,
+    /// JSON request payload. Omit to get the endpoint's expected request schema instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabricksGenieAskArgs {
+    /// Natural-language question about the data
+    pub question: String,
+    /// Genie space ID. Defaults to DATABRICKS_GENIE_SPACE_ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space_id: Option<String>,
+    /// Conversation ID from a previous answer, to ask a follow-up question
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct DatabricksListDashboardsArgs {
+    // no parameters needed - lists all Lakeview dashboards
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DatabricksExportDashboardQueriesArgs {
+    /// Lakeview dashboard ID (from databricks_list_dashboards)
+    pub dashboard_id: String,
 }
 
 // ============================================================================
@@ -208,11 +533,43 @@ fn default_sample_size() -> usize {
     5
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListVolumeFilesRequest {
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadFileRequest {
+    pub volume_path: String,
+    pub contents: Vec<u8>,
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenieAskRequest {
+    pub question: String,
+    pub space_id: Option<String>,
+    pub conversation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDashboardRequest {
+    pub display_name: String,
+    /// Workspace folder to create the dashboard in, e.g. `/Workspace/Users/me@example.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_path: Option<String>,
+    /// Dashboard definition (the `.lvdash.json` contents) as a JSON string
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialized_dashboard: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warehouse_id: Option<String>,
+}
+
 // ============================================================================
 // Response Types
 // ============================================================================
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableDetails {
     pub full_name: String,
     pub table_type: String,
@@ -225,7 +582,7 @@ pub struct TableDetails {
     pub row_count: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColumnMetadata {
     pub name: String,
     pub data_type: String,
@@ -233,7 +590,7 @@ pub struct ColumnMetadata {
     pub nullable: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TableInfo {
     pub name: String,
     pub catalog_name: String,
@@ -267,11 +624,186 @@ pub struct ListTablesResult {
     pub limit: usize,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VolumeFileInfo {
+    pub path: String,
+    pub name: String,
+    pub is_directory: bool,
+    pub file_size: Option<i64>,
+    pub last_modified: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListVolumeFilesResult {
+    pub path: String,
+    pub files: Vec<VolumeFileInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UploadFileResult {
+    pub volume_path: String,
+    pub bytes_written: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SqlWarehouse {
+    pub id: String,
+    pub name: String,
+    pub state: Option<String>,
+    pub cluster_size: Option<String>,
+    pub warehouse_type: Option<String>,
+    pub enable_serverless_compute: Option<bool>,
+    pub auto_stop_mins: Option<i64>,
+}
+
+impl SqlWarehouse {
+    fn state(&self) -> &str {
+        self.state.as_deref().unwrap_or("UNKNOWN")
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListWarehousesResult {
+    pub warehouses: Vec<SqlWarehouse>,
+    /// Warehouse from `DATABRICKS_WAREHOUSE_ID`, if set.
+    pub configured_warehouse_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServingEndpoint {
+    pub name: String,
+    pub creator: Option<String>,
+    pub task: Option<String>,
+    pub state: Option<ServingEndpointState>,
+    pub config: Option<ServingEndpointConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServingEndpointState {
+    pub ready: Option<String>,
+    pub config_update: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServingEndpointConfig {
+    #[serde(default)]
+    pub served_entities: Vec<ServedEntity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServedEntity {
+    pub name: Option<String>,
+    pub entity_name: Option<String>,
+    pub entity_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListServingEndpointsResult {
+    pub endpoints: Vec<ServingEndpoint>,
+}
+
+/// Request schema discovered from the endpoint's OpenAPI spec.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServingEndpointSchema {
+    pub name: String,
+    pub task: Option<String>,
+    pub request_schema: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenieSpace {
+    pub space_id: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenieAnswer {
+    pub space_id: String,
+    pub conversation_id: String,
+    pub message_id: String,
+    /// Genie's text reply (explanations, clarifying questions)
+    pub text: Option<String>,
+    /// SQL generated for the question
+    pub sql: Option<String>,
+    pub sql_description: Option<String>,
+    pub rows: Vec<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LakeviewDashboard {
+    pub dashboard_id: String,
+    pub display_name: Option<String>,
+    pub path: Option<String>,
+    pub lifecycle_state: Option<String>,
+    pub warehouse_id: Option<String>,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+    /// Dashboard definition JSON; only returned when fetching a single dashboard
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialized_dashboard: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDashboardsResult {
+    pub dashboards: Vec<LakeviewDashboard>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardDataset {
+    pub name: String,
+    pub display_name: Option<String>,
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DashboardQueriesResult {
+    pub dashboard_id: String,
+    pub display_name: Option<String>,
+    pub warehouse_id: Option<String>,
+    pub datasets: Vec<DashboardDataset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QueryServingEndpointResult {
+    pub name: String,
+    pub response: Value,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExecuteSqlResult {
     pub rows: Vec<HashMap<String, Value>>,
 }
 
+/// Format of result chunks downloaded from external links.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultFormat {
+    #[default]
+    JsonArray,
+    Csv,
+    /// Arrow IPC stream; keeps NULLs apart from empty strings
+    ArrowStream,
+}
+
+/// Per-call knobs for [`DatabricksRestClient::execute_sql_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteSqlOptions {
+    /// Maximum rows to return; above 100 rows results are streamed via EXTERNAL_LINKS
+    pub max_rows: Option<usize>,
+    /// Cancel the statement if it hasn't finished within this duration
+    pub timeout: Option<Duration>,
+}
+
+impl ResultFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::JsonArray => "JSON_ARRAY",
+            Self::Csv => "CSV",
+            Self::ArrowStream => "ARROW_STREAM",
+        }
+    }
+}
+
 // ============================================================================
 // Display Trait for Tool Results
 // ============================================================================
@@ -412,8 +944,8 @@ impl ToolResultDisplay for ExecuteSqlResult {
                 lines.push("Results:".to_string());
             }
 
-            let limit = std::cmp::min(self.rows.len(), 100);
-            for (i, row) in self.rows.iter().take(limit).enumerate() {
+            // row count is bounded by the caller's max_rows, so render everything
+            for (i, row) in self.rows.iter().enumerate() {
                 let row_str: Vec<String> = row
                     .iter()
                     .map(|(k, v)| format!("{}: {}", k, format_value(v)))
@@ -421,18 +953,331 @@ impl ToolResultDisplay for ExecuteSqlResult {
                 lines.push(format!("  Row {}: {}", i + 1, row_str.join(", ")));
             }
 
-            if self.rows.len() > 100 {
-                lines.push(format!(
-                    "\n... showing first 100 of {} total rows",
-                    self.rows.len()
-                ));
+            lines.join("\n")
+        }
+    }
+}
+
+/// Server-side wait before switching to polling, never past the query's own timeout. The
+/// API accepts 0 (return at once) or 5-50s.
+fn wait_timeout(timeout: Option<Duration>) -> String {
+    match timeout.map(|timeout| timeout.as_secs()) {
+        Some(secs) if secs < 5 => "0s".to_string(),
+        Some(secs) => format!("{}s", secs.min(30)),
+        None => DEFAULT_WAIT_TIMEOUT.to_string(),
+    }
+}
+
+fn statement_error(response: &SqlStatementResponse) -> anyhow::Error {
+    let error_msg = response
+        .status
+        .as_ref()
+        .and_then(|s| s.error.as_ref())
+        .and_then(|e| e.message.as_deref())
+        .unwrap_or("Unknown error");
+    anyhow!("SQL execution failed: {}", error_msg)
+}
+
+/// Parses a CSV result chunk into rows. Empty fields are treated as NULL.
+fn parse_csv_chunk(body: &[u8]) -> Result<Vec<Vec<Option<String>>>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(body);
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| anyhow!("Failed to parse CSV result chunk: {}", e))?;
+        rows.push(
+            record
+                .iter()
+                .map(|field| (!field.is_empty()).then(|| field.to_string()))
+                .collect(),
+        );
+    }
+    Ok(rows)
+}
+
+/// Parses an Arrow IPC stream result chunk into rows of display strings.
+fn parse_arrow_chunk(body: &[u8]) -> Result<Vec<Vec<Option<String>>>> {
+    use arrow_array::Array;
+
+    let reader = arrow_ipc::reader::StreamReader::try_new(body, None)
+        .map_err(|e| anyhow!("Failed to parse Arrow result chunk: {}", e))?;
+    let options = arrow_cast::display::FormatOptions::default();
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| anyhow!("Failed to parse Arrow result chunk: {}", e))?;
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| {
+                arrow_cast::display::ArrayFormatter::try_new(column.as_ref(), &options)
+                    .map(|formatter| (column, formatter))
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("Failed to read Arrow result chunk: {}", e))?;
+        for row in 0..batch.num_rows() {
+            rows.push(
+                columns
+                    .iter()
+                    .map(|(column, formatter)| {
+                        (!column.is_null(row)).then(|| formatter.value(row).to_string())
+                    })
+                    .collect(),
+            );
+        }
+    }
+    Ok(rows)
+}
+
+impl ToolResultDisplay for ListVolumeFilesResult {
+    fn display(&self) -> String {
+        if self.files.is_empty() {
+            return format!("No files found in {}.", self.path);
+        }
+        let mut lines = vec![
+            format!("Found {} entries in {}:", self.files.len(), self.path),
+            String::new(),
+        ];
+        for file in &self.files {
+            if file.is_directory {
+                lines.push(format!("• {}/", file.name));
+            } else {
+                let size = file
+                    .file_size
+                    .map(|s| format!(" ({} bytes)", s))
+                    .unwrap_or_default();
+                lines.push(format!("• {}{}", file.name, size));
             }
+        }
+        lines.join("\n")
+    }
+}
 
-            lines.join("\n")
+impl ToolResultDisplay for UploadFileResult {
+    fn display(&self) -> String {
+        format!("Uploaded {} bytes to {}", self.bytes_written, self.volume_path)
+    }
+}
+
+impl ToolResultDisplay for ListWarehousesResult {
+    fn display(&self) -> String {
+        if self.warehouses.is_empty() {
+            return "No SQL warehouses found.".to_string();
         }
+        let mut lines = vec![
+            format!("Found {} SQL warehouses:", self.warehouses.len()),
+            String::new(),
+        ];
+        for warehouse in &self.warehouses {
+            let mut info = format!("• {} ({}) [{}]", warehouse.name, warehouse.id, warehouse.state());
+            if let Some(size) = &warehouse.cluster_size {
+                info.push_str(&format!(" - size: {}", size));
+            }
+            if warehouse.enable_serverless_compute == Some(true) {
+                info.push_str(" - serverless");
+            } else if let Some(warehouse_type) = &warehouse.warehouse_type {
+                info.push_str(&format!(" - type: {}", warehouse_type));
+            }
+            if let Some(mins) = warehouse.auto_stop_mins {
+                info.push_str(&format!(" - auto stop: {} min", mins));
+            }
+            if self.configured_warehouse_id.as_deref() == Some(warehouse.id.as_str()) {
+                info.push_str(" (configured)");
+            }
+            lines.push(info);
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for GenieAnswer {
+    fn display(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(text) = &self.text {
+            lines.push(text.clone());
+            lines.push(String::new());
+        }
+        if let Some(sql) = &self.sql {
+            if let Some(description) = &self.sql_description {
+                lines.push(description.clone());
+            }
+            lines.push("```sql".to_string());
+            lines.push(sql.trim_end().to_string());
+            lines.push("```".to_string());
+            lines.push(String::new());
+            lines.push(ExecuteSqlResult { rows: self.rows.clone() }.display());
+            lines.push(String::new());
+        }
+        if lines.is_empty() {
+            lines.push("Genie returned no answer.".to_string());
+            lines.push(String::new());
+        }
+        lines.push(format!(
+            "Conversation: {} (pass as conversation_id for follow-up questions)",
+            self.conversation_id
+        ));
+        lines.join("\n")
     }
 }
 
+impl ToolResultDisplay for ListDashboardsResult {
+    fn display(&self) -> String {
+        if self.dashboards.is_empty() {
+            return "No dashboards found.".to_string();
+        }
+        let mut lines = vec![
+            format!("Found {} dashboards:", self.dashboards.len()),
+            String::new(),
+        ];
+        for dashboard in &self.dashboards {
+            let name = dashboard.display_name.as_deref().unwrap_or("(untitled)");
+            let mut info = format!("• {} (id: {})", name, dashboard.dashboard_id);
+            if let Some(path) = &dashboard.path {
+                info.push_str(&format!(" - {}", path));
+            }
+            if let Some(updated) = &dashboard.update_time {
+                info.push_str(&format!(" - updated {}", updated));
+            }
+            lines.push(info);
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for DashboardQueriesResult {
+    fn display(&self) -> String {
+        let name = self.display_name.as_deref().unwrap_or(&self.dashboard_id);
+        if self.datasets.is_empty() {
+            return format!("Dashboard '{}' has no datasets.", name);
+        }
+        let mut lines = vec![format!(
+            "Dashboard '{}' has {} datasets:",
+            name,
+            self.datasets.len()
+        )];
+        if let Some(warehouse_id) = &self.warehouse_id {
+            lines.push(format!("Warehouse: {}", warehouse_id));
+        }
+        for dataset in &self.datasets {
+            lines.push(String::new());
+            match &dataset.display_name {
+                Some(display_name) => lines.push(format!("• {} ({})", display_name, dataset.name)),
+                None => lines.push(format!("• {}", dataset.name)),
+            }
+            lines.push("```sql".to_string());
+            lines.push(dataset.query.trim_end().to_string());
+            lines.push("```".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ListServingEndpointsResult {
+    fn display(&self) -> String {
+        if self.endpoints.is_empty() {
+            return "No serving endpoints found.".to_string();
+        }
+        let mut lines = vec![
+            format!("Found {} serving endpoints:", self.endpoints.len()),
+            String::new(),
+        ];
+        for endpoint in &self.endpoints {
+            let mut info = format!("• {}", endpoint.name);
+            if let Some(ready) = endpoint.state.as_ref().and_then(|s| s.ready.as_ref()) {
+                info.push_str(&format!(" [{}]", ready));
+            }
+            if let Some(task) = &endpoint.task {
+                info.push_str(&format!(" - task: {}", task));
+            }
+            let entities: Vec<String> = endpoint
+                .config
+                .iter()
+                .flat_map(|c| &c.served_entities)
+                .filter_map(|e| {
+                    e.entity_name.as_ref().map(|name| match &e.entity_version {
+                        Some(version) => format!("{} v{}", name, version),
+                        None => name.clone(),
+                    })
+                })
+                .collect();
+            if !entities.is_empty() {
+                info.push_str(&format!(" - serves: {}", entities.join(", ")));
+            }
+            lines.push(info);
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ServingEndpointSchema {
+    fn display(&self) -> String {
+        let mut lines = vec![format!("Endpoint: {}", self.name)];
+        if let Some(task) = &self.task {
+            lines.push(format!("Task: {}", task));
+        }
+        match &self.request_schema {
+            Some(schema) => {
+                lines.push("\nRequest schema:".to_string());
+                lines.push(serde_json::to_string_pretty(schema).unwrap_or_default());
+            }
+            None => lines.push("\nNo request schema published for this endpoint.".to_string()),
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for QueryServingEndpointResult {
+    fn display(&self) -> String {
+        format!(
+            "Response from {}:\n{}",
+            self.name,
+            serde_json::to_string_pretty(&self.response).unwrap_or_default()
+        )
+    }
+}
+
+/// Extracts the JSON request body schema from a serving endpoint OpenAPI spec.
+fn extract_request_schema(openapi: &Value) -> Option<Value> {
+    openapi
+        .get("paths")?
+        .as_object()?
+        .values()
+        .find_map(|path| {
+            path.pointer("/post/requestBody/content/application~1json/schema")
+                .cloned()
+        })
+}
+
+/// Extracts dataset queries from a Lakeview `serialized_dashboard` definition.
+/// Queries are stored either as `query` or split across `queryLines`.
+fn extract_dashboard_datasets(serialized_dashboard: &str) -> Result<Vec<DashboardDataset>> {
+    let definition: Value = serde_json::from_str(serialized_dashboard)
+        .map_err(|e| anyhow!("Failed to parse dashboard definition: {}", e))?;
+    let datasets = definition
+        .get("datasets")
+        .and_then(|d| d.as_array())
+        .map(|d| d.as_slice())
+        .unwrap_or_default();
+
+    Ok(datasets
+        .iter()
+        .filter_map(|dataset| {
+            let query = match dataset.get("queryLines").and_then(|l| l.as_array()) {
+                Some(lines) => lines.iter().filter_map(|l| l.as_str()).collect::<String>(),
+                None => dataset.get("query")?.as_str()?.to_string(),
+            };
+            Some(DashboardDataset {
+                name: dataset.get("name")?.as_str()?.to_string(),
+                display_name: dataset
+                    .get("displayName")
+                    .and_then(|n| n.as_str())
+                    .map(str::to_string),
+                query,
+            })
+        })
+        .collect())
+}
+
 fn format_value(value: &Value) -> String {
     match value {
         Value::String(s) => s.clone(),
@@ -494,6 +1339,7 @@ struct StatementError {
 #[derive(Debug, Deserialize)]
 struct ResultManifest {
     schema: Option<Schema>,
+    total_row_count: Option<usize>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -510,46 +1356,180 @@ struct Column {
 #[derive(Debug, Deserialize)]
 struct StatementResult {
     data_array: Option<Vec<Vec<Option<String>>>>,
+    external_links: Option<Vec<ExternalLink>>,
+    next_chunk_index: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalLink {
+    external_link: String,
+    next_chunk_index: Option<usize>,
+}
+
+/// Streams rows of a statement executed with EXTERNAL_LINKS disposition, one chunk at a time.
+pub struct SqlResultStream<'a> {
+    client: &'a DatabricksRestClient,
+    statement_id: String,
+    schema: Schema,
+    format: ResultFormat,
+    total_row_count: Option<usize>,
+    pending: Option<StatementResult>,
+    next_chunk_index: Option<usize>,
+}
+
+impl SqlResultStream<'_> {
+    pub fn columns(&self) -> Vec<String> {
+        self.schema.columns.iter().map(|c| c.name.clone()).collect()
+    }
+
+    /// Total rows reported by the warehouse manifest, if known
+    pub fn total_row_count(&self) -> Option<usize> {
+        self.total_row_count
+    }
+
+    /// Fetch the next chunk of rows. Returns `None` once all chunks are consumed.
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<HashMap<String, Value>>>> {
+        let result = match (self.pending.take(), self.next_chunk_index) {
+            (Some(result), _) => result,
+            (None, Some(index)) => self.client.fetch_chunk(&self.statement_id, index).await?,
+            (None, None) => return Ok(None),
+        };
+
+        let mut rows = Vec::new();
+        let mut next_chunk_index = result.next_chunk_index;
+
+        if let Some(data_array) = &result.data_array {
+            rows.extend(self.client.process_data_array(&self.schema, data_array)?);
+        }
+        for link in result.external_links.iter().flatten() {
+            let mut data_array = self.client.download_chunk(&link.external_link, self.format).await?;
+            // CSV chunks may start with a header row
+            if self.format == ResultFormat::Csv && data_array.first().is_some_and(|row| self.is_header(row)) {
+                data_array.remove(0);
+            }
+            rows.extend(self.client.process_data_array(&self.schema, &data_array)?);
+            next_chunk_index = link.next_chunk_index.or(next_chunk_index);
+        }
+
+        debug!(
+            "Fetched {} rows for statement {}, next chunk: {:?}",
+            rows.len(),
+            self.statement_id,
+            next_chunk_index
+        );
+        self.next_chunk_index = next_chunk_index;
+        Ok(Some(rows))
+    }
+
+    fn is_header(&self, row: &[Option<String>]) -> bool {
+        row.len() == self.schema.columns.len()
+            && row
+                .iter()
+                .zip(&self.schema.columns)
+                .all(|(value, column)| value.as_deref() == Some(column.name.as_str()))
+    }
+
+    /// Drain the stream, stopping after `max_rows` rows.
+    pub async fn collect(mut self, max_rows: usize) -> Result<Vec<HashMap<String, Value>>> {
+        let mut rows = Vec::new();
+        while rows.len() < max_rows {
+            match self.next_chunk().await? {
+                Some(chunk) => rows.extend(chunk),
+                None => break,
+            }
+        }
+        rows.truncate(max_rows);
+        Ok(rows)
+    }
 }
 
 pub struct DatabricksRestClient {
     host: String,
-    token: String,
-    warehouse_id: String,
+    auth: TokenProvider,
+    /// Warehouse from config/env; when unset one is picked from the workspace on first use.
+    warehouse_id: Option<String>,
+    active_warehouse: tokio::sync::OnceCell<String>,
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    circuit_breaker: CircuitBreaker,
+    names_cache: TtlCache<Vec<String>>,
+    tables_cache: TtlCache<Vec<TableInfo>>,
+    details_cache: TtlCache<TableDetails>,
 }
 
 impl DatabricksRestClient {
     pub fn new() -> Result<Self> {
-        let host = std::env::var("DATABRICKS_HOST")
-            .map_err(|_| anyhow!("DATABRICKS_HOST environment variable not set"))?;
-        let token = std::env::var("DATABRICKS_TOKEN")
-            .map_err(|_| anyhow!("DATABRICKS_TOKEN environment variable not set"))?;
-        let warehouse_id = std::env::var("DATABRICKS_WAREHOUSE_ID")
-            .map_err(|_| anyhow!("DATABRICKS_WAREHOUSE_ID environment variable not set"))?;
-
-        let host = if host.starts_with("http") {
-            host
-        } else {
-            format!("https://{}", host)
-        };
+        Self::with_auth(&DatabricksAuthConfig::default())
+    }
+
+    /// Create a client using the given auth method (PAT, OAuth or `~/.databrickscfg` profile)
+    pub fn with_auth(config: &DatabricksAuthConfig) -> Result<Self> {
+        let resolved = config.resolve()?;
 
         Ok(Self {
-            host,
-            token,
-            warehouse_id,
+            auth: TokenProvider::new(resolved.host.clone(), resolved.credentials),
+            host: resolved.host,
+            warehouse_id: resolved.warehouse_id,
+            active_warehouse: tokio::sync::OnceCell::new(),
             client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            circuit_breaker: CircuitBreaker::new(CIRCUIT_BREAKER_THRESHOLD, CIRCUIT_BREAKER_COOLDOWN),
+            names_cache: TtlCache::new(METADATA_CACHE_TTL),
+            tables_cache: TtlCache::new(METADATA_CACHE_TTL),
+            details_cache: TtlCache::new(METADATA_CACHE_TTL),
         })
     }
 
-    fn auth_headers(&self) -> reqwest::header::HeaderMap {
+    /// Override how transient failures (429, 5xx, network errors) are retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Override how long catalog/schema/table metadata is cached. `Duration::ZERO` disables caching.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.names_cache = TtlCache::new(ttl);
+        self.tables_cache = TtlCache::new(ttl);
+        self.details_cache = TtlCache::new(ttl);
+        self
+    }
+
+    /// Drop cached metadata. With `None` everything is dropped, otherwise only entries
+    /// under the given `catalog`, `catalog.schema` or `catalog.schema.table`.
+    pub fn invalidate_metadata_cache(&self, scope: Option<&str>) {
+        let Some(scope) = scope else {
+            self.names_cache.clear();
+            self.tables_cache.clear();
+            self.details_cache.clear();
+            return;
+        };
+        let catalog = scope.split('.').next().unwrap_or(scope);
+        self.names_cache.invalidate_prefix("catalogs");
+        self.names_cache.invalidate_prefix(&format!("schemas:{}", catalog));
+        self.tables_cache.invalidate_prefix(&format!("tables:{}", scope));
+        self.details_cache.invalidate_prefix(&format!("describe:{}", scope));
+    }
+
+    /// Drop metadata the statement may change: everything for DDL, table details for DML.
+    fn invalidate_for_statement(&self, sql: &str) {
+        if is_ddl(sql) {
+            self.invalidate_metadata_cache(None);
+        } else if is_dml(sql) {
+            self.details_cache.clear();
+        }
+    }
+
+    async fn auth_headers(&self) -> Result<reqwest::header::HeaderMap> {
+        let token = self.auth.token(&self.client).await?;
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             "Authorization",
-            format!("Bearer {}", self.token).parse().unwrap(),
+            format!("Bearer {}", token)
+                .parse()
+                .map_err(|_| anyhow!("Invalid characters in Databricks token"))?,
         );
         headers.insert("Content-Type", "application/json".parse().unwrap());
-        headers
+        Ok(headers)
     }
 
     async fn api_request<T>(
@@ -561,53 +1541,252 @@ impl DatabricksRestClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        debug!("Making {} request to {}", method, url);
+        let response = self
+            .send_with_retry(&method, url, |request| match body {
+                Some(body) => request.json(body),
+                None => request,
+            })
+            .await?;
 
-        let mut request = self
-            .client
-            .request(method, url)
-            .headers(self.auth_headers());
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| anyhow!("Failed to read response text: {}", e))?;
+
+        debug!("Response body: {}", response_text);
+
+        serde_json::from_str(&response_text).map_err(|e| {
+            anyhow!(
+                "Failed to parse JSON response: {}. Response: {}",
+                e,
+                response_text
+            )
+        })
+    }
+
+    /// Send an authenticated request, retrying rate limits (honoring `Retry-After`),
+    /// 5xx and network errors with jittered exponential backoff. Requests that aren't
+    /// idempotent (e.g. statement submissions) are only retried when the server can't
+    /// have acted on them: rate limited or not connected. Repeated failures open a
+    /// circuit breaker that fails fast until the cooldown passes.
+    /// Non-success responses are returned as [`DatabricksError`].
+    async fn send_with_retry(
+        &self,
+        method: &reqwest::Method,
+        url: &str,
+        build: impl Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            if let Err(retry_in) = self.circuit_breaker.check() {
+                return Err(DatabricksError::CircuitOpen { retry_in }.into());
+            }
+
+            debug!("Making {} request to {} (attempt {})", method, url, attempt + 1);
+            let request = self
+                .client
+                .request(method.clone(), url)
+                .headers(self.auth_headers().await?);
+
+            let (error, connected) = match build(request).send().await {
+                Ok(response) if response.status().is_success() => {
+                    self.circuit_breaker.record_success();
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let headers = response.headers().clone();
+                    let message = response.text().await.unwrap_or_default();
+                    debug!("Response status: {}, body: {}", status, message);
+                    (DatabricksError::from_response(status, &headers, message), true)
+                }
+                Err(e) => (DatabricksError::Network(e.to_string()), !e.is_connect()),
+            };
+
+            if !error.is_transient() {
+                // the API answered, so it's up
+                self.circuit_breaker.record_success();
+                return Err(error.into());
+            }
+            self.circuit_breaker.record_failure();
+            let safe_to_retry = is_idempotent(method)
+                || !connected
+                || matches!(error, DatabricksError::RateLimited { .. });
+            if !safe_to_retry || attempt >= self.retry_policy.max_retries {
+                return Err(error.into());
+            }
+
+            let delay = error
+                .retry_after()
+                .map(|d| d.min(self.retry_policy.max_delay))
+                .unwrap_or_else(|| self.retry_policy.backoff(attempt));
+            debug!("Transient Databricks error ({}), retrying in {:?}", error, delay);
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
 
-        if let Some(body) = body {
-            request = request.json(body);
+    pub async fn execute_sql(
+        &self,
+        request: &ExecuteSqlRequest,
+    ) -> Result<ExecuteSqlResult> {
+        self.invalidate_for_statement(&request.query);
+        let rows = self.execute_sql_impl(&request.query).await?;
+        Ok(ExecuteSqlResult { rows })
+    }
+
+    /// Execute SQL with a row cap and/or timeout. Small results are fetched inline,
+    /// larger ones are streamed chunk by chunk via EXTERNAL_LINKS.
+    /// Statements exceeding the timeout are cancelled on the warehouse.
+    pub async fn execute_sql_with_options(
+        &self,
+        request: &ExecuteSqlRequest,
+        options: &ExecuteSqlOptions,
+    ) -> Result<ExecuteSqlResult> {
+        self.invalidate_for_statement(&request.query);
+        let max_rows = options.max_rows.unwrap_or(INLINE_ROW_LIMIT);
+        if max_rows <= INLINE_ROW_LIMIT {
+            let mut rows = self
+                .execute_inline(&request.query, options.timeout)
+                .await?;
+            rows.truncate(max_rows);
+            return Ok(ExecuteSqlResult { rows });
         }
+        let stream = self
+            .submit_external_links(
+                &request.query,
+                Some(max_rows),
+                ResultFormat::ArrowStream,
+                options.timeout,
+            )
+            .await?;
+        let rows = stream.collect(max_rows).await?;
+        Ok(ExecuteSqlResult { rows })
+    }
 
-        let response = request
+    /// Cancel a running statement (`POST /api/2.0/sql/statements/{id}/cancel`).
+    /// Cancellation is best-effort; the statement may already have finished.
+    pub async fn cancel_statement(&self, statement_id: &str) -> Result<()> {
+        let url = format!(
+            "{}{}/{}/cancel",
+            self.host, SQL_STATEMENTS_ENDPOINT, statement_id
+        );
+        debug!("Cancelling statement {}", statement_id);
+        let response = self
+            .client
+            .post(&url)
+            .headers(self.auth_headers().await?)
             .send()
             .await
             .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
-
         let status = response.status();
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| anyhow!("Failed to read response text: {}", e))?;
-
-        debug!("Response status: {}, body: {}", status, response_text);
-
         if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
             return Err(anyhow!(
-                "API request failed with status {}: {}",
+                "Failed to cancel statement {} (status {}): {}",
+                statement_id,
                 status,
-                response_text
+                body
             ));
         }
+        Ok(())
+    }
 
-        serde_json::from_str(&response_text).map_err(|e| {
-            anyhow!(
-                "Failed to parse JSON response: {}. Response: {}",
-                e,
-                response_text
-            )
+    /// Execute SQL with EXTERNAL_LINKS disposition and return a stream over the result chunks.
+    pub async fn execute_sql_stream(
+        &self,
+        sql: &str,
+        row_limit: Option<usize>,
+        format: ResultFormat,
+    ) -> Result<SqlResultStream<'_>> {
+        self.submit_external_links(sql, row_limit, format, None)
+            .await
+    }
+
+    async fn submit_external_links(
+        &self,
+        sql: &str,
+        row_limit: Option<usize>,
+        format: ResultFormat,
+        timeout: Option<Duration>,
+    ) -> Result<SqlResultStream<'_>> {
+        let request = SqlStatementRequest {
+            statement: sql.to_string(),
+            warehouse_id: self.warehouse_id().await?,
+            catalog: None,
+            schema: None,
+            parameters: None,
+            row_limit: row_limit.map(|l| l as i64),
+            byte_limit: None,
+            disposition: "EXTERNAL_LINKS".to_string(),
+            format: format.as_str().to_string(),
+            wait_timeout: Some(wait_timeout(timeout)),
+            on_wait_timeout: Some("CONTINUE".to_string()),
+        };
+
+        // the timeout covers the server-side wait too
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let url = format!("{}{}", self.host, SQL_STATEMENTS_ENDPOINT);
+        let response: SqlStatementResponse = self
+            .api_request(reqwest::Method::POST, &url, Some(&request))
+            .await?;
+        let response = self.wait_for_statement(response, timeout, deadline).await?;
+
+        let manifest = response
+            .manifest
+            .ok_or_else(|| anyhow!("No manifest in response"))?;
+        let schema = manifest
+            .schema
+            .ok_or_else(|| anyhow!("No schema in response"))?;
+
+        Ok(SqlResultStream {
+            client: self,
+            statement_id: response.statement_id,
+            schema,
+            format,
+            total_row_count: manifest.total_row_count,
+            pending: response.result,
+            next_chunk_index: None,
         })
     }
 
-    pub async fn execute_sql(
-        &self,
-        request: &ExecuteSqlRequest,
-    ) -> Result<ExecuteSqlResult> {
-        let rows = self.execute_sql_impl(&request.query).await?;
-        Ok(ExecuteSqlResult { rows })
+    async fn fetch_chunk(&self, statement_id: &str, chunk_index: usize) -> Result<StatementResult> {
+        let url = format!(
+            "{}{}/{}/result/chunks/{}",
+            self.host, SQL_STATEMENTS_ENDPOINT, statement_id, chunk_index
+        );
+        self.api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await
+    }
+
+    /// Download a chunk from a presigned external link (must not carry the Databricks token).
+    async fn download_chunk(
+        &self,
+        link: &str,
+        format: ResultFormat,
+    ) -> Result<Vec<Vec<Option<String>>>> {
+        let response = self
+            .client
+            .get(link)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to download result chunk: {}", e))?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "Result chunk download failed with status {}: {}",
+                status,
+                String::from_utf8_lossy(&body)
+            ));
+        }
+
+        match format {
+            ResultFormat::JsonArray => serde_json::from_slice(&body)
+                .map_err(|e| anyhow!("Failed to parse JSON result chunk: {}", e)),
+            ResultFormat::Csv => parse_csv_chunk(&body),
+            ResultFormat::ArrowStream => parse_arrow_chunk(&body),
+        }
     }
 
     /// Execute SQL with named parameters for safe dynamic queries
@@ -624,7 +1803,7 @@ impl DatabricksRestClient {
 
         let request = SqlStatementRequest {
             statement: sql.to_string(),
-            warehouse_id: self.warehouse_id.clone(),
+            warehouse_id: self.warehouse_id().await?,
             catalog: None,
             schema: None,
             parameters: params,
@@ -660,50 +1839,82 @@ impl DatabricksRestClient {
     }
 
     async fn execute_sql_impl(&self, sql: &str) -> Result<Vec<HashMap<String, Value>>> {
+        self.execute_inline(sql, None).await
+    }
+
+    async fn execute_inline(
+        &self,
+        sql: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<HashMap<String, Value>>> {
         let request = SqlStatementRequest {
             statement: sql.to_string(),
-            warehouse_id: self.warehouse_id.clone(),
+            warehouse_id: self.warehouse_id().await?,
             catalog: None,
             schema: None,
             parameters: None,
-            row_limit: Some(100),
+            row_limit: Some(INLINE_ROW_LIMIT as i64),
             byte_limit: None,
             disposition: "INLINE".to_string(),
             format: "JSON_ARRAY".to_string(),
-            wait_timeout: Some(DEFAULT_WAIT_TIMEOUT.to_string()),
+            wait_timeout: Some(wait_timeout(timeout)),
             on_wait_timeout: Some("CONTINUE".to_string()),
         };
 
+        // the timeout covers the server-side wait too
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
         let url = format!("{}{}", self.host, SQL_STATEMENTS_ENDPOINT);
         let response: SqlStatementResponse = self
             .api_request(reqwest::Method::POST, &url, Some(&request))
             .await?;
 
-        // Check if we need to poll for results
-        if let Some(status) = &response.status {
-            if status.state == "PENDING" || status.state == "RUNNING" {
-                return self.poll_for_results(&response.statement_id).await;
-            } else if status.state == "FAILED" {
-                let error_msg = status
-                    .error
-                    .as_ref()
-                    .and_then(|e| e.message.as_ref())
-                    .map(|m| m.as_str())
-                    .unwrap_or("Unknown error");
-                return Err(anyhow!("SQL execution failed: {}", error_msg));
-            }
-        }
-
+        let response = self.wait_for_statement(response, timeout, deadline).await?;
         self.process_statement_result(&response)
     }
 
     async fn poll_for_results(&self, statement_id: &str) -> Result<Vec<HashMap<String, Value>>> {
-        for attempt in 0..MAX_POLL_ATTEMPTS {
-            debug!(
-                "Polling attempt {} for statement {}",
-                attempt + 1,
-                statement_id
-            );
+        let response = self.poll_statement(statement_id, None, None).await?;
+        self.process_statement_result(&response)
+    }
+
+    /// Wait for a submitted statement to finish, polling if it is still running.
+    /// `deadline` is when `timeout` runs out, counted from the submission.
+    async fn wait_for_statement(
+        &self,
+        response: SqlStatementResponse,
+        timeout: Option<Duration>,
+        deadline: Option<Instant>,
+    ) -> Result<SqlStatementResponse> {
+        match response.status.as_ref().map(|s| s.state.as_str()) {
+            Some("PENDING") | Some("RUNNING") => {
+                self.poll_statement(&response.statement_id, timeout, deadline)
+                    .await
+            }
+            Some("FAILED") => Err(statement_error(&response)),
+            _ => Ok(response),
+        }
+    }
+
+    /// Poll until the statement finishes. With a deadline the statement is cancelled
+    /// once it passes, otherwise polling gives up after MAX_POLL_ATTEMPTS.
+    async fn poll_statement(
+        &self,
+        statement_id: &str,
+        timeout: Option<Duration>,
+        deadline: Option<Instant>,
+    ) -> Result<SqlStatementResponse> {
+        let mut attempt = 0;
+
+        loop {
+            let expired = match deadline {
+                Some(deadline) => Instant::now() >= deadline,
+                None => attempt >= MAX_POLL_ATTEMPTS,
+            };
+            if expired {
+                break;
+            }
+            attempt += 1;
+            debug!("Polling attempt {} for statement {}", attempt, statement_id);
 
             let url = format!("{}{}/{}", self.host, SQL_STATEMENTS_ENDPOINT, statement_id);
             let response: SqlStatementResponse = self
@@ -712,18 +1923,17 @@ impl DatabricksRestClient {
 
             if let Some(status) = &response.status {
                 match status.state.as_str() {
-                    "SUCCEEDED" => return self.process_statement_result(&response),
-                    "FAILED" => {
-                        let error_msg = status
-                            .error
-                            .as_ref()
-                            .and_then(|e| e.message.as_ref())
-                            .map(|m| m.as_str())
-                            .unwrap_or("Unknown error");
-                        return Err(anyhow!("SQL execution failed: {}", error_msg));
+                    "SUCCEEDED" => return Ok(response),
+                    "FAILED" => return Err(statement_error(&response)),
+                    "CANCELED" | "CLOSED" => {
+                        return Err(anyhow!("Statement {} was {}", statement_id, status.state.to_lowercase()));
                     }
                     "PENDING" | "RUNNING" => {
-                        sleep(Duration::from_secs(2)).await;
+                        let delay = match deadline {
+                            Some(deadline) => POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())),
+                            None => POLL_INTERVAL,
+                        };
+                        sleep(delay).await;
                         continue;
                     }
                     _ => return Err(anyhow!("Unexpected statement state: {}", status.state)),
@@ -731,10 +1941,20 @@ impl DatabricksRestClient {
             }
         }
 
-        Err(anyhow!(
-            "Polling timeout exceeded for statement {}",
-            statement_id
-        ))
+        if let Err(e) = self.cancel_statement(statement_id).await {
+            debug!("Failed to cancel timed out statement {}: {}", statement_id, e);
+        }
+        match timeout {
+            Some(timeout) => Err(anyhow!(
+                "Query timed out after {}s and was cancelled (statement {})",
+                timeout.as_secs(),
+                statement_id
+            )),
+            None => Err(anyhow!(
+                "Polling timeout exceeded for statement {}",
+                statement_id
+            )),
+        }
     }
 
     fn process_statement_result(
@@ -804,7 +2024,14 @@ impl DatabricksRestClient {
     }
 
     pub async fn list_catalogs(&self) -> Result<ListCatalogsResult> {
-        let catalogs = self.list_catalogs_impl().await?;
+        let catalogs = match self.names_cache.get("catalogs") {
+            Some(catalogs) => catalogs,
+            None => {
+                let catalogs = self.list_catalogs_impl().await?;
+                self.names_cache.insert("catalogs", catalogs.clone());
+                catalogs
+            }
+        };
         Ok(ListCatalogsResult { catalogs })
     }
 
@@ -849,7 +2076,15 @@ impl DatabricksRestClient {
         &self,
         request: &ListSchemasRequest,
     ) -> Result<ListSchemasResult> {
-        let mut schemas = self.list_schemas_impl(&request.catalog_name).await?;
+        let cache_key = format!("schemas:{}", request.catalog_name);
+        let mut schemas = match self.names_cache.get(&cache_key) {
+            Some(schemas) => schemas,
+            None => {
+                let schemas = self.list_schemas_impl(&request.catalog_name).await?;
+                self.names_cache.insert(cache_key, schemas.clone());
+                schemas
+            }
+        };
 
         // Apply filter if provided
         if let Some(filter) = &request.filter {
@@ -907,12 +2142,20 @@ impl DatabricksRestClient {
         match (&request.catalog_name, &request.schema_name) {
             (Some(catalog), Some(schema)) => {
                 // Fast path - use REST API for specific catalog/schema
-                let mut tables = self.list_tables_impl(
-                    catalog,
-                    schema,
-                    true, // always exclude inaccessible tables
-                )
-                .await?;
+                let cache_key = format!("tables:{}.{}", catalog, schema);
+                let mut tables = match self.tables_cache.get(&cache_key) {
+                    Some(tables) => tables,
+                    None => {
+                        let tables = self.list_tables_impl(
+                            catalog,
+                            schema,
+                            true, // always exclude inaccessible tables
+                        )
+                        .await?;
+                        self.tables_cache.insert(cache_key, tables.clone());
+                        tables
+                    }
+                };
 
                 // Apply filter if provided
                 if let Some(filter) = &request.filter {
@@ -1104,12 +2347,498 @@ impl DatabricksRestClient {
         Ok(tables)
     }
 
+    /// Send a request to the Files API, returning the raw response on success.
+    async fn files_request(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response> {
+        self.send_with_retry(&method, url, |request| {
+            let request = request.header("Content-Type", "application/octet-stream");
+            match &body {
+                Some(body) => request.body(body.clone()),
+                None => request,
+            }
+        })
+        .await
+    }
+
+    pub async fn list_volume_files(
+        &self,
+        request: &ListVolumeFilesRequest,
+    ) -> Result<ListVolumeFilesResult> {
+        let encoded = encode_volume_path(request.path.trim_end_matches('/'))?;
+        let mut files = Vec::new();
+        let mut next_page_token: Option<String> = None;
+
+        loop {
+            let mut url = format!("{}{}{}", self.host, DIRECTORIES_ENDPOINT, encoded);
+            if let Some(token) = &next_page_token {
+                url.push_str(&format!("?page_token={}", urlencoding::encode(token)));
+            }
+
+            let response: DirectoryContentsResponse = self
+                .api_request(reqwest::Method::GET, &url, None::<&()>)
+                .await?;
+
+            for entry in response.contents.unwrap_or_default() {
+                let name = entry.name.unwrap_or_else(|| {
+                    entry
+                        .path
+                        .trim_end_matches('/')
+                        .rsplit('/')
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                });
+                files.push(VolumeFileInfo {
+                    path: entry.path,
+                    name,
+                    is_directory: entry.is_directory,
+                    file_size: entry.file_size,
+                    last_modified: entry.last_modified,
+                });
+            }
+
+            if response.next_page_token.is_some() {
+                next_page_token = response.next_page_token;
+            } else {
+                break;
+            }
+        }
+
+        Ok(ListVolumeFilesResult {
+            path: request.path.clone(),
+            files,
+        })
+    }
+
+    pub async fn upload_file(&self, request: &UploadFileRequest) -> Result<UploadFileResult> {
+        let url = format!(
+            "{}{}{}?overwrite={}",
+            self.host,
+            FILES_ENDPOINT,
+            encode_volume_path(&request.volume_path)?,
+            request.overwrite
+        );
+        self.files_request(reqwest::Method::PUT, &url, Some(request.contents.clone()))
+            .await?;
+        Ok(UploadFileResult {
+            volume_path: request.volume_path.clone(),
+            bytes_written: request.contents.len(),
+        })
+    }
+
+    pub async fn download_file(&self, volume_path: &str) -> Result<Vec<u8>> {
+        let url = format!(
+            "{}{}{}",
+            self.host,
+            FILES_ENDPOINT,
+            encode_volume_path(volume_path)?
+        );
+        let response = self
+            .files_request(reqwest::Method::GET, &url, None)
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| anyhow!("Failed to read file contents: {}", e))?;
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn list_warehouses(&self) -> Result<ListWarehousesResult> {
+        let url = format!("{}{}", self.host, WAREHOUSES_ENDPOINT);
+        let response: WarehousesListResponse = self
+            .api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await?;
+        Ok(ListWarehousesResult {
+            warehouses: response.warehouses.unwrap_or_default(),
+            configured_warehouse_id: self.warehouse_id.clone(),
+        })
+    }
+
+    pub async fn get_warehouse(&self, warehouse_id: &str) -> Result<SqlWarehouse> {
+        let url = format!("{}{}/{}", self.host, WAREHOUSES_ENDPOINT, warehouse_id);
+        self.api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await
+    }
+
+    /// Start a stopped warehouse. Returns immediately; use [`Self::wait_for_warehouse`] to block.
+    pub async fn start_warehouse(&self, warehouse_id: &str) -> Result<()> {
+        let url = format!("{}{}/{}/start", self.host, WAREHOUSES_ENDPOINT, warehouse_id);
+        debug!("Starting warehouse {}", warehouse_id);
+        let _: Value = self
+            .api_request(reqwest::Method::POST, &url, None::<&()>)
+            .await?;
+        Ok(())
+    }
+
+    /// Poll until the warehouse is RUNNING, starting it if it is stopped.
+    pub async fn wait_for_warehouse(
+        &self,
+        warehouse_id: &str,
+        timeout: Duration,
+    ) -> Result<SqlWarehouse> {
+        let deadline = Instant::now() + timeout;
+        // the state can read STOPPED for a while after the start request, so start only once
+        let mut started = false;
+        loop {
+            let warehouse = self.get_warehouse(warehouse_id).await?;
+            match warehouse.state() {
+                "RUNNING" => return Ok(warehouse),
+                "STOPPED" if !started => {
+                    self.start_warehouse(warehouse_id).await?;
+                    started = true;
+                }
+                "DELETING" | "DELETED" => {
+                    return Err(anyhow!(
+                        "Warehouse {} ({}) is {}",
+                        warehouse.name,
+                        warehouse_id,
+                        warehouse.state()
+                    ));
+                }
+                state => debug!("Warehouse {} is {}, waiting", warehouse_id, state),
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Warehouse {} did not become available within {}s (state: {})",
+                    warehouse_id,
+                    timeout.as_secs(),
+                    warehouse.state()
+                ));
+            }
+            sleep(WAREHOUSE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Return a running warehouse, starting one if needed. Prefers `DATABRICKS_WAREHOUSE_ID`;
+    /// otherwise picks a RUNNING warehouse, then a STARTING one, then starts a STOPPED one.
+    pub async fn get_available_warehouse(&self) -> Result<String> {
+        if let Some(warehouse_id) = &self.warehouse_id {
+            self.wait_for_warehouse(warehouse_id, WAREHOUSE_START_TIMEOUT)
+                .await?;
+            return Ok(warehouse_id.clone());
+        }
+
+        let warehouses = self.list_warehouses().await?.warehouses;
+        let candidate = ["RUNNING", "STARTING", "STOPPED"].iter().find_map(|state| {
+            warehouses.iter().find(|w| w.state() == *state)
+        });
+        let warehouse = candidate.ok_or_else(|| {
+            anyhow!(
+                "No usable SQL warehouse found in the workspace. \
+                 Create one or set DATABRICKS_WAREHOUSE_ID."
+            )
+        })?;
+        debug!(
+            "Using warehouse {} ({}) in state {}",
+            warehouse.name,
+            warehouse.id,
+            warehouse.state()
+        );
+        if warehouse.state() != "RUNNING" {
+            self.wait_for_warehouse(&warehouse.id, WAREHOUSE_START_TIMEOUT)
+                .await?;
+        }
+        Ok(warehouse.id.clone())
+    }
+
+    /// Warehouse used for statements, resolved once per client.
+    async fn warehouse_id(&self) -> Result<String> {
+        self.active_warehouse
+            .get_or_try_init(|| self.get_available_warehouse())
+            .await
+            .cloned()
+    }
+
+    pub async fn list_serving_endpoints(&self) -> Result<ListServingEndpointsResult> {
+        let url = format!("{}{}", self.host, SERVING_ENDPOINTS_ENDPOINT);
+        let response: ServingEndpointsListResponse = self
+            .api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await?;
+        Ok(ListServingEndpointsResult {
+            endpoints: response.endpoints.unwrap_or_default(),
+        })
+    }
+
+    pub async fn get_serving_endpoint(&self, name: &str) -> Result<ServingEndpoint> {
+        let url = format!(
+            "{}{}/{}",
+            self.host,
+            SERVING_ENDPOINTS_ENDPOINT,
+            urlencoding::encode(name)
+        );
+        self.api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await
+    }
+
+    /// Discover the expected request payload from the endpoint's OpenAPI spec.
+    pub async fn get_serving_endpoint_schema(&self, name: &str) -> Result<ServingEndpointSchema> {
+        let endpoint = self.get_serving_endpoint(name).await?;
+        let url = format!(
+            "{}{}/{}/openapi",
+            self.host,
+            SERVING_ENDPOINTS_ENDPOINT,
+            urlencoding::encode(name)
+        );
+        // not every endpoint type publishes a spec
+        let request_schema = match self
+            .api_request::<Value>(reqwest::Method::GET, &url, None::<&()>)
+            .await
+        {
+            Ok(openapi) => extract_request_schema(&openapi),
+            Err(e) => {
+                debug!("No OpenAPI spec for serving endpoint {}: {}", name, e);
+                None
+            }
+        };
+        Ok(ServingEndpointSchema {
+            name: endpoint.name,
+            task: endpoint.task,
+            request_schema,
+        })
+    }
+
+    pub async fn query_serving_endpoint(
+        &self,
+        name: &str,
+        payload: &Value,
+    ) -> Result<QueryServingEndpointResult> {
+        let url = format!(
+            "{}{}/{}/invocations",
+            self.host,
+            SERVING_INVOCATIONS_ENDPOINT,
+            urlencoding::encode(name)
+        );
+        let response: Value = self
+            .api_request(reqwest::Method::POST, &url, Some(payload))
+            .await?;
+        Ok(QueryServingEndpointResult {
+            name: name.to_string(),
+            response,
+        })
+    }
+
+    pub async fn list_genie_spaces(&self) -> Result<Vec<GenieSpace>> {
+        let url = format!("{}{}", self.host, GENIE_SPACES_ENDPOINT);
+        let response: GenieSpacesListResponse = self
+            .api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await?;
+        Ok(response.spaces.unwrap_or_default())
+    }
+
+    /// Ask a Genie space a natural-language question. Genie translates it to SQL,
+    /// runs it and returns the query, its result and any text reply.
+    pub async fn genie_ask(&self, request: &GenieAskRequest) -> Result<GenieAnswer> {
+        let space_id = match request
+            .space_id
+            .clone()
+            .or_else(|| crate::env::var("DATABRICKS_GENIE_SPACE_ID").ok())
+        {
+            Some(space_id) => space_id,
+            None => {
+                let spaces = self.list_genie_spaces().await.unwrap_or_default();
+                let available: Vec<String> = spaces
+                    .iter()
+                    .map(|s| {
+                        format!("{} ({})", s.space_id, s.title.as_deref().unwrap_or("untitled"))
+                    })
+                    .collect();
+                return Err(anyhow!(
+                    "No Genie space specified: pass space_id or set DATABRICKS_GENIE_SPACE_ID. \
+                     Available spaces: {}",
+                    if available.is_empty() {
+                        "none".to_string()
+                    } else {
+                        available.join(", ")
+                    }
+                ));
+            }
+        };
+        let space_url = format!(
+            "{}{}/{}",
+            self.host,
+            GENIE_SPACES_ENDPOINT,
+            urlencoding::encode(&space_id)
+        );
+        let body = GenieMessageRequest {
+            content: &request.question,
+        };
+
+        let (conversation_id, message_id) = match &request.conversation_id {
+            Some(conversation_id) => {
+                let url = format!(
+                    "{}/conversations/{}/messages",
+                    space_url,
+                    urlencoding::encode(conversation_id)
+                );
+                let message: GenieMessage = self
+                    .api_request(reqwest::Method::POST, &url, Some(&body))
+                    .await?;
+                (message.conversation_id, message.id)
+            }
+            None => {
+                let url = format!("{}/start-conversation", space_url);
+                let response: GenieStartConversationResponse = self
+                    .api_request(reqwest::Method::POST, &url, Some(&body))
+                    .await?;
+                (response.conversation_id, response.message_id)
+            }
+        };
+
+        let message_url = format!(
+            "{}/conversations/{}/messages/{}",
+            space_url,
+            urlencoding::encode(&conversation_id),
+            urlencoding::encode(&message_id)
+        );
+        let deadline = Instant::now() + GENIE_TIMEOUT;
+        let message: GenieMessage = loop {
+            let message: GenieMessage = self
+                .api_request(reqwest::Method::GET, &message_url, None::<&()>)
+                .await?;
+            match message.status.as_deref() {
+                Some("COMPLETED") => break message,
+                Some("FAILED") | Some("CANCELLED") | Some("QUERY_RESULT_EXPIRED") => {
+                    return Err(anyhow!(
+                        "Genie could not answer ({}): {}",
+                        message.status.as_deref().unwrap_or_default(),
+                        message
+                            .error
+                            .as_ref()
+                            .map(|e| e.to_string())
+                            .unwrap_or_else(|| "no error details".to_string())
+                    ));
+                }
+                status => debug!("Genie message {} is {:?}, waiting", message_id, status),
+            }
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Genie did not answer within {}s",
+                    GENIE_TIMEOUT.as_secs()
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        };
+
+        let mut answer = GenieAnswer {
+            space_id,
+            conversation_id,
+            message_id,
+            text: None,
+            sql: None,
+            sql_description: None,
+            rows: Vec::new(),
+        };
+        for attachment in message.attachments.unwrap_or_default() {
+            if let Some(content) = attachment.text.and_then(|t| t.content) {
+                answer.text = Some(content);
+            }
+            let Some(query) = attachment.query else {
+                continue;
+            };
+            answer.sql = query.query;
+            answer.sql_description = query.description;
+            if let Some(attachment_id) = attachment.attachment_id {
+                let url = format!(
+                    "{}/attachments/{}/query-result",
+                    message_url,
+                    urlencoding::encode(&attachment_id)
+                );
+                let result: GenieQueryResultResponse = self
+                    .api_request(reqwest::Method::GET, &url, None::<&()>)
+                    .await?;
+                if let Some(statement) = &result.statement_response {
+                    answer.rows = self.process_statement_result(statement)?;
+                    answer.rows.truncate(INLINE_ROW_LIMIT);
+                }
+            }
+        }
+        Ok(answer)
+    }
+
+    pub async fn list_dashboards(&self) -> Result<ListDashboardsResult> {
+        let mut dashboards = Vec::new();
+        let mut next_page_token: Option<String> = None;
+
+        loop {
+            let mut url = format!("{}{}?page_size=100", self.host, LAKEVIEW_DASHBOARDS_ENDPOINT);
+            if let Some(token) = &next_page_token {
+                url.push_str(&format!("&page_token={}", urlencoding::encode(token)));
+            }
+
+            let response: DashboardsListResponse = self
+                .api_request(reqwest::Method::GET, &url, None::<&()>)
+                .await?;
+            dashboards.extend(response.dashboards.unwrap_or_default());
+
+            match response.next_page_token {
+                Some(token) if !token.is_empty() => next_page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        Ok(ListDashboardsResult { dashboards })
+    }
+
+    pub async fn get_dashboard(&self, dashboard_id: &str) -> Result<LakeviewDashboard> {
+        let url = format!(
+            "{}{}/{}",
+            self.host,
+            LAKEVIEW_DASHBOARDS_ENDPOINT,
+            urlencoding::encode(dashboard_id)
+        );
+        self.api_request(reqwest::Method::GET, &url, None::<&()>)
+            .await
+    }
+
+    /// Create a draft dashboard. Uses the client's warehouse if none is given.
+    pub async fn create_dashboard(
+        &self,
+        request: &CreateDashboardRequest,
+    ) -> Result<LakeviewDashboard> {
+        let mut request = request.clone();
+        if request.warehouse_id.is_none() {
+            request.warehouse_id = Some(self.warehouse_id().await?);
+        }
+        let url = format!("{}{}", self.host, LAKEVIEW_DASHBOARDS_ENDPOINT);
+        self.api_request(reqwest::Method::POST, &url, Some(&request))
+            .await
+    }
+
+    /// Fetch a dashboard and extract the SQL behind each of its datasets.
+    pub async fn export_dashboard_queries(
+        &self,
+        dashboard_id: &str,
+    ) -> Result<DashboardQueriesResult> {
+        let dashboard = self.get_dashboard(dashboard_id).await?;
+        let datasets = match &dashboard.serialized_dashboard {
+            Some(serialized) => extract_dashboard_datasets(serialized)?,
+            None => Vec::new(),
+        };
+        Ok(DashboardQueriesResult {
+            dashboard_id: dashboard.dashboard_id,
+            display_name: dashboard.display_name,
+            warehouse_id: dashboard.warehouse_id,
+            datasets,
+        })
+    }
+
     pub async fn describe_table(
         &self,
         request: &DescribeTableRequest,
     ) -> Result<TableDetails> {
-        self.get_table_details_impl(&request.table_full_name, request.sample_size)
-            .await
+        let cache_key = format!("describe:{}:{}", request.table_full_name, request.sample_size);
+        if let Some(details) = self.details_cache.get(&cache_key) {
+            return Ok(details);
+        }
+        let details = self
+            .get_table_details_impl(&request.table_full_name, request.sample_size)
+            .await?;
+        self.details_cache.insert(cache_key, details.clone());
+        Ok(details)
     }
 
     async fn get_table_details_impl(
@@ -1182,3 +2911,140 @@ impl DatabricksRestClient {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_classification() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "7".parse().unwrap());
+        let err = DatabricksError::from_response(
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            "slow down".to_string(),
+        );
+        assert!(err.is_transient());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+
+        let empty = reqwest::header::HeaderMap::new();
+        let err = DatabricksError::from_response(reqwest::StatusCode::FORBIDDEN, &empty, String::new());
+        assert!(err.is_auth() && !err.is_transient());
+        let err = DatabricksError::from_response(reqwest::StatusCode::NOT_FOUND, &empty, String::new());
+        assert!(!err.is_transient());
+        assert_eq!(err.status(), Some(404));
+    }
+
+    #[test]
+    fn test_is_ddl() {
+        assert!(is_ddl("  create table main.default.t (id INT)"));
+        assert!(is_ddl("DROP SCHEMA dev"));
+        assert!(!is_ddl("SELECT * FROM main.default.t"));
+        assert!(!is_ddl("CREATED"));
+        assert!(is_dml("insert into main.default.t values (1)"));
+        assert!(is_dml("MERGE INTO t USING s ON t.id = s.id WHEN MATCHED THEN DELETE"));
+        assert!(!is_dml("SELECT * FROM main.default.t"));
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(is_idempotent(&reqwest::Method::GET));
+        assert!(is_idempotent(&reqwest::Method::DELETE));
+        assert!(!is_idempotent(&reqwest::Method::POST));
+        assert!(!is_idempotent(&reqwest::Method::PATCH));
+    }
+
+    #[test]
+    fn test_wait_timeout() {
+        assert_eq!(wait_timeout(None), DEFAULT_WAIT_TIMEOUT);
+        assert_eq!(wait_timeout(Some(Duration::from_secs(2))), "0s");
+        assert_eq!(wait_timeout(Some(Duration::from_secs(10))), "10s");
+        assert_eq!(wait_timeout(Some(Duration::from_secs(300))), "30s");
+    }
+
+    #[test]
+    fn test_encode_volume_path() {
+        assert_eq!(
+            encode_volume_path("/Volumes/main/default/vol/my file.csv").unwrap(),
+            "/Volumes/main/default/vol/my%20file.csv"
+        );
+        assert!(encode_volume_path("/tmp/file.csv").is_err());
+        assert!(encode_volume_path("/Volumes/main/../etc").is_err());
+    }
+
+    #[test]
+    fn test_extract_dashboard_datasets() {
+        let serialized = r#"{
+            "datasets": [
+                {"name": "a1b2", "displayName": "Revenue", "queryLines": ["SELECT day, sum(amount)\n", "FROM sales GROUP BY day"]},
+                {"name": "c3d4", "query": "SELECT * FROM customers"}
+            ],
+            "pages": []
+        }"#;
+        let datasets = extract_dashboard_datasets(serialized).unwrap();
+        assert_eq!(datasets.len(), 2);
+        assert_eq!(datasets[0].display_name.as_deref(), Some("Revenue"));
+        assert_eq!(datasets[0].query, "SELECT day, sum(amount)\nFROM sales GROUP BY day");
+        assert_eq!(datasets[1].query, "SELECT * FROM customers");
+        assert!(extract_dashboard_datasets("{}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extract_request_schema() {
+        let openapi = serde_json::json!({
+            "paths": {
+                "/serving-endpoints/chat/invocations": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {"type": "object", "required": ["messages"]}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+        let schema = extract_request_schema(&openapi).unwrap();
+        assert_eq!(schema["required"][0], "messages");
+        assert!(extract_request_schema(&serde_json::json!({})).is_none());
+    }
+
+    #[test]
+    fn test_parse_csv_chunk() {
+        let rows = parse_csv_chunk(b"id,name\n1,alice\n2,\n3,\"x, y\"\n").unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[1], vec![Some("1".to_string()), Some("alice".to_string())]);
+        assert_eq!(rows[2], vec![Some("2".to_string()), None]);
+        assert_eq!(rows[3][1].as_deref(), Some("x, y"));
+    }
+
+    #[test]
+    fn test_parse_arrow_chunk() {
+        use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+        use std::sync::Arc;
+
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("alice"), None, Some("")])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut body = Vec::new();
+        let mut writer =
+            arrow_ipc::writer::StreamWriter::try_new(&mut body, &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let rows = parse_arrow_chunk(&body).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][1].as_deref(), Some("alice"));
+        assert_eq!(rows[1], vec![Some("2".to_string()), None]);
+        assert_eq!(rows[2][1].as_deref(), Some(""));
+    }
+}
@@ -0,0 +1,756 @@
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use log::{debug, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+const OIDC_TOKEN_ENDPOINT: &str = "/oidc/v1/token";
+const OIDC_AUTHORIZE_ENDPOINT: &str = "/oidc/v1/authorize";
+const DEFAULT_U2M_CLIENT_ID: &str = "databricks-cli";
+const DEFAULT_U2M_PORT: u16 = 8020;
+const DEFAULT_PROFILE: &str = "DEFAULT";
+const TOKEN_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+const TOKEN_CACHE_FILE: &str = "edda-token-cache.json";
+/// How long a browser login may take before giving up, other requests wait on it
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// How the Databricks client obtains credentials.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum DatabricksAuthConfig {
+    /// Resolve from environment: PAT, service principal, then `~/.databrickscfg` profile
+    #[default]
+    Env,
+    /// Personal access token from DATABRICKS_TOKEN
+    Pat,
+    /// Service principal (client credentials) from DATABRICKS_CLIENT_ID / DATABRICKS_CLIENT_SECRET
+    OAuthM2M,
+    /// Interactive browser login with refresh token caching
+    OAuthU2M {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+    },
+    /// Named profile from `~/.databrickscfg` (or DATABRICKS_CONFIG_FILE)
+    Profile { name: String },
+}
+
+/// Resolved credentials for a workspace.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatabricksCredentials {
+    Pat {
+        token: String,
+    },
+    OAuthM2M {
+        client_id: String,
+        client_secret: String,
+    },
+    OAuthU2M {
+        client_id: String,
+        port: u16,
+    },
+}
+
+/// Host, warehouse and credentials resolved from config, env and profile.
+#[derive(Debug, Clone)]
+pub struct ResolvedAuth {
+    pub host: String,
+    pub warehouse_id: Option<String>,
+    pub credentials: DatabricksCredentials,
+}
+
+impl DatabricksAuthConfig {
+    pub fn resolve(&self) -> Result<ResolvedAuth> {
+        match self {
+            Self::Env => resolve_from_env(),
+            Self::Pat => {
+                let token = env_var("DATABRICKS_TOKEN")?;
+                Ok(ResolvedAuth {
+                    host: normalize_host(&env_var("DATABRICKS_HOST")?),
+                    warehouse_id: crate::env::var("DATABRICKS_WAREHOUSE_ID").ok(),
+                    credentials: DatabricksCredentials::Pat { token },
+                })
+            }
+            Self::OAuthM2M => Ok(ResolvedAuth {
+                host: normalize_host(&env_var("DATABRICKS_HOST")?),
+                warehouse_id: crate::env::var("DATABRICKS_WAREHOUSE_ID").ok(),
+                credentials: DatabricksCredentials::OAuthM2M {
+                    client_id: env_var("DATABRICKS_CLIENT_ID")?,
+                    client_secret: env_var("DATABRICKS_CLIENT_SECRET")?,
+                },
+            }),
+            Self::OAuthU2M { client_id, port } => Ok(ResolvedAuth {
+                host: normalize_host(&env_var("DATABRICKS_HOST")?),
+                warehouse_id: crate::env::var("DATABRICKS_WAREHOUSE_ID").ok(),
+                credentials: DatabricksCredentials::OAuthU2M {
+                    client_id: client_id
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_U2M_CLIENT_ID.to_string()),
+                    port: port.unwrap_or(DEFAULT_U2M_PORT),
+                },
+            }),
+            Self::Profile { name } => resolve_profile(name),
+        }
+    }
+}
+
+fn env_var(name: &str) -> Result<String> {
+    crate::env::var(name).map_err(|_| anyhow!("{} environment variable not set", name))
+}
+
+pub(crate) fn normalize_host(host: &str) -> String {
+    let host = host.trim_end_matches('/');
+    if host.starts_with("http") {
+        host.to_string()
+    } else {
+        format!("https://{}", host)
+    }
+}
+
+fn resolve_from_env() -> Result<ResolvedAuth> {
+    if let Ok(profile) = crate::env::var("DATABRICKS_CONFIG_PROFILE") {
+        return resolve_profile(&profile);
+    }
+
+    let host = match crate::env::var("DATABRICKS_HOST") {
+        Ok(host) => normalize_host(&host),
+        // no host in env - fall back to the default profile if one exists
+        Err(_) => {
+            return resolve_profile(DEFAULT_PROFILE)
+                .map_err(|_| anyhow!("DATABRICKS_HOST environment variable not set"));
+        }
+    };
+    let warehouse_id = crate::env::var("DATABRICKS_WAREHOUSE_ID").ok();
+
+    if let Ok(token) = crate::env::var("DATABRICKS_TOKEN") {
+        return Ok(ResolvedAuth {
+            host,
+            warehouse_id,
+            credentials: DatabricksCredentials::Pat { token },
+        });
+    }
+
+    if let (Ok(client_id), Ok(client_secret)) = (
+        crate::env::var("DATABRICKS_CLIENT_ID"),
+        crate::env::var("DATABRICKS_CLIENT_SECRET"),
+    ) {
+        return Ok(ResolvedAuth {
+            host,
+            warehouse_id,
+            credentials: DatabricksCredentials::OAuthM2M {
+                client_id,
+                client_secret,
+            },
+        });
+    }
+
+    if matches!(
+        crate::env::var("DATABRICKS_AUTH_TYPE").as_deref(),
+        Ok("external-browser") | Ok("databricks-cli")
+    ) {
+        return Ok(ResolvedAuth {
+            host,
+            warehouse_id,
+            credentials: DatabricksCredentials::OAuthU2M {
+                client_id: DEFAULT_U2M_CLIENT_ID.to_string(),
+                port: DEFAULT_U2M_PORT,
+            },
+        });
+    }
+
+    Err(anyhow!(
+        "DATABRICKS_TOKEN environment variable not set (or set DATABRICKS_CLIENT_ID/DATABRICKS_CLIENT_SECRET, \
+         DATABRICKS_CONFIG_PROFILE or DATABRICKS_AUTH_TYPE=external-browser)"
+    ))
+}
+
+// ============================================================================
+// ~/.databrickscfg profiles
+// ============================================================================
+
+fn config_file_path() -> Result<PathBuf> {
+    if let Ok(path) = crate::env::var("DATABRICKS_CONFIG_FILE") {
+        return Ok(PathBuf::from(path));
+    }
+    let home = crate::env::var("HOME").map_err(|_| anyhow!("HOME environment variable not set"))?;
+    Ok(PathBuf::from(home).join(".databrickscfg"))
+}
+
+/// Parses an INI-style `.databrickscfg` into `profile -> (key -> value)`.
+pub fn parse_databricks_config(contents: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut profiles: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let section = section.trim().to_string();
+            profiles.entry(section.clone()).or_default();
+            current = Some(section);
+            continue;
+        }
+        if let (Some(section), Some((key, value))) = (&current, line.split_once('=')) {
+            profiles
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    profiles
+}
+
+fn resolve_profile(name: &str) -> Result<ResolvedAuth> {
+    let path = config_file_path()?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    let profiles = parse_databricks_config(&contents);
+    let profile = profiles
+        .get(name)
+        .ok_or_else(|| anyhow!("Profile '{}' not found in {}", name, path.display()))?;
+    profile_to_auth(name, profile)
+}
+
+fn profile_to_auth(name: &str, profile: &HashMap<String, String>) -> Result<ResolvedAuth> {
+    let host = profile
+        .get("host")
+        .map(|h| normalize_host(h))
+        .ok_or_else(|| anyhow!("Profile '{}' has no host", name))?;
+    // env var wins so a single profile can be reused against different warehouses
+    let warehouse_id = crate::env::var("DATABRICKS_WAREHOUSE_ID")
+        .ok()
+        .or_else(|| profile.get("warehouse_id").cloned());
+
+    let credentials = if let Some(token) = profile.get("token") {
+        DatabricksCredentials::Pat {
+            token: token.clone(),
+        }
+    } else if let (Some(client_id), Some(client_secret)) =
+        (profile.get("client_id"), profile.get("client_secret"))
+    {
+        DatabricksCredentials::OAuthM2M {
+            client_id: client_id.clone(),
+            client_secret: client_secret.clone(),
+        }
+    } else {
+        match profile.get("auth_type").map(String::as_str) {
+            Some("databricks-cli") | Some("external-browser") | None => {
+                DatabricksCredentials::OAuthU2M {
+                    client_id: profile
+                        .get("client_id")
+                        .cloned()
+                        .unwrap_or_else(|| DEFAULT_U2M_CLIENT_ID.to_string()),
+                    port: DEFAULT_U2M_PORT,
+                }
+            }
+            Some(other) => {
+                return Err(anyhow!(
+                    "Profile '{}' uses unsupported auth_type '{}'",
+                    name,
+                    other
+                ));
+            }
+        }
+    };
+
+    Ok(ResolvedAuth {
+        host,
+        warehouse_id,
+        credentials,
+    })
+}
+
+// ============================================================================
+// Token Provider
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct OAuthTokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_at: Instant,
+}
+
+impl CachedToken {
+    fn is_fresh(&self) -> bool {
+        self.expires_at > Instant::now() + TOKEN_REFRESH_MARGIN
+    }
+}
+
+impl From<OAuthTokenResponse> for CachedToken {
+    fn from(response: OAuthTokenResponse) -> Self {
+        Self {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+            expires_at: Instant::now() + Duration::from_secs(response.expires_in.unwrap_or(3600)),
+        }
+    }
+}
+
+/// Hands out bearer tokens, refreshing OAuth tokens before they expire.
+pub struct TokenProvider {
+    host: String,
+    credentials: DatabricksCredentials,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl TokenProvider {
+    pub fn new(host: String, credentials: DatabricksCredentials) -> Self {
+        Self {
+            host,
+            credentials,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub async fn token(&self, client: &reqwest::Client) -> Result<String> {
+        if let DatabricksCredentials::Pat { token } = &self.credentials {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached.as_ref().filter(|t| t.is_fresh()) {
+            return Ok(token.access_token.clone());
+        }
+
+        let refresh_token = cached
+            .as_ref()
+            .and_then(|t| t.refresh_token.clone())
+            .or_else(|| self.load_refresh_token());
+
+        let token = match (&self.credentials, refresh_token) {
+            (
+                DatabricksCredentials::OAuthM2M {
+                    client_id,
+                    client_secret,
+                },
+                _,
+            ) => {
+                self.client_credentials(client, client_id, client_secret)
+                    .await?
+            }
+            (DatabricksCredentials::OAuthU2M { client_id, .. }, Some(refresh_token)) => {
+                match self.refresh(client, client_id, &refresh_token).await {
+                    Ok(token) => token,
+                    Err(e) => {
+                        debug!(
+                            "Refresh token rejected, falling back to browser login: {}",
+                            e
+                        );
+                        self.browser_login(client).await?
+                    }
+                }
+            }
+            (DatabricksCredentials::OAuthU2M { .. }, None) => self.browser_login(client).await?,
+            (DatabricksCredentials::Pat { .. }, _) => unreachable!("handled above"),
+        };
+
+        if let Some(refresh_token) = &token.refresh_token {
+            self.store_refresh_token(refresh_token);
+        }
+        let access_token = token.access_token.clone();
+        *cached = Some(token);
+        Ok(access_token)
+    }
+
+    async fn token_request(
+        &self,
+        client: &reqwest::Client,
+        form: &[(&str, &str)],
+        basic_auth: Option<(&str, &str)>,
+    ) -> Result<CachedToken> {
+        let url = format!("{}{}", self.host, OIDC_TOKEN_ENDPOINT);
+        let mut request = client.post(&url).form(form);
+        if let Some((user, password)) = basic_auth {
+            request = request.basic_auth(user, Some(password));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow!("OAuth token request failed: {}", e))?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(anyhow!(
+                "OAuth token request failed with status {}: {}",
+                status,
+                body
+            ));
+        }
+        let parsed: OAuthTokenResponse = serde_json::from_str(&body)
+            .map_err(|e| anyhow!("Failed to parse OAuth token response: {}", e))?;
+        Ok(parsed.into())
+    }
+
+    async fn client_credentials(
+        &self,
+        client: &reqwest::Client,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<CachedToken> {
+        debug!("Requesting service principal token for {}", client_id);
+        self.token_request(
+            client,
+            &[("grant_type", "client_credentials"), ("scope", "all-apis")],
+            Some((client_id, client_secret)),
+        )
+        .await
+    }
+
+    async fn refresh(
+        &self,
+        client: &reqwest::Client,
+        client_id: &str,
+        refresh_token: &str,
+    ) -> Result<CachedToken> {
+        debug!("Refreshing OAuth token for {}", self.host);
+        let mut token = self
+            .token_request(
+                client,
+                &[
+                    ("grant_type", "refresh_token"),
+                    ("refresh_token", refresh_token),
+                    ("client_id", client_id),
+                ],
+                None,
+            )
+            .await?;
+        // some IdPs don't rotate refresh tokens
+        if token.refresh_token.is_none() {
+            token.refresh_token = Some(refresh_token.to_string());
+        }
+        Ok(token)
+    }
+
+    async fn browser_login(&self, client: &reqwest::Client) -> Result<CachedToken> {
+        let DatabricksCredentials::OAuthU2M { client_id, port } = &self.credentials else {
+            return Err(anyhow!("Browser login requires OAuth U2M credentials"));
+        };
+
+        let verifier = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        let state = uuid::Uuid::new_v4().simple().to_string();
+        let redirect_uri = format!("http://localhost:{}", port);
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", *port))
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to listen on port {} for OAuth callback: {}",
+                    port,
+                    e
+                )
+            })?;
+
+        let auth_url = format!(
+            "{}{}?client_id={}&redirect_uri={}&response_type=code&scope={}&state={}&code_challenge={}&code_challenge_method=S256",
+            self.host,
+            OIDC_AUTHORIZE_ENDPOINT,
+            urlencoding::encode(client_id),
+            urlencoding::encode(&redirect_uri),
+            urlencoding::encode("all-apis offline_access"),
+            state,
+            challenge,
+        );
+        warn!(
+            "Open the following URL to log in to Databricks: {}",
+            auth_url
+        );
+        open_browser(&auth_url);
+
+        let code = tokio::time::timeout(LOGIN_TIMEOUT, wait_for_callback(&listener, &state))
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Databricks login wasn't completed within {}s",
+                    LOGIN_TIMEOUT.as_secs()
+                )
+            })??;
+        self.token_request(
+            client,
+            &[
+                ("grant_type", "authorization_code"),
+                ("code", &code),
+                ("redirect_uri", &redirect_uri),
+                ("client_id", client_id),
+                ("code_verifier", &verifier),
+            ],
+            None,
+        )
+        .await
+    }
+
+    fn cache_path() -> Option<PathBuf> {
+        crate::env::var("HOME").ok().map(|home| {
+            PathBuf::from(home)
+                .join(".databricks")
+                .join(TOKEN_CACHE_FILE)
+        })
+    }
+
+    fn load_refresh_token(&self) -> Option<String> {
+        if !matches!(self.credentials, DatabricksCredentials::OAuthU2M { .. }) {
+            return None;
+        }
+        let contents = std::fs::read_to_string(Self::cache_path()?).ok()?;
+        let cache: HashMap<String, String> = serde_json::from_str(&contents).ok()?;
+        cache.get(&self.host).cloned()
+    }
+
+    fn store_refresh_token(&self, refresh_token: &str) {
+        if !matches!(self.credentials, DatabricksCredentials::OAuthU2M { .. }) {
+            return;
+        }
+        let Some(path) = Self::cache_path() else {
+            return;
+        };
+        let mut cache: HashMap<String, String> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        cache.insert(self.host.clone(), refresh_token.to_string());
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .transpose()
+            .and_then(|_| {
+                write_private(
+                    &path,
+                    &serde_json::to_string_pretty(&cache).unwrap_or_default(),
+                )
+            });
+        if let Err(e) = result {
+            debug!(
+                "Failed to persist refresh token to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Write a file only the current user can read, it holds refresh tokens
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // the mode only applies to new files
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+pub(crate) fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = std::process::Command::new(opener).arg(url).spawn() {
+        debug!("Failed to open browser with {}: {}", opener, e);
+    }
+}
+
+/// Waits for the OAuth redirect and returns the authorization code.
+async fn wait_for_callback(
+    listener: &tokio::net::TcpListener,
+    expected_state: &str,
+) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = vec![0u8; 8192];
+        let n = stream.read(&mut buf).await?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+
+        // request line: GET /?code=...&state=... HTTP/1.1
+        let Some(query) = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|path| path.split_once('?'))
+            .map(|(_, query)| query.to_string())
+        else {
+            // favicon and other stray requests
+            let _ = stream
+                .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            continue;
+        };
+
+        let params: HashMap<String, String> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| {
+                let v = urlencoding::decode(v)
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| v.to_string());
+                (k.to_string(), v)
+            })
+            .collect();
+
+        // not the redirect of this login (a stale tab or another page), keep waiting
+        if params.get("state").map(String::as_str) != Some(expected_state) {
+            let _ = stream
+                .write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n")
+                .await;
+            continue;
+        }
+
+        let body = "Authentication complete. You can close this tab.";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+
+        if let Some(error) = params.get("error") {
+            return Err(anyhow!(
+                "OAuth login failed: {} {}",
+                error,
+                params
+                    .get("error_description")
+                    .map(String::as_str)
+                    .unwrap_or("")
+            ));
+        }
+        return params
+            .get("code")
+            .cloned()
+            .ok_or_else(|| anyhow!("OAuth callback missing authorization code"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_databricks_config() {
+        let contents = r#"
+; comment
+[DEFAULT]
+host = https://example.cloud.databricks.com
+token = dapi123
+
+[sp]
+host = other.cloud.databricks.com/
+client_id = abc
+client_secret = secret
+
+[browser]
+host = https://browser.cloud.databricks.com
+auth_type = databricks-cli
+"#;
+        let profiles = parse_databricks_config(contents);
+        assert_eq!(profiles.len(), 3);
+
+        let default = profile_to_auth("DEFAULT", &profiles["DEFAULT"]).unwrap();
+        assert_eq!(default.host, "https://example.cloud.databricks.com");
+        assert_eq!(
+            default.credentials,
+            DatabricksCredentials::Pat {
+                token: "dapi123".to_string()
+            }
+        );
+
+        let sp = profile_to_auth("sp", &profiles["sp"]).unwrap();
+        assert_eq!(sp.host, "https://other.cloud.databricks.com");
+        assert!(matches!(
+            sp.credentials,
+            DatabricksCredentials::OAuthM2M { .. }
+        ));
+
+        let browser = profile_to_auth("browser", &profiles["browser"]).unwrap();
+        assert!(matches!(
+            browser.credentials,
+            DatabricksCredentials::OAuthU2M { .. }
+        ));
+    }
+
+    #[test]
+    fn test_auth_config_serde() {
+        let config: DatabricksAuthConfig =
+            serde_json::from_str(r#"{"type":"Profile","name":"dev"}"#).unwrap();
+        assert_eq!(
+            config,
+            DatabricksAuthConfig::Profile {
+                name: "dev".to_string()
+            }
+        );
+        let config: DatabricksAuthConfig = serde_json::from_str(r#"{"type":"OAuthU2M"}"#).unwrap();
+        assert_eq!(
+            config,
+            DatabricksAuthConfig::OAuthU2M {
+                client_id: None,
+                port: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_callback_ignores_other_requests() {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let send = |path: &'static str| async move {
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .unwrap();
+            let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response).await;
+            response
+        };
+
+        let callback = tokio::spawn(async move { wait_for_callback(&listener, "expected").await });
+        assert!(send("/favicon.ico").await.starts_with("HTTP/1.1 404"));
+        assert!(
+            send("/?code=stolen&state=other")
+                .await
+                .starts_with("HTTP/1.1 400")
+        );
+        assert!(
+            send("/?code=abc&state=expected")
+                .await
+                .starts_with("HTTP/1.1 200")
+        );
+        assert_eq!(callback.await.unwrap().unwrap(), "abc");
+    }
+}
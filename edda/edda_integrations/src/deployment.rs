@@ -1,19 +1,27 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Upper bound for a Databricks Apps deployment to reach a terminal state.
+pub const DEPLOYMENT_TIMEOUT: Duration = Duration::from_secs(1200);
+const DEPLOYMENT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Status {
+    #[serde(default)]
     pub message: String,
+    #[serde(default)]
     pub state: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct DeploymentArtifacts {
     pub source_code_path: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Deployments that are still in progress may lack artifacts and timestamps.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Deployment {
     pub create_time: String,
     pub creator: String,
@@ -25,9 +33,31 @@ pub struct Deployment {
     pub update_time: String,
 }
 
+impl Deployment {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.status.state.as_str(),
+            "SUCCEEDED" | "FAILED" | "CANCELLED"
+        )
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.status.state == "SUCCEEDED"
+    }
+
+    /// Workspace path of the source snapshot taken for this deployment.
+    pub fn snapshot_path(&self) -> &str {
+        match self.deployment_artifacts.source_code_path.as_str() {
+            "" => &self.source_code_path,
+            path => path,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AppInfo {
     pub active_deployment: Option<Deployment>,
+    pub pending_deployment: Option<Deployment>,
     pub app_status: Status,
     pub compute_status: Status,
     pub create_time: String,
@@ -56,6 +86,21 @@ impl AppInfo {
     }
 }
 
+/// Entry of `databricks apps list`; apps that never deployed lack most fields.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppSummary {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub creator: String,
+    pub create_time: String,
+    pub update_time: String,
+    pub app_status: Status,
+    pub compute_status: Status,
+    pub active_deployment: Option<Deployment>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Permission {
@@ -70,17 +115,93 @@ pub struct Warehouse {
     pub permission: Permission,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SecretPermission {
+    #[default]
+    Read,
+    Write,
+    Manage,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Secret {
+    pub scope: String,
+    pub key: String,
+    pub permission: SecretPermission,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ServingEndpointPermission {
+    #[default]
+    CanQuery,
+    CanManage,
+    CanView,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServingEndpointResource {
+    pub name: String,
+    pub permission: ServingEndpointPermission,
+}
+
+/// A single Databricks Apps resource; exactly one of the resource fields is set.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Resources {
     pub name: String,
     pub description: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sql_warehouse: Option<Warehouse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serving_endpoint: Option<ServingEndpointResource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<Secret>,
 }
 
 impl Resources {
+    pub fn serving_endpoint(endpoint_name: &str) -> Self {
+        Self {
+            name: format!("serving-endpoint-{}", endpoint_name.to_lowercase()),
+            description: format!("serving endpoint {}", endpoint_name),
+            sql_warehouse: None,
+            serving_endpoint: Some(ServingEndpointResource {
+                name: endpoint_name.to_string(),
+                permission: ServingEndpointPermission::CanQuery,
+            }),
+            secret: None,
+        }
+    }
+
+    /// Secret resource named after the lowercased key, so app.yaml can use
+    /// `valueFrom: <key lowercased>`.
+    pub fn secret(scope: &str, key: &str) -> Self {
+        Self {
+            name: key.to_lowercase(),
+            description: format!("secret {}", key),
+            sql_warehouse: None,
+            serving_endpoint: None,
+            secret: Some(Secret {
+                scope: scope.to_string(),
+                key: key.to_string(),
+                permission: SecretPermission::Read,
+            }),
+        }
+    }
+
+    /// The app.yaml env entry exposing this resource. The SQL warehouse is always
+    /// `DATABRICKS_WAREHOUSE_ID` (the templates read it); other resources use
+    /// their uppercased name.
+    pub fn env_var(&self) -> AppEnvVar {
+        let name = match self.sql_warehouse {
+            Some(_) => "DATABRICKS_WAREHOUSE_ID".to_string(),
+            None => self.name.to_uppercase().replace('-', "_"),
+        };
+        AppEnvVar::value_from(&name, &self.name)
+    }
+
     pub fn from_env() -> Result<Self> {
-        let warehouse_id = std::env::var("DATABRICKS_WAREHOUSE_ID").map_err(|_| {
+        let warehouse_id = crate::env::var("DATABRICKS_WAREHOUSE_ID").map_err(|_| {
             anyhow::anyhow!(
                 "DATABRICKS_WAREHOUSE_ID environment variable is required for app deployment. \
                  Set this to your Databricks SQL warehouse ID."
@@ -102,10 +223,57 @@ impl Default for Resources {
             name: "base".to_string(),
             description: "template resources".to_string(),
             sql_warehouse: None,
+            serving_endpoint: None,
+            secret: None,
         }
     }
 }
 
+/// An `env` entry of app.yaml: either a literal value or a reference to an app resource.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AppEnvVar {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(
+        default,
+        rename = "valueFrom",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub value_from: Option<String>,
+}
+
+impl AppEnvVar {
+    pub fn value(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: Some(value.to_string()),
+            value_from: None,
+        }
+    }
+
+    pub fn value_from(name: &str, resource: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: None,
+            value_from: Some(resource.to_string()),
+        }
+    }
+
+    fn to_yaml(&self) -> String {
+        let mut entry = format!("\n  - name: {}", self.name);
+        if let Some(resource) = &self.value_from {
+            entry.push_str(&format!("\n    valueFrom: {}", resource));
+        } else {
+            // a JSON string is a valid double-quoted YAML scalar
+            let value = serde_json::to_string(self.value.as_deref().unwrap_or_default())
+                .unwrap_or_default();
+            entry.push_str(&format!("\n    value: {}", value));
+        }
+        entry
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateApp {
     pub name: String,
@@ -153,7 +321,13 @@ pub struct CreateDeployment {
 }
 
 pub fn get_app_info(app_name: &str) -> Result<AppInfo> {
-    let output = Command::new("databricks")
+    let app_info: AppInfo = serde_json::from_str(&get_app_json(app_name)?)?;
+    Ok(app_info)
+}
+
+/// `databricks apps get` output as is, including fields `AppInfo` doesn't model
+fn get_app_json(app_name: &str) -> Result<String> {
+    let output = crate::env::command("databricks")
         .args(&["apps", "get", app_name])
         .output()?;
 
@@ -164,14 +338,12 @@ pub fn get_app_info(app_name: &str) -> Result<AppInfo> {
         ));
     }
 
-    let json_str = String::from_utf8(output.stdout)?;
-    let app_info: AppInfo = serde_json::from_str(&json_str)?;
-    Ok(app_info)
+    Ok(String::from_utf8(output.stdout)?)
 }
 
 pub fn create_app(app: &CreateApp) -> Result<AppInfo> {
     let json = serde_json::to_string(app)?;
-    let output = Command::new("databricks")
+    let output = crate::env::command("databricks")
         .args(&["apps", "create", "--json", &json])
         .output()?;
 
@@ -186,7 +358,7 @@ pub fn create_app(app: &CreateApp) -> Result<AppInfo> {
 }
 
 pub fn sync_workspace(app_info: &AppInfo, source_dir: &str) -> Result<()> {
-    let output = Command::new("databricks")
+    let output = crate::env::command("databricks")
         .args(&[
             "sync",
             "--include",
@@ -195,6 +367,10 @@ pub fn sync_workspace(app_info: &AppInfo, source_dir: &str) -> Result<()> {
             "node_modules",
             "--exclude",
             ".env",
+            "--exclude",
+            ".edda_template",
+            "--exclude",
+            ".edda",
             ".",
             &app_info.source_path(),
         ]) // specific for trpc template
@@ -211,14 +387,23 @@ pub fn sync_workspace(app_info: &AppInfo, source_dir: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn deploy_app(app_info: &AppInfo) -> Result<()> {
-    let output = Command::new("databricks")
-        .args(&[
+/// Trigger a deployment and return it without waiting; use [`wait_for_deployment`]
+/// to follow it to completion.
+pub fn deploy_app(app_info: &AppInfo) -> Result<Deployment> {
+    deploy_source(&app_info.name, &app_info.source_path())
+}
+
+fn deploy_source(app_name: &str, source_code_path: &str) -> Result<Deployment> {
+    let output = crate::env::command("databricks")
+        .args([
             "apps",
             "deploy",
-            app_info.name.as_str(),
+            app_name,
             "--source-code-path",
-            &app_info.source_path(),
+            source_code_path,
+            "--mode",
+            "SNAPSHOT",
+            "--no-wait",
         ])
         .output()?;
 
@@ -229,11 +414,186 @@ pub fn deploy_app(app_info: &AppInfo) -> Result<()> {
         ));
     }
 
-    Ok(())
+    let json_str = String::from_utf8(output.stdout)?;
+    let deployment: Deployment = serde_json::from_str(&json_str)?;
+    Ok(deployment)
+}
+
+pub fn get_deployment(app_name: &str, deployment_id: &str) -> Result<Deployment> {
+    let output = crate::env::command("databricks")
+        .args(["apps", "get-deployment", app_name, deployment_id])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to get deployment {}: {}",
+            deployment_id,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let deployment: Deployment = serde_json::from_str(&json_str)?;
+    Ok(deployment)
+}
+
+/// All deployments of an app, newest first. Every snapshot deployment keeps a
+/// copy of the synced sources, so each entry is a version that can be restored
+/// with [`rollback_app`].
+pub fn list_deployments(app_name: &str) -> Result<Vec<Deployment>> {
+    let output = crate::env::command("databricks")
+        .args(["apps", "list-deployments", app_name, "--output", "json"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to list deployments: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let mut deployments: Vec<Deployment> = serde_json::from_str(&json_str)?;
+    // RFC 3339 timestamps sort chronologically as strings
+    deployments.sort_by(|a, b| b.create_time.cmp(&a.create_time));
+    Ok(deployments)
+}
+
+/// All apps in the workspace, most recently updated first.
+pub fn list_apps() -> Result<Vec<AppSummary>> {
+    let output = crate::env::command("databricks")
+        .args(["apps", "list", "--output", "json"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to list apps: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let json_str = String::from_utf8(output.stdout)?;
+    let mut apps: Vec<AppSummary> = serde_json::from_str(&json_str)?;
+    apps.sort_by(|a, b| b.update_time.cmp(&a.update_time));
+    Ok(apps)
+}
+
+/// Delete an app together with the secret scope created by [`provision_secrets`].
+/// Returns whether the secret scope existed and was removed.
+pub fn delete_app(app_name: &str) -> Result<bool> {
+    let output = crate::env::command("databricks")
+        .args(["apps", "delete", app_name])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to delete app '{}': {}",
+            app_name,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    // the app is gone either way; a leftover scope only holds copies of local env vars
+    let output = crate::env::command("databricks")
+        .args(["secrets", "delete-scope", &secret_scope_name(app_name)])
+        .output()?;
+    Ok(output.status.success())
+}
+
+/// Redeploy the source snapshot of a previous successful deployment. Returns the
+/// new deployment without waiting for it.
+pub fn rollback_app(app_name: &str, deployment_id: &str) -> Result<Deployment> {
+    let deployments = list_deployments(app_name)?;
+    let target = deployments
+        .iter()
+        .find(|d| d.deployment_id == deployment_id)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Deployment {} not found for app '{}'",
+                deployment_id,
+                app_name
+            )
+        })?;
+    if !target.succeeded() {
+        return Err(anyhow::anyhow!(
+            "Cannot roll back to deployment {}: it did not succeed (state: {})",
+            deployment_id,
+            target.status.state
+        ));
+    }
+    deploy_source(app_name, target.snapshot_path())
+}
+
+/// Fetch the last `tail_lines` lines of the app's build and runtime logs.
+pub fn get_deployment_logs(app_name: &str, tail_lines: usize) -> Result<String> {
+    let output = crate::env::command("databricks")
+        .args([
+            "apps",
+            "logs",
+            app_name,
+            "--tail-lines",
+            &tail_lines.to_string(),
+        ])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to get app logs: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Poll a deployment until it reaches a terminal state, calling `on_progress`
+/// whenever its state or status message changes. Returns the final deployment,
+/// which may have failed; errors if polling fails, `timeout` elapses or
+/// `is_cancelled` returns true.
+pub fn wait_for_deployment(
+    app_name: &str,
+    deployment_id: &str,
+    timeout: Duration,
+    mut on_progress: impl FnMut(&Deployment),
+    is_cancelled: impl Fn() -> bool,
+) -> Result<Deployment> {
+    let start = Instant::now();
+    let mut last_status: Option<(String, String)> = None;
+    loop {
+        if is_cancelled() {
+            return Err(anyhow::anyhow!(
+                "Cancelled waiting for deployment {} of app '{}'",
+                deployment_id,
+                app_name
+            ));
+        }
+        let deployment = get_deployment(app_name, deployment_id)?;
+        let status = (
+            deployment.status.state.clone(),
+            deployment.status.message.clone(),
+        );
+        if last_status.as_ref() != Some(&status) {
+            on_progress(&deployment);
+            last_status = Some(status);
+        }
+        if deployment.is_terminal() {
+            return Ok(deployment);
+        }
+        if start.elapsed() >= timeout {
+            return Err(anyhow::anyhow!(
+                "Timed out after {}s waiting for deployment {} of app '{}' (last state: {})",
+                timeout.as_secs(),
+                deployment_id,
+                app_name,
+                deployment.status.state
+            ));
+        }
+        std::thread::sleep(DEPLOYMENT_POLL_INTERVAL);
+    }
 }
 
 pub fn get_user_info() -> Result<UserInfo> {
-    let output = Command::new("databricks")
+    let output = crate::env::command("databricks")
         .args(&["current-user", "me"])
         .output()?;
 
@@ -248,6 +608,150 @@ pub fn get_user_info() -> Result<UserInfo> {
     Ok(user_info)
 }
 
+/// Secret scope used for an app's provisioned secrets.
+pub fn secret_scope_name(app_name: &str) -> String {
+    format!("edda-{}", app_name)
+}
+
+/// Store the given local env vars as secrets in the app's scope and return
+/// app resources referencing them. Resource names are the lowercased keys,
+/// so app.yaml can use `valueFrom: <key lowercased>`.
+pub fn provision_secrets(app_name: &str, env_keys: &[String]) -> Result<Vec<Resources>> {
+    if env_keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let scope = secret_scope_name(app_name);
+    let output = crate::env::command("databricks")
+        .args(["secrets", "create-scope", &scope])
+        .output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.contains("already exists") {
+            return Err(anyhow::anyhow!("Failed to create secret scope: {}", stderr));
+        }
+    }
+
+    let mut resources = Vec::new();
+    for key in env_keys {
+        let value = crate::env::var(key).map_err(|_| {
+            anyhow::anyhow!("Environment variable {} is not set, cannot provision secret", key)
+        })?;
+        put_secret(&scope, key, &value)?;
+        resources.push(Resources::secret(&scope, key));
+    }
+    Ok(resources)
+}
+
+fn put_secret(scope: &str, key: &str, value: &str) -> Result<()> {
+    use std::io::Write;
+
+    // pass the value via stdin so it never shows up in the process list
+    let mut child = crate::env::command("databricks")
+        .args(["secrets", "put-secret", scope, key])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Failed to open stdin for databricks CLI"))?
+        .write_all(value.as_bytes())?;
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to put secret {}: {}",
+            key,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+/// Add `resources` to an existing app. Resources it already has are kept, those with the
+/// name of a new one are replaced by it.
+pub fn update_app_resources(app_name: &str, resources: &[Resources]) -> Result<AppInfo> {
+    let app: serde_json::Value = serde_json::from_str(&get_app_json(app_name)?)?;
+    let existing = match app.get("resources") {
+        Some(serde_json::Value::Array(existing)) => existing.clone(),
+        _ => Vec::new(),
+    };
+    let resources = merge_resources(existing, resources)?;
+    let json = serde_json::to_string(&serde_json::json!({ "resources": resources }))?;
+    let output = crate::env::command("databricks")
+        .args(["apps", "update", app_name, "--json", &json])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "Failed to update app resources: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    get_app_info(app_name)
+}
+
+/// The app's resources as returned by the API, so kinds `Resources` doesn't model (jobs,
+/// volumes, ...) survive an update, followed by `resources`
+fn merge_resources(
+    existing: Vec<serde_json::Value>,
+    resources: &[Resources],
+) -> Result<Vec<serde_json::Value>> {
+    let mut merged: Vec<serde_json::Value> = existing
+        .into_iter()
+        .filter(|resource| {
+            let name = resource.get("name").and_then(|name| name.as_str());
+            resources.iter().all(|new| Some(new.name.as_str()) != name)
+        })
+        .collect();
+    for resource in resources {
+        merged.push(serde_json::to_value(resource)?);
+    }
+    Ok(merged)
+}
+
+/// Add `valueFrom` env entries for provisioned secrets to an app.yaml, skipping keys already present.
+pub fn add_secret_env_to_app_yaml(app_yaml: &str, env_keys: &[String]) -> String {
+    let entries: Vec<AppEnvVar> = env_keys
+        .iter()
+        .map(|key| AppEnvVar::value_from(key, &key.to_lowercase()))
+        .collect();
+    add_env_to_app_yaml(app_yaml, &entries)
+}
+
+/// Append env entries to an app.yaml (creating the `env:` section if needed),
+/// skipping names that are already defined so hand-written entries win.
+pub fn add_env_to_app_yaml(app_yaml: &str, entries: &[AppEnvVar]) -> String {
+    let mut output = app_yaml.trim_end().to_string();
+    let has_env = output.lines().any(|line| line.trim_end() == "env:");
+    if !has_env && !entries.is_empty() {
+        output.push_str("\nenv:");
+    }
+    for entry in entries {
+        let already_defined = output.lines().any(|line| {
+            let line = line.trim().trim_start_matches("- ").trim();
+            line == format!("name: {}", entry.name) || line == format!("name: \"{}\"", entry.name)
+        });
+        if !already_defined {
+            output.push_str(&entry.to_yaml());
+        }
+    }
+    output.push('\n');
+    output
+}
+
+/// Env entries for app.yaml covering every resource plus user-provided literal values.
+pub fn app_env(resources: &[Resources], env: &[(String, String)]) -> Vec<AppEnvVar> {
+    resources
+        .iter()
+        .map(Resources::env_var)
+        .chain(env.iter().map(|(name, value)| AppEnvVar::value(name, value)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -261,6 +765,8 @@ mod tests {
                 id: "1".to_string(),
                 permission: Permission::CanUse,
             }),
+            serving_endpoint: None,
+            secret: None,
         };
         let json = serde_json::to_string(&resources).unwrap();
         assert_eq!(
@@ -268,4 +774,83 @@ mod tests {
             "{\"name\":\"\",\"description\":\"\",\"sql_warehouse\":{\"id\":\"1\",\"permission\":\"CAN_USE\"}}"
         );
     }
+
+    #[test]
+    fn test_deployment_in_progress_serde() {
+        let json =
+            r#"{"deployment_id":"01f0","status":{"state":"IN_PROGRESS","message":"Building app"}}"#;
+        let deployment: Deployment = serde_json::from_str(json).unwrap();
+        assert!(!deployment.is_terminal());
+        assert_eq!(deployment.status.message, "Building app");
+    }
+
+    #[test]
+    fn test_app_summary_without_deployment_serde() {
+        let json = r#"[{"name":"sales-dash","app_status":{"state":"UNAVAILABLE"},"compute_status":{"state":"STOPPED"}}]"#;
+        let apps: Vec<AppSummary> = serde_json::from_str(json).unwrap();
+        assert_eq!(apps[0].name, "sales-dash");
+        assert_eq!(apps[0].compute_status.state, "STOPPED");
+        assert!(apps[0].active_deployment.is_none());
+    }
+
+    #[test]
+    fn test_app_env() {
+        let warehouse = Resources {
+            sql_warehouse: Some(Warehouse {
+                id: "1".to_string(),
+                permission: Permission::CanUse,
+            }),
+            ..Default::default()
+        };
+        let resources = vec![
+            warehouse,
+            Resources::serving_endpoint("chat-llm"),
+            Resources::secret("edda-app", "OPENAI_API_KEY"),
+        ];
+        let env = vec![("LOG_LEVEL".to_string(), "debug \"verbose\"".to_string())];
+        let app_yaml = add_env_to_app_yaml(
+            "command: [\"npm\", \"run\", \"start\"]\nenv:\n  - name: DATABRICKS_WAREHOUSE_ID\n    valueFrom: base\n",
+            &app_env(&resources, &env),
+        );
+        assert_eq!(app_yaml.matches("DATABRICKS_WAREHOUSE_ID").count(), 1);
+        assert!(app_yaml.contains(
+            "  - name: SERVING_ENDPOINT_CHAT_LLM\n    valueFrom: serving-endpoint-chat-llm"
+        ));
+        assert!(app_yaml.contains("  - name: OPENAI_API_KEY\n    valueFrom: openai_api_key"));
+        assert!(app_yaml.contains("  - name: LOG_LEVEL\n    value: \"debug \\\"verbose\\\"\""));
+    }
+
+    #[test]
+    fn test_snapshot_path() {
+        let mut deployment = Deployment {
+            source_code_path: "/Workspace/Users/me/app".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(deployment.snapshot_path(), "/Workspace/Users/me/app");
+        deployment.deployment_artifacts.source_code_path =
+            "/Workspace/Users/me/.bundle/app/01f0".to_string();
+        assert_eq!(deployment.snapshot_path(), "/Workspace/Users/me/.bundle/app/01f0");
+    }
+
+    #[test]
+    fn test_add_secret_env_to_app_yaml() {
+        let app_yaml = "command: [\"npm\", \"run\", \"start\"]\nenv:\n  - name: \"DATABRICKS_AUTH_MODE\"\n    value: \"app\"\n";
+        let keys = vec!["OPENAI_API_KEY".to_string(), "DATABRICKS_AUTH_MODE".to_string()];
+        let result = add_secret_env_to_app_yaml(app_yaml, &keys);
+        assert!(result.contains("  - name: OPENAI_API_KEY\n    valueFrom: openai_api_key"));
+        assert_eq!(result.matches("DATABRICKS_AUTH_MODE").count(), 1);
+    }
+
+    #[test]
+    fn test_merge_resources() {
+        let existing = vec![
+            serde_json::json!({"name": "nightly", "job": {"id": "42", "permission": "CAN_MANAGE_RUN"}}),
+            serde_json::json!({"name": "openai_api_key", "secret": {"scope": "old", "key": "k", "permission": "READ"}}),
+        ];
+        let resources = vec![Resources::secret("edda-app", "OPENAI_API_KEY")];
+        let merged = merge_resources(existing, &resources).unwrap();
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0]["job"]["id"], "42");
+        assert_eq!(merged[1]["secret"]["scope"], "edda-app");
+    }
 }
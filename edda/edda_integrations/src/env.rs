@@ -0,0 +1,67 @@
+//! Environment variables with values set while serving layered over the process
+//! environment. Changing the real environment isn't safe once other threads run, so
+//! credentials saved or reloaded at runtime live here, and lookups and spawned commands
+//! see them on top of the process values.
+
+use std::collections::HashMap;
+use std::env::VarError;
+use std::sync::{LazyLock, RwLock};
+
+static OVERLAY: LazyLock<RwLock<HashMap<String, String>>> = LazyLock::new(Default::default);
+
+/// `std::env::var`, preferring a value [`set`] at runtime
+pub fn var(key: &str) -> Result<String, VarError> {
+    if let Some(value) = OVERLAY.read().unwrap().get(key) {
+        return Ok(value.clone());
+    }
+    std::env::var(key)
+}
+
+/// `std::env::vars` with the values [`set`] at runtime replacing the process ones
+pub fn vars() -> HashMap<String, String> {
+    let mut vars: HashMap<String, String> = std::env::vars().collect();
+    vars.extend(overlay());
+    vars
+}
+
+/// Set `key` for every later lookup and spawned command of this process
+pub fn set(key: &str, value: impl Into<String>) {
+    OVERLAY
+        .write()
+        .unwrap()
+        .insert(key.to_string(), value.into());
+}
+
+/// The values [`set`] at runtime, for `Command::envs` of child processes
+pub fn overlay() -> Vec<(String, String)> {
+    OVERLAY
+        .read()
+        .unwrap()
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// `Command::new(program)` with the values [`set`] at runtime in its environment
+pub fn command(program: &str) -> std::process::Command {
+    let mut command = std::process::Command::new(program);
+    command.envs(overlay());
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlay() {
+        set("EDDA_TEST_OVERLAY", "runtime");
+        assert_eq!(var("EDDA_TEST_OVERLAY").as_deref(), Ok("runtime"));
+        assert_eq!(
+            vars().get("EDDA_TEST_OVERLAY").map(String::as_str),
+            Some("runtime")
+        );
+        assert!(std::env::var("EDDA_TEST_OVERLAY").is_err());
+        assert!(var("EDDA_TEST_UNSET").is_err());
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{Result, anyhow};
+use base64::Engine;
+use log::debug;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+/// Files above this size are skipped; they are almost always build output or data dumps
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// Directories and files never published from an exported workspace
+const IGNORED_NAMES: &[&str] = &[
+    ".git",
+    "node_modules",
+    "dist",
+    "build",
+    "target",
+    ".venv",
+    "__pycache__",
+    ".env",
+    ".edda",
+    ".edda_template",
+    ".DS_Store",
+];
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn parse_repo(repo: &str) -> Result<(String, String)> {
+    let repo = repo
+        .trim()
+        .trim_start_matches("https://github.com/")
+        .trim_end_matches(".git");
+    match repo.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Ok((owner.to_string(), name.to_string()))
+        }
+        _ => Err(anyhow!(
+            "Invalid repository '{}': expected 'owner/repo'",
+            repo
+        )),
+    }
+}
+
+/// Whether a file or directory called `name` is kept out of published workspaces (and
+/// out of the files edda_mcp exposes as resources)
+pub fn is_ignored(name: &str) -> bool {
+    IGNORED_NAMES.contains(&name) || (name.starts_with(".env.") && name != ".env.example")
+}
+
+/// Collect publishable files under `root` as (repository path, local path) pairs.
+fn collect_files(root: &Path, prefix: &str) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if is_ignored(&name) {
+                continue;
+            }
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                stack.push(path);
+            } else if file_type.is_file() {
+                if entry.metadata()?.len() > MAX_FILE_SIZE {
+                    debug!("Skipping large file {}", path.display());
+                    continue;
+                }
+                let relative = path
+                    .strip_prefix(root)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let repo_path = match prefix.trim_matches('/') {
+                    "" => relative,
+                    prefix => format!("{}/{}", prefix, relative),
+                };
+                files.push((repo_path, path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Git tree mode for a local file, keeping the executable bit so scripts stay runnable
+fn blob_mode(path: &Path) -> Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if std::fs::metadata(path)?.permissions().mode() & 0o111 != 0 {
+            return Ok("100755");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok("100644")
+}
+
+fn default_branch_name() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format!("edda/app-{}", secs)
+}
+
+// ============================================================================
+// Argument Types (shared between agent and MCP)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PublishToGithubArgs {
+    /// Absolute path to the exported workspace to publish
+    pub work_dir: String,
+    /// Target repository as 'owner/repo'
+    pub repo: String,
+    /// Pull request title
+    pub title: String,
+    /// Pull request description summarizing what was built and how it was validated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Branch to create (default: edda/app-<timestamp>)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Branch to open the pull request against (default: the repository's default branch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// Directory inside the repository to place the files in (default: repository root)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_prefix: Option<String>,
+    /// Open the pull request as a draft
+    #[serde(default)]
+    pub draft: bool,
+}
+
+// ============================================================================
+// Request Types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+struct CreateBlobRequest {
+    content: String,
+    encoding: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct TreeEntry {
+    path: String,
+    mode: &'static str,
+    #[serde(rename = "type")]
+    entry_type: &'static str,
+    sha: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateTreeRequest {
+    base_tree: String,
+    tree: Vec<TreeEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateCommitRequest {
+    message: String,
+    tree: String,
+    parents: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateRefRequest {
+    #[serde(rename = "ref")]
+    git_ref: String,
+    sha: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatePullRequest {
+    title: String,
+    head: String,
+    base: String,
+    body: String,
+    draft: bool,
+}
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct RepositoryResponse {
+    default_branch: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitObject {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RefResponse {
+    object: GitObject,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitResponse {
+    sha: String,
+    tree: GitObject,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShaResponse {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestResponse {
+    number: u64,
+    html_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublishToGithubResult {
+    pub repo: String,
+    pub branch: String,
+    pub base: String,
+    pub commit_sha: String,
+    pub files: usize,
+    pub pull_request_number: u64,
+    pub pull_request_url: String,
+}
+
+// ============================================================================
+// Display Trait for Tool Results
+// ============================================================================
+
+use crate::ToolResultDisplay;
+
+impl ToolResultDisplay for PublishToGithubResult {
+    fn display(&self) -> String {
+        [
+            format!("Opened pull request #{}", self.pull_request_number),
+            format!("URL: {}", self.pull_request_url),
+            format!("• Repository: {}", self.repo),
+            format!("• Branch: {} -> {}", self.branch, self.base),
+            format!("• Commit: {} ({} files)", self.commit_sha, self.files),
+        ]
+        .join("\n")
+    }
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+pub struct GithubClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+}
+
+impl GithubClient {
+    /// Authenticate with `GITHUB_TOKEN` (or `GH_TOKEN`); `GITHUB_API_URL` selects GitHub Enterprise.
+    pub fn new() -> Result<Self> {
+        let token = crate::env::var("GITHUB_TOKEN")
+            .or_else(|_| crate::env::var("GH_TOKEN"))
+            .map_err(|_| anyhow!("GITHUB_TOKEN environment variable not set"))?;
+        let api_url = crate::env::var("GITHUB_API_URL")
+            .unwrap_or_else(|_| DEFAULT_API_URL.to_string())
+            .trim_end_matches('/')
+            .to_string();
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("edda/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        Ok(Self {
+            client,
+            api_url,
+            token,
+        })
+    }
+
+    async fn request<T: serde::de::DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<&impl Serialize>,
+    ) -> Result<T> {
+        let url = format!("{}{}", self.api_url, path);
+        debug!("GitHub {} {}", method, url);
+        let mut request = self
+            .client
+            .request(method, &url)
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(anyhow!("GitHub API request failed ({}): {}", status, text));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.request(reqwest::Method::GET, path, None::<&()>).await
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T> {
+        self.request(reqwest::Method::POST, path, Some(body)).await
+    }
+
+    /// Commit the workspace onto a new branch off `base` and open a pull request.
+    pub async fn publish(&self, args: &PublishToGithubArgs) -> Result<PublishToGithubResult> {
+        let (owner, name) = parse_repo(&args.repo)?;
+        let repo_path = format!("/repos/{}/{}", owner, name);
+
+        let files = collect_files(
+            Path::new(&args.work_dir),
+            args.path_prefix.as_deref().unwrap_or_default(),
+        )?;
+        if files.is_empty() {
+            return Err(anyhow!("No files to publish in {}", args.work_dir));
+        }
+
+        let base = match &args.base {
+            Some(base) => base.clone(),
+            None => {
+                let repo: RepositoryResponse = self.get(&repo_path).await?;
+                repo.default_branch
+            }
+        };
+        let base_ref: RefResponse = self
+            .get(&format!("{}/git/ref/heads/{}", repo_path, base))
+            .await?;
+        let base_commit: CommitResponse = self
+            .get(&format!(
+                "{}/git/commits/{}",
+                repo_path, base_ref.object.sha
+            ))
+            .await?;
+
+        let mut tree = Vec::with_capacity(files.len());
+        for (path, local_path) in &files {
+            let content = std::fs::read(local_path)?;
+            let blob: ShaResponse = self
+                .post(
+                    &format!("{}/git/blobs", repo_path),
+                    &CreateBlobRequest {
+                        content: base64::engine::general_purpose::STANDARD.encode(content),
+                        encoding: "base64",
+                    },
+                )
+                .await?;
+            tree.push(TreeEntry {
+                path: path.clone(),
+                mode: blob_mode(local_path)?,
+                entry_type: "blob",
+                sha: blob.sha,
+            });
+        }
+
+        let tree: ShaResponse = self
+            .post(
+                &format!("{}/git/trees", repo_path),
+                &CreateTreeRequest {
+                    base_tree: base_commit.tree.sha,
+                    tree,
+                },
+            )
+            .await?;
+        let commit: ShaResponse = self
+            .post(
+                &format!("{}/git/commits", repo_path),
+                &CreateCommitRequest {
+                    message: args.title.clone(),
+                    tree: tree.sha,
+                    parents: vec![base_commit.sha],
+                },
+            )
+            .await?;
+
+        let branch = args.branch.clone().unwrap_or_else(default_branch_name);
+        let _: RefResponse = self
+            .post(
+                &format!("{}/git/refs", repo_path),
+                &CreateRefRequest {
+                    git_ref: format!("refs/heads/{}", branch),
+                    sha: commit.sha.clone(),
+                },
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to create branch '{}': {}", branch, e))?;
+
+        let pull: PullRequestResponse = self
+            .post(
+                &format!("{}/pulls", repo_path),
+                &CreatePullRequest {
+                    title: args.title.clone(),
+                    head: branch.clone(),
+                    base: base.clone(),
+                    body: args.body.clone().unwrap_or_default(),
+                    draft: args.draft,
+                },
+            )
+            .await?;
+
+        Ok(PublishToGithubResult {
+            repo: format!("{}/{}", owner, name),
+            branch,
+            base,
+            commit_sha: commit.sha,
+            files: files.len(),
+            pull_request_number: pull.number,
+            pull_request_url: pull.html_url,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repo() {
+        assert_eq!(
+            parse_repo("neondatabase/app").unwrap(),
+            ("neondatabase".to_string(), "app".to_string())
+        );
+        assert_eq!(
+            parse_repo("https://github.com/neondatabase/app.git").unwrap(),
+            ("neondatabase".to_string(), "app".to_string())
+        );
+        assert!(parse_repo("app").is_err());
+        assert!(parse_repo("a/b/c").is_err());
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        assert!(is_ignored("node_modules"));
+        assert!(is_ignored(".env"));
+        assert!(is_ignored(".env.local"));
+        assert!(!is_ignored(".env.example"));
+        assert!(!is_ignored("server"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_blob_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        assert_eq!(blob_mode(&script).unwrap(), "100644");
+
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(blob_mode(&script).unwrap(), "100755");
+    }
+}
@@ -4,8 +4,14 @@ use log::{debug, info, warn};
 use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::path::PathBuf;
-use yup_oauth2::{ServiceAccountAuthenticator, ServiceAccountKey};
+use std::pin::Pin;
+use yup_oauth2::authenticator_delegate::InstalledFlowDelegate;
+use yup_oauth2::{
+    InstalledFlowAuthenticator, InstalledFlowReturnMethod, ServiceAccountAuthenticator,
+    ServiceAccountKey,
+};
 
 // ============================================================================
 // Request Types
@@ -156,6 +162,34 @@ impl ToolResultDisplay for SpreadsheetData {
     }
 }
 
+/// Opens the consent page in the browser and logs the URL, in case no browser opens.
+struct BrowserFlowDelegate;
+
+impl InstalledFlowDelegate for BrowserFlowDelegate {
+    fn present_user_url<'a>(
+        &'a self,
+        url: &'a str,
+        _need_code: bool,
+    ) -> Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>> {
+        Box::pin(async move {
+            warn!(
+                "Open the following URL to authorize Google Sheets access: {}",
+                url
+            );
+            crate::databricks_auth::open_browser(url);
+            Ok(String::new())
+        })
+    }
+}
+
+/// Google credentials found on disk or in the environment.
+enum GoogleCredentials {
+    /// Service account key JSON
+    ServiceAccount(String),
+    /// OAuth client ID JSON ("Desktop app" type) for the installed-app flow
+    OAuthClient(String),
+}
+
 pub struct GoogleSheetsClient {
     hub: Sheets<hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>>,
 }
@@ -167,24 +201,41 @@ impl GoogleSheetsClient {
     /// 1. ~/.config/gspread/credentials.json (preferred, standard gspread location)
     /// 2. GOOGLE_SERVICE_ACCOUNT_KEY environment variable (fallback)
     ///
+    /// The file can hold either a service account key or an OAuth client ID
+    /// ("Desktop app" type). With an OAuth client the user authorizes access in the
+    /// browser on first use; tokens are cached in ~/.config/gspread/edda_token.json.
+    ///
     /// To set up credentials:
-    /// 1. Download your service account JSON file from Google Cloud Console
+    /// 1. Download your service account JSON file (or OAuth client JSON) from Google Cloud Console
     /// 2. Either:
     ///    - Place it at ~/.config/gspread/credentials.json, or
     ///    - Set GOOGLE_SERVICE_ACCOUNT_KEY to the JSON content
     pub async fn new() -> Result<Self> {
         // Try to read from standard gspread location first, then fall back to environment variable
-        let service_account_key = Self::read_credentials()?;
-
-        // Parse the service account key
-        let key: ServiceAccountKey = serde_json::from_str(&service_account_key)
-            .map_err(|e| anyhow!("Failed to parse service account key: {}", e))?;
-
-        // Create authenticator
-        let auth = ServiceAccountAuthenticator::builder(key)
-            .build()
-            .await
-            .map_err(|e| anyhow!("Failed to build authenticator: {}", e))?;
+        let auth = match Self::read_credentials()? {
+            GoogleCredentials::ServiceAccount(service_account_key) => {
+                // Parse the service account key
+                let key: ServiceAccountKey = serde_json::from_str(&service_account_key)
+                    .map_err(|e| anyhow!("Failed to parse service account key: {}", e))?;
+
+                ServiceAccountAuthenticator::builder(key)
+                    .build()
+                    .await
+                    .map_err(|e| anyhow!("Failed to build authenticator: {}", e))?
+            }
+            GoogleCredentials::OAuthClient(client_secret) => {
+                let secret = yup_oauth2::parse_application_secret(&client_secret)
+                    .map_err(|e| anyhow!("Failed to parse OAuth client secret: {}", e))?;
+                let token_path = Self::gspread_dir()?.join("edda_token.json");
+
+                InstalledFlowAuthenticator::builder(secret, InstalledFlowReturnMethod::HTTPRedirect)
+                    .persist_tokens_to_disk(token_path)
+                    .flow_delegate(Box::new(BrowserFlowDelegate))
+                    .build()
+                    .await
+                    .map_err(|e| anyhow!("Failed to build OAuth authenticator: {}", e))?
+            }
+        };
 
         // Create HTTPS connector
         let connector = hyper_rustls::HttpsConnectorBuilder::new()
@@ -205,7 +256,26 @@ impl GoogleSheetsClient {
         Ok(Self { hub })
     }
 
-    fn read_credentials() -> Result<String> {
+    fn read_credentials() -> Result<GoogleCredentials> {
+        let content = Self::read_credentials_json()?;
+        Self::classify_credentials(content)
+    }
+
+    /// OAuth client files have a top-level "installed" (or "web") object;
+    /// anything else is treated as a service account key.
+    fn classify_credentials(content: String) -> Result<GoogleCredentials> {
+        let json: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse Google credentials: {}", e))?;
+        if json.get("installed").is_some() || json.get("web").is_some() {
+            debug!("Using OAuth client credentials (installed-app flow)");
+            Ok(GoogleCredentials::OAuthClient(content))
+        } else {
+            debug!("Using service account credentials");
+            Ok(GoogleCredentials::ServiceAccount(content))
+        }
+    }
+
+    fn read_credentials_json() -> Result<String> {
         // Try to read from ~/.config/gspread/credentials.json (standard gspread location)
         if let Some(home_dir) = std::env::var_os("HOME") {
             let mut credentials_path = PathBuf::from(home_dir);
@@ -231,10 +301,10 @@ impl GoogleSheetsClient {
 
         // Fall back to environment variable
         debug!("Trying to read credentials from GOOGLE_SERVICE_ACCOUNT_KEY environment variable");
-        std::env::var("GOOGLE_SERVICE_ACCOUNT_KEY").map_err(|_| {
+        crate::env::var("GOOGLE_SERVICE_ACCOUNT_KEY").map_err(|_| {
             anyhow!(
                 "Could not find Google credentials. Please either:\n\
-                1. Place your service account JSON file at ~/.config/gspread/credentials.json, or\n\
+                1. Place your service account or OAuth client JSON file at ~/.config/gspread/credentials.json, or\n\
                 2. Set the GOOGLE_SERVICE_ACCOUNT_KEY environment variable with the JSON content"
             )
         })
@@ -242,28 +312,29 @@ impl GoogleSheetsClient {
 
     /// Create the gspread credentials directory if it doesn't exist
     pub fn ensure_credentials_dir() -> Result<PathBuf> {
-        if let Some(home_dir) = std::env::var_os("HOME") {
-            let mut credentials_dir = PathBuf::from(home_dir);
-            credentials_dir.push(".config");
-            credentials_dir.push("gspread");
-
-            if !credentials_dir.exists() {
-                std::fs::create_dir_all(&credentials_dir).map_err(|e| {
-                    anyhow!(
-                        "Failed to create credentials directory at {:?}: {}",
-                        credentials_dir,
-                        e
-                    )
-                })?;
-                info!("Created credentials directory at: {:?}", credentials_dir);
-            }
+        Ok(Self::gspread_dir()?.join("credentials.json"))
+    }
 
-            let mut credentials_path = credentials_dir;
-            credentials_path.push("credentials.json");
-            Ok(credentials_path)
-        } else {
-            Err(anyhow!("HOME environment variable not set"))
+    /// ~/.config/gspread, created if missing
+    fn gspread_dir() -> Result<PathBuf> {
+        let Some(home_dir) = std::env::var_os("HOME") else {
+            return Err(anyhow!("HOME environment variable not set"));
+        };
+        let mut credentials_dir = PathBuf::from(home_dir);
+        credentials_dir.push(".config");
+        credentials_dir.push("gspread");
+
+        if !credentials_dir.exists() {
+            std::fs::create_dir_all(&credentials_dir).map_err(|e| {
+                anyhow!(
+                    "Failed to create credentials directory at {:?}: {}",
+                    credentials_dir,
+                    e
+                )
+            })?;
+            info!("Created credentials directory at: {:?}", credentials_dir);
         }
+        Ok(credentials_dir)
     }
 
     fn extract_spreadsheet_id(url_or_id: &str) -> Result<String> {
@@ -531,3 +602,29 @@ impl GoogleSheetsClient {
         markdown
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_credentials() {
+        let oauth = r#"{"installed": {"client_id": "id.apps.googleusercontent.com"}}"#;
+        assert!(matches!(
+            GoogleSheetsClient::classify_credentials(oauth.to_string()),
+            Ok(GoogleCredentials::OAuthClient(content)) if content == oauth
+        ));
+        let web = r#"{"web": {"client_id": "id.apps.googleusercontent.com"}}"#;
+        assert!(matches!(
+            GoogleSheetsClient::classify_credentials(web.to_string()),
+            Ok(GoogleCredentials::OAuthClient(_))
+        ));
+        let service_account =
+            r#"{"type": "service_account", "client_email": "a@b.iam.gserviceaccount.com"}"#;
+        assert!(matches!(
+            GoogleSheetsClient::classify_credentials(service_account.to_string()),
+            Ok(GoogleCredentials::ServiceAccount(_))
+        ));
+        assert!(GoogleSheetsClient::classify_credentials("not json".to_string()).is_err());
+    }
+}
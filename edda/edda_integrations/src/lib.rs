@@ -1,6 +1,14 @@
+pub mod cache;
 pub mod databricks;
+pub mod databricks_auth;
 pub mod deployment;
+pub mod env;
+pub mod github;
 pub mod google_sheets;
+pub mod notifications;
+pub mod object_storage;
+pub mod postgres;
+pub mod retry;
 
 // ============================================================================
 // Shared Display Trait
@@ -11,18 +19,46 @@ pub trait ToolResultDisplay {
 }
 
 pub use databricks::{
-    ColumnMetadata, DatabricksDescribeTableArgs, DatabricksExecuteQueryArgs,
-    DatabricksListCatalogsArgs, DatabricksListSchemasArgs, DatabricksListTablesArgs,
-    DatabricksRestClient, DescribeTableRequest, ExecuteSqlRequest, ExecuteSqlResult,
-    ListCatalogsResult, ListSchemasRequest, ListSchemasResult, ListTablesRequest,
-    ListTablesResult, TableDetails, TableInfo,
+    ColumnMetadata, CreateDashboardRequest, DashboardDataset, DashboardQueriesResult,
+    DatabricksDescribeTableArgs, DatabricksError, DatabricksExecuteQueryArgs,
+    DatabricksExportDashboardQueriesArgs, DatabricksGenieAskArgs, DatabricksListCatalogsArgs,
+    DatabricksListDashboardsArgs, DatabricksListSchemasArgs, DatabricksListServingEndpointsArgs,
+    DatabricksListTablesArgs, DatabricksListVolumeFilesArgs, DatabricksListWarehousesArgs,
+    DatabricksQueryServingEndpointArgs, DatabricksRestClient, DatabricksUploadFileArgs,
+    DescribeTableRequest, ExecuteSqlOptions, ExecuteSqlRequest, ExecuteSqlResult, GenieAnswer,
+    GenieAskRequest, GenieSpace, LakeviewDashboard, ListCatalogsResult, ListDashboardsResult,
+    ListSchemasRequest, ListSchemasResult, ListServingEndpointsResult, ListTablesRequest,
+    ListTablesResult, ListVolumeFilesRequest, ListVolumeFilesResult, ListWarehousesResult,
+    QueryServingEndpointResult, ResultFormat, ServedEntity, ServingEndpoint, ServingEndpointConfig,
+    ServingEndpointSchema, ServingEndpointState, SqlResultStream, SqlWarehouse, TableDetails,
+    TableInfo, UploadFileRequest, UploadFileResult, VolumeFileInfo,
 };
+pub use databricks_auth::{DatabricksAuthConfig, DatabricksCredentials, TokenProvider};
 pub use deployment::{
-    AppInfo, CreateApp, Resources, create_app, deploy_app, get_app_info, get_user_info,
-    sync_workspace,
+    AppEnvVar, AppInfo, AppSummary, CreateApp, DEPLOYMENT_TIMEOUT, Deployment, Resources, Secret,
+    SecretPermission, ServingEndpointPermission, ServingEndpointResource, add_env_to_app_yaml,
+    add_secret_env_to_app_yaml, app_env, create_app, delete_app, deploy_app, get_app_info,
+    get_deployment, get_deployment_logs, get_user_info, list_apps, list_deployments,
+    provision_secrets, rollback_app, secret_scope_name, sync_workspace, update_app_resources,
+    wait_for_deployment,
 };
+pub use github::{GithubClient, PublishToGithubArgs, PublishToGithubResult};
 pub use google_sheets::{
     FetchSpreadsheetDataRequest, GetSpreadsheetMetadataRequest, GoogleSheetsClient,
     ReadRangeRequest, ReadRangeResult, SheetData, SheetMetadata, SpreadsheetData,
     SpreadsheetMetadata,
 };
+pub use notifications::{
+    Notification, NotificationEvent, NotificationsConfig, WebhookFormat, WebhookNotifier,
+};
+pub use object_storage::{
+    ObjectInfo, ObjectStoreClient, ObjectStoreGetArgs, ObjectStoreGetResult, ObjectStoreHeadArgs,
+    ObjectStoreListArgs, ObjectStoreListBucketsArgs, ObjectStoreListBucketsResult,
+    ObjectStoreListResult, ObjectStorePresignArgs, ObjectStorePresignResult,
+};
+pub use postgres::{
+    PostgresClient, PostgresColumn, PostgresDescribeTableArgs, PostgresListSchemasArgs,
+    PostgresListSchemasResult, PostgresListTablesArgs, PostgresListTablesResult, PostgresQueryArgs,
+    PostgresQueryResult, PostgresTableDetails, PostgresTableInfo,
+};
+pub use retry::{CircuitBreaker, RetryPolicy};
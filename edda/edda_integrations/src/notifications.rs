@@ -0,0 +1,213 @@
+use anyhow::{Result, anyhow};
+use log::debug;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Fallback when no `webhook_url` is configured, keeps the URL out of config files
+const WEBHOOK_URL_ENV: &str = "EDDA_NOTIFICATION_WEBHOOK_URL";
+/// Slack truncates long messages anyway; keep payloads small
+const MAX_MESSAGE_LEN: usize = 2000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    ValidationPassed,
+    DeploymentFinished,
+    DeploymentFailed,
+    YellReport,
+}
+
+impl NotificationEvent {
+    pub fn all() -> Vec<Self> {
+        vec![
+            Self::ValidationPassed,
+            Self::DeploymentFinished,
+            Self::DeploymentFailed,
+            Self::YellReport,
+        ]
+    }
+
+    fn emoji(&self) -> &'static str {
+        match self {
+            Self::ValidationPassed => "✅",
+            Self::DeploymentFinished => "🚀",
+            Self::DeploymentFailed => "❌",
+            Self::YellReport => "🐛",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum WebhookFormat {
+    /// Slack incoming webhook (`{"text": ...}`), also accepted by Mattermost and Discord's /slack endpoint
+    #[default]
+    Slack,
+    /// The notification serialized as JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Webhook URL; falls back to EDDA_NOTIFICATION_WEBHOOK_URL. Never serialized, the URL
+    /// is a credential and configs end up in session trajectories.
+    #[serde(skip_serializing)]
+    pub webhook_url: Option<String>,
+    pub format: WebhookFormat,
+    /// Events that trigger a notification
+    pub events: Vec<NotificationEvent>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            format: WebhookFormat::default(),
+            events: NotificationEvent::all(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Notification {
+    pub fn new(event: NotificationEvent, title: &str, message: &str) -> Self {
+        let message = match message.char_indices().nth(MAX_MESSAGE_LEN) {
+            Some((idx, _)) => format!("{}…", &message[..idx]),
+            None => message.to_string(),
+        };
+        Self {
+            event,
+            title: title.to_string(),
+            message,
+            url: None,
+        }
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    fn slack_text(&self) -> String {
+        let mut text = format!("{} *{}*", self.event.emoji(), self.title);
+        if !self.message.is_empty() {
+            text.push_str(&format!("\n{}", self.message));
+        }
+        if let Some(url) = &self.url {
+            text.push_str(&format!("\n<{}>", url));
+        }
+        text
+    }
+}
+
+/// Sends notifications to a webhook for the configured events.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+    format: WebhookFormat,
+    events: Vec<NotificationEvent>,
+}
+
+impl WebhookNotifier {
+    /// Returns `None` when no webhook URL is configured.
+    pub fn from_config(config: &NotificationsConfig) -> Option<Self> {
+        let url = config
+            .webhook_url
+            .clone()
+            .or_else(|| crate::env::var(WEBHOOK_URL_ENV).ok())
+            .filter(|url| !url.is_empty())?;
+        let client = reqwest::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .ok()?;
+        Some(Self {
+            client,
+            url,
+            format: config.format,
+            events: config.events.clone(),
+        })
+    }
+
+    pub fn is_enabled(&self, event: NotificationEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    fn payload(&self, notification: &Notification) -> serde_json::Value {
+        match self.format {
+            WebhookFormat::Slack => serde_json::json!({ "text": notification.slack_text() }),
+            WebhookFormat::Json => serde_json::to_value(notification).unwrap_or_default(),
+        }
+    }
+
+    /// Send a notification; events not enabled in the config are skipped.
+    pub async fn notify(&self, notification: &Notification) -> Result<()> {
+        if !self.is_enabled(notification.event) {
+            return Ok(());
+        }
+        debug!("Sending {:?} notification", notification.event);
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&self.payload(notification))
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to send notification: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Notification webhook returned {}: {}",
+                status,
+                body
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slack_payload() {
+        let config = NotificationsConfig {
+            webhook_url: Some("https://hooks.slack.com/services/x".to_string()),
+            events: vec![NotificationEvent::DeploymentFinished],
+            ..Default::default()
+        };
+        let notifier = WebhookNotifier::from_config(&config).unwrap();
+        assert!(notifier.is_enabled(NotificationEvent::DeploymentFinished));
+        assert!(!notifier.is_enabled(NotificationEvent::ValidationPassed));
+
+        let notification = Notification::new(
+            NotificationEvent::DeploymentFinished,
+            "Deployed app 'sales'",
+            "",
+        )
+        .with_url("https://sales.databricksapps.com");
+        assert_eq!(
+            notifier.payload(&notification),
+            serde_json::json!({
+                "text": "🚀 *Deployed app 'sales'*\n<https://sales.databricksapps.com>"
+            })
+        );
+    }
+
+    #[test]
+    fn test_message_truncation() {
+        let message = "é".repeat(MAX_MESSAGE_LEN + 10);
+        let notification = Notification::new(NotificationEvent::YellReport, "report", &message);
+        assert_eq!(notification.message.chars().count(), MAX_MESSAGE_LEN + 1);
+    }
+}
@@ -0,0 +1,522 @@
+use anyhow::{Result, anyhow};
+use log::debug;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::signer::Signer;
+use object_store::{ObjectMeta, ObjectStore};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const DEFAULT_PREVIEW_BYTES: u64 = 64 * 1024;
+const MAX_PREVIEW_BYTES: u64 = 1024 * 1024;
+const MAX_LISTED_OBJECTS: usize = 1000;
+const DEFAULT_PRESIGN_EXPIRY_SECS: u64 = 3600;
+/// SigV4 presigned URLs are valid for at most 7 days
+const MAX_PRESIGN_EXPIRY_SECS: u64 = 7 * 24 * 3600;
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Resolves the bucket and key of an object. The key may be a full
+/// `s3://bucket/key` URL, in which case it takes precedence over `bucket`.
+fn resolve_location(bucket: Option<&str>, key: &str) -> (Option<String>, String) {
+    match key
+        .strip_prefix("s3://")
+        .or_else(|| key.strip_prefix("s3a://"))
+    {
+        Some(rest) => match rest.split_once('/') {
+            Some((bucket, key)) => (Some(bucket.to_string()), key.to_string()),
+            None => (Some(rest.to_string()), String::new()),
+        },
+        None => (
+            bucket.map(|b| b.to_string()),
+            key.trim_start_matches('/').to_string(),
+        ),
+    }
+}
+
+fn file_format(key: &str) -> Option<&'static str> {
+    let ext = key.rsplit('.').next()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "csv" => Some("csv"),
+        "tsv" => Some("tsv"),
+        "parquet" | "pq" => Some("parquet"),
+        "json" => Some("json"),
+        "jsonl" | "ndjson" => Some("jsonl"),
+        _ => None,
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+// ============================================================================
+// Argument Types (shared between agent and MCP)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct ObjectStoreListBucketsArgs {
+    // no parameters needed - lists configured buckets
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct ObjectStoreListArgs {
+    /// Bucket to list (default: first configured bucket)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Prefix ("directory") to list, e.g. 'raw/sales/' or 's3://bucket/raw/'
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectStoreHeadArgs {
+    /// Bucket containing the object (default: first configured bucket)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Object key or full 's3://bucket/key' URL
+    pub key: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectStoreGetArgs {
+    /// Bucket containing the object (default: first configured bucket)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Object key or full 's3://bucket/key' URL
+    pub key: String,
+    /// Maximum bytes to read from the start of the object (default: 65536, max: 1048576)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectStorePresignArgs {
+    /// Bucket containing the object (default: first configured bucket)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Object key or full 's3://bucket/key' URL
+    pub key: String,
+    /// URL lifetime in seconds (default: 3600, max: 604800)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in_secs: Option<u64>,
+}
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectStoreListBucketsResult {
+    pub buckets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectInfo {
+    pub bucket: String,
+    pub key: String,
+    pub size: u64,
+    pub last_modified: String,
+    pub e_tag: Option<String>,
+    /// Format inferred from the key extension (csv, parquet, json, ...)
+    pub format: Option<String>,
+}
+
+impl ObjectInfo {
+    fn from_meta(bucket: &str, meta: ObjectMeta) -> Self {
+        let key = meta.location.to_string();
+        Self {
+            bucket: bucket.to_string(),
+            format: file_format(&key).map(|f| f.to_string()),
+            key,
+            size: meta.size,
+            last_modified: meta.last_modified.to_rfc3339(),
+            e_tag: meta.e_tag,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.key)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectStoreListResult {
+    pub bucket: String,
+    pub prefix: String,
+    pub prefixes: Vec<String>,
+    pub objects: Vec<ObjectInfo>,
+    /// More objects were available than could be listed
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectStoreGetResult {
+    pub object: ObjectInfo,
+    /// UTF-8 preview of the first bytes, absent for binary objects
+    pub preview: Option<String>,
+    pub bytes_read: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ObjectStorePresignResult {
+    pub bucket: String,
+    pub key: String,
+    pub url: String,
+    pub expires_in_secs: u64,
+}
+
+// ============================================================================
+// Display Trait for Tool Results
+// ============================================================================
+
+use crate::ToolResultDisplay;
+
+impl ToolResultDisplay for ObjectStoreListBucketsResult {
+    fn display(&self) -> String {
+        if self.buckets.is_empty() {
+            return "No buckets configured. Set OBJECT_STORE_BUCKETS (comma-separated)."
+                .to_string();
+        }
+        let mut lines = vec![
+            format!("Found {} buckets:", self.buckets.len()),
+            String::new(),
+        ];
+        for bucket in &self.buckets {
+            lines.push(format!("• s3://{}", bucket));
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ObjectStoreListResult {
+    fn display(&self) -> String {
+        if self.prefixes.is_empty() && self.objects.is_empty() {
+            return format!(
+                "No objects found under s3://{}/{}",
+                self.bucket, self.prefix
+            );
+        }
+        let mut lines = vec![format!("s3://{}/{}", self.bucket, self.prefix)];
+
+        if !self.prefixes.is_empty() {
+            lines.push(format!("\nPrefixes ({}):", self.prefixes.len()));
+            for prefix in &self.prefixes {
+                lines.push(format!("• {}/", prefix));
+            }
+        }
+
+        if !self.objects.is_empty() {
+            lines.push(format!("\nObjects ({}):", self.objects.len()));
+            for object in &self.objects {
+                let mut info = format!("• {} ({})", object.key, format_size(object.size));
+                if let Some(format) = &object.format {
+                    info.push_str(&format!(" [{}]", format));
+                }
+                lines.push(info);
+            }
+        }
+
+        if self.truncated {
+            lines.push(format!(
+                "\n... listing truncated at {} objects, narrow the prefix to see more",
+                MAX_LISTED_OBJECTS
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ObjectInfo {
+    fn display(&self) -> String {
+        let mut lines = vec![
+            format!("Object: {}", self.url()),
+            format!("Size: {} ({} bytes)", format_size(self.size), self.size),
+            format!("Last Modified: {}", self.last_modified),
+        ];
+        if let Some(format) = &self.format {
+            lines.push(format!("Format: {}", format));
+        }
+        if let Some(e_tag) = &self.e_tag {
+            lines.push(format!("ETag: {}", e_tag));
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ObjectStoreGetResult {
+    fn display(&self) -> String {
+        let mut lines = vec![self.object.display()];
+        match &self.preview {
+            Some(preview) => {
+                lines.push(format!(
+                    "\nContent (first {} of {} bytes):",
+                    self.bytes_read, self.object.size
+                ));
+                lines.push(preview.clone());
+            }
+            None => {
+                lines.push(
+                    "\nBinary content not shown. Use object_store_presign to get a download URL."
+                        .to_string(),
+                );
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for ObjectStorePresignResult {
+    fn display(&self) -> String {
+        format!(
+            "Presigned GET URL for s3://{}/{} (valid for {}s):\n{}",
+            self.bucket, self.key, self.expires_in_secs, self.url
+        )
+    }
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+/// S3-compatible object storage client. Credentials and region come from the
+/// standard AWS environment (`AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+/// `AWS_REGION`, `AWS_ENDPOINT` for MinIO/R2 and other compatible stores).
+pub struct ObjectStoreClient {
+    buckets: Vec<String>,
+    stores: Mutex<HashMap<String, Arc<AmazonS3>>>,
+}
+
+impl ObjectStoreClient {
+    /// Reads the accessible buckets from `OBJECT_STORE_BUCKETS` (comma-separated).
+    /// S3 bucket listing needs account-wide permissions, so buckets are configured
+    /// explicitly instead.
+    pub fn new() -> Result<Self> {
+        let buckets = crate::env::var("OBJECT_STORE_BUCKETS")
+            .map_err(|_| anyhow!("OBJECT_STORE_BUCKETS environment variable not set"))?;
+        Self::with_buckets(buckets.split(',').map(|b| b.trim().to_string()))
+    }
+
+    pub fn with_buckets(buckets: impl IntoIterator<Item = String>) -> Result<Self> {
+        let buckets: Vec<String> = buckets.into_iter().filter(|b| !b.is_empty()).collect();
+        if buckets.is_empty() {
+            return Err(anyhow!("At least one bucket must be configured"));
+        }
+        Ok(Self {
+            buckets,
+            stores: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn store(&self, bucket: Option<&str>) -> Result<(String, Arc<AmazonS3>)> {
+        let bucket = bucket.unwrap_or(&self.buckets[0]).to_string();
+        if !self.buckets.contains(&bucket) {
+            return Err(anyhow!(
+                "Bucket '{}' is not configured. Accessible buckets: {}",
+                bucket,
+                self.buckets.join(", ")
+            ));
+        }
+        let mut stores = self.stores.lock().unwrap();
+        if let Some(store) = stores.get(&bucket) {
+            return Ok((bucket, store.clone()));
+        }
+        debug!("Creating object store client for bucket {}", bucket);
+        // from_env only sees the process environment, credentials set at runtime
+        // live in the overlay
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(&bucket);
+        if let Ok(access_key_id) = crate::env::var("AWS_ACCESS_KEY_ID") {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Ok(secret_access_key) = crate::env::var("AWS_SECRET_ACCESS_KEY") {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+        if let Ok(region) = crate::env::var("AWS_REGION") {
+            builder = builder.with_region(region);
+        }
+        if let Ok(endpoint) = crate::env::var("AWS_ENDPOINT") {
+            builder = builder.with_endpoint(endpoint);
+        }
+        let store = builder
+            .build()
+            .map_err(|e| anyhow!("Failed to configure bucket '{}': {}", bucket, e))?;
+        let store = Arc::new(store);
+        stores.insert(bucket.clone(), store.clone());
+        Ok((bucket, store))
+    }
+
+    pub fn list_buckets(&self) -> ObjectStoreListBucketsResult {
+        ObjectStoreListBucketsResult {
+            buckets: self.buckets.clone(),
+        }
+    }
+
+    pub async fn list(&self, args: &ObjectStoreListArgs) -> Result<ObjectStoreListResult> {
+        let (bucket, prefix) = resolve_location(
+            args.bucket.as_deref(),
+            args.prefix.as_deref().unwrap_or_default(),
+        );
+        let (bucket, store) = self.store(bucket.as_deref())?;
+        let prefix = Path::from(prefix.trim_end_matches('/'));
+        debug!("Listing s3://{}/{}", bucket, prefix);
+
+        let listing = store
+            .list_with_delimiter(Some(&prefix))
+            .await
+            .map_err(|e| anyhow!("Failed to list s3://{}/{}: {}", bucket, prefix, e))?;
+
+        let truncated = listing.objects.len() > MAX_LISTED_OBJECTS;
+        let objects = listing
+            .objects
+            .into_iter()
+            .take(MAX_LISTED_OBJECTS)
+            .map(|meta| ObjectInfo::from_meta(&bucket, meta))
+            .collect();
+        let prefix = match prefix.as_ref() {
+            "" => String::new(),
+            p => format!("{}/", p),
+        };
+
+        Ok(ObjectStoreListResult {
+            prefixes: listing
+                .common_prefixes
+                .into_iter()
+                .map(|p| p.to_string())
+                .collect(),
+            bucket,
+            prefix,
+            objects,
+            truncated,
+        })
+    }
+
+    pub async fn head(&self, args: &ObjectStoreHeadArgs) -> Result<ObjectInfo> {
+        let (bucket, key) = resolve_location(args.bucket.as_deref(), &args.key);
+        let (bucket, store) = self.store(bucket.as_deref())?;
+        let meta = store
+            .head(&Path::from(key.as_str()))
+            .await
+            .map_err(|e| anyhow!("Failed to get s3://{}/{}: {}", bucket, key, e))?;
+        Ok(ObjectInfo::from_meta(&bucket, meta))
+    }
+
+    /// Reads the first `max_bytes` of an object. Text objects are returned as a
+    /// preview; binary objects (e.g. Parquet) only return metadata.
+    pub async fn get(&self, args: &ObjectStoreGetArgs) -> Result<ObjectStoreGetResult> {
+        let (bucket, key) = resolve_location(args.bucket.as_deref(), &args.key);
+        let (bucket, store) = self.store(bucket.as_deref())?;
+        let path = Path::from(key.as_str());
+        let meta = store
+            .head(&path)
+            .await
+            .map_err(|e| anyhow!("Failed to get s3://{}/{}: {}", bucket, key, e))?;
+
+        let limit = args
+            .max_bytes
+            .unwrap_or(DEFAULT_PREVIEW_BYTES)
+            .clamp(1, MAX_PREVIEW_BYTES)
+            .min(meta.size);
+        let bytes = match limit {
+            0 => Default::default(),
+            _ => store
+                .get_range(&path, 0..limit)
+                .await
+                .map_err(|e| anyhow!("Failed to read s3://{}/{}: {}", bucket, key, e))?,
+        };
+
+        let preview = match std::str::from_utf8(&bytes) {
+            _ if bytes.starts_with(PARQUET_MAGIC) => None,
+            Ok(text) => Some(text.to_string()),
+            // the range may cut a multi-byte character at the end
+            Err(e) if e.error_len().is_none() => {
+                Some(String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned())
+            }
+            Err(_) => None,
+        };
+
+        Ok(ObjectStoreGetResult {
+            object: ObjectInfo::from_meta(&bucket, meta),
+            bytes_read: bytes.len() as u64,
+            preview,
+        })
+    }
+
+    pub async fn presign(&self, args: &ObjectStorePresignArgs) -> Result<ObjectStorePresignResult> {
+        let (bucket, key) = resolve_location(args.bucket.as_deref(), &args.key);
+        let (bucket, store) = self.store(bucket.as_deref())?;
+        let expires_in_secs = args
+            .expires_in_secs
+            .unwrap_or(DEFAULT_PRESIGN_EXPIRY_SECS)
+            .clamp(1, MAX_PRESIGN_EXPIRY_SECS);
+        let url = store
+            .signed_url(
+                reqwest::Method::GET,
+                &Path::from(key.as_str()),
+                Duration::from_secs(expires_in_secs),
+            )
+            .await
+            .map_err(|e| anyhow!("Failed to presign s3://{}/{}: {}", bucket, key, e))?;
+        Ok(ObjectStorePresignResult {
+            bucket,
+            key,
+            url: url.to_string(),
+            expires_in_secs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_location() {
+        assert_eq!(
+            resolve_location(Some("data"), "raw/sales.csv"),
+            (Some("data".to_string()), "raw/sales.csv".to_string())
+        );
+        assert_eq!(
+            resolve_location(Some("data"), "s3://other/raw/sales.csv"),
+            (Some("other".to_string()), "raw/sales.csv".to_string())
+        );
+        assert_eq!(
+            resolve_location(None, "s3://other"),
+            (Some("other".to_string()), String::new())
+        );
+        assert_eq!(resolve_location(None, "/raw/"), (None, "raw/".to_string()));
+    }
+
+    #[test]
+    fn test_file_format() {
+        assert_eq!(file_format("raw/sales.CSV"), Some("csv"));
+        assert_eq!(file_format("part-0000.parquet"), Some("parquet"));
+        assert_eq!(file_format("README"), None);
+    }
+
+    #[test]
+    fn test_store_rejects_unconfigured_bucket() {
+        let client = ObjectStoreClient::with_buckets(["data".to_string()]).unwrap();
+        assert!(client.store(Some("other")).is_err());
+        assert_eq!(client.store(None).unwrap().0, "data");
+        assert_eq!(client.store(Some("data")).unwrap().0, "data");
+    }
+}
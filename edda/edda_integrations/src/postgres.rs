@@ -0,0 +1,471 @@
+use anyhow::{Result, anyhow};
+use log::debug;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use std::collections::HashMap;
+use std::time::Duration;
+
+const DEFAULT_ROW_LIMIT: usize = 100;
+const MAX_ROW_LIMIT: usize = 10_000;
+const STATEMENT_TIMEOUT_SECS: u64 = 60;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// Splits `schema.table` into its parts; unqualified names live in `public`.
+fn split_table_name(table_name: &str) -> Result<(String, String)> {
+    let parts: Vec<&str> = table_name.split('.').collect();
+    match parts.as_slice() {
+        [table] if !table.is_empty() => Ok(("public".to_string(), table.to_string())),
+        [schema, table] if !schema.is_empty() && !table.is_empty() => {
+            Ok((schema.to_string(), table.to_string()))
+        }
+        _ => Err(anyhow!(
+            "Invalid table name '{}': expected 'table' or 'schema.table'",
+            table_name
+        )),
+    }
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "null".to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn format_row(row: &HashMap<String, Value>) -> String {
+    row.iter()
+        .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// ============================================================================
+// Argument Types (shared between agent and MCP)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(default)]
+pub struct PostgresListSchemasArgs {
+    // no parameters needed - lists all user schemas
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostgresListTablesArgs {
+    /// Schema to list tables from (default: public)
+    #[serde(default = "default_schema")]
+    pub schema_name: String,
+    /// Optional case-insensitive substring filter on table names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+fn default_schema() -> String {
+    "public".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostgresDescribeTableArgs {
+    /// Table name, optionally schema-qualified ('schema.table')
+    pub table_name: String,
+    /// Number of sample rows to include (default: 5)
+    #[serde(default = "default_sample_size")]
+    pub sample_size: usize,
+}
+
+fn default_sample_size() -> usize {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostgresQueryArgs {
+    /// Single read-only SQL statement (SELECT / WITH ... SELECT)
+    pub query: String,
+    /// Maximum rows to return (default: 100, max: 10000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rows: Option<usize>,
+}
+
+// ============================================================================
+// Response Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostgresListSchemasResult {
+    pub schemas: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostgresTableInfo {
+    pub schema_name: String,
+    pub name: String,
+    pub table_type: String,
+    /// Planner estimate from pg_class, absent if the table was never analyzed
+    pub estimated_rows: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostgresListTablesResult {
+    pub tables: Vec<PostgresTableInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostgresColumn {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostgresTableDetails {
+    pub schema_name: String,
+    pub name: String,
+    pub columns: Vec<PostgresColumn>,
+    pub primary_key: Vec<String>,
+    pub estimated_rows: Option<i64>,
+    pub sample_data: Vec<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostgresQueryResult {
+    pub rows: Vec<HashMap<String, Value>>,
+    /// More rows were available than `max_rows`
+    pub truncated: bool,
+}
+
+// ============================================================================
+// Display Trait for Tool Results
+// ============================================================================
+
+use crate::ToolResultDisplay;
+
+impl ToolResultDisplay for PostgresListSchemasResult {
+    fn display(&self) -> String {
+        if self.schemas.is_empty() {
+            return "No schemas found.".to_string();
+        }
+        let mut lines = vec![
+            format!("Found {} schemas:", self.schemas.len()),
+            String::new(),
+        ];
+        for schema in &self.schemas {
+            lines.push(format!("• {}", schema));
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for PostgresListTablesResult {
+    fn display(&self) -> String {
+        if self.tables.is_empty() {
+            return "No tables found.".to_string();
+        }
+        let mut lines = vec![
+            format!("Found {} tables:", self.tables.len()),
+            String::new(),
+        ];
+        for table in &self.tables {
+            let mut info = format!(
+                "• {}.{} ({})",
+                table.schema_name, table.name, table.table_type
+            );
+            if let Some(rows) = table.estimated_rows {
+                info.push_str(&format!(" - ~{} rows", rows));
+            }
+            lines.push(info);
+        }
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for PostgresTableDetails {
+    fn display(&self) -> String {
+        let mut lines = vec![format!("Table: {}.{}", self.schema_name, self.name)];
+        if let Some(rows) = self.estimated_rows {
+            lines.push(format!("Estimated Rows: {}", rows));
+        }
+        if !self.primary_key.is_empty() {
+            lines.push(format!("Primary Key: {}", self.primary_key.join(", ")));
+        }
+
+        lines.push(format!("\nColumns ({}):", self.columns.len()));
+        for col in &self.columns {
+            let nullable_str = if col.nullable { "nullable" } else { "required" };
+            let mut col_info = format!("  - {}: {} ({})", col.name, col.data_type, nullable_str);
+            if let Some(default) = &col.default {
+                col_info.push_str(&format!(" default {}", default));
+            }
+            lines.push(col_info);
+        }
+
+        if !self.sample_data.is_empty() {
+            lines.push(format!("\nSample Data ({} rows):", self.sample_data.len()));
+            for (i, row) in self.sample_data.iter().enumerate() {
+                lines.push(format!("  Row {}: {}", i + 1, format_row(row)));
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl ToolResultDisplay for PostgresQueryResult {
+    fn display(&self) -> String {
+        if self.rows.is_empty() {
+            return "Query executed successfully but returned no results.".to_string();
+        }
+        let mut lines = vec![
+            format!("Query returned {} rows:", self.rows.len()),
+            String::new(),
+        ];
+        for (i, row) in self.rows.iter().enumerate() {
+            lines.push(format!("  Row {}: {}", i + 1, format_row(row)));
+        }
+        if self.truncated {
+            lines.push(format!(
+                "\n... more rows available, showing first {} (raise max_rows to see more)",
+                self.rows.len()
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+pub struct PostgresClient {
+    pool: PgPool,
+}
+
+impl PostgresClient {
+    /// Connect using `POSTGRES_URL` (or `DATABASE_URL`), e.g. a Neon connection string.
+    pub async fn new() -> Result<Self> {
+        let url = crate::env::var("POSTGRES_URL")
+            .or_else(|_| crate::env::var("DATABASE_URL"))
+            .map_err(|_| anyhow!("POSTGRES_URL environment variable not set"))?;
+        Self::connect(&url).await
+    }
+
+    pub async fn connect(url: &str) -> Result<Self> {
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(CONNECT_TIMEOUT)
+            .connect(url)
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Postgres: {}", e))?;
+        Ok(Self { pool })
+    }
+
+    pub async fn list_schemas(&self) -> Result<PostgresListSchemasResult> {
+        let schemas: Vec<(String,)> = sqlx::query_as(
+            "SELECT schema_name::text FROM information_schema.schemata \
+             WHERE schema_name NOT IN ('pg_catalog', 'information_schema') \
+             AND schema_name NOT LIKE 'pg_toast%' AND schema_name NOT LIKE 'pg_temp%' \
+             ORDER BY schema_name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(PostgresListSchemasResult {
+            schemas: schemas.into_iter().map(|(s,)| s).collect(),
+        })
+    }
+
+    pub async fn list_tables(
+        &self,
+        args: &PostgresListTablesArgs,
+    ) -> Result<PostgresListTablesResult> {
+        let rows: Vec<(String, String, String, Option<i64>)> = sqlx::query_as(
+            "SELECT t.table_schema::text, t.table_name::text, t.table_type::text, c.reltuples::bigint \
+             FROM information_schema.tables t \
+             LEFT JOIN pg_namespace n ON n.nspname = t.table_schema \
+             LEFT JOIN pg_class c ON c.relnamespace = n.oid AND c.relname = t.table_name \
+             WHERE t.table_schema = $1 \
+             ORDER BY t.table_name",
+        )
+        .bind(&args.schema_name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut tables: Vec<PostgresTableInfo> = rows
+            .into_iter()
+            .map(
+                |(schema_name, name, table_type, estimated_rows)| PostgresTableInfo {
+                    schema_name,
+                    name,
+                    table_type,
+                    estimated_rows: estimated_rows.filter(|r| *r >= 0),
+                },
+            )
+            .collect();
+
+        if let Some(filter) = &args.filter {
+            let filter_lower = filter.to_lowercase();
+            tables.retain(|t| t.name.to_lowercase().contains(&filter_lower));
+        }
+
+        Ok(PostgresListTablesResult { tables })
+    }
+
+    pub async fn describe_table(
+        &self,
+        args: &PostgresDescribeTableArgs,
+    ) -> Result<PostgresTableDetails> {
+        let (schema_name, name) = split_table_name(&args.table_name)?;
+
+        let columns: Vec<(String, String, String, Option<String>)> = sqlx::query_as(
+            "SELECT column_name::text, \
+             CASE WHEN data_type = 'USER-DEFINED' THEN udt_name::text ELSE data_type::text END, \
+             is_nullable::text, column_default::text \
+             FROM information_schema.columns \
+             WHERE table_schema = $1 AND table_name = $2 \
+             ORDER BY ordinal_position",
+        )
+        .bind(&schema_name)
+        .bind(&name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if columns.is_empty() {
+            return Err(anyhow!(
+                "Table '{}.{}' not found or has no visible columns",
+                schema_name,
+                name
+            ));
+        }
+
+        let qualified = format!("{}.{}", quote_ident(&schema_name), quote_ident(&name));
+        let primary_key: Vec<(String,)> = sqlx::query_as(
+            "SELECT a.attname::text FROM pg_index i \
+             JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey) \
+             WHERE i.indrelid = $1::regclass AND i.indisprimary",
+        )
+        .bind(&qualified)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let estimated_rows: Option<(i64,)> =
+            sqlx::query_as("SELECT reltuples::bigint FROM pg_class WHERE oid = $1::regclass")
+                .bind(&qualified)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        let sample_data = if args.sample_size > 0 {
+            self.run_read_only(&format!("SELECT * FROM {}", qualified), args.sample_size)
+                .await?
+                .0
+        } else {
+            Vec::new()
+        };
+
+        Ok(PostgresTableDetails {
+            schema_name,
+            name,
+            columns: columns
+                .into_iter()
+                .map(|(name, data_type, is_nullable, default)| PostgresColumn {
+                    name,
+                    data_type,
+                    nullable: is_nullable == "YES",
+                    default,
+                })
+                .collect(),
+            primary_key: primary_key.into_iter().map(|(c,)| c).collect(),
+            estimated_rows: estimated_rows.map(|(r,)| r).filter(|r| *r >= 0),
+            sample_data,
+        })
+    }
+
+    /// Run a query in a read-only transaction with a statement timeout.
+    pub async fn query(&self, args: &PostgresQueryArgs) -> Result<PostgresQueryResult> {
+        let max_rows = args
+            .max_rows
+            .unwrap_or(DEFAULT_ROW_LIMIT)
+            .clamp(1, MAX_ROW_LIMIT);
+        let (rows, truncated) = self.run_read_only(&args.query, max_rows).await?;
+        Ok(PostgresQueryResult { rows, truncated })
+    }
+
+    /// Wraps the statement as a subquery so rows come back as JSON objects,
+    /// fetching one extra row to detect truncation. The transaction is always rolled back.
+    async fn run_read_only(
+        &self,
+        sql: &str,
+        max_rows: usize,
+    ) -> Result<(Vec<HashMap<String, Value>>, bool)> {
+        let sql = sql.trim().trim_end_matches(';').trim();
+        if sql.is_empty() {
+            return Err(anyhow!("Query is empty"));
+        }
+        debug!("Running read-only Postgres query: {}", sql);
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SET TRANSACTION READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!(
+            "SET LOCAL statement_timeout = '{}s'",
+            STATEMENT_TIMEOUT_SECS
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        let wrapped = format!(
+            "SELECT row_to_json(q) FROM ({}) q LIMIT {}",
+            sql,
+            max_rows + 1
+        );
+        let result: std::result::Result<Vec<(Value,)>, sqlx::Error> =
+            sqlx::query_as(&wrapped).fetch_all(&mut *tx).await;
+        tx.rollback().await?;
+        let rows = result.map_err(|e| anyhow!("Query failed: {}", e))?;
+
+        let truncated = rows.len() > max_rows;
+        let rows = rows
+            .into_iter()
+            .take(max_rows)
+            .map(|(row,)| match row {
+                Value::Object(map) => map.into_iter().collect(),
+                other => HashMap::from([("value".to_string(), other)]),
+            })
+            .collect();
+        Ok((rows, truncated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_table_name() {
+        assert_eq!(
+            split_table_name("orders").unwrap(),
+            ("public".to_string(), "orders".to_string())
+        );
+        assert_eq!(
+            split_table_name("sales.orders").unwrap(),
+            ("sales".to_string(), "orders".to_string())
+        );
+        assert!(split_table_name("a.b.c").is_err());
+        assert!(split_table_name("sales.").is_err());
+    }
+
+    #[test]
+    fn test_quote_ident() {
+        assert_eq!(quote_ident("orders"), "\"orders\"");
+        assert_eq!(quote_ident("we\"ird"), "\"we\"\"ird\"");
+    }
+}
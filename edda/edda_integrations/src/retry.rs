@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Exponential backoff with jitter for retrying transient API failures.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// No retries, fail on the first error.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry `attempt` (0-based): a random point in the upper half
+    /// of the exponential window, capped at `max_delay`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let window = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = window / 2;
+        half + half.mul_f64(fastrand::f64())
+    }
+}
+
+/// Circuit breaker that opens after `threshold` consecutive failures and rejects
+/// calls for `cooldown`. Once the cooldown passes a single probe call is let through;
+/// a success closes the circuit, a failure opens it again.
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    failures: u32,
+    opened_at: Option<Instant>,
+    /// When the probe of an open circuit was let through, others wait for its outcome
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Returns the remaining cooldown if the circuit is open. After the cooldown only
+    /// the probe call gets `Ok` until its outcome is recorded (or it is abandoned for
+    /// another cooldown).
+    pub fn check(&self) -> Result<(), Duration> {
+        let mut state = self.state.lock().unwrap();
+        match (state.opened_at, state.probe_started) {
+            (Some(opened_at), _) if opened_at.elapsed() < self.cooldown => {
+                Err(self.cooldown - opened_at.elapsed())
+            }
+            (Some(_), Some(probe)) if probe.elapsed() < self.cooldown => Err(Duration::ZERO),
+            (Some(_), _) => {
+                state.probe_started = Some(Instant::now());
+                Ok(())
+            }
+            (None, _) => Ok(()),
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures = 0;
+        state.opened_at = None;
+        state.probe_started = None;
+    }
+
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures += 1;
+        state.probe_started = None;
+        if state.failures >= self.threshold {
+            state.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_bounds() {
+        let policy = RetryPolicy::default();
+        for attempt in 0..10 {
+            let delay = policy.backoff(attempt);
+            assert!(delay <= policy.max_delay);
+        }
+        let first = policy.backoff(0);
+        assert!(first >= Duration::from_millis(250) && first <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(breaker.check().is_ok());
+        // one probe at a time
+        assert!(breaker.check().is_err());
+        breaker.record_failure();
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(breaker.check().is_ok());
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+        breaker.record_failure();
+        assert!(breaker.check().is_ok());
+    }
+}
@@ -34,8 +34,13 @@ async fn main() -> Result<()> {
 
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider =
-        CombinedProvider::new(session_ctx, databricks, None, deployment, google_sheets, io, None, &config).map_err(|_| {
+    let provider = CombinedProvider::builder(session_ctx)
+        .databricks(databricks)
+        .deployment(deployment)
+        .google_sheets(google_sheets)
+        .io(io)
+        .build(&config)
+        .map_err(|_| {
             eyre::eyre!(
                 "No integrations available. Configure at least one:\n\
              - Databricks: Set DATABRICKS_HOST and DATABRICKS_TOKEN\n\
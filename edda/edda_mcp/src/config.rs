@@ -1,5 +1,9 @@
 use crate::providers::ProviderType;
+use edda_integrations::{DatabricksAuthConfig, NotificationsConfig};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Trait that all config types must implement to support CLI overrides.
 /// Ensures consistency when adding new config fields.
@@ -11,38 +15,205 @@ pub trait ConfigOverride: Sized + Default {
     fn apply_override(self, override_val: Self::Override) -> Self;
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(default)]
 pub struct Config {
     pub with_deployment: bool,
     pub with_workspace_tools: bool,
     pub required_providers: Vec<ProviderType>,
     pub io_config: Option<IoConfig>,
+    /// how the Databricks REST client authenticates (env, PAT, OAuth or ~/.databrickscfg profile)
+    pub databricks_auth: DatabricksAuthConfig,
+    /// webhook (e.g. Slack) notified when validation passes, deployments finish or bug reports are created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notifications: Option<NotificationsConfig>,
+    /// per-tool time limits enforced around every tool call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeouts: Option<TimeoutsConfig>,
+    /// masking of secrets in tool results, notifications and ~/.edda/history.jsonl
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<RedactionConfig>,
+    /// add a JSON content block with the structured result to every tool result (also
+    /// enabled by EDDA_JSON_OUTPUT=1), for programmatic clients
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub json_output: bool,
+    /// directories outside the project that workspace tools may read, write and reference
+    /// in bash commands (absolute or ~/...)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub workspace_allowed_paths: Vec<String>,
+    /// whether the engine guide is prepended to the first tool result
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_guide: Option<EngineGuideConfig>,
+    /// opt-in anonymous tool usage metrics (counts, durations, failures; no arguments or results)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<TelemetryConfig>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Environment variable enabling `json_output` without editing the config
+pub const JSON_OUTPUT_ENV: &str = "EDDA_JSON_OUTPUT";
+
+/// Extra patterns are regexes; when a pattern has a `secret` named group only that group
+/// is masked (e.g. the password in a DSN), otherwise the whole match is.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// turn off the built-in token, API key and DSN patterns
+    pub disable_builtin: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+}
+
+/// Clients that fetch the guide themselves (the edda://guide/engine resource or the
+/// get_started tool) can skip having it prepended to their first tool result.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct EngineGuideConfig {
+    /// never prepend the guide
+    pub disable_injection: bool,
+    /// client names, as sent in the MCP initialize request, that don't get the guide
+    /// prepended (e.g. "cursor")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip_clients: Vec<String>,
+}
+
+impl EngineGuideConfig {
+    pub fn injects_for(&self, client_name: Option<&str>) -> bool {
+        !self.disable_injection
+            && !client_name.is_some_and(|name| {
+                self.skip_clients
+                    .iter()
+                    .any(|skipped| skipped.eq_ignore_ascii_case(name))
+            })
+    }
+}
+
+/// Metrics are only sent when `enabled` is set and an `endpoint` is given; `--no-telemetry`
+/// turns them off for a single run.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct TelemetryConfig {
+    pub enabled: bool,
+    /// URL the batches are POSTed to as JSON
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// seconds between batches (default 300)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flush_interval_secs: Option<u64>,
+}
+
+/// Time limits in seconds. The most specific entry wins: tool name, then provider
+/// category (e.g. "DatabricksRest", "Io"), then `default_secs`. A value of 0 means no limit.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct TimeoutsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_secs: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<String, u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, u64>,
+}
+
+impl TimeoutsConfig {
+    pub fn for_tool(&self, tool: &str, category: Option<&str>) -> Option<Duration> {
+        self.tools
+            .get(tool)
+            .or_else(|| category.and_then(|c| self.categories.get(c)))
+            .or(self.default_secs.as_ref())
+            .filter(|secs| **secs > 0)
+            .map(|secs| Duration::from_secs(*secs))
+    }
+
+    /// Dagger's execute timeout for sandboxes of tool calls, so it never cuts a call
+    /// short of its limit: the default unless a configured limit is longer, `None` when
+    /// some limit is disabled.
+    pub fn sandbox_execute_secs(&self) -> Option<u64> {
+        self.default_secs
+            .iter()
+            .chain(self.categories.values())
+            .chain(self.tools.values())
+            .try_fold(DEFAULT_SANDBOX_EXECUTE_SECS, |max, secs| match secs {
+                0 => None,
+                secs => Some(max.max(*secs)),
+            })
+    }
+}
+
+/// Dagger's execute timeout when no configured time limit is longer
+pub const DEFAULT_SANDBOX_EXECUTE_SECS: u64 = 600;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub enum TemplateConfig {
     Trpc,
+    Streamlit,
+    FastApi,
+    Axum,
+    /// `path` is a template source: a local directory, `git+<url>[#<rev>]` or
+    /// `oci://<reference>`
     Custom { name: String, path: String },
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct IoConfig {
     pub template: TemplateConfig,
+    /// extra templates scaffold_data_app can pick by name (name -> local template directory,
+    /// `git+<url>[#<rev>]` or `oci://<reference>`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, String>,
     pub validation: Option<ValidationConfig>,
     pub screenshot: Option<ScreenshotConfig>,
+    /// run `"run_in": "host"` hooks of templates fetched from git or OCI (hooks of
+    /// embedded and local templates always run)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_host_hooks: bool,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct ValidationConfig {
     pub command: String,
     pub docker_image: String,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Unset fields fall back to the `edda_screenshot::ScreenshotOptions` defaults, screenshots
+/// are enabled unless `enabled` is false
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
 pub struct ScreenshotConfig {
     pub enabled: Option<bool>,
     pub url: Option<String>,
+    /// More URL paths to capture after `url`, one screenshot each
+    #[serde(default)]
+    pub routes: Option<Vec<String>>,
+    /// Screens to capture every route on, e.g. ["desktop", "mobile"]
+    #[serde(default)]
+    pub viewports: Option<Vec<edda_screenshot::Viewport>>,
+    /// Steps run on the landing page before it's captured, e.g. to log in
+    #[serde(default)]
+    pub interactions: Option<Vec<edda_screenshot::InteractionStep>>,
+    /// Browser errors that fail validation (default: uncaught exceptions and failing
+    /// tRPC requests)
+    #[serde(default)]
+    pub log_policy: Option<edda_screenshot::LogPolicy>,
+    /// Record every request with its status and timing to network.json
+    #[serde(default)]
+    pub record_network: Option<bool>,
+    /// Audit captured pages with axe-core, violations are reported as warnings
+    /// (default: false)
+    #[serde(default)]
+    pub accessibility_audit: Option<bool>,
+    /// Capture with a browser container kept running by the server instead of a new one
+    /// per validation (default: false)
+    #[serde(default)]
+    pub daemon: Option<bool>,
+    /// CSS selector of the element to capture instead of the whole page
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Make a screenshot that looks different from the baseline the new baseline, set per
+    /// validation (default: false)
+    #[serde(default)]
+    pub accept_visual_changes: Option<bool>,
+    /// Record the app loading as a video or Playwright trace, attached to validation results
+    #[serde(default)]
+    pub recording: Option<edda_screenshot::Recording>,
     pub port: Option<u16>,
     pub wait_time_ms: Option<u64>,
 }
@@ -73,28 +244,67 @@ pub struct ValidationConfigOverrides {
 pub struct ScreenshotConfigOverrides {
     pub enabled: Option<bool>,
     pub url: Option<String>,
+    pub routes: Option<Vec<String>>,
+    pub viewports: Option<Vec<edda_screenshot::Viewport>>,
+    pub record_network: Option<bool>,
+    pub accessibility_audit: Option<bool>,
+    pub daemon: Option<bool>,
+    pub recording: Option<edda_screenshot::Recording>,
     pub port: Option<u16>,
     pub wait_time_ms: Option<u64>,
 }
 
 impl Config {
+    pub fn json_output_enabled(&self) -> bool {
+        self.json_output_with(std::env::var(JSON_OUTPUT_ENV).ok().as_deref())
+    }
+
+    /// `json_output_enabled` with the value of the environment variable given
+    fn json_output_with(&self, env_value: Option<&str>) -> bool {
+        self.json_output || matches!(env_value, Some("1" | "true" | "yes"))
+    }
+
     pub fn load_from_dir() -> eyre::Result<Self> {
         let edda_dir = match crate::paths::edda_dir() {
             Ok(dir) => dir,
             Err(_) => return Ok(Self::default()),
         };
 
-        let config_path = edda_dir.join("config.json");
+        let config_path = crate::paths::config_path()?;
         if !config_path.exists() {
+            // a mistyped profile shouldn't quietly run with the defaults
+            if let Some(profile) = crate::paths::profile()? {
+                eyre::bail!(
+                    "profile '{}' has no config, create {} (e.g. from a copy of config.json)",
+                    profile,
+                    config_path.display()
+                );
+            }
             let json = serde_json::to_string_pretty(&Self::default())?;
             std::fs::create_dir_all(&edda_dir)?;
             std::fs::write(&config_path, json)?;
         }
 
         let contents = std::fs::read_to_string(&config_path)?;
-        serde_json::from_str::<Config>(&contents).map_err(Into::into)
+        Self::parse(&contents)
+            .map_err(|e| eyre::eyre!("{} is invalid: {}", config_path.display(), e))
     }
 
+    /// Parse a config, failing on mistyped values and missing fields with their location.
+    /// Unknown keys are warned about and ignored, so an older binary still starts.
+    pub fn parse(contents: &str) -> eyre::Result<Self> {
+        let (unknown, issues): (Vec<_>, Vec<_>) = crate::config_schema::validate(contents)?
+            .into_iter()
+            .partition(|issue| issue.unknown_key);
+        for issue in unknown {
+            eprintln!("⚠️  Ignoring config key {}", issue);
+        }
+        if !issues.is_empty() {
+            let issues: Vec<String> = issues.iter().map(|issue| format!("  {}", issue)).collect();
+            eyre::bail!("\n{}", issues.join("\n"));
+        }
+        serde_json::from_str(contents).map_err(Into::into)
+    }
 }
 
 impl Default for Config {
@@ -108,6 +318,14 @@ impl Default for Config {
                 ProviderType::Io,
             ],
             io_config: Some(IoConfig::default()),
+            databricks_auth: DatabricksAuthConfig::default(),
+            notifications: None,
+            timeouts: None,
+            redaction: None,
+            json_output: false,
+            workspace_allowed_paths: Vec::new(),
+            engine_guide: None,
+            telemetry: None,
         }
     }
 }
@@ -116,8 +334,10 @@ impl Default for IoConfig {
     fn default() -> Self {
         Self {
             template: TemplateConfig::Trpc,
+            templates: BTreeMap::new(),
             validation: None,
             screenshot: Some(ScreenshotConfig::default()),
+            allow_host_hooks: false,
         }
     }
 }
@@ -131,17 +351,6 @@ impl Default for ValidationConfig {
     }
 }
 
-impl Default for ScreenshotConfig {
-    fn default() -> Self {
-        Self {
-            enabled: Some(true),
-            url: Some("/".to_string()),
-            port: Some(8000),
-            wait_time_ms: Some(30000),
-        }
-    }
-}
-
 // ConfigOverride trait implementations
 
 impl ConfigOverride for Config {
@@ -183,6 +392,8 @@ impl ConfigOverride for IoConfig {
             // special case: enabled=false with no other fields means disable screenshot
             if screenshot_override.enabled == Some(false)
                 && screenshot_override.url.is_none()
+                && screenshot_override.routes.is_none()
+                && screenshot_override.viewports.is_none()
                 && screenshot_override.port.is_none()
                 && screenshot_override.wait_time_ms.is_none()
             {
@@ -223,6 +434,24 @@ impl ConfigOverride for ScreenshotConfig {
         if let Some(v) = override_val.url {
             self.url = Some(v);
         }
+        if let Some(v) = override_val.routes {
+            self.routes = Some(v);
+        }
+        if let Some(v) = override_val.viewports {
+            self.viewports = Some(v);
+        }
+        if let Some(v) = override_val.record_network {
+            self.record_network = Some(v);
+        }
+        if let Some(v) = override_val.accessibility_audit {
+            self.accessibility_audit = Some(v);
+        }
+        if let Some(v) = override_val.daemon {
+            self.daemon = Some(v);
+        }
+        if let Some(v) = override_val.recording {
+            self.recording = Some(v);
+        }
         if let Some(v) = override_val.port {
             self.port = Some(v);
         }
@@ -232,3 +461,63 @@ impl ConfigOverride for ScreenshotConfig {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_output_enabled() {
+        let config = Config::default();
+        assert!(!config.json_output_with(None));
+        assert!(!config.json_output_with(Some("0")));
+        assert!(config.json_output_with(Some("1")));
+        assert!(config.json_output_with(Some("true")));
+
+        let config = Config {
+            json_output: true,
+            ..Config::default()
+        };
+        assert!(config.json_output_with(None));
+    }
+
+    #[test]
+    fn test_timeout_precedence() {
+        let timeouts = TimeoutsConfig {
+            default_secs: Some(120),
+            categories: BTreeMap::from([("Io".to_string(), 900), ("Git".to_string(), 0)]),
+            tools: BTreeMap::from([("validate_data_app".to_string(), 1800)]),
+        };
+        let secs = |tool, category| timeouts.for_tool(tool, category).map(|d| d.as_secs());
+        // tool name over category over default
+        assert_eq!(secs("validate_data_app", Some("Io")), Some(1800));
+        assert_eq!(secs("read_file", Some("Io")), Some(900));
+        assert_eq!(secs("databricks_query", Some("DatabricksRest")), Some(120));
+        assert_eq!(secs("databricks_query", None), Some(120));
+        // 0 turns the limit off, also over the default
+        assert_eq!(secs("git_status", Some("Git")), None);
+        assert_eq!(TimeoutsConfig::default().for_tool("read_file", Some("Io")), None);
+    }
+
+    #[test]
+    fn test_sandbox_execute_secs() {
+        let default = Some(DEFAULT_SANDBOX_EXECUTE_SECS);
+        let mut timeouts = TimeoutsConfig::default();
+        assert_eq!(timeouts.sandbox_execute_secs(), default);
+        // shorter limits are enforced by the tool call itself
+        timeouts.default_secs = Some(60);
+        assert_eq!(timeouts.sandbox_execute_secs(), default);
+        timeouts.tools.insert("validate_data_app".to_string(), 1800);
+        assert_eq!(timeouts.sandbox_execute_secs(), Some(1800));
+        timeouts.categories.insert("Io".to_string(), 0);
+        assert_eq!(timeouts.sandbox_execute_secs(), None);
+    }
+
+    #[test]
+    fn test_parse_ignores_unknown_keys() {
+        let config = Config::parse(r#"{"with_deploymnet": false, "json_output": true}"#).unwrap();
+        assert!(config.json_output);
+        assert!(config.with_deployment);
+        assert!(Config::parse(r#"{"json_output": "yes"}"#).is_err());
+    }
+}
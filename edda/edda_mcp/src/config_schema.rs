@@ -0,0 +1,574 @@
+//! JSON Schema of `config.json`, generated from [`Config`], and validation against it so
+//! mistakes are reported with their key path and line instead of being silently ignored.
+
+use crate::config::Config;
+use serde_json::Value;
+use std::fmt;
+
+/// The schema `config.json` is validated against, also printed by `edda_mcp config schema`
+pub fn schema() -> Value {
+    schemars::schema_for!(Config).to_value()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A mismatch between a config file and the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub path: Vec<Segment>,
+    pub message: String,
+    /// Line of the offending key or value, 1-based
+    pub line: Option<usize>,
+    /// That line as written
+    pub source: Option<String>,
+    /// A key the schema doesn't know, e.g. one of a newer version's config. Only warned
+    /// about, the config still loads.
+    pub unknown_key: bool,
+}
+
+impl ConfigIssue {
+    pub fn path(&self) -> String {
+        let mut out = String::new();
+        for segment in self.path.iter() {
+            match segment {
+                Segment::Key(key) if out.is_empty() => out.push_str(key),
+                Segment::Key(key) => {
+                    out.push('.');
+                    out.push_str(key);
+                }
+                Segment::Index(index) => out.push_str(&format!("[{}]", index)),
+            }
+        }
+        match out.is_empty() {
+            true => "(root)".to_string(),
+            false => out,
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path(), self.message)?;
+        if let (Some(line), Some(source)) = (self.line, &self.source) {
+            write!(f, "\n    {:>4} | {}", line, source.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Unknown keys, type mismatches and missing fields of `contents`, which must be valid JSON
+pub fn validate(contents: &str) -> eyre::Result<Vec<ConfigIssue>> {
+    let value: Value = serde_json::from_str(contents)?;
+    let root = schema();
+    let mut issues = Vec::new();
+    Validator { root: &root }.check(&root, &value, &mut Vec::new(), &mut issues);
+    // a one-line config, as passed to --json, is already on screen
+    let multiline = contents.trim().contains('\n');
+    for issue in issues.iter_mut() {
+        issue.line = locate(contents, &issue.path);
+        issue.source = issue
+            .line
+            .filter(|_| multiline)
+            .and_then(|line| contents.lines().nth(line - 1))
+            .map(str::to_string);
+    }
+    issues.sort_by_key(|issue| issue.line);
+    Ok(issues)
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Validator<'a> {
+    /// Follow `$ref`s into the root's `$defs`
+    fn resolve(&self, mut schema: &'a Value) -> &'a Value {
+        while let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let pointer = reference.trim_start_matches('#');
+            match self.root.pointer(pointer) {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+
+    fn check(
+        &self,
+        schema: &'a Value,
+        value: &Value,
+        path: &mut Vec<Segment>,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let schema = self.resolve(schema);
+        let branches = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array);
+        if let Some(branches) = branches {
+            self.check_branches(branches, value, path, issues);
+            return;
+        }
+
+        if !self.type_matches(schema, value) {
+            let message = format!(
+                "expected {}, found {}",
+                self.describe(schema),
+                describe_value(value)
+            );
+            issues.push(issue(path, message));
+            return;
+        }
+        if let Some(expected) = schema.get("const")
+            && expected != value
+        {
+            let message = format!("expected {}, found {}", expected, value);
+            issues.push(issue(path, message));
+            return;
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+            && !allowed.contains(value)
+        {
+            let message = format!(
+                "expected {}, found {}",
+                self.describe(schema),
+                describe_value(value)
+            );
+            issues.push(issue(path, message));
+            return;
+        }
+
+        match value {
+            Value::Object(object) => self.check_object(schema, object, path, issues),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        path.push(Segment::Index(index));
+                        self.check(item_schema, item, path, issues);
+                        path.pop();
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                let minimum = schema.get("minimum").and_then(Value::as_f64);
+                let maximum = schema.get("maximum").and_then(Value::as_f64);
+                if minimum.is_some_and(|min| number < min)
+                    || maximum.is_some_and(|max| number > max)
+                {
+                    let range = match (minimum, maximum) {
+                        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+                        (Some(min), None) => format!("at least {}", min),
+                        _ => format!("at most {}", maximum.unwrap_or_default()),
+                    };
+                    issues.push(issue(
+                        path,
+                        format!("{} is out of range, expected {}", number, range),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &'a Value,
+        object: &serde_json::Map<String, Value>,
+        path: &mut Vec<Segment>,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (key, item) in object.iter() {
+            path.push(Segment::Key(key.clone()));
+            match (properties.and_then(|p| p.get(key)), additional) {
+                (Some(property), _) => self.check(property, item, path, issues),
+                (None, Some(Value::Bool(true))) => {}
+                (None, Some(additional)) if additional.is_object() => {
+                    self.check(additional, item, path, issues)
+                }
+                (None, _) => {
+                    let expected: Vec<&str> = properties
+                        .map(|p| p.keys().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    let message = match suggestion(key, &expected) {
+                        Some(close) => format!("unknown key, did you mean {:?}?", close),
+                        None if expected.is_empty() => "unknown key".to_string(),
+                        None => format!("unknown key, expected one of {}", expected.join(", ")),
+                    };
+                    issues.push(ConfigIssue {
+                        unknown_key: true,
+                        ..issue(path, message)
+                    });
+                }
+            }
+            path.pop();
+        }
+        let required = schema.get("required").and_then(Value::as_array);
+        for key in required.into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                issues.push(issue(path, format!("missing required key {:?}", key)));
+            }
+        }
+    }
+
+    /// Enums and optional values: the value has to match one branch. Branches that
+    /// can't be meant (another type, another tag) are ruled out first, so the issues come
+    /// from the variant the user was going for.
+    fn check_branches(
+        &self,
+        branches: &'a [Value],
+        value: &Value,
+        path: &mut Vec<Segment>,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let mut best: Option<Vec<ConfigIssue>> = None;
+        for branch in branches.iter().filter(|b| self.could_match(b, value)) {
+            let mut found = Vec::new();
+            self.check(branch, value, path, &mut found);
+            if found.is_empty() {
+                return;
+            }
+            if best.as_ref().is_none_or(|best| found.len() < best.len()) {
+                best = Some(found);
+            }
+        }
+        match best {
+            Some(found) => issues.extend(found),
+            None => {
+                let expected: Vec<String> = branches.iter().map(|b| self.describe(b)).collect();
+                let message = format!(
+                    "expected {}, found {}",
+                    expected.join(" or "),
+                    describe_value(value)
+                );
+                issues.push(issue(path, message));
+            }
+        }
+    }
+
+    fn could_match(&self, branch: &'a Value, value: &Value) -> bool {
+        let branch = self.resolve(branch);
+        if let Some(branches) = branch
+            .get("anyOf")
+            .or_else(|| branch.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            return branches.iter().any(|b| self.could_match(b, value));
+        }
+        if !self.type_matches(branch, value) {
+            return false;
+        }
+        if let Some(expected) = branch.get("const") {
+            return expected == value;
+        }
+        if let Some(allowed) = branch.get("enum").and_then(Value::as_array) {
+            return allowed.contains(value);
+        }
+        // the tag of an internally tagged enum, e.g. `"type": "Profile"`
+        let tags = branch
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, property)| Some((key, self.resolve(property).get("const")?)));
+        for (key, tag) in tags {
+            if value.get(key).is_some_and(|found| found != tag) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn type_matches(&self, schema: &Value, value: &Value) -> bool {
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(name)) => vec![name.as_str()],
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+            _ => return true,
+        };
+        types.iter().any(|name| match *name {
+            "null" => value.is_null(),
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "number" => value.is_number(),
+            _ => true,
+        })
+    }
+
+    /// What a value matching `schema` looks like, for messages
+    fn describe(&self, schema: &'a Value) -> String {
+        let schema = self.resolve(schema);
+        if let Some(branches) = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            let parts: Vec<String> = branches.iter().map(|b| self.describe(b)).collect();
+            return parts.join(" or ");
+        }
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            let parts: Vec<String> = allowed.iter().map(Value::to_string).collect();
+            return parts.join(" or ");
+        }
+        if let Some(expected) = schema.get("const") {
+            return expected.to_string();
+        }
+        let tags: Vec<String> = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(key, property)| {
+                let tag = self.resolve(property).get("const")?;
+                Some(format!("{:?}: {}", key, tag))
+            })
+            .collect();
+        if !tags.is_empty() {
+            return format!("{{{}}}", tags.join(", "));
+        }
+        match schema.get("type") {
+            Some(Value::String(name)) => name.clone(),
+            Some(Value::Array(names)) => {
+                let names: Vec<&str> = names.iter().filter_map(Value::as_str).collect();
+                names.join(" or ")
+            }
+            _ => "any value".to_string(),
+        }
+    }
+}
+
+fn issue(path: &[Segment], message: String) -> ConfigIssue {
+    ConfigIssue {
+        path: path.to_vec(),
+        message,
+        line: None,
+        source: None,
+        unknown_key: false,
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::String(s) => format!("string {:?}", s),
+        Value::Array(_) => "array".to_string(),
+        Value::Object(_) => "object".to_string(),
+    }
+}
+
+/// A known key one or two edits away from `key`, for typos
+fn suggestion<'k>(key: &str, expected: &[&'k str]) -> Option<&'k str> {
+    expected
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Line of the key or array item at `path` in valid JSON `contents`
+fn locate(contents: &str, path: &[Segment]) -> Option<usize> {
+    let mut cursor = Cursor {
+        bytes: contents.as_bytes(),
+        pos: 0,
+    };
+    cursor.skip_whitespace();
+    let mut found = cursor.pos;
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                cursor.expect(b'{')?;
+                loop {
+                    cursor.skip_whitespace();
+                    let start = cursor.pos;
+                    let name = cursor.string()?;
+                    cursor.skip_whitespace();
+                    cursor.expect(b':')?;
+                    cursor.skip_whitespace();
+                    if name == key.as_bytes() {
+                        found = start;
+                        break;
+                    }
+                    cursor.skip_value()?;
+                    cursor.skip_whitespace();
+                    cursor.expect(b',')?;
+                }
+            }
+            Segment::Index(index) => {
+                cursor.expect(b'[')?;
+                cursor.skip_whitespace();
+                for _ in 0..*index {
+                    cursor.skip_value()?;
+                    cursor.skip_whitespace();
+                    cursor.expect(b',')?;
+                    cursor.skip_whitespace();
+                }
+                found = cursor.pos;
+            }
+        }
+    }
+    Some(contents[..found].matches('\n').count() + 1)
+}
+
+/// Just enough of a JSON scanner to find where a key is written
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        (self.bytes.get(self.pos) == Some(&byte)).then(|| self.pos += 1)
+    }
+
+    /// Raw contents of the string at the cursor, escapes left as written
+    fn string(&mut self) -> Option<&'a [u8]> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.bytes.get(self.pos)? {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        Some(&self.bytes[start..self.pos - 1])
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.bytes.get(self.pos)? {
+            b'"' => {
+                self.string()?;
+            }
+            b'{' | b'[' => {
+                let mut depth = 0usize;
+                loop {
+                    match self.bytes.get(self.pos)? {
+                        b'"' => {
+                            self.string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+                    self.pos += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            _ => {
+                while self
+                    .bytes
+                    .get(self.pos)
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(contents: &str) -> Vec<String> {
+        validate(contents)
+            .unwrap()
+            .iter()
+            .map(|issue| {
+                format!(
+                    "{} (line {:?}): {}",
+                    issue.path(),
+                    issue.line,
+                    issue.message
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        let contents = serde_json::to_string_pretty(&Config::default()).unwrap();
+        assert_eq!(messages(&contents), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_unknown_keys_and_type_mismatches() {
+        let contents = r#"{
+  "with_deploymnet": false,
+  "io_config": {
+    "template": "Trpc",
+    "screenshot": {
+      "port": "8000",
+      "routes": ["/", 3]
+    }
+  },
+  "databricks_auth": {"type": "Profile"},
+  "required_providers": ["Databricks", "Githb"]
+}"#;
+        assert_eq!(
+            messages(contents),
+            vec![
+                r#"with_deploymnet (line Some(2)): unknown key, did you mean "with_deployment"?"#,
+                r#"io_config.screenshot.port (line Some(6)): expected integer or null, found string "8000""#,
+                r#"io_config.screenshot.routes[1] (line Some(7)): expected string, found number 3"#,
+                r#"databricks_auth (line Some(10)): missing required key "name""#,
+                r#"required_providers[1] (line Some(11)): expected "Deployment" or "Github" or "GoogleSheets" or "Io" or "ObjectStore" or "Postgres" or "Workspace" or "DatabricksRest" or "DatabricksCli" or "Databricks", found string "Githb""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_variants_are_told_apart() {
+        assert_eq!(
+            messages(r#"{"io_config": {"template": {"Custom": {"name": "app"}}}}"#),
+            vec![r#"io_config.template.Custom (line Some(1)): missing required key "path""#]
+        );
+        assert_eq!(
+            messages(r#"{"io_config": {"template": "Trpc", "screenshot": {"recording": "vid"}}}"#),
+            vec![
+                r#"io_config.screenshot.recording (line Some(1)): expected "video" or "trace" or null, found string "vid""#
+            ]
+        );
+    }
+}
@@ -0,0 +1,671 @@
+//! `edda_mcp doctor`: diagnose the environment (docker, dagger, config files, credentials,
+//! template prerequisites, sandbox images, disk space) and, with `--fix`, repair what can be repaired.
+
+use crate::config::Config;
+use crate::env::{self, EnvVars};
+use crate::paths;
+use crate::providers::{IOProvider, ProviderType};
+use dialoguer::{Confirm, Input, Password};
+use edda_integrations::DatabricksAuthConfig;
+use edda_sandbox::dagger::{ConnectOpts, Logger};
+use edda_sandbox::{DaggerSandbox, Sandbox};
+use edda_templates::TemplateCapabilities;
+use eyre::Result;
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// Image used by the default validation strategies when no template manifest can be read
+const DEFAULT_SANDBOX_IMAGE: &str = "node:20-alpine3.22";
+
+/// Sandbox and browser images plus node_modules of a few projects
+const MIN_FREE_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Images pulled by the last successful `doctor --fix`, one per line
+const WARMUP_MARKER: &str = ".warmup";
+
+const PLAYWRIGHT_MARKER: &str = "playwright";
+
+pub struct DoctorOptions {
+    /// Offer fixes for failed checks
+    pub fix: bool,
+    /// Apply fixes without asking
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fix {
+    CreateConfig,
+    CreateEnvFile,
+    DatabricksCredentials,
+    ProviderVariables,
+    PullImages,
+}
+
+impl Fix {
+    fn prompt(&self) -> &'static str {
+        match self {
+            Fix::CreateConfig => {
+                "Write a default ~/.edda/config.json (a broken one is kept as config.json.bak)?"
+            }
+            Fix::CreateEnvFile => "Create ~/.edda/.env from .env.example?",
+            Fix::DatabricksCredentials => {
+                "Enter Databricks credentials and save them to ~/.edda/.env?"
+            }
+            Fix::ProviderVariables => "Enter the missing variables and save them to ~/.edda/.env?",
+            Fix::PullImages => "Pull sandbox images now? This may take a few minutes",
+        }
+    }
+
+    /// Fixes asking for values rather than a yes or no, skipped under `--yes` and without
+    /// a terminal so unattended runs (install.sh, CI) don't hang
+    fn needs_input(&self) -> bool {
+        matches!(self, Fix::DatabricksCredentials | Fix::ProviderVariables)
+    }
+}
+
+struct Finding {
+    status: Status,
+    details: Vec<String>,
+    fix: Option<Fix>,
+}
+
+impl Finding {
+    fn pass(details: Vec<String>) -> Self {
+        Self {
+            status: Status::Pass,
+            details,
+            fix: None,
+        }
+    }
+
+    fn warn(detail: impl Into<String>, fix: Option<Fix>) -> Self {
+        Self {
+            status: Status::Warn,
+            details: vec![detail.into()],
+            fix,
+        }
+    }
+
+    fn fail(detail: impl Into<String>, fix: Option<Fix>) -> Self {
+        Self {
+            status: Status::Fail,
+            details: vec![detail.into()],
+            fix,
+        }
+    }
+
+    fn print(&self, name: &str) {
+        let mark = match self.status {
+            Status::Pass => "✓",
+            Status::Warn => "⚠",
+            Status::Fail => "✗",
+        };
+        println!("  {}... {}", name, mark);
+        for detail in &self.details {
+            println!("    {}", detail);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Check {
+    ConfigFile,
+    EnvFile,
+    Docker,
+    Dagger,
+    DiskSpace,
+    DatabricksCredentials,
+    DatabricksCli,
+    ProviderVariables,
+    Templates,
+    SandboxImages,
+}
+
+impl Check {
+    fn name(&self) -> &'static str {
+        match self {
+            Check::ConfigFile => "Config file",
+            Check::EnvFile => "Env file",
+            Check::Docker => "Docker availability",
+            Check::Dagger => "Dagger engine",
+            Check::DiskSpace => "Disk space",
+            Check::DatabricksCredentials => "Databricks credentials",
+            Check::DatabricksCli => "Databricks CLI",
+            Check::ProviderVariables => "Provider variables",
+            Check::Templates => "Template prerequisites",
+            Check::SandboxImages => "Sandbox images",
+        }
+    }
+}
+
+/// check if docker is available by running 'docker ps'
+pub async fn check_docker_available() -> Result<()> {
+    let output = tokio::process::Command::new("docker")
+        .arg("ps")
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(_) => Err(eyre::eyre!(
+            "docker command found but not responding (is the daemon running?)"
+        )),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(eyre::eyre!("docker command not found"))
+        }
+        Err(e) => Err(eyre::eyre!("failed to check docker: {}", e)),
+    }
+}
+
+/// Available bytes from `df -Pk` output (second line, fourth column in KiB).
+fn parse_df_available(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let kib: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+async fn free_disk_space(path: &Path) -> Option<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .await
+        .ok()?;
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The configured validation image, if any
+fn validation_image(config: &Config) -> Option<String> {
+    config
+        .io_config
+        .as_ref()
+        .and_then(|io| io.validation.as_ref())
+        .map(|validation| validation.docker_image.clone())
+        .filter(|image| !image.is_empty())
+}
+
+/// Capability manifests of the configured templates, by template name
+fn template_capabilities(config: &Config) -> Vec<(String, Result<TemplateCapabilities>)> {
+    match IOProvider::new(config.io_config.clone()) {
+        Ok(provider) => provider.template_capabilities(),
+        Err(e) => vec![("default".to_string(), Err(e))],
+    }
+}
+
+/// Images validation and screenshots pull on first use: the configured validation image,
+/// otherwise the ones the templates' manifests ask for.
+fn sandbox_images(config: &Config) -> Vec<String> {
+    let io = config.io_config.clone().unwrap_or_default();
+    let mut images = match validation_image(config) {
+        Some(image) => vec![image],
+        None => {
+            let mut images = Vec::new();
+            for (_, capabilities) in template_capabilities(config) {
+                let Ok(capabilities) = capabilities else {
+                    continue;
+                };
+                let image = capabilities.sandbox_image();
+                if !images.contains(&image) {
+                    images.push(image);
+                }
+            }
+            images
+        }
+    };
+    if images.is_empty() {
+        images.push(DEFAULT_SANDBOX_IMAGE.to_string());
+    }
+    if io
+        .screenshot
+        .is_some_and(|screenshot| screenshot.enabled.unwrap_or(true))
+    {
+        images.push(PLAYWRIGHT_MARKER.to_string());
+    }
+    images
+}
+
+/// pull the sandbox images (and install playwright browsers) through dagger
+async fn pull_images(images: Vec<String>) -> Result<()> {
+    let opts = ConnectOpts::default()
+        .with_logger(Logger::Silent)
+        .with_execute_timeout(Some(1800));
+
+    opts.connect(|client| async move {
+        for image in images {
+            if image == PLAYWRIGHT_MARKER {
+                edda_screenshot::warmup_playwright(&client).await?;
+                continue;
+            }
+            let container = client
+                .container()
+                .from(image)
+                .with_exec(vec!["mkdir", "-p", "/app"]);
+            let sandbox = DaggerSandbox::from_container(container, client.clone());
+            // force evaluation to ensure image is pulled
+            let _ = sandbox.list_directory("/app").await?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| eyre::eyre!("dagger connect failed: {}", e))?;
+
+    Ok(())
+}
+
+struct Doctor<'a> {
+    config: &'a Config,
+    options: &'a DoctorOptions,
+    env: EnvVars,
+    docker_available: bool,
+}
+
+impl Doctor<'_> {
+    fn databricks_required(&self) -> bool {
+        self.config
+            .required_providers
+            .iter()
+            .any(|p| matches!(p, ProviderType::DatabricksRest | ProviderType::Deployment))
+    }
+
+    fn deployment_required(&self) -> bool {
+        self.config
+            .required_providers
+            .contains(&ProviderType::Deployment)
+    }
+
+    fn checks(&self) -> Vec<Check> {
+        let mut checks = vec![
+            Check::ConfigFile,
+            Check::EnvFile,
+            Check::Docker,
+            Check::Dagger,
+            Check::DiskSpace,
+        ];
+        if self.databricks_required() {
+            checks.push(Check::DatabricksCredentials);
+        }
+        if self.deployment_required() {
+            checks.push(Check::DatabricksCli);
+        }
+        if !env::requirements(self.config).is_empty() {
+            checks.push(Check::ProviderVariables);
+        }
+        if self.config.required_providers.contains(&ProviderType::Io) {
+            checks.push(Check::Templates);
+        }
+        checks.push(Check::SandboxImages);
+        checks
+    }
+
+    async fn run_check(&mut self, check: Check) -> Result<Finding> {
+        let edda_dir = paths::edda_dir()?;
+        let finding = match check {
+            Check::ConfigFile => {
+                let path = paths::config_path()?;
+                match std::fs::read_to_string(&path) {
+                    Err(_) => Finding::fail(
+                        format!("{} not found", path.display()),
+                        Some(Fix::CreateConfig),
+                    ),
+                    Ok(contents) => match Config::parse(&contents) {
+                        Ok(_) => Finding::pass(vec![path.display().to_string()]),
+                        Err(e) => Finding::fail(
+                            format!("{} is invalid: {}", path.display(), e),
+                            Some(Fix::CreateConfig),
+                        ),
+                    },
+                }
+            }
+            Check::EnvFile => {
+                let path = edda_dir.join(".env");
+                match path.exists() {
+                    true => Finding::pass(vec![path.display().to_string()]),
+                    false => Finding::warn(
+                        format!(
+                            "{} not found, credentials are read from the system environment only",
+                            path.display()
+                        ),
+                        Some(Fix::CreateEnvFile),
+                    ),
+                }
+            }
+            Check::Docker => match check_docker_available().await {
+                Ok(_) => Finding::pass(Vec::new()),
+                Err(e) => Finding::fail(
+                    format!("Error: {} (install or start Docker Desktop)", e),
+                    None,
+                ),
+            },
+            Check::Dagger if !self.docker_available => {
+                Finding::warn("skipped, docker is not available", None)
+            }
+            Check::Dagger => {
+                let opts = ConnectOpts::default()
+                    .with_logger(Logger::Silent)
+                    .with_execute_timeout(Some(300));
+                match opts.connect(|_client| async move { Ok(()) }).await {
+                    Ok(_) => Finding::pass(Vec::new()),
+                    Err(e) => Finding::fail(format!("Error: dagger connect failed: {}", e), None),
+                }
+            }
+            Check::DiskSpace => match free_disk_space(&edda_dir).await {
+                Some(free) if free < MIN_FREE_BYTES => Finding::warn(
+                    format!(
+                        "{:.1} GB free, sandbox images and node_modules need about {} GB",
+                        free as f64 / 1e9,
+                        MIN_FREE_BYTES / (1024 * 1024 * 1024)
+                    ),
+                    None,
+                ),
+                Some(free) => Finding::pass(vec![format!("{:.1} GB free", free as f64 / 1e9)]),
+                None => Finding::warn("could not determine free disk space", None),
+            },
+            Check::DatabricksCredentials => self.check_databricks(),
+            Check::DatabricksCli => {
+                match tokio::process::Command::new("databricks")
+                    .arg("--version")
+                    .output()
+                    .await
+                {
+                    Ok(output) if output.status.success() => Finding::pass(Vec::new()),
+                    _ => Finding::warn("optional, needed for deployment", None),
+                }
+            }
+            Check::ProviderVariables => self.check_provider_variables(),
+            Check::Templates => self.check_templates(),
+            Check::SandboxImages if !self.docker_available => {
+                Finding::warn("skipped, docker is not available", None)
+            }
+            Check::SandboxImages => {
+                let pulled =
+                    std::fs::read_to_string(edda_dir.join(WARMUP_MARKER)).unwrap_or_default();
+                let missing: Vec<String> = sandbox_images(self.config)
+                    .into_iter()
+                    .filter(|image| !pulled.lines().any(|line| line == image))
+                    .collect();
+                match missing.is_empty() {
+                    true => Finding::pass(Vec::new()),
+                    false => Finding::warn(
+                        format!(
+                            "not pre-pulled: {} (the first validation will download them)",
+                            missing.join(", ")
+                        ),
+                        Some(Fix::PullImages),
+                    ),
+                }
+            }
+        };
+        if check == Check::Docker {
+            self.docker_available = finding.status == Status::Pass;
+        }
+        Ok(finding)
+    }
+
+    fn check_databricks(&self) -> Finding {
+        let require_warehouse = self.deployment_required();
+        let env = &self.env;
+        // PAT via env is validated against ~/.edda/.env, other methods resolve their own credentials
+        let auth_check = match &self.config.databricks_auth {
+            DatabricksAuthConfig::Pat => env.validate_databricks(require_warehouse),
+            DatabricksAuthConfig::Env if env.databricks_token().is_some() => {
+                env.validate_databricks(require_warehouse)
+            }
+            auth => auth.resolve().map(|_| ()).map_err(|e| eyre::eyre!("{}", e)),
+        };
+        // only env based auth can be fixed by writing ~/.edda/.env
+        let fix = matches!(
+            self.config.databricks_auth,
+            DatabricksAuthConfig::Pat | DatabricksAuthConfig::Env
+        )
+        .then_some(Fix::DatabricksCredentials);
+        if let Err(e) = auth_check {
+            return Finding::fail(format!("Error: {}", e), fix);
+        }
+
+        let mut details = Vec::new();
+        if let Some(host) = env.databricks_host() {
+            details.push(format!("DATABRICKS_HOST: {}", host));
+        }
+        match &self.config.databricks_auth {
+            DatabricksAuthConfig::Env if env.databricks_token().is_none() => {
+                details.push("auth: OAuth / profile".to_string())
+            }
+            DatabricksAuthConfig::Env | DatabricksAuthConfig::Pat => {
+                let token = env.databricks_token().unwrap_or_default();
+                details.push(format!(
+                    "DATABRICKS_TOKEN: {}",
+                    env::mask("DATABRICKS_TOKEN", token)
+                ))
+            }
+            auth => details.push(format!("auth: {:?}", auth)),
+        }
+        if require_warehouse && let Some(warehouse_id) = env.databricks_warehouse_id() {
+            details.push(format!("DATABRICKS_WAREHOUSE_ID: {}", warehouse_id));
+        }
+        Finding::pass(details)
+    }
+
+    /// Every variable the enabled providers need is set, shown masked
+    fn check_provider_variables(&self) -> Finding {
+        let requirements = env::requirements(self.config);
+        let missing = self.env.missing(&requirements);
+        if !missing.is_empty() {
+            let names: Vec<String> = missing
+                .iter()
+                .map(|(provider, name)| format!("{} ({})", name, provider.display_name()))
+                .collect();
+            return Finding::fail(
+                format!("not set: {}", names.join(", ")),
+                Some(Fix::ProviderVariables),
+            );
+        }
+        let details = requirements
+            .iter()
+            .map(|(_, name)| {
+                let value = self.env.get(name).unwrap_or_default();
+                format!("{}: {}", name, env::mask(name, value))
+            })
+            .collect();
+        Finding::pass(details)
+    }
+
+    /// Every configured template's manifest can be read, the providers it needs are enabled
+    /// and the configured validation image runs its runtime
+    fn check_templates(&self) -> Finding {
+        let enabled: Vec<&str> = self
+            .config
+            .required_providers
+            .iter()
+            .map(|provider| provider.name())
+            .collect();
+        let validation_image = validation_image(self.config);
+        let mut details = Vec::new();
+        let mut problems = Vec::new();
+        for (name, capabilities) in template_capabilities(self.config) {
+            let capabilities = match capabilities {
+                Ok(capabilities) => capabilities,
+                Err(e) => {
+                    problems.push(format!("{}: {}", name, e));
+                    continue;
+                }
+            };
+            let missing: Vec<&str> = capabilities
+                .required_providers
+                .iter()
+                .map(String::as_str)
+                .filter(|provider| !enabled.contains(provider))
+                .collect();
+            if !missing.is_empty() {
+                problems.push(format!(
+                    "{}: needs {} in required_providers",
+                    name,
+                    missing.join(", ")
+                ));
+            }
+            if let Some(mismatch) = validation_image
+                .as_deref()
+                .and_then(|image| capabilities.image_mismatch(image))
+            {
+                problems.push(format!("{}: {}", name, mismatch));
+            }
+            details.push(format!("{}: {}", name, capabilities));
+        }
+        match problems.is_empty() {
+            true => Finding::pass(details),
+            false => Finding {
+                status: Status::Fail,
+                details: problems,
+                fix: None,
+            },
+        }
+    }
+
+    fn confirm(&self, fix: Fix) -> bool {
+        self.options.yes
+            || Confirm::new()
+                .with_prompt(fix.prompt())
+                .default(true)
+                .interact()
+                .unwrap_or(false)
+    }
+
+    async fn apply(&mut self, fix: Fix) -> Result<()> {
+        let edda_dir = paths::edda_dir()?;
+        match fix {
+            Fix::CreateConfig => {
+                let path = paths::config_path()?;
+                if path.exists() {
+                    std::fs::rename(&path, path.with_extension("json.bak"))?;
+                }
+                std::fs::create_dir_all(&edda_dir)?;
+                std::fs::write(&path, serde_json::to_string_pretty(&Config::default())?)?;
+            }
+            Fix::CreateEnvFile => {
+                env::create_env_example()?;
+                let path = edda_dir.join(".env");
+                std::fs::copy(edda_dir.join(".env.example"), &path)?;
+                env::restrict_permissions(&path)?;
+            }
+            Fix::DatabricksCredentials => {
+                let host: String = Input::new()
+                    .with_prompt("DATABRICKS_HOST (e.g. your-workspace.cloud.databricks.com)")
+                    .with_initial_text(self.env.databricks_host().unwrap_or_default())
+                    .interact_text()?;
+                let token = Password::new()
+                    .with_prompt("DATABRICKS_TOKEN (personal access token, dapi...)")
+                    .interact()?;
+                let mut vars = vec![("DATABRICKS_HOST", host), ("DATABRICKS_TOKEN", token)];
+                if self.deployment_required() {
+                    let warehouse_id: String = Input::new()
+                        .with_prompt("DATABRICKS_WAREHOUSE_ID")
+                        .with_initial_text(self.env.databricks_warehouse_id().unwrap_or_default())
+                        .interact_text()?;
+                    vars.push(("DATABRICKS_WAREHOUSE_ID", warehouse_id));
+                }
+                // credentials belong to the profile's workspace when one is selected
+                env::save_env_vars(&env::env_file()?, &vars)?;
+                for (key, value) in vars {
+                    self.env.set(key, value);
+                }
+            }
+            Fix::ProviderVariables => {
+                let missing = self.env.missing(&env::requirements(self.config));
+                let mut vars = Vec::new();
+                for (provider, name) in missing {
+                    let prompt = format!("{} (for {})", name, provider.display_name());
+                    let value = match env::is_secret(name) {
+                        true => Password::new().with_prompt(prompt).interact()?,
+                        false => Input::<String>::new().with_prompt(prompt).interact_text()?,
+                    };
+                    vars.push((name, value));
+                }
+                env::save_env_vars(&env::env_file()?, &vars)?;
+                for (key, value) in vars {
+                    self.env.set(key, value);
+                }
+            }
+            Fix::PullImages => {
+                println!("    Pulling images, this may take a while on first run...");
+                let images = sandbox_images(self.config);
+                pull_images(images.clone()).await?;
+                std::fs::write(edda_dir.join(WARMUP_MARKER), images.join("\n"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub async fn run(config: &Config, options: &DoctorOptions) -> Result<()> {
+    println!("🔍 Checking environment configuration...\n");
+
+    env::create_env_example()?;
+    let mut doctor = Doctor {
+        config,
+        options,
+        env: EnvVars::load()?,
+        docker_available: false,
+    };
+
+    let mut all_passed = true;
+    let mut fixable = 0;
+    for check in doctor.checks() {
+        let mut finding = doctor.run_check(check).await?;
+        finding.print(check.name());
+        if let Some(fix) = finding.fix.filter(|_| finding.status != Status::Pass) {
+            if !options.fix {
+                fixable += 1;
+            } else if fix.needs_input() && (options.yes || !std::io::stdin().is_terminal()) {
+                println!("    Skipped, run `edda_mcp doctor --fix` in a terminal to enter them");
+            } else if doctor.confirm(fix) {
+                match doctor.apply(fix).await {
+                    Ok(_) => {
+                        finding = doctor.run_check(check).await?;
+                        finding.print(check.name());
+                    }
+                    Err(e) => println!("    Fix failed: {}", e),
+                }
+            }
+        }
+        if finding.status == Status::Fail {
+            all_passed = false;
+        }
+    }
+
+    println!();
+    if fixable > 0 {
+        println!(
+            "💡 {} problem(s) can be fixed automatically, run `edda_mcp doctor --fix`",
+            fixable
+        );
+    }
+    if all_passed {
+        println!("✅ All checks passed!");
+        Ok(())
+    } else {
+        println!("❌ Some checks failed. Please review the errors above.");
+        Err(eyre::eyre!("Environment check failed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/disk3s5   482797652 312461968 152103748      68% /System/Volumes/Data\n";
+        assert_eq!(parse_df_available(output), Some(152103748 * 1024));
+        assert_eq!(
+            parse_df_available("df: /nope: No such file or directory\n"),
+            None
+        );
+    }
+}
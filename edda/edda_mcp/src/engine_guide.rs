@@ -1,6 +1,6 @@
 /// Engine-level guidance for using edda_mcp tools effectively
-/// Injected once per session on first tool call
-
+/// Prepended to the first tool result of a session (see `engine_guide` in the config to
+/// turn that off), and served by the get_started tool and the edda://guide/engine resource
 pub const ENGINE_GUIDE: &str = r#"
 Your session in Edda MCP has been successfully initialized. Here are the guidelines to follow while working on projects using edda_mcp tools:
 
@@ -10,7 +10,7 @@ This project uses a state file (`.edda_state`) managed by edda MCP to enforce th
 2. **Validated**: `validate_data_app` runs build + tests in sandbox, computes BLAKE3 checksum of package.json and all core source files
 3. **Deployed**: `deploy_databricks_app` deploys to Databricks Apps, but ONLY if checksum hasn't changed since validation
 
-Re-validation is allowed (Deployed → Validated) to update the checksum after intentional changes. The edda MCP tools enforce these state transitions and prevent invalid state changes.
+Re-validation is allowed (Deployed → Validated) to update the checksum after intentional changes. Re-validating an unchanged project is cheap: the cached result is returned without rebuilding. The edda MCP tools enforce these state transitions and prevent invalid state changes.
 
 When in need to find some data (e.g. Databricks), use sub-agents for efficiency.
 
@@ -0,0 +1,223 @@
+//! Streamable HTTP transport, used when the server runs remotely or is shared by
+//! several clients instead of being spawned over stdio.
+
+use axum::extract::Request;
+use axum::http::{
+    StatusCode,
+    header::{AUTHORIZATION, HOST, ORIGIN},
+};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use eyre::Result;
+use rmcp::ServerHandler;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Path the MCP endpoint is mounted at.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Environment variable holding the bearer token clients must send.
+pub const HTTP_TOKEN_ENV: &str = "EDDA_MCP_HTTP_TOKEN";
+
+/// Host names a server bound to loopback answers to; any other Host is DNS rebinding.
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+#[derive(Debug, Clone)]
+pub struct HttpOptions {
+    pub addr: SocketAddr,
+    /// Required `Authorization: Bearer <token>`; no auth when None
+    pub bearer_token: Option<String>,
+}
+
+impl HttpOptions {
+    /// Without a token only loopback addresses may be bound, the tools include `bash`.
+    pub fn check(&self) -> Result<()> {
+        if self.bearer_token.is_none() && !self.addr.ip().is_loopback() {
+            eyre::bail!(
+                "refusing to listen on {} without authentication, set {} or bind a loopback address",
+                self.addr,
+                HTTP_TOKEN_ENV
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Serve MCP over streamable HTTP until ctrl-c. `factory` is called once per client
+/// session, so every session gets its own provider state.
+pub async fn serve<S, F>(options: &HttpOptions, factory: F) -> Result<()>
+where
+    S: ServerHandler,
+    F: Fn() -> std::result::Result<S, std::io::Error> + Send + Sync + 'static,
+{
+    options.check()?;
+    let service = StreamableHttpService::new(
+        factory,
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+
+    let mut router = axum::Router::new().nest_service(MCP_PATH, service);
+    if let Some(token) = options.bearer_token.clone() {
+        let token: Arc<str> = token.into();
+        router = router.layer(axum::middleware::from_fn(
+            move |req: Request, next: Next| {
+                let token = token.clone();
+                async move { require_bearer(&token, req, next).await }
+            },
+        ));
+    }
+    // outermost, so it covers every request including unauthenticated ones
+    let loopback = options.addr.ip().is_loopback();
+    router = router.layer(axum::middleware::from_fn(
+        move |req: Request, next: Next| check_origin(loopback, req, next),
+    ));
+
+    let listener = tokio::net::TcpListener::bind(options.addr).await?;
+    tracing::info!(
+        "MCP server listening on http://{}{}",
+        listener.local_addr()?,
+        MCP_PATH
+    );
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+async fn require_bearer(token: &str, req: Request, next: Next) -> Response {
+    let header = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    match is_authorized(header, token) {
+        true => next.run(req).await,
+        false => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+async fn check_origin(loopback: bool, req: Request, next: Next) -> Response {
+    let headers = req.headers();
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let host = header(HOST).or_else(|| req.uri().authority().map(|a| a.as_str()));
+    let allowed = is_allowed_origin(host, header(ORIGIN), loopback);
+    match allowed {
+        true => next.run(req).await,
+        false => StatusCode::FORBIDDEN.into_response(),
+    }
+}
+
+/// Host part of a `host[:port]` authority, lowercased
+fn host_name(authority: &str) -> String {
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    host.to_ascii_lowercase()
+}
+
+/// Requests a browser sends for another site are refused: an Origin, when present, must
+/// be the host the request was sent to, and a loopback server only answers to loopback
+/// host names, which stops DNS rebinding. Clients that aren't browsers send no Origin.
+fn is_allowed_origin(host: Option<&str>, origin: Option<&str>, loopback: bool) -> bool {
+    let Some(host) = host.map(host_name) else {
+        return false;
+    };
+    let is_loopback = |host: &str| LOOPBACK_HOSTS.contains(&host);
+    if loopback && !is_loopback(&host) {
+        return false;
+    }
+    let Some(origin) = origin else {
+        return true;
+    };
+    match origin.split_once("://") {
+        Some((_, authority)) => {
+            let origin = host_name(authority.trim_end_matches('/'));
+            origin == host || (loopback && is_loopback(&origin))
+        }
+        None => false,
+    }
+}
+
+fn is_authorized(header: Option<&str>, token: &str) -> bool {
+    let Some(provided) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    // constant-time comparison so the token can't be guessed byte by byte
+    provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer secreT"), "secret"));
+        assert!(!is_authorized(Some("Bearer secret2"), "secret"));
+        assert!(!is_authorized(Some("secret"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+    }
+
+    #[test]
+    fn test_is_allowed_origin() {
+        assert!(is_allowed_origin(Some("localhost:8080"), None, true));
+        assert!(is_allowed_origin(Some("127.0.0.1:8080"), None, true));
+        assert!(is_allowed_origin(Some("[::1]:8080"), None, true));
+        assert!(is_allowed_origin(
+            Some("localhost:8080"),
+            Some("http://127.0.0.1:3000"),
+            true
+        ));
+        // DNS rebinding: attacker.example resolves to 127.0.0.1
+        assert!(!is_allowed_origin(
+            Some("attacker.example:8080"),
+            Some("http://attacker.example:8080"),
+            true
+        ));
+        assert!(!is_allowed_origin(
+            Some("localhost:8080"),
+            Some("https://attacker.example"),
+            true
+        ));
+        assert!(!is_allowed_origin(
+            Some("localhost:8080"),
+            Some("null"),
+            true
+        ));
+        assert!(!is_allowed_origin(None, None, true));
+
+        assert!(is_allowed_origin(Some("mcp.example.com"), None, false));
+        assert!(is_allowed_origin(
+            Some("mcp.example.com"),
+            Some("https://mcp.example.com"),
+            false
+        ));
+        assert!(!is_allowed_origin(
+            Some("mcp.example.com"),
+            Some("https://attacker.example"),
+            false
+        ));
+    }
+
+    #[test]
+    fn test_check_requires_token_off_loopback() {
+        let options = |addr: &str, token: Option<&str>| HttpOptions {
+            addr: addr.parse().unwrap(),
+            bearer_token: token.map(str::to_string),
+        };
+        assert!(options("127.0.0.1:8080", None).check().is_ok());
+        assert!(options("0.0.0.0:8080", None).check().is_err());
+        assert!(options("0.0.0.0:8080", Some("secret")).check().is_ok());
+    }
+}
@@ -1,11 +1,25 @@
 pub mod config;
+pub mod config_schema;
+pub mod doctor;
 pub mod engine_guide;
 pub mod env;
+pub mod http;
+pub mod lint;
 pub mod paths;
+pub mod progress;
+pub mod prompts;
 pub mod providers;
+pub mod redaction;
+pub mod reload;
+pub mod resources;
+pub mod screenshot_daemon;
+pub mod search;
 pub mod session;
 pub mod mcp_helpers;
 pub mod state;
+pub mod telemetry;
 pub mod trajectory;
+pub mod trajectory_export;
+pub mod trajectory_replay;
 pub mod version_check;
 pub mod yell;
@@ -0,0 +1,296 @@
+//! Lint and format commands run in the validation sandbox, and parsing of their output
+//! into per-line diagnostics.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Packages of a scaffolded project that are linted and formatted, with the path passed
+/// to the linter relative to the package directory.
+pub const PACKAGES: &[(&str, &str)] = &[("client", "."), ("server", "src")];
+
+/// Files prettier rewrites, relative to a package directory
+const FORMAT_GLOB: &str = "src/**/*.{ts,tsx,css}";
+
+/// Extensions of the files linters fix
+const LINTED_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linter {
+    Eslint,
+    Biome,
+}
+
+impl Linter {
+    /// Biome when the package has a biome config, eslint otherwise (the template default).
+    pub fn detect(package_dir: &Path) -> Self {
+        match ["biome.json", "biome.jsonc"]
+            .iter()
+            .any(|config| package_dir.join(config).exists())
+        {
+            true => Linter::Biome,
+            false => Linter::Eslint,
+        }
+    }
+
+    pub fn command(&self, package: &str, target: &str, fix: bool) -> String {
+        let lint = match (self, fix) {
+            (Linter::Eslint, false) => format!("npx eslint --format json {}", target),
+            (Linter::Eslint, true) => format!("npx eslint --format json --fix {}", target),
+            (Linter::Biome, false) => {
+                format!("npx @biomejs/biome lint --reporter=github {}", target)
+            }
+            (Linter::Biome, true) => {
+                format!(
+                    "npx @biomejs/biome lint --reporter=github --write {}",
+                    target
+                )
+            }
+        };
+        format!("{} && {}", install_command(package), lint)
+    }
+
+    /// Parse linter stdout; `package` is prepended so paths are relative to the project.
+    pub fn parse(&self, package: &str, stdout: &str) -> Vec<Diagnostic> {
+        match self {
+            Linter::Eslint => parse_eslint(package, stdout),
+            Linter::Biome => parse_biome(package, stdout),
+        }
+    }
+}
+
+/// `check` only lists unformatted files, otherwise they are rewritten in place. Either
+/// way prettier prints the affected files one per line.
+pub fn format_command(package: &str, check: bool) -> String {
+    let mode = if check { "" } else { " --write" };
+    format!(
+        "{} && npx --yes prettier@3 --list-different{} \"{}\"",
+        install_command(package),
+        mode,
+        FORMAT_GLOB
+    )
+}
+
+/// Checksums of a package's lintable sources outside node_modules, one `<md5>  ./<path>`
+/// per line, to find the files a `--fix` run changed
+pub fn checksum_command(package: &str) -> String {
+    format!(
+        "cd /app/{} && find . -path ./node_modules -prune -o -type f \\( {} \\) -print0 | xargs -0 -r md5sum",
+        package,
+        LINTED_EXTENSIONS
+            .iter()
+            .map(|ext| format!("-name '*.{}'", ext))
+            .collect::<Vec<_>>()
+            .join(" -o ")
+    )
+}
+
+/// Files, relative to the project, that are new or differ between two
+/// [`checksum_command`] outputs
+pub fn changed_files(package: &str, before: &str, after: &str) -> Vec<String> {
+    let checksums = |output: &str| -> HashMap<String, String> {
+        output
+            .lines()
+            .filter_map(|line| line.split_once("  "))
+            .map(|(sum, path)| (path.to_string(), sum.to_string()))
+            .collect()
+    };
+    let before = checksums(before);
+    let mut changed: Vec<String> = checksums(after)
+        .into_iter()
+        .filter(|(path, sum)| before.get(path) != Some(sum))
+        .map(|(path, _)| project_path(package, &path))
+        .collect();
+    changed.sort();
+    changed
+}
+
+fn install_command(package: &str) -> String {
+    format!("cd /app/{} && npm install --silent > /dev/null", package)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Diagnostic {
+    /// Path relative to the project root, e.g. client/src/App.tsx
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub severity: Severity,
+    pub message: String,
+    pub rule: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn display(&self) -> String {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.rule {
+            Some(rule) => format!(
+                "{}:{}:{}: {} {} ({})",
+                self.file, self.line, self.column, severity, self.message, rule
+            ),
+            None => format!(
+                "{}:{}:{}: {} {}",
+                self.file, self.line, self.column, severity, self.message
+            ),
+        }
+    }
+}
+
+/// Map a path reported inside the sandbox (absolute under /app, or relative to the
+/// package) to a project-relative one.
+fn project_path(package: &str, file: &str) -> String {
+    match file.strip_prefix("/app/") {
+        Some(relative) => relative.to_string(),
+        None => format!("{}/{}", package, file.trim_start_matches("./")),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintFile {
+    file_path: String,
+    #[serde(default)]
+    messages: Vec<EslintMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    rule_id: Option<String>,
+    /// 1 = warning, 2 = error
+    severity: u8,
+    message: String,
+    #[serde(default)]
+    line: u32,
+    #[serde(default)]
+    column: u32,
+}
+
+fn parse_eslint(package: &str, stdout: &str) -> Vec<Diagnostic> {
+    // npm may print notices before the report, which is a single JSON array
+    let json = stdout.find('[').map(|start| &stdout[start..]).unwrap_or("");
+    let files: Vec<EslintFile> = serde_json::from_str(json.trim()).unwrap_or_default();
+    files
+        .into_iter()
+        .flat_map(|file| {
+            let path = project_path(package, &file.file_path);
+            file.messages.into_iter().map(move |message| Diagnostic {
+                file: path.clone(),
+                line: message.line,
+                column: message.column,
+                severity: match message.severity {
+                    2 => Severity::Error,
+                    _ => Severity::Warning,
+                },
+                message: message.message,
+                rule: message.rule_id,
+            })
+        })
+        .collect()
+}
+
+/// Parse GitHub annotation lines, e.g.
+/// `::error title=lint/style/useConst,file=src/a.ts,line=3,endLine=3,col=5,endColumn=8::message`
+fn parse_biome(package: &str, stdout: &str) -> Vec<Diagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let line = line.strip_prefix("::")?;
+            let (kind, rest) = line.split_once(' ')?;
+            let (properties, message) = rest.split_once("::")?;
+            let property = |name: &str| {
+                properties
+                    .split(',')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            };
+            Some(Diagnostic {
+                file: project_path(package, &property("file")?),
+                line: property("line")?.parse().ok()?,
+                column: property("col").and_then(|c| c.parse().ok()).unwrap_or(1),
+                severity: match kind {
+                    "error" => Severity::Error,
+                    _ => Severity::Warning,
+                },
+                message: message.to_string(),
+                rule: property("title"),
+            })
+        })
+        .collect()
+}
+
+/// Files listed by `prettier --list-different`, relative to the project.
+pub fn parse_prettier(package: &str, stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("src/"))
+        .map(|line| project_path(package, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_linter_output() {
+        let eslint = r#"npm notice new version available
+[{"filePath":"/app/client/src/App.tsx","messages":[
+  {"ruleId":"no-unused-vars","severity":2,"message":"'x' is unused.","line":4,"column":7},
+  {"ruleId":null,"severity":1,"message":"Parsing warning","line":9,"column":1}
+]},{"filePath":"/app/client/src/main.tsx","messages":[]}]"#;
+        let diagnostics = Linter::Eslint.parse("client", eslint);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].display(),
+            "client/src/App.tsx:4:7: error 'x' is unused. (no-unused-vars)"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+
+        let biome = "Checked 3 files\n\
+            ::error title=lint/style/useConst,file=src/index.ts,line=3,endLine=3,col=5,endColumn=8::This let is never reassigned\n";
+        let diagnostics = Linter::Biome.parse("server", biome);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                file: "server/src/index.ts".to_string(),
+                line: 3,
+                column: 5,
+                severity: Severity::Error,
+                message: "This let is never reassigned".to_string(),
+                rule: Some("lint/style/useConst".to_string()),
+            }]
+        );
+
+        assert_eq!(
+            parse_prettier("server", "src/index.ts\nsrc/db.ts\n"),
+            vec!["server/src/index.ts", "server/src/db.ts"]
+        );
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let before = "aaa  ./src/App.tsx\nbbb  ./vite.config.ts\nccc  ./src/main.tsx\n";
+        let after =
+            "aaa  ./src/App.tsx\nddd  ./vite.config.ts\nccc  ./src/main.tsx\neee  ./src/new.ts\n";
+        assert_eq!(
+            changed_files("client", before, after),
+            vec!["client/src/new.ts", "client/vite.config.ts"]
+        );
+        assert!(changed_files("client", before, before).is_empty());
+        assert!(checksum_command("server").starts_with("cd /app/server && find . "));
+    }
+}
@@ -1,14 +1,15 @@
 use clap::{Parser, Subcommand};
+use edda_integrations::{Notification, NotificationEvent, WebhookNotifier};
+use edda_mcp::doctor::{self, DoctorOptions};
+use edda_mcp::http::{HTTP_TOKEN_ENV, HttpOptions};
 use edda_mcp::paths;
-use edda_mcp::providers::{
-    CombinedProvider, DatabricksCliProvider, DatabricksRestProvider, DeploymentProvider,
-    GoogleSheetsProvider, IOProvider, ProviderType, WorkspaceTools,
-};
+use edda_mcp::providers::CombinedProvider;
+use edda_mcp::reload::{self, ReloadableProvider};
 use edda_mcp::session::SessionContext;
 use edda_mcp::trajectory::TrajectoryTrackingProvider;
+use edda_mcp::trajectory_export::{self, ExportFormat};
+use edda_mcp::trajectory_replay::{self, ReplayOptions, ReplayStatus};
 use edda_mcp::yell;
-use edda_sandbox::dagger::{ConnectOpts, Logger};
-use edda_sandbox::{DaggerSandbox, Sandbox};
 use eyre::Result;
 use rmcp::ServiceExt;
 use rmcp::transport::stdio;
@@ -30,6 +31,12 @@ struct Cli {
             "validation_docker_image",
             "screenshot_enabled",
             "screenshot_url",
+            "screenshot_routes",
+            "screenshot_viewports",
+            "screenshot_record_network",
+            "screenshot_accessibility_audit",
+            "screenshot_daemon",
+            "screenshot_recording",
             "screenshot_port",
             "screenshot_wait_time_ms",
         ]
@@ -44,7 +51,7 @@ struct Cli {
     #[arg(long = "with-workspace-tools")]
     with_workspace_tools: Option<bool>,
 
-    /// Override template (currently only supports 'Trpc', use --json for Custom)
+    /// Override template ('Trpc', 'Streamlit', 'FastApi' or 'Axum', use --json for Custom)
     #[arg(long = "template")]
     template: Option<String>,
 
@@ -64,6 +71,30 @@ struct Cli {
     #[arg(long = "screenshot.url")]
     screenshot_url: Option<String>,
 
+    /// Override extra screenshot routes (comma-separated URL paths)
+    #[arg(long = "screenshot.routes", value_delimiter = ',')]
+    screenshot_routes: Option<Vec<String>>,
+
+    /// Override screenshot viewports (comma-separated: desktop, tablet, mobile or a device name)
+    #[arg(long = "screenshot.viewports", value_delimiter = ',')]
+    screenshot_viewports: Option<Vec<edda_screenshot::Viewport>>,
+
+    /// Override whether requests are recorded to network.json during screenshots
+    #[arg(long = "screenshot.record_network")]
+    screenshot_record_network: Option<bool>,
+
+    /// Override whether captured pages are audited for accessibility
+    #[arg(long = "screenshot.accessibility_audit")]
+    screenshot_accessibility_audit: Option<bool>,
+
+    /// Override whether screenshots use a long-lived browser container
+    #[arg(long = "screenshot.daemon")]
+    screenshot_daemon: Option<bool>,
+
+    /// Override screenshot recording of the app loading (video or trace)
+    #[arg(long = "screenshot.recording")]
+    screenshot_recording: Option<edda_screenshot::Recording>,
+
     /// Override screenshot port
     #[arg(long = "screenshot.port")]
     screenshot_port: Option<u16>,
@@ -72,6 +103,23 @@ struct Cli {
     #[arg(long = "screenshot.wait_time_ms")]
     screenshot_wait_time_ms: Option<u64>,
 
+    /// Serve over streamable HTTP on this address (e.g. 127.0.0.1:8765) instead of stdio.
+    /// Set EDDA_MCP_HTTP_TOKEN to require `Authorization: Bearer <token>`
+    #[arg(long = "http", value_name = "ADDR")]
+    http: Option<std::net::SocketAddr>,
+
+    /// Continue a previous session (its project directory and trajectory) after a restart
+    #[arg(long = "resume", value_name = "SESSION_ID", conflicts_with = "http")]
+    resume: Option<String>,
+
+    /// Don't send usage metrics for this run, even if "telemetry" is enabled in the config
+    #[arg(long = "no-telemetry")]
+    no_telemetry: bool,
+
+    /// Use ~/.edda/config.<NAME>.json and ~/.edda/.env.<NAME> (default: $EDDA_PROFILE)
+    #[arg(long = "profile", value_name = "NAME", global = true)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -82,9 +130,86 @@ enum Commands {
     Yell {
         /// Bug description (optional, will prompt if not provided)
         message: Option<String>,
+        /// Attach the session's trajectory, log tail, validation output and project file
+        /// tree (redacted, asks for confirmation)
+        #[arg(long)]
+        attach: bool,
+        /// Session to attach, id or prefix (default: the most recent one)
+        #[arg(long, requires = "attach")]
+        session: Option<String>,
+        /// Lines from the end of the session log to attach
+        #[arg(long, default_value_t = 200, requires = "attach")]
+        log_lines: usize,
+        /// Attach without asking for confirmation
+        #[arg(long, short, requires = "attach")]
+        yes: bool,
+    },
+    /// Check environment configuration and prerequisites, optionally fixing problems
+    #[command(alias = "check")]
+    Doctor {
+        /// Offer to fix failed checks (pull images, create config files, enter credentials)
+        #[arg(long)]
+        fix: bool,
+        /// Apply fixes without asking
+        #[arg(long, short, requires = "fix")]
+        yes: bool,
+    },
+    /// Inspect recorded session trajectories (~/.edda/history.jsonl)
+    Trajectory {
+        #[command(subcommand)]
+        command: TrajectoryCommands,
+    },
+    /// Inspect ~/.edda/config.json
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the config file, validated and with defaults filled in
+    Show {
+        /// Print the config the server would run with, after --json or the override flags
+        #[arg(long)]
+        effective: bool,
+    },
+    /// Print the JSON Schema of the config file, e.g. for editor completion
+    Schema,
+    /// List the profiles with a config file in ~/.edda
+    Profiles,
+}
+
+#[derive(Subcommand)]
+enum TrajectoryCommands {
+    /// Export a session's tool calls, results, screenshots and timings as a report
+    Export {
+        /// Session id, or a unique prefix of it (as in the log file name)
+        session: String,
+        #[arg(long, value_enum, default_value = "html")]
+        format: ExportFormat,
+        /// Output file (default: ./edda-<session>.<ext>)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Re-run a recorded session's tool calls against this binary and report differences
+    Replay {
+        /// History file to replay (e.g. a copy of ~/.edda/history.jsonl)
+        file: std::path::PathBuf,
+        /// Session id or prefix, required when the file holds several sessions
+        #[arg(long)]
+        session: Option<String>,
+        /// Only check that the recorded tools still exist, don't call them
+        #[arg(long)]
+        dry_run: bool,
+        /// Directory to recreate the recorded projects in (default: a temp dir)
+        #[arg(long)]
+        work_dir: Option<std::path::PathBuf>,
+        /// Also replay tools that may act outside the work directory, e.g. deploys, SQL
+        /// statements and bash commands
+        #[arg(long)]
+        allow_side_effects: bool,
     },
-    /// Check environment configuration and prerequisites
-    Check,
 }
 
 /// Build config overrides from CLI flags
@@ -98,9 +223,12 @@ fn build_overrides_from_cli(cli: &Cli) -> Result<edda_mcp::config::ConfigOverrid
     let template = if let Some(template_str) = &cli.template {
         match template_str.as_str() {
             "Trpc" => Some(TemplateConfig::Trpc),
+            "Streamlit" => Some(TemplateConfig::Streamlit),
+            "FastApi" => Some(TemplateConfig::FastApi),
+            "Axum" => Some(TemplateConfig::Axum),
             _ => {
                 return Err(eyre::eyre!(
-                    "Invalid template '{}'. Only 'Trpc' is supported via CLI. Use --json for Custom templates.",
+                    "Invalid template '{}'. Only 'Trpc', 'Streamlit', 'FastApi' and 'Axum' are supported via CLI. Use --json for Custom templates.",
                     template_str
                 ));
             }
@@ -122,12 +250,24 @@ fn build_overrides_from_cli(cli: &Cli) -> Result<edda_mcp::config::ConfigOverrid
     // build screenshot overrides if any field is provided
     let screenshot = if cli.screenshot_enabled.is_some()
         || cli.screenshot_url.is_some()
+        || cli.screenshot_routes.is_some()
+        || cli.screenshot_viewports.is_some()
+        || cli.screenshot_record_network.is_some()
+        || cli.screenshot_accessibility_audit.is_some()
+        || cli.screenshot_daemon.is_some()
+        || cli.screenshot_recording.is_some()
         || cli.screenshot_port.is_some()
         || cli.screenshot_wait_time_ms.is_some()
     {
         Some(ScreenshotConfigOverrides {
             enabled: cli.screenshot_enabled,
             url: cli.screenshot_url.clone(),
+            routes: cli.screenshot_routes.clone(),
+            viewports: cli.screenshot_viewports.clone(),
+            record_network: cli.screenshot_record_network,
+            accessibility_audit: cli.screenshot_accessibility_audit,
+            daemon: cli.screenshot_daemon,
+            recording: cli.screenshot_recording,
             port: cli.screenshot_port,
             wait_time_ms: cli.screenshot_wait_time_ms,
         })
@@ -159,8 +299,11 @@ fn load_config_with_overrides(cli: &Cli) -> Result<edda_mcp::config::Config> {
 
     // Mode 1: JSON replacement
     if let Some(json_str) = &cli.json {
-        let config: edda_mcp::config::Config = serde_json::from_str(json_str)
+        let mut config = edda_mcp::config::Config::parse(json_str)
             .map_err(|e| eyre::eyre!("Failed to parse --json config: {}", e))?;
+        if cli.no_telemetry {
+            config.telemetry = None;
+        }
         return Ok(config);
     }
 
@@ -175,203 +318,197 @@ fn load_config_with_overrides(cli: &Cli) -> Result<edda_mcp::config::Config> {
             .required_providers
             .retain(|p| !matches!(p, edda_mcp::providers::ProviderType::Deployment));
     }
-
-    Ok(config)
-}
-
-/// check if docker is available by running 'docker ps'
-async fn check_docker_available() -> Result<()> {
-    let output = tokio::process::Command::new("docker")
-        .arg("ps")
-        .output()
-        .await;
-
-    match output {
-        Ok(output) if output.status.success() => Ok(()),
-        Ok(_) => Err(eyre::eyre!(
-            "docker command found but not responding (is the daemon running?)"
-        )),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            Err(eyre::eyre!("docker command not found"))
-        }
-        Err(e) => Err(eyre::eyre!("failed to check docker: {}", e)),
+    if cli.no_telemetry {
+        config.telemetry = None;
     }
-}
-
-/// warmup sandbox by pre-pulling node image and creating a test container
-async fn warmup_sandbox() -> Result<()> {
-    let opts = ConnectOpts::default()
-        .with_logger(Logger::Silent)
-        .with_execute_timeout(Some(600));
-
-    opts.connect(|client| async move {
-        let container = client
-            .container()
-            .from("node:20-alpine3.22")
-            .with_exec(vec!["mkdir", "-p", "/app"]);
-        let sandbox = DaggerSandbox::from_container(container, client);
-        // force evaluation to ensure image is pulled
-        let _ = sandbox.list_directory("/app").await?;
-        Ok(())
-    })
-    .await
-    .map_err(|e| eyre::eyre!("dagger connect failed: {}", e))?;
 
-    Ok(())
-}
-
-/// helper to check if provider should be enabled based on config
-fn should_enable_provider(config: &edda_mcp::config::Config, provider: ProviderType) -> bool {
-    config.required_providers.contains(&provider)
-}
-
-/// helper to check if DatabricksRest provider should be enabled (checks both DatabricksRest and legacy Databricks)
-fn should_enable_databricks_rest(config: &edda_mcp::config::Config) -> bool {
-    config.required_providers.contains(&ProviderType::DatabricksRest)
-}
-
-/// helper to check if DatabricksCli provider should be enabled
-fn should_enable_databricks_cli(config: &edda_mcp::config::Config) -> bool {
-    config.required_providers.contains(&ProviderType::DatabricksCli)
+    Ok(config)
 }
 
-/// check environment configuration and prerequisites
-async fn check_environment(config: &edda_mcp::config::Config) -> Result<()> {
-    use edda_mcp::providers::ProviderType;
-
-    println!("🔍 Checking environment configuration...\n");
-
-    let mut all_passed = true;
-
-    // load env vars for validation
-    edda_mcp::env::create_env_example()?;
-    let env = edda_mcp::env::EnvVars::load()?;
-
-    // check docker
-    print!("  Docker availability... ");
-    match check_docker_available().await {
-        Ok(_) => println!("✓"),
-        Err(e) => {
-            println!("✗\n    Error: {}", e);
-            all_passed = false;
-        }
-    }
-
-    // check databricks environment variables only if databricks rest or deployment is required
-    let databricks_required = config
-        .required_providers
-        .contains(&ProviderType::DatabricksRest)
-        || config
-            .required_providers
-            .contains(&ProviderType::Deployment);
-
-    if databricks_required {
-        print!("  Databricks credentials... ");
-        let require_warehouse = config
-            .required_providers
-            .contains(&ProviderType::Deployment);
-        match env.validate_databricks(require_warehouse) {
-            Ok(_) => {
-                println!("✓");
-                // show which env vars were found
-                if let Some(host) = env.databricks_host() {
-                    println!("    DATABRICKS_HOST: {}", host);
-                }
-                println!("    DATABRICKS_TOKEN: [set]");
-                if require_warehouse {
-                    if let Some(warehouse_id) = env.databricks_warehouse_id() {
-                        println!("    DATABRICKS_WAREHOUSE_ID: {}", warehouse_id);
-                    }
-                }
-            }
-            Err(e) => {
-                println!("✗\n    Error: {}", e);
-                all_passed = false;
-            }
-        }
-
-        // check databricks CLI (optional, only if deployment is required)
-        if config
-            .required_providers
-            .contains(&ProviderType::Deployment)
-        {
-            print!("  Databricks CLI... ");
-            match tokio::process::Command::new("databricks")
-                .arg("--version")
-                .output()
-                .await
-            {
-                Ok(output) if output.status.success() => println!("✓"),
-                _ => println!("⚠ (optional, needed for deployment)"),
-            }
-        }
-    }
-
-    // sandbox warmup (only if docker is available)
-    if check_docker_available().await.is_ok() {
-        println!("\n  Sandbox warmup started. It may take a while on first run...");
-        match warmup_sandbox().await {
-            Ok(_) => println!("  Sandbox warmup complete ✓"),
-            Err(e) => {
-                println!("  Sandbox warmup failed ✗\n    Error: {}", e);
-                all_passed = false;
-            }
-        }
-    }
-
-    println!();
-
-    if all_passed {
-        println!("✅ All checks passed!");
-        Ok(())
-    } else {
-        println!("❌ Some checks failed. Please review the errors above.");
-        Err(eyre::eyre!("Environment check failed"))
+/// tell the configured webhook that a bug report bundle was created
+async fn notify_yell(bundle_path: &std::path::Path, message: Option<&str>) {
+    let notifier = edda_mcp::config::Config::load_from_dir()
+        .ok()
+        .and_then(|config| config.notifications)
+        .and_then(|notifications| WebhookNotifier::from_config(&notifications));
+    let Some(notifier) = notifier else {
+        return;
+    };
+    let notification = Notification::new(
+        NotificationEvent::YellReport,
+        "Bug report created",
+        &format!("{}\n{}", message.unwrap_or_default(), bundle_path.display()),
+    );
+    if let Err(e) = notifier.notify(&notification).await {
+        eprintln!("⚠️  Failed to send notification: {}", e);
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    // the profile decides which config and env files are read
+    if let Some(profile) = &cli.profile {
+        paths::set_profile(profile)?;
+    }
+
     // load environment variables early (before any other initialization)
     // this ensures .env.example is created and env vars are available
     edda_mcp::env::create_env_example()?;
     let _env = edda_mcp::env::EnvVars::load()?;
 
-    let cli = Cli::parse();
-
     match cli.command {
-        Some(Commands::Yell { message }) => yell::run_yell(message),
-        Some(Commands::Check) => {
-            let config = load_config_with_overrides(&cli)?;
-            check_environment(&config).await
+        Some(Commands::Yell {
+            message,
+            attach,
+            session,
+            log_lines,
+            yes,
+        }) => {
+            let attach = attach.then_some(yell::AttachOptions {
+                session,
+                log_lines,
+                yes,
+            });
+            let bundle_path = yell::run_yell(message.clone(), attach)?;
+            notify_yell(&bundle_path, message.as_deref()).await;
+            Ok(())
+        }
+        Some(Commands::Doctor { fix, yes }) => {
+            // a broken config.json is one of the things doctor reports and fixes
+            let config = load_config_with_overrides(&cli).unwrap_or_else(|e| {
+                eprintln!(
+                    "⚠️  Failed to load config ({}), checking with defaults\n",
+                    e
+                );
+                edda_mcp::config::Config::default()
+            });
+            doctor::run(&config, &DoctorOptions { fix, yes }).await
+        }
+        // matched to the leaf so nothing is moved out of `cli`, which is still borrowed
+        Some(Commands::Config {
+            command: ConfigCommands::Show { effective },
+        }) => {
+            let config = match effective {
+                true => load_config_with_overrides(&cli)?,
+                false => edda_mcp::config::Config::load_from_dir()?,
+            };
+            println!("{}", serde_json::to_string_pretty(&config)?);
+            Ok(())
+        }
+        Some(Commands::Config {
+            command: ConfigCommands::Schema,
+        }) => {
+            let schema = edda_mcp::config_schema::schema();
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(())
         }
+        Some(Commands::Config {
+            command: ConfigCommands::Profiles,
+        }) => {
+            let selected = paths::profile()?;
+            for name in paths::profiles()? {
+                let marker = match selected.as_ref() == Some(&name) {
+                    true => "*",
+                    false => " ",
+                };
+                println!("{} {}", marker, name);
+            }
+            Ok(())
+        }
+        Some(Commands::Trajectory { command }) => match command {
+            TrajectoryCommands::Export {
+                session,
+                format,
+                output,
+            } => {
+                let path = trajectory_export::export(
+                    &paths::trajectory_path()?,
+                    &session,
+                    format,
+                    output,
+                )?;
+                println!("Exported session report to {}", path.display());
+                Ok(())
+            }
+            TrajectoryCommands::Replay {
+                file,
+                session,
+                dry_run,
+                work_dir,
+                allow_side_effects,
+            } => {
+                let options = ReplayOptions {
+                    file,
+                    session,
+                    dry_run,
+                    work_dir,
+                    allow_side_effects,
+                };
+                let outcomes = trajectory_replay::replay(&options).await?;
+                for (idx, outcome) in outcomes.iter().enumerate() {
+                    println!("{}", outcome.display(idx + 1));
+                }
+                let regressions = outcomes
+                    .iter()
+                    .filter(|o| matches!(o.status, ReplayStatus::Regressed { .. }))
+                    .count();
+                if regressions > 0 {
+                    eyre::bail!("{} of {} calls regressed", regressions, outcomes.len());
+                }
+                println!("Replayed {} calls, no regressions", outcomes.len());
+                Ok(())
+            }
+        },
         None => {
             let config = load_config_with_overrides(&cli)?;
-            run_server(config).await
+            let http = cli.http.map(|addr| HttpOptions {
+                addr,
+                bearer_token: std::env::var(HTTP_TOKEN_ENV).ok().filter(|t| !t.is_empty()),
+            });
+            let resume = cli.resume.clone();
+            // a --json config isn't read from the config file, so there's nothing to watch
+            let updates = match (&cli.json, paths::config_path()) {
+                (None, Ok(path)) => {
+                    reload::watch_config(path, move || load_config_with_overrides(&cli))
+                }
+                _ => reload::disabled(),
+            };
+            run_server(config, http, resume, updates).await
         }
     }
 }
 
-async fn run_server(config: edda_mcp::config::Config) -> Result<()> {
+async fn run_server(
+    config: edda_mcp::config::Config,
+    http: Option<HttpOptions>,
+    resume: Option<String>,
+    updates: reload::ProviderUpdates,
+) -> Result<()> {
     // detect if running as binary (not via cargo run)
     let is_binary = std::env::var("CARGO").is_err();
 
+    if let Some(options) = &http {
+        options.check()?;
+    }
+
+    // restore the resumed session before anything else, so a bad id fails fast
+    let resumed_ctx = resume.as_deref().map(SessionContext::resume).transpose()?;
+
     // generate session ID for binary mode (used for both logs and trajectory tracking)
-    let session_id = match is_binary {
-        true => Some(Uuid::new_v4().to_string()),
-        false => None,
+    let session_id = match (&resume, is_binary) {
+        (Some(session_id), _) => Some(session_id.clone()),
+        (None, true) => Some(Uuid::new_v4().to_string()),
+        (None, false) => None,
     };
 
     // configure tracing: enabled by default for binary builds, opt-in for cargo run
     let log_path = match (&session_id, std::env::var("RUST_LOG").is_ok()) {
         (Some(session_id), _) => {
             // binary mode: write to session file by default
-            let session_short = &session_id[..8];
-
-            let log_dir = paths::session_log_dir();
-            std::fs::create_dir_all(&log_dir)?;
+            std::fs::create_dir_all(paths::session_log_dir())?;
 
-            let log_path_buf = log_dir.join(format!("session-{}.log", session_short));
+            let log_path_buf = paths::session_log_path(session_id);
 
             let log_file = std::fs::OpenOptions::new()
                 .create(true)
@@ -397,7 +534,7 @@ async fn run_server(config: edda_mcp::config::Config) -> Result<()> {
     };
 
     // check if docker is available before initializing providers
-    let docker_available = check_docker_available().await.is_ok();
+    let docker_available = doctor::check_docker_available().await.is_ok();
     if !docker_available {
         eprintln!(
             "⚠️  Warning: docker not available - you may have issues with sandbox operations\n"
@@ -411,97 +548,84 @@ async fn run_server(config: edda_mcp::config::Config) -> Result<()> {
         }
     });
 
-    // initialize all available providers
-    let databricks = match should_enable_databricks_rest(&config) {
-        true => DatabricksRestProvider::new().ok(),
-        false => None,
-    };
-
-    // enable DatabricksCli provider if explicitly requested in config
-    let databricks_cli = match should_enable_databricks_cli(&config) {
-        true => DatabricksCliProvider::new().ok(),
-        false => None,
-    };
-
-    let deployment = match config.with_deployment {
-        true => DeploymentProvider::new().ok(),
-        false => None,
-    };
-    let google_sheets = match should_enable_provider(&config, ProviderType::GoogleSheets) {
-        true => GoogleSheetsProvider::new().await.ok(),
-        false => None,
-    };
-    let io = IOProvider::new(config.io_config.clone()).ok();
-
     // create session context (session_id populated earlier)
-    let session_ctx = SessionContext::new(session_id.clone());
-
-    let workspace = match config.with_workspace_tools {
-        true => WorkspaceTools::new(session_ctx.clone()).ok(),
-        false => None,
+    let session_ctx = match resumed_ctx {
+        Some(ctx) => ctx,
+        None => SessionContext::new(session_id.clone()),
     };
 
+    // create combined provider with all available integrations
+    let provider = CombinedProvider::from_config(session_ctx, &config).await?;
+
     // print startup banner to stderr (won't interfere with stdio MCP transport)
-    let mut providers_list = Vec::new();
-    if databricks.is_some() {
-        providers_list.push("Databricks");
-    }
-    if databricks_cli.is_some() {
-        providers_list.push("Databricks CLI");
-    }
-    if deployment.is_some() {
-        providers_list.push("Deployment");
-    }
-    if google_sheets.is_some() {
-        providers_list.push("Google Sheets");
-    }
-    if config.with_deployment && io.is_some() {
-        providers_list.push("I/O");
-    }
-    if workspace.is_some() {
-        providers_list.push("Workspace");
-    }
+    let providers_list: Vec<&str> = provider
+        .provider_names()
+        .into_iter()
+        .filter(|name| *name != "I/O" || config.with_deployment)
+        .collect();
 
     let log_info = match &log_path {
         Some(path) => format!("\n Logs: {}", path),
         None => String::new(),
     };
+    let profile_info = match paths::profile()? {
+        Some(profile) => format!("\n Profile: {}", profile),
+        None => String::new(),
+    };
+    // opted in, but the user should still see where metrics go
+    let telemetry_endpoint = config
+        .telemetry
+        .as_ref()
+        .filter(|telemetry| telemetry.enabled)
+        .and_then(|telemetry| telemetry.endpoint.as_deref());
+    let telemetry_info = match telemetry_endpoint {
+        Some(endpoint) => format!(
+            "\n Telemetry: sending usage metrics to {} (disable with --no-telemetry)",
+            endpoint
+        ),
+        None => String::new(),
+    };
+    let resume_info = match (&session_id, &resume) {
+        (Some(session_id), Some(_)) => format!("\n Resumed session {}", session_id),
+        (Some(session_id), None) => format!("\n Resume with: edda_mcp --resume {}", session_id),
+        (None, _) => String::new(),
+    };
+
+    let transport_info = match &http {
+        Some(options) => format!(
+            "Server running on http://{}{}",
+            options.addr,
+            edda_mcp::http::MCP_PATH
+        ),
+        None => "Server running on stdio transport...".to_string(),
+    };
 
     eprintln!(
         "🚀 Edda MCP Server v{} - build data apps deployable on Databricks Apps platform \n\
          Configured providers: {}\n\
-         Got questions? eng-appbuild@databricks.com{}\n\
-         Server running on stdio transport...",
+         Got questions? eng-appbuild@databricks.com{}{}{}{}\n\
+         {}",
         env!("CARGO_PKG_VERSION"),
         providers_list.join(", "),
-        log_info
+        profile_info,
+        log_info,
+        telemetry_info,
+        resume_info,
+        transport_info
     );
 
-    // create combined provider with all available integrations
-    let provider = CombinedProvider::new(
-        session_ctx,
-        databricks,
-        databricks_cli,
-        deployment,
-        google_sheets,
-        io,
-        workspace,
-        &config,
-    )
-    .map_err(|_| {
-        eyre::eyre!(
-            "No integrations available. Configure at least one:\n\
-             - Databricks: Set DATABRICKS_HOST and DATABRICKS_TOKEN\n\
-             - Deployment: Set DATABRICKS_HOST and DATABRICKS_TOKEN)\n\
-             - Google Sheets: Place credentials at ~/.config/gspread/credentials.json\n\
-             - I/O: Always available (includes Workspace tools)"
-        )
-    })?;
-
     provider
         .check_availability(&config.required_providers)
         .map_err(|e| eyre::eyre!(e))?;
 
+    if let Some(options) = http {
+        let result = serve_http(provider, updates, session_id.is_some(), config, options).await;
+        edda_mcp::telemetry::flush_all().await;
+        return result;
+    }
+
+    let provider = ReloadableProvider::new(provider, updates);
+
     // wrap with trajectory tracking in binary mode
     match session_id {
         Some(session_id) => {
@@ -514,6 +638,40 @@ async fn run_server(config: edda_mcp::config::Config) -> Result<()> {
             service.waiting().await?;
         }
     }
+    edda_mcp::telemetry::flush_all().await;
 
     Ok(())
 }
+
+/// serve over HTTP, giving every client session its own session context (and trajectory in binary mode)
+async fn serve_http(
+    provider: CombinedProvider,
+    updates: reload::ProviderUpdates,
+    track_trajectory: bool,
+    config: edda_mcp::config::Config,
+    options: HttpOptions,
+) -> Result<()> {
+    match track_trajectory {
+        true => {
+            edda_mcp::http::serve(&options, move || {
+                let session_id = Uuid::new_v4().to_string();
+                let provider = ReloadableProvider::new(
+                    provider.with_session(SessionContext::new(Some(session_id.clone()))),
+                    updates.clone(),
+                );
+                TrajectoryTrackingProvider::new(provider, session_id, config.clone())
+                    .map_err(|e| std::io::Error::other(e.to_string()))
+            })
+            .await
+        }
+        false => {
+            edda_mcp::http::serve(&options, move || {
+                Ok(ReloadableProvider::new(
+                    provider.with_session(SessionContext::new(None)),
+                    updates.clone(),
+                ))
+            })
+            .await
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use rmcp::model::{Implementation, ProtocolVersion, ServerCapabilities, ServerInfo};
+use edda_integrations::ToolResultDisplay;
+use rmcp::model::{
+    CallToolResult, Content, Implementation, ProtocolVersion, ServerCapabilities, ServerInfo,
+};
+use serde::Serialize;
 
 /// Helper to create generic ServerInfo for internal providers.
 /// These are only used internally by CombinedProvider for routing.
@@ -17,3 +21,18 @@ pub fn internal_server_info() -> ServerInfo {
         instructions: None,
     }
 }
+
+/// Tool result with the human-readable text as content and the serialized result as
+/// structured content, which CombinedProvider only passes on in JSON output mode.
+pub fn structured_result<T: ToolResultDisplay + Serialize>(
+    result: &T,
+    success: bool,
+) -> CallToolResult {
+    let content = vec![Content::text(result.display())];
+    let mut call_result = match success {
+        true => CallToolResult::success(content),
+        false => CallToolResult::error(content),
+    };
+    call_result.structured_content = serde_json::to_value(result).ok();
+    call_result
+}
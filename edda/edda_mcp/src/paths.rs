@@ -1,9 +1,18 @@
 use eyre::Result;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 pub const SESSION_LOG_DIR: &str = "/tmp/edda-mcp";
 pub const EDDA_DIR: &str = ".edda";
+pub const CONFIG_FILE: &str = "config.json";
 pub const HISTORY_FILE: &str = "history.jsonl";
+pub const SESSIONS_DIR: &str = "sessions";
+pub const TEMPLATES_DIR: &str = "templates";
+pub const ENV_FILE: &str = ".env";
+/// Selects a config profile when --profile isn't given
+pub const PROFILE_ENV: &str = "EDDA_PROFILE";
+
+static PROFILE: OnceLock<String> = OnceLock::new();
 
 /// get the edda directory path (~/.edda)
 pub fn edda_dir() -> Result<PathBuf> {
@@ -12,12 +21,101 @@ pub fn edda_dir() -> Result<PathBuf> {
     Ok(home.join(EDDA_DIR))
 }
 
+/// select the config profile for this process (--profile), before any config is loaded.
+/// It takes precedence over EDDA_PROFILE.
+pub fn set_profile(name: &str) -> Result<()> {
+    check_profile_name(name)?;
+    PROFILE
+        .set(name.to_string())
+        .map_err(|_| eyre::eyre!("a config profile is already selected"))
+}
+
+/// the selected config profile (--profile or EDDA_PROFILE), None for the default config
+pub fn profile() -> Result<Option<String>> {
+    if let Some(name) = PROFILE.get() {
+        return Ok(Some(name.clone()));
+    }
+    match std::env::var(PROFILE_ENV) {
+        Ok(name) if !name.is_empty() => {
+            check_profile_name(&name)?;
+            Ok(Some(name))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn check_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    match valid {
+        true => Ok(()),
+        false => Err(eyre::eyre!(
+            "invalid profile name '{}', use letters, digits, '-' and '_'",
+            name
+        )),
+    }
+}
+
+/// get the config file path (~/.edda/config.json, or ~/.edda/config.<profile>.json when a
+/// profile is selected)
+pub fn config_path() -> Result<PathBuf> {
+    let file = match profile()? {
+        Some(name) => format!("config.{}.json", name),
+        None => CONFIG_FILE.to_string(),
+    };
+    Ok(edda_dir()?.join(file))
+}
+
+/// get the env file of the selected profile (~/.edda/.env.<profile>), loaded before ~/.edda/.env
+pub fn profile_env_path() -> Result<Option<PathBuf>> {
+    match profile()? {
+        Some(name) => Ok(Some(edda_dir()?.join(format!("{}.{}", ENV_FILE, name)))),
+        None => Ok(None),
+    }
+}
+
+/// names of the profiles with a config file in ~/.edda
+pub fn profiles() -> Result<Vec<String>> {
+    let mut names: Vec<String> = match std::fs::read_dir(edda_dir()?) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file = entry.file_name().into_string().ok()?;
+                let name = file.strip_prefix("config.")?.strip_suffix(".json")?;
+                check_profile_name(name).ok()?;
+                Some(name.to_string())
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    Ok(names)
+}
+
 /// get the trajectory history file path (~/.edda/history.jsonl)
 pub fn trajectory_path() -> Result<PathBuf> {
     Ok(edda_dir()?.join(HISTORY_FILE))
 }
 
+/// get the directory holding resumable session state (~/.edda/sessions)
+pub fn session_dir() -> Result<PathBuf> {
+    Ok(edda_dir()?.join(SESSIONS_DIR))
+}
+
+/// get the directory caching git and OCI templates (~/.edda/templates)
+pub fn template_cache_dir() -> Result<PathBuf> {
+    Ok(edda_dir()?.join(TEMPLATES_DIR))
+}
+
 /// get the session log directory path (/tmp/edda-mcp)
 pub fn session_log_dir() -> PathBuf {
     PathBuf::from(SESSION_LOG_DIR)
 }
+
+/// get the log file path for a session (/tmp/edda-mcp/session-<id prefix>.log)
+pub fn session_log_path(session_id: &str) -> PathBuf {
+    let session_short = session_id.get(..8).unwrap_or(session_id);
+    session_log_dir().join(format!("session-{}.log", session_short))
+}
@@ -0,0 +1,53 @@
+//! MCP progress notifications for long-running tools (validation, deployment, scaffolding).
+
+use rmcp::Peer;
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::{RequestContext, RoleServer};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Reports staged progress to the client. Only sends notifications when the request
+/// carried a progress token, otherwise stages are just logged.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    target: Option<(Peer<RoleServer>, ProgressToken)>,
+    total: Option<u32>,
+    step: Arc<AtomicU32>,
+}
+
+impl ProgressReporter {
+    /// `total` is the number of stages when known up front.
+    pub fn new(context: &RequestContext<RoleServer>, total: Option<u32>) -> Self {
+        Self {
+            target: context
+                .meta
+                .get_progress_token()
+                .map(|token| (context.peer.clone(), token)),
+            total,
+            step: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    /// Advance to the next stage.
+    pub async fn step(&self, message: impl Into<String>) {
+        let step = self.step.fetch_add(1, Ordering::SeqCst) + 1;
+        let message = message.into();
+        match self.total {
+            Some(total) => tracing::info!("[{}/{}] {}", step, total, message),
+            None => tracing::info!("[{}] {}", step, message),
+        }
+
+        let Some((peer, token)) = &self.target else {
+            return;
+        };
+        let param = ProgressNotificationParam {
+            progress_token: token.clone(),
+            progress: step as f64,
+            total: self.total.map(f64::from),
+            message: Some(message),
+        };
+        if let Err(e) = peer.notify_progress(param).await {
+            tracing::warn!("Failed to send progress notification: {}", e);
+        }
+    }
+}
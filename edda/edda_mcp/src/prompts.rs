@@ -0,0 +1,198 @@
+//! MCP prompts: parameterized starting points that IDE clients can offer as one-click
+//! tasks. Each recipe spells out the tool call sequence so the agent doesn't have to
+//! discover the workflow on its own.
+
+use rmcp::ErrorData;
+use rmcp::model::{
+    GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage, PromptMessageRole,
+};
+
+struct Recipe {
+    name: &'static str,
+    description: &'static str,
+    /// (name, description, required)
+    arguments: &'static [(&'static str, &'static str, bool)],
+    /// Prompt text, `{argument}` placeholders are replaced with the argument values
+    template: &'static str,
+}
+
+const RECIPES: &[Recipe] = &[
+    Recipe {
+        name: "build_dashboard",
+        description: "Build and validate a data app dashboard on top of a Databricks table",
+        arguments: &[
+            (
+                "table",
+                "Fully qualified table name (catalog.schema.table)",
+                true,
+            ),
+            ("focus", "What the dashboard should help answer", false),
+        ],
+        template: r#"Build a dashboard data app for the Databricks table `{table}`.
+Focus: {focus}
+
+Steps:
+1. Call `databricks_describe_table` for `{table}` to learn the columns and look at sample rows.
+2. Call `scaffold_data_app` with a new work_dir for the project.
+3. Implement the backend queries and the dashboard UI, with tests next to the code.
+4. Call `validate_data_app` and fix every issue until it passes.
+5. Summarize what was built; do not deploy unless asked."#,
+    },
+    Recipe {
+        name: "build_app_from_sheet",
+        description: "Build a data app on top of a Google Sheets spreadsheet",
+        arguments: &[
+            (
+                "spreadsheet_id",
+                "Google Sheets spreadsheet ID or URL",
+                true,
+            ),
+            ("focus", "What the app should help answer", false),
+        ],
+        template: r#"Build a data app on top of the Google Sheets spreadsheet `{spreadsheet_id}`.
+Focus: {focus}
+
+Steps:
+1. Call `google_sheets_get_metadata` and `google_sheets_read_range` to understand the sheets and their columns.
+2. Call `scaffold_data_app` with a new work_dir for the project.
+3. Implement the app, with tests next to the code.
+4. Call `validate_data_app` and fix every issue until it passes."#,
+    },
+    Recipe {
+        name: "add_auth",
+        description: "Add user authentication and per-user authorization to an existing app",
+        arguments: &[
+            ("work_dir", "Absolute path to the app project", true),
+            ("rules", "Who should be able to see or change what", false),
+        ],
+        template: r#"Add authentication to the app in `{work_dir}`.
+Access rules: {rules}
+
+Steps:
+1. Use `read_file`, `grep` and `glob` to find the server entry point and the API routes.
+2. Read the current user from the `X-Forwarded-Email` and `X-Forwarded-Access-Token` headers that Databricks Apps sets, and reject requests without them.
+3. Enforce the access rules in the backend, not only in the UI.
+4. Add tests covering allowed and rejected requests.
+5. Call `validate_data_app` and fix every issue until it passes."#,
+    },
+    Recipe {
+        name: "fix_validation",
+        description: "Make a failing app pass validation",
+        arguments: &[("work_dir", "Absolute path to the app project", true)],
+        template: r#"The app in `{work_dir}` does not pass validation.
+
+Steps:
+1. Call `validate_data_app` to get the current errors.
+2. Fix the root cause of each error with `edit_file`; do not delete or skip tests to make them pass.
+3. Repeat until `validate_data_app` passes, then summarize the fixes."#,
+    },
+    Recipe {
+        name: "deploy_app",
+        description: "Validate and deploy an app to Databricks Apps",
+        arguments: &[
+            ("work_dir", "Absolute path to the app project", true),
+            (
+                "name",
+                "Databricks app name (lowercase letters, numbers and dashes)",
+                true,
+            ),
+        ],
+        template: r#"Deploy the app in `{work_dir}` to Databricks Apps as `{name}`.
+
+Steps:
+1. Call `validate_data_app`; fix any issue until it passes.
+2. Call `deploy_databricks_app` with name `{name}`.
+3. If the deployment fails, call `get_deployment_logs`, fix the problem, validate again and redeploy.
+4. Report the app URL."#,
+    },
+];
+
+fn find(name: &str) -> Option<&'static Recipe> {
+    RECIPES.iter().find(|recipe| recipe.name == name)
+}
+
+pub fn list_prompts() -> Vec<Prompt> {
+    RECIPES
+        .iter()
+        .map(|recipe| {
+            let arguments = recipe
+                .arguments
+                .iter()
+                .map(|(name, description, required)| PromptArgument {
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    required: Some(*required),
+                })
+                .collect();
+            Prompt::new(recipe.name, Some(recipe.description), Some(arguments))
+        })
+        .collect()
+}
+
+pub fn get_prompt(
+    name: &str,
+    arguments: Option<&JsonObject>,
+) -> Result<GetPromptResult, ErrorData> {
+    let recipe = find(name)
+        .ok_or_else(|| ErrorData::invalid_params(format!("Unknown prompt: {}", name), None))?;
+
+    let mut text = recipe.template.to_string();
+    for (arg, _, required) in recipe.arguments {
+        let value = arguments
+            .and_then(|args| args.get(*arg))
+            .map(|value| match value {
+                serde_json::Value::String(s) => s.trim().to_string(),
+                other => other.to_string(),
+            })
+            .filter(|value| !value.is_empty());
+        let value = match (value, required) {
+            (Some(value), _) => value,
+            (None, true) => {
+                return Err(ErrorData::invalid_params(
+                    format!("Missing required argument '{}' for prompt '{}'", arg, name),
+                    None,
+                ));
+            }
+            (None, false) => "not specified, ask the user if unclear".to_string(),
+        };
+        text = text.replace(&format!("{{{}}}", arg), &value);
+    }
+
+    Ok(GetPromptResult {
+        description: Some(recipe.description.to_string()),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_prompt() {
+        let args = serde_json::json!({ "table": "main.sales.orders" });
+        let result = get_prompt("build_dashboard", args.as_object()).unwrap();
+        let rmcp::model::PromptMessageContent::Text { text } = &result.messages[0].content else {
+            panic!("expected text prompt");
+        };
+        assert!(text.contains("`main.sales.orders`"));
+        assert!(!text.contains('{'));
+
+        assert!(get_prompt("build_dashboard", None).is_err());
+        assert!(get_prompt("unknown", None).is_err());
+    }
+
+    #[test]
+    fn test_templates_use_declared_arguments() {
+        for recipe in RECIPES {
+            let placeholders = recipe.template.matches('{').count();
+            let declared: usize = recipe
+                .arguments
+                .iter()
+                .map(|(arg, _, _)| recipe.template.matches(&format!("{{{}}}", arg)).count())
+                .sum();
+            assert_eq!(placeholders, declared, "prompt {}", recipe.name);
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use crate::mcp_helpers::structured_result;
+use crate::providers::workspace::PathPolicy;
+use crate::session::SessionContext;
 use edda_integrations::{
-    DatabricksRestClient, DescribeTableRequest, ExecuteSqlRequest, ToolResultDisplay,
+    DatabricksAuthConfig, DatabricksRestClient, DescribeTableRequest, ExecuteSqlRequest, ToolResultDisplay,
 };
 use eyre::Result;
 use rmcp::handler::server::router::tool::ToolRouter;
@@ -8,7 +11,18 @@ use rmcp::model::{CallToolResult, Content, ServerInfo};
 use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{mpsc, Mutex};
+
+/// How long run_app_locally waits for the app to print its local URL
+const RUN_LOCAL_STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+/// Output lines kept to explain a failed start
+const RUN_LOCAL_TAIL_LINES: usize = 40;
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct DatabricksCliArgs {
@@ -16,24 +30,350 @@ pub struct DatabricksCliArgs {
     pub args: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ExportWorkspaceArgs {
+    /// Workspace path of a notebook, file or directory (e.g., /Workspace/Users/me@example.com/app)
+    pub workspace_path: String,
+    /// Absolute local path inside the project (or workspace_allowed_paths) to write to; a
+    /// directory when exporting a directory
+    pub local_path: String,
+    /// Export format for a single notebook: SOURCE (default), HTML, JUPYTER, DBC or AUTO
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Overwrite existing local files
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ImportWorkspaceArgs {
+    /// Absolute local path of a file or directory inside the project (or
+    /// workspace_allowed_paths) to upload
+    pub local_path: String,
+    /// Workspace path to upload to (e.g., /Workspace/Users/me@example.com/app)
+    pub workspace_path: String,
+    /// Overwrite existing workspace files
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceTransferResult {
+    pub source: String,
+    pub destination: String,
+    /// Files copied, as (source, destination) pairs reported by the CLI
+    pub files: Vec<(String, String)>,
+}
+
+impl ToolResultDisplay for WorkspaceTransferResult {
+    fn display(&self) -> String {
+        let mut lines = vec![format!(
+            "Copied {} -> {} ({} file(s))",
+            self.source,
+            self.destination,
+            self.files.len().max(1)
+        )];
+        lines.extend(
+            self.files
+                .iter()
+                .map(|(from, to)| format!("  {} -> {}", from, to)),
+        );
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DeployBundleArgs {
+    /// Absolute path to the directory containing databricks.yml
+    pub work_dir: String,
+    /// Bundle target to deploy (default: the bundle's default target)
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Deploy even if the remote state was changed outside the bundle
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BundleResource {
+    /// Resource type as named in databricks.yml, e.g. "apps" or "jobs"
+    pub kind: String,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeployBundleResult {
+    pub success: bool,
+    pub target: Option<String>,
+    pub resources: Vec<BundleResource>,
+    /// `Error:` lines printed by the CLI
+    pub errors: Vec<String>,
+    /// Full CLI output when the deploy failed
+    pub output: Option<String>,
+}
+
+impl ToolResultDisplay for DeployBundleResult {
+    fn display(&self) -> String {
+        let target = self.target.as_deref().unwrap_or("default target");
+        if !self.success {
+            let mut message = format!("Bundle deploy to {} failed", target);
+            if !self.errors.is_empty() {
+                message.push_str(&format!("\n\n{}", self.errors.join("\n")));
+            }
+            if let Some(output) = &self.output {
+                message.push_str(&format!("\n\nOutput:\n{}", output));
+            }
+            return message;
+        }
+        let mut lines = vec![format!("Deployed bundle to {}", target)];
+        lines.extend(self.resources.iter().map(|r| match &r.url {
+            Some(url) => format!("  {} {}: {}", r.kind, r.name, url),
+            None => format!("  {} {}", r.kind, r.name),
+        }));
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RunAppLocallyArgs {
+    /// Absolute path to the app directory (containing app.yaml)
+    pub work_dir: String,
+    /// Local port to serve the app on (default: chosen by the CLI)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Install the app's dependencies into a local environment before starting it
+    #[serde(default)]
+    pub prepare_environment: bool,
+    /// Stop the app started for this directory instead of starting it
+    #[serde(default)]
+    pub stop: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunAppLocallyResult {
+    pub url: String,
+    pub pid: Option<u32>,
+}
+
+impl ToolResultDisplay for RunAppLocallyResult {
+    fn display(&self) -> String {
+        format!(
+            "App running at {}. Call run_app_locally with stop=true to stop it.",
+            self.url
+        )
+    }
+}
+
+/// An app started by `databricks apps run-local`. It runs in its own process group so
+/// stopping it also stops the server the CLI spawned.
+struct LocalApp {
+    url: String,
+    child: tokio::process::Child,
+}
+
+impl Drop for LocalApp {
+    fn drop(&mut self) {
+        if let (Ok(None), Some(pid)) = (self.child.try_wait(), self.child.id()) {
+            let _ = std::process::Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", pid)])
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+/// Captured output of a finished CLI command
+struct CliOutput {
+    success: bool,
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl CliOutput {
+    fn failure_message(&self) -> String {
+        format!(
+            "databricks CLI command failed (exit code: {})\n\nStdout:\n{}\n\nStderr:\n{}",
+            self.code, self.stdout, self.stderr
+        )
+    }
+}
+
+/// Run the databricks CLI to completion, in `work_dir` if given
+async fn run_cli(args: &[&str], work_dir: Option<&Path>) -> Result<CliOutput, ErrorData> {
+    let mut command = tokio::process::Command::new("databricks");
+    command
+        .args(args)
+        .envs(edda_integrations::env::overlay())
+        .stdin(Stdio::null());
+    if let Some(dir) = work_dir {
+        command.current_dir(dir);
+    }
+    let output = command.output().await.map_err(|e| {
+        ErrorData::internal_error(format!("Failed to execute databricks command: {}", e), None)
+    })?;
+    Ok(CliOutput {
+        success: output.status.success(),
+        code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+/// `path` checked against `policy` like the workspace tools' paths: inside the session's
+/// project `work_dir` or the allowlist
+fn project_path(
+    policy: &PathPolicy,
+    work_dir: Option<&Path>,
+    path: &str,
+) -> Result<PathBuf, ErrorData> {
+    let path = absolute_path(path)?;
+    let Some(work_dir) = work_dir else {
+        return Err(ErrorData::invalid_request(
+            "Workspace directory not set. Run scaffold_data_app or switch_project first, local paths must be inside the project.",
+            None,
+        ));
+    };
+    policy
+        .validate_path(work_dir, &path.display().to_string())
+        .map_err(|e| ErrorData::invalid_params(e.to_string(), None))
+}
+
+fn absolute_path(path: &str) -> Result<PathBuf, ErrorData> {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(ErrorData::invalid_params(
+            format!("Path must be absolute: {}", path.display()),
+            None,
+        ));
+    }
+    Ok(path)
+}
+
+/// `a -> b` lines printed by `workspace export-dir` and `import-dir` for each file
+fn parse_transfers(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(" -> "))
+        .map(|(from, to)| (from.trim().to_string(), to.trim().to_string()))
+        .filter(|(from, to)| !from.is_empty() && !to.is_empty())
+        .collect()
+}
+
+/// `Error: ...` lines of CLI output, without the prefix
+fn parse_errors(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Error:"))
+        .map(|message| message.trim().to_string())
+        .collect()
+}
+
+/// Resources from `bundle summary --output json`, keyed by type then by name
+fn parse_bundle_resources(summary: &serde_json::Value) -> Vec<BundleResource> {
+    let Some(kinds) = summary.get("resources").and_then(|r| r.as_object()) else {
+        return vec![];
+    };
+    kinds
+        .iter()
+        .filter_map(|(kind, resources)| Some((kind, resources.as_object()?)))
+        .flat_map(|(kind, resources)| {
+            resources.iter().map(move |(name, resource)| BundleResource {
+                kind: kind.clone(),
+                name: name.clone(),
+                url: resource
+                    .get("url")
+                    .and_then(|u| u.as_str())
+                    .map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// First localhost URL in a line of run-local output
+fn find_local_url(line: &str) -> Option<String> {
+    ["http://localhost", "http://127.0.0.1", "http://0.0.0.0"]
+        .iter()
+        .filter_map(|prefix| line.find(prefix))
+        .min()
+        .map(|start| {
+            line[start..]
+                .split(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(['.', ',', ')'])
+                .replace("0.0.0.0", "localhost")
+        })
+}
+
+/// Forward lines of a child's output to `lines` until it closes. Keeps reading after the
+/// receiver is gone so the child never blocks on a full pipe.
+fn forward_lines<R>(reader: R, lines: mpsc::UnboundedSender<String>)
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            let _ = lines.send(line);
+        }
+    });
+}
+
 /// Provider for Databricks CLI operations
 #[derive(Clone)]
 pub struct DatabricksCliProvider {
     rest_client: Arc<DatabricksRestClient>,
+    /// Apps started by run_app_locally, by app directory
+    local_apps: Arc<Mutex<HashMap<PathBuf, LocalApp>>>,
+    /// Scope of the local paths of workspace exports and imports
+    session_ctx: SessionContext,
+    policy: PathPolicy,
     tool_router: ToolRouter<Self>,
 }
 
 #[tool_router]
 impl DatabricksCliProvider {
     pub fn new() -> Result<Self> {
-        let rest_client = DatabricksRestClient::new()
+        Self::with_auth(&DatabricksAuthConfig::default())
+    }
+
+    pub fn with_auth(auth: &DatabricksAuthConfig) -> Result<Self> {
+        let rest_client = DatabricksRestClient::with_auth(auth)
             .map_err(|e| eyre::eyre!("Failed to create Databricks REST client: {}", e))?;
         Ok(Self {
             rest_client: Arc::new(rest_client),
+            local_apps: Arc::new(Mutex::new(HashMap::new())),
+            session_ctx: SessionContext::new(None),
+            policy: PathPolicy::default(),
             tool_router: Self::tool_router(),
         })
     }
 
+    /// Keep the local paths of workspace exports and imports inside the session's project
+    /// or the `policy` allowlist
+    pub fn with_paths(self, session_ctx: SessionContext, policy: PathPolicy) -> Self {
+        Self {
+            session_ctx,
+            policy,
+            ..self
+        }
+    }
+
+    /// same provider bound to another session
+    pub fn with_session(&self, session_ctx: SessionContext) -> Self {
+        Self {
+            session_ctx,
+            ..self.clone()
+        }
+    }
+
+    async fn local_path(&self, path: &str) -> Result<PathBuf, ErrorData> {
+        let work_dir = self.session_ctx.work_dir.read().await.clone();
+        project_path(&self.policy, work_dir.as_deref(), path)
+    }
+
     #[tool(
         name = "run_databricks_cli",
         description = "Execute Databricks CLI command for accessing Databricks data. Pass all arguments as a single string.\
@@ -264,37 +604,280 @@ impl DatabricksCliProvider {
         command: &str,
         args: Vec<&str>,
     ) -> Result<CallToolResult, ErrorData> {
-        let output = tokio::process::Command::new("databricks")
-            .arg(command)
-            .args(args)
-            .output()
-            .await
-            .map_err(|e| {
-                ErrorData::internal_error(
-                    format!("Failed to execute databricks command: {}", e),
-                    None,
-                )
-            })?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let cli_args: Vec<&str> = std::iter::once(command).chain(args).collect();
+        let output = run_cli(&cli_args, None).await?;
 
-        if output.status.success() {
-            let result = if !stderr.is_empty() {
-                format!("{}\n\nWarnings/Info:\n{}", stdout, stderr)
+        if output.success {
+            let result = if !output.stderr.is_empty() {
+                format!("{}\n\nWarnings/Info:\n{}", output.stdout, output.stderr)
             } else {
-                stdout
+                output.stdout
             };
             Ok(CallToolResult::success(vec![Content::text(result)]))
         } else {
-            let error_msg = format!(
-                "databricks CLI command failed (exit code: {})\n\nStdout:\n{}\n\nStderr:\n{}",
-                output.status.code().unwrap_or(-1),
-                stdout,
-                stderr
-            );
-            Err(ErrorData::internal_error(error_msg, None))
+            Err(ErrorData::internal_error(output.failure_message(), None))
+        }
+    }
+
+    #[tool(
+        name = "export_workspace_files",
+        description = "Download a notebook, file or directory from the Databricks workspace to a local path. Directories are exported recursively with notebooks as source files."
+    )]
+    pub async fn export_workspace_files(
+        &self,
+        Parameters(args): Parameters<ExportWorkspaceArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let local_path = self.local_path(&args.local_path).await?;
+        let local = local_path.display().to_string();
+
+        let status = run_cli(
+            &["workspace", "get-status", &args.workspace_path, "--output", "json"],
+            None,
+        )
+        .await?;
+        if !status.success {
+            return Err(ErrorData::invalid_params(status.failure_message(), None));
+        }
+        let is_directory = serde_json::from_str::<serde_json::Value>(&status.stdout)
+            .ok()
+            .and_then(|s| s.get("object_type")?.as_str().map(|t| t == "DIRECTORY"))
+            .unwrap_or(false);
+
+        let format = args.format.as_deref().unwrap_or("SOURCE");
+        let mut cli_args = match is_directory {
+            true => vec!["workspace", "export-dir", args.workspace_path.as_str(), local.as_str()],
+            false => vec![
+                "workspace",
+                "export",
+                args.workspace_path.as_str(),
+                "--file",
+                local.as_str(),
+                "--format",
+                format,
+            ],
+        };
+        if args.overwrite {
+            cli_args.push("--overwrite");
+        }
+        let output = run_cli(&cli_args, None).await?;
+        if !output.success {
+            return Err(ErrorData::internal_error(output.failure_message(), None));
+        }
+
+        let result = WorkspaceTransferResult {
+            source: args.workspace_path,
+            destination: local,
+            files: parse_transfers(&format!("{}\n{}", output.stdout, output.stderr)),
+        };
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "import_workspace_files",
+        description = "Upload a local file or directory to the Databricks workspace. Source files with a notebook header are imported as notebooks."
+    )]
+    pub async fn import_workspace_files(
+        &self,
+        Parameters(args): Parameters<ImportWorkspaceArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let local_path = self.local_path(&args.local_path).await?;
+        if !local_path.exists() {
+            return Err(ErrorData::invalid_params(
+                format!("Local path does not exist: {}", local_path.display()),
+                None,
+            ));
+        }
+        let local = local_path.display().to_string();
+
+        let mut cli_args = match local_path.is_dir() {
+            true => vec!["workspace", "import-dir", local.as_str(), args.workspace_path.as_str()],
+            false => vec![
+                "workspace",
+                "import",
+                args.workspace_path.as_str(),
+                "--file",
+                local.as_str(),
+                "--format",
+                "AUTO",
+            ],
+        };
+        if args.overwrite {
+            cli_args.push("--overwrite");
+        }
+        let output = run_cli(&cli_args, None).await?;
+        if !output.success {
+            return Err(ErrorData::internal_error(output.failure_message(), None));
         }
+
+        let result = WorkspaceTransferResult {
+            source: local,
+            destination: args.workspace_path,
+            files: parse_transfers(&format!("{}\n{}", output.stdout, output.stderr)),
+        };
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "deploy_bundle",
+        description = "Deploy the Databricks Asset Bundle in work_dir (databricks bundle deploy) and return the deployed resources with their workspace URLs. Use it for bundles with jobs, pipelines or several apps; deploy_databricks_app covers a single app."
+    )]
+    pub async fn deploy_bundle(
+        &self,
+        Parameters(args): Parameters<DeployBundleArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_dir = absolute_path(&args.work_dir)?;
+        if !work_dir.join("databricks.yml").exists() {
+            return Err(ErrorData::invalid_params(
+                format!("No databricks.yml found in {}", work_dir.display()),
+                None,
+            ));
+        }
+
+        let mut target_args = vec![];
+        if let Some(target) = &args.target {
+            target_args.extend(["--target", target.as_str()]);
+        }
+
+        let mut deploy_args = vec!["bundle", "deploy"];
+        deploy_args.extend(&target_args);
+        if args.force {
+            deploy_args.push("--force");
+        }
+        let output = run_cli(&deploy_args, Some(&work_dir)).await?;
+        if !output.success {
+            let combined = format!("{}\n{}", output.stdout, output.stderr);
+            let result = DeployBundleResult {
+                success: false,
+                target: args.target,
+                resources: vec![],
+                errors: parse_errors(&combined),
+                output: Some(combined.trim().to_string()),
+            };
+            return Ok(structured_result(&result, false));
+        }
+
+        let mut summary_args = vec!["bundle", "summary", "--output", "json"];
+        summary_args.extend(&target_args);
+        let summary = run_cli(&summary_args, Some(&work_dir)).await?;
+        let resources = match summary.success {
+            true => serde_json::from_str::<serde_json::Value>(&summary.stdout)
+                .map(|summary| parse_bundle_resources(&summary))
+                .unwrap_or_default(),
+            false => {
+                tracing::warn!("bundle summary failed: {}", summary.stderr.trim());
+                vec![]
+            }
+        };
+
+        let result = DeployBundleResult {
+            success: true,
+            target: args.target,
+            resources,
+            errors: vec![],
+            output: None,
+        };
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "run_app_locally",
+        description = "Run a Databricks app on this machine with the app's app.yaml command and environment (databricks apps run-local) and return its local URL. The app keeps running in the background; calling it again for the same directory restarts it, pass stop=true to stop it."
+    )]
+    pub async fn run_app_locally(
+        &self,
+        Parameters(args): Parameters<RunAppLocallyArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_dir = absolute_path(&args.work_dir)?;
+
+        // replacing an app of the same directory stops the old one
+        let previous = self.local_apps.lock().await.remove(&work_dir);
+        if args.stop {
+            let message = match previous {
+                Some(app) => format!("Stopped app at {}", app.url),
+                None => format!("No app running for {}", work_dir.display()),
+            };
+            return Ok(CallToolResult::success(vec![Content::text(message)]));
+        }
+        drop(previous);
+
+        let port = args.port.map(|p| p.to_string());
+        let mut command = tokio::process::Command::new("databricks");
+        command
+            .args(["apps", "run-local"])
+            .envs(edda_integrations::env::overlay());
+        if let Some(port) = &port {
+            command.args(["--port", port]);
+        }
+        if args.prepare_environment {
+            command.arg("--prepare-environment");
+        }
+        let mut child = command
+            .current_dir(&work_dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                ErrorData::internal_error(format!("Failed to execute databricks command: {}", e), None)
+            })?;
+
+        let (sender, mut lines) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender);
+        }
+
+        let mut tail = std::collections::VecDeque::new();
+        let wait_for_url = async {
+            while let Some(line) = lines.recv().await {
+                if let Some(url) = find_local_url(&line) {
+                    return Some(url);
+                }
+                if tail.len() == RUN_LOCAL_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            // both streams closed, the CLI exited
+            None
+        };
+        let url = match tokio::time::timeout(RUN_LOCAL_STARTUP_TIMEOUT, wait_for_url).await {
+            Ok(Some(url)) => url,
+            Ok(None) => {
+                return Err(ErrorData::internal_error(
+                    format!(
+                        "App exited before it started listening\n\nOutput:\n{}",
+                        Vec::from(tail).join("\n")
+                    ),
+                    None,
+                ));
+            }
+            Err(_) => {
+                return Err(ErrorData::internal_error(
+                    format!(
+                        "App did not report a local URL within {} seconds\n\nOutput:\n{}",
+                        RUN_LOCAL_STARTUP_TIMEOUT.as_secs(),
+                        Vec::from(tail).join("\n")
+                    ),
+                    None,
+                ));
+            }
+        };
+
+        let result = RunAppLocallyResult {
+            url: url.clone(),
+            pid: child.id(),
+        };
+        tracing::info!("App in {} running locally at {}", work_dir.display(), url);
+        self.local_apps
+            .lock()
+            .await
+            .insert(work_dir, LocalApp { url, child });
+        Ok(structured_result(&result, true))
     }
 }
 
@@ -304,3 +887,69 @@ impl ServerHandler for DatabricksCliProvider {
         crate::mcp_helpers::internal_server_info()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_path() {
+        let project = tempfile::TempDir::new().unwrap();
+        let shared = tempfile::TempDir::new().unwrap();
+        let policy = PathPolicy::new(&[shared.path().display().to_string()]);
+        let work_dir = Some(project.path());
+        let path = |path: &Path| path.display().to_string();
+
+        let export = project_path(&policy, work_dir, &path(&project.path().join("notebooks")));
+        assert!(export.unwrap().ends_with("notebooks"));
+        assert!(project_path(&policy, work_dir, &path(&shared.path().join("a.py"))).is_ok());
+        assert!(project_path(&policy, work_dir, "/etc/passwd").is_err());
+        assert!(project_path(&policy, work_dir, "notebooks").is_err());
+        let escape = format!("{}/../outside", path(project.path()));
+        assert!(project_path(&policy, work_dir, &escape).is_err());
+        // the project is the base of the policy, so nothing goes without one
+        assert!(project_path(&policy, None, &path(&shared.path().join("a.py"))).is_err());
+    }
+
+    #[test]
+    fn test_parse_cli_output() {
+        let output = "Exporting files from /Workspace/app\n\
+/Workspace/app/main.py -> /tmp/app/main.py\n\
+/Workspace/app/notebook (PYTHON) -> /tmp/app/notebook.py\n\
+Export complete";
+        assert_eq!(
+            parse_transfers(output),
+            vec![
+                ("/Workspace/app/main.py".to_string(), "/tmp/app/main.py".to_string()),
+                (
+                    "/Workspace/app/notebook (PYTHON)".to_string(),
+                    "/tmp/app/notebook.py".to_string()
+                ),
+            ]
+        );
+
+        assert_eq!(
+            parse_errors("Uploading bundle files...\nError: terraform apply: exit status 1\n"),
+            vec!["terraform apply: exit status 1".to_string()]
+        );
+
+        let summary = serde_json::json!({
+            "resources": {
+                "apps": {"dashboard": {"name": "dashboard", "url": "https://dashboard.databricksapps.com"}},
+                "jobs": {"refresh": {"name": "[dev] refresh"}}
+            }
+        });
+        let resources = parse_bundle_resources(&summary);
+        assert_eq!(resources.len(), 2);
+        assert_eq!(resources[0].kind, "apps");
+        assert_eq!(resources[0].url.as_deref(), Some("https://dashboard.databricksapps.com"));
+        assert_eq!(resources[1].name, "refresh");
+        assert!(resources[1].url.is_none());
+
+        assert_eq!(
+            find_local_url("App is running at http://0.0.0.0:8001."),
+            Some("http://localhost:8001".to_string())
+        );
+        assert_eq!(find_local_url("Installing dependencies"), None);
+    }
+}
@@ -1,8 +1,12 @@
+use crate::mcp_helpers::structured_result;
 use edda_integrations::{
-    DatabricksDescribeTableArgs, DatabricksExecuteQueryArgs, DatabricksListCatalogsArgs,
-    DatabricksListSchemasArgs, DatabricksListTablesArgs, DatabricksRestClient,
-    DescribeTableRequest, ExecuteSqlRequest, ListSchemasRequest, ListTablesRequest,
-    ToolResultDisplay,
+    DatabricksAuthConfig, DatabricksDescribeTableArgs, DatabricksError, DatabricksExecuteQueryArgs,
+    DatabricksExportDashboardQueriesArgs, DatabricksGenieAskArgs, DatabricksListCatalogsArgs,
+    DatabricksListDashboardsArgs, DatabricksListSchemasArgs, DatabricksListServingEndpointsArgs,
+    DatabricksListTablesArgs, DatabricksListVolumeFilesArgs, DatabricksListWarehousesArgs,
+    DatabricksQueryServingEndpointArgs, DatabricksRestClient, DatabricksUploadFileArgs,
+    DescribeTableRequest, ExecuteSqlOptions, ExecuteSqlRequest, GenieAskRequest, ListSchemasRequest,
+    ListTablesRequest, ListVolumeFilesRequest, ToolResultDisplay, UploadFileRequest,
 };
 use eyre::Result;
 use rmcp::handler::server::router::tool::ToolRouter;
@@ -10,6 +14,7 @@ use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{CallToolResult, Content, ServerInfo};
 use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct DatabricksRestProvider {
@@ -20,7 +25,11 @@ pub struct DatabricksRestProvider {
 #[tool_router]
 impl DatabricksRestProvider {
     pub fn new() -> Result<Self> {
-        let client = DatabricksRestClient::new()
+        Self::with_auth(&DatabricksAuthConfig::default())
+    }
+
+    pub fn with_auth(auth: &DatabricksAuthConfig) -> Result<Self> {
+        let client = DatabricksRestClient::with_auth(auth)
             .map_err(|e| eyre::eyre!("Failed to create Databricks client: {}", e))?;
         Ok(Self {
             client: Arc::new(client),
@@ -34,7 +43,8 @@ impl DatabricksRestProvider {
                        Only single SQL statements are supported - do not send multiple statements separated by semicolons. \
                        For multiple statements, call this tool separately for each one. \
                        DO NOT create catalogs, schemas or tables - requires metastore admin privileges. Query existing data instead. \
-                       Timeout: 60 seconds for query execution."
+                       Returns up to 100 rows by default; set max_rows to fetch larger result sets in chunks. \
+                       Timeout: 60 seconds by default; set timeout (seconds) to override - the query is cancelled when exceeded."
     )]
     pub async fn execute_sql(
         &self,
@@ -43,9 +53,14 @@ impl DatabricksRestProvider {
         let request = ExecuteSqlRequest {
             query: args.query,
         };
-        match self.client.execute_sql(&request).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
-            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        let options = ExecuteSqlOptions {
+            max_rows: args.max_rows,
+            timeout: args.timeout.map(Duration::from_secs),
+        };
+        let result = self.client.execute_sql_with_options(&request, &options).await;
+        match result {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
         }
     }
 
@@ -55,8 +70,8 @@ impl DatabricksRestProvider {
         Parameters(_args): Parameters<DatabricksListCatalogsArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         match self.client.list_catalogs().await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
-            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
         }
     }
 
@@ -72,8 +87,8 @@ impl DatabricksRestProvider {
             offset: args.offset,
         };
         match self.client.list_schemas(&request).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
-            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
         }
     }
 
@@ -90,8 +105,8 @@ impl DatabricksRestProvider {
             offset: args.offset,
         };
         match self.client.list_tables(&request).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
-            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
         }
     }
 
@@ -108,10 +123,181 @@ impl DatabricksRestProvider {
             sample_size: args.sample_size,
         };
         match self.client.describe_table(&request).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
-            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_list_volume_files",
+        description = "List files and directories in a Unity Catalog volume. Path format: /Volumes/<catalog>/<schema>/<volume>[/dir]"
+    )]
+    pub async fn list_volume_files(
+        &self,
+        Parameters(args): Parameters<DatabricksListVolumeFilesArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let request = ListVolumeFilesRequest { path: args.path };
+        match self.client.list_volume_files(&request).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_upload_file",
+        description = "Upload a local file (e.g. CSV) to a Unity Catalog volume. local_path must be absolute; volume_path format: /Volumes/<catalog>/<schema>/<volume>/<file>"
+    )]
+    pub async fn upload_file(
+        &self,
+        Parameters(args): Parameters<DatabricksUploadFileArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let local_path = std::path::Path::new(&args.local_path);
+        if !local_path.is_absolute() {
+            return Err(ErrorData::invalid_params(
+                format!("local_path must be absolute, got: {}", args.local_path),
+                None,
+            ));
+        }
+        let contents = tokio::fs::read(local_path).await.map_err(|e| {
+            ErrorData::invalid_params(
+                format!("Failed to read {}: {}", local_path.display(), e),
+                None,
+            )
+        })?;
+        let request = UploadFileRequest {
+            volume_path: args.volume_path,
+            contents,
+            overwrite: args.overwrite,
+        };
+        match self.client.upload_file(&request).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_list_warehouses",
+        description = "List Databricks SQL warehouses with their state (RUNNING/STOPPED/...), size and type. Stopped warehouses are started automatically when a query needs them."
+    )]
+    pub async fn list_warehouses(
+        &self,
+        Parameters(_args): Parameters<DatabricksListWarehousesArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list_warehouses().await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_genie_ask",
+        description = "Ask a Databricks Genie space a natural-language question about its data. \
+                       Genie generates and runs the SQL and returns the query, its result and any text reply. \
+                       Pass the returned conversation_id to ask follow-up questions. \
+                       Space defaults to DATABRICKS_GENIE_SPACE_ID."
+    )]
+    pub async fn genie_ask(
+        &self,
+        Parameters(args): Parameters<DatabricksGenieAskArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let request = GenieAskRequest {
+            question: args.question,
+            space_id: args.space_id,
+            conversation_id: args.conversation_id,
+        };
+        match self.client.genie_ask(&request).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
         }
     }
+
+    #[tool(
+        name = "databricks_list_dashboards",
+        description = "List Databricks Lakeview (AI/BI) dashboards with their IDs and workspace paths"
+    )]
+    pub async fn list_dashboards(
+        &self,
+        Parameters(_args): Parameters<DatabricksListDashboardsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list_dashboards().await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_export_dashboard_queries",
+        description = "Export the SQL queries behind each dataset of a Lakeview dashboard. Use this to replicate or extend an existing dashboard in an app."
+    )]
+    pub async fn export_dashboard_queries(
+        &self,
+        Parameters(args): Parameters<DatabricksExportDashboardQueriesArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.export_dashboard_queries(&args.dashboard_id).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_list_serving_endpoints",
+        description = "List Databricks Model Serving endpoints with their state, task and served models"
+    )]
+    pub async fn list_serving_endpoints(
+        &self,
+        Parameters(_args): Parameters<DatabricksListServingEndpointsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list_serving_endpoints().await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+
+    #[tool(
+        name = "databricks_query_serving_endpoint",
+        description = "Query a Databricks Model Serving endpoint. Call without payload first to discover the expected request schema, then call with a JSON payload matching it."
+    )]
+    pub async fn query_serving_endpoint(
+        &self,
+        Parameters(args): Parameters<DatabricksQueryServingEndpointArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = match &args.payload {
+            Some(payload) => self
+                .client
+                .query_serving_endpoint(&args.name, payload)
+                .await
+                .map(|r| r.display()),
+            None => self
+                .client
+                .get_serving_endpoint_schema(&args.name)
+                .await
+                .map(|r| r.display()),
+        };
+        match result {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err(e) => Err(tool_error(&*e)),
+        }
+    }
+}
+
+/// Map client errors to MCP errors, tagging auth and transient failures
+/// so callers can decide whether retrying makes sense.
+fn tool_error(e: &(dyn std::error::Error + 'static)) -> ErrorData {
+    match e.downcast_ref::<DatabricksError>() {
+        Some(err) if err.is_auth() => ErrorData::internal_error(
+            err.to_string(),
+            Some(serde_json::json!({ "kind": "auth", "retryable": false })),
+        ),
+        Some(err) if err.is_transient() => ErrorData::internal_error(
+            err.to_string(),
+            Some(serde_json::json!({
+                "kind": "transient",
+                "retryable": true,
+                "retry_after_secs": err.retry_after().map(|d| d.as_secs()),
+            })),
+        ),
+        _ => ErrorData::internal_error(e.to_string(), None),
+    }
 }
 
 #[tool_handler]
@@ -1,7 +1,13 @@
+use crate::mcp_helpers::structured_result;
+use crate::progress::ProgressReporter;
 use crate::state;
+use edda_templates::TemplateCapabilities;
+use edda_templates::capabilities::DeployMode;
 use edda_integrations::{
-    AppInfo, CreateApp, Resources, ToolResultDisplay, create_app, deploy_app, get_app_info,
-    get_user_info, sync_workspace,
+    AppInfo, AppSummary, CreateApp, DEPLOYMENT_TIMEOUT, Deployment, Resources, ToolResultDisplay,
+    add_env_to_app_yaml, app_env, create_app, delete_app, deploy_app, get_app_info,
+    get_deployment_logs, get_user_info, list_apps, list_deployments, provision_secrets,
+    rollback_app, sync_workspace, update_app_resources, wait_for_deployment,
 };
 use eyre::Result;
 use rmcp::handler::server::router::tool::ToolRouter;
@@ -9,10 +15,17 @@ use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{
     CallToolResult, Content, ServerInfo,
 };
+use rmcp::service::{RequestContext, RoleServer};
 use rmcp::{ErrorData, ServerHandler, tool, tool_handler, tool_router};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Log lines attached to a failed deployment result
+const FAILURE_LOG_LINES: usize = 100;
 
 #[derive(Clone)]
 pub struct DeploymentProvider {
@@ -29,6 +42,17 @@ pub struct DeployDatabricksAppArgs {
     pub description: String,
     /// Force re-deployment if the app already exists
     pub force: Option<bool>,
+    /// Names of local environment variables to store as Databricks secrets and expose to the app
+    /// through app.yaml `valueFrom` (never hard-code tokens in app.yaml)
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    /// Names of model serving endpoints the app queries; each is granted CAN_QUERY and exposed
+    /// as env var SERVING_ENDPOINT_<NAME> (uppercased, dashes become underscores)
+    #[serde(default)]
+    pub serving_endpoints: Vec<String>,
+    /// Non-secret environment variables written to app.yaml as literal values
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,6 +63,216 @@ pub struct DeployDatabricksAppResult {
     pub app_name: String,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GetDeploymentLogsArgs {
+    /// Name of the Databricks app
+    pub name: String,
+    /// Number of log lines to return from the end of the log (default: 200)
+    #[serde(default = "default_tail_lines")]
+    pub tail_lines: usize,
+}
+
+fn default_tail_lines() -> usize {
+    200
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct WaitForDeploymentArgs {
+    /// Name of the Databricks app
+    pub name: String,
+    /// Deployment to wait for (default: the app's pending or active deployment)
+    #[serde(default)]
+    pub deployment_id: Option<String>,
+    /// Maximum time to wait in seconds (default: 1200)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WaitForDeploymentResult {
+    pub app_name: String,
+    pub deployment: Deployment,
+    /// Status transitions observed while waiting
+    pub progress: Vec<String>,
+}
+
+impl ToolResultDisplay for WaitForDeploymentResult {
+    fn display(&self) -> String {
+        let mut lines = vec![format!(
+            "Deployment {} of app '{}' finished with state {}",
+            self.deployment.deployment_id, self.app_name, self.deployment.status.state
+        )];
+        if !self.deployment.status.message.is_empty() {
+            lines.push(format!("Message: {}", self.deployment.status.message));
+        }
+        if !self.progress.is_empty() {
+            lines.push("\nProgress:".to_string());
+            for step in &self.progress {
+                lines.push(format!("• {}", step));
+            }
+        }
+        if !self.deployment.succeeded() {
+            lines.push(
+                "\nUse get_deployment_logs to inspect build and runtime logs.".to_string(),
+            );
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListAppDeploymentsArgs {
+    /// Name of the Databricks app
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListAppDeploymentsResult {
+    pub app_name: String,
+    pub active_deployment_id: Option<String>,
+    /// Newest first
+    pub deployments: Vec<Deployment>,
+}
+
+impl ToolResultDisplay for ListAppDeploymentsResult {
+    fn display(&self) -> String {
+        if self.deployments.is_empty() {
+            return format!("App '{}' has no deployments.", self.app_name);
+        }
+        let mut lines = vec![
+            format!(
+                "Found {} deployments of app '{}' (newest first):",
+                self.deployments.len(),
+                self.app_name
+            ),
+            String::new(),
+        ];
+        for deployment in &self.deployments {
+            let mut info = format!(
+                "• {} [{}] created {} by {}",
+                deployment.deployment_id,
+                deployment.status.state,
+                deployment.create_time,
+                deployment.creator
+            );
+            if self.active_deployment_id.as_deref() == Some(deployment.deployment_id.as_str()) {
+                info.push_str(" (active)");
+            }
+            lines.push(info);
+        }
+        lines.push("\nUse rollback_app with a SUCCEEDED deployment_id to restore it.".to_string());
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct RollbackAppArgs {
+    /// Name of the Databricks app
+    pub name: String,
+    /// Previous successful deployment to restore (see list_app_deployments)
+    pub deployment_id: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ListDatabricksAppsArgs {
+    /// Only list apps created by the current user (default: true)
+    #[serde(default = "default_mine_only")]
+    pub mine_only: bool,
+    /// Only list apps whose name contains this text
+    #[serde(default)]
+    pub name_contains: Option<String>,
+}
+
+fn default_mine_only() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListDatabricksAppsResult {
+    pub apps: Vec<AppSummary>,
+    pub mine_only: bool,
+}
+
+impl ToolResultDisplay for ListDatabricksAppsResult {
+    fn display(&self) -> String {
+        let scope = if self.mine_only { "your apps" } else { "apps" };
+        if self.apps.is_empty() {
+            return format!("No {} found.", scope);
+        }
+        let mut lines = vec![
+            format!("Found {} {} (most recently updated first):", self.apps.len(), scope),
+            String::new(),
+        ];
+        for app in &self.apps {
+            let mut info = format!(
+                "• {} [app: {}, compute: {}] updated {}",
+                app.name, app.app_status.state, app.compute_status.state, app.update_time
+            );
+            if !app.url.is_empty() {
+                info.push_str(&format!("\n  {}", app.url));
+            }
+            if !app.description.is_empty() {
+                info.push_str(&format!("\n  {}", app.description));
+            }
+            lines.push(info);
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DeleteDatabricksAppArgs {
+    /// Name of the Databricks app to delete
+    pub name: String,
+    /// Delete the app even if it was created by another user
+    pub force: Option<bool>,
+}
+
+fn progress_line(deployment: &Deployment) -> String {
+    match deployment.status.message.as_str() {
+        "" => deployment.status.state.clone(),
+        message => format!("{}: {}", deployment.status.state, message),
+    }
+}
+
+/// Runs the blocking poll loop off the async runtime, reporting each status change.
+/// Polling stops once `cancellation` fires, even if the awaiting call was dropped.
+async fn wait_for_deployment_blocking(
+    app_name: &str,
+    deployment_id: &str,
+    timeout: Duration,
+    reporter: &ProgressReporter,
+    cancellation: &CancellationToken,
+) -> Result<WaitForDeploymentResult> {
+    let app_name = app_name.to_string();
+    let deployment_id = deployment_id.to_string();
+    let reporter = reporter.clone();
+    let cancellation = cancellation.clone();
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        let mut progress = Vec::new();
+        let deployment = wait_for_deployment(
+            &app_name,
+            &deployment_id,
+            timeout,
+            |d| {
+                let line = progress_line(d);
+                tracing::info!("Deployment {}: {}", d.deployment_id, line);
+                runtime.block_on(reporter.step(format!("Deployment {}", line)));
+                progress.push(line);
+            },
+            || cancellation.is_cancelled(),
+        )
+        .map_err(|e| eyre::eyre!("{}", e))?;
+        Ok(WaitForDeploymentResult {
+            app_name,
+            deployment,
+            progress,
+        })
+    })
+    .await?
+}
+
 impl ToolResultDisplay for DeployDatabricksAppResult {
     fn display(&self) -> String {
         if self.success {
@@ -71,6 +305,11 @@ impl DeploymentProvider {
         name: &str,
         description: &str,
         force: bool,
+        secrets: &[String],
+        serving_endpoints: &[String],
+        env: &BTreeMap<String, String>,
+        progress: &ProgressReporter,
+        cancellation: &CancellationToken,
     ) -> Result<DeployDatabricksAppResult> {
         let start_time = std::time::Instant::now();
         // Validate work directory exists
@@ -141,18 +380,61 @@ impl DeploymentProvider {
             }
         }
 
-        // Install project dependencies
-        run_format_cmd(
-            std::process::Command::new("npm")
-                .args(&["install"])
-                .current_dir(&work_path),
-        )?;
+        // the template decides how the app is built and which directory is shipped
+        let capabilities = TemplateCapabilities::load(&work_path)?.unwrap_or_default();
+        let unsupported = match capabilities.deploy {
+            DeployMode::DatabricksApp => None,
+            DeployMode::Docker => Some(
+                "This project's template deploys from its Dockerfile, not as a Databricks App. Build and run the image with docker instead.",
+            ),
+            DeployMode::None => Some("This project's template isn't meant to be deployed."),
+        };
+        if let Some(message) = unsupported {
+            return Ok(DeployDatabricksAppResult {
+                success: false,
+                message: message.to_string(),
+                app_url: None,
+                app_name: name.to_string(),
+            });
+        }
+
+        for command in capabilities.build_commands() {
+            progress.step(format!("Running {}", command.join(" "))).await;
+            run_format_cmd(
+                std::process::Command::new(command[0])
+                    .args(&command[1..])
+                    .current_dir(&work_path),
+            )?;
+        }
+        let app_dir = work_path.join(capabilities.app_dir());
+
+        // store requested env vars as secrets so app.yaml only references them
+        progress.step("Configuring app resources").await;
+        let secret_resources = provision_secrets(name, secrets)
+            .map_err(|e| eyre::eyre!("Failed to provision secrets: {}", e))?;
+        if !secrets.is_empty() {
+            tracing::info!("Provisioned {} app secrets", secrets.len());
+        }
 
-        // Build frontend
-        run_format_cmd(
-            std::process::Command::new("npm")
-                .args(&["run", "build"])
-                .current_dir(&work_path),
+        let mut resources = vec![Resources::from_env().map_err(|e| eyre::eyre!("{}", e))?];
+        resources.extend(serving_endpoints.iter().map(|e| Resources::serving_endpoint(e)));
+        resources.extend(secret_resources);
+
+        // expose every resource and user-provided value through app.yaml
+        let env: Vec<(String, String)> = env.clone().into_iter().collect();
+        // app.yaml sits in the deployed directory (the project root, or server/ in the tRPC
+        // layout); writing a fresh one would drop the template's start command
+        let app_yaml_path = app_dir.join("app.yaml");
+        if !app_yaml_path.is_file() {
+            return Err(eyre::eyre!(
+                "No app.yaml in {}, the app can't be started without one",
+                app_dir.display()
+            ));
+        }
+        let app_yaml = std::fs::read_to_string(&app_yaml_path)?;
+        std::fs::write(
+            &app_yaml_path,
+            add_env_to_app_yaml(&app_yaml, &app_env(&resources, &env)),
         )?;
 
         // Get or create app
@@ -168,21 +450,28 @@ impl DeploymentProvider {
                         info.creator
                     ));
                 }
-                info
+                if resources.len() == 1 {
+                    info
+                } else {
+                    update_app_resources(name, &resources)
+                        .map_err(|e| eyre::eyre!("Failed to update app resources: {}", e))?
+                }
             }
             Err(_) => {
                 tracing::info!("App not found, creating new app: {}", name);
-                let resources = Resources::from_env().map_err(|e| eyre::eyre!("{}", e))?;
-                let command = CreateApp::new(name, description).with_resources(resources);
+                let command = resources
+                    .into_iter()
+                    .fold(CreateApp::new(name, description), CreateApp::with_resources);
                 create_app(&command).map_err(|e| eyre::eyre!("Failed to create app: {}", e))?
             }
         };
 
         // Sync workspace
-        let server_dir = format!("{work_dir}/server");
+        let app_dir = app_dir.to_string_lossy();
         let sync_start = std::time::Instant::now();
-        tracing::info!("Syncing workspace from {} to Databricks", server_dir);
-        sync_workspace(&app_info, &server_dir)
+        tracing::info!("Syncing workspace from {} to Databricks", app_dir);
+        progress.step("Syncing workspace").await;
+        sync_workspace(&app_info, &app_dir)
             .map_err(|e| eyre::eyre!("Failed to sync workspace: {}", e))?;
         let sync_duration = sync_start.elapsed().as_secs_f64();
         tracing::info!(duration = sync_duration, "Workspace sync completed");
@@ -191,10 +480,11 @@ impl DeploymentProvider {
         const DEPLOY_RETRIES: usize = 3;
         let deploy_start = std::time::Instant::now();
         tracing::info!("Deploying app: {}", name);
+        progress.step("Starting deployment").await;
         let mut attempt = 0;
-        loop {
+        let deployment = loop {
             match deploy_app(&app_info) {
-                Ok(_) => break,
+                Ok(deployment) => break deployment,
                 Err(e) => {
                     attempt += 1;
                     if attempt >= DEPLOY_RETRIES {
@@ -207,6 +497,31 @@ impl DeploymentProvider {
                     tracing::warn!("Deploy attempt {} failed: {}. Retrying...", attempt, e);
                 }
             }
+        };
+
+        let waited = wait_for_deployment_blocking(
+            name,
+            &deployment.deployment_id,
+            DEPLOYMENT_TIMEOUT,
+            progress,
+            cancellation,
+        )
+        .await?;
+        if !waited.deployment.succeeded() {
+            let logs = get_deployment_logs(name, FAILURE_LOG_LINES)
+                .unwrap_or_else(|e| format!("(failed to fetch logs: {})", e));
+            return Ok(DeployDatabricksAppResult {
+                success: false,
+                message: format!(
+                    "Deployment {} ended in state {}: {}\n\nRecent logs:\n{}",
+                    waited.deployment.deployment_id,
+                    waited.deployment.status.state,
+                    waited.deployment.status.message,
+                    logs
+                ),
+                app_url: Some(app_info.url.clone()),
+                app_name: name.to_string(),
+            });
         }
         let deploy_duration = deploy_start.elapsed().as_secs_f64();
         tracing::info!(duration = deploy_duration, "App deployment completed");
@@ -233,6 +548,7 @@ impl DeploymentProvider {
     pub async fn deploy_databricks_app(
         &self,
         Parameters(args): Parameters<DeployDatabricksAppArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let work_path = PathBuf::from(&args.work_dir);
 
@@ -252,18 +568,219 @@ impl DeploymentProvider {
             &args.name,
             &args.description,
             args.force.unwrap_or(false),
+            &args.secrets,
+            &args.serving_endpoints,
+            &args.env,
+            &ProgressReporter::new(&context, None),
+            &context.ct,
         )
         .await
         .map_err(|e| ErrorData::internal_error(format!("Failed to deploy app: {}", e), None))?;
 
         if result.success {
-            Ok(CallToolResult::success(vec![Content::text(
-                result.display(),
-            )]))
+            Ok(structured_result(&result, true))
         } else {
             Err(ErrorData::internal_error(result.message, None))
         }
     }
+
+    #[tool(
+        name = "list_app_deployments",
+        description = "List the deployment history of a Databricks app with state, creation time and which deployment is active. Each successful deployment is a version that rollback_app can restore."
+    )]
+    pub async fn list_app_deployments(
+        &self,
+        Parameters(args): Parameters<ListAppDeploymentsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let app_info = get_app_info(&args.name)
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        let deployments = list_deployments(&args.name)
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        let result = ListAppDeploymentsResult {
+            app_name: args.name,
+            active_deployment_id: app_info.active_deployment.map(|d| d.deployment_id),
+            deployments,
+        };
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "rollback_app",
+        description = "Roll a Databricks app back to a previous successful deployment by redeploying its source snapshot, then wait for the rollback to finish. Only use after direct user request."
+    )]
+    pub async fn rollback_app(
+        &self,
+        Parameters(args): Parameters<RollbackAppArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let deployment = rollback_app(&args.name, &args.deployment_id)
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        tracing::info!(
+            "Rolling back app {} to deployment {} (new deployment {})",
+            args.name,
+            args.deployment_id,
+            deployment.deployment_id
+        );
+
+        let result = wait_for_deployment_blocking(
+            &args.name,
+            &deployment.deployment_id,
+            DEPLOYMENT_TIMEOUT,
+            &ProgressReporter::new(&context, None),
+            &context.ct,
+        )
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        if !result.deployment.succeeded() {
+            return Err(ErrorData::internal_error(
+                format!("Rollback failed.\n{}", result.display()),
+                None,
+            ));
+        }
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Rolled back app '{}' to deployment {}.\n{}",
+            args.name,
+            args.deployment_id,
+            result.display()
+        ))]))
+    }
+
+    #[tool(
+        name = "list_databricks_apps",
+        description = "List Databricks apps with their status, URL and last update time. By default only apps created by the current user. Use it to find apps left over from earlier experiments."
+    )]
+    pub async fn list_databricks_apps(
+        &self,
+        Parameters(args): Parameters<ListDatabricksAppsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mine_only = args.mine_only;
+        let apps = tokio::task::spawn_blocking(move || -> Result<Vec<AppSummary>> {
+            let creator = match mine_only {
+                true => Some(
+                    get_user_info()
+                        .map_err(|e| eyre::eyre!("Failed to get user info: {}", e))?
+                        .user_name,
+                ),
+                false => None,
+            };
+            let apps = list_apps()
+                .map_err(|e| eyre::eyre!("{}", e))?
+                .into_iter()
+                .filter(|app| creator.as_ref().is_none_or(|c| &app.creator == c))
+                .filter(|app| {
+                    args.name_contains
+                        .as_ref()
+                        .is_none_or(|text| app.name.contains(text.as_str()))
+                })
+                .collect();
+            Ok(apps)
+        })
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+
+        let result = ListDatabricksAppsResult { apps, mine_only };
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "delete_databricks_app",
+        description = "Permanently delete a Databricks app and the secret scope edda created for it. Apps created by another user are only deleted with force. Only use after direct user request, and confirm the exact app name with the user first."
+    )]
+    pub async fn delete_databricks_app(
+        &self,
+        Parameters(args): Parameters<DeleteDatabricksAppArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let name = args.name.clone();
+        let force = args.force.unwrap_or(false);
+        let scope_deleted = tokio::task::spawn_blocking(move || {
+            let info = get_app_info(&name).map_err(|e| eyre::eyre!("{}", e))?;
+            let user_info =
+                get_user_info().map_err(|e| eyre::eyre!("Failed to get user info: {}", e))?;
+            if !force && info.creator != user_info.user_name {
+                return Err(eyre::eyre!(
+                    "App '{}' was created by another user: {}. Use 'force' option to delete it anyway.",
+                    name,
+                    info.creator
+                ));
+            }
+            delete_app(&name).map_err(|e| eyre::eyre!("{}", e))
+        })
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        tracing::info!("Deleted app {}", args.name);
+
+        let mut message = format!("Deleted app '{}'.", args.name);
+        if scope_deleted {
+            message.push_str(" Its secret scope was removed as well.");
+        }
+        Ok(CallToolResult::success(vec![Content::text(message)]))
+    }
+
+    #[tool(
+        name = "get_deployment_logs",
+        description = "Get recent build and runtime logs of a Databricks app. Use this to diagnose failed deployments or apps that crash after starting."
+    )]
+    pub async fn get_deployment_logs(
+        &self,
+        Parameters(args): Parameters<GetDeploymentLogsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let logs =
+            tokio::task::spawn_blocking(move || get_deployment_logs(&args.name, args.tail_lines))
+                .await
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        let text = match logs.trim() {
+            "" => "No logs available yet.".to_string(),
+            logs => logs.to_string(),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    #[tool(
+        name = "wait_for_deployment",
+        description = "Wait until a Databricks app deployment succeeds, fails or is cancelled, and report the status transitions observed."
+    )]
+    pub async fn wait_for_deployment(
+        &self,
+        Parameters(args): Parameters<WaitForDeploymentArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let deployment_id = match args.deployment_id {
+            Some(id) => id,
+            None => {
+                let app_info = get_app_info(&args.name)
+                    .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+                app_info
+                    .pending_deployment
+                    .or(app_info.active_deployment)
+                    .map(|d| d.deployment_id)
+                    .ok_or_else(|| {
+                        ErrorData::invalid_params(
+                            format!("App '{}' has no deployments", args.name),
+                            None,
+                        )
+                    })?
+            }
+        };
+        let timeout = args
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEPLOYMENT_TIMEOUT);
+
+        let progress = ProgressReporter::new(&context, None);
+        let result = wait_for_deployment_blocking(
+            &args.name,
+            &deployment_id,
+            timeout,
+            &progress,
+            &context.ct,
+        )
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok(structured_result(&result, true))
+    }
 }
 
 #[tool_handler]
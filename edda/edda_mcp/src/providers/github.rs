@@ -0,0 +1,59 @@
+use crate::mcp_helpers::structured_result;
+use edda_integrations::{GithubClient, PublishToGithubArgs};
+use eyre::Result;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::{CallToolResult, ServerInfo};
+use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct GithubProvider {
+    client: Arc<GithubClient>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl GithubProvider {
+    pub fn new() -> Result<Self> {
+        let client = GithubClient::new()
+            .map_err(|e| eyre::eyre!("Failed to create GitHub client: {}", e))?;
+        Ok(Self {
+            client: Arc::new(client),
+            tool_router: Self::tool_router(),
+        })
+    }
+
+    #[tool(
+        name = "publish_to_github",
+        description = "Publish an app workspace to GitHub for review: commits all project files (excluding node_modules, build output and .env files) to a new branch and opens a pull request with the given title and summary. Only use after direct user request and running validation."
+    )]
+    pub async fn publish_to_github(
+        &self,
+        Parameters(args): Parameters<PublishToGithubArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = Path::new(&args.work_dir);
+        if !work_path.is_absolute() || !work_path.is_dir() {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "work_dir must be an absolute path to an existing directory, got: '{}'",
+                    args.work_dir
+                ),
+                None,
+            ));
+        }
+
+        match self.client.publish(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for GithubProvider {
+    fn get_info(&self) -> ServerInfo {
+        crate::mcp_helpers::internal_server_info()
+    }
+}
@@ -1,11 +1,12 @@
+use crate::mcp_helpers::structured_result;
 use edda_integrations::{
     FetchSpreadsheetDataRequest, GetSpreadsheetMetadataRequest, GoogleSheetsClient,
-    ReadRangeRequest, ToolResultDisplay,
+    ReadRangeRequest,
 };
 use eyre::Result;
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
-use rmcp::model::{CallToolResult, Content, ServerInfo};
+use rmcp::model::{CallToolResult, ServerInfo};
 use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
 use std::sync::Arc;
 
@@ -33,7 +34,7 @@ impl GoogleSheetsProvider {
         Parameters(args): Parameters<GetSpreadsheetMetadataRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         match self.client.get_spreadsheet_metadata(&args).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
+            Ok(result) => Ok(structured_result(&result, true)),
             Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
         }
     }
@@ -44,7 +45,7 @@ impl GoogleSheetsProvider {
         Parameters(args): Parameters<ReadRangeRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         match self.client.read_range(&args).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
+            Ok(result) => Ok(structured_result(&result, true)),
             Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
         }
     }
@@ -55,7 +56,7 @@ impl GoogleSheetsProvider {
         Parameters(args): Parameters<FetchSpreadsheetDataRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         match self.client.fetch_spreadsheet_data(&args).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(result.display())])),
+            Ok(result) => Ok(structured_result(&result, true)),
             Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
         }
     }
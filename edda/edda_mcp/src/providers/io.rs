@@ -1,24 +1,78 @@
-use crate::config::TemplateConfig;
+use crate::config::{DEFAULT_SANDBOX_EXECUTE_SECS, TemplateConfig, TimeoutsConfig};
+use crate::lint;
+use crate::mcp_helpers::structured_result;
+use crate::progress::ProgressReporter;
 use crate::state;
 use edda_integrations::ToolResultDisplay;
 use edda_sandbox::dagger::{ConnectOpts, Logger};
 use edda_sandbox::{DaggerConn, DaggerSandbox, Sandbox};
-use edda_templates::{LocalTemplate, Template, TemplateCore, TemplateTRPC};
+use edda_templates::merge::{self, MergeReport, UpgradeReport};
+use edda_templates::{
+    Hook, HookTarget, LocalTemplate, Template, TemplateCache, TemplateCapabilities, TemplateCore,
+    TemplatePyFastAPI, TemplatePyStreamlit, TemplateRustAxum, TemplateSource, TemplateTRPC,
+    TemplateVars,
+};
 use eyre::{Context, Result};
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{CallToolResult, Content, ServerInfo};
+use rmcp::service::{RequestContext, RoleServer};
 use rmcp::{ErrorData, ServerHandler, tool, tool_handler, tool_router};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Names of the built-in templates in the `template` argument of scaffold_data_app
+const TRPC_TEMPLATE: &str = "trpc";
+const STREAMLIT_TEMPLATE: &str = "streamlit";
+const FASTAPI_TEMPLATE: &str = "fastapi";
+const AXUM_TEMPLATE: &str = "axum";
+
+/// Aborts the wrapped task when dropped, so a cancelled validation doesn't leave the
+/// screenshot container running in the background.
+struct AbortOnDrop<T>(tokio::task::JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
+/// Default and maximum lifetime of a preview started by preview_data_app
+const DEFAULT_PREVIEW_TTL_MINUTES: u64 = 30;
+const MAX_PREVIEW_TTL_MINUTES: u64 = 240;
+
+/// A running preview; dropping it stops the app.
+struct Preview {
+    url: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    _task: AbortOnDrop<Result<()>>,
+}
+
+/// Screenshots exported to the work directory by `capture_screenshot`
+struct CapturedScreenshots {
+    screenshot_path: String,
+    route_screenshots: Vec<String>,
+    browser_logs: Option<String>,
+    /// `logs.txt` parsed, without the screenshot errors
+    log_entries: Vec<edda_screenshot::LogEntry>,
+    network_log: Option<NetworkLog>,
+    a11y_violations: Vec<edda_screenshot::A11yViolation>,
+    recording_path: Option<String>,
+}
 
 #[derive(Clone)]
 pub struct IOProvider {
     tool_router: ToolRouter<Self>,
     config: Option<crate::config::IoConfig>,
+    /// Running previews by work directory
+    previews: Arc<Mutex<HashMap<PathBuf, Preview>>>,
+    /// Dagger's execute timeout for the sandboxes of tool calls
+    execute_timeout: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -28,6 +82,32 @@ pub struct InitiateProjectArgs {
     /// If true, wipe the work directory before copying
     #[serde(default)]
     pub force_rewrite: bool,
+    /// Template to scaffold from: trpc (TypeScript), streamlit or fastapi (Python), axum
+    /// (Rust), or a configured one (default: the server's configured template)
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Name of the app, used for the package name and page title (default: the work
+    /// directory name)
+    #[serde(default)]
+    pub project_name: Option<String>,
+    /// One line description of the app
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Unity Catalog catalog holding the app's tables (default: main)
+    #[serde(default)]
+    pub databricks_catalog: Option<String>,
+    /// Schema of the app's tables within the catalog (default: default)
+    #[serde(default)]
+    pub databricks_schema: Option<String>,
+    /// Port the app listens on (default: the port screenshots expect, 8000)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// If true, don't run the template's post-scaffold hooks (e.g. dependency install)
+    #[serde(default)]
+    pub skip_hooks: bool,
+    /// If true, fetch a git or OCI template again instead of using the cached copy
+    #[serde(default)]
+    pub refresh_template: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,11 +117,14 @@ pub struct InitiateProjectResult {
     pub template_name: String,
     pub template_description: String,
     pub file_tree: String,
+    /// Post-scaffold hooks of the template, in the order they ran
+    #[serde(default)]
+    pub hooks: Vec<HookResult>,
 }
 
 impl ToolResultDisplay for InitiateProjectResult {
     fn display(&self) -> String {
-        format!(
+        let mut output = format!(
             "Successfully copied {} files from {} template to {}\n\nTemplate: {}\n\n{}\n\nFile structure:\n{}",
             self.files_copied,
             self.template_name,
@@ -49,6 +132,260 @@ impl ToolResultDisplay for InitiateProjectResult {
             self.template_name,
             self.template_description,
             self.file_tree
+        );
+        if !self.hooks.is_empty() {
+            output.push_str("\nSetup hooks:\n");
+            for hook in &self.hooks {
+                output.push_str(&hook.display());
+            }
+        }
+        output
+    }
+}
+
+/// Outcome of one post-scaffold hook. A failing hook doesn't fail the scaffold, its output
+/// is reported so the setup step can be fixed and rerun.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HookResult {
+    pub command: String,
+    pub run_in: HookTarget,
+    pub exit_code: isize,
+    /// Tail of stdout and stderr
+    pub output: String,
+}
+
+impl ToolResultDisplay for HookResult {
+    fn display(&self) -> String {
+        let status = match self.exit_code {
+            0 => "ok".to_string(),
+            code => format!("failed with exit code {}", code),
+        };
+        let mut output = format!("- `{}` ({}): {}\n", self.command, self.run_in, status);
+        if self.exit_code != 0 && !self.output.is_empty() {
+            output.push_str(&format!("```\n{}\n```\n", self.output.trim_end()));
+        }
+        output
+    }
+}
+
+/// Hook output kept in the result, from the end where errors usually are
+const HOOK_OUTPUT_CHARS: usize = 2000;
+
+/// Time a single hook may run, e.g. for a dependency install
+const HOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+fn output_tail(stdout: &str, stderr: &str) -> String {
+    let output = format!("{}{}", stdout, stderr);
+    let chars = output.chars().count();
+    match chars > HOOK_OUTPUT_CHARS {
+        true => output.chars().skip(chars - HOOK_OUTPUT_CHARS).collect(),
+        false => output,
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct AddFeatureArgs {
+    /// Absolute path to the scaffolded project (e.g., /path/to/project)
+    pub work_dir: String,
+    /// Feature to add: auth, charts or refresh_worker
+    pub feature: String,
+    /// Replace project files that differ from the feature's version (default: keep them)
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug)]
+pub struct AddFeatureResult {
+    pub feature: String,
+    pub work_dir: PathBuf,
+    pub report: MergeReport,
+    pub instructions: String,
+}
+
+impl ToolResultDisplay for AddFeatureResult {
+    fn display(&self) -> String {
+        let relative = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|p| {
+                    let p = p.strip_prefix(&self.work_dir).unwrap_or(p);
+                    format!("  {}", p.display())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut sections = vec![format!(
+            "Added feature '{}' to {}",
+            self.feature,
+            self.work_dir.display()
+        )];
+        for (title, paths) in [
+            ("Added files", &self.report.added),
+            ("Overwritten files", &self.report.overwritten),
+            ("Already up to date", &self.report.unchanged),
+        ] {
+            if !paths.is_empty() {
+                sections.push(format!("{}:\n{}", title, relative(paths)));
+            }
+        }
+        if !self.report.conflicts.is_empty() {
+            sections.push(format!(
+                "Kept existing files that differ from the feature's version (merge by hand or pass overwrite=true):\n{}",
+                relative(&self.report.conflicts)
+            ));
+        }
+        sections.push(self.instructions.clone());
+        sections.join("\n\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct UpgradeProjectArgs {
+    /// Absolute path to the scaffolded project (e.g., /path/to/project)
+    pub work_dir: String,
+    /// Template the project was scaffolded from (default: the server's configured template)
+    #[serde(default)]
+    pub template: Option<String>,
+    /// If true, fetch a git or OCI template again instead of using the cached copy
+    #[serde(default)]
+    pub refresh_template: bool,
+}
+
+#[derive(Debug)]
+pub struct UpgradeProjectResult {
+    pub template_name: String,
+    pub work_dir: PathBuf,
+    pub report: UpgradeReport,
+}
+
+impl ToolResultDisplay for UpgradeProjectResult {
+    fn display(&self) -> String {
+        let relative = |paths: &[PathBuf]| {
+            paths
+                .iter()
+                .map(|p| {
+                    let p = p.strip_prefix(&self.work_dir).unwrap_or(p);
+                    format!("  {}", p.display())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut sections = vec![format!(
+            "Upgraded {} to the current {} template",
+            self.work_dir.display(),
+            self.template_name
+        )];
+        for (title, paths) in [
+            ("Added files", &self.report.added),
+            ("Updated files", &self.report.updated),
+            ("Merged with project changes", &self.report.merged),
+            ("Removed files", &self.report.removed),
+        ] {
+            if !paths.is_empty() {
+                sections.push(format!("{}:\n{}", title, relative(paths)));
+            }
+        }
+        if self.report.conflicts.is_empty() {
+            sections.push("No conflicts, validate the project to check the upgrade.".to_string());
+        } else {
+            sections.push(format!(
+                "Conflicts, the project and the template changed the same lines. Resolve the <<<<<<< ours (project) / >>>>>>> theirs (template) markers, or merge by hand files deleted on one side, then validate:\n{}",
+                relative(&self.report.conflicts)
+            ));
+        }
+        sections.join("\n\n")
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct LintProjectArgs {
+    /// Absolute path to the work directory to lint (e.g., /path/to/project)
+    pub work_dir: String,
+    /// Apply automatic fixes and write them back to the project
+    #[serde(default)]
+    pub fix: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LintProjectResult {
+    pub diagnostics: Vec<lint::Diagnostic>,
+    pub fixed: bool,
+}
+
+impl ToolResultDisplay for LintProjectResult {
+    fn display(&self) -> String {
+        let errors = self
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == lint::Severity::Error)
+            .count();
+        let fixed = if self.fixed {
+            ", remaining after applying fixes"
+        } else {
+            ""
+        };
+        let mut msg = format!(
+            "{} lint errors, {} warnings{}",
+            errors,
+            self.diagnostics.len() - errors,
+            fixed
+        );
+        for diagnostic in &self.diagnostics {
+            msg.push_str(&format!("\n{}", diagnostic.display()));
+        }
+        msg
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct FormatProjectArgs {
+    /// Absolute path to the work directory to format (e.g., /path/to/project)
+    pub work_dir: String,
+    /// Only report unformatted files instead of rewriting them
+    #[serde(default)]
+    pub check: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FormatProjectResult {
+    /// Files that were (or, when checking, would be) reformatted, relative to the project
+    pub files: Vec<String>,
+    pub check: bool,
+}
+
+impl ToolResultDisplay for FormatProjectResult {
+    fn display(&self) -> String {
+        match (self.files.is_empty(), self.check) {
+            (true, _) => "All files are formatted".to_string(),
+            (false, true) => format!("Unformatted files:\n{}", self.files.join("\n")),
+            (false, false) => format!("Formatted files:\n{}", self.files.join("\n")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PreviewProjectArgs {
+    /// Absolute path to the work directory to preview (e.g., /path/to/project)
+    pub work_dir: String,
+    /// Minutes to keep the preview running (default 30, max 240)
+    #[serde(default)]
+    pub ttl_minutes: Option<u64>,
+    /// Stop the running preview of this project instead of starting one
+    #[serde(default)]
+    pub stop: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewProjectResult {
+    pub url: String,
+    pub expires_at: String,
+}
+
+impl ToolResultDisplay for PreviewProjectResult {
+    fn display(&self) -> String {
+        format!(
+            "App preview running at {} until {}. Share the URL with the user to click through the app; call preview_data_app again after changes to rebuild it.",
+            self.url, self.expires_at
         )
     }
 }
@@ -57,6 +394,15 @@ impl ToolResultDisplay for InitiateProjectResult {
 pub struct ValidateProjectArgs {
     /// Absolute path to the work directory to validate (e.g., /path/to/project)
     pub work_dir: String,
+    /// Run every check even for parts of the project unchanged since the last successful validation
+    #[serde(default)]
+    pub full: bool,
+    /// CSS selector of one element to screenshot instead of the whole page, e.g. "#revenue-chart"
+    #[serde(default)]
+    pub screenshot_selector: Option<String>,
+    /// If true, make the screenshot the new visual baseline even if it looks different
+    #[serde(default)]
+    pub accept_visual_changes: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,7 +411,40 @@ pub struct ValidateProjectResult {
     pub message: String,
     pub details: Option<ValidationDetails>,
     pub screenshot_path: Option<String>,
+    /// Screenshots of the configured extra routes and viewports, and their contact sheet
+    #[serde(default)]
+    pub route_screenshots: Vec<String>,
     pub browser_logs: Option<String>,
+    /// Requests made while capturing, when `screenshot.record_network` is set
+    #[serde(default)]
+    pub network_log: Option<NetworkLog>,
+    /// Accessibility violations of the captured pages, most severe first
+    #[serde(default)]
+    pub a11y_warnings: Vec<edda_screenshot::A11yViolation>,
+    /// Video or Playwright trace of the app loading, when `screenshot.recording` is set
+    #[serde(default)]
+    pub recording_path: Option<String>,
+    /// How the screenshot differs from the one of the last successful validation
+    #[serde(default)]
+    pub visual_diff: Option<VisualDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NetworkLog {
+    /// Every request as JSON, with its status and timing
+    pub path: String,
+    /// Failed and slow requests
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VisualDiff {
+    pub report: edda_screenshot::DiffReport,
+    /// The new screenshot with the changed regions outlined
+    pub diff_path: Option<String>,
+    /// Whether the new screenshot replaced the baseline
+    #[serde(default)]
+    pub accepted: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,9 +461,51 @@ impl ToolResultDisplay for ValidateProjectResult {
             if let Some(screenshot) = &self.screenshot_path {
                 msg.push_str(&format!("\n\nScreenshot: {}, review it to make sure the app is visually fine.", screenshot));
             }
+            if !self.route_screenshots.is_empty() {
+                msg.push_str(&format!(
+                    "\n\nMore screenshots:\n{}",
+                    self.route_screenshots.join("\n")
+                ));
+            }
+            if !self.a11y_warnings.is_empty() {
+                msg.push_str(&format!(
+                    "\n\nAccessibility warnings ({}), fix the serious and critical ones:\n{}",
+                    self.a11y_warnings.len(),
+                    edda_screenshot::a11y_summary(&self.a11y_warnings)
+                ));
+            }
+            if let Some(diff) = &self.visual_diff {
+                msg.push_str(&format!(
+                    "\n\nThe app looks different than at the last successful validation: {}",
+                    diff.report.summary()
+                ));
+                if let Some(path) = &diff.diff_path {
+                    msg.push_str(&format!(
+                        "\nChanged regions are outlined in {}, make sure every change is intended.",
+                        path
+                    ));
+                }
+                if !diff.accepted {
+                    msg.push_str(
+                        "\nThe baseline is kept until you validate with accept_visual_changes=true.",
+                    );
+                }
+            }
             if let Some(logs) = &self.browser_logs {
                 msg.push_str(&format!("\n\nBrowser console logs:\n{}", logs));
             }
+            if let Some(network) = &self.network_log {
+                msg.push_str(&format!(
+                    "\n\nNetwork activity ({}):\n{}",
+                    network.path, network.summary
+                ));
+            }
+            if let Some(recording) = &self.recording_path {
+                msg.push_str(&format!("\n\nRecording of the app loading: {}", recording));
+                if recording.ends_with(edda_screenshot::Recording::Trace.file()) {
+                    msg.push_str(" (open it with `npx playwright show-trace`)");
+                }
+            }
             msg
         } else {
             let mut msg = format!("Validation failed: {}", self.message);
@@ -97,6 +518,18 @@ impl ToolResultDisplay for ValidateProjectResult {
             if let Some(logs) = &self.browser_logs {
                 msg.push_str(&format!("\n\nBrowser console logs:\n{}", logs));
             }
+            if let Some(network) = &self.network_log {
+                msg.push_str(&format!(
+                    "\n\nNetwork activity ({}):\n{}",
+                    network.path, network.summary
+                ));
+            }
+            if let Some(recording) = &self.recording_path {
+                msg.push_str(&format!("\n\nRecording of the app loading: {}", recording));
+                if recording.ends_with(edda_screenshot::Recording::Trace.file()) {
+                    msg.push_str(" (open it with `npx playwright show-trace`)");
+                }
+            }
             msg
         }
     }
@@ -108,36 +541,171 @@ impl IOProvider {
         Ok(Self {
             tool_router: Self::tool_router(),
             config,
+            previews: Arc::new(Mutex::new(HashMap::new())),
+            execute_timeout: Some(DEFAULT_SANDBOX_EXECUTE_SECS),
         })
     }
 
-    /// Get the template to use based on the config
-    fn get_template(&self) -> TemplateFiles {
-        match self.config {
-            Some(ref cfg) => match &cfg.template {
-                TemplateConfig::Trpc => TemplateFiles::Trpc(TemplateTRPC),
-                TemplateConfig::Custom { name, path } => {
-                    let path = std::path::PathBuf::from(path);
-                    let template = LocalTemplate::from_dir(&name, &path).unwrap();
-                    TemplateFiles::Local(template)
+    /// Let sandboxes run as long as the longest configured time limit allows
+    pub fn with_timeouts(mut self, timeouts: &TimeoutsConfig) -> Self {
+        self.execute_timeout = timeouts.sandbox_execute_secs();
+        self
+    }
+
+    /// Names accepted by the `template` argument of scaffold_data_app
+    fn template_names(&self) -> Vec<String> {
+        let mut names = vec![
+            TRPC_TEMPLATE.to_string(),
+            STREAMLIT_TEMPLATE.to_string(),
+            FASTAPI_TEMPLATE.to_string(),
+            AXUM_TEMPLATE.to_string(),
+        ];
+        if let Some(cfg) = &self.config {
+            if let TemplateConfig::Custom { name, .. } = &cfg.template {
+                names.push(name.clone());
+            }
+            names.extend(cfg.templates.keys().cloned());
+        }
+        names
+    }
+
+    /// `get_template` off the async runtime, fetching a remote template runs git or oras
+    async fn fetch_template(
+        &self,
+        name: Option<String>,
+        refresh: bool,
+    ) -> Result<TemplateFiles, ErrorData> {
+        let provider = self.clone();
+        tokio::task::spawn_blocking(move || provider.get_template(name.as_deref(), refresh))
+            .await
+            .map_err(|e| {
+                ErrorData::internal_error(format!("Loading template failed: {}", e), None)
+            })?
+    }
+
+    /// Get the template to use: the requested one by name, otherwise the configured default.
+    /// With `refresh` git and OCI templates are fetched again.
+    fn get_template(&self, name: Option<&str>, refresh: bool) -> Result<TemplateFiles, ErrorData> {
+        let default = self.config.as_ref().map(|cfg| &cfg.template);
+        match (name, default) {
+            (None, Some(TemplateConfig::Custom { name, path })) => {
+                Self::template_from_source(name, path, refresh)
+            }
+            (None, Some(TemplateConfig::Streamlit)) => {
+                Ok(TemplateFiles::Streamlit(TemplatePyStreamlit))
+            }
+            (None, Some(TemplateConfig::FastApi)) => Ok(TemplateFiles::FastApi(TemplatePyFastAPI)),
+            (None, Some(TemplateConfig::Axum)) => Ok(TemplateFiles::Axum(TemplateRustAxum)),
+            (None, _) => Ok(TemplateFiles::Trpc(TemplateTRPC)),
+            (Some(name), _) if name.eq_ignore_ascii_case(TRPC_TEMPLATE) => {
+                Ok(TemplateFiles::Trpc(TemplateTRPC))
+            }
+            (Some(name), _) if name.eq_ignore_ascii_case(STREAMLIT_TEMPLATE) => {
+                Ok(TemplateFiles::Streamlit(TemplatePyStreamlit))
+            }
+            (Some(name), _) if name.eq_ignore_ascii_case(FASTAPI_TEMPLATE) => {
+                Ok(TemplateFiles::FastApi(TemplatePyFastAPI))
+            }
+            (Some(name), _) if name.eq_ignore_ascii_case(AXUM_TEMPLATE) => {
+                Ok(TemplateFiles::Axum(TemplateRustAxum))
+            }
+            (Some(requested), Some(TemplateConfig::Custom { name, path })) if requested == name => {
+                Self::template_from_source(name, path, refresh)
+            }
+            (Some(requested), _) => {
+                let named = self
+                    .config
+                    .as_ref()
+                    .and_then(|cfg| cfg.templates.get(requested));
+                match named {
+                    Some(path) => Self::template_from_source(requested, path, refresh),
+                    None => Err(ErrorData::invalid_params(
+                        format!(
+                            "Unknown template '{}'. Available templates: {}",
+                            requested,
+                            self.template_names().join(", ")
+                        ),
+                        None,
+                    )),
                 }
-            },
-            None => TemplateFiles::Trpc(TemplateTRPC),
+            }
         }
     }
 
-    fn get_validation_strategy(&self) -> Box<dyn validation::ValidationDyn> {
-        use validation::Validation;
+    /// Capability manifests of the default template and the named ones from the config,
+    /// by template name ("default" for the default one)
+    pub fn template_capabilities(&self) -> Vec<(String, Result<TemplateCapabilities>)> {
+        let mut names = vec![None];
         if let Some(cfg) = &self.config {
-            if let Some(val_config) = &cfg.validation {
-                return validation::ValidationCmd {
-                    command: val_config.command.clone(),
-                    docker_image: val_config.docker_image.clone(),
-                }
-                .boxed();
+            names.extend(cfg.templates.keys().map(|name| Some(name.as_str())));
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                let capabilities = self
+                    .get_template(name, false)
+                    .map_err(|e| eyre::eyre!("{}", e.message))
+                    .and_then(|template| template.capabilities());
+                (name.unwrap_or("default").to_string(), capabilities)
+            })
+            .collect()
+    }
+
+    /// Load a configured template, fetching git and OCI sources into the template cache
+    /// on first use or when `refresh` is set
+    fn template_from_source(
+        name: &str,
+        source: &str,
+        refresh: bool,
+    ) -> Result<TemplateFiles, ErrorData> {
+        let internal = |e: eyre::Report| ErrorData::internal_error(format!("{:#}", e), None);
+        let source: TemplateSource = source.parse().map_err(internal)?;
+        match &source {
+            TemplateSource::Embedded => Ok(TemplateFiles::Trpc(TemplateTRPC)),
+            TemplateSource::LocalDir(path) => LocalTemplate::from_dir(name, path)
+                .map(TemplateFiles::Local)
+                .map_err(internal),
+            TemplateSource::GitRepo { .. } | TemplateSource::OciArtifact { .. } => {
+                let cache_dir = crate::paths::template_cache_dir().map_err(internal)?;
+                tracing::info!("Loading template {} from {}", name, source);
+                let dir = TemplateCache::new(cache_dir)
+                    .fetch(&source, refresh)
+                    .map_err(internal)?;
+                LocalTemplate::from_dir(name, &dir)
+                    .map(TemplateFiles::Fetched)
+                    .map_err(internal)
             }
         }
-        validation::ValidationTRPC.boxed()
+    }
+
+    /// The configured validation command, otherwise the built-in strategy for the project
+    /// Validation of the project at `work_dir`. The configured validation command is for the
+    /// default template, projects scaffolded from another one are validated as their
+    /// template says.
+    fn get_validation_strategy(&self, work_dir: &Path) -> Box<dyn validation::ValidationDyn> {
+        use validation::Validation;
+        if let Some(val_config) = self.config.as_ref().and_then(|cfg| cfg.validation.as_ref())
+            && merge::base_template(work_dir)
+                .is_none_or(|name| name == self.default_template_name())
+        {
+            return validation::ValidationCmd {
+                command: val_config.command.clone(),
+                docker_image: val_config.docker_image.clone(),
+            }
+            .boxed();
+        }
+        validation::for_project(work_dir)
+    }
+
+    /// Name the configured default template records in the projects scaffolded from it
+    fn default_template_name(&self) -> String {
+        match self.config.as_ref().map(|cfg| &cfg.template) {
+            Some(TemplateConfig::Custom { name, .. }) => name.clone(),
+            Some(TemplateConfig::Streamlit) => TemplatePyStreamlit.name(),
+            Some(TemplateConfig::FastApi) => TemplatePyFastAPI.name(),
+            Some(TemplateConfig::Axum) => TemplateRustAxum.name(),
+            Some(TemplateConfig::Trpc) | None => TemplateTRPC.name(),
+        }
     }
 
     /// Core logic for initiating a project from template.
@@ -146,8 +714,11 @@ impl IOProvider {
     pub fn initiate_project_impl(
         work_dir: &Path,
         template: impl Template,
+        vars: &TemplateVars,
         force_rewrite: bool,
     ) -> Result<InitiateProjectResult> {
+        vars.validate()?;
+
         // handle force rewrite
         if force_rewrite {
             match std::fs::remove_dir_all(work_dir) {
@@ -168,7 +739,9 @@ impl IOProvider {
 
         let template_name = template.name().to_string();
         let template_description = template.description().unwrap_or("".to_string());
-        let files = template.extract(work_dir)?;
+        let files = template.extract(work_dir, vars)?;
+        // the ancestor of a later upgrade_project
+        merge::save_base(work_dir, &template_name, vars, &files)?;
 
         // generate file tree
         let file_tree = Self::generate_file_tree(work_dir, &files)?;
@@ -179,9 +752,114 @@ impl IOProvider {
             template_name,
             template_description,
             file_tree,
+            hooks: Vec::new(),
         })
     }
 
+    /// Run post-scaffold hooks in `work_dir`, in order. Sandbox hooks each get a fresh
+    /// sandbox from the current state of `work_dir` in `docker_image`, with their `export`
+    /// paths copied back so later hooks see them. Host hooks are skipped unless
+    /// `allow_host`, and a hook running longer than [`HOOK_TIMEOUT`] is stopped.
+    pub async fn run_hooks_impl(
+        work_dir: &Path,
+        hooks: &[Hook],
+        docker_image: &str,
+        allow_host: bool,
+    ) -> Result<Vec<HookResult>> {
+        let failed = |message: String| edda_sandbox::ExecResult {
+            exit_code: -1,
+            stdout: String::new(),
+            stderr: message,
+        };
+        let timed_out = || failed(format!("timed out after {}s", HOOK_TIMEOUT.as_secs()));
+        let mut results = Vec::with_capacity(hooks.len());
+        for hook in hooks {
+            let result = match hook.run_in {
+                HookTarget::Host if !allow_host => failed(
+                    "skipped: host hooks of templates fetched from git or OCI only run with io_config.allow_host_hooks"
+                        .to_string(),
+                ),
+                HookTarget::Host => {
+                    let mut command = tokio::process::Command::new("sh");
+                    command
+                        .arg("-c")
+                        .arg(&hook.command)
+                        .current_dir(work_dir)
+                        .kill_on_drop(true);
+                    match tokio::time::timeout(HOOK_TIMEOUT, command.output()).await {
+                        Ok(output) => {
+                            let output = output.wrap_err_with(|| {
+                                format!("failed to run hook `{}`", hook.command)
+                            })?;
+                            edda_sandbox::ExecResult {
+                                exit_code: output.status.code().unwrap_or(-1) as isize,
+                                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                            }
+                        }
+                        Err(_) => timed_out(),
+                    }
+                }
+                HookTarget::Sandbox => {
+                    let command = format!("cd /app && {}", hook.command);
+                    let export = hook.export.clone();
+                    let host_dir = work_dir.to_path_buf();
+                    let run = Self::with_sandbox(
+                        work_dir,
+                        docker_image.to_string(),
+                        Some(HOOK_TIMEOUT.as_secs()),
+                        move |mut sandbox| async move {
+                            let result = sandbox.exec(&command).await?;
+                            if result.exit_code == 0 {
+                                for path in export {
+                                    Self::export_path(&mut sandbox, &host_dir, &path).await?;
+                                }
+                            }
+                            Ok(result)
+                        },
+                    );
+                    match tokio::time::timeout(HOOK_TIMEOUT, run).await {
+                        Ok(result) => result?,
+                        Err(_) => timed_out(),
+                    }
+                }
+            };
+            results.push(HookResult {
+                command: hook.command.clone(),
+                run_in: hook.run_in,
+                exit_code: result.exit_code,
+                output: output_tail(&result.stdout, &result.stderr),
+            });
+        }
+        Ok(results)
+    }
+
+    /// Copy `path` (relative to /app) from the sandbox into `host_dir`
+    async fn export_path(sandbox: &mut DaggerSandbox, host_dir: &Path, path: &str) -> Result<()> {
+        let container_path = format!("/app/{}", path);
+        let host_path = host_dir.join(path);
+        let is_dir = sandbox
+            .exec(&format!("test -d '{}'", container_path))
+            .await?
+            .exit_code
+            == 0;
+        if is_dir {
+            sandbox
+                .export_directory(&container_path, &host_path.display().to_string())
+                .await?;
+            return Ok(());
+        }
+        let content = sandbox
+            .read_file(&container_path)
+            .await
+            .wrap_err_with(|| format!("hook did not produce {}", path))?;
+        if let Some(parent) = host_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&host_path, content)?;
+        Ok(())
+    }
+
     /// Generate a tree-style visualization of the file structure
     /// Collapses directories with more than 10 files to avoid clutter
     fn generate_file_tree(_base_dir: &Path, files: &[PathBuf]) -> Result<String> {
@@ -196,90 +874,609 @@ impl IOProvider {
             let path_str = file.to_string_lossy().to_string();
             let parts: Vec<&str> = path_str.split('/').collect();
 
-            if parts.len() == 1 {
-                // root level file
-                tree.entry("".to_string())
-                    .or_insert_with(Vec::new)
-                    .push(parts[0].to_string());
-            } else {
-                // file in subdirectory
-                let dir = parts[..parts.len() - 1].join("/");
-                let file_name = parts[parts.len() - 1].to_string();
-                tree.entry(dir)
-                    .or_insert_with(Vec::new)
-                    .push(file_name);
-            }
+            if parts.len() == 1 {
+                // root level file
+                tree.entry("".to_string())
+                    .or_insert_with(Vec::new)
+                    .push(parts[0].to_string());
+            } else {
+                // file in subdirectory
+                let dir = parts[..parts.len() - 1].join("/");
+                let file_name = parts[parts.len() - 1].to_string();
+                tree.entry(dir)
+                    .or_insert_with(Vec::new)
+                    .push(file_name);
+            }
+        }
+
+        // format as tree
+        let mut output = String::new();
+        let mut sorted_dirs: Vec<_> = tree.keys().collect();
+        sorted_dirs.sort();
+
+        for dir in sorted_dirs {
+            let files_in_dir = &tree[dir];
+            if dir.is_empty() {
+                // root files - always show all
+                for file in files_in_dir {
+                    output.push_str(&format!("{}\n", file));
+                }
+            } else {
+                // directory
+                output.push_str(&format!("{}/\n", dir));
+                if files_in_dir.len() <= MAX_FILES_TO_SHOW {
+                    // show all files
+                    for file in files_in_dir {
+                        output.push_str(&format!("  {}\n", file));
+                    }
+                } else {
+                    // collapse large directories
+                    output.push_str(&format!("  ({} files)\n", files_in_dir.len()));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    #[tool(
+        name = "scaffold_data_app",
+        description = "Initialize a project by copying template files from the default TypeScript (tRPC + React) template to a work directory. Pass `template` to use another one: `streamlit` or `fastapi` for Python apps, `axum` for a Rust backend, or a configured template; an unknown name returns the available ones. Pass project_name, description, databricks_catalog and databricks_schema to have them filled into the scaffold. Supports force rewrite to wipe and recreate the directory. Setup hooks declared by the template (e.g. generating a lockfile) run after copying and their output is returned; pass skip_hooks to skip them. It sets up a basic project structure, and should be ALWAYS used as the first step in creating a new data or web app."
+    )]
+    pub async fn scaffold_data_app(
+        &self,
+        Parameters(args): Parameters<InitiateProjectArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = PathBuf::from(&args.work_dir);
+
+        // validate that the path is absolute
+        if !work_path.is_absolute() {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "work_dir must be an absolute path, got: '{}'. Relative paths are not supported",
+                    args.work_dir
+                ),
+                None,
+            ));
+        }
+
+        let template = self
+            .fetch_template(args.template.clone(), args.refresh_template)
+            .await?;
+        let capabilities = template.capabilities().map_err(|e| {
+            ErrorData::internal_error(format!("failed to read template manifest: {}", e), None)
+        })?;
+        let hooks = match args.skip_hooks {
+            true => Vec::new(),
+            false => capabilities.hooks.clone(),
+        };
+        // templates fetched from git or OCI can't run commands on the host unless allowed
+        let allow_host_hooks = !matches!(template, TemplateFiles::Fetched(_))
+            || self.config.as_ref().is_some_and(|cfg| cfg.allow_host_hooks);
+        let progress = ProgressReporter::new(&context, Some(2 + hooks.len() as u32));
+        progress
+            .step(format!("Copying {} template", template.name()))
+            .await;
+        let vars = TemplateVars {
+            project_name: args.project_name,
+            description: args.description,
+            databricks_catalog: args.databricks_catalog,
+            databricks_schema: args.databricks_schema,
+            // the app has to listen where screenshots and previews connect
+            port: args.port.or_else(|| {
+                self.config
+                    .as_ref()
+                    .and_then(|cfg| cfg.screenshot.as_ref())
+                    .and_then(|c| c.port)
+            }),
+        };
+        let mut result =
+            Self::initiate_project_impl(&work_path, template, &vars, args.force_rewrite).map_err(
+                |e| ErrorData::internal_error(format!("failed to initiate project: {}", e), None),
+            )?;
+        progress
+            .step(format!("Copied {} files", result.files_copied))
+            .await;
+
+        let docker_image = capabilities.sandbox_image();
+        for hook in &hooks {
+            progress
+                .step(format!("Running `{}` ({})", hook.command, hook.run_in))
+                .await;
+            let hook_results = Self::run_hooks_impl(
+                &work_path,
+                std::slice::from_ref(hook),
+                &docker_image,
+                allow_host_hooks,
+            )
+            .await
+            .map_err(|e| {
+                ErrorData::internal_error(
+                    format!("failed to run hook `{}`: {}", hook.command, e),
+                    None,
+                )
+            })?;
+            result.hooks.extend(hook_results);
+        }
+
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "add_feature",
+        description = "Add an optional feature to an already scaffolded project: auth (Databricks Apps user identity), charts (dashboard chart components) or refresh_worker (scheduled background refresh of query results). Adds the feature's files without touching existing ones and returns instructions for wiring it up."
+    )]
+    pub async fn add_feature(
+        &self,
+        Parameters(args): Parameters<AddFeatureArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = PathBuf::from(&args.work_dir);
+        if !work_path.is_absolute() {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "work_dir must be an absolute path, got: '{}'. Relative paths are not supported",
+                    args.work_dir
+                ),
+                None,
+            ));
+        }
+        let Some(instructions) = merge::feature_instructions(&args.feature) else {
+            let available: Vec<String> = merge::features()
+                .into_iter()
+                .map(|f| format!("{} ({})", f.name, f.summary))
+                .collect();
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "Unknown feature '{}'. Available features:\n{}",
+                    args.feature,
+                    available.join("\n")
+                ),
+                None,
+            ));
+        };
+
+        let report = merge::merge_feature(&work_path, &args.feature, args.overwrite)
+            .map_err(|e| ErrorData::internal_error(format!("failed to add feature: {}", e), None))?;
+        let result = AddFeatureResult {
+            feature: args.feature,
+            work_dir: work_path,
+            report,
+            instructions,
+        };
+        Ok(CallToolResult::success(vec![Content::text(
+            result.display(),
+        )]))
+    }
+
+    #[tool(
+        name = "upgrade_project",
+        description = "Upgrade a scaffolded project to the current version of its template. Template changes are merged into the project while keeping the changes made since scaffolding; where both changed the same lines the file gets conflict markers to resolve. Validate the project afterwards."
+    )]
+    pub async fn upgrade_project(
+        &self,
+        Parameters(args): Parameters<UpgradeProjectArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = PathBuf::from(&args.work_dir);
+        if !work_path.is_absolute() {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "work_dir must be an absolute path, got: '{}'. Relative paths are not supported",
+                    args.work_dir
+                ),
+                None,
+            ));
+        }
+        let template = self
+            .fetch_template(args.template.clone(), args.refresh_template)
+            .await?;
+        let report = merge::upgrade_project(&work_path, &template).map_err(|e| {
+            ErrorData::internal_error(format!("failed to upgrade project: {}", e), None)
+        })?;
+        let result = UpgradeProjectResult {
+            template_name: template.name(),
+            work_dir: work_path,
+            report,
+        };
+        Ok(CallToolResult::success(vec![Content::text(
+            result.display(),
+        )]))
+    }
+
+    /// Run `f` against a sandbox holding a copy of `work_dir`, set up like validation's.
+    async fn with_sandbox<T, F, Fut>(
+        work_dir: &Path,
+        docker_image: String,
+        execute_timeout: Option<u64>,
+        f: F,
+    ) -> Result<T>
+    where
+        T: 'static,
+        F: FnOnce(DaggerSandbox) -> Fut + 'static,
+        Fut: Future<Output = Result<T>>,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let work_dir_str = work_dir.display().to_string();
+        let opts = ConnectOpts::default()
+            .with_logger(Logger::Silent)
+            .with_execute_timeout(execute_timeout);
+
+        let connect_result = opts
+            .connect(move |client| async move {
+                let host_dir = client.host().directory(work_dir_str);
+                let container = client
+                    .container()
+                    .from(&docker_image)
+                    .with_directory("/app", host_dir);
+                let sandbox = DaggerSandbox::from_container(container, client);
+                let _ = tx.send(f(sandbox).await);
+                Ok(())
+            })
+            .await;
+
+        if let Err(e) = connect_result {
+            eyre::bail!("failed to connect to dagger: {}", e);
+        }
+
+        rx.await
+            .map_err(|_| eyre::eyre!("sandbox task was cancelled"))?
+    }
+
+    /// Packages of the project that exist on disk (client/, server/) with their lint target
+    fn lint_packages(work_dir: &Path) -> Vec<(&'static str, &'static str)> {
+        lint::PACKAGES
+            .iter()
+            .filter(|(package, _)| work_dir.join(package).join("package.json").exists())
+            .copied()
+            .collect()
+    }
+
+    fn absolute_work_dir(work_dir: &str) -> Result<PathBuf, ErrorData> {
+        let work_path = PathBuf::from(work_dir);
+        if !work_path.is_absolute() {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "work_dir must be an absolute path, got: '{}'. Relative paths are not supported",
+                    work_dir
+                ),
+                None,
+            ));
+        }
+        if !work_path.is_dir() {
+            return Err(ErrorData::invalid_params(
+                format!("work directory does not exist: {}", work_dir),
+                None,
+            ));
+        }
+        Ok(work_path)
+    }
+
+    pub async fn lint_project_impl(
+        work_dir: &Path,
+        docker_image: String,
+        fix: bool,
+        execute_timeout: Option<u64>,
+    ) -> Result<LintProjectResult> {
+        let packages: Vec<(&str, &str, lint::Linter)> = Self::lint_packages(work_dir)
+            .into_iter()
+            .map(|(package, target)| {
+                (
+                    package,
+                    target,
+                    lint::Linter::detect(&work_dir.join(package)),
+                )
+            })
+            .collect();
+        if packages.is_empty() {
+            eyre::bail!(
+                "no client/ or server/ package found in {}",
+                work_dir.display()
+            );
+        }
+
+        let host_dir = work_dir.to_path_buf();
+        let diagnostics = Self::with_sandbox(
+            work_dir,
+            docker_image,
+            execute_timeout,
+            move |mut sandbox| async move {
+                let mut diagnostics = Vec::new();
+                for (package, target, linter) in packages {
+                    let before = match fix {
+                        true => Some(sandbox.exec(&lint::checksum_command(package)).await?.stdout),
+                        false => None,
+                    };
+                    let result = sandbox.exec(&linter.command(package, target, fix)).await?;
+                    let found = linter.parse(package, &result.stdout);
+                    // linters exit non-zero when they report problems, so only treat it as a
+                    // failure when nothing could be parsed
+                    if result.exit_code != 0 && found.is_empty() {
+                        eyre::bail!(
+                            "{} linter failed in {}/ (exit code {}):\n{}{}",
+                            match linter {
+                                lint::Linter::Eslint => "eslint",
+                                lint::Linter::Biome => "biome",
+                            },
+                            package,
+                            result.exit_code,
+                            result.stdout,
+                            result.stderr
+                        );
+                    }
+                    // fixes can touch config files at the package root as well as src/
+                    if let Some(before) = before {
+                        let after = sandbox.exec(&lint::checksum_command(package)).await?.stdout;
+                        for path in lint::changed_files(package, &before, &after) {
+                            Self::export_path(&mut sandbox, &host_dir, &path).await?;
+                        }
+                    }
+                    diagnostics.extend(found);
+                }
+                Ok(diagnostics)
+            },
+        )
+        .await?;
+
+        Ok(LintProjectResult {
+            diagnostics,
+            fixed: fix,
+        })
+    }
+
+    pub async fn format_project_impl(
+        work_dir: &Path,
+        docker_image: String,
+        check: bool,
+        execute_timeout: Option<u64>,
+    ) -> Result<FormatProjectResult> {
+        let packages = Self::lint_packages(work_dir);
+        if packages.is_empty() {
+            eyre::bail!(
+                "no client/ or server/ package found in {}",
+                work_dir.display()
+            );
+        }
+
+        let host_dir = work_dir.display().to_string();
+        let files = Self::with_sandbox(
+            work_dir,
+            docker_image,
+            execute_timeout,
+            move |mut sandbox| async move {
+                let mut files = Vec::new();
+                for (package, _) in packages {
+                    let result = sandbox.exec(&lint::format_command(package, check)).await?;
+                    let listed = lint::parse_prettier(package, &result.stdout);
+                    // with --list-different prettier exits 1 when it lists files
+                    if result.exit_code != 0 && listed.is_empty() {
+                        eyre::bail!(
+                            "prettier failed in {}/ (exit code {}):\n{}{}",
+                            package,
+                            result.exit_code,
+                            result.stdout,
+                            result.stderr
+                        );
+                    }
+                    if !check && !listed.is_empty() {
+                        sandbox
+                            .export_directory(
+                                &format!("/app/{}/src", package),
+                                &format!("{}/{}/src", host_dir, package),
+                            )
+                            .await?;
+                    }
+                    files.extend(listed);
+                }
+                Ok(files)
+            },
+        )
+        .await?;
+
+        Ok(FormatProjectResult { files, check })
+    }
+
+    #[tool(
+        name = "lint_data_app",
+        description = "Lint a scaffolded project's client and server code (eslint, or biome when configured) in the validation sandbox. Returns diagnostics with file, line, column and rule. Much faster than validate_data_app for fixing style issues; pass fix=true to apply automatic fixes to the project."
+    )]
+    pub async fn lint_data_app(
+        &self,
+        Parameters(args): Parameters<LintProjectArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = Self::absolute_work_dir(&args.work_dir)?;
+        let docker_image = self.get_validation_strategy(&work_path).docker_image();
+        let result =
+            Self::lint_project_impl(&work_path, docker_image, args.fix, self.execute_timeout)
+                .await
+                .map_err(|e| {
+                    ErrorData::internal_error(format!("failed to lint project: {}", e), None)
+                })?;
+
+        let has_errors = result
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == lint::Severity::Error);
+        Ok(structured_result(&result, !has_errors))
+    }
+
+    #[tool(
+        name = "format_data_app",
+        description = "Format a scaffolded project's client and server sources with prettier in the validation sandbox and write the changes back. Pass check=true to only list unformatted files."
+    )]
+    pub async fn format_data_app(
+        &self,
+        Parameters(args): Parameters<FormatProjectArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let work_path = Self::absolute_work_dir(&args.work_dir)?;
+        let docker_image = self.get_validation_strategy(&work_path).docker_image();
+        let result =
+            Self::format_project_impl(&work_path, docker_image, args.check, self.execute_timeout)
+                .await
+                .map_err(|e| {
+                    ErrorData::internal_error(format!("failed to format project: {}", e), None)
+                })?;
+
+        let success = !args.check || result.files.is_empty();
+        Ok(structured_result(&result, success))
+    }
+
+    /// DATABRICKS_* variables passed to the app container when running it
+    fn app_env_vars() -> Vec<(String, String)> {
+        let mut vars = vec![];
+        if let Ok(host) = edda_integrations::env::var("DATABRICKS_HOST") {
+            vars.push(("DATABRICKS_HOST".to_string(), host));
+        }
+        if let Ok(token) = edda_integrations::env::var("DATABRICKS_TOKEN") {
+            vars.push(("DATABRICKS_TOKEN".to_string(), token));
+        }
+        if let Ok(warehouse_id) = edda_integrations::env::var("DATABRICKS_WAREHOUSE_ID") {
+            vars.push(("DATABRICKS_WAREHOUSE_ID".to_string(), warehouse_id));
+        }
+        vars
+    }
+
+    /// Build the app and serve it on a free local port until `ttl` elapses. Returns once
+    /// the port accepts connections.
+    pub async fn start_preview(
+        &self,
+        work_dir: &Path,
+        ttl: std::time::Duration,
+    ) -> Result<PreviewProjectResult> {
+        let dockerfile_path = work_dir.join("Dockerfile");
+        if !dockerfile_path.exists() {
+            eyre::bail!(
+                "Dockerfile required for preview. Expected at: {}",
+                dockerfile_path.display()
+            );
         }
 
-        // format as tree
-        let mut output = String::new();
-        let mut sorted_dirs: Vec<_> = tree.keys().collect();
-        sorted_dirs.sort();
+        // replacing a preview of the same project stops the old one
+        self.previews.lock().await.remove(work_dir);
 
-        for dir in sorted_dirs {
-            let files_in_dir = &tree[dir];
-            if dir.is_empty() {
-                // root files - always show all
-                for file in files_in_dir {
-                    output.push_str(&format!("{}\n", file));
-                }
-            } else {
-                // directory
-                output.push_str(&format!("{}/\n", dir));
-                if files_in_dir.len() <= MAX_FILES_TO_SHOW {
-                    // show all files
-                    for file in files_in_dir {
-                        output.push_str(&format!("  {}\n", file));
-                    }
-                } else {
-                    // collapse large directories
-                    output.push_str(&format!("  ({} files)\n", files_in_dir.len()));
-                }
+        let host_port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let screenshot_config = self.config.as_ref().and_then(|c| c.screenshot.as_ref());
+        let options = edda_screenshot::ScreenshotOptions {
+            port: Self::app_port(work_dir, screenshot_config),
+            env_vars: Self::app_env_vars(),
+            ..Default::default()
+        };
+        let work_dir_str = work_dir.display().to_string();
+        let mut task = AbortOnDrop(tokio::spawn(async move {
+            let opts = ConnectOpts::default().with_logger(Logger::Silent);
+            let serve = opts.connect(move |client| async move {
+                let app_source = client.host().directory(work_dir_str);
+                edda_screenshot::serve_app(app_source, options, host_port).await
+            });
+            match tokio::time::timeout(ttl, serve).await {
+                Ok(Err(e)) => Err(eyre::eyre!("failed to run app: {}", e)),
+                // the app stopping on its own or at the end of its TTL are both fine
+                _ => Ok(()),
+            }
+        }));
+
+        // building the image dominates startup, give it as long as validation gets
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(600);
+        loop {
+            if tokio::net::TcpStream::connect(("127.0.0.1", host_port))
+                .await
+                .is_ok()
+            {
+                break;
             }
+            if task.0.is_finished() {
+                return match (&mut task.0).await {
+                    Ok(Err(e)) => Err(e),
+                    _ => Err(eyre::eyre!("app exited before it started listening")),
+                };
+            }
+            if tokio::time::Instant::now() > deadline {
+                eyre::bail!("app did not start listening within 10 minutes");
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
         }
 
-        Ok(output)
+        let url = format!("http://localhost:{}", host_port);
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::zero());
+        tracing::info!("Preview of {} running at {}", work_dir.display(), url);
+        self.previews.lock().await.insert(
+            work_dir.to_path_buf(),
+            Preview {
+                url: url.clone(),
+                expires_at,
+                _task: task,
+            },
+        );
+        Ok(PreviewProjectResult {
+            url,
+            expires_at: expires_at.to_rfc3339(),
+        })
     }
 
     #[tool(
-        name = "scaffold_data_app",
-        description = "Initialize a project by copying template files from the default TypeScript (tRPC + React) template to a work directory. Supports force rewrite to wipe and recreate the directory. It sets up a basic project structure, and should be ALWAYS used as the first step in creating a new data or web app."
+        name = "preview_data_app",
+        description = "Build and run a scaffolded project locally and return a URL where the user can click through the app before deploying. The preview stops after ttl_minutes (default 30). Calling it again for the same project rebuilds the preview; pass stop=true to stop it."
     )]
-    pub async fn scaffold_data_app(
+    pub async fn preview_data_app(
         &self,
-        Parameters(args): Parameters<InitiateProjectArgs>,
+        Parameters(args): Parameters<PreviewProjectArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let work_path = PathBuf::from(&args.work_dir);
-
-        // validate that the path is absolute
-        if !work_path.is_absolute() {
-            return Err(ErrorData::invalid_params(
-                format!(
-                    "work_dir must be an absolute path, got: '{}'. Relative paths are not supported",
-                    args.work_dir
-                ),
-                None,
-            ));
+        let work_path = Self::absolute_work_dir(&args.work_dir)?;
+
+        if args.stop {
+            let message = match self.previews.lock().await.remove(&work_path) {
+                Some(preview) => format!("Stopped preview at {}", preview.url),
+                None => format!("No preview running for {}", work_path.display()),
+            };
+            return Ok(CallToolResult::success(vec![Content::text(message)]));
         }
 
-        let template = self.get_template();
-        let result = Self::initiate_project_impl(&work_path, template, args.force_rewrite)
+        // drop expired previews (their tasks have already finished)
+        let now = chrono::Utc::now();
+        self.previews
+            .lock()
+            .await
+            .retain(|_, preview| preview.expires_at > now);
+
+        let ttl_minutes = args
+            .ttl_minutes
+            .unwrap_or(DEFAULT_PREVIEW_TTL_MINUTES)
+            .clamp(1, MAX_PREVIEW_TTL_MINUTES);
+        let progress = ProgressReporter::new(&context, None);
+        progress.step("Building and starting app").await;
+        let result = self
+            .start_preview(&work_path, std::time::Duration::from_secs(ttl_minutes * 60))
+            .await
             .map_err(|e| {
-                ErrorData::internal_error(format!("failed to initiate project: {}", e), None)
+                ErrorData::internal_error(format!("failed to start preview: {}", e), None)
             })?;
+        Ok(structured_result(&result, true))
+    }
 
-        Ok(CallToolResult::success(vec![Content::text(
-            result.display(),
-        )]))
+    /// Port the app at `work_dir` listens on: the one it was scaffolded with, else the
+    /// configured one
+    fn app_port(
+        work_dir: &Path,
+        screenshot_config: Option<&crate::config::ScreenshotConfig>,
+    ) -> u16 {
+        merge::base_vars(work_dir)
+            .ok()
+            .flatten()
+            .and_then(|vars| vars.port)
+            .or_else(|| screenshot_config.and_then(|c| c.port))
+            .unwrap_or(edda_screenshot::ScreenshotOptions::default().port)
     }
 
     /// Capture screenshot of the app using edda_screenshot
-    /// Returns (screenshot_path, browser_logs) on success, or error with logs attached
+    /// Returns the exported screenshots on success, or error with logs attached
     async fn capture_screenshot(
         client: &DaggerConn,
         work_dir: &Path,
         screenshot_config: Option<&crate::config::ScreenshotConfig>,
-    ) -> Result<(String, Option<String>)> {
+    ) -> Result<CapturedScreenshots> {
         // check if Dockerfile exists
         let dockerfile_path = work_dir.join("Dockerfile");
         if !dockerfile_path.exists() {
@@ -296,33 +1493,55 @@ impl IOProvider {
 
         let options = edda_screenshot::ScreenshotOptions {
             url: screenshot_cfg.url.clone().unwrap_or(defaults.url),
-            port: screenshot_cfg.port.unwrap_or(defaults.port),
+            routes: screenshot_cfg.routes.clone().unwrap_or_default(),
+            viewports: screenshot_cfg.viewports.clone().unwrap_or_default(),
+            interactions: screenshot_cfg.interactions.clone().unwrap_or_default(),
+            record_network: screenshot_cfg.record_network.unwrap_or(false),
+            accessibility_audit: screenshot_cfg.accessibility_audit.unwrap_or(false),
+            selector: screenshot_cfg.selector.clone(),
+            recording: screenshot_cfg.recording,
+            port: Self::app_port(work_dir, screenshot_config),
             wait_time_ms: screenshot_cfg.wait_time_ms.unwrap_or(defaults.wait_time_ms),
-            env_vars: {
-                let mut vars = vec![];
-                if let Ok(host) = std::env::var("DATABRICKS_HOST") {
-                    vars.push(("DATABRICKS_HOST".to_string(), host));
-                }
-                if let Ok(token) = std::env::var("DATABRICKS_TOKEN") {
-                    vars.push(("DATABRICKS_TOKEN".to_string(), token));
-                }
-                if let Ok(warehouse_id) = std::env::var("DATABRICKS_WAREHOUSE_ID") {
-                    vars.push(("DATABRICKS_WAREHOUSE_ID".to_string(), warehouse_id));
-                }
-                vars
-            },
+            env_vars: Self::app_env_vars(),
         };
 
         tracing::info!("Starting screenshot capture with options: url={}, port={}, wait_time={}ms",
             options.url, options.port, options.wait_time_ms);
+        let captures = options.captures();
+        let record_network = options.record_network;
+        let accessibility_audit = options.accessibility_audit;
+        let recording = options.recording;
 
         // get app source directory
         let app_source = client.host().directory(work_dir.display().to_string());
 
+        let daemon = match screenshot_cfg.daemon.unwrap_or(false) {
+            true => crate::screenshot_daemon::daemon()
+                .await
+                .inspect_err(|e| {
+                    tracing::warn!("Screenshot daemon unavailable, using a new container: {}", e)
+                })
+                .ok(),
+            false => None,
+        };
+        // files captured by the daemon, kept until they're exported
+        let daemon_output = tempfile::tempdir()?;
+
         // capture screenshot and handle errors with context
-        let result_dir = edda_screenshot::screenshot_app(client, app_source, options)
-            .await
-            .context("Screenshot capture failed (app may not have started)")?;
+        let result_dir = match &daemon {
+            Some(daemon) => {
+                edda_screenshot::screenshot_app_with_daemon(
+                    client,
+                    daemon,
+                    app_source,
+                    options,
+                    daemon_output.path(),
+                )
+                .await
+            }
+            None => edda_screenshot::screenshot_app(client, app_source, options).await,
+        }
+        .context("Screenshot capture failed (app may not have started)")?;
 
         // export screenshot to work_dir/screenshot.png
         let screenshot_path = work_dir.join("screenshot.png");
@@ -334,8 +1553,25 @@ impl IOProvider {
 
         tracing::info!("Screenshot saved to: {}", screenshot_path.display());
 
+        // images of the other routes and viewports go to work_dir/.edda/screenshots/, a route
+        // that failed to load has none (its error is in the browser logs)
+        let mut route_screenshots = vec![];
+        if captures.len() > 1 {
+            let files = captures[1..]
+                .iter()
+                .map(|capture| capture.file.as_str())
+                .chain([edda_screenshot::CONTACT_SHEET_FILE]);
+            for file in files {
+                let path = state::artifacts_dir(work_dir).join("screenshots").join(file);
+                match result_dir.file(file).export(path.display().to_string()).await {
+                    Ok(_) => route_screenshots.push(path.display().to_string()),
+                    Err(e) => tracing::warn!("No screenshot {}: {}", file, e),
+                }
+            }
+        }
+
         // read browser console logs if available (soft failure - empty string if missing)
-        let browser_logs = match result_dir.file("logs.txt").contents().await {
+        let mut browser_logs = match result_dir.file(edda_screenshot::LOGS_FILE).contents().await {
             Ok(logs) => {
                 if !logs.trim().is_empty() {
                     tracing::info!("Browser console logs captured ({} bytes)", logs.len());
@@ -351,15 +1587,133 @@ impl IOProvider {
             }
         };
 
-        // return absolute path for agent to read
-        Ok((screenshot_path.display().to_string(), browser_logs))
+        let log_entries = browser_logs
+            .as_deref()
+            .map(edda_screenshot::parse_logs)
+            .unwrap_or_default();
+
+        // routes that failed to load
+        if let Ok(errors) = result_dir.file("error.txt").contents().await {
+            let errors = format!("Screenshot errors:\n{}", errors.trim());
+            browser_logs = Some(match browser_logs {
+                Some(logs) => format!("{}\n\n{}", logs, errors),
+                None => errors,
+            });
+        }
+
+        // requests with their statuses and timings go to work_dir/.edda/network.json
+        let network_log = match record_network {
+            true => match result_dir.file(edda_screenshot::NETWORK_FILE).contents().await {
+                Ok(json) => Self::write_network_log(&json, work_dir)
+                    .inspect_err(|e| tracing::warn!("Failed to write network log: {}", e))
+                    .ok(),
+                Err(e) => {
+                    tracing::warn!("Failed to read network log: {}", e);
+                    None
+                }
+            },
+            false => None,
+        };
+
+        let a11y_violations = match accessibility_audit {
+            true => match result_dir.file(edda_screenshot::A11Y_FILE).contents().await {
+                Ok(json) => edda_screenshot::parse_a11y(&json)
+                    .inspect_err(|e| tracing::warn!("Ignoring accessibility report: {}", e))
+                    .unwrap_or_default(),
+                Err(e) => {
+                    tracing::warn!("Failed to read accessibility report: {}", e);
+                    vec![]
+                }
+            },
+            false => vec![],
+        };
+
+        // the recording goes to work_dir/.edda/recording.webm or trace.zip
+        let recording_path = match recording {
+            Some(recording) => {
+                let path = state::artifacts_dir(work_dir).join(recording.file());
+                match result_dir.file(recording.file()).export(path.display().to_string()).await {
+                    Ok(_) => Some(path.display().to_string()),
+                    Err(e) => {
+                        tracing::warn!("No {} recording: {}", recording.name(), e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // return absolute paths for agent to read
+        Ok(CapturedScreenshots {
+            screenshot_path: screenshot_path.display().to_string(),
+            route_screenshots,
+            browser_logs,
+            log_entries,
+            network_log,
+            a11y_violations,
+            recording_path,
+        })
+    }
+
+    /// Keep the recorded requests with the other validation artifacts and summarize them
+    fn write_network_log(json: &str, work_dir: &Path) -> Result<NetworkLog> {
+        let requests = edda_screenshot::parse_network(json)?;
+        let artifacts_dir = state::artifacts_dir(work_dir);
+        std::fs::create_dir_all(&artifacts_dir).context("failed to create artifacts directory")?;
+        let path = artifacts_dir.join(edda_screenshot::NETWORK_FILE);
+        std::fs::write(&path, json).context("failed to write network log")?;
+        Ok(NetworkLog {
+            path: path.display().to_string(),
+            summary: edda_screenshot::network_summary(&requests),
+        })
+    }
+
+    /// Compare a new screenshot with the baseline of the last successful validation and make
+    /// it the new baseline unless it differs beyond the threshold, then only with `accept`.
+    /// Returns the differences if the app looks different.
+    fn compare_with_baseline(
+        work_dir: &Path,
+        screenshot: &Path,
+        accept: bool,
+    ) -> Option<VisualDiff> {
+        let baseline = state::baseline_screenshot(work_dir);
+        let report = match baseline.exists() {
+            true => edda_screenshot::compare_screenshots(&baseline, screenshot)
+                .inspect_err(|e| tracing::warn!("Failed to compare with baseline: {}", e))
+                .ok(),
+            false => None,
+        };
+        // an unaccepted change keeps showing up until it's fixed or accepted
+        let accepted = accept || !report.as_ref().is_some_and(|report| report.differs);
+        if accepted && let Err(e) = state::save_baseline_screenshot(work_dir, screenshot) {
+            tracing::warn!("{}", e);
+        }
+
+        let report = report.filter(|report| report.differs)?;
+        let diff_path = state::artifacts_dir(work_dir).join("screenshot-diff.png");
+        let diff_path = match edda_screenshot::diff::write_annotated(screenshot, &report, &diff_path) {
+            Ok(()) => Some(diff_path.display().to_string()),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                None
+            }
+        };
+        Some(VisualDiff {
+            report,
+            diff_path,
+            accepted,
+        })
     }
 
-    /// Core logic for validating a project
+    /// Core logic for validating a project. With `incremental`, stages covering parts of
+    /// the project unchanged since the last successful validation are skipped.
     pub async fn validate_project_impl(
         work_dir: &Path,
         validation_strategy: Box<dyn validation::ValidationDyn>,
         screenshot_config: Option<crate::config::ScreenshotConfig>,
+        progress: &ProgressReporter,
+        incremental: bool,
+        execute_timeout: Option<u64>,
     ) -> Result<ValidateProjectResult> {
         // validate work directory exists
         if !work_dir.exists() {
@@ -379,6 +1733,51 @@ impl IOProvider {
             }
         };
 
+        // nothing changed since the last successful validation: return its cached result
+        if incremental {
+            let checksum = state::compute_checksum(work_dir)?;
+            if let Some(cached) = project_state.cached_validation(&checksum).cloned() {
+                tracing::info!(
+                    "No changes since the last successful validation, using cached result"
+                );
+                // re-validating a deployed project makes it deployable again
+                let project_state = project_state.validate(checksum, Some(cached.clone()))?;
+                state::save_state(work_dir, &project_state)?;
+                return Ok(ValidateProjectResult {
+                    success: true,
+                    message: format!(
+                        "{} (cached: no changes since the last validation, pass full=true to re-run it)",
+                        cached.message
+                    ),
+                    details: None,
+                    screenshot_path: cached.screenshot_path,
+                    route_screenshots: vec![],
+                    browser_logs: None,
+                    network_log: None,
+                    a11y_warnings: vec![],
+                    recording_path: None,
+                    visual_diff: None,
+                });
+            }
+        }
+
+        // compare against the file manifest of the last successful validation
+        let previous_manifest = match incremental && project_state.is_validated() {
+            true => state::load_manifest(work_dir)?,
+            false => None,
+        };
+        let scope = match previous_manifest {
+            Some(previous) => {
+                let changed = state::changed_files(&previous, &state::compute_manifest(work_dir)?);
+                tracing::info!(
+                    "{} files changed since the last successful validation",
+                    changed.len()
+                );
+                validation::ValidationScope::from_changes(&changed)
+            }
+            None => validation::ValidationScope::Full,
+        };
+
         // optimization: spawn screenshot task early if enabled
         // this allows parallel execution: validation checks + screenshot warmup/capture
         let screenshot_task = if screenshot_config
@@ -390,12 +1789,12 @@ impl IOProvider {
 
             tracing::info!("Spawning screenshot task in parallel with validation");
 
-            Some(tokio::spawn(async move {
+            Some(AbortOnDrop(tokio::spawn(async move {
                 let (screenshot_tx, screenshot_rx) = tokio::sync::oneshot::channel();
 
                 let screenshot_opts = ConnectOpts::default()
                     .with_logger(Logger::Silent)
-                    .with_execute_timeout(Some(600));
+                    .with_execute_timeout(execute_timeout);
 
                 let screenshot_connect_result = screenshot_opts
                     .connect(move |client| async move {
@@ -417,7 +1816,7 @@ impl IOProvider {
                 screenshot_rx
                     .await
                     .map_err(|_| eyre::eyre!("screenshot task was cancelled"))?
-            }))
+            })))
         } else {
             None
         };
@@ -426,10 +1825,12 @@ impl IOProvider {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let work_dir_str = work_dir.display().to_string();
         let docker_image = validation_strategy.docker_image();
+        let validation_progress = progress.clone();
 
+        progress.step("Starting validation sandbox").await;
         let opts = ConnectOpts::default()
             .with_logger(Logger::Silent)
-            .with_execute_timeout(Some(600));
+            .with_execute_timeout(execute_timeout);
 
         let connect_result = opts
             .connect(move |client| async move {
@@ -440,13 +1841,13 @@ impl IOProvider {
                     .with_exec(vec!["mkdir", "-p", "/app"]);
 
                 // propagate DATABRICKS_* env vars if set
-                if let Ok(host) = std::env::var("DATABRICKS_HOST") {
+                if let Ok(host) = edda_integrations::env::var("DATABRICKS_HOST") {
                     container = container.with_env_variable("DATABRICKS_HOST", host);
                 }
-                if let Ok(token) = std::env::var("DATABRICKS_TOKEN") {
+                if let Ok(token) = edda_integrations::env::var("DATABRICKS_TOKEN") {
                     container = container.with_env_variable("DATABRICKS_TOKEN", token);
                 }
-                if let Ok(warehouse_id) = std::env::var("DATABRICKS_WAREHOUSE_ID") {
+                if let Ok(warehouse_id) = edda_integrations::env::var("DATABRICKS_WAREHOUSE_ID") {
                     container =
                         container.with_env_variable("DATABRICKS_WAREHOUSE_ID", warehouse_id);
                 }
@@ -459,7 +1860,7 @@ impl IOProvider {
 
                 // run validation checks using the strategy
                 let validation_result = validation_strategy
-                    .validate(&mut sandbox, &work_dir_str)
+                    .validate(&mut sandbox, &work_dir_str, &validation_progress, scope)
                     .await;
 
                 let _ = tx.send(validation_result);
@@ -477,30 +1878,28 @@ impl IOProvider {
 
         let result = match validation_result {
             Ok(_) => {
-                // validation passed - update state and await screenshot if spawned
+                // validation passed - await screenshot if spawned, then update state
                 let checksum = state::compute_checksum(work_dir)?;
-                let project_state = project_state.validate(checksum)?;
-                state::save_state(work_dir, &project_state)?;
+                let manifest = state::compute_manifest(work_dir)?;
 
                 // await screenshot task with timeout if it was spawned
-                let (screenshot_path, browser_logs) = if let Some(task) = screenshot_task {
+                let (captured, screenshot_error) = if let Some(mut task) = screenshot_task {
                     tracing::info!("Validation passed, awaiting screenshot result");
+                    progress.step("Capturing screenshot").await;
 
                     use tokio::time::{timeout, Duration};
                     let screenshot_timeout = Duration::from_secs(300); // 5 minutes
 
-                    match timeout(screenshot_timeout, task).await {
-                        Ok(Ok(Ok((path, logs)))) => (Some(path), logs),
+                    match timeout(screenshot_timeout, &mut task.0).await {
+                        Ok(Ok(Ok(captured))) => (Some(captured), None),
                         Ok(Ok(Err(e))) => {
                             // Screenshot failed, but validation passed - soft failure
                             tracing::warn!("Screenshot capture failed (validation passed): {}", e);
-                            let error_msg = format!("Screenshot failed: {}", e);
-                            (None, Some(error_msg))
+                            (None, Some(format!("Screenshot failed: {}", e)))
                         }
                         Ok(Err(e)) => {
                             tracing::warn!("Screenshot task panicked (validation passed): {}", e);
-                            let error_msg = format!("Screenshot task panicked: {}", e);
-                            (None, Some(error_msg))
+                            (None, Some(format!("Screenshot task panicked: {}", e)))
                         }
                         Err(_) => {
                             tracing::warn!("Screenshot timed out after {} seconds (validation passed)", screenshot_timeout.as_secs());
@@ -511,20 +1910,114 @@ impl IOProvider {
                     (None, None)
                 };
 
+                // a blank page from a crashed render screenshots fine, the browser logs
+                // tell it apart
+                let policy = screenshot_config
+                    .as_ref()
+                    .and_then(|c| c.log_policy.clone())
+                    .unwrap_or_default();
+                let browser_errors: Vec<String> = captured
+                    .as_ref()
+                    .map(|captured| policy.violations(&captured.log_entries))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|entry| match entry.level {
+                        edda_screenshot::LogLevel::PageError => {
+                            format!("Uncaught exception: {}", entry.message)
+                        }
+                        _ => format!("Failed request: {}", entry.message),
+                    })
+                    .collect();
+
+                let (screenshot_path, route_screenshots, browser_logs, network_log, a11y_warnings, recording_path) = match captured {
+                    Some(captured) => (
+                        Some(captured.screenshot_path),
+                        captured.route_screenshots,
+                        captured.browser_logs,
+                        captured.network_log,
+                        captured.a11y_violations,
+                        captured.recording_path,
+                    ),
+                    None => (None, vec![], screenshot_error, None, vec![], None),
+                };
+
+                if !browser_errors.is_empty() {
+                    // not validated, the baseline screenshot stays the last good one
+                    return Ok(ValidateProjectResult {
+                        success: false,
+                        message: format!(
+                            "The app has errors in the browser:\n{}",
+                            browser_errors.join("\n")
+                        ),
+                        details: None,
+                        screenshot_path,
+                        route_screenshots,
+                        browser_logs,
+                        network_log,
+                        a11y_warnings,
+                        recording_path,
+                        visual_diff: None,
+                    });
+                }
+
+                // an element image isn't comparable to the baseline of the whole page
+                let element_only = screenshot_config
+                    .as_ref()
+                    .is_some_and(|c| c.selector.is_some());
+                let accept = screenshot_config
+                    .as_ref()
+                    .and_then(|c| c.accept_visual_changes)
+                    .unwrap_or(false);
+                let visual_diff = match screenshot_path.clone() {
+                    Some(_) if element_only => None,
+                    Some(path) => {
+                        let work_dir = work_dir.to_path_buf();
+                        tokio::task::spawn_blocking(move || {
+                            Self::compare_with_baseline(&work_dir, Path::new(&path), accept)
+                        })
+                        .await
+                        .ok()
+                        .flatten()
+                    }
+                    None => None,
+                };
+
+                let message = match scope.skipped() {
+                    Some(skipped) => format!("All validations passed ({})", skipped),
+                    None => "All validations passed".to_string(),
+                };
+                // an unaccepted visual change has to show up again on the next call, the
+                // cached result doesn't carry it
+                let cached = match visual_diff.as_ref().is_some_and(|diff| !diff.accepted) {
+                    true => None,
+                    false => Some(state::CachedValidation {
+                        message: message.clone(),
+                        screenshot_path: screenshot_path.clone(),
+                    }),
+                };
+                let project_state = project_state.validate(checksum, cached)?;
+                state::save_state(work_dir, &project_state)?;
+                state::save_manifest(work_dir, &manifest)?;
+
                 ValidateProjectResult {
                     success: true,
-                    message: "All validations passed".to_string(),
+                    message,
                     details: None,
                     screenshot_path,
+                    route_screenshots,
                     browser_logs,
+                    network_log,
+                    a11y_warnings,
+                    recording_path,
+                    visual_diff,
                 }
             }
             Err(details) => {
                 // validation failed - explicitly abort screenshot task
-                if let Some(task) = screenshot_task {
+                if let Some(mut task) = screenshot_task {
                     tracing::info!("Validation failed, aborting screenshot task");
-                    task.abort();
-                    let _ = task.await; // Wait for abort to complete, ignore result
+                    task.0.abort();
+                    let _ = (&mut task.0).await; // Wait for abort to complete, ignore result
                 }
 
                 ValidateProjectResult {
@@ -532,7 +2025,12 @@ impl IOProvider {
                     message: "Validation failed".to_string(),
                     details: Some(details),
                     screenshot_path: None,
+                    route_screenshots: vec![],
                     browser_logs: None,
+                    network_log: None,
+                    a11y_warnings: vec![],
+                    recording_path: None,
+                    visual_diff: None,
                 }
             }
         };
@@ -542,11 +2040,12 @@ impl IOProvider {
 
     #[tool(
         name = "validate_data_app",
-        description = "Validate a project by copying files to a sandbox and running validation checks. Project should be scaffolded first. Returns validation result with success status and details."
+        description = "Validate a project by copying files to a sandbox and running validation checks. Project should be scaffolded first. Checks for parts of the project unchanged since the last successful validation are skipped unless full=true. Pass screenshot_selector to screenshot just one element, e.g. a chart you're iterating on. A screenshot that looks different from the last validated one becomes the new baseline only with accept_visual_changes=true. Returns validation result with success status and details."
     )]
     pub async fn validate_data_app(
         &self,
         Parameters(args): Parameters<ValidateProjectArgs>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
         let work_path = PathBuf::from(&args.work_dir);
 
@@ -561,37 +2060,65 @@ impl IOProvider {
             ));
         }
 
-        let validation_strategy = self.get_validation_strategy();
-        let screenshot_config = self.config.as_ref().and_then(|c| c.screenshot.clone());
+        let validation_strategy = self.get_validation_strategy(&work_path);
+        // an element screenshot or accepting a change needs a fresh capture, not the cached result
+        let incremental =
+            !args.full && args.screenshot_selector.is_none() && !args.accept_visual_changes;
+        let mut screenshot_config = self.config.as_ref().and_then(|c| c.screenshot.clone());
+        if let Some(config) = &mut screenshot_config {
+            if let Some(selector) = args.screenshot_selector {
+                config.selector = Some(selector);
+            }
+            config.accept_visual_changes = Some(args.accept_visual_changes);
+        }
+        let progress = ProgressReporter::new(&context, None);
         let result = Self::validate_project_impl(
             &work_path,
             validation_strategy,
             screenshot_config,
+            &progress,
+            incremental,
+            self.execute_timeout,
         )
         .await
         .map_err(|e| {
             ErrorData::internal_error(format!("failed to validate project: {}", e), None)
         })?;
 
-        match result.success {
-            true => Ok(CallToolResult::success(vec![Content::text(
-                result.display(),
-            )])),
-            false => Ok(CallToolResult::error(vec![Content::text(result.display())])),
-        }
+        Ok(structured_result(&result, result.success))
     }
 }
 
 enum TemplateFiles {
     Trpc(TemplateTRPC),
+    Streamlit(TemplatePyStreamlit),
+    FastApi(TemplatePyFastAPI),
+    Axum(TemplateRustAxum),
     Local(LocalTemplate),
+    /// Fetched from a git repository or OCI artifact
+    Fetched(LocalTemplate),
 }
 
 impl Template for TemplateFiles {
     fn name(&self) -> String {
         match self {
             TemplateFiles::Trpc(t) => t.name(),
+            TemplateFiles::Streamlit(t) => t.name(),
+            TemplateFiles::FastApi(t) => t.name(),
+            TemplateFiles::Axum(t) => t.name(),
             TemplateFiles::Local(t) => t.name(),
+            TemplateFiles::Fetched(t) => t.name(),
+        }
+    }
+
+    async fn smoke_test<S: Sandbox + Send>(&self, sandbox: &mut S) -> Result<()> {
+        match self {
+            TemplateFiles::Trpc(t) => t.smoke_test(sandbox).await,
+            TemplateFiles::Streamlit(t) => t.smoke_test(sandbox).await,
+            TemplateFiles::FastApi(t) => t.smoke_test(sandbox).await,
+            TemplateFiles::Axum(t) => t.smoke_test(sandbox).await,
+            TemplateFiles::Local(t) => t.smoke_test(sandbox).await,
+            TemplateFiles::Fetched(t) => t.smoke_test(sandbox).await,
         }
     }
 }
@@ -600,14 +2127,22 @@ impl TemplateCore for TemplateFiles {
     fn description(&self) -> Option<String> {
         match self {
             TemplateFiles::Trpc(t) => t.description(),
+            TemplateFiles::Streamlit(t) => t.description(),
+            TemplateFiles::FastApi(t) => t.description(),
+            TemplateFiles::Axum(t) => t.description(),
             TemplateFiles::Local(t) => t.description(),
+            TemplateFiles::Fetched(t) => t.description(),
         }
     }
 
-    fn extract(&self, work_dir: &Path) -> Result<Vec<PathBuf>> {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>> {
         match self {
-            TemplateFiles::Trpc(t) => t.extract(work_dir),
-            TemplateFiles::Local(t) => t.extract(work_dir),
+            TemplateFiles::Trpc(t) => t.files(),
+            TemplateFiles::Streamlit(t) => t.files(),
+            TemplateFiles::FastApi(t) => t.files(),
+            TemplateFiles::Axum(t) => t.files(),
+            TemplateFiles::Local(t) => t.files(),
+            TemplateFiles::Fetched(t) => t.files(),
         }
     }
 }
@@ -615,13 +2150,52 @@ impl TemplateCore for TemplateFiles {
 pub mod validation {
     use super::*;
     use edda_sandbox::DaggerSandbox;
+    use edda_templates::capabilities::ValidationSpec;
     use std::pin::Pin;
 
+    /// Which parts of the project a validation run checks, based on the files changed
+    /// since the last successful validation.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ValidationScope {
+        Full,
+        /// only server/ changed
+        Server,
+        /// only client/ changed
+        Client,
+    }
+
+    impl ValidationScope {
+        pub fn from_changes(changed: &[String]) -> Self {
+            let all_under = |dir: &str| changed.iter().all(|path| path.starts_with(dir));
+            if changed.is_empty() {
+                Self::Full
+            } else if all_under("server/") {
+                Self::Server
+            } else if all_under("client/") {
+                Self::Client
+            } else {
+                Self::Full
+            }
+        }
+
+        /// What was skipped, for the validation result message
+        pub fn skipped(&self) -> Option<&'static str> {
+            match self {
+                Self::Full => None,
+                Self::Server => Some("only server files changed, client build skipped"),
+                Self::Client => Some("only client files changed, server check and tests skipped"),
+            }
+        }
+    }
+
     pub trait Validation {
+        /// Strategies that can't split their checks may ignore `scope` and run everything.
         fn validate(
             &self,
             sandbox: &mut DaggerSandbox,
             work_dir: &str,
+            progress: &ProgressReporter,
+            scope: ValidationScope,
         ) -> impl Future<Output = Result<(), ValidationDetails>> + Send;
 
         fn docker_image(&self) -> String {
@@ -641,6 +2215,8 @@ pub mod validation {
             &'a self,
             sandbox: &'a mut DaggerSandbox,
             work_dir: &'a str,
+            progress: &'a ProgressReporter,
+            scope: ValidationScope,
         ) -> Pin<Box<dyn Future<Output = Result<(), ValidationDetails>> + Send + 'a>>;
 
         fn docker_image(&self) -> String {
@@ -653,8 +2229,14 @@ pub mod validation {
             &'a self,
             sandbox: &'a mut DaggerSandbox,
             work_dir: &'a str,
+            progress: &'a ProgressReporter,
+            scope: ValidationScope,
         ) -> Pin<Box<dyn Future<Output = Result<(), ValidationDetails>> + Send + 'a>> {
-            Box::pin(self.validate(sandbox, work_dir))
+            Box::pin(self.validate(sandbox, work_dir, progress, scope))
+        }
+
+        fn docker_image(&self) -> String {
+            Validation::docker_image(self)
         }
     }
 
@@ -665,14 +2247,54 @@ pub mod validation {
             &self,
             sandbox: &mut DaggerSandbox,
             work_dir: &str,
+            progress: &ProgressReporter,
+            scope: ValidationScope,
         ) -> Result<(), ValidationDetails> {
             let start_time = std::time::Instant::now();
-            tracing::info!("Starting tRPC validation (build + tests + type checks)...");
+            tracing::info!(
+                ?scope,
+                "Starting tRPC validation (build + tests + type checks)..."
+            );
 
+            progress.step("Copying project files").await;
             refresh_sandbox_files(sandbox, work_dir).await?;
-            Self::run_build(sandbox).await?;
-            Self::run_client_type_check(sandbox).await?;
-            Self::run_tests(sandbox).await?;
+            match scope {
+                ValidationScope::Full => {
+                    progress.step("Building").await;
+                    Self::run_build(sandbox).await?;
+                    progress.step("Type checking client").await;
+                    Self::run_client_type_check(sandbox).await?;
+                    progress.step("Running tests").await;
+                    Self::run_tests(sandbox).await?;
+                }
+                ValidationScope::Server => {
+                    progress.step("Type checking server").await;
+                    run_command(
+                        sandbox,
+                        "cd /app/server && npm install && npm run check",
+                        "server check",
+                    )
+                    .await?;
+                    // the client imports the server's router types, so server changes can
+                    // break its type check
+                    progress.step("Type checking client").await;
+                    run_command(sandbox, "cd /app/client && npm install", "client install").await?;
+                    Self::run_client_type_check(sandbox).await?;
+                    progress.step("Running tests").await;
+                    Self::run_tests(sandbox).await?;
+                }
+                ValidationScope::Client => {
+                    progress.step("Building client").await;
+                    run_command(
+                        sandbox,
+                        "cd /app/client && npm install && npm run build",
+                        "client build",
+                    )
+                    .await?;
+                    progress.step("Type checking client").await;
+                    Self::run_client_type_check(sandbox).await?;
+                }
+            }
 
             let duration = start_time.elapsed().as_secs_f64();
             tracing::info!(duration, "All tRPC validation checks passed");
@@ -760,6 +2382,160 @@ pub mod validation {
         }
     }
 
+    /// Strategy from the template manifest of the project at `work_dir`. Projects without
+    /// one are told apart by their files: a Cargo.toml or requirements.txt without a
+    /// package.json is a Rust or Python template, anything else the tRPC one.
+    pub fn for_project(work_dir: &Path) -> Box<dyn ValidationDyn> {
+        match TemplateCapabilities::load(work_dir) {
+            Ok(Some(capabilities)) => return from_capabilities(&capabilities),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Ignoring template manifest: {}", e),
+        }
+        if work_dir.join("package.json").exists() {
+            ValidationTRPC.boxed()
+        } else if work_dir.join("Cargo.toml").exists() {
+            ValidationRust.boxed()
+        } else if work_dir.join("requirements.txt").exists() {
+            ValidationPython.boxed()
+        } else {
+            ValidationTRPC.boxed()
+        }
+    }
+
+    pub fn from_capabilities(capabilities: &TemplateCapabilities) -> Box<dyn ValidationDyn> {
+        let docker_image = capabilities.sandbox_image();
+        match &capabilities.validation {
+            ValidationSpec::Trpc => WithImage {
+                validation: ValidationTRPC,
+                docker_image,
+            }
+            .boxed(),
+            ValidationSpec::Python => WithImage {
+                validation: ValidationPython,
+                docker_image,
+            }
+            .boxed(),
+            ValidationSpec::Rust => WithImage {
+                validation: ValidationRust,
+                docker_image,
+            }
+            .boxed(),
+            ValidationSpec::Command { command, .. } => ValidationCmd {
+                command: command.clone(),
+                docker_image,
+            }
+            .boxed(),
+        }
+    }
+
+    /// A built-in strategy run in another image, e.g. the runtime version a template
+    /// manifest asks for
+    pub struct WithImage<V> {
+        pub validation: V,
+        pub docker_image: String,
+    }
+
+    impl<V: Validation + Send + Sync> Validation for WithImage<V> {
+        async fn validate(
+            &self,
+            sandbox: &mut DaggerSandbox,
+            work_dir: &str,
+            progress: &ProgressReporter,
+            scope: ValidationScope,
+        ) -> Result<(), ValidationDetails> {
+            self.validation
+                .validate(sandbox, work_dir, progress, scope)
+                .await
+        }
+
+        fn docker_image(&self) -> String {
+            self.docker_image.clone()
+        }
+    }
+
+    /// Validation of the Streamlit and FastAPI templates: dependencies install, sources
+    /// compile and pytest passes
+    pub struct ValidationPython;
+
+    impl Validation for ValidationPython {
+        async fn validate(
+            &self,
+            sandbox: &mut DaggerSandbox,
+            work_dir: &str,
+            progress: &ProgressReporter,
+            _scope: ValidationScope,
+        ) -> Result<(), ValidationDetails> {
+            // the scopes follow the tRPC client/ and server/ split, so Python projects
+            // always run in full
+            let start_time = std::time::Instant::now();
+            tracing::info!("Starting Python validation (install + compile + tests)...");
+
+            progress.step("Copying project files").await;
+            refresh_sandbox_files(sandbox, work_dir).await?;
+            progress.step("Installing dependencies").await;
+            run_command(
+                sandbox,
+                "cd /app && pip install --quiet --disable-pip-version-check -r requirements.txt",
+                "dependency install",
+            )
+            .await?;
+            progress.step("Compiling sources").await;
+            run_command(
+                sandbox,
+                "cd /app && python -m compileall -q -x '/(\\.venv|\\.edda_template)/' .",
+                "compile check",
+            )
+            .await?;
+            progress.step("Running tests").await;
+            run_command(sandbox, "cd /app && python -m pytest -q", "pytest").await?;
+
+            let duration = start_time.elapsed().as_secs_f64();
+            tracing::info!(duration, "All Python validation checks passed");
+            Ok(())
+        }
+
+        fn docker_image(&self) -> String {
+            "python:3.12-slim".to_string()
+        }
+    }
+
+    /// Validation of the Axum template: cargo check of every target and cargo test
+    pub struct ValidationRust;
+
+    impl Validation for ValidationRust {
+        async fn validate(
+            &self,
+            sandbox: &mut DaggerSandbox,
+            work_dir: &str,
+            progress: &ProgressReporter,
+            _scope: ValidationScope,
+        ) -> Result<(), ValidationDetails> {
+            // a Rust project has no client/ server/ split, it always runs in full
+            let start_time = std::time::Instant::now();
+            tracing::info!("Starting Rust validation (check + tests)...");
+
+            progress.step("Copying project files").await;
+            refresh_sandbox_files(sandbox, work_dir).await?;
+            progress.step("Checking").await;
+            run_command(
+                sandbox,
+                "cd /app && cargo check --all-targets --quiet",
+                "cargo check",
+            )
+            .await?;
+            progress.step("Running tests").await;
+            run_command(sandbox, "cd /app && cargo test --quiet", "cargo test").await?;
+
+            let duration = start_time.elapsed().as_secs_f64();
+            tracing::info!(duration, "All Rust validation checks passed");
+            Ok(())
+        }
+
+        fn docker_image(&self) -> String {
+            "rust:1.85-slim".to_string()
+        }
+    }
+
     pub struct ValidationCmd {
         pub command: String,
         pub docker_image: String,
@@ -770,11 +2546,16 @@ pub mod validation {
             &self,
             sandbox: &mut DaggerSandbox,
             work_dir: &str,
+            progress: &ProgressReporter,
+            _scope: ValidationScope,
         ) -> Result<(), ValidationDetails> {
+            // a custom command is opaque, so it always runs in full
             let start_time = std::time::Instant::now();
             tracing::info!("Starting custom validation: {}", self.command);
 
+            progress.step("Copying project files").await;
             refresh_sandbox_files(sandbox, work_dir).await?;
+            progress.step(format!("Running `{}`", self.command)).await;
 
             let result = sandbox
                 .exec(&format!("cd /app && {}", self.command))
@@ -805,6 +2586,32 @@ pub mod validation {
     }
 
     // Helper functions (kept internal to validation module)
+    async fn run_command(
+        sandbox: &mut DaggerSandbox,
+        command: &str,
+        what: &str,
+    ) -> Result<(), ValidationDetails> {
+        let start_time = std::time::Instant::now();
+        let result = sandbox.exec(command).await.map_err(|e| ValidationDetails {
+            exit_code: -1,
+            stdout: String::new(),
+            stderr: format!("Failed to run {}: {}", what, e),
+        })?;
+
+        if result.exit_code != 0 {
+            tracing::error!("{} failed: {:?}", what, result);
+            return Err(ValidationDetails {
+                exit_code: result.exit_code,
+                stdout: result.stdout,
+                stderr: result.stderr,
+            });
+        }
+
+        let duration = start_time.elapsed().as_secs_f64();
+        tracing::info!(duration, "{} passed", what);
+        Ok(())
+    }
+
     async fn refresh_sandbox_files(
         sandbox: &mut DaggerSandbox,
         work_dir: &str,
@@ -826,3 +2633,44 @@ impl ServerHandler for IOProvider {
         crate::mcp_helpers::internal_server_info()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{IoConfig, ValidationConfig};
+
+    #[test]
+    fn test_validation_follows_scaffolded_template() {
+        let provider = IOProvider::new(Some(IoConfig {
+            validation: Some(ValidationConfig {
+                command: "npm run ci".to_string(),
+                docker_image: "custom-node:22".to_string(),
+            }),
+            ..Default::default()
+        }))
+        .unwrap();
+        let vars = TemplateVars::default();
+
+        // the configured command is for the default template
+        let trpc = tempfile::TempDir::new().unwrap();
+        IOProvider::initiate_project_impl(trpc.path(), TemplateTRPC, &vars, false).unwrap();
+        let strategy = provider.get_validation_strategy(trpc.path());
+        assert_eq!(strategy.docker_image(), "custom-node:22");
+
+        // other templates keep their own validation
+        let streamlit = tempfile::TempDir::new().unwrap();
+        IOProvider::initiate_project_impl(streamlit.path(), TemplatePyStreamlit, &vars, false)
+            .unwrap();
+        let strategy = provider.get_validation_strategy(streamlit.path());
+        assert_eq!(
+            strategy.docker_image(),
+            validation::for_project(streamlit.path()).docker_image()
+        );
+        assert_ne!(strategy.docker_image(), "custom-node:22");
+
+        // projects without a recorded template get the configured command
+        let plain = tempfile::TempDir::new().unwrap();
+        let strategy = provider.get_validation_strategy(plain.path());
+        assert_eq!(strategy.docker_image(), "custom-node:22");
+    }
+}
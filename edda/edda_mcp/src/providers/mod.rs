@@ -1,30 +1,43 @@
 pub mod databricks_cli;
 pub mod databricks_rest;
 pub mod deployment;
+pub mod github;
 pub mod google_sheets;
 pub mod io;
+pub mod object_storage;
+pub mod postgres;
+pub mod registry;
 pub mod workspace;
 
 pub use databricks_cli::DatabricksCliProvider;
 pub use databricks_rest::DatabricksRestProvider;
 pub use deployment::DeploymentProvider;
+pub use github::GithubProvider;
 pub use google_sheets::GoogleSheetsProvider;
 pub use io::IOProvider;
-pub use workspace::WorkspaceTools;
+pub use object_storage::ObjectStoreProvider;
+pub use postgres::PostgresProvider;
+pub use registry::{ProviderEntry, ToolProvider};
+pub use workspace::{PathPolicy, WorkspaceTools};
 
+use crate::redaction::Redactor;
 use crate::session::SessionContext;
-use eyre::Result;
+use crate::telemetry::Telemetry;
+use edda_integrations::{Notification, NotificationEvent, WebhookNotifier};
+use eyre::{Result, WrapErr};
 use rmcp::model::{
-    CallToolRequestParam, CallToolResult, Implementation, PaginatedRequestParam, ProtocolVersion,
-    RawContent, ServerCapabilities, ServerInfo,
+    CallToolRequestParam, CallToolResult, Content, Implementation, PaginatedRequestParam,
+    ProtocolVersion, RawContent, ServerCapabilities, ServerInfo, Tool,
 };
 use rmcp::service::{RequestContext, RoleServer};
 use rmcp::{ErrorData, ServerHandler};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
-#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
+#[schemars(transform = databricks_alias)]
 pub enum ProviderType {
     /// Databricks REST API provider (backward compatible, maps to DatabricksRest)
     #[serde(alias = "Databricks")]
@@ -32,18 +45,254 @@ pub enum ProviderType {
     /// Databricks CLI provider (guidance only, no tools)
     DatabricksCli,
     Deployment,
+    Github,
     GoogleSheets,
     Io,
+    ObjectStore,
+    Postgres,
     Workspace,
 }
 
-enum TargetProvider {
-    DatabricksRest(Arc<DatabricksRestProvider>),
-    DatabricksCli(Arc<DatabricksCliProvider>),
-    Deployment(Arc<DeploymentProvider>),
-    GoogleSheets(Arc<GoogleSheetsProvider>),
-    Io(Arc<IOProvider>),
-    Workspace(Arc<WorkspaceTools>),
+/// The schema doesn't know serde aliases, so `Databricks` is added to it by hand
+fn databricks_alias(schema: &mut schemars::Schema) {
+    if let Some(serde_json::Value::Array(variants)) = schema.get_mut("oneOf") {
+        variants.push(serde_json::json!({
+            "description": "Old name of DatabricksRest",
+            "type": "string",
+            "const": "Databricks",
+            "deprecated": true,
+        }));
+    }
+}
+
+impl ProviderType {
+    /// name used in config files, also the category of the timeouts config
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProviderType::DatabricksRest => "DatabricksRest",
+            ProviderType::DatabricksCli => "DatabricksCli",
+            ProviderType::Deployment => "Deployment",
+            ProviderType::Github => "Github",
+            ProviderType::GoogleSheets => "GoogleSheets",
+            ProviderType::Io => "Io",
+            ProviderType::ObjectStore => "ObjectStore",
+            ProviderType::Postgres => "Postgres",
+            ProviderType::Workspace => "Workspace",
+        }
+    }
+
+    /// name listed in the server instructions
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ProviderType::DatabricksRest => "Databricks",
+            ProviderType::DatabricksCli => "Databricks CLI",
+            ProviderType::Deployment => "Deployment",
+            ProviderType::Github => "GitHub",
+            ProviderType::GoogleSheets => "Google Sheets",
+            ProviderType::Io => "I/O",
+            ProviderType::ObjectStore => "Object Store",
+            ProviderType::Postgres => "Postgres",
+            ProviderType::Workspace => "Workspace",
+        }
+    }
+
+    /// every built-in provider, in the order their tools are listed
+    pub const ALL: [ProviderType; 9] = [
+        ProviderType::DatabricksRest,
+        ProviderType::DatabricksCli,
+        ProviderType::Deployment,
+        ProviderType::Github,
+        ProviderType::GoogleSheets,
+        ProviderType::Io,
+        ProviderType::ObjectStore,
+        ProviderType::Postgres,
+        ProviderType::Workspace,
+    ];
+
+    /// tools routed to the provider, by prefix (a full tool name is a prefix too)
+    pub fn tools(&self) -> &'static [&'static str] {
+        match self {
+            ProviderType::DatabricksRest => &["databricks_"],
+            ProviderType::DatabricksCli => &[
+                "run_databricks_cli",
+                "export_workspace_files",
+                "import_workspace_files",
+                "deploy_bundle",
+                "run_app_locally",
+            ],
+            ProviderType::Deployment => &[
+                "deploy_databricks_app",
+                "get_deployment_logs",
+                "wait_for_deployment",
+                "list_app_deployments",
+                "rollback_app",
+                "list_databricks_apps",
+                "delete_databricks_app",
+            ],
+            ProviderType::Github => &["publish_to_github"],
+            ProviderType::GoogleSheets => &["google_sheets_"],
+            ProviderType::Io => &[
+                "scaffold_data_app",
+                "validate_data_app",
+                "add_feature",
+                "upgrade_project",
+                "lint_data_app",
+                "format_data_app",
+                "preview_data_app",
+            ],
+            ProviderType::ObjectStore => &["object_store_"],
+            ProviderType::Postgres => &["postgres_"],
+            ProviderType::Workspace => &[
+                "read_file",
+                "write_file",
+                "edit_file",
+                "bash",
+                "bash_background",
+                "bash_output",
+                "kill_shell",
+                "grep",
+                "glob",
+                "search_replace",
+                "list_projects",
+                "switch_project",
+            ],
+        }
+    }
+
+    /// built-in provider owning `tool_name`
+    pub fn for_tool(tool_name: &str) -> Option<ProviderType> {
+        Self::ALL.into_iter().find(|kind| {
+            kind.tools()
+                .iter()
+                .any(|prefix| tool_name.starts_with(prefix))
+        })
+    }
+
+    /// hint returned for the provider's tools while it isn't configured
+    fn not_configured_hint(&self) -> &'static str {
+        match self {
+            ProviderType::DatabricksRest => {
+                "Databricks provider not configured. Set DATABRICKS_HOST and DATABRICKS_TOKEN."
+            }
+            ProviderType::DatabricksCli => "DatabricksCli provider not configured.",
+            ProviderType::Deployment => {
+                "Deployment provider not configured. Set with_deployment and Databricks credentials."
+            }
+            ProviderType::Github => "GitHub provider not configured. Set GITHUB_TOKEN.",
+            ProviderType::GoogleSheets => {
+                "Google Sheets provider not configured. Provide credentials at ~/.config/gspread/credentials.json."
+            }
+            ProviderType::Io => "I/O provider not configured.",
+            ProviderType::ObjectStore => {
+                "Object store provider not configured. Set OBJECT_STORE_BUCKETS and AWS credentials."
+            }
+            ProviderType::Postgres => {
+                "Postgres provider not configured. Set POSTGRES_URL to a connection string."
+            }
+            ProviderType::Workspace => {
+                "Workspace provider not configured. Set with_workspace_tools."
+            }
+        }
+    }
+
+    /// error shown when the provider is required but couldn't be initialized
+    fn missing_message(&self) -> &'static str {
+        match self {
+            ProviderType::DatabricksRest => {
+                "DatabricksRest provider is required but not configured. Set DATABRICKS_HOST and credentials (DATABRICKS_TOKEN, a service principal or a ~/.databrickscfg profile)."
+            }
+            ProviderType::DatabricksCli => "DatabricksCli provider is required but not configured.",
+            ProviderType::Deployment => "Deployment provider is required but not configured.",
+            ProviderType::Github => {
+                "GitHub provider is required but not configured. Set GITHUB_TOKEN."
+            }
+            ProviderType::GoogleSheets => "Google Sheets provider is required but not configured.",
+            ProviderType::Io => "I/O provider is required but not configured.",
+            ProviderType::ObjectStore => {
+                "Object store provider is required but not configured. Set OBJECT_STORE_BUCKETS."
+            }
+            ProviderType::Postgres => {
+                "Postgres provider is required but not configured. Set POSTGRES_URL."
+            }
+            ProviderType::Workspace => "Workspace provider is required but not configured.",
+        }
+    }
+}
+
+/// `entry` bound to `session_ctx` when its provider scopes local paths to the session
+fn bind_session(entry: &ProviderEntry, session_ctx: &SessionContext) -> ProviderEntry {
+    let handler = entry.handler.as_any();
+    match handler.downcast_ref::<DatabricksCliProvider>() {
+        Some(cli) => entry.with_handler(cli.with_session(session_ctx.clone())),
+        None => entry.clone(),
+    }
+}
+
+fn first_text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .find_map(|content| match &content.raw {
+            RawContent::Text(text) => Some(text.text.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// result returned when a tool call exceeds its configured time limit
+fn timed_out_result(tool_name: &str, limit: std::time::Duration) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "Tool call '{}' timed out after {}s (see \"timeouts\" in ~/.edda/config.json)",
+        tool_name,
+        limit.as_secs()
+    ))])
+}
+
+/// result returned when the client cancels a tool call before it finishes
+fn cancelled_result(tool_name: &str) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "Tool call '{}' was cancelled",
+        tool_name
+    ))])
+}
+
+/// the get_started tool, answered by CombinedProvider itself with the engine guide
+const GET_STARTED_TOOL: &str = "get_started";
+
+fn get_started_tool() -> Tool {
+    let schema = serde_json::json!({ "type": "object", "properties": {} });
+    Tool::new(
+        GET_STARTED_TOOL,
+        "Guidelines for building data apps with these tools: project states, workflow and conventions. Call it once before starting work.",
+        schema.as_object().cloned().unwrap_or_default(),
+    )
+}
+
+/// the set_env_var tool, answered by CombinedProvider itself so it's there before the
+/// provider needing the variable could be initialized
+pub const SET_ENV_VAR_TOOL: &str = "set_env_var";
+
+fn set_env_var_tool() -> Tool {
+    let schema = serde_json::json!({
+        "type": "object",
+        "properties": {
+            "name": {
+                "type": "string",
+                "enum": crate::env::SETTABLE_VARS,
+                "description": "Variable to set"
+            },
+            "value": {
+                "type": "string",
+                "description": "Value as given by the user"
+            }
+        },
+        "required": ["name", "value"]
+    });
+    Tool::new(
+        SET_ENV_VAR_TOOL,
+        "Save a credential or setting (e.g. GITHUB_TOKEN, POSTGRES_URL, DATABRICKS_WAREHOUSE_ID) to the edda .env file when a tool reports it missing. Variables that are already set can't be changed. Ask the user for the value, never guess it. Lists the variables enabled providers still miss; providers are reloaded once the file changes.",
+        schema.as_object().cloned().unwrap_or_default(),
+    )
 }
 
 /// inject engine guide into the first text content of a tool result
@@ -57,36 +306,160 @@ fn inject_engine_guide(result: &mut CallToolResult) {
     }
 }
 
+fn builtin(kind: ProviderType, handler: impl ToolProvider) -> ProviderEntry {
+    ProviderEntry::new(kind.name(), kind.display_name(), kind.tools(), handler)
+}
+
+/// providers other than I/O and workspace are only enabled when listed in required_providers
+fn should_enable_provider(config: &crate::config::Config, provider: ProviderType) -> bool {
+    config.required_providers.contains(&provider)
+}
+
 #[derive(Clone)]
 pub struct CombinedProvider {
     session_ctx: SessionContext,
-    databricks: Option<Arc<DatabricksRestProvider>>,
-    databricks_cli: Option<Arc<DatabricksCliProvider>>,
-    deployment: Option<Arc<DeploymentProvider>>,
-    google_sheets: Option<Arc<GoogleSheetsProvider>>,
-    io: Option<Arc<IOProvider>>,
-    workspace: Option<Arc<WorkspaceTools>>,
+    /// providers in registration order, which is also the order tools are listed in
+    providers: Vec<ProviderEntry>,
     screenshot_enabled: bool,
+    /// whether screenshots go through the long-lived daemon
+    screenshot_daemon: bool,
+    notifier: Option<Arc<WebhookNotifier>>,
+    telemetry: Option<Arc<Telemetry>>,
+    timeouts: crate::config::TimeoutsConfig,
+    redactor: Arc<Redactor>,
+    json_output: bool,
+    engine_guide: crate::config::EngineGuideConfig,
+    /// variables the enabled providers need, reported by set_env_var until they are set
+    env_requirements: Vec<(ProviderType, &'static str)>,
+}
+
+/// Integrations for a [`CombinedProvider`], created with [`CombinedProvider::builder`];
+/// integrations left unset are disabled.
+pub struct CombinedProviderBuilder {
+    session_ctx: SessionContext,
+    databricks: Option<DatabricksRestProvider>,
+    databricks_cli: Option<DatabricksCliProvider>,
+    deployment: Option<DeploymentProvider>,
+    github: Option<GithubProvider>,
+    google_sheets: Option<GoogleSheetsProvider>,
+    io: Option<IOProvider>,
+    object_store: Option<ObjectStoreProvider>,
+    postgres: Option<PostgresProvider>,
+    workspace: Option<WorkspaceTools>,
+}
+
+impl CombinedProviderBuilder {
+    pub fn databricks(mut self, provider: impl Into<Option<DatabricksRestProvider>>) -> Self {
+        self.databricks = provider.into();
+        self
+    }
+
+    pub fn databricks_cli(mut self, provider: impl Into<Option<DatabricksCliProvider>>) -> Self {
+        self.databricks_cli = provider.into();
+        self
+    }
+
+    pub fn deployment(mut self, provider: impl Into<Option<DeploymentProvider>>) -> Self {
+        self.deployment = provider.into();
+        self
+    }
+
+    pub fn github(mut self, provider: impl Into<Option<GithubProvider>>) -> Self {
+        self.github = provider.into();
+        self
+    }
+
+    pub fn google_sheets(mut self, provider: impl Into<Option<GoogleSheetsProvider>>) -> Self {
+        self.google_sheets = provider.into();
+        self
+    }
+
+    pub fn io(mut self, provider: impl Into<Option<IOProvider>>) -> Self {
+        self.io = provider.into();
+        self
+    }
+
+    pub fn object_store(mut self, provider: impl Into<Option<ObjectStoreProvider>>) -> Self {
+        self.object_store = provider.into();
+        self
+    }
+
+    pub fn postgres(mut self, provider: impl Into<Option<PostgresProvider>>) -> Self {
+        self.postgres = provider.into();
+        self
+    }
+
+    pub fn workspace(mut self, provider: impl Into<Option<WorkspaceTools>>) -> Self {
+        self.workspace = provider.into();
+        self
+    }
+
+    /// no integration was set
+    fn is_empty(&self) -> bool {
+        self.databricks.is_none()
+            && self.databricks_cli.is_none()
+            && self.deployment.is_none()
+            && self.github.is_none()
+            && self.google_sheets.is_none()
+            && self.io.is_none()
+            && self.object_store.is_none()
+            && self.postgres.is_none()
+            && self.workspace.is_none()
+    }
+
+    pub fn build(self, config: &crate::config::Config) -> Result<CombinedProvider> {
+        CombinedProvider::from_builder(self, config)
+    }
 }
 
 impl CombinedProvider {
-    pub fn new(
-        session_ctx: SessionContext,
-        databricks: Option<DatabricksRestProvider>,
-        databricks_cli: Option<DatabricksCliProvider>,
-        deployment: Option<DeploymentProvider>,
-        google_sheets: Option<GoogleSheetsProvider>,
-        io: Option<IOProvider>,
-        workspace: Option<WorkspaceTools>,
+    /// start building a provider from individually constructed integrations
+    pub fn builder(session_ctx: SessionContext) -> CombinedProviderBuilder {
+        CombinedProviderBuilder {
+            session_ctx,
+            databricks: None,
+            databricks_cli: None,
+            deployment: None,
+            github: None,
+            google_sheets: None,
+            io: None,
+            object_store: None,
+            postgres: None,
+            workspace: None,
+        }
+    }
+
+    fn from_builder(
+        builder: CombinedProviderBuilder,
         config: &crate::config::Config,
     ) -> Result<Self> {
-        if databricks.is_none()
-            && databricks_cli.is_none()
-            && deployment.is_none()
-            && google_sheets.is_none()
-            && io.is_none()
-            && workspace.is_none()
-        {
+        let CombinedProviderBuilder {
+            session_ctx,
+            databricks,
+            databricks_cli,
+            deployment,
+            github,
+            google_sheets,
+            io,
+            object_store,
+            postgres,
+            workspace,
+        } = builder;
+        let providers: Vec<ProviderEntry> = [
+            databricks.map(|p| builtin(ProviderType::DatabricksRest, p)),
+            databricks_cli.map(|p| builtin(ProviderType::DatabricksCli, p)),
+            deployment.map(|p| builtin(ProviderType::Deployment, p)),
+            github.map(|p| builtin(ProviderType::Github, p)),
+            google_sheets.map(|p| builtin(ProviderType::GoogleSheets, p)),
+            io.map(|p| builtin(ProviderType::Io, p)),
+            object_store.map(|p| builtin(ProviderType::ObjectStore, p)),
+            postgres.map(|p| builtin(ProviderType::Postgres, p)),
+            workspace.map(|p| builtin(ProviderType::Workspace, p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if providers.is_empty() {
             return Err(eyre::eyre!("at least one provider must be available"));
         }
 
@@ -97,94 +470,398 @@ impl CombinedProvider {
             .and_then(|io| io.screenshot.as_ref())
             .and_then(|screenshot| screenshot.enabled)
             .unwrap_or(true);
+        let screenshot_daemon = config
+            .io_config
+            .as_ref()
+            .and_then(|io| io.screenshot.as_ref())
+            .and_then(|screenshot| screenshot.daemon)
+            .unwrap_or(false);
+
+        let notifier = config
+            .notifications
+            .as_ref()
+            .and_then(WebhookNotifier::from_config)
+            .map(Arc::new);
+
+        let telemetry = config
+            .telemetry
+            .as_ref()
+            .and_then(Telemetry::from_config)
+            .map(Arc::new);
+
+        let redactor = Arc::new(Redactor::new(config.redaction.as_ref())?);
 
         Ok(Self {
             session_ctx,
-            databricks: databricks.map(Arc::new),
-            databricks_cli: databricks_cli.map(Arc::new),
-            deployment: deployment.map(Arc::new),
-            google_sheets: google_sheets.map(Arc::new),
-            io: io.map(Arc::new),
-            workspace: workspace.map(Arc::new),
+            providers,
             screenshot_enabled,
+            screenshot_daemon,
+            notifier,
+            telemetry,
+            timeouts: config.timeouts.clone().unwrap_or_default(),
+            redactor,
+            json_output: config.json_output_enabled(),
+            engine_guide: config.engine_guide.clone().unwrap_or_default(),
+            env_requirements: crate::env::requirements(config),
         })
     }
 
-    fn resolve_provider(&self, tool_name: &str) -> std::result::Result<TargetProvider, ErrorData> {
-        if tool_name.starts_with("databricks_") {
-            let provider = self.databricks.clone().ok_or_else(|| {
-                ErrorData::invalid_params(
-                    "Databricks provider not configured. Set DATABRICKS_HOST and DATABRICKS_TOKEN.",
-                    None,
-                )
-            })?;
-            return Ok(TargetProvider::DatabricksRest(provider));
+    /// Initialize every provider enabled by `config`; providers whose credentials are
+    /// missing are left out.
+    pub async fn from_config(
+        session_ctx: SessionContext,
+        config: &crate::config::Config,
+    ) -> Result<Self> {
+        let databricks = match should_enable_provider(config, ProviderType::DatabricksRest) {
+            true => DatabricksRestProvider::with_auth(&config.databricks_auth).ok(),
+            false => None,
+        };
+        let databricks_cli = match should_enable_provider(config, ProviderType::DatabricksCli) {
+            true => DatabricksCliProvider::with_auth(&config.databricks_auth)
+                .ok()
+                .map(|cli| {
+                    cli.with_paths(
+                        session_ctx.clone(),
+                        PathPolicy::new(&config.workspace_allowed_paths),
+                    )
+                }),
+            false => None,
+        };
+        let deployment = match config.with_deployment {
+            true => DeploymentProvider::new().ok(),
+            false => None,
+        };
+        let github = match should_enable_provider(config, ProviderType::Github) {
+            true => GithubProvider::new().ok(),
+            false => None,
+        };
+        let google_sheets = match should_enable_provider(config, ProviderType::GoogleSheets) {
+            true => GoogleSheetsProvider::new().await.ok(),
+            false => None,
+        };
+        let object_store = match should_enable_provider(config, ProviderType::ObjectStore) {
+            true => ObjectStoreProvider::new().ok(),
+            false => None,
+        };
+        let postgres = match should_enable_provider(config, ProviderType::Postgres) {
+            true => PostgresProvider::new().await.ok(),
+            false => None,
+        };
+        let io = IOProvider::new(config.io_config.clone())
+            .ok()
+            .map(|io| io.with_timeouts(&config.timeouts.clone().unwrap_or_default()));
+        let workspace = match config.with_workspace_tools {
+            true => WorkspaceTools::new(
+                session_ctx.clone(),
+                PathPolicy::new(&config.workspace_allowed_paths),
+            )
+            .ok(),
+            false => None,
+        };
+
+        let builder = Self::builder(session_ctx)
+            .databricks(databricks)
+            .databricks_cli(databricks_cli)
+            .deployment(deployment)
+            .github(github)
+            .google_sheets(google_sheets)
+            .io(io)
+            .object_store(object_store)
+            .postgres(postgres)
+            .workspace(workspace);
+        if builder.is_empty() {
+            return Err(eyre::eyre!(
+                "No integrations available. Configure at least one:\n\
+                 - Databricks: Set DATABRICKS_HOST and DATABRICKS_TOKEN\n\
+                 - Deployment: Set DATABRICKS_HOST and DATABRICKS_TOKEN)\n\
+                 - Google Sheets: Place credentials at ~/.config/gspread/credentials.json\n\
+                 - GitHub: Set GITHUB_TOKEN and add Github to required_providers\n\
+                 - Object Store: Set OBJECT_STORE_BUCKETS and AWS credentials, add ObjectStore to required_providers\n\
+                 - Postgres: Set POSTGRES_URL and add Postgres to required_providers\n\
+                 - I/O: Always available (includes Workspace tools)"
+            ));
         }
+        builder
+            .build(config)
+            .wrap_err("failed to initialize the providers")
+    }
 
-        if tool_name == "run_databricks_cli" {
-            let provider = self.databricks_cli.clone().ok_or_else(|| {
-                ErrorData::invalid_params(
-                    "DatabricksCli provider not configured.",
-                    None,
-                )
-            })?;
-            return Ok(TargetProvider::DatabricksCli(provider));
+    /// display names of the configured providers
+    pub fn provider_names(&self) -> Vec<&str> {
+        self.providers
+            .iter()
+            .map(|entry| entry.display_name.as_str())
+            .collect()
+    }
+
+    /// Add a provider after the built-in ones, e.g. one maintained in a fork. Its tools
+    /// are listed and routed like the others; tools matched by an earlier entry stay
+    /// with that entry.
+    pub fn register(&mut self, entry: ProviderEntry) {
+        self.providers.push(entry);
+    }
+
+    fn workspace(&self) -> Option<&WorkspaceTools> {
+        self.providers
+            .iter()
+            .find_map(|entry| entry.handler.as_any().downcast_ref::<WorkspaceTools>())
+    }
+
+    /// copy of this provider bound to another session, used when one server handles
+    /// several clients (HTTP transport)
+    pub fn with_session(&self, session_ctx: SessionContext) -> Self {
+        let providers = self
+            .providers
+            .iter()
+            .map(
+                |entry| match entry.handler.as_any().downcast_ref::<WorkspaceTools>() {
+                    Some(workspace) => {
+                        entry.with_handler(workspace.with_session(session_ctx.clone()))
+                    }
+                    None => bind_session(entry, &session_ctx),
+                },
+            )
+            .collect();
+        Self {
+            session_ctx,
+            providers,
+            ..self.clone()
         }
+    }
 
-        if tool_name.starts_with("google_sheets_") {
-            let provider = self.google_sheets.clone().ok_or_else(|| {
-                ErrorData::invalid_params(
-                    "Google Sheets provider not configured. Provide credentials at ~/.config/gspread/credentials.json.",
-                    None,
-                )
-            })?;
-            return Ok(TargetProvider::GoogleSheets(provider));
+    /// `rebuilt` (from a changed config) bound to this provider's session; background
+    /// shells keep running when workspace tools stay enabled and providers added with
+    /// `register` stay registered
+    pub fn rebind(&self, rebuilt: &CombinedProvider) -> Self {
+        let current = self.workspace();
+        let mut providers: Vec<ProviderEntry> = rebuilt
+            .providers
+            .iter()
+            .map(
+                |entry| match entry.handler.as_any().downcast_ref::<WorkspaceTools>() {
+                    Some(workspace) => entry.with_handler(match current {
+                        Some(current) => workspace.with_state_of(current),
+                        None => workspace.with_session(self.session_ctx.clone()),
+                    }),
+                    None => bind_session(entry, &self.session_ctx),
+                },
+            )
+            .collect();
+        let registered: Vec<ProviderEntry> = self
+            .providers
+            .iter()
+            .filter(|entry| {
+                ProviderType::ALL
+                    .iter()
+                    .all(|kind| kind.name() != entry.category)
+                    && providers
+                        .iter()
+                        .all(|other| other.category != entry.category)
+            })
+            .cloned()
+            .collect();
+        providers.extend(registered);
+        Self {
+            session_ctx: self.session_ctx.clone(),
+            providers,
+            ..rebuilt.clone()
         }
+    }
 
-        if let Some(deployment) = self.deployment.clone() {
-            match tool_name {
-                "deploy_databricks_app" => {
-                    return Ok(TargetProvider::Deployment(deployment));
-                }
-                _ => {}
-            }
+    /// save session state for `--resume`; failures only cost resumability, so just log them
+    async fn persist_session(&self) {
+        if let Err(e) = self.session_ctx.persist().await {
+            tracing::warn!("Failed to persist session state: {}", e);
         }
+    }
 
-        if let Some(io) = self.io.clone() {
-            match tool_name {
-                "scaffold_data_app" | "validate_data_app" => {
-                    return Ok(TargetProvider::Io(io));
-                }
-                _ => {}
-            }
+    /// Make the project a call refers to active, based on its `work_dir` argument or an
+    /// absolute file path, so two apps can be worked on in one session.
+    async fn route_to_project(&self, params: &CallToolRequestParam) {
+        let Some(args) = params.arguments.as_ref() else {
+            return;
+        };
+        let path = ["work_dir", "file_path", "path"]
+            .iter()
+            .filter_map(|key| args.get(*key)?.as_str())
+            .map(std::path::Path::new)
+            .find(|path| path.is_absolute());
+        let Some(path) = path else {
+            return;
+        };
+        let previous = self.session_ctx.work_dir.read().await.clone();
+        let routed = self.session_ctx.route_to_project(path).await;
+        if routed.is_some() && routed != previous {
+            self.persist_session().await;
         }
+    }
 
-        // check workspace tools
-        if let Some(workspace) = self.workspace.clone() {
-            if matches!(
-                tool_name,
-                "read_file" | "write_file" | "edit_file" | "bash" | "grep" | "glob"
-            ) {
-                return Ok(TargetProvider::Workspace(workspace));
+    /// whether the engine guide is prepended for the client making this request
+    fn injects_guide(&self, context: &RequestContext<RoleServer>) -> bool {
+        let client = context
+            .peer
+            .peer_info()
+            .map(|info| info.client_info.name.as_str());
+        self.engine_guide.injects_for(client)
+    }
+
+    /// mask secrets before a result leaves the server (client, notifications, history)
+    fn redact(
+        &self,
+        result: std::result::Result<CallToolResult, ErrorData>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        match result {
+            Ok(mut result) => {
+                self.redactor.redact_result(&mut result);
+                Ok(result)
+            }
+            Err(mut error) => {
+                self.redactor.redact_error(&mut error);
+                Err(error)
             }
         }
+    }
 
-        let mut configured = Vec::new();
-        if let Some(provider) = &self.databricks {
-            configured.push(TargetProvider::DatabricksRest(Arc::clone(provider)));
+    /// In JSON output mode append the structured result (or the text, for tools without
+    /// one) as a JSON content block; otherwise the content stays text-only as before. The
+    /// structured result is kept either way, clients reading it shouldn't depend on the mode.
+    fn apply_output_mode(&self, result: &mut CallToolResult) {
+        if !self.json_output {
+            return;
         }
-        if let Some(provider) = &self.deployment {
-            configured.push(TargetProvider::Deployment(Arc::clone(provider)));
+        let value = result
+            .structured_content
+            .clone()
+            .unwrap_or_else(|| serde_json::json!({ "text": first_text(result) }));
+        if let Ok(content) = Content::json(&value) {
+            result.content.push(content);
         }
-        if let Some(provider) = &self.google_sheets {
-            configured.push(TargetProvider::GoogleSheets(Arc::clone(provider)));
+        result.structured_content = Some(value);
+    }
+
+    /// route a tool call to the provider that owns it
+    async fn dispatch_tool(
+        &self,
+        params: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        self.resolve_provider(&params.name)?
+            .handler
+            .call(params, context)
+            .await
+    }
+
+    /// send a webhook notification for tool outcomes teams care about, without blocking the tool call
+    fn notify_tool_outcome(
+        &self,
+        params: &CallToolRequestParam,
+        result: &std::result::Result<CallToolResult, ErrorData>,
+    ) {
+        let Some(notifier) = self.notifier.clone() else {
+            return;
+        };
+        let arg = |key: &str| {
+            params
+                .arguments
+                .as_ref()
+                .and_then(|args| args.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let notification = match (params.name.as_ref(), result) {
+            ("validate_data_app", Ok(result)) if result.is_error != Some(true) => {
+                Notification::new(
+                    NotificationEvent::ValidationPassed,
+                    "Validation passed",
+                    &arg("work_dir"),
+                )
+            }
+            ("deploy_databricks_app", Ok(result)) => Notification::new(
+                NotificationEvent::DeploymentFinished,
+                &format!("Deployed app '{}'", arg("name")),
+                &first_text(result),
+            ),
+            ("deploy_databricks_app", Err(e)) => Notification::new(
+                NotificationEvent::DeploymentFailed,
+                &format!("Deployment of app '{}' failed", arg("name")),
+                &e.message,
+            ),
+            _ => return,
+        };
+
+        tokio::spawn(async move {
+            if let Err(e) = notifier.notify(&notification).await {
+                tracing::warn!("Failed to send notification: {}", e);
+            }
+        });
+    }
+
+    fn set_env_var(
+        &self,
+        params: &CallToolRequestParam,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let arg = |key: &str| {
+            params
+                .arguments
+                .as_ref()
+                .and_then(|args| args.get(key))
+                .and_then(|value| value.as_str())
+        };
+        let (Some(name), Some(value)) = (arg("name"), arg("value")) else {
+            return Err(ErrorData::invalid_params(
+                "name and value are required",
+                None,
+            ));
+        };
+        let path = crate::env::set_env_var(name, value)
+            .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+
+        let mut text = format!(
+            "Saved {}={} to {}",
+            name,
+            crate::env::mask(name, value),
+            path.display()
+        );
+        let missing = crate::env::EnvVars::current().missing(&self.env_requirements);
+        match missing.is_empty() {
+            true => text.push_str("\nEvery variable the enabled providers need is set."),
+            false => {
+                let names: Vec<String> = missing
+                    .iter()
+                    .map(|(provider, name)| format!("{} ({})", name, provider.display_name()))
+                    .collect();
+                text.push_str(&format!("\nStill missing: {}", names.join(", ")));
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+
+    /// name a call is recorded under: only tools this server knows about are reported,
+    /// anything else a client sends could be arbitrary text
+    fn telemetry_name<'a>(&self, tool_name: &'a str) -> &'a str {
+        let known = [GET_STARTED_TOOL, SET_ENV_VAR_TOOL].contains(&tool_name)
+            || ProviderType::for_tool(tool_name).is_some()
+            || self.providers.iter().any(|entry| entry.handles(tool_name));
+        match known {
+            true => tool_name,
+            false => "unknown",
         }
-        if let Some(provider) = &self.io {
-            configured.push(TargetProvider::Io(Arc::clone(provider)));
+    }
+
+    fn resolve_provider(&self, tool_name: &str) -> std::result::Result<&ProviderEntry, ErrorData> {
+        if let Some(entry) = self.providers.iter().find(|entry| entry.handles(tool_name)) {
+            return Ok(entry);
         }
 
-        if configured.len() == 1 {
-            return Ok(configured.into_iter().next().unwrap());
+        if let Some(kind) = ProviderType::for_tool(tool_name) {
+            return Err(ErrorData::invalid_params(kind.not_configured_hint(), None));
+        }
+
+        // a lone provider gets every call, it reports unknown tools itself
+        if let [entry] = self.providers.as_slice() {
+            return Ok(entry);
         }
 
         Err(ErrorData::invalid_params(
@@ -195,93 +872,19 @@ impl CombinedProvider {
 
     pub fn check_availability(&self, required: &[ProviderType]) -> Result<()> {
         for provider in required {
-            match provider {
-                ProviderType::DatabricksRest => {
-                    if self.databricks.is_none() {
-                        return Err(eyre::eyre!(
-                            "DatabricksRest provider is required but not configured. Environment variables DATABRICKS_HOST, DATABRICKS_TOKEN, DATABRICKS_WAREHOUSE_ID must be set."
-                        ));
-                    }
-                }
-                ProviderType::DatabricksCli => {
-                    if self.databricks_cli.is_none() {
-                        return Err(eyre::eyre!(
-                            "DatabricksCli provider is required but not configured."
-                        ));
-                    }
-                }
-                ProviderType::Deployment => {
-                    if self.deployment.is_none() {
-                        return Err(eyre::eyre!(
-                            "Deployment provider is required but not configured."
-                        ));
-                    }
-                }
-                ProviderType::GoogleSheets => {
-                    if self.google_sheets.is_none() {
-                        return Err(eyre::eyre!(
-                            "Google Sheets provider is required but not configured."
-                        ));
-                    }
-                }
-                ProviderType::Io => {
-                    if self.io.is_none() {
-                        return Err(eyre::eyre!("I/O provider is required but not configured."));
-                    }
-                }
-                ProviderType::Workspace => {
-                    if self.workspace.is_none() {
-                        return Err(eyre::eyre!(
-                            "Workspace provider is required but not configured."
-                        ));
-                    }
-                }
+            if !self
+                .providers
+                .iter()
+                .any(|entry| entry.category == provider.name())
+            {
+                return Err(eyre::eyre!(provider.missing_message()));
             }
         }
         Ok(())
     }
-}
-
-impl ServerHandler for CombinedProvider {
-    fn get_info(&self) -> ServerInfo {
-        let mut providers = Vec::new();
-        if self.databricks.is_some() {
-            providers.push("Databricks");
-        }
-        if self.databricks_cli.is_some() {
-            providers.push("Databricks CLI");
-        }
-        if self.deployment.is_some() {
-            providers.push("Deployment");
-        }
-        if self.google_sheets.is_some() {
-            providers.push("Google Sheets");
-        }
-        if self.io.is_some() {
-            providers.push("I/O");
-        }
-        if self.workspace.is_some() {
-            providers.push("Workspace");
-        }
-
-        ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
-            server_info: Implementation {
-                name: "edda-mcp".to_string(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-                title: Some("Edda MCP Server".to_string()),
-                website_url: None,
-                icons: None,
-            },
-            instructions: Some(format!(
-                "MCP server providing integrations for: {}",
-                providers.join(", ")
-            )),
-        }
-    }
 
-    async fn call_tool(
+    /// everything `call_tool` does apart from recording telemetry
+    async fn handle_tool_call(
         &self,
         params: CallToolRequestParam,
         context: RequestContext<RoleServer>,
@@ -295,9 +898,15 @@ impl ServerHandler for CombinedProvider {
             }
             is_first
         };
+        if is_first_call {
+            self.persist_session().await;
+        }
 
-        // optimization: warmup playwright on first tool call if screenshots enabled
-        if is_first_call && self.screenshot_enabled {
+        // optimization: warmup playwright on first tool call if screenshots enabled, the
+        // daemon builds the same container and keeps it running
+        if is_first_call && self.screenshot_enabled && self.screenshot_daemon {
+            tokio::spawn(crate::screenshot_daemon::warmup());
+        } else if is_first_call && self.screenshot_enabled {
             let playwright_warmed = self.session_ctx.playwright_warmed.clone();
 
             // spawn non-blocking warmup task
@@ -342,92 +951,153 @@ impl ServerHandler for CombinedProvider {
             });
         }
 
-        // intercept scaffold_data_app to set work_dir in session context
-        if params.name == "scaffold_data_app" {
-            if let Some(ref io) = self.io {
-                let mut result = io.call_tool(params.clone(), context.clone()).await?;
+        if params.name == GET_STARTED_TOOL {
+            let mut result = CallToolResult::success(vec![Content::text(
+                crate::engine_guide::ENGINE_GUIDE.trim(),
+            )]);
+            self.apply_output_mode(&mut result);
+            return Ok(result);
+        }
+        if params.name == SET_ENV_VAR_TOOL {
+            let mut result = self.set_env_var(&params)?;
+            self.apply_output_mode(&mut result);
+            return Ok(result);
+        }
+        let inject_guide = is_first_call && self.injects_guide(&context);
 
-                // extract work_dir from arguments and set it in session context
-                if let Some(args) = params.arguments {
-                    if let Some(work_dir) = args.get("work_dir").and_then(|v| v.as_str()) {
-                        let path = std::path::PathBuf::from(work_dir);
+        // scaffold_data_app registers its work_dir as a project once it's done
+        let scaffold_dir = match params.name == "scaffold_data_app" {
+            true => params
+                .arguments
+                .as_ref()
+                .and_then(|args| args.get("work_dir"))
+                .and_then(|v| v.as_str())
+                .map(std::path::PathBuf::from),
+            false => {
+                self.route_to_project(&params).await;
+                None
+            }
+        };
 
-                        // validate path exists and is directory
-                        if path.exists() && path.is_dir() {
-                            let mut work_dir_lock = self.session_ctx.work_dir.write().await;
-                            if work_dir_lock.is_none() {
-                                *work_dir_lock = Some(path);
-                            }
-                        }
-                    }
-                }
+        let notify_params = self.notifier.as_ref().map(|_| params.clone());
+        let tool_name = params.name.clone();
+        let cancellation = context.ct.clone();
+        let category = self
+            .resolve_provider(&tool_name)
+            .ok()
+            .map(|entry| entry.category.as_str());
+        let time_limit = self.timeouts.for_tool(&tool_name, category);
 
-                if is_first_call {
-                    inject_engine_guide(&mut result);
-                }
+        // the provider gets a child token so hitting the time limit also stops background
+        // work such as deployment polling, the same way a client cancellation does
+        let mut context = context;
+        let call_token = context.ct.child_token();
+        context.ct = call_token.clone();
+        let call = async {
+            match time_limit {
+                Some(limit) => tokio::time::timeout(limit, self.dispatch_tool(params, context))
+                    .await
+                    .ok(),
+                None => Some(self.dispatch_tool(params, context).await),
+            }
+        };
 
-                return Ok(result);
+        // dropping the in-flight call on cancellation tears down its dagger session and
+        // stops deployment polling (see the cancellation token handling in the providers)
+        let result = tokio::select! {
+            result = call => match result {
+                Some(result) => result,
+                None => {
+                    call_token.cancel();
+                    let limit = time_limit.unwrap_or_default();
+                    tracing::warn!("Tool call '{}' timed out after {}s", tool_name, limit.as_secs());
+                    return Ok(timed_out_result(&tool_name, limit));
+                }
+            },
+            _ = cancellation.cancelled() => {
+                tracing::info!("Tool call '{}' cancelled by client", tool_name);
+                return Ok(cancelled_result(&tool_name));
             }
+        };
+        let result = self.redact(result);
+        if let Some(params) = notify_params {
+            self.notify_tool_outcome(&params, &result);
         }
+        let mut result = result?;
+        self.apply_output_mode(&mut result);
 
-        let mut result = match self.resolve_provider(&params.name)? {
-            TargetProvider::DatabricksRest(provider) => provider.call_tool(params, context).await,
-            TargetProvider::DatabricksCli(provider) => provider.call_tool(params, context).await,
-            TargetProvider::Deployment(provider) => provider.call_tool(params, context).await,
-            TargetProvider::GoogleSheets(provider) => provider.call_tool(params, context).await,
-            TargetProvider::Io(provider) => provider.call_tool(params, context).await,
-            TargetProvider::Workspace(provider) => provider.call_tool(params, context).await,
-        }?;
+        // a new project becomes the active one, earlier projects stay reachable via
+        // switch_project; a failed scaffold leaves the active project as it was
+        if let Some(path) = scaffold_dir
+            && result.is_error != Some(true)
+            && path.is_dir()
+        {
+            self.session_ctx.register_project(path).await;
+            self.persist_session().await;
+        }
 
-        if is_first_call {
+        if inject_guide {
             inject_engine_guide(&mut result);
         }
 
         Ok(result)
     }
+}
 
-    async fn list_tools(
-        &self,
-        params: Option<PaginatedRequestParam>,
-        context: RequestContext<RoleServer>,
-    ) -> std::result::Result<rmcp::model::ListToolsResult, ErrorData> {
-        let mut tools = Vec::new();
-
-        if let Some(ref databricks) = self.databricks {
-            if let Ok(result) = databricks.list_tools(params.clone(), context.clone()).await {
-                tools.extend(result.tools);
-            }
-        }
-
-        if let Some(ref databricks_cli) = self.databricks_cli {
-            if let Ok(result) = databricks_cli.list_tools(params.clone(), context.clone()).await {
-                tools.extend(result.tools);
-            }
-        }
-
-        if let Some(ref deployment) = self.deployment {
-            if let Ok(result) = deployment.list_tools(params.clone(), context.clone()).await {
-                tools.extend(result.tools);
-            }
-        }
+impl ServerHandler for CombinedProvider {
+    fn get_info(&self) -> ServerInfo {
+        let providers = self.provider_names();
 
-        if let Some(ref google_sheets) = self.google_sheets {
-            if let Ok(result) = google_sheets
-                .list_tools(params.clone(), context.clone())
-                .await
-            {
-                tools.extend(result.tools);
-            }
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
+            server_info: Implementation {
+                name: "edda-mcp".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                title: Some("Edda MCP Server".to_string()),
+                website_url: None,
+                icons: None,
+            },
+            instructions: Some(format!(
+                "MCP server providing integrations for: {}",
+                providers.join(", ")
+            )),
         }
+    }
 
-        if let Some(ref io) = self.io {
-            if let Ok(result) = io.list_tools(params.clone(), context.clone()).await {
-                tools.extend(result.tools);
-            }
+    async fn call_tool(
+        &self,
+        params: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let Some(telemetry) = &self.telemetry else {
+            return self.handle_tool_call(params, context).await;
+        };
+        let tool_name = params.name.clone();
+        let cancellation = context.ct.clone();
+        let started = std::time::Instant::now();
+        let result = self.handle_tool_call(params, context).await;
+        // cancelled calls say nothing about the tool
+        if !cancellation.is_cancelled() {
+            let success = matches!(&result, Ok(result) if !result.is_error.unwrap_or(false));
+            telemetry.record(self.telemetry_name(&tool_name), started.elapsed(), success);
         }
+        result
+    }
 
-        if let Some(ref workspace) = self.workspace {
-            if let Ok(result) = workspace.list_tools(params.clone(), context.clone()).await {
+    async fn list_tools(
+        &self,
+        params: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<rmcp::model::ListToolsResult, ErrorData> {
+        let mut tools = vec![get_started_tool(), set_env_var_tool()];
+        for entry in &self.providers {
+            if let Ok(result) = entry.handler.list(params.clone(), context.clone()).await {
                 tools.extend(result.tools);
             }
         }
@@ -437,4 +1107,39 @@ impl ServerHandler for CombinedProvider {
             next_cursor: None,
         })
     }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<rmcp::model::ListResourcesResult, ErrorData> {
+        let resources = crate::resources::list_resources(&self.session_ctx).await;
+        Ok(rmcp::model::ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn read_resource(
+        &self,
+        request: rmcp::model::ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<rmcp::model::ReadResourceResult, ErrorData> {
+        crate::resources::read_resource(&self.session_ctx, &request.uri).await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<rmcp::model::ListPromptsResult, ErrorData> {
+        Ok(rmcp::model::ListPromptsResult::with_all_items(
+            crate::prompts::list_prompts(),
+        ))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: rmcp::model::GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<rmcp::model::GetPromptResult, ErrorData> {
+        crate::prompts::get_prompt(&request.name, request.arguments.as_ref())
+    }
 }
@@ -0,0 +1,107 @@
+use crate::mcp_helpers::structured_result;
+use edda_integrations::{
+    ObjectStoreClient, ObjectStoreGetArgs, ObjectStoreHeadArgs, ObjectStoreListArgs,
+    ObjectStoreListBucketsArgs, ObjectStorePresignArgs,
+};
+use eyre::Result;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::{CallToolResult, ServerInfo};
+use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ObjectStoreProvider {
+    client: Arc<ObjectStoreClient>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl ObjectStoreProvider {
+    pub fn new() -> Result<Self> {
+        let client = ObjectStoreClient::new()
+            .map_err(|e| eyre::eyre!("Failed to create object store client: {}", e))?;
+        Ok(Self {
+            client: Arc::new(client),
+            tool_router: Self::tool_router(),
+        })
+    }
+
+    #[tool(
+        name = "object_store_list_buckets",
+        description = "List the S3-compatible buckets configured for this session"
+    )]
+    pub async fn list_buckets(
+        &self,
+        Parameters(_args): Parameters<ObjectStoreListBucketsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let result = self.client.list_buckets();
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "object_store_list",
+        description = "List prefixes and objects under a prefix in an S3-compatible bucket. \
+                       Use this to discover CSV/Parquet/JSON data files."
+    )]
+    pub async fn list(
+        &self,
+        Parameters(args): Parameters<ObjectStoreListArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "object_store_head",
+        description = "Get size, last modified time and inferred format of an object"
+    )]
+    pub async fn head(
+        &self,
+        Parameters(args): Parameters<ObjectStoreHeadArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.head(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "object_store_get",
+        description = "Read the beginning of an object (64KB by default) to inspect headers and sample rows. \
+                       Binary objects such as Parquet only return metadata."
+    )]
+    pub async fn get(
+        &self,
+        Parameters(args): Parameters<ObjectStoreGetArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.get(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "object_store_presign",
+        description = "Generate a time-limited presigned GET URL for an object, \
+                       e.g. to let a generated app download a data file without credentials"
+    )]
+    pub async fn presign(
+        &self,
+        Parameters(args): Parameters<ObjectStorePresignArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.presign(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for ObjectStoreProvider {
+    fn get_info(&self) -> ServerInfo {
+        crate::mcp_helpers::internal_server_info()
+    }
+}
@@ -0,0 +1,92 @@
+use crate::mcp_helpers::structured_result;
+use edda_integrations::{
+    PostgresClient, PostgresDescribeTableArgs, PostgresListSchemasArgs, PostgresListTablesArgs,
+    PostgresQueryArgs,
+};
+use eyre::Result;
+use rmcp::handler::server::router::tool::ToolRouter;
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::{CallToolResult, ServerInfo};
+use rmcp::{tool, tool_handler, tool_router, ErrorData, ServerHandler};
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct PostgresProvider {
+    client: Arc<PostgresClient>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl PostgresProvider {
+    pub async fn new() -> Result<Self> {
+        let client = PostgresClient::new()
+            .await
+            .map_err(|e| eyre::eyre!("Failed to create Postgres client: {}", e))?;
+        Ok(Self {
+            client: Arc::new(client),
+            tool_router: Self::tool_router(),
+        })
+    }
+
+    #[tool(name = "postgres_list_schemas", description = "List schemas in the Postgres database")]
+    pub async fn list_schemas(
+        &self,
+        Parameters(_args): Parameters<PostgresListSchemasArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list_schemas().await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "postgres_list_tables",
+        description = "List tables and views in a Postgres schema (default: public) with estimated row counts"
+    )]
+    pub async fn list_tables(
+        &self,
+        Parameters(args): Parameters<PostgresListTablesArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.list_tables(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "postgres_describe_table",
+        description = "Get columns, primary key, estimated row count and sample rows of a Postgres table"
+    )]
+    pub async fn describe_table(
+        &self,
+        Parameters(args): Parameters<PostgresDescribeTableArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.describe_table(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+
+    #[tool(
+        name = "postgres_query",
+        description = "Run a read-only SQL query against Postgres. \
+                       Runs in a READ ONLY transaction with a 60 second statement timeout - writes are rejected. \
+                       Returns up to 100 rows by default; set max_rows (up to 10000) for more."
+    )]
+    pub async fn query(
+        &self,
+        Parameters(args): Parameters<PostgresQueryArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        match self.client.query(&args).await {
+            Ok(result) => Ok(structured_result(&result, true)),
+            Err(e) => Err(ErrorData::internal_error(e.to_string(), None)),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for PostgresProvider {
+    fn get_info(&self) -> ServerInfo {
+        crate::mcp_helpers::internal_server_info()
+    }
+}
@@ -0,0 +1,95 @@
+//! Registry of the providers behind `CombinedProvider`. Each entry names the tools it
+//! owns by prefix, so a fork can add a provider by registering one more entry.
+
+use rmcp::model::{CallToolRequestParam, CallToolResult, ListToolsResult, PaginatedRequestParam};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::{ErrorData, ServerHandler};
+use std::any::Any;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The part of a provider `CombinedProvider` needs, in a form that can be stored as a
+/// trait object. Every `ServerHandler` implements it.
+pub trait ToolProvider: Send + Sync + 'static {
+    fn list(
+        &self,
+        params: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> BoxFuture<'_, Result<ListToolsResult, ErrorData>>;
+
+    fn call(
+        &self,
+        params: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> BoxFuture<'_, Result<CallToolResult, ErrorData>>;
+
+    /// access to the concrete provider, for providers bound to a session
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: ServerHandler> ToolProvider for T {
+    fn list(
+        &self,
+        params: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> BoxFuture<'_, Result<ListToolsResult, ErrorData>> {
+        Box::pin(ServerHandler::list_tools(self, params, context))
+    }
+
+    fn call(
+        &self,
+        params: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> BoxFuture<'_, Result<CallToolResult, ErrorData>> {
+        Box::pin(ServerHandler::call_tool(self, params, context))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct ProviderEntry {
+    /// Name used in `required_providers` and the timeouts config, e.g. "Postgres"
+    pub category: String,
+    /// Name listed in the server instructions, e.g. "Google Sheets"
+    pub display_name: String,
+    /// Tools routed to this provider: a call goes to the first entry with a prefix of
+    /// the tool name (a full tool name is a prefix too)
+    pub prefixes: Vec<String>,
+    pub handler: Arc<dyn ToolProvider>,
+}
+
+impl ProviderEntry {
+    pub fn new(
+        category: &str,
+        display_name: &str,
+        prefixes: &[&str],
+        handler: impl ToolProvider,
+    ) -> Self {
+        Self {
+            category: category.to_string(),
+            display_name: display_name.to_string(),
+            prefixes: prefixes.iter().map(|p| p.to_string()).collect(),
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn handles(&self, tool_name: &str) -> bool {
+        self.prefixes
+            .iter()
+            .any(|prefix| tool_name.starts_with(prefix.as_str()))
+    }
+
+    /// same entry served by another handler, e.g. one bound to a different session
+    pub fn with_handler(&self, handler: impl ToolProvider) -> Self {
+        Self {
+            handler: Arc::new(handler),
+            ..self.clone()
+        }
+    }
+}
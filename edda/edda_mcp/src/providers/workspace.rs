@@ -1,3 +1,5 @@
+use crate::mcp_helpers::structured_result;
+use crate::search;
 use crate::session::SessionContext;
 use eyre::{eyre, Result};
 use rmcp::handler::server::router::tool::ToolRouter;
@@ -6,41 +8,316 @@ use rmcp::model::{CallToolResult, Content, ServerInfo};
 use rmcp::{ErrorData, ServerHandler, tool, tool_handler, tool_router};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
+use tokio::sync::Mutex;
 
-/// validates that file_path is within base_dir to prevent directory traversal
-fn validate_path(base_dir: &Path, file_path: &str) -> Result<PathBuf> {
-    let base = base_dir.canonicalize()?;
-    let target = base.join(file_path);
-
-    // resolve symlinks and check if within base
-    let resolved = match target.canonicalize() {
-        Ok(p) => p,
-        Err(_) => {
-            // if file doesn't exist yet, check parent directory
-            if let Some(parent) = target.parent() {
-                if parent.exists() {
-                    let parent_canonical = parent.canonicalize()?;
-                    if !parent_canonical.starts_with(&base) {
-                        return Err(eyre!("Access denied: path outside base directory"));
-                    }
-                    target
-                } else {
-                    return Err(eyre!("Parent directory does not exist"));
+/// Paths outside the work directory that bash commands may always reference
+const BUILTIN_ALLOWED_PATHS: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr", "/tmp"];
+
+/// Directories whose executables bash commands may run by absolute path
+const SYSTEM_BINARY_PATHS: &[&str] = &["/bin", "/sbin", "/usr", "/opt"];
+
+/// Canonicalize the longest existing ancestor of `path` and append the rest, so files
+/// that don't exist yet still resolve through symlinked parents.
+fn resolve(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.file_name(), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name.to_os_string());
+                existing = parent.to_path_buf();
+            }
+            _ => break,
+        }
+    }
+    let mut resolved = existing.canonicalize().unwrap_or(existing);
+    resolved.extend(rest.iter().rev());
+    resolved
+}
+
+/// Which paths workspace tools may touch: the session work_dir plus an explicit allowlist
+/// (`workspace_allowed_paths` in ~/.edda/config.json).
+#[derive(Debug, Clone, Default)]
+pub struct PathPolicy {
+    allowed: Vec<PathBuf>,
+}
+
+impl PathPolicy {
+    pub fn new(allowed: &[String]) -> Self {
+        let home = dirs::home_dir();
+        let allowed = allowed
+            .iter()
+            .map(|path| match (path.strip_prefix("~/"), &home) {
+                (Some(relative), Some(home)) => home.join(relative),
+                _ => PathBuf::from(path),
+            })
+            .filter(|path| path.is_absolute())
+            .map(|path| resolve(&path))
+            .collect();
+        Self { allowed }
+    }
+
+    fn is_allowed(&self, base: &Path, resolved: &Path) -> bool {
+        resolved.starts_with(base) || self.is_allowlisted(resolved)
+    }
+
+    /// `resolved` is inside one of the configured allowed paths
+    fn is_allowlisted(&self, resolved: &Path) -> bool {
+        self.allowed.iter().any(|a| resolved.starts_with(a))
+    }
+
+    /// Resolve `file_path` (relative to base_dir, or absolute) and check it stays inside
+    /// base_dir or the allowlist. `..` components are rejected outright.
+    pub fn validate_path(&self, base_dir: &Path, file_path: &str) -> Result<PathBuf> {
+        let path = Path::new(file_path);
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err(eyre!("Access denied: '..' is not allowed in paths"));
+        }
+        let base = base_dir.canonicalize()?;
+        let resolved = resolve(&base.join(path));
+        if !self.is_allowed(&base, &resolved) {
+            return Err(eyre!(
+                "Access denied: {} is outside the project directory {}",
+                file_path,
+                base.display()
+            ));
+        }
+        Ok(resolved)
+    }
+
+    /// Best-effort check of the paths a shell command mentions. Absolute paths are only
+    /// checked when their top-level directory exists on this machine (so "/api/users" in
+    /// a grep pattern passes) or when they contain glob characters; in command position
+    /// they must be a system binary (so /usr/bin/env works) or inside the allowed paths.
+    /// Relative paths resolve against the directory the last `cd` moved to.
+    pub fn check_command(&self, base_dir: &Path, command: &str) -> Result<()> {
+        let base = base_dir.canonicalize()?;
+        let words = shell_words(command);
+        let mut cwd = base.clone();
+        for (index, (word, is_command)) in words.iter().enumerate() {
+            let is_command = *is_command;
+            let cd_target = index > 0 && words[index - 1].1 && words[index - 1].0 == "cd";
+            if is_command && word == "cd" && words.get(index + 1).is_none_or(|next| next.1) {
+                return Err(eyre!(
+                    "Access denied: 'cd' without a directory goes to the home directory, stay inside {}",
+                    base.display()
+                ));
+            }
+            // --out=/path and VAR=/path
+            let word = word.rsplit('=').next().unwrap_or(word);
+            if word.starts_with('~') || word.contains("$HOME") || word.contains("${HOME}") {
+                return Err(eyre!(
+                    "Access denied: '{}' refers to the home directory, stay inside {}",
+                    word,
+                    base.display()
+                ));
+            }
+            let path = Path::new(word);
+            let normalized = match path.is_absolute() {
+                true => normalize(path),
+                false => normalize(&cwd.join(path)),
+            };
+            let escapes = match (&normalized, path.is_absolute()) {
+                (None, _) => true,
+                (Some(normalized), true) if is_command => {
+                    !SYSTEM_BINARY_PATHS
+                        .iter()
+                        .any(|p| normalized.starts_with(p))
+                        && !self.permits(&base, normalized)
                 }
-            } else {
-                return Err(eyre!("Invalid path"));
+                (Some(normalized), true) => {
+                    let top_level_exists = normalized
+                        .components()
+                        .nth(1)
+                        .is_some_and(|top| Path::new("/").join(top).exists());
+                    // the shell expands globs, so /e*/passwd can't be judged by its text
+                    let is_glob = word.contains(['*', '?', '[']);
+                    (top_level_exists || cd_target || is_glob) && !self.permits(&base, normalized)
+                }
+                (Some(normalized), false) => {
+                    (cd_target || path.components().any(|c| c == Component::ParentDir))
+                        && !self.is_allowed(&base, &resolve(normalized))
+                }
+            };
+            if escapes {
+                return Err(eyre!(
+                    "Access denied: '{}' is outside the project directory {}",
+                    word,
+                    base.display()
+                ));
+            }
+            if let (true, Some(normalized)) = (cd_target, normalized) {
+                cwd = resolve(&normalized);
             }
         }
+        Ok(())
+    }
+
+    /// `path` is inside base, the allowlist or the paths every command may use
+    fn permits(&self, base: &Path, path: &Path) -> bool {
+        BUILTIN_ALLOWED_PATHS.iter().any(|p| path.starts_with(p))
+            || self.is_allowed(base, &resolve(path))
+    }
+}
+
+/// Lexically resolve `.` and `..`; None when `..` climbs above the root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+/// Split a shell command into words with quotes removed, flagging words in command
+/// position (first word after the start, `;`, `|`, `&`, `(` or a newline).
+fn shell_words(command: &str) -> Vec<(String, bool)> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut command_position = true;
+    let mut flush = |current: &mut String, words: &mut Vec<(String, bool)>, next_is_command| {
+        if !current.is_empty() {
+            words.push((std::mem::take(current), command_position));
+            command_position = false;
+        }
+        if next_is_command {
+            command_position = true;
+        }
     };
+    for c in command.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, ';' | '|' | '&' | '(' | ')' | '\n') => flush(&mut current, &mut words, true),
+            (None, '<' | '>') => flush(&mut current, &mut words, false),
+            (None, c) if c.is_whitespace() => flush(&mut current, &mut words, false),
+            (None, c) => current.push(c),
+        }
+    }
+    flush(&mut current, &mut words, false);
+    words
+}
+
+/// Running bash_background commands per session
+const MAX_BACKGROUND_SHELLS: usize = 8;
+
+/// Output kept per background shell; older output is dropped first
+const MAX_BACKGROUND_OUTPUT: usize = 1_000_000;
+
+/// Output returned by one bash_output call, the rest is returned by the next poll
+const MAX_OUTPUT_CHARS: usize = 30000;
+
+#[derive(Debug, Default)]
+struct OutputBuffer {
+    text: String,
+    /// offset of the first byte not yet returned by bash_output
+    read: usize,
+    /// bytes dropped because the buffer was full
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    fn push_line(&mut self, line: &str) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        if self.text.len() > MAX_BACKGROUND_OUTPUT {
+            let excess = floor_char_boundary(&self.text, self.text.len() - MAX_BACKGROUND_OUTPUT);
+            self.text.drain(..excess);
+            self.read = self.read.saturating_sub(excess);
+            self.dropped += excess;
+        }
+    }
+
+    /// Output since the previous call, at most MAX_OUTPUT_CHARS bytes.
+    fn take_unread(&mut self) -> &str {
+        let start = self.read;
+        let end = floor_char_boundary(&self.text, (start + MAX_OUTPUT_CHARS).min(self.text.len()));
+        self.read = end;
+        &self.text[start..end]
+    }
+}
+
+fn floor_char_boundary(text: &str, mut idx: usize) -> usize {
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+/// A command started by bash_background. It runs in its own process group so kill_shell
+/// also stops the processes it spawned (e.g. the node server behind `npm run dev`).
+#[derive(Debug)]
+struct BackgroundShell {
+    command: String,
+    child: tokio::process::Child,
+    output: Arc<std::sync::Mutex<OutputBuffer>>,
+}
+
+impl BackgroundShell {
+    fn is_running(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn is_drained(&self) -> bool {
+        self.output
+            .lock()
+            .map(|output| output.read >= output.text.len())
+            .unwrap_or(true)
+    }
+
+    fn kill_group(&self) {
+        if let Some(pid) = self.child.id() {
+            let _ = std::process::Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", pid)])
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
 
-    if !resolved.starts_with(&base) {
-        return Err(eyre!("Access denied: path outside base directory"));
+impl Drop for BackgroundShell {
+    fn drop(&mut self) {
+        if self.is_running() {
+            self.kill_group();
+        }
     }
+}
 
-    Ok(resolved)
+#[derive(Debug, Default)]
+struct BackgroundShells {
+    next_id: usize,
+    shells: HashMap<String, BackgroundShell>,
+}
+
+/// Append lines from a child's stdout or stderr to its output buffer until it closes.
+fn collect_output<R>(reader: R, output: Arc<std::sync::Mutex<OutputBuffer>>)
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Ok(mut output) = output.lock() {
+                output.push_line(&line);
+            }
+        }
+    });
 }
 
 /// WorkspaceTools provides file operation tools scoped to a session's workspace directory.
@@ -49,17 +326,40 @@ fn validate_path(base_dir: &Path, file_path: &str) -> Result<PathBuf> {
 #[derive(Debug, Clone)]
 pub struct WorkspaceTools {
     session_ctx: SessionContext,
+    policy: PathPolicy,
+    shells: Arc<Mutex<BackgroundShells>>,
     tool_router: ToolRouter<Self>,
 }
 
 impl WorkspaceTools {
-    pub fn new(session_ctx: SessionContext) -> Result<Self> {
+    pub fn new(session_ctx: SessionContext, policy: PathPolicy) -> Result<Self> {
         Ok(Self {
             session_ctx,
+            policy,
+            shells: Arc::new(Mutex::new(BackgroundShells::default())),
             tool_router: Self::tool_router(),
         })
     }
 
+    /// same tools and policy bound to another session, without its background shells
+    pub fn with_session(&self, session_ctx: SessionContext) -> Self {
+        Self {
+            session_ctx,
+            shells: Arc::new(Mutex::new(BackgroundShells::default())),
+            ..self.clone()
+        }
+    }
+
+    /// this tool's policy with the session and background shells of `current`, used
+    /// when the tools are rebuilt after a config change
+    pub fn with_state_of(&self, current: &WorkspaceTools) -> Self {
+        Self {
+            session_ctx: current.session_ctx.clone(),
+            shells: Arc::clone(&current.shells),
+            ..self.clone()
+        }
+    }
+
     async fn get_work_dir(&self) -> Result<PathBuf, ErrorData> {
         let work_dir = self.session_ctx.work_dir.read().await;
         match work_dir.as_ref() {
@@ -121,6 +421,33 @@ struct BashArgs {
     timeout: Option<u64>,
 }
 
+// bash_background tool
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct BashBackgroundArgs {
+    /// Command to start, e.g. 'npm run dev'. Always quote paths with spaces.
+    command: String,
+    /// 5-10 word description of what command does
+    #[serde(default)]
+    description: Option<String>,
+}
+
+// bash_output tool
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct BashOutputArgs {
+    /// Id returned by bash_background
+    shell_id: String,
+    /// Only return output lines matching this regex
+    #[serde(default)]
+    filter: Option<String>,
+}
+
+// kill_shell tool
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct KillShellArgs {
+    /// Id returned by bash_background
+    shell_id: String,
+}
+
 // grep tool
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
 struct GrepArgs {
@@ -132,6 +459,12 @@ struct GrepArgs {
     /// Case insensitive search (default: false)
     #[serde(default)]
     case_insensitive: bool,
+    /// Only search files matching this glob (e.g., '*.tsx')
+    #[serde(default)]
+    glob: Option<String>,
+    /// Lines of context to show before and after each match
+    #[serde(default)]
+    context: Option<usize>,
     /// Limit output to first N matches
     #[serde(default)]
     head_limit: Option<usize>,
@@ -144,6 +477,85 @@ struct GlobArgs {
     pattern: String,
 }
 
+// search_replace tool
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct SearchReplaceArgs {
+    /// Text to search for (a regex when regex=true)
+    pattern: String,
+    /// Replacement text; with regex=true, $1 / ${name} refer to capture groups
+    replacement: String,
+    /// Glob of files to change, relative to base directory (default: '**/*')
+    #[serde(default)]
+    glob: Option<String>,
+    /// Treat pattern as a regex instead of literal text (default: false)
+    #[serde(default)]
+    regex: bool,
+    /// Only show what would change without writing files (default: false)
+    #[serde(default)]
+    dry_run: bool,
+}
+
+/// Glob patterns are joined to the work directory, so they must stay relative to it
+fn check_glob(pattern: &str) -> Result<(), ErrorData> {
+    if Path::new(pattern).is_absolute() || pattern.split('/').any(|c| c == "..") {
+        return Err(ErrorData::invalid_params(
+            "glob must be relative to the base directory and must not contain '..'".to_string(),
+            None,
+        ));
+    }
+    Ok(())
+}
+
+/// Directories never touched by search_replace
+const SEARCH_REPLACE_SKIP_DIRS: &[&str] = &["node_modules", ".git", "dist", "build"];
+
+/// Lines of preview shown per file in dry-run mode
+const PREVIEW_LINES_PER_FILE: usize = 10;
+
+/// Apply `regex` to `content`. Returns the new content and the number of replacements.
+fn replace_in(
+    content: &str,
+    regex: &regex::Regex,
+    replacement: &str,
+    expand: bool,
+) -> (String, usize) {
+    let count = regex.find_iter(content).count();
+    if count == 0 {
+        return (content.to_string(), 0);
+    }
+    let replaced = match expand {
+        true => regex.replace_all(content, replacement),
+        false => regex.replace_all(content, regex::NoExpand(replacement)),
+    };
+    (replaced.into_owned(), count)
+}
+
+/// `- old` / `+ new` pairs for the changed lines, for dry-run previews.
+fn preview_changes(old: &str, new: &str) -> Vec<String> {
+    // replacements spanning lines shift everything after them, only a count is useful then
+    if old.lines().count() != new.lines().count() {
+        return vec!["  (line structure changes, preview unavailable)".to_string()];
+    }
+    old.lines()
+        .zip(new.lines())
+        .enumerate()
+        .filter(|(_, (before, after))| before != after)
+        .flat_map(|(idx, (before, after))| {
+            [
+                format!("  {}: - {}", idx + 1, before),
+                format!("  {}: + {}", idx + 1, after),
+            ]
+        })
+        .collect()
+}
+
+// switch_project tool
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct SwitchProjectArgs {
+    /// Absolute path to the project directory
+    work_dir: String,
+}
+
 #[tool_router]
 impl WorkspaceTools {
     #[tool(
@@ -155,7 +567,9 @@ impl WorkspaceTools {
         Parameters(args): Parameters<ReadFileArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_dir = self.get_work_dir().await?;
-        let path = validate_path(&base_dir, &args.file_path)
+        let path = self
+            .policy
+            .validate_path(&base_dir, &args.file_path)
             .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
 
         let content = tokio::fs::read_to_string(&path)
@@ -193,7 +607,9 @@ impl WorkspaceTools {
         Parameters(args): Parameters<WriteFileArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_dir = self.get_work_dir().await?;
-        let path = validate_path(&base_dir, &args.file_path)
+        let path = self
+            .policy
+            .validate_path(&base_dir, &args.file_path)
             .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
 
         if let Some(parent) = path.parent() {
@@ -231,7 +647,9 @@ impl WorkspaceTools {
         }
 
         let base_dir = self.get_work_dir().await?;
-        let path = validate_path(&base_dir, &args.file_path)
+        let path = self
+            .policy
+            .validate_path(&base_dir, &args.file_path)
             .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
 
         let content = tokio::fs::read_to_string(&path)
@@ -278,6 +696,9 @@ impl WorkspaceTools {
         Parameters(args): Parameters<BashArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_dir = self.get_work_dir().await?;
+        self.policy
+            .check_command(&base_dir, &args.command)
+            .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
         let timeout_ms = args.timeout.unwrap_or(120000);
         let timeout_duration = tokio::time::Duration::from_millis(timeout_ms);
 
@@ -285,6 +706,7 @@ impl WorkspaceTools {
             .arg("-c")
             .arg(&args.command)
             .current_dir(&base_dir)
+            .envs(edda_integrations::env::overlay())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
@@ -321,104 +743,374 @@ impl WorkspaceTools {
     }
 
     #[tool(
-        name = "grep",
-        description = "Search file contents with regex. Returns file:line:content by default. Limit results with head_limit."
+        name = "bash_background",
+        description = "Start a long-running bash command (dev server, watcher) in the workspace directory without waiting for it. Returns a shell id for bash_output and kill_shell."
     )]
-    pub async fn grep(
+    pub async fn bash_background(
         &self,
-        Parameters(args): Parameters<GrepArgs>,
+        Parameters(args): Parameters<BashBackgroundArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_dir = self.get_work_dir().await?;
-        let search_path = args.path.as_deref().unwrap_or(".");
-        let path = validate_path(&base_dir, search_path)
+        self.policy
+            .check_command(&base_dir, &args.command)
             .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
 
-        let regex = if args.case_insensitive {
-            regex::RegexBuilder::new(&args.pattern)
-                .case_insensitive(true)
-                .build()
-        } else {
-            regex::Regex::new(&args.pattern)
-        }
-        .map_err(|e| ErrorData::invalid_params(format!("Invalid regex pattern: {}", e), None))?;
-
-        let mut matches = Vec::new();
-        let mut files_to_search = Vec::new();
-
-        if path.is_file() {
-            files_to_search.push(path);
-        } else if path.is_dir() {
-            for entry in walkdir::WalkDir::new(&path)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().is_file())
-            {
-                files_to_search.push(entry.path().to_path_buf());
-            }
+        let mut shells = self.shells.lock().await;
+        // forget exited shells whose output was already read
+        shells
+            .shells
+            .retain(|_, shell| shell.is_running() || !shell.is_drained());
+        let running = shells
+            .shells
+            .values_mut()
+            .map(|shell| shell.is_running())
+            .filter(|running| *running)
+            .count();
+        if running >= MAX_BACKGROUND_SHELLS {
+            return Err(ErrorData::invalid_request(
+                format!(
+                    "{} background shells are already running, stop one with kill_shell first",
+                    MAX_BACKGROUND_SHELLS
+                ),
+                None,
+            ));
         }
 
-        for file in files_to_search {
-            if let Ok(content) = tokio::fs::read_to_string(&file).await {
-                for (line_num, line) in content.lines().enumerate() {
-                    if regex.is_match(line) {
-                        let rel_path = file
-                            .strip_prefix(&base_dir)
-                            .unwrap_or(&file)
-                            .display();
-                        matches.push(format!("{}:{}: {}", rel_path, line_num + 1, line));
-
-                        if let Some(limit) = args.head_limit {
-                            if matches.len() >= limit {
-                                return Ok(CallToolResult::success(vec![Content::text(
-                                    matches.join("\n"),
-                                )]));
-                            }
-                        }
-                    }
-                }
-            }
-            // silently skip files that can't be read (e.g., binary files)
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&args.command)
+            .current_dir(&base_dir)
+            .envs(edda_integrations::env::overlay())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| {
+                ErrorData::internal_error(format!("Failed to spawn command: {}", e), None)
+            })?;
+        let output = Arc::new(std::sync::Mutex::new(OutputBuffer::default()));
+        if let Some(stdout) = child.stdout.take() {
+            collect_output(stdout, output.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            collect_output(stderr, output.clone());
         }
 
-        let result = if matches.is_empty() {
-            "No matches found".to_string()
-        } else {
-            matches.join("\n")
+        shells.next_id += 1;
+        let shell_id = format!("shell_{}", shells.next_id);
+        let pid = child.id().unwrap_or_default();
+        shells.shells.insert(
+            shell_id.clone(),
+            BackgroundShell {
+                command: args.command,
+                child,
+                output,
+            },
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Started {} (pid {}). Poll its output with bash_output, stop it with kill_shell.",
+            shell_id, pid
+        ))]))
+    }
+
+    #[tool(
+        name = "bash_output",
+        description = "Get output of a bash_background command produced since the last call, and whether it is still running."
+    )]
+    pub async fn bash_output(
+        &self,
+        Parameters(args): Parameters<BashOutputArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let filter = args
+            .filter
+            .as_deref()
+            .map(regex::Regex::new)
+            .transpose()
+            .map_err(|e| ErrorData::invalid_params(format!("Invalid filter regex: {}", e), None))?;
+
+        let mut shells = self.shells.lock().await;
+        let shell = shells.shells.get_mut(&args.shell_id).ok_or_else(|| {
+            ErrorData::invalid_params(format!("Unknown shell id: {}", args.shell_id), None)
+        })?;
+        let status = match shell.child.try_wait() {
+            Ok(None) => "running".to_string(),
+            Ok(Some(status)) => format!("exited (exit code: {})", status.code().unwrap_or(-1)),
+            Err(e) => format!("unknown ({})", e),
+        };
+
+        let mut buffer = shell
+            .output
+            .lock()
+            .map_err(|_| ErrorData::internal_error("Output buffer poisoned".to_string(), None))?;
+        let dropped = std::mem::take(&mut buffer.dropped);
+        let unread = buffer.take_unread();
+        let output = match &filter {
+            Some(filter) => unread
+                .lines()
+                .filter(|line| filter.is_match(line))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => unread.trim_end().to_string(),
         };
+        let more = buffer.read < buffer.text.len();
+        drop(buffer);
 
+        let mut result = format!("{}: {}\nStatus: {}", args.shell_id, shell.command, status);
+        if dropped > 0 {
+            result.push_str(&format!("\n[{} bytes of older output dropped]", dropped));
+        }
+        match output.is_empty() {
+            true => result.push_str("\n(no new output)"),
+            false => result.push_str(&format!("\n\n{}", output)),
+        }
+        if more {
+            result.push_str("\n[More output available, call bash_output again]");
+        }
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
 
-    #[tool(name = "glob", description = "Find files matching a glob pattern")]
+    #[tool(
+        name = "kill_shell",
+        description = "Stop a bash_background command and the processes it started."
+    )]
+    pub async fn kill_shell(
+        &self,
+        Parameters(args): Parameters<KillShellArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut shell = self
+            .shells
+            .lock()
+            .await
+            .shells
+            .remove(&args.shell_id)
+            .ok_or_else(|| {
+                ErrorData::invalid_params(format!("Unknown shell id: {}", args.shell_id), None)
+            })?;
+        let result = match shell.child.try_wait() {
+            Ok(Some(status)) => format!(
+                "{} had already exited (exit code: {})",
+                args.shell_id,
+                status.code().unwrap_or(-1)
+            ),
+            _ => {
+                shell.kill_group();
+                let _ = shell.child.wait().await;
+                format!("Killed {}: {}", args.shell_id, shell.command)
+            }
+        };
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "grep",
+        description = "Search file contents with regex, skipping .gitignore'd files. Returns file:line:column: content, with context lines when requested. Limit results with head_limit."
+    )]
+    pub async fn grep(
+        &self,
+        Parameters(args): Parameters<GrepArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let base_dir = self.get_work_dir().await?;
+        let search_path = args.path.as_deref().unwrap_or(".");
+        let path = self
+            .policy
+            .validate_path(&base_dir, search_path)
+            .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+        if let Some(glob) = &args.glob {
+            check_glob(glob)?;
+        }
+
+        let options = search::GrepOptions {
+            pattern: args.pattern,
+            case_insensitive: args.case_insensitive,
+            glob: args.glob,
+            context: args.context.unwrap_or(0),
+            limit: args.head_limit,
+        };
+        let result = tokio::task::spawn_blocking(move || search::grep(&base_dir, &path, &options))
+            .await
+            .map_err(|e| ErrorData::internal_error(format!("Search failed: {}", e), None))?
+            .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+
+        Ok(structured_result(&result, true))
+    }
+
+    #[tool(
+        name = "glob",
+        description = "Find files matching a glob pattern, skipping .gitignore'd files"
+    )]
     pub async fn glob(
         &self,
         Parameters(args): Parameters<GlobArgs>,
     ) -> Result<CallToolResult, ErrorData> {
         let base_dir = self.get_work_dir().await?;
-        let pattern_path = base_dir.join(&args.pattern);
-        let pattern_str = pattern_path
-            .to_str()
-            .ok_or_else(|| ErrorData::invalid_params("Invalid pattern path".to_string(), None))?;
-
-        let mut matches: Vec<String> = glob::glob(pattern_str)
-            .map_err(|e| ErrorData::invalid_params(format!("Invalid glob pattern: {}", e), None))?
-            .filter_map(|entry| entry.ok())
-            .filter_map(|path| {
-                path.strip_prefix(&base_dir)
-                    .ok()
-                    .map(|p| p.display().to_string())
-            })
-            .collect();
+        check_glob(&args.pattern)?;
+
+        let matches = tokio::task::spawn_blocking(move || search::glob(&base_dir, &args.pattern))
+            .await
+            .map_err(|e| ErrorData::internal_error(format!("Search failed: {}", e), None))?
+            .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
 
         let result = if matches.is_empty() {
             "No files matched pattern".to_string()
         } else {
-            matches.sort();
             matches.join("\n")
         };
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    #[tool(
+        name = "search_replace",
+        description = "Replace text across all files matching a glob (literal by default, regex with regex=true). Use dry_run=true to preview changes first. Skips node_modules, .git, dist, build and .gitignore'd files."
+    )]
+    pub async fn search_replace(
+        &self,
+        Parameters(args): Parameters<SearchReplaceArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let base_dir = self.get_work_dir().await?;
+        let glob_pattern = args.glob.as_deref().unwrap_or("**/*");
+        check_glob(glob_pattern)?;
+        let pattern = match args.regex {
+            true => args.pattern.clone(),
+            false => regex::escape(&args.pattern),
+        };
+        let regex = regex::Regex::new(&pattern).map_err(|e| {
+            ErrorData::invalid_params(format!("Invalid regex pattern: {}", e), None)
+        })?;
+
+        let walk_dir = base_dir.clone();
+        let glob = glob_pattern.to_string();
+        let files = tokio::task::spawn_blocking(move || {
+            search::glob_files(&walk_dir, &glob, SEARCH_REPLACE_SKIP_DIRS)
+        })
+        .await
+        .map_err(|e| ErrorData::internal_error(format!("Search failed: {}", e), None))?
+        .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+
+        // every path is checked before anything is written, so a bad one leaves no
+        // half-applied replacement behind
+        let mut changes = Vec::new();
+        for relative in files {
+            // paths come from the walk; check none escapes through a symlink
+            let path = self
+                .policy
+                .validate_path(&base_dir, &relative)
+                .map_err(|e| ErrorData::invalid_params(e.to_string(), None))?;
+            // skip binary and unreadable files
+            let Ok(content) = tokio::fs::read_to_string(&path).await else {
+                continue;
+            };
+            let (new_content, count) = replace_in(&content, &regex, &args.replacement, args.regex);
+            if count > 0 {
+                changes.push((relative, path, content, new_content, count));
+            }
+        }
+
+        let mut report = Vec::new();
+        let mut total = 0;
+        let changed_files = changes.len();
+        for (relative, path, content, new_content, count) in changes {
+            total += count;
+            report.push(format!("{}: {} replacement(s)", relative, count));
+            if args.dry_run {
+                let preview = preview_changes(&content, &new_content);
+                let hidden = preview.len().saturating_sub(PREVIEW_LINES_PER_FILE * 2);
+                report.extend(preview.into_iter().take(PREVIEW_LINES_PER_FILE * 2));
+                if hidden > 0 {
+                    report.push(format!("  ... {} more lines", hidden / 2));
+                }
+            } else {
+                tokio::fs::write(&path, new_content).await.map_err(|e| {
+                    ErrorData::internal_error(format!("Failed to write {}: {}", relative, e), None)
+                })?;
+            }
+        }
+
+        if total == 0 {
+            return Ok(CallToolResult::success(vec![Content::text(
+                "No matches found".to_string(),
+            )]));
+        }
+        let summary = match args.dry_run {
+            true => format!(
+                "Dry run: {} replacement(s) in {} file(s), nothing written",
+                total, changed_files
+            ),
+            false => format!(
+                "Replaced {} occurrence(s) in {} file(s)",
+                total, changed_files
+            ),
+        };
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "{}\n{}",
+            summary,
+            report.join("\n")
+        ))]))
+    }
+
+    #[tool(
+        name = "list_projects",
+        description = "List the projects in this session. File tools operate on the active project."
+    )]
+    pub async fn list_projects(&self) -> Result<CallToolResult, ErrorData> {
+        let active = self.session_ctx.work_dir.read().await.clone();
+        let projects = self.session_ctx.projects.read().await;
+        let result = if projects.is_empty() {
+            "No projects yet. Run scaffold_data_app to create one.".to_string()
+        } else {
+            projects
+                .iter()
+                .map(|project| {
+                    let marker = if active.as_ref() == Some(project) { "*" } else { " " };
+                    format!("{} {}", marker, project.display())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "switch_project",
+        description = "Make another project of this session the active one for file tools. Existing projects that were not scaffolded in this session can be opened when they are under workspace_allowed_paths."
+    )]
+    pub async fn switch_project(
+        &self,
+        Parameters(args): Parameters<SwitchProjectArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let path = PathBuf::from(&args.work_dir);
+        if !path.is_absolute() || !path.is_dir() {
+            return Err(ErrorData::invalid_params(
+                format!("Not an existing absolute directory: {}", args.work_dir),
+                None,
+            ));
+        }
+        // the project becomes the base of every path check, so it can't be an arbitrary
+        // directory like / or $HOME
+        let path = resolve(&path);
+        let known = self.session_ctx.projects.read().await.contains(&path);
+        if !known && !self.policy.is_allowlisted(&path) {
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "Access denied: {} is not a project of this session or under workspace_allowed_paths",
+                    args.work_dir
+                ),
+                None,
+            ));
+        }
+        self.session_ctx.register_project(path).await;
+        if let Err(e) = self.session_ctx.persist().await {
+            tracing::warn!("Failed to persist session state: {}", e);
+        }
+        let active = self.get_work_dir().await?;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Active project: {}",
+            active.display()
+        ))]))
+    }
 }
 
 // Internal ServerHandler impl for routing by CombinedProvider
@@ -428,3 +1120,171 @@ impl ServerHandler for WorkspaceTools {
         crate::mcp_helpers::internal_server_info()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("app");
+        std::fs::create_dir(&base).unwrap();
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        let policy = PathPolicy::new(&[shared.display().to_string()]);
+
+        assert!(policy.validate_path(&base, "server/src/new.ts").is_ok());
+        assert!(policy.validate_path(&base, "../shared/a.txt").is_err());
+        assert!(policy.validate_path(&base, "/etc/passwd").is_err());
+        let allowed = shared.join("a.txt").display().to_string();
+        assert!(policy.validate_path(&base, &allowed).is_ok());
+
+        assert!(
+            policy
+                .check_command(&base, "npm run build && cat server/src/index.ts")
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check_command(&base, "/usr/bin/env node -v 2>/dev/null")
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check_command(&base, "grep -r '/api/trpc' client/src")
+                .is_ok()
+        );
+        assert!(policy.check_command(&base, "ls ./client/../server").is_ok());
+        assert!(policy.check_command(&base, "cat ~/.ssh/id_rsa").is_err());
+        assert!(
+            policy
+                .check_command(&base, "echo x > \"$HOME/.bashrc\"")
+                .is_err()
+        );
+        assert!(policy.check_command(&base, "cat /etc/passwd").is_err());
+        assert!(policy.check_command(&base, "cd ..; rm -rf app").is_err());
+        assert!(policy.check_command(&base, "cp --target=../.. a").is_err());
+        assert!(policy.check_command(&base, "cat /tmp/../etc/passwd").is_err());
+        assert!(policy.check_command(&base, "cat /../etc/passwd").is_err());
+        assert!(policy.check_command(&base, "ls /tmp/cache").is_ok());
+        assert!(policy.check_command(&base, "cd /etc && cat passwd").is_err());
+        assert!(policy.check_command(&base, "cd && cat .bashrc").is_err());
+        assert!(policy.check_command(&base, "cd /tmp; cat ../etc/passwd").is_err());
+        assert!(
+            policy
+                .check_command(&base, "cd client/src && cat ../../server/index.ts")
+                .is_ok()
+        );
+        let shared_script = format!("{}/build.sh", shared.display());
+        assert!(policy.check_command(&base, &shared_script).is_ok());
+        assert!(policy.check_command(&base, "/root/.local/bin/tool").is_err());
+        assert!(policy.check_command(&base, "/usr/../root/tool").is_err());
+        assert!(policy.check_command(&base, "cat /e*/passwd").is_err());
+        assert!(policy.check_command(&base, "cat /e?c/shadow").is_err());
+        assert!(policy.check_command(&base, "cat /[e]tc/shadow").is_err());
+        assert!(policy.check_command(&base, "ls /tmp/*.log").is_ok());
+        let shared_glob = format!("cat {}/*.txt", shared.display());
+        assert!(policy.check_command(&base, &shared_glob).is_ok());
+    }
+
+    #[test]
+    fn test_output_buffer() {
+        let mut buffer = OutputBuffer::default();
+        buffer.push_line("> vite");
+        buffer.push_line("ready in 300ms");
+        assert_eq!(buffer.take_unread(), "> vite\nready in 300ms\n");
+        assert_eq!(buffer.take_unread(), "");
+
+        let line = "x".repeat(1000);
+        for _ in 0..(MAX_BACKGROUND_OUTPUT / 1000 + 1) {
+            buffer.push_line(&line);
+        }
+        assert!(buffer.text.len() <= MAX_BACKGROUND_OUTPUT);
+        assert!(buffer.dropped > 0);
+        assert_eq!(buffer.take_unread().len(), MAX_OUTPUT_CHARS);
+    }
+
+    #[test]
+    fn test_replace_in() {
+        let content = "const appName = 'sales';\nexport default appName;\n";
+
+        let literal = regex::Regex::new(&regex::escape("appName")).unwrap();
+        let (replaced, count) = replace_in(content, &literal, "title$1", false);
+        assert_eq!(count, 2);
+        assert_eq!(
+            replaced,
+            "const title$1 = 'sales';\nexport default title$1;\n"
+        );
+
+        let pattern = regex::Regex::new(r"'(\w+)'").unwrap();
+        let (replaced, count) = replace_in(content, &pattern, "\"${1}_v2\"", true);
+        assert_eq!(count, 1);
+        assert_eq!(
+            preview_changes(content, &replaced),
+            vec![
+                "  1: - const appName = 'sales';",
+                "  1: + const appName = \"sales_v2\";"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_replace() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        for path in ["client/src/App.tsx", "node_modules/pkg/index.ts"] {
+            std::fs::create_dir_all(base.join(path).parent().unwrap()).unwrap();
+            std::fs::write(base.join(path), "const appName = 'sales';\n").unwrap();
+        }
+        let session_ctx = SessionContext::new(None);
+        *session_ctx.work_dir.write().await = Some(base.to_path_buf());
+        let tools = WorkspaceTools::new(session_ctx, PathPolicy::new(&[])).unwrap();
+        let args = |dry_run| SearchReplaceArgs {
+            pattern: "appName".to_string(),
+            replacement: "title".to_string(),
+            glob: None,
+            regex: false,
+            dry_run,
+        };
+        let read = |path: &str| std::fs::read_to_string(base.join(path)).unwrap();
+
+        tools.search_replace(Parameters(args(true))).await.unwrap();
+        assert_eq!(read("client/src/App.tsx"), "const appName = 'sales';\n");
+
+        tools.search_replace(Parameters(args(false))).await.unwrap();
+        assert_eq!(read("client/src/App.tsx"), "const title = 'sales';\n");
+        assert_eq!(read("node_modules/pkg/index.ts"), "const appName = 'sales';\n");
+    }
+
+    #[tokio::test]
+    async fn test_switch_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let scaffolded = dir.path().join("app");
+        let shared = dir.path().join("shared");
+        let existing = shared.join("existing");
+        std::fs::create_dir(&scaffolded).unwrap();
+        std::fs::create_dir_all(&existing).unwrap();
+        let session_ctx = SessionContext::new(None);
+        session_ctx.register_project(scaffolded.clone()).await;
+        let tools = WorkspaceTools::new(
+            session_ctx.clone(),
+            PathPolicy::new(&[shared.display().to_string()]),
+        )
+        .unwrap();
+        let switch = |work_dir: &Path| {
+            tools.switch_project(Parameters(SwitchProjectArgs {
+                work_dir: work_dir.display().to_string(),
+            }))
+        };
+
+        assert!(switch(Path::new("/")).await.is_err());
+        assert!(switch(dir.path()).await.is_err());
+        assert!(switch(&existing).await.is_ok());
+        assert!(switch(&scaffolded).await.is_ok());
+        assert_eq!(
+            *session_ctx.work_dir.read().await,
+            Some(scaffolded.canonicalize().unwrap())
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Hot reload of the providers when ~/.edda/config.json changes, so toggling deployment or
+//! screenshots doesn't need a restart of the whole IDE session.
+
+use crate::config::Config;
+use crate::providers::CombinedProvider;
+use crate::session::SessionContext;
+use eyre::Result;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, GetPromptRequestParam, GetPromptResult,
+    ListPromptsResult, ListResourcesResult, ListToolsResult, PaginatedRequestParam,
+    ReadResourceRequestParam, ReadResourceResult, ServerInfo,
+};
+use rmcp::service::{NotificationContext, RequestContext, RoleServer};
+use rmcp::{ErrorData, ServerHandler};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+
+/// How often the config file's modification time is checked
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Providers rebuilt after a config change, None until the first one
+pub type ProviderUpdates = watch::Receiver<Option<CombinedProvider>>;
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Rebuild the providers whenever the file at `path` changes and publish them to every
+/// client session. `load` is re-run on each change so CLI overrides keep applying. An
+/// invalid config or a missing required provider is logged and the previous providers
+/// stay in use. Changes to the .env file credentials are saved to trigger a rebuild too,
+/// so a variable added with `set_env_var` enables its provider.
+pub fn watch_config<F>(path: PathBuf, load: F) -> ProviderUpdates
+where
+    F: Fn() -> Result<Config> + Send + 'static,
+{
+    let (updates, receiver) = watch::channel(None);
+    let mut last_modified = modified(&path);
+    let env_path = crate::env::env_file().ok();
+    let mut last_env_modified = env_path.as_deref().and_then(modified);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let current = modified(&path);
+            let env_current = env_path.as_deref().and_then(modified);
+            if current == last_modified && env_current == last_env_modified {
+                continue;
+            }
+            last_modified = current;
+            if env_current != last_env_modified {
+                last_env_modified = env_current;
+                // variables added or edited by hand; set_env_var already applied its own
+                if let Err(e) = crate::env::reload_env_files() {
+                    tracing::warn!("Failed to reload environment: {}", e);
+                }
+            }
+
+            let config = match load() {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::warn!("Ignoring config change, failed to load it: {}", e);
+                    continue;
+                }
+            };
+            // built without a session, `rebind` attaches each client's own
+            let rebuilt = CombinedProvider::from_config(SessionContext::new(None), &config)
+                .await
+                .and_then(|provider| {
+                    provider.check_availability(&config.required_providers)?;
+                    Ok(provider)
+                });
+            let provider = match rebuilt {
+                Ok(provider) => provider,
+                Err(e) => {
+                    tracing::warn!("Ignoring config change, providers not reloaded: {}", e);
+                    continue;
+                }
+            };
+            tracing::info!(
+                "Config changed, reloaded providers: {}",
+                provider.provider_names().join(", ")
+            );
+            if updates.send(Some(provider)).is_err() {
+                // every session is gone
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+/// Updates that never arrive, for configs not read from the config file (`--json`)
+pub fn disabled() -> ProviderUpdates {
+    watch::channel(None).1
+}
+
+/// Serves the latest providers built from the config. Each client session keeps its own
+/// copy bound to its session context and swaps it when the config changes, then tells
+/// the client to refetch the tool list.
+#[derive(Clone)]
+pub struct ReloadableProvider {
+    current: Arc<RwLock<CombinedProvider>>,
+    updates: ProviderUpdates,
+}
+
+impl ReloadableProvider {
+    /// Serve `provider` until a change arrives on `updates`. A session created after a
+    /// reload catches up right after it's initialized.
+    pub fn new(provider: CombinedProvider, updates: ProviderUpdates) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(provider)),
+            updates,
+        }
+    }
+
+    fn provider(&self) -> CombinedProvider {
+        self.current.read().unwrap().clone()
+    }
+}
+
+impl ServerHandler for ReloadableProvider {
+    fn get_info(&self) -> ServerInfo {
+        self.provider().get_info()
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        let mut updates = self.updates.clone();
+        let current = Arc::clone(&self.current);
+        let peer = context.peer;
+        tokio::spawn(async move {
+            while updates.changed().await.is_ok() {
+                let Some(rebuilt) = updates.borrow_and_update().clone() else {
+                    continue;
+                };
+                {
+                    let mut current = current.write().unwrap();
+                    *current = current.rebind(&rebuilt);
+                }
+                if let Err(e) = peer.notify_tool_list_changed().await {
+                    // the client disconnected, its session copy is dropped with this task
+                    tracing::debug!("Failed to notify tool list change: {}", e);
+                    return;
+                }
+            }
+        });
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, ErrorData> {
+        self.provider().list_tools(request, context).await
+    }
+
+    async fn call_tool(
+        &self,
+        params: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        self.provider().call_tool(params, context).await
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListResourcesResult, ErrorData> {
+        self.provider().list_resources(request, context).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ReadResourceResult, ErrorData> {
+        self.provider().read_resource(request, context).await
+    }
+
+    async fn list_prompts(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListPromptsResult, ErrorData> {
+        self.provider().list_prompts(request, context).await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<GetPromptResult, ErrorData> {
+        self.provider().get_prompt(request, context).await
+    }
+}
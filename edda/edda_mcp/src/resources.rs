@@ -0,0 +1,248 @@
+//! MCP resources: the engine guide, the scaffolded project files, the latest validation
+//! screenshot and the session log, so clients can browse them without going through tool
+//! calls.
+
+use crate::engine_guide::ENGINE_GUIDE;
+use crate::paths;
+use crate::session::SessionContext;
+use base64::Engine;
+use edda_integrations::github::is_ignored;
+use rmcp::ErrorData;
+use rmcp::model::{AnnotateAble, RawResource, ReadResourceResult, Resource, ResourceContents};
+use std::path::{Path, PathBuf};
+
+pub const ENGINE_GUIDE_URI: &str = "edda://guide/engine";
+pub const SESSION_LOG_URI: &str = "edda://session/log";
+const SCREENSHOT_FILE: &str = "screenshot.png";
+/// Cap on listed files so a runaway project doesn't produce a giant response
+const MAX_LISTED_FILES: usize = 1000;
+/// Files above this size can't be read as resources
+const MAX_RESOURCE_SIZE: u64 = 2 * 1024 * 1024;
+
+fn file_uri(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+    {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        "json" => "application/json",
+        "md" => "text/markdown",
+        "ts" | "tsx" => "text/typescript",
+        "js" | "jsx" | "mjs" => "text/javascript",
+        "html" => "text/html",
+        "css" => "text/css",
+        "yaml" | "yml" => "application/yaml",
+        "py" => "text/x-python",
+        _ => "text/plain",
+    }
+}
+
+fn resource(uri: String, name: String, mime_type: &str, size: Option<u64>) -> Resource {
+    let mut raw = RawResource::new(uri, name);
+    raw.mime_type = Some(mime_type.to_string());
+    raw.size = size.and_then(|s| u32::try_from(s).ok());
+    raw.no_annotation()
+}
+
+fn session_log_path(session_ctx: &SessionContext) -> Option<PathBuf> {
+    let path = paths::session_log_path(session_ctx.session_id.as_deref()?);
+    path.exists().then_some(path)
+}
+
+/// List the resources available in this session; only the engine guide and the session
+/// log until a project is scaffolded.
+pub async fn list_resources(session_ctx: &SessionContext) -> Vec<Resource> {
+    let mut resources = vec![resource(
+        ENGINE_GUIDE_URI.to_string(),
+        "Engine guide".to_string(),
+        "text/markdown",
+        Some(ENGINE_GUIDE.len() as u64),
+    )];
+
+    if let Some(log_path) = session_log_path(session_ctx) {
+        let size = std::fs::metadata(&log_path).ok().map(|m| m.len());
+        resources.push(resource(
+            SESSION_LOG_URI.to_string(),
+            "Session log".to_string(),
+            "text/plain",
+            size,
+        ));
+    }
+
+    let Some(work_dir) = session_ctx.work_dir.read().await.clone() else {
+        return resources;
+    };
+
+    let screenshot = work_dir.join(SCREENSHOT_FILE);
+    if screenshot.is_file() {
+        let size = std::fs::metadata(&screenshot).ok().map(|m| m.len());
+        resources.push(resource(
+            file_uri(&screenshot),
+            "Latest screenshot".to_string(),
+            "image/png",
+            size,
+        ));
+    }
+
+    let files = walkdir::WalkDir::new(&work_dir)
+        .sort_by_file_name()
+        .into_iter()
+        // generated or huge directories, and secrets like .env files
+        .filter_entry(|e| e.depth() == 0 || !is_ignored(&e.file_name().to_string_lossy()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path() != screenshot)
+        .take(MAX_LISTED_FILES);
+    for entry in files {
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(&work_dir) else {
+            continue;
+        };
+        let size = entry.metadata().ok().map(|m| m.len());
+        resources.push(resource(
+            file_uri(path),
+            relative.display().to_string(),
+            mime_type(path),
+            size,
+        ));
+    }
+
+    resources
+}
+
+/// Resolve a resource URI to a local path, refusing anything outside the workspace.
+async fn resolve(session_ctx: &SessionContext, uri: &str) -> Result<PathBuf, ErrorData> {
+    if uri == SESSION_LOG_URI {
+        return session_log_path(session_ctx)
+            .ok_or_else(|| ErrorData::resource_not_found("No session log for this session", None));
+    }
+
+    let not_found = || ErrorData::resource_not_found(format!("Resource not found: {}", uri), None);
+    let path = uri.strip_prefix("file://").ok_or_else(not_found)?;
+    let work_dir = session_ctx
+        .work_dir
+        .read()
+        .await
+        .clone()
+        .ok_or_else(not_found)?;
+
+    let work_dir = work_dir.canonicalize().map_err(|_| not_found())?;
+    let path = Path::new(path).canonicalize().map_err(|_| not_found())?;
+    let hidden = path.strip_prefix(&work_dir).map(|relative| {
+        relative
+            .components()
+            .any(|c| is_ignored(&c.as_os_str().to_string_lossy()))
+    });
+    match (hidden, path.is_file()) {
+        (Ok(false), true) => Ok(path),
+        _ => Err(not_found()),
+    }
+}
+
+pub async fn read_resource(
+    session_ctx: &SessionContext,
+    uri: &str,
+) -> Result<ReadResourceResult, ErrorData> {
+    if uri == ENGINE_GUIDE_URI {
+        return Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some("text/markdown".to_string()),
+                text: ENGINE_GUIDE.trim().to_string(),
+                meta: None,
+            }],
+        });
+    }
+
+    let path = resolve(session_ctx, uri).await?;
+
+    let size = std::fs::metadata(&path)
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?
+        .len();
+    if size > MAX_RESOURCE_SIZE {
+        return Err(ErrorData::invalid_params(
+            format!("Resource is too large to read ({} bytes)", size),
+            None,
+        ));
+    }
+
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+    let mime_type = match uri {
+        SESSION_LOG_URI => "text/plain",
+        _ => mime_type(&path),
+    };
+    let contents = match String::from_utf8(bytes) {
+        Ok(text) if !mime_type.starts_with("image/") || mime_type == "image/svg+xml" => {
+            ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+                meta: None,
+            }
+        }
+        result => {
+            let bytes = result
+                .map(String::into_bytes)
+                .unwrap_or_else(|e| e.into_bytes());
+            ResourceContents::BlobResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                blob: base64::engine::general_purpose::STANDARD.encode(bytes),
+                meta: None,
+            }
+        }
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![contents],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_resource_stays_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path().join("app");
+        std::fs::create_dir_all(work_dir.join("node_modules/pkg")).unwrap();
+        std::fs::write(work_dir.join("package.json"), "{}").unwrap();
+        std::fs::write(work_dir.join("node_modules/pkg/index.js"), "").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        std::fs::create_dir_all(work_dir.join(".edda")).unwrap();
+        std::fs::write(work_dir.join(".edda/network.json"), "[]").unwrap();
+        std::fs::write(work_dir.join(".env"), "DATABRICKS_TOKEN=dapi").unwrap();
+        std::fs::write(work_dir.join(".env.local"), "DATABRICKS_TOKEN=dapi").unwrap();
+
+        let session_ctx = SessionContext::new(None);
+        *session_ctx.work_dir.write().await = Some(work_dir.clone());
+
+        let resources = list_resources(&session_ctx).await;
+        let names: Vec<_> = resources.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Engine guide", "package.json"]);
+
+        let result = read_resource(&session_ctx, &resources[1].uri)
+            .await
+            .unwrap();
+        assert!(matches!(
+            &result.contents[0],
+            ResourceContents::TextResourceContents { text, .. } if text == "{}"
+        ));
+
+        let escape = file_uri(&work_dir.join("../secret.txt"));
+        assert!(read_resource(&session_ctx, &escape).await.is_err());
+        for hidden in [".env", ".env.local", ".edda/network.json"] {
+            let uri = file_uri(&work_dir.join(hidden));
+            assert!(read_resource(&session_ctx, &uri).await.is_err());
+        }
+    }
+}
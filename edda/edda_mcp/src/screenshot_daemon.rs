@@ -0,0 +1,77 @@
+//! Screenshot daemon of this server process, started on first use when
+//! `screenshot.daemon` is set. Every validation captures with the same warm browser
+//! instead of building a Playwright container of its own.
+
+use edda_sandbox::dagger::{ConnectOpts, Logger};
+use edda_screenshot::DaemonClient;
+use eyre::Result;
+use std::net::TcpListener;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// The first start builds the Playwright container
+const READY_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct Daemon {
+    client: DaemonClient,
+    task: JoinHandle<()>,
+}
+
+static DAEMON: Mutex<Option<Daemon>> = Mutex::const_new(None);
+
+/// The running daemon, started if there is none yet or the last one stopped
+pub async fn daemon() -> Result<DaemonClient> {
+    let mut daemon = DAEMON.lock().await;
+    if let Some(running) = daemon.as_ref().filter(|d| !d.task.is_finished()) {
+        return Ok(running.client.clone());
+    }
+
+    // both listeners are open at once so the ports differ
+    let (control, app) = (
+        TcpListener::bind("127.0.0.1:0")?,
+        TcpListener::bind("127.0.0.1:0")?,
+    );
+    let (control_port, app_port) = (control.local_addr()?.port(), app.local_addr()?.port());
+    drop((control, app));
+
+    tracing::info!("Starting screenshot daemon");
+    let mut task = tokio::spawn(async move {
+        let result = ConnectOpts::default()
+            .with_logger(Logger::Silent)
+            // the daemon runs as long as the server
+            .with_execute_timeout(None)
+            .connect(move |client| async move {
+                edda_screenshot::run_daemon(&client, control_port, app_port).await
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::warn!("Screenshot daemon stopped: {}", e);
+        }
+    });
+
+    let client = DaemonClient::new(control_port, app_port);
+    tokio::select! {
+        ready = client.wait_ready(READY_TIMEOUT) => {
+            if let Err(e) = ready {
+                task.abort();
+                return Err(e);
+            }
+        }
+        _ = &mut task => eyre::bail!("screenshot daemon failed to start"),
+    }
+    tracing::info!("Screenshot daemon ready on port {}", control_port);
+
+    *daemon = Some(Daemon {
+        client: client.clone(),
+        task,
+    });
+    Ok(client)
+}
+
+/// Start the daemon in the background so the first validation doesn't wait for it
+pub async fn warmup() {
+    if let Err(e) = daemon().await {
+        tracing::warn!("Screenshot daemon warmup failed: {}", e);
+    }
+}
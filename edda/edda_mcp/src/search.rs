@@ -0,0 +1,316 @@
+//! File search for the workspace `grep` and `glob` tools, built on the ripgrep crates so
+//! results honor .gitignore and look the same on every platform.
+
+use edda_integrations::ToolResultDisplay;
+use eyre::Result;
+use globset::GlobBuilder;
+use grep_matcher::Matcher;
+use grep_regex::{RegexMatcher, RegexMatcherBuilder};
+use grep_searcher::{
+    BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkMatch,
+};
+use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    pub pattern: String,
+    pub case_insensitive: bool,
+    /// Only search files matching this glob, e.g. '*.tsx'
+    pub glob: Option<String>,
+    /// Lines of context before and after each match
+    pub context: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GrepMatch {
+    /// Path relative to the work directory
+    pub path: String,
+    pub line: u64,
+    /// 1-based byte offset of the first match in the line
+    pub column: u64,
+    pub text: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<String>,
+}
+
+impl GrepMatch {
+    /// ripgrep style: ':' after the line number marks the match, '-' a context line
+    fn display(&self) -> String {
+        let first = self.line - self.before.len() as u64;
+        let context = |line: u64, text: &String| format!("{}-{}- {}", self.path, line, text);
+        let mut lines: Vec<String> = (first..)
+            .zip(&self.before)
+            .map(|(line, text)| context(line, text))
+            .collect();
+        lines.push(format!(
+            "{}:{}:{}: {}",
+            self.path, self.line, self.column, self.text
+        ));
+        lines.extend(
+            (self.line + 1..)
+                .zip(&self.after)
+                .map(|(line, text)| context(line, text)),
+        );
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct GrepResult {
+    pub matches: Vec<GrepMatch>,
+    /// the match limit was reached before the search finished
+    pub truncated: bool,
+}
+
+impl ToolResultDisplay for GrepResult {
+    fn display(&self) -> String {
+        if self.matches.is_empty() {
+            return "No matches found".to_string();
+        }
+        let with_context = self
+            .matches
+            .iter()
+            .any(|m| !m.before.is_empty() || !m.after.is_empty());
+        let blocks: Vec<String> = self.matches.iter().map(GrepMatch::display).collect();
+        let mut out = blocks.join(if with_context { "\n--\n" } else { "\n" });
+        if self.truncated {
+            out.push_str(&format!("\n[Showing first {} matches]", self.matches.len()));
+        }
+        out
+    }
+}
+
+/// Walker over `root` honoring .gitignore (even outside a git repo) but including other
+/// hidden files such as .env; .git and `skip_dirs` are never entered.
+fn walker(
+    root: &Path,
+    glob: Option<&str>,
+    skip_dirs: &'static [&'static str],
+) -> Result<WalkBuilder> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry(move |entry| {
+            let name = entry.file_name();
+            let skipped = entry.file_type().is_some_and(|t| t.is_dir())
+                && skip_dirs.iter().any(|dir| name == *dir);
+            name != ".git" && !skipped
+        });
+    if let Some(glob) = glob {
+        let overrides = OverrideBuilder::new(root)
+            .add(glob)
+            .and_then(|builder| builder.build())
+            .map_err(|e| eyre::eyre!("Invalid glob '{}': {}", glob, e))?;
+        builder.overrides(overrides);
+    }
+    Ok(builder)
+}
+
+fn relative(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn line_text(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches(['\n', '\r'])
+        .to_string()
+}
+
+/// Collects matches of one file, attaching context lines to the nearest match.
+struct MatchSink<'a> {
+    matcher: &'a RegexMatcher,
+    path: String,
+    matches: &'a mut Vec<GrepMatch>,
+    before: Vec<String>,
+    limit: usize,
+}
+
+impl Sink for MatchSink<'_> {
+    type Error = std::io::Error;
+
+    fn matched(&mut self, _: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, Self::Error> {
+        let column = self
+            .matcher
+            .find(mat.bytes())
+            .ok()
+            .flatten()
+            .map(|m| m.start() as u64 + 1)
+            .unwrap_or(1);
+        self.matches.push(GrepMatch {
+            path: self.path.clone(),
+            line: mat.line_number().unwrap_or_default(),
+            column,
+            text: line_text(mat.bytes()),
+            before: std::mem::take(&mut self.before),
+            after: Vec::new(),
+        });
+        Ok(self.matches.len() < self.limit)
+    }
+
+    fn context(&mut self, _: &Searcher, context: &SinkContext<'_>) -> Result<bool, Self::Error> {
+        let text = line_text(context.bytes());
+        match (context.kind(), self.matches.last_mut()) {
+            (SinkContextKind::After, Some(last)) if last.path == self.path => last.after.push(text),
+            _ => self.before.push(text),
+        }
+        Ok(true)
+    }
+}
+
+/// Search files under `root` (a file or directory inside `base`) for `options.pattern`.
+pub fn grep(base: &Path, root: &Path, options: &GrepOptions) -> Result<GrepResult> {
+    let matcher = RegexMatcherBuilder::new()
+        .case_insensitive(options.case_insensitive)
+        .build(&options.pattern)
+        .map_err(|e| eyre::eyre!("Invalid regex pattern: {}", e))?;
+    let mut searcher = SearcherBuilder::new()
+        .line_number(true)
+        .before_context(options.context)
+        .after_context(options.context)
+        .binary_detection(BinaryDetection::quit(b'\x00'))
+        .build();
+    let base = base.canonicalize().unwrap_or_else(|_| base.to_path_buf());
+    let limit = options.limit.unwrap_or(usize::MAX);
+
+    let mut result = GrepResult::default();
+    for entry in walker(root, options.glob.as_deref(), &[])?.build() {
+        let Ok(entry) = entry else { continue };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        let sink = MatchSink {
+            matcher: &matcher,
+            path: relative(&base, entry.path()),
+            matches: &mut result.matches,
+            before: Vec::new(),
+            limit,
+        };
+        // unreadable files are skipped like binary ones
+        let _ = searcher.search_path(&matcher, entry.path(), sink);
+        if result.matches.len() >= limit {
+            result.truncated = true;
+            break;
+        }
+    }
+    Ok(result)
+}
+
+/// Paths under `base` matching `pattern` (relative, '*' doesn't cross '/'), sorted.
+pub fn glob(base: &Path, pattern: &str) -> Result<Vec<String>> {
+    glob_paths(base, pattern, &[], false)
+}
+
+/// Files under `base` matching `pattern` like `glob`, without descending into `skip_dirs`.
+pub fn glob_files(
+    base: &Path,
+    pattern: &str,
+    skip_dirs: &'static [&'static str],
+) -> Result<Vec<String>> {
+    glob_paths(base, pattern, skip_dirs, true)
+}
+
+fn glob_paths(
+    base: &Path,
+    pattern: &str,
+    skip_dirs: &'static [&'static str],
+    files_only: bool,
+) -> Result<Vec<String>> {
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|e| eyre::eyre!("Invalid glob pattern: {}", e))?
+        .compile_matcher();
+    let mut matches: Vec<String> = walker(base, None, skip_dirs)?
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() > 0)
+        .filter(|entry| !files_only || entry.file_type().is_some_and(|t| t.is_file()))
+        .map(|entry| PathBuf::from(relative(base, entry.path())))
+        .filter(|path| matcher.is_match(path))
+        .map(|path| path.display().to_string())
+        .collect();
+    matches.sort();
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grep_and_glob() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let write = |path: &str, content: &str| {
+            let path = base.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(".gitignore", "node_modules\n");
+        write("node_modules/pkg/index.ts", "const appName = 1;\n");
+        write(
+            "client/src/App.tsx",
+            "import x;\nconst appName = 'sales';\nexport {};\n",
+        );
+        write("server/src/index.ts", "// appName\n");
+
+        let options = GrepOptions {
+            pattern: "appName".to_string(),
+            context: 1,
+            ..Default::default()
+        };
+        let result = grep(base, base, &options).unwrap();
+        assert_eq!(
+            result.matches[0],
+            GrepMatch {
+                path: "client/src/App.tsx".to_string(),
+                line: 2,
+                column: 7,
+                text: "const appName = 'sales';".to_string(),
+                before: vec!["import x;".to_string()],
+                after: vec!["export {};".to_string()],
+            }
+        );
+        assert_eq!(result.matches.len(), 2);
+        assert_eq!(result.display().lines().nth(3), Some("--"));
+
+        let options = GrepOptions {
+            pattern: "APPNAME".to_string(),
+            case_insensitive: true,
+            glob: Some("*.ts".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let result = grep(base, base, &options).unwrap();
+        assert_eq!(result.matches[0].path, "server/src/index.ts");
+        assert!(result.truncated);
+
+        assert_eq!(
+            glob(base, "**/*.ts*").unwrap(),
+            vec!["client/src/App.tsx", "server/src/index.ts"]
+        );
+        assert_eq!(
+            glob(base, "*").unwrap(),
+            vec![".gitignore", "client", "server"]
+        );
+
+        // skipped directories aren't entered even when not ignored
+        write("dist/index.js", "appName\n");
+        assert_eq!(
+            glob_files(base, "**/*", &["dist"]).unwrap(),
+            vec![".gitignore", "client/src/App.tsx", "server/src/index.ts"]
+        );
+    }
+}
@@ -1,22 +1,57 @@
-use std::path::PathBuf;
+use crate::paths;
+use chrono::Utc;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Session state that survives a server restart, stored as ~/.edda/sessions/<id>.json
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedSession {
+    /// also the trajectory id in ~/.edda/history.jsonl
+    session_id: String,
+    work_dir: Option<PathBuf>,
+    /// absent in sessions saved before multi-project support
+    #[serde(default)]
+    projects: Vec<PathBuf>,
+    first_tool_called: bool,
+    updated_at: String,
+}
+
+fn session_file(session_id: &str) -> Result<PathBuf> {
+    // session ids are uuids; reject anything that could escape the sessions dir
+    if session_id.is_empty()
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        eyre::bail!("invalid session id: '{}'", session_id);
+    }
+    Ok(paths::session_dir()?.join(format!("{}.json", session_id)))
+}
+
 /// Session-scoped context shared across all providers in an MCP session.
 ///
 /// Each MCP stdio connection creates a new session with isolated state.
 /// This struct holds session-level data that providers may need to access.
+/// In binary mode the state is persisted so `--resume` can pick the session up again.
 #[derive(Debug, Clone)]
 pub struct SessionContext {
     /// Optional session identifier (Some for binary mode, None for cargo run).
     /// Used for trajectory logging and debugging.
     pub session_id: Option<String>,
 
-    /// Workspace directory path, set after successful scaffolding.
-    /// Starts as None, gets set by scaffold_data_app tool.
+    /// Active project directory, set after successful scaffolding.
+    /// Starts as None, gets set by scaffold_data_app tool and changed by switch_project.
     /// Used by WorkspaceTools to scope file operations to the project directory.
     pub work_dir: Arc<RwLock<Option<PathBuf>>>,
 
+    /// Every project scaffolded or opened in this session, keyed by work_dir.
+    /// Tool calls that reference a path inside one of them make it the active project.
+    pub projects: Arc<RwLock<BTreeSet<PathBuf>>>,
+
     /// Tracks whether any tool has been called in this session.
     /// Used to inject engine guidance on first tool call only.
     pub first_tool_called: Arc<RwLock<bool>>,
@@ -33,8 +68,104 @@ impl SessionContext {
         Self {
             session_id,
             work_dir: Arc::new(RwLock::new(None)),
+            projects: Arc::new(RwLock::new(BTreeSet::new())),
             first_tool_called: Arc::new(RwLock::new(false)),
             playwright_warmed: Arc::new(RwLock::new(false)),
         }
     }
+
+    /// Restore a session saved by `persist`, used by `--resume <session-id>`.
+    pub fn resume(session_id: &str) -> Result<Self> {
+        let path = session_file(session_id)?;
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|_| eyre::eyre!("no saved session '{}' in {}", session_id, path.display()))?;
+        let saved: PersistedSession = serde_json::from_str(&contents)?;
+
+        // the project may have been moved or deleted since
+        let work_dir = saved.work_dir.filter(|dir| dir.is_dir());
+        let mut projects: BTreeSet<PathBuf> =
+            saved.projects.into_iter().filter(|dir| dir.is_dir()).collect();
+        projects.extend(work_dir.clone());
+        Ok(Self {
+            session_id: Some(saved.session_id),
+            work_dir: Arc::new(RwLock::new(work_dir)),
+            projects: Arc::new(RwLock::new(projects)),
+            first_tool_called: Arc::new(RwLock::new(saved.first_tool_called)),
+            playwright_warmed: Arc::new(RwLock::new(false)),
+        })
+    }
+
+    /// Save the resumable parts of the session; a no-op for sessions without an id.
+    pub async fn persist(&self) -> Result<()> {
+        let Some(session_id) = &self.session_id else {
+            return Ok(());
+        };
+        let saved = PersistedSession {
+            session_id: session_id.clone(),
+            work_dir: self.work_dir.read().await.clone(),
+            projects: self.projects.read().await.iter().cloned().collect(),
+            first_tool_called: *self.first_tool_called.read().await,
+            updated_at: Utc::now().to_rfc3339(),
+        };
+        let path = session_file(session_id)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&saved)?)?;
+        Ok(())
+    }
+
+    /// Add a project to the registry and make it the active one.
+    pub async fn register_project(&self, work_dir: PathBuf) {
+        let work_dir = work_dir.canonicalize().unwrap_or(work_dir);
+        self.projects.write().await.insert(work_dir.clone());
+        *self.work_dir.write().await = Some(work_dir);
+    }
+
+    /// Make the registered project containing `path` active. Returns the project when
+    /// `path` belongs to one, None when it's unknown (the active project is unchanged).
+    pub async fn route_to_project(&self, path: &Path) -> Option<PathBuf> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        // nested projects: the innermost one wins
+        let project = self
+            .projects
+            .read()
+            .await
+            .iter()
+            .filter(|project| path.starts_with(project))
+            .max_by_key(|project| project.components().count())
+            .cloned()?;
+        let mut work_dir = self.work_dir.write().await;
+        if work_dir.as_ref() != Some(&project) {
+            tracing::info!("Switching active project to {}", project.display());
+            *work_dir = Some(project.clone());
+        }
+        Some(project)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_route_to_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().canonicalize().unwrap();
+        let (sales, hr) = (dir.join("sales"), dir.join("hr"));
+        std::fs::create_dir_all(sales.join("server")).unwrap();
+        std::fs::create_dir_all(&hr).unwrap();
+
+        let session_ctx = SessionContext::new(None);
+        session_ctx.register_project(sales.clone()).await;
+        session_ctx.register_project(hr.clone()).await;
+        assert_eq!(*session_ctx.work_dir.read().await, Some(hr.clone()));
+
+        let routed = session_ctx.route_to_project(&sales.join("server")).await;
+        assert_eq!(routed, Some(sales.clone()));
+        assert_eq!(*session_ctx.work_dir.read().await, Some(sales.clone()));
+
+        assert_eq!(session_ctx.route_to_project(&dir.join("other")).await, None);
+        assert_eq!(*session_ctx.work_dir.read().await, Some(sales));
+    }
 }
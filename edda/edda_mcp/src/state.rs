@@ -1,10 +1,22 @@
 use chrono::{DateTime, Utc};
+use edda_templates::TemplateCapabilities;
 use eyre::{eyre, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 const STATE_FILE_NAME: &str = ".edda_state";
+const MANIFEST_FILE_NAME: &str = ".edda_manifest";
+const ARTIFACTS_DIR_NAME: &str = ".edda";
+const BASELINE_DIR_NAME: &str = "baseline";
+
+/// outcome of the last successful validation, returned again while the checksum matches
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedValidation {
+    pub message: String,
+    pub screenshot_path: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "state", content = "data")]
@@ -13,11 +25,15 @@ pub enum ProjectState {
     Validated {
         validated_at: DateTime<Utc>,
         checksum: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cached: Option<CachedValidation>,
     },
     Deployed {
         validated_at: DateTime<Utc>,
         checksum: String,
         deployed_at: DateTime<Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cached: Option<CachedValidation>,
     },
 }
 
@@ -26,12 +42,13 @@ impl ProjectState {
         Self::Scaffolded
     }
 
-    pub fn validate(self, checksum: String) -> Result<Self> {
+    pub fn validate(self, checksum: String, cached: Option<CachedValidation>) -> Result<Self> {
         match self {
             Self::Scaffolded | Self::Validated { .. } | Self::Deployed { .. } => {
                 Ok(Self::Validated {
                     validated_at: Utc::now(),
                     checksum,
+                    cached,
                 })
             }
         }
@@ -39,10 +56,15 @@ impl ProjectState {
 
     pub fn deploy(self) -> Result<Self> {
         match self {
-            Self::Validated { validated_at, checksum } => Ok(Self::Deployed {
+            Self::Validated {
+                validated_at,
+                checksum,
+                cached,
+            } => Ok(Self::Deployed {
                 validated_at,
                 checksum,
                 deployed_at: Utc::now(),
+                cached,
             }),
             Self::Scaffolded => Err(eyre!("cannot deploy: project not validated")),
             Self::Deployed { .. } => Err(eyre!("cannot deploy: project already deployed (re-validate first)")),
@@ -56,6 +78,23 @@ impl ProjectState {
         }
     }
 
+    /// cached result of the last validation if the project still has `checksum`
+    pub fn cached_validation(&self, checksum: &str) -> Option<&CachedValidation> {
+        match self {
+            Self::Validated {
+                checksum: validated,
+                cached,
+                ..
+            }
+            | Self::Deployed {
+                checksum: validated,
+                cached,
+                ..
+            } if validated == checksum => cached.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn is_validated(&self) -> bool {
         matches!(self, Self::Validated { .. } | Self::Deployed { .. })
     }
@@ -95,24 +134,24 @@ pub fn save_state(work_dir: &Path, state: &ProjectState) -> Result<()> {
     Ok(())
 }
 
-/// compute BLAKE3 checksum of critical project files
-pub fn compute_checksum(work_dir: &Path) -> Result<String> {
+/// critical project files (the template's source paths, e.g. client/, server/ and the root
+/// package.json of the tRPC template), sorted
+fn project_files(work_dir: &Path) -> Result<Vec<PathBuf>> {
+    let capabilities = TemplateCapabilities::load(work_dir)?.unwrap_or_default();
     let mut files_to_hash = Vec::new();
 
-    // collect all source files in client/ and server/
-    for dir in &["client", "server"] {
-        let dir_path = work_dir.join(dir);
-        if dir_path.exists() {
-            collect_source_files(&dir_path, &mut files_to_hash)?;
+    for source in capabilities.source_paths() {
+        let path = match *source {
+            "." => work_dir.to_path_buf(),
+            source => work_dir.join(source),
+        };
+        match path.is_dir() {
+            true => collect_source_files(&path, &mut files_to_hash)?,
+            false if path.exists() => files_to_hash.push(path),
+            false => {}
         }
     }
 
-    // include root package.json
-    let package_json = work_dir.join("package.json");
-    if package_json.exists() {
-        files_to_hash.push(package_json);
-    }
-
     // sort files deterministically
     files_to_hash.sort();
 
@@ -120,6 +159,13 @@ pub fn compute_checksum(work_dir: &Path) -> Result<String> {
         return Err(eyre!("no files to hash - project structure appears invalid"));
     }
 
+    Ok(files_to_hash)
+}
+
+/// compute BLAKE3 checksum of critical project files
+pub fn compute_checksum(work_dir: &Path) -> Result<String> {
+    let files_to_hash = project_files(work_dir)?;
+
     // compute combined hash
     let mut hasher = blake3::Hasher::new();
 
@@ -132,6 +178,83 @@ pub fn compute_checksum(work_dir: &Path) -> Result<String> {
     Ok(hasher.finalize().to_hex().to_string())
 }
 
+/// per-file BLAKE3 hashes keyed by path relative to work_dir, written after each
+/// successful validation so the next one can tell which parts of the project changed
+pub type FileManifest = BTreeMap<String, String>;
+
+pub fn compute_manifest(work_dir: &Path) -> Result<FileManifest> {
+    let mut manifest = FileManifest::new();
+    for file_path in project_files(work_dir)? {
+        let content = fs::read(&file_path)
+            .map_err(|e| eyre!("failed to read {}: {}", file_path.display(), e))?;
+        let relative = file_path.strip_prefix(work_dir).unwrap_or(&file_path);
+        manifest.insert(
+            relative.to_string_lossy().to_string(),
+            blake3::hash(&content).to_hex().to_string(),
+        );
+    }
+    Ok(manifest)
+}
+
+/// load manifest of the last successful validation from work_dir/.edda_manifest
+pub fn load_manifest(work_dir: &Path) -> Result<Option<FileManifest>> {
+    let manifest_path = work_dir.join(MANIFEST_FILE_NAME);
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&manifest_path)
+        .map_err(|e| eyre!("failed to read manifest file: {}", e))?;
+    // an unreadable manifest only costs a full validation
+    Ok(serde_json::from_str(&content).ok())
+}
+
+pub fn save_manifest(work_dir: &Path, manifest: &FileManifest) -> Result<()> {
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| eyre!("failed to serialize manifest: {}", e))?;
+    fs::write(work_dir.join(MANIFEST_FILE_NAME), content)
+        .map_err(|e| eyre!("failed to write manifest file: {}", e))?;
+    Ok(())
+}
+
+/// validation artifacts of work_dir (route screenshots, recordings, network log, baseline),
+/// kept out of deployed and published files
+pub fn artifacts_dir(work_dir: &Path) -> PathBuf {
+    work_dir.join(ARTIFACTS_DIR_NAME)
+}
+
+/// screenshot of the last successful validation, the next screenshot is compared to it
+pub fn baseline_screenshot(work_dir: &Path) -> PathBuf {
+    artifacts_dir(work_dir)
+        .join(BASELINE_DIR_NAME)
+        .join("screenshot.png")
+}
+
+/// make `screenshot` the baseline of work_dir
+pub fn save_baseline_screenshot(work_dir: &Path, screenshot: &Path) -> Result<()> {
+    fs::create_dir_all(artifacts_dir(work_dir).join(BASELINE_DIR_NAME))
+        .map_err(|e| eyre!("failed to create baseline directory: {}", e))?;
+    fs::copy(screenshot, baseline_screenshot(work_dir))
+        .map_err(|e| eyre!("failed to save baseline screenshot: {}", e))?;
+    Ok(())
+}
+
+/// paths added, removed or modified between two manifests, sorted
+pub fn changed_files(previous: &FileManifest, current: &FileManifest) -> Vec<String> {
+    let mut changed: Vec<String> = current
+        .iter()
+        .filter(|(path, hash)| previous.get(*path) != Some(*hash))
+        .map(|(path, _)| path.clone())
+        .chain(
+            previous
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned(),
+        )
+        .collect();
+    changed.sort();
+    changed
+}
+
 /// verify checksum matches current project state
 pub fn verify_checksum(work_dir: &Path, expected: &str) -> Result<bool> {
     let current = compute_checksum(work_dir)?;
@@ -156,7 +279,10 @@ fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
                 // skip excluded directories
                 if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
                     match name {
-                        "node_modules" | "dist" | ".git" | "build" | "coverage" => continue,
+                        "node_modules" | "dist" | "build" | "coverage" | "venv"
+                        | "__pycache__" | "target" => continue,
+                        // .git, .venv and edda's own state
+                        name if name.starts_with('.') => continue,
                         _ => {}
                     }
                 }
@@ -166,9 +292,8 @@ fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
                 // include meaningful source and config files
                 if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
                     match ext {
-                        "ts" | "tsx" | "js" | "jsx" | "json" | "css" | "html" | "yaml" | "yml" => {
-                            files.push(path)
-                        }
+                        "ts" | "tsx" | "js" | "jsx" | "json" | "css" | "html" | "yaml" | "yml"
+                        | "py" | "txt" | "ini" | "rs" | "toml" | "lock" => files.push(path),
                         _ => {}
                     }
                 }
@@ -178,3 +303,111 @@ fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_validation() {
+        let cached = CachedValidation {
+            message: "All validations passed".to_string(),
+            screenshot_path: None,
+        };
+        let state = ProjectState::new()
+            .validate("abc".to_string(), Some(cached.clone()))
+            .unwrap()
+            .deploy()
+            .unwrap();
+        assert_eq!(state.cached_validation("abc"), Some(&cached));
+        assert_eq!(state.cached_validation("def"), None);
+
+        // state files written before results were cached still load
+        let legacy = r#"{"state":"Validated","data":{"validated_at":"2025-10-01T00:00:00Z","checksum":"abc"}}"#;
+        let state: ProjectState = serde_json::from_str(legacy).unwrap();
+        assert_eq!(state.cached_validation("abc"), None);
+    }
+
+    #[test]
+    fn test_baseline_screenshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        fs::write(work_dir.join("screenshot.png"), "first").unwrap();
+        save_baseline_screenshot(work_dir, &work_dir.join("screenshot.png")).unwrap();
+        fs::write(work_dir.join("screenshot.png"), "second").unwrap();
+        save_baseline_screenshot(work_dir, &work_dir.join("screenshot.png")).unwrap();
+        assert_eq!(
+            fs::read_to_string(baseline_screenshot(work_dir)).unwrap(),
+            "second"
+        );
+    }
+
+    #[test]
+    fn test_python_project_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        fs::write(
+            work_dir.join("edda.json"),
+            r#"{"validation": {"strategy": "python"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(work_dir.join("app/__pycache__")).unwrap();
+        fs::create_dir_all(work_dir.join(".edda_template")).unwrap();
+        fs::write(work_dir.join("app/main.py"), "a").unwrap();
+        fs::write(work_dir.join("app/__pycache__/main.json"), "b").unwrap();
+        fs::write(work_dir.join(".edda_template/app.py"), "c").unwrap();
+        fs::write(work_dir.join("requirements.txt"), "fastapi").unwrap();
+
+        let manifest = compute_manifest(work_dir).unwrap();
+        assert_eq!(
+            manifest.keys().collect::<Vec<_>>(),
+            vec!["app/main.py", "edda.json", "requirements.txt"]
+        );
+    }
+
+    #[test]
+    fn test_rust_project_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        fs::write(
+            work_dir.join("edda.json"),
+            r#"{"validation": {"strategy": "rust"}}"#,
+        )
+        .unwrap();
+        fs::create_dir_all(work_dir.join("src")).unwrap();
+        fs::create_dir_all(work_dir.join("target/debug")).unwrap();
+        fs::write(work_dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(work_dir.join("target/debug/build.rs"), "").unwrap();
+        fs::write(work_dir.join("Cargo.toml"), "[package]").unwrap();
+        fs::write(work_dir.join("Cargo.lock"), "version = 4").unwrap();
+
+        let manifest = compute_manifest(work_dir).unwrap();
+        assert_eq!(
+            manifest.keys().collect::<Vec<_>>(),
+            vec!["Cargo.lock", "Cargo.toml", "edda.json", "src/main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        fs::create_dir_all(work_dir.join("server/src")).unwrap();
+        fs::create_dir_all(work_dir.join("client/src")).unwrap();
+        fs::write(work_dir.join("server/src/index.ts"), "a").unwrap();
+        fs::write(work_dir.join("client/src/App.tsx"), "b").unwrap();
+        fs::write(work_dir.join("client/src/old.ts"), "c").unwrap();
+
+        let previous = compute_manifest(work_dir).unwrap();
+        assert!(changed_files(&previous, &previous).is_empty());
+
+        fs::write(work_dir.join("server/src/index.ts"), "changed").unwrap();
+        fs::remove_file(work_dir.join("client/src/old.ts")).unwrap();
+        fs::write(work_dir.join("server/src/new.ts"), "d").unwrap();
+        let current = compute_manifest(work_dir).unwrap();
+        assert_eq!(
+            changed_files(&previous, &current),
+            vec!["client/src/old.ts", "server/src/index.ts", "server/src/new.ts"]
+        );
+    }
+}
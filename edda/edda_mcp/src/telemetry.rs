@@ -0,0 +1,285 @@
+//! Opt-in anonymous usage metrics: per-tool call counts, failures and durations, batched
+//! and POSTed to the endpoint of the `telemetry` config section. Tool arguments, results,
+//! paths and session ids never leave the machine.
+
+use crate::config::TelemetryConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 300;
+
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Senders of every running telemetry task, so shutdown can flush them
+static LIVE: LazyLock<Mutex<Vec<mpsc::WeakUnboundedSender<Message>>>> =
+    LazyLock::new(Default::default);
+
+struct ToolCall {
+    tool: String,
+    duration: Duration,
+    success: bool,
+}
+
+enum Message {
+    Call(ToolCall),
+    /// Send what was recorded so far, then acknowledge
+    Flush(oneshot::Sender<()>),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ToolStats {
+    pub tool: String,
+    pub calls: u64,
+    pub failures: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+/// What is sent: the server version and platform, and the tool calls since the last batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Batch {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub tools: Vec<ToolStats>,
+}
+
+/// Calls aggregated per tool until the next batch
+#[derive(Debug, Default)]
+struct Stats {
+    tools: BTreeMap<String, ToolStats>,
+}
+
+impl Stats {
+    fn record(&mut self, call: ToolCall) {
+        let ms = call.duration.as_millis() as u64;
+        let stats = self
+            .tools
+            .entry(call.tool.clone())
+            .or_insert_with(|| ToolStats {
+                tool: call.tool,
+                ..Default::default()
+            });
+        stats.calls += 1;
+        if !call.success {
+            stats.failures += 1;
+        }
+        stats.total_ms += ms;
+        stats.max_ms = stats.max_ms.max(ms);
+    }
+
+    /// Everything recorded so far, `None` if there were no calls
+    fn take_batch(&mut self) -> Option<Batch> {
+        if self.tools.is_empty() {
+            return None;
+        }
+        let tools = std::mem::take(&mut self.tools).into_values().collect();
+        Some(Batch {
+            version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            tools,
+        })
+    }
+}
+
+/// Handle recording tool calls. Batches are sent from a background task, which sends
+/// the remainder and stops once every handle is dropped (e.g. providers were reloaded).
+/// The runtime doesn't wait for that task on exit, so shutdown calls [`flush_all`].
+pub struct Telemetry {
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+impl Telemetry {
+    /// Returns `None` unless telemetry is enabled and has an endpoint.
+    pub fn from_config(config: &TelemetryConfig) -> Option<Self> {
+        let endpoint = config
+            .endpoint
+            .clone()
+            .filter(|endpoint| config.enabled && !endpoint.is_empty())?;
+        let client = reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .ok()?;
+        let interval = Duration::from_secs(
+            config
+                .flush_interval_secs
+                .unwrap_or(DEFAULT_FLUSH_INTERVAL_SECS)
+                .max(1),
+        );
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run(client, endpoint, interval, receiver));
+        let mut live = LIVE.lock().unwrap();
+        live.retain(|sender| sender.strong_count() > 0);
+        live.push(sender.downgrade());
+        Some(Self { sender })
+    }
+
+    pub fn record(&self, tool: &str, duration: Duration, success: bool) {
+        // the task only stops after every handle is gone
+        let _ = self.sender.send(Message::Call(ToolCall {
+            tool: tool.to_string(),
+            duration,
+            success,
+        }));
+    }
+}
+
+/// Send what every running telemetry task recorded so far, waiting for each send
+pub async fn flush_all() {
+    let senders: Vec<_> = LIVE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(mpsc::WeakUnboundedSender::upgrade)
+        .collect();
+    for sender in senders {
+        let (done, flushed) = oneshot::channel();
+        if sender.send(Message::Flush(done)).is_ok() {
+            let _ = tokio::time::timeout(SEND_TIMEOUT, flushed).await;
+        }
+    }
+}
+
+async fn run(
+    client: reqwest::Client,
+    endpoint: String,
+    interval: Duration,
+    mut receiver: mpsc::UnboundedReceiver<Message>,
+) {
+    let mut stats = Stats::default();
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Some(Message::Call(call)) => stats.record(call),
+                Some(Message::Flush(done)) => {
+                    if let Some(batch) = stats.take_batch() {
+                        send(&client, &endpoint, &batch).await;
+                    }
+                    let _ = done.send(());
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {
+                if let Some(batch) = stats.take_batch() {
+                    send(&client, &endpoint, &batch).await;
+                }
+            }
+        }
+    }
+    if let Some(batch) = stats.take_batch() {
+        send(&client, &endpoint, &batch).await;
+    }
+}
+
+/// Failures are only logged, metrics aren't worth retrying or bothering the user with
+async fn send(client: &reqwest::Client, endpoint: &str, batch: &Batch) {
+    match client.post(endpoint).json(batch).send().await {
+        Ok(response) if response.status().is_success() => {
+            tracing::debug!("Sent telemetry for {} tools", batch.tools.len())
+        }
+        Ok(response) => tracing::debug!("Telemetry endpoint returned {}", response.status()),
+        Err(e) => tracing::debug!("Failed to send telemetry: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(tool: &str, ms: u64, success: bool) -> ToolCall {
+        ToolCall {
+            tool: tool.to_string(),
+            duration: Duration::from_millis(ms),
+            success,
+        }
+    }
+
+    #[test]
+    fn test_stats_batch() {
+        let mut stats = Stats::default();
+        assert_eq!(stats.take_batch(), None);
+
+        stats.record(call("validate_data_app", 1200, false));
+        stats.record(call("read_file", 5, true));
+        stats.record(call("validate_data_app", 800, true));
+        let batch = stats.take_batch().unwrap();
+        assert_eq!(
+            batch.tools,
+            vec![
+                ToolStats {
+                    tool: "read_file".to_string(),
+                    calls: 1,
+                    failures: 0,
+                    total_ms: 5,
+                    max_ms: 5,
+                },
+                ToolStats {
+                    tool: "validate_data_app".to_string(),
+                    calls: 2,
+                    failures: 1,
+                    total_ms: 2000,
+                    max_ms: 1200,
+                },
+            ]
+        );
+        // a batch starts over
+        assert_eq!(stats.take_batch(), None);
+    }
+
+    #[test]
+    fn test_disabled_without_opt_in() {
+        let config = TelemetryConfig {
+            enabled: false,
+            endpoint: Some("https://telemetry.example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(Telemetry::from_config(&config).is_none());
+
+        let config = TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(Telemetry::from_config(&config).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flush_all_sends_recorded_calls() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = TelemetryConfig {
+            enabled: true,
+            endpoint: Some(format!("http://{}", listener.local_addr().unwrap())),
+            flush_interval_secs: Some(3600),
+        };
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("validate_data_app") {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before the batch arrived");
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let telemetry = Telemetry::from_config(&config).unwrap();
+        telemetry.record("validate_data_app", Duration::from_millis(10), true);
+        // well before the interval, the batch goes out on flush
+        flush_all().await;
+        tokio::time::timeout(Duration::from_secs(1), server)
+            .await
+            .expect("batch not sent on flush")
+            .unwrap();
+        drop(telemetry);
+    }
+}
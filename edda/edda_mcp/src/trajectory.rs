@@ -1,7 +1,7 @@
 use chrono::Utc;
 use eyre::Result;
 use rmcp::model::{CallToolRequestParam, CallToolResult, ServerInfo};
-use rmcp::service::{RequestContext, RoleServer};
+use rmcp::service::{NotificationContext, RequestContext, RoleServer};
 use rmcp::{ErrorData, ServerHandler};
 use serde::{Deserialize, Serialize};
 use std::io::Write as IoWrite;
@@ -10,6 +10,7 @@ use std::sync::Mutex;
 
 use crate::paths;
 use crate::providers::CombinedProvider;
+use crate::redaction::Redactor;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(tag = "entry_type")]
@@ -36,23 +37,29 @@ pub struct TrajectoryEntry {
     pub success: bool,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// wall time of the tool call; absent in entries recorded by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
 }
 
-pub struct TrajectoryTrackingProvider {
-    inner: CombinedProvider,
+/// Records every tool call of the wrapped provider (a `CombinedProvider`, or the
+/// `ReloadableProvider` around it) in the history file.
+pub struct TrajectoryTrackingProvider<P = CombinedProvider> {
+    inner: P,
     history_file: Mutex<std::fs::File>,
     session_id: String,
+    redactor: Redactor,
 }
 
-impl TrajectoryTrackingProvider {
-    pub fn new(inner: CombinedProvider, session_id: String, config: crate::config::Config) -> Result<Self> {
+impl<P: ServerHandler> TrajectoryTrackingProvider<P> {
+    pub fn new(inner: P, session_id: String, config: crate::config::Config) -> Result<Self> {
         let history_path = paths::trajectory_path()?;
         Self::new_with_path(inner, session_id, config, history_path)
     }
 
     #[doc(hidden)]
     pub fn new_with_path(
-        inner: CombinedProvider,
+        inner: P,
         session_id: String,
         config: crate::config::Config,
         history_path: PathBuf,
@@ -71,6 +78,7 @@ impl TrajectoryTrackingProvider {
             inner,
             history_file: Mutex::new(history_file),
             session_id: session_id.clone(),
+            redactor: Redactor::new(config.redaction.as_ref())?,
         };
 
         // write session metadata as first entry
@@ -97,11 +105,15 @@ impl TrajectoryTrackingProvider {
     }
 }
 
-impl ServerHandler for TrajectoryTrackingProvider {
+impl<P: ServerHandler> ServerHandler for TrajectoryTrackingProvider<P> {
     fn get_info(&self) -> ServerInfo {
         self.inner.get_info()
     }
 
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        self.inner.on_initialized(context).await
+    }
+
     async fn list_tools(
         &self,
         request: Option<rmcp::model::PaginatedRequestParam>,
@@ -110,6 +122,38 @@ impl ServerHandler for TrajectoryTrackingProvider {
         self.inner.list_tools(request, context).await
     }
 
+    async fn list_resources(
+        &self,
+        request: Option<rmcp::model::PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListResourcesResult, ErrorData> {
+        self.inner.list_resources(request, context).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: rmcp::model::ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ReadResourceResult, ErrorData> {
+        self.inner.read_resource(request, context).await
+    }
+
+    async fn list_prompts(
+        &self,
+        request: Option<rmcp::model::PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListPromptsResult, ErrorData> {
+        self.inner.list_prompts(request, context).await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: rmcp::model::GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::GetPromptResult, ErrorData> {
+        self.inner.get_prompt(request, context).await
+    }
+
     async fn call_tool(
         &self,
         params: CallToolRequestParam,
@@ -117,12 +161,23 @@ impl ServerHandler for TrajectoryTrackingProvider {
     ) -> Result<CallToolResult, ErrorData> {
         let timestamp = Utc::now().to_rfc3339();
         let tool_name = params.name.to_string();
+        // arguments can carry secrets too (connection strings, tokens pasted by the user)
         let arguments = params.arguments.as_ref().map(|args| {
-            serde_json::to_value(args).unwrap_or(serde_json::Value::Null)
+            let mut value = serde_json::to_value(args).unwrap_or(serde_json::Value::Null);
+            self.redactor.redact_value(&mut value);
+            // set_env_var values are credentials the patterns may not recognize
+            if tool_name == crate::providers::SET_ENV_VAR_TOOL
+                && let Some(secret) = value.get_mut("value")
+            {
+                *secret = serde_json::Value::String("[REDACTED]".to_string());
+            }
+            value
         });
 
         // call inner provider
+        let start = std::time::Instant::now();
         let result = self.inner.call_tool(params, ctx).await;
+        let duration_ms = Some(start.elapsed().as_millis() as u64);
 
         // record trajectory
         let entry = match &result {
@@ -132,8 +187,14 @@ impl ServerHandler for TrajectoryTrackingProvider {
                 tool_name,
                 arguments,
                 success: !call_result.is_error.unwrap_or(false),
-                result: Some(serde_json::to_value(call_result).unwrap_or(serde_json::Value::Null)),
+                result: Some({
+                    let mut value =
+                        serde_json::to_value(call_result).unwrap_or(serde_json::Value::Null);
+                    self.redactor.redact_value(&mut value);
+                    value
+                }),
                 error: None,
+                duration_ms,
             },
             Err(error_data) => TrajectoryEntry {
                 session_id: self.session_id.clone(),
@@ -142,7 +203,8 @@ impl ServerHandler for TrajectoryTrackingProvider {
                 arguments,
                 success: false,
                 result: None,
-                error: Some(error_data.to_string()),
+                error: Some(self.redactor.redact_str(&error_data.to_string())),
+                duration_ms,
             },
         };
 
@@ -170,6 +232,7 @@ mod tests {
             success: true,
             result: Some(serde_json::json!({"output": "success"})),
             error: None,
+            duration_ms: None,
         };
 
         let json_line = serde_json::to_string(&HistoryEntry::Tool(entry)).unwrap();
@@ -196,6 +259,7 @@ mod tests {
             success: false,
             result: None,
             error: Some("Tool execution failed".to_string()),
+            duration_ms: None,
         };
 
         let json_line = serde_json::to_string(&HistoryEntry::Tool(entry)).unwrap();
@@ -222,6 +286,7 @@ mod tests {
             success: true,
             result: Some(serde_json::json!({"url": "https://example.com"})),
             error: None,
+            duration_ms: None,
         };
 
         let json_line = serde_json::to_string(&HistoryEntry::Tool(entry)).unwrap();
@@ -247,9 +312,19 @@ mod tests {
             required_providers: vec![ProviderType::DatabricksCli, ProviderType::Io],
             io_config: Some(IoConfig {
                 template: TemplateConfig::Trpc,
+                templates: Default::default(),
                 validation: None,
                 screenshot: None,
+                allow_host_hooks: false,
             }),
+            databricks_auth: Default::default(),
+            notifications: None,
+            timeouts: None,
+            redaction: None,
+            json_output: false,
+            workspace_allowed_paths: Vec::new(),
+            engine_guide: None,
+            telemetry: None,
         };
 
         let metadata = SessionMetadata {
@@ -286,9 +361,19 @@ mod tests {
                     name: "MyTemplate".to_string(),
                     path: "/path/to/template".to_string(),
                 },
+                templates: Default::default(),
                 validation: None,
                 screenshot: None,
+                allow_host_hooks: false,
             }),
+            databricks_auth: Default::default(),
+            notifications: None,
+            timeouts: None,
+            redaction: None,
+            json_output: false,
+            workspace_allowed_paths: Vec::new(),
+            engine_guide: None,
+            telemetry: None,
         };
 
         let metadata = SessionMetadata {
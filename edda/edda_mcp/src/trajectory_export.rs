@@ -0,0 +1,311 @@
+//! Export one recorded session from ~/.edda/history.jsonl as a self-contained report
+//! (markdown or HTML with embedded screenshots) for sharing and debugging.
+
+use crate::trajectory::{HistoryEntry, SessionMetadata, TrajectoryEntry};
+use base64::Engine;
+use eyre::Result;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+/// Tool results longer than this are truncated in reports
+const MAX_RESULT_CHARS: usize = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Html,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Html => "html",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// All history entries of one session, in recording order.
+pub struct SessionTrajectory {
+    pub session_id: String,
+    /// Every `Session` entry (one per start, resumed sessions have several)
+    pub starts: Vec<SessionMetadata>,
+    pub calls: Vec<TrajectoryEntry>,
+}
+
+/// Load a session from the history file. `session` may be the full id or a unique prefix
+/// (e.g. the 8 characters used in log file names).
+pub fn load_session(history_path: &Path, session: &str) -> Result<SessionTrajectory> {
+    let file = std::fs::File::open(history_path)
+        .map_err(|e| eyre::eyre!("failed to open {}: {}", history_path.display(), e))?;
+
+    let mut starts = Vec::new();
+    let mut calls = Vec::new();
+    let mut session_ids = std::collections::BTreeSet::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        // skip lines written by incompatible versions instead of failing the export
+        let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line) else {
+            continue;
+        };
+        let session_id = match &entry {
+            HistoryEntry::Session(metadata) => &metadata.session_id,
+            HistoryEntry::Tool(call) => &call.session_id,
+        };
+        if !session_id.starts_with(session) {
+            continue;
+        }
+        session_ids.insert(session_id.clone());
+        match entry {
+            HistoryEntry::Session(metadata) => starts.push(metadata),
+            HistoryEntry::Tool(call) => calls.push(call),
+        }
+    }
+
+    let session_id = match session_ids.len() {
+        0 => eyre::bail!(
+            "no session matching '{}' in {}",
+            session,
+            history_path.display()
+        ),
+        1 => session_ids.into_iter().next().unwrap_or_default(),
+        n => eyre::bail!("'{}' matches {} sessions, use a longer id", session, n),
+    };
+    Ok(SessionTrajectory {
+        session_id,
+        starts,
+        calls,
+    })
+}
+
+/// Text content of a recorded CallToolResult, or the error message.
+pub fn call_output(call: &TrajectoryEntry) -> String {
+    if let Some(error) = &call.error {
+        return error.clone();
+    }
+    let texts: Vec<&str> = call
+        .result
+        .as_ref()
+        .and_then(|result| result.get("content"))
+        .and_then(|content| content.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+    truncate(&texts.join("\n"))
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_RESULT_CHARS) {
+        Some((idx, _)) => format!("{}\n… (truncated)", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+fn arguments(call: &TrajectoryEntry) -> String {
+    call.arguments
+        .as_ref()
+        .and_then(|args| serde_json::to_string_pretty(args).ok())
+        .unwrap_or_else(|| "{}".to_string())
+}
+
+fn duration(call: &TrajectoryEntry) -> String {
+    match call.duration_ms {
+        Some(ms) if ms >= 1000 => format!("{:.1}s", ms as f64 / 1000.0),
+        Some(ms) => format!("{}ms", ms),
+        None => "-".to_string(),
+    }
+}
+
+/// Screenshot files referenced by validation results ("Screenshot: /path/screenshot.png").
+fn screenshots(output: &str) -> Vec<PathBuf> {
+    output
+        .split_whitespace()
+        .map(|word| word.trim_end_matches([',', '.']))
+        .filter(|word| word.ends_with(".png") && word.starts_with('/'))
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn summary_line(trajectory: &SessionTrajectory) -> String {
+    let failed = trajectory.calls.iter().filter(|c| !c.success).count();
+    let total_ms: u64 = trajectory.calls.iter().filter_map(|c| c.duration_ms).sum();
+    format!(
+        "{} tool calls, {} failed, {:.1}s in tools",
+        trajectory.calls.len(),
+        failed,
+        total_ms as f64 / 1000.0
+    )
+}
+
+pub fn render_markdown(trajectory: &SessionTrajectory) -> String {
+    let mut out = format!("# Edda session {}\n\n", trajectory.session_id);
+    if let Some(start) = trajectory.starts.first() {
+        out.push_str(&format!("Started: {}\n\n", start.timestamp));
+    }
+    out.push_str(&format!("{}\n", summary_line(trajectory)));
+
+    for (idx, call) in trajectory.calls.iter().enumerate() {
+        let status = if call.success { "ok" } else { "failed" };
+        out.push_str(&format!(
+            "\n## {}. `{}` ({}, {})\n\n{}\n\n**Arguments**\n\n```json\n{}\n```\n\n**Result**\n\n```\n{}\n```\n",
+            idx + 1,
+            call.tool_name,
+            status,
+            duration(call),
+            call.timestamp,
+            arguments(call),
+            call_output(call)
+        ));
+        for screenshot in screenshots(&call_output(call)) {
+            out.push_str(&format!("\n![screenshot]({})\n", screenshot.display()));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(trajectory: &SessionTrajectory) -> String {
+    let mut body = String::new();
+    for (idx, call) in trajectory.calls.iter().enumerate() {
+        let output = call_output(call);
+        let status = if call.success { "ok" } else { "failed" };
+        body.push_str(&format!(
+            "<details class=\"{status}\"{open}><summary>{n}. <code>{tool}</code> <span>{status} · {duration} · {timestamp}</span></summary>\n\
+             <h4>Arguments</h4><pre>{args}</pre>\n<h4>Result</h4><pre>{output}</pre>\n",
+            status = status,
+            open = if call.success { "" } else { " open" },
+            n = idx + 1,
+            tool = escape_html(&call.tool_name),
+            duration = duration(call),
+            timestamp = escape_html(&call.timestamp),
+            args = escape_html(&arguments(call)),
+            output = escape_html(&output),
+        ));
+        for screenshot in screenshots(&output) {
+            // embed so the report stays readable after the workspace is gone
+            if let Ok(bytes) = std::fs::read(&screenshot) {
+                body.push_str(&format!(
+                    "<img alt=\"{}\" src=\"data:image/png;base64,{}\">\n",
+                    escape_html(&screenshot.display().to_string()),
+                    base64::engine::general_purpose::STANDARD.encode(bytes)
+                ));
+            }
+        }
+        body.push_str("</details>\n");
+    }
+
+    let started = trajectory
+        .starts
+        .first()
+        .map(|start| format!("Started {} · ", escape_html(&start.timestamp)))
+        .unwrap_or_default();
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Edda session {id}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 1100px; margin: 2em auto; padding: 0 1em; color: #222; }}
+details {{ border: 1px solid #ddd; border-left: 4px solid #2da44e; border-radius: 4px; margin: .5em 0; padding: .4em .8em; }}
+details.failed {{ border-left-color: #cf222e; }}
+summary {{ cursor: pointer; }}
+summary span {{ color: #666; font-size: .9em; margin-left: .5em; }}
+pre {{ background: #f6f8fa; padding: .6em; overflow-x: auto; white-space: pre-wrap; }}
+img {{ max-width: 100%; border: 1px solid #ddd; }}
+</style>
+</head>
+<body>
+<h1>Edda session <code>{id}</code></h1>
+<p>{started}{summary}</p>
+{body}</body>
+</html>
+"#,
+        id = escape_html(&trajectory.session_id),
+        started = started,
+        summary = summary_line(trajectory),
+        body = body,
+    )
+}
+
+/// Render a session and write it to `output` (default: ./edda-<session prefix>.<ext>).
+pub fn export(
+    history_path: &Path,
+    session: &str,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let trajectory = load_session(history_path, session)?;
+    let contents = match format {
+        ExportFormat::Html => render_html(&trajectory),
+        ExportFormat::Markdown => render_markdown(&trajectory),
+    };
+    let output = output.unwrap_or_else(|| {
+        let short = trajectory
+            .session_id
+            .get(..8)
+            .unwrap_or(&trajectory.session_id);
+        PathBuf::from(format!("edda-{}.{}", short, format.extension()))
+    });
+    std::fs::write(&output, contents)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn call(session_id: &str, tool_name: &str, success: bool) -> HistoryEntry {
+        HistoryEntry::Tool(TrajectoryEntry {
+            session_id: session_id.to_string(),
+            timestamp: "2025-10-29T10:00:00Z".to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: Some(serde_json::json!({"work_dir": "/tmp/app"})),
+            success,
+            result: Some(serde_json::json!({
+                "content": [{"type": "text", "text": "<done>"}],
+                "isError": !success
+            })),
+            error: None,
+            duration_ms: Some(1500),
+        })
+    }
+
+    #[test]
+    fn test_export_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let history_path = dir.path().join("history.jsonl");
+        let mut file = std::fs::File::create(&history_path).unwrap();
+        for entry in [
+            call("aaaa1111-x", "scaffold_data_app", true),
+            call("bbbb2222-y", "other_session_tool", true),
+            call("aaaa1111-x", "validate_data_app", false),
+        ] {
+            writeln!(file, "{}", serde_json::to_string(&entry).unwrap()).unwrap();
+        }
+
+        let trajectory = load_session(&history_path, "aaaa1111").unwrap();
+        assert_eq!(trajectory.session_id, "aaaa1111-x");
+        assert_eq!(trajectory.calls.len(), 2);
+        assert!(load_session(&history_path, "cccc").is_err());
+
+        let html = render_html(&trajectory);
+        assert!(html.contains("&lt;done&gt;"));
+        assert!(html.contains("2 tool calls, 1 failed, 3.0s in tools"));
+
+        let markdown = render_markdown(&trajectory);
+        assert!(markdown.contains("## 2. `validate_data_app` (failed, 1.5s)"));
+    }
+}
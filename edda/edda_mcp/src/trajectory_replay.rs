@@ -0,0 +1,289 @@
+//! Re-execute a recorded session against the current binary to catch regressions in
+//! scaffolding and validation behavior.
+
+use crate::trajectory::TrajectoryEntry;
+use crate::trajectory_export::{SessionTrajectory, load_session};
+use eyre::Result;
+use rmcp::ServiceExt;
+use rmcp::model::CallToolRequestParam;
+use rmcp::transport::TokioChildProcess;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Tools that only read or only change the (remapped) work directory. Anything else may
+/// touch a workspace, the host or a paid service and is skipped unless explicitly allowed.
+const REPLAYABLE_TOOLS: &[&str] = &[
+    "get_started",
+    "scaffold_data_app",
+    "validate_data_app",
+    "add_feature",
+    "upgrade_project",
+    "format_data_app",
+    "lint_data_app",
+    "list_projects",
+    "read_file",
+    "write_file",
+    "edit_file",
+    "search_replace",
+    "glob",
+    "grep",
+    "databricks_list_catalogs",
+    "databricks_list_schemas",
+    "databricks_find_tables",
+    "databricks_describe_table",
+    "databricks_list_warehouses",
+    "databricks_list_dashboards",
+    "databricks_list_serving_endpoints",
+    "databricks_list_volume_files",
+    "list_databricks_apps",
+    "list_app_deployments",
+    "get_deployment_logs",
+    "wait_for_deployment",
+    "google_sheets_get_metadata",
+    "google_sheets_read_range",
+    "google_sheets_fetch_full",
+    "object_store_list_buckets",
+    "object_store_list",
+    "object_store_head",
+    "object_store_get",
+    "postgres_list_schemas",
+    "postgres_list_tables",
+    "postgres_describe_table",
+];
+
+pub struct ReplayOptions {
+    /// History file (~/.edda/history.jsonl or a copy of it)
+    pub file: PathBuf,
+    /// Session id or prefix; may be omitted when the file holds a single session
+    pub session: Option<String>,
+    /// Only check that every recorded tool still exists, don't call anything
+    pub dry_run: bool,
+    /// Directory recorded work directories are remapped into (default: a fresh temp dir)
+    pub work_dir: Option<PathBuf>,
+    pub allow_side_effects: bool,
+}
+
+#[derive(Debug)]
+pub enum ReplayStatus {
+    /// Same outcome as recorded
+    Matched,
+    /// Outcome differs from the recording
+    Regressed {
+        replayed_success: bool,
+    },
+    Skipped(String),
+}
+
+#[derive(Debug)]
+pub struct ReplayOutcome {
+    pub tool_name: String,
+    pub recorded_success: bool,
+    pub status: ReplayStatus,
+    pub duration_ms: Option<u64>,
+}
+
+impl ReplayOutcome {
+    pub fn display(&self, idx: usize) -> String {
+        let recorded = if self.recorded_success {
+            "ok"
+        } else {
+            "failed"
+        };
+        match &self.status {
+            ReplayStatus::Matched => format!(
+                "✓ {}. {} ({}, {}ms)",
+                idx,
+                self.tool_name,
+                recorded,
+                self.duration_ms.unwrap_or_default()
+            ),
+            ReplayStatus::Regressed { replayed_success } => format!(
+                "✗ {}. {} (recorded {}, replay {})",
+                idx,
+                self.tool_name,
+                recorded,
+                if *replayed_success { "ok" } else { "failed" }
+            ),
+            ReplayStatus::Skipped(reason) => {
+                format!("- {}. {} (skipped: {})", idx, self.tool_name, reason)
+            }
+        }
+    }
+}
+
+/// Map every recorded `work_dir` to a fresh directory under `base`, so replaying never
+/// touches the original projects.
+fn remap_work_dirs(calls: &[TrajectoryEntry], base: &Path) -> BTreeMap<String, String> {
+    let work_dirs: BTreeSet<String> = calls
+        .iter()
+        .filter_map(|call| call.arguments.as_ref()?.get("work_dir")?.as_str())
+        .map(str::to_string)
+        .collect();
+    work_dirs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, dir)| {
+            let name = Path::new(&dir)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "app".to_string());
+            let target = base.join(format!("{}-{}", idx, name));
+            (dir, target.display().to_string())
+        })
+        .collect()
+}
+
+fn rewrite_paths(value: &mut serde_json::Value, mapping: &BTreeMap<String, String>) {
+    match value {
+        serde_json::Value::String(s) => {
+            // longest prefix first so nested work dirs map correctly
+            if let Some((from, to)) = mapping
+                .iter()
+                .filter(|(from, _)| s.starts_with(from.as_str()))
+                .max_by_key(|(from, _)| from.len())
+            {
+                *s = format!("{}{}", to, &s[from.len()..]);
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| rewrite_paths(v, mapping)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| rewrite_paths(v, mapping)),
+        _ => {}
+    }
+}
+
+/// Spawn the current binary as an MCP server with the session's recorded config.
+async fn spawn_server(
+    trajectory: &SessionTrajectory,
+) -> Result<rmcp::service::RunningService<rmcp::RoleClient, ()>> {
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    if let Some(start) = trajectory.starts.last() {
+        command
+            .arg("--json")
+            .arg(serde_json::to_string(&start.config)?);
+    }
+    let (transport, _) = TokioChildProcess::builder(command)
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(().serve(transport).await?)
+}
+
+pub async fn replay(options: &ReplayOptions) -> Result<Vec<ReplayOutcome>> {
+    let trajectory = load_session(&options.file, options.session.as_deref().unwrap_or(""))
+        .map_err(|e| eyre::eyre!("{} (pass --session to pick one)", e))?;
+
+    let base = match &options.work_dir {
+        Some(dir) => dir.clone(),
+        None => std::env::temp_dir().join(format!("edda-replay-{}", uuid::Uuid::new_v4())),
+    };
+    let mapping = remap_work_dirs(&trajectory.calls, &base);
+
+    let service = spawn_server(&trajectory).await?;
+    let available: BTreeSet<String> = service
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+
+    let mut outcomes = Vec::new();
+    for call in &trajectory.calls {
+        let skip_reason = if !available.contains(&call.tool_name) {
+            // a missing tool is a regression even in dry-run mode
+            outcomes.push(ReplayOutcome {
+                tool_name: call.tool_name.clone(),
+                recorded_success: call.success,
+                status: ReplayStatus::Regressed {
+                    replayed_success: false,
+                },
+                duration_ms: None,
+            });
+            continue;
+        } else if options.dry_run {
+            Some("dry run".to_string())
+        } else if !options.allow_side_effects
+            && !REPLAYABLE_TOOLS.contains(&call.tool_name.as_str())
+        {
+            Some("may have side effects, pass --allow-side-effects".to_string())
+        } else {
+            None
+        };
+        if let Some(reason) = skip_reason {
+            outcomes.push(ReplayOutcome {
+                tool_name: call.tool_name.clone(),
+                recorded_success: call.success,
+                status: ReplayStatus::Skipped(reason),
+                duration_ms: None,
+            });
+            continue;
+        }
+
+        let mut arguments = call.arguments.clone();
+        if let Some(arguments) = arguments.as_mut() {
+            rewrite_paths(arguments, &mapping);
+        }
+        let start = std::time::Instant::now();
+        let result = service
+            .call_tool(CallToolRequestParam {
+                name: call.tool_name.clone().into(),
+                arguments: arguments.and_then(|a| a.as_object().cloned()),
+            })
+            .await;
+        let replayed_success = matches!(&result, Ok(r) if !r.is_error.unwrap_or(false));
+        outcomes.push(ReplayOutcome {
+            tool_name: call.tool_name.clone(),
+            recorded_success: call.success,
+            status: match replayed_success == call.success {
+                true => ReplayStatus::Matched,
+                false => ReplayStatus::Regressed { replayed_success },
+            },
+            duration_ms: Some(start.elapsed().as_millis() as u64),
+        });
+    }
+
+    service.cancel().await?;
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_paths() {
+        let calls = vec![TrajectoryEntry {
+            session_id: "s".to_string(),
+            timestamp: String::new(),
+            tool_name: "scaffold_data_app".to_string(),
+            arguments: Some(serde_json::json!({"work_dir": "/home/me/sales"})),
+            success: true,
+            result: None,
+            error: None,
+            duration_ms: None,
+        }];
+        let mapping = remap_work_dirs(&calls, Path::new("/tmp/replay"));
+        let mut args = serde_json::json!({
+            "work_dir": "/home/me/sales",
+            "files": ["/home/me/sales/server/index.ts", "/home/me/other"]
+        });
+        rewrite_paths(&mut args, &mapping);
+        assert_eq!(
+            args,
+            serde_json::json!({
+                "work_dir": "/tmp/replay/0-sales",
+                "files": ["/tmp/replay/0-sales/server/index.ts", "/home/me/other"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_replayable_tools_exist() {
+        for tool in REPLAYABLE_TOOLS.iter().filter(|tool| **tool != "get_started") {
+            assert!(
+                crate::providers::ProviderType::for_tool(tool).is_some(),
+                "{} isn't a tool of any provider",
+                tool
+            );
+        }
+    }
+}
@@ -1,16 +1,19 @@
 use chrono::Utc;
-use dialoguer::Input;
+use dialoguer::{Confirm, Input};
 use eyre::{Context, Result};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use serde::Serialize;
 use sha2::{Sha256, Digest};
 use std::fs::{self, File};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use tar::Builder;
 
 use crate::paths;
+use crate::redaction::Redactor;
+use crate::trajectory::HistoryEntry;
+use crate::trajectory_export::{self, SessionTrajectory};
 
 #[derive(Serialize)]
 struct Metadata {
@@ -21,12 +24,216 @@ struct Metadata {
     binary_checksum: String,
 }
 
-pub fn run_yell(message: Option<String>) -> Result<()> {
+/// Entries listed in the attached file tree, enough to see the project layout
+const MAX_TREE_ENTRIES: usize = 5000;
+
+/// How `yell --attach` picks and attaches session state.
+pub struct AttachOptions {
+    /// Session id or prefix (default: the most recent session in the history)
+    pub session: Option<String>,
+    /// Lines from the end of the session log to include
+    pub log_lines: usize,
+    /// Attach without asking for confirmation
+    pub yes: bool,
+}
+
+/// Session state bundled into a bug report: the session's trajectory, the tail of its
+/// log, the last validation output and the project file tree. Text is redacted first,
+/// along with the full history and session logs of the bundle.
+pub struct SessionAttachment {
+    /// Session id or a unique prefix of it
+    pub session_id: String,
+    pub log_lines: usize,
+    pub redactor: Redactor,
+}
+
+/// Create a bug report bundle and return its path.
+pub fn run_yell(message: Option<String>, attach: Option<AttachOptions>) -> Result<PathBuf> {
     let trajectory_path = paths::trajectory_path()?;
     let session_log_dir = paths::session_log_dir();
     let output_dir = std::env::temp_dir();
 
-    run_yell_with_paths(message, &trajectory_path, &session_log_dir, &output_dir)
+    let attachment = match attach {
+        Some(options) => confirm_attachment(&trajectory_path, options)?,
+        None => None,
+    };
+
+    run_yell_with_attachment(
+        message,
+        &trajectory_path,
+        &session_log_dir,
+        &output_dir,
+        attachment.as_ref(),
+    )
+}
+
+/// Id of the last session recorded in the history file.
+fn latest_session_id(trajectory_path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(trajectory_path).ok()?;
+    contents.lines().rev().find_map(|line| {
+        match serde_json::from_str::<HistoryEntry>(line).ok()? {
+            HistoryEntry::Session(metadata) => Some(metadata.session_id),
+            HistoryEntry::Tool(call) => Some(call.session_id),
+        }
+    })
+}
+
+/// The most recent project directory a session's tools worked in.
+fn session_work_dir(trajectory: &SessionTrajectory) -> Option<PathBuf> {
+    trajectory
+        .calls
+        .iter()
+        .rev()
+        .filter_map(|call| call.arguments.as_ref()?.get("work_dir")?.as_str())
+        .map(PathBuf::from)
+        .find(|dir| dir.is_dir())
+}
+
+/// Show what would be attached and ask before including it.
+fn confirm_attachment(
+    trajectory_path: &Path,
+    options: AttachOptions,
+) -> Result<Option<SessionAttachment>> {
+    let Some(session_id) = options
+        .session
+        .or_else(|| latest_session_id(trajectory_path))
+    else {
+        eprintln!("⚠️  No recorded session found, nothing to attach");
+        return Ok(None);
+    };
+    let trajectory = trajectory_export::load_session(trajectory_path, &session_id)?;
+
+    println!("Session {} will be attached:", trajectory.session_id);
+    println!("  - {} tool calls with arguments and results", trajectory.calls.len());
+    println!("  - last {} lines of the session log", options.log_lines);
+    if let Some(work_dir) = session_work_dir(&trajectory) {
+        println!("  - validation output and file tree of {}", work_dir.display());
+    }
+    println!("  Tokens, keys and passwords are redacted from the whole bundle.");
+    let confirmed = options.yes
+        || Confirm::new()
+            .with_prompt("Attach session state to the report?")
+            .default(true)
+            .interact()
+            .wrap_err("failed to read input")?;
+    if !confirmed {
+        return Ok(None);
+    }
+
+    let redaction = crate::config::Config::load_from_dir()
+        .ok()
+        .and_then(|config| config.redaction);
+    Ok(Some(SessionAttachment {
+        session_id: trajectory.session_id,
+        log_lines: options.log_lines,
+        redactor: Redactor::new(redaction.as_ref())?,
+    }))
+}
+
+/// Write the session attachment into `dir` (the `session/` directory of the bundle).
+fn write_session_attachment(
+    dir: &Path,
+    trajectory_path: &Path,
+    session_log_dir: &Path,
+    attachment: &SessionAttachment,
+) -> Result<()> {
+    let redactor = &attachment.redactor;
+    let trajectory = trajectory_export::load_session(trajectory_path, &attachment.session_id)?;
+    fs::create_dir_all(dir).wrap_err("failed to create session directory")?;
+
+    // the validation output comes from the last validate call, find it before the
+    // entries are consumed below
+    let work_dir = session_work_dir(&trajectory);
+    let validation = trajectory
+        .calls
+        .iter()
+        .rev()
+        .find(|call| call.tool_name == "validate_data_app")
+        .map(|call| {
+            let status = if call.success { "passed" } else { "failed" };
+            format!(
+                "validate_data_app {} at {}\n\n{}",
+                status,
+                call.timestamp,
+                trajectory_export::call_output(call)
+            )
+        });
+
+    let short_id = trajectory
+        .session_id
+        .get(..8)
+        .unwrap_or(&trajectory.session_id)
+        .to_string();
+    let mut entries = Vec::new();
+    let starts = trajectory.starts.into_iter().map(HistoryEntry::Session);
+    let calls = trajectory.calls.into_iter().map(HistoryEntry::Tool);
+    for entry in starts.chain(calls) {
+        let mut value = serde_json::to_value(&entry).wrap_err("failed to serialize entry")?;
+        redactor.redact_value(&mut value);
+        entries.push(value.to_string());
+    }
+    fs::write(dir.join("trajectory.jsonl"), entries.join("\n"))
+        .wrap_err("failed to write session trajectory")?;
+
+    let log_path = session_log_dir.join(format!("session-{}.log", short_id));
+    if let Ok(log) = fs::read_to_string(&log_path) {
+        let lines: Vec<&str> = log.lines().collect();
+        let tail = lines[lines.len().saturating_sub(attachment.log_lines)..].join("\n");
+        fs::write(dir.join("log_tail.log"), redactor.redact_str(&tail))
+            .wrap_err("failed to write log tail")?;
+    }
+
+    let Some(work_dir) = work_dir else {
+        return Ok(());
+    };
+    let mut validation = validation.unwrap_or_else(|| "no validation recorded".to_string());
+    if let Ok(Some(state)) = crate::state::load_state(&work_dir) {
+        validation.push_str(&format!(
+            "\n\nProject state:\n{}",
+            serde_json::to_string_pretty(&state).unwrap_or_default()
+        ));
+    }
+    fs::write(dir.join("validation.txt"), redactor.redact_str(&validation))
+        .wrap_err("failed to write validation output")?;
+
+    // file names only, contents stay on the user's machine
+    let mut tree: Vec<String> = ignore::WalkBuilder::new(&work_dir)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.depth() > 0)
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(&work_dir).ok()?.display().to_string();
+            match entry.file_type().is_some_and(|t| t.is_dir()) {
+                true => Some(format!("{}/", relative)),
+                false => Some(relative),
+            }
+        })
+        .take(MAX_TREE_ENTRIES)
+        .collect();
+    tree.sort();
+    fs::write(
+        dir.join("file_tree.txt"),
+        format!("{}\n\n{}", work_dir.display(), tree.join("\n")),
+    )
+    .wrap_err("failed to write file tree")?;
+    Ok(())
+}
+
+/// Copy a file into the bundle, redacted when session state is attached.
+fn copy_into_bundle(src: &Path, dest: &Path, redactor: Option<&Redactor>) -> Result<()> {
+    match redactor {
+        Some(redactor) => {
+            let contents = fs::read_to_string(src)?;
+            fs::write(dest, redactor.redact_str(&contents))?;
+        }
+        None => {
+            fs::copy(src, dest)?;
+        }
+    }
+    Ok(())
 }
 
 pub fn run_yell_with_paths(
@@ -34,7 +241,17 @@ pub fn run_yell_with_paths(
     trajectory_path: &Path,
     session_log_dir: &Path,
     output_dir: &Path,
-) -> Result<()> {
+) -> Result<PathBuf> {
+    run_yell_with_attachment(message, trajectory_path, session_log_dir, output_dir, None)
+}
+
+pub fn run_yell_with_attachment(
+    message: Option<String>,
+    trajectory_path: &Path,
+    session_log_dir: &Path,
+    output_dir: &Path,
+    attachment: Option<&SessionAttachment>,
+) -> Result<PathBuf> {
     // get bug description
     let description = match message {
         Some(msg) => msg,
@@ -51,11 +268,13 @@ pub fn run_yell_with_paths(
     let bundle_name = format!("bug-report-{}", timestamp);
     let temp_dir = output_dir.join(&bundle_name);
     fs::create_dir_all(&temp_dir).wrap_err("failed to create temp directory")?;
+    let redactor = attachment.map(|attachment| &attachment.redactor);
 
     // collect trajectories
     if trajectory_path.exists() {
         let dest = temp_dir.join("history.jsonl");
-        fs::copy(trajectory_path, &dest).wrap_err("failed to copy trajectory file")?;
+        copy_into_bundle(trajectory_path, &dest, redactor)
+            .wrap_err("failed to copy trajectory file")?;
     } else {
         eprintln!("⚠️  Warning: trajectory file not found at {:?}", trajectory_path);
     }
@@ -77,7 +296,7 @@ pub fn run_yell_with_paths(
                         if modified >= cutoff {
                             let file_name = path.file_name().unwrap().to_string_lossy().to_string();
                             let dest = logs_dir.join(&file_name);
-                            fs::copy(&path, &dest).wrap_err_with(|| format!("failed to copy log file: {:?}", path))?;
+                            copy_into_bundle(&path, &dest, redactor).wrap_err_with(|| format!("failed to copy log file: {:?}", path))?;
                         }
                     }
                 }
@@ -87,6 +306,16 @@ pub fn run_yell_with_paths(
         eprintln!("⚠️  Warning: session log directory not found at {:?}", session_log_dir);
     }
 
+    // attach session state when requested
+    if let Some(attachment) = attachment {
+        write_session_attachment(
+            &temp_dir.join("session"),
+            trajectory_path,
+            session_log_dir,
+            attachment,
+        )?;
+    }
+
     // write description
     let description_path = temp_dir.join("description.txt");
     fs::write(&description_path, &description).wrap_err("failed to write description file")?;
@@ -133,5 +362,5 @@ pub fn run_yell_with_paths(
     println!("\n✅ Bug report created: {}", bundle_path.display());
     println!("📤 Please send this file to the devs via Slack or eng-appbuild@databricks.com\n");
 
-    Ok(())
+    Ok(bundle_path)
 }
@@ -0,0 +1,80 @@
+//! Config hot reload: editing the config file rebuilds the providers of a running server
+//! and the client is told to refetch the tool list.
+
+use edda_mcp::config::Config;
+use edda_mcp::providers::CombinedProvider;
+use edda_mcp::reload::{ReloadableProvider, watch_config};
+use edda_mcp::session::SessionContext;
+use eyre::Result;
+use rmcp::service::{NotificationContext, RoleClient};
+use rmcp::{ClientHandler, ServiceExt};
+use rmcp_in_process_transport::in_process::TokioInProcess;
+use std::fs;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+
+/// Client forwarding tools/list_changed notifications to the test
+struct ListChangedClient(mpsc::UnboundedSender<()>);
+
+impl ClientHandler for ListChangedClient {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        let _ = self.0.send(());
+    }
+}
+
+fn load(path: &std::path::Path) -> Result<Config> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[tokio::test]
+async fn test_config_change_reloads_providers() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let config_path = temp_dir.path().join("config.json");
+    fs::write(
+        &config_path,
+        r#"{"with_deployment": false, "required_providers": ["Io"]}"#,
+    )?;
+
+    let config = load(&config_path)?;
+    let provider = CombinedProvider::from_config(SessionContext::new(None), &config).await?;
+    let path = config_path.clone();
+    let updates = watch_config(config_path.clone(), move || load(&path));
+
+    let (sender, mut changed) = mpsc::unbounded_channel();
+    let server = TokioInProcess::new(ReloadableProvider::new(provider, updates)).await?;
+    let service = ListChangedClient(sender).serve(server).await?;
+
+    let tools = service.list_tools(Default::default()).await?.tools;
+    assert!(tools.iter().all(|t| t.name != "read_file"));
+
+    fs::write(
+        &config_path,
+        r#"{"with_deployment": false, "with_workspace_tools": true, "required_providers": ["Io"]}"#,
+    )?;
+    // filesystems with coarse timestamps could otherwise hide the change
+    let later = SystemTime::now() + Duration::from_secs(10);
+    filetime::set_file_mtime(&config_path, filetime::FileTime::from_system_time(later))?;
+
+    tokio::time::timeout(Duration::from_secs(10), changed.recv()).await?;
+    let tools = service.list_tools(Default::default()).await?.tools;
+    assert!(tools.iter().any(|t| t.name == "read_file"));
+    assert!(tools.iter().any(|t| t.name == "scaffold_data_app"));
+
+    service.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_config_keeps_its_error() -> Result<()> {
+    let config: Config = serde_json::from_str(
+        r#"{"with_deployment": false, "required_providers": ["Io"], "redaction": {"patterns": ["(unclosed"]}}"#,
+    )?;
+    let err = match CombinedProvider::from_config(SessionContext::new(None), &config).await {
+        Ok(_) => panic!("invalid redaction pattern was accepted"),
+        Err(err) => format!("{err:#}"),
+    };
+    assert!(!err.contains("No integrations available"), "{err}");
+    assert!(err.contains("unclosed"), "{err}");
+    Ok(())
+}
@@ -1,5 +1,11 @@
 use edda_mcp::providers::IOProvider;
-use edda_templates::TemplateTRPC;
+use edda_mcp::providers::io::validation::for_project;
+use edda_templates::capabilities::ValidationSpec;
+use edda_templates::{
+    Hook, HookTarget, LocalTemplate, TemplateCache, TemplateCapabilities, TemplateCore,
+    TemplatePyFastAPI, TemplatePyStreamlit, TemplateRustAxum, TemplateSource, TemplateTRPC,
+    TemplateVars,
+};
 use std::fs;
 use std::path::Path;
 use tempfile::TempDir;
@@ -33,7 +39,9 @@ fn test_optimistic() {
     let temp_dir = TempDir::new().unwrap();
     let work_dir = temp_dir.path().join("optimistic_test");
 
-    let result = IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, false).unwrap();
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), false)
+            .unwrap();
 
     // verify result
     assert!(result.files_copied > 0);
@@ -50,7 +58,8 @@ fn test_force_rewrite() {
     let work_dir = temp_dir.path().join("force_rewrite_test");
 
     // initial copy
-    IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, false).unwrap();
+    IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), false)
+        .unwrap();
 
     // read original .gitignore content
     let gitignore_path = work_dir.join(".gitignore");
@@ -62,7 +71,9 @@ fn test_force_rewrite() {
     assert!(work_dir.join("extra_file.txt").exists());
 
     // force rewrite
-    let result = IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, true).unwrap();
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), true)
+            .unwrap();
 
     // verify result
     assert!(result.files_copied > 0);
@@ -88,7 +99,9 @@ fn test_force_rewrite_on_missing_directory() {
     let temp_dir = TempDir::new().unwrap();
     let work_dir = temp_dir.path().join("missing_force_rewrite");
 
-    let result = IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, true).unwrap();
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), true)
+            .unwrap();
 
     assert!(result.files_copied > 0);
     verify_template_files(&work_dir);
@@ -110,7 +123,8 @@ fn test_pessimistic_no_write_access() {
         fs::set_permissions(&work_dir, perms).unwrap();
     }
 
-    let result = IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, false);
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), false);
 
     // should fail with permission error
     assert!(result.is_err(), "should fail due to permission denied");
@@ -124,3 +138,371 @@ fn test_pessimistic_no_write_access() {
         fs::set_permissions(&work_dir, perms).unwrap();
     }
 }
+
+#[test]
+fn test_add_feature_keeps_existing_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path().join("add_feature_test");
+    IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &TemplateVars::default(), false)
+        .unwrap();
+
+    let report = edda_templates::merge::merge_feature(&work_dir, "auth", false).unwrap();
+    assert!(report.added.contains(&work_dir.join("server/src/auth.ts")));
+    assert!(!work_dir.join("FEATURE.md").exists());
+
+    // a second merge must not clobber edits made in between
+    fs::write(work_dir.join("server/src/auth.ts"), "// edited").unwrap();
+    let report = edda_templates::merge::merge_feature(&work_dir, "auth", false).unwrap();
+    assert!(report.added.is_empty());
+    assert_eq!(report.conflicts, vec![work_dir.join("server/src/auth.ts")]);
+    assert_eq!(
+        fs::read_to_string(work_dir.join("server/src/auth.ts")).unwrap(),
+        "// edited"
+    );
+
+    assert!(edda_templates::merge::merge_feature(&work_dir, "unknown", false).is_err());
+}
+
+#[test]
+fn test_git_template_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(repo.join("server")).unwrap();
+    fs::write(repo.join("CLAUDE.md"), "Team template").unwrap();
+    fs::write(repo.join("server/index.ts"), "v1").unwrap();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "--quiet"]);
+    git(&["add", "-A"]);
+    git(&["commit", "--quiet", "-m", "v1"]);
+    git(&["tag", "v1"]);
+    fs::write(repo.join("server/index.ts"), "v2").unwrap();
+    git(&["commit", "--quiet", "-am", "v2"]);
+
+    let source: TemplateSource = format!("git+file://{}#v1", repo.display()).parse().unwrap();
+    let cache = TemplateCache::new(temp_dir.path().join("cache"));
+    let template_dir = cache.fetch(&source, false).unwrap();
+    assert!(!template_dir.join(".git").exists());
+
+    let template = LocalTemplate::from_dir("team", &template_dir).unwrap();
+    let work_dir = temp_dir.path().join("git_template_test");
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, template, &TemplateVars::default(), false)
+            .unwrap();
+    assert_eq!(result.template_name, "team");
+    assert_eq!(result.template_description, "Team template");
+    assert_eq!(
+        fs::read_to_string(work_dir.join("server/index.ts")).unwrap(),
+        "v1"
+    );
+
+    // cached, a refresh is needed to see a moved rev
+    git(&["tag", "--force", "v1"]);
+    assert_eq!(cache.fetch(&source, false).unwrap(), template_dir);
+    assert_eq!(
+        fs::read_to_string(template_dir.join("server/index.ts")).unwrap(),
+        "v1"
+    );
+    assert_eq!(cache.fetch(&source, true).unwrap(), template_dir);
+    assert_eq!(
+        fs::read_to_string(template_dir.join("server/index.ts")).unwrap(),
+        "v2"
+    );
+}
+
+#[test]
+fn test_template_vars() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path().join("sales_dashboard");
+    let vars = TemplateVars {
+        description: Some("Weekly sales by \"region\"".to_string()),
+        databricks_catalog: Some("retail".to_string()),
+        port: Some(3000),
+        ..Default::default()
+    };
+    IOProvider::initiate_project_impl(&work_dir, TemplateTRPC, &vars, false).unwrap();
+
+    let package = fs::read_to_string(work_dir.join("package.json")).unwrap();
+    let package: serde_json::Value = serde_json::from_str(&package).unwrap();
+    assert_eq!(package["name"], "sales-dashboard");
+    assert_eq!(package["description"], "Weekly sales by region");
+
+    let dockerfile = fs::read_to_string(work_dir.join("Dockerfile")).unwrap();
+    assert!(dockerfile.contains("EXPOSE 3000"));
+    let app_yaml = fs::read_to_string(work_dir.join("server/app.yaml")).unwrap();
+    assert!(app_yaml.contains("value: \"retail\""));
+    assert!(app_yaml.contains("value: \"default\""));
+
+    // no placeholder is left in the scaffold
+    for file in ["client/index.html", "server/src/databricks.ts"] {
+        let content = fs::read_to_string(work_dir.join(file)).unwrap();
+        assert!(!content.contains("{{"), "{} has placeholders left", file);
+    }
+    let recorded = edda_templates::merge::base_vars(&work_dir)
+        .unwrap()
+        .unwrap();
+    assert_eq!(recorded.port, Some(3000));
+
+    let vars = TemplateVars {
+        databricks_schema: Some("sales; DROP TABLE x".to_string()),
+        ..Default::default()
+    };
+    let other_dir = temp_dir.path().join("other");
+    assert!(IOProvider::initiate_project_impl(&other_dir, TemplateTRPC, &vars, false).is_err());
+    assert!(!other_dir.exists(), "nothing is written for invalid vars");
+}
+
+#[test]
+fn test_upgrade_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let template_dir = temp_dir.path().join("template");
+    fs::create_dir_all(template_dir.join("server")).unwrap();
+    fs::write(
+        template_dir.join("package.json"),
+        r#"{"name": "{{project_name}}"}"#,
+    )
+    .unwrap();
+    fs::write(
+        template_dir.join("server/index.ts"),
+        "one\ntwo\nthree\nfour\n",
+    )
+    .unwrap();
+    fs::write(template_dir.join("server/db.ts"), "pool()\n").unwrap();
+    fs::write(template_dir.join("server/legacy.ts"), "old\n").unwrap();
+
+    let work_dir = temp_dir.path().join("upgrade_test");
+    let template = LocalTemplate::from_dir("team", &template_dir).unwrap();
+    IOProvider::initiate_project_impl(&work_dir, template, &TemplateVars::default(), false)
+        .unwrap();
+
+    // the agent edits the project while the template moves on
+    fs::write(work_dir.join("server/index.ts"), "ONE\ntwo\nthree\nfour\n").unwrap();
+    fs::write(work_dir.join("server/db.ts"), "pool({ max: 4 })\n").unwrap();
+    fs::write(
+        template_dir.join("server/index.ts"),
+        "one\ntwo\nthree\nFOUR\n",
+    )
+    .unwrap();
+    fs::write(template_dir.join("server/db.ts"), "createPool()\n").unwrap();
+    fs::write(template_dir.join("server/health.ts"), "ok\n").unwrap();
+    fs::remove_file(template_dir.join("server/legacy.ts")).unwrap();
+
+    let template = LocalTemplate::from_dir("team", &template_dir).unwrap();
+    let report = edda_templates::merge::upgrade_project(&work_dir, &template).unwrap();
+    assert_eq!(report.added, vec![work_dir.join("server/health.ts")]);
+    assert_eq!(report.merged, vec![work_dir.join("server/index.ts")]);
+    assert_eq!(report.removed, vec![work_dir.join("server/legacy.ts")]);
+    assert_eq!(report.conflicts, vec![work_dir.join("server/db.ts")]);
+    assert_eq!(
+        fs::read_to_string(work_dir.join("server/index.ts")).unwrap(),
+        "ONE\ntwo\nthree\nFOUR\n"
+    );
+    let conflicted = fs::read_to_string(work_dir.join("server/db.ts")).unwrap();
+    assert!(conflicted.contains("pool({ max: 4 })") && conflicted.contains("createPool()"));
+    // rendered with the vars of the scaffold, so it isn't a change
+    assert!(!report.updated.contains(&work_dir.join("package.json")));
+
+    // the new template version is the base of the next upgrade
+    let report = edda_templates::merge::upgrade_project(&work_dir, &template).unwrap();
+    assert!(report.added.is_empty() && report.merged.is_empty() && report.conflicts.is_empty());
+
+    // a project only upgrades to the template it was scaffolded from
+    let other = LocalTemplate::from_dir("other", &template_dir).unwrap();
+    let error = edda_templates::merge::upgrade_project(&work_dir, &other).unwrap_err();
+    assert!(error.to_string().contains("'team' template"));
+}
+
+#[test]
+fn test_python_templates() {
+    let temp_dir = TempDir::new().unwrap();
+    let vars = TemplateVars {
+        description: Some("Store inventory".to_string()),
+        port: Some(8501),
+        ..Default::default()
+    };
+
+    let streamlit_dir = temp_dir.path().join("inventory_app");
+    let result =
+        IOProvider::initiate_project_impl(&streamlit_dir, TemplatePyStreamlit, &vars, false)
+            .unwrap();
+    assert_eq!(result.template_name, "Streamlit Python");
+    assert!(result.template_description.contains("AppTest"));
+    for file in [
+        "app.py",
+        "db.py",
+        "requirements.txt",
+        "app.yaml",
+        "tests/test_app.py",
+    ] {
+        assert!(streamlit_dir.join(file).exists(), "{} should exist", file);
+    }
+    let app = fs::read_to_string(streamlit_dir.join("app.py")).unwrap();
+    assert!(app.contains("page_title=\"inventory-app\""));
+    assert!(app.contains("Store inventory"));
+    let dockerfile = fs::read_to_string(streamlit_dir.join("Dockerfile")).unwrap();
+    assert!(dockerfile.contains("--server.port=8501"));
+
+    let fastapi_dir = temp_dir.path().join("inventory_api");
+    let result =
+        IOProvider::initiate_project_impl(&fastapi_dir, TemplatePyFastAPI, &vars, false).unwrap();
+    assert_eq!(result.template_name, "FastAPI Python");
+    for file in [
+        "app/main.py",
+        "app/db.py",
+        "static/index.html",
+        "tests/test_main.py",
+    ] {
+        assert!(fastapi_dir.join(file).exists(), "{} should exist", file);
+    }
+    let db = fs::read_to_string(fastapi_dir.join("app/db.py")).unwrap();
+    assert!(db.contains("or \"main\""));
+
+    // no placeholder is left in either scaffold
+    for (dir, template) in [
+        (&streamlit_dir, TemplatePyStreamlit.files().unwrap()),
+        (&fastapi_dir, TemplatePyFastAPI.files().unwrap()),
+    ] {
+        for (path, _) in template {
+            let content = fs::read_to_string(dir.join(&path)).unwrap();
+            assert!(!content.contains("{{"), "{} has placeholders left", path);
+        }
+    }
+}
+
+#[test]
+fn test_template_capabilities() {
+    let trpc = TemplateTRPC.capabilities().unwrap();
+    assert_eq!(trpc.validation, ValidationSpec::Trpc);
+    assert_eq!(trpc.required_providers, vec!["DatabricksRest"]);
+    assert_eq!(trpc.sandbox_image(), "node:20-alpine3.22");
+    let streamlit = TemplatePyStreamlit.capabilities().unwrap();
+    assert_eq!(streamlit.validation, ValidationSpec::Python);
+    assert_eq!(streamlit.sandbox_image(), "python:3.12-slim");
+
+    assert!(trpc.image_mismatch("node:20").is_none());
+    assert!(
+        trpc.image_mismatch("docker.io/library/node:20.11-alpine")
+            .is_none()
+    );
+    assert!(trpc.image_mismatch("node:22-alpine").is_some());
+    assert!(trpc.image_mismatch("python:3.12-slim").is_some());
+    // custom images can't be checked
+    assert!(trpc.image_mismatch("ghcr.io/acme/builder:1").is_none());
+
+    // the manifest is scaffolded with the project and picks its validation
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path().join("app");
+    IOProvider::initiate_project_impl(
+        &work_dir,
+        TemplatePyFastAPI,
+        &TemplateVars::default(),
+        false,
+    )
+    .unwrap();
+    let loaded = TemplateCapabilities::load(&work_dir).unwrap().unwrap();
+    assert_eq!(loaded.validation, ValidationSpec::Python);
+    assert_eq!(for_project(&work_dir).docker_image(), "python:3.12-slim");
+    assert!(
+        TemplateCapabilities::load(temp_dir.path())
+            .unwrap()
+            .is_none()
+    );
+
+    let provider = IOProvider::new(None).unwrap();
+    let capabilities = provider.template_capabilities();
+    assert_eq!(capabilities.len(), 1);
+    assert_eq!(capabilities[0].0, "default");
+    assert_eq!(capabilities[0].1.as_ref().unwrap(), &trpc);
+}
+
+#[test]
+fn test_rust_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path().join("orders_service");
+    let vars = TemplateVars {
+        databricks_catalog: Some("retail".to_string()),
+        port: Some(3000),
+        ..Default::default()
+    };
+    let result =
+        IOProvider::initiate_project_impl(&work_dir, TemplateRustAxum, &vars, false).unwrap();
+    assert_eq!(result.template_name, "Axum Rust");
+
+    let cargo_toml = fs::read_to_string(work_dir.join("Cargo.toml")).unwrap();
+    assert!(cargo_toml.contains("name = \"orders-service\""));
+    let config = fs::read_to_string(work_dir.join("src/config.rs")).unwrap();
+    assert!(config.contains(".unwrap_or(3000)"));
+    assert!(config.contains("\"retail\""));
+    let dockerfile = fs::read_to_string(work_dir.join("Dockerfile")).unwrap();
+    assert!(dockerfile.contains("target/release/orders-service"));
+
+    let capabilities = TemplateRustAxum.capabilities().unwrap();
+    assert_eq!(capabilities.validation, ValidationSpec::Rust);
+    assert_eq!(capabilities.sandbox_image(), "rust:1.85-slim");
+    assert!(capabilities.image_mismatch("node:20").is_some());
+    assert_eq!(for_project(&work_dir).docker_image(), "rust:1.85-slim");
+}
+
+#[tokio::test]
+async fn test_template_hooks() {
+    let axum = TemplateRustAxum.capabilities().unwrap();
+    assert_eq!(axum.hooks.len(), 1);
+    assert_eq!(axum.hooks[0].run_in, HookTarget::Sandbox);
+    assert_eq!(axum.hooks[0].export, vec!["Cargo.lock"]);
+
+    let manifest = br#"{"hooks": [{"command": "cp .env.example .env", "run_in": "host"}]}"#;
+    let parsed = TemplateCapabilities::parse(manifest).unwrap();
+    assert_eq!(parsed.hooks[0].run_in, HookTarget::Host);
+    assert!(parsed.hooks[0].export.is_empty());
+
+    let temp_dir = TempDir::new().unwrap();
+    let hooks = vec![
+        Hook {
+            command: "echo DATABRICKS_HOST= > .env".to_string(),
+            run_in: HookTarget::Host,
+            export: Vec::new(),
+        },
+        Hook {
+            command: "echo missing >&2 && exit 3".to_string(),
+            run_in: HookTarget::Host,
+            export: Vec::new(),
+        },
+    ];
+    let results = IOProvider::run_hooks_impl(temp_dir.path(), &hooks, "node:20-alpine3.22", true)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].exit_code, 0);
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".env")).unwrap(),
+        "DATABRICKS_HOST=\n"
+    );
+    // a failing hook is reported, not raised
+    assert_eq!(results[1].exit_code, 3);
+    assert_eq!(results[1].output.trim(), "missing");
+
+    // host hooks of fetched templates need allow_host_hooks
+    fs::remove_file(temp_dir.path().join(".env")).unwrap();
+    let results =
+        IOProvider::run_hooks_impl(temp_dir.path(), &hooks[..1], "node:20-alpine3.22", false)
+            .await
+            .unwrap();
+    assert_ne!(results[0].exit_code, 0);
+    assert!(results[0].output.contains("allow_host_hooks"));
+    assert!(!temp_dir.path().join(".env").exists());
+
+    for export in ["../outside", "/etc/cron.d/job", "target/../../x"] {
+        let manifest = format!(
+            r#"{{"hooks": [{{"command": "true", "export": ["{}"]}}]}}"#,
+            export
+        );
+        assert!(TemplateCapabilities::parse(manifest.as_bytes()).is_err());
+    }
+}
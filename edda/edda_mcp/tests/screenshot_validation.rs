@@ -1,12 +1,21 @@
+use base64::Engine;
+use edda_mcp::config::{DEFAULT_SANDBOX_EXECUTE_SECS, ScreenshotConfig};
+use edda_mcp::progress::ProgressReporter;
 use edda_mcp::providers::IOProvider;
 use edda_mcp::providers::io::validation::{Validation, ValidationTRPC};
-use edda_mcp::config::ScreenshotConfig;
-use edda_templates::TemplateTRPC;
+use edda_mcp::state;
+use edda_templates::{TemplateTRPC, TemplateVars};
 use std::path::Path;
 use tempfile::TempDir;
 
 fn initiate_project_for_tests(work_dir: &Path, force_rewrite: bool) {
-    IOProvider::initiate_project_impl(work_dir, TemplateTRPC, force_rewrite).unwrap();
+    IOProvider::initiate_project_impl(
+        work_dir,
+        TemplateTRPC,
+        &TemplateVars::default(),
+        force_rewrite,
+    )
+    .unwrap();
 }
 
 #[tokio::test]
@@ -22,14 +31,19 @@ async fn test_screenshot_capture_success() {
     let validation_strategy = ValidationTRPC.boxed();
     let screenshot_config = Some(ScreenshotConfig {
         enabled: Some(true),
-        url: None,
-        port: None,
-        wait_time_ms: None,
+        ..Default::default()
     });
 
-    let result = IOProvider::validate_project_impl(work_dir, validation_strategy, screenshot_config)
-        .await
-        .unwrap();
+    let result = IOProvider::validate_project_impl(
+        work_dir,
+        validation_strategy,
+        screenshot_config,
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
 
     // validation should pass
     assert!(
@@ -78,14 +92,19 @@ async fn test_screenshot_failure_missing_dockerfile() {
     let validation_strategy = ValidationTRPC.boxed();
     let screenshot_config = Some(ScreenshotConfig {
         enabled: Some(true),
-        url: None,
-        port: None,
-        wait_time_ms: None,
+        ..Default::default()
     });
 
-    let result = IOProvider::validate_project_impl(work_dir, validation_strategy, screenshot_config)
-        .await
-        .unwrap();
+    let result = IOProvider::validate_project_impl(
+        work_dir,
+        validation_strategy,
+        screenshot_config,
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
 
     // validation should still pass (screenshot is non-blocking)
     assert!(
@@ -113,3 +132,44 @@ async fn test_screenshot_failure_missing_dockerfile() {
         );
     }
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "dagger"), ignore)]
+async fn test_unaccepted_visual_diff_is_not_cached() {
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path();
+    initiate_project_for_tests(work_dir, false);
+
+    let progress = ProgressReporter::default();
+    let validate = || {
+        IOProvider::validate_project_impl(
+            work_dir,
+            ValidationTRPC.boxed(),
+            Some(ScreenshotConfig {
+                enabled: Some(true),
+                ..Default::default()
+            }),
+            &progress,
+            true,
+            Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+        )
+    };
+    let result = validate().await.unwrap();
+    assert!(result.success);
+    assert!(result.screenshot_path.is_some());
+
+    // a 1x1 baseline differs from any real screenshot
+    let pixel = base64::engine::general_purpose::STANDARD
+        .decode("iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==")
+        .unwrap();
+    std::fs::write(state::baseline_screenshot(work_dir), pixel).unwrap();
+
+    // the change is reported on every call until it's accepted, not only the first
+    for _ in 0..2 {
+        let result = validate().await.unwrap();
+        assert!(result.success);
+        assert!(!result.message.contains("cached"), "{}", result.message);
+        let diff = result.visual_diff.expect("the baseline differs");
+        assert!(!diff.accepted);
+    }
+}
@@ -5,11 +5,19 @@
 //! - Basic MCP protocol operations work (list_tools, call_tool)
 //! - At least one provider is available
 
-use edda_mcp::config::Config;
-use edda_mcp::providers::{CombinedProvider, IOProvider};
+use edda_mcp::config::{Config, EngineGuideConfig, TimeoutsConfig};
+use edda_mcp::providers::{
+    CombinedProvider, IOProvider, PathPolicy, ProviderEntry, WorkspaceTools,
+};
+use edda_mcp::resources::ENGINE_GUIDE_URI;
 use edda_mcp::session::SessionContext;
 use eyre::Result;
-use rmcp::ServiceExt;
+use rmcp::model::{
+    CallToolRequestParam, CallToolResult, Content, ListToolsResult, PaginatedRequestParam,
+    ReadResourceRequestParam, ResourceContents, Tool,
+};
+use rmcp::service::{RequestContext, RoleServer};
+use rmcp::{ErrorData, ServerHandler, ServiceExt};
 use rmcp_in_process_transport::in_process::TokioInProcess;
 
 #[tokio::test]
@@ -20,7 +28,9 @@ async fn smoke_test_mcp_server() -> Result<()> {
     // create provider (no need to try other providers for smoke test)
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider = CombinedProvider::new(session_ctx, None, None, None, None, Some(io), None, &config)?;
+    let provider = CombinedProvider::builder(session_ctx)
+        .io(io)
+        .build(&config)?;
 
     // create in-process service
     let tokio_in_process = TokioInProcess::new(provider).await?;
@@ -64,3 +74,264 @@ async fn smoke_test_mcp_server() -> Result<()> {
 
     Ok(())
 }
+
+/// Provider maintained outside this crate, e.g. in a fork
+#[derive(Clone)]
+struct WarehouseProvider;
+
+impl ServerHandler for WarehouseProvider {
+    async fn list_tools(
+        &self,
+        _params: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, ErrorData> {
+        let schema = serde_json::json!({"type": "object"});
+        Ok(ListToolsResult {
+            tools: vec![Tool::new(
+                "warehouse_query",
+                "Run a warehouse query",
+                schema.as_object().cloned().unwrap_or_default(),
+            )],
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        params: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "handled {}",
+            params.name
+        ))]))
+    }
+}
+
+#[tokio::test]
+async fn smoke_test_registered_provider() -> Result<()> {
+    let io = IOProvider::new(None)?;
+    // the first result would start with the engine guide
+    let config = Config {
+        engine_guide: Some(EngineGuideConfig {
+            disable_injection: true,
+            ..Default::default()
+        }),
+        ..Config::default()
+    };
+    let mut provider = CombinedProvider::builder(SessionContext::new(None))
+        .io(io)
+        .build(&config)?;
+    provider.register(ProviderEntry::new(
+        "Warehouse",
+        "Warehouse",
+        &["warehouse_"],
+        WarehouseProvider,
+    ));
+    assert_eq!(provider.provider_names(), vec!["I/O", "Warehouse"]);
+
+    // a config reload rebuilds the built-in providers only
+    let rebuilt = CombinedProvider::builder(SessionContext::new(None))
+        .io(IOProvider::new(None)?)
+        .build(&config)?;
+    let provider = provider.rebind(&rebuilt);
+    assert_eq!(provider.provider_names(), vec!["I/O", "Warehouse"]);
+
+    let tokio_in_process = TokioInProcess::new(provider).await?;
+    let service = ().serve(tokio_in_process).await?;
+
+    let tools = service.list_tools(Default::default()).await?.tools;
+    assert!(tools.iter().any(|t| t.name == "scaffold_data_app"));
+    assert!(tools.iter().any(|t| t.name == "warehouse_query"));
+
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "warehouse_query".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(
+        result.content[0].as_text().map(|t| t.text.as_str()),
+        Some("handled warehouse_query")
+    );
+
+    service.cancel().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn smoke_test_engine_guide() -> Result<()> {
+    let io = IOProvider::new(None)?;
+    let config = Config {
+        engine_guide: Some(EngineGuideConfig {
+            disable_injection: false,
+            skip_clients: vec!["Cursor".to_string()],
+        }),
+        ..Config::default()
+    };
+    let provider = CombinedProvider::builder(SessionContext::new(None))
+        .io(io)
+        .build(&config)?;
+
+    let tokio_in_process = TokioInProcess::new(provider).await?;
+    let service = ().serve(tokio_in_process).await?;
+
+    let result = service
+        .call_tool(CallToolRequestParam {
+            name: "get_started".into(),
+            arguments: None,
+        })
+        .await?;
+    let guide = result.content[0]
+        .as_text()
+        .map(|t| t.text.clone())
+        .unwrap_or_default();
+    assert!(guide.contains("## Workflow:"));
+
+    let resources = service.list_resources(Default::default()).await?.resources;
+    assert!(resources.iter().any(|r| r.uri == ENGINE_GUIDE_URI));
+    let contents = service
+        .read_resource(ReadResourceRequestParam {
+            uri: ENGINE_GUIDE_URI.to_string(),
+        })
+        .await?
+        .contents;
+    assert!(matches!(
+        &contents[0],
+        ResourceContents::TextResourceContents { text, .. } if *text == guide
+    ));
+
+    let guide_config = config.engine_guide.unwrap_or_default();
+    assert!(guide_config.injects_for(Some("claude-code")));
+    assert!(!guide_config.injects_for(Some("cursor")));
+
+    service.cancel().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn smoke_test_output_mode() -> Result<()> {
+    let work_dir = tempfile::TempDir::new()?;
+    let scaffold = CallToolRequestParam {
+        name: "scaffold_data_app".into(),
+        arguments: serde_json::json!({
+            "work_dir": work_dir.path().join("app").display().to_string(),
+            "skip_hooks": true,
+        })
+        .as_object()
+        .cloned(),
+    };
+
+    for json_output in [false, true] {
+        let config = Config {
+            json_output,
+            engine_guide: Some(EngineGuideConfig {
+                disable_injection: true,
+                ..Default::default()
+            }),
+            ..Config::default()
+        };
+        let provider = CombinedProvider::builder(SessionContext::new(None))
+            .io(IOProvider::new(None)?)
+            .build(&config)?;
+        let service = ().serve(TokioInProcess::new(provider).await?).await?;
+
+        let result = service.call_tool(scaffold.clone()).await?;
+        // the structured result is there in both modes, only JSON mode adds it as content
+        assert!(result.structured_content.is_some());
+        assert_eq!(result.content.len(), 1 + json_output as usize);
+
+        // tools without a structured result get their text wrapped
+        let result = service
+            .call_tool(CallToolRequestParam {
+                name: "get_started".into(),
+                arguments: None,
+            })
+            .await?;
+        assert_eq!(result.structured_content.is_some(), json_output);
+        if json_output {
+            let text = result.structured_content.unwrap()["text"].clone();
+            assert!(text.as_str().is_some_and(|t| t.contains("## Workflow:")));
+        }
+
+        service.cancel().await?;
+    }
+
+    Ok(())
+}
+
+/// Scaffolder that fails when asked to and otherwise never finishes
+#[derive(Clone)]
+struct StuckScaffolder;
+
+impl ServerHandler for StuckScaffolder {
+    async fn call_tool(
+        &self,
+        params: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, ErrorData> {
+        let fail = params
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("fail"))
+            .is_some_and(|fail| fail == true);
+        if !fail {
+            tokio::time::sleep(std::time::Duration::from_secs(600)).await;
+        }
+        Ok(CallToolResult::error(vec![Content::text(
+            "scaffold failed",
+        )]))
+    }
+}
+
+#[tokio::test]
+async fn smoke_test_scaffold_timeout() -> Result<()> {
+    let work_dir = tempfile::TempDir::new()?;
+    let config = Config {
+        timeouts: Some(TimeoutsConfig {
+            tools: [("scaffold_data_app".to_string(), 1)].into(),
+            ..Default::default()
+        }),
+        ..Config::default()
+    };
+    let session_ctx = SessionContext::new(None);
+    let workspace = WorkspaceTools::new(session_ctx.clone(), PathPolicy::new(&[]))?;
+    let mut provider = CombinedProvider::builder(session_ctx.clone())
+        .workspace(workspace)
+        .build(&config)?;
+    provider.register(ProviderEntry::new(
+        "Scaffold",
+        "Scaffold",
+        &["scaffold_data_app"],
+        StuckScaffolder,
+    ));
+    let service = ().serve(TokioInProcess::new(provider).await?).await?;
+    let scaffold = |fail: bool| CallToolRequestParam {
+        name: "scaffold_data_app".into(),
+        arguments: serde_json::json!({
+            "work_dir": work_dir.path().display().to_string(),
+            "fail": fail,
+        })
+        .as_object()
+        .cloned(),
+    };
+
+    let result = service.call_tool(scaffold(true)).await?;
+    assert_eq!(result.is_error, Some(true));
+    // a failed scaffold doesn't make its directory the active project
+    assert!(session_ctx.work_dir.read().await.is_none());
+
+    let started = std::time::Instant::now();
+    let result = service.call_tool(scaffold(false)).await?;
+    assert!(started.elapsed() < std::time::Duration::from_secs(30));
+    assert_eq!(result.is_error, Some(true));
+    let text = result.content[0].as_text().map(|t| t.text.clone());
+    assert!(text.unwrap_or_default().contains("timed out after 1s"));
+    assert!(session_ctx.work_dir.read().await.is_none());
+
+    service.cancel().await?;
+
+    Ok(())
+}
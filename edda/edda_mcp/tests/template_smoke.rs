@@ -0,0 +1,101 @@
+//! Self-test of the embedded templates
+//!
+//! Every template is scaffolded, validated with its own strategy, screenshotted and then
+//! smoke tested in a fresh Dagger sandbox, so a template regression fails here instead of
+//! in a user session. Needs Docker:
+//! `cargo test -p edda_mcp --features dagger --test template_smoke`
+
+use edda_mcp::config::{DEFAULT_SANDBOX_EXECUTE_SECS, ScreenshotConfig};
+use edda_mcp::progress::ProgressReporter;
+use edda_mcp::providers::IOProvider;
+use edda_mcp::providers::io::validation::for_project;
+use edda_sandbox::DaggerSandbox;
+use edda_sandbox::dagger::{ConnectOpts, Logger};
+use edda_templates::{
+    Template, TemplatePyFastAPI, TemplatePyStreamlit, TemplateRustAxum, TemplateTRPC, TemplateVars,
+};
+use std::path::Path;
+use tempfile::TempDir;
+
+fn screenshot_config() -> ScreenshotConfig {
+    ScreenshotConfig {
+        enabled: Some(true),
+        ..Default::default()
+    }
+}
+
+/// Run `template`'s smoke test against a copy of the scaffold at `work_dir`
+async fn run_smoke_test<T: Template + Send + Sync + 'static>(template: T, work_dir: &Path) {
+    let image = template.capabilities().unwrap().sandbox_image();
+    let work_dir = work_dir.display().to_string();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    ConnectOpts::default()
+        .with_logger(Logger::Silent)
+        .with_execute_timeout(Some(600))
+        .connect(move |client| async move {
+            let container = client
+                .container()
+                .from(&image)
+                .with_directory("/app", client.host().directory(work_dir));
+            let mut sandbox = DaggerSandbox::from_container(container, client);
+            let _ = tx.send(template.smoke_test(&mut sandbox).await);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    rx.await.unwrap().unwrap();
+}
+
+async fn check_template<T: Template + Copy + Send + Sync + 'static>(template: T) {
+    let temp_dir = TempDir::new().unwrap();
+    let work_dir = temp_dir.path().join("smoke_app");
+    IOProvider::initiate_project_impl(&work_dir, template, &TemplateVars::default(), false)
+        .unwrap();
+
+    let result = IOProvider::validate_project_impl(
+        &work_dir,
+        for_project(&work_dir),
+        Some(screenshot_config()),
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
+    assert!(
+        result.success,
+        "{} template should pass validation. Details: {:?}",
+        template.name(),
+        result.details
+    );
+    let screenshot = result
+        .screenshot_path
+        .unwrap_or_else(|| panic!("{} template should be screenshotted", template.name()));
+    assert!(std::fs::metadata(&screenshot).unwrap().len() > 0);
+
+    run_smoke_test(template, &work_dir).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "dagger"), ignore)]
+async fn test_smoke_trpc_template() {
+    check_template(TemplateTRPC).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "dagger"), ignore)]
+async fn test_smoke_streamlit_template() {
+    check_template(TemplatePyStreamlit).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "dagger"), ignore)]
+async fn test_smoke_fastapi_template() {
+    check_template(TemplatePyFastAPI).await;
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "dagger"), ignore)]
+async fn test_smoke_axum_template() {
+    check_template(TemplateRustAxum).await;
+}
@@ -17,7 +17,9 @@ async fn test_trajectory_tracking_records_tool_calls() -> Result<()> {
     let io = IOProvider::new(None)?;
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider = CombinedProvider::new(session_ctx, None, None, None, None, Some(io), None, &config)?;
+    let provider = CombinedProvider::builder(session_ctx)
+        .io(io)
+        .build(&config)?;
 
     let session_id = "test-session-123".to_string();
     let tracking_provider = TrajectoryTrackingProvider::new_with_path(
@@ -91,7 +93,9 @@ async fn test_trajectory_tracking_multiple_calls() -> Result<()> {
     let io = IOProvider::new(None)?;
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider = CombinedProvider::new(session_ctx, None, None, None, None, Some(io), None, &config)?;
+    let provider = CombinedProvider::builder(session_ctx)
+        .io(io)
+        .build(&config)?;
     let tracking_provider = TrajectoryTrackingProvider::new_with_path(
         provider,
         "multi-test".to_string(),
@@ -146,7 +150,9 @@ async fn test_trajectory_entry_format() -> Result<()> {
     let io = IOProvider::new(None)?;
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider = CombinedProvider::new(session_ctx, None, None, None, None, Some(io), None, &config)?;
+    let provider = CombinedProvider::builder(session_ctx)
+        .io(io)
+        .build(&config)?;
     let tracking_provider = TrajectoryTrackingProvider::new_with_path(
         provider,
         "format-test".to_string(),
@@ -207,7 +213,9 @@ async fn test_trajectory_tracking_error_case() -> Result<()> {
     let io = IOProvider::new(None)?;
     let session_ctx = SessionContext::new(None);
     let config = Config::default();
-    let provider = CombinedProvider::new(session_ctx, None, None, None, None, Some(io), None, &config)?;
+    let provider = CombinedProvider::builder(session_ctx)
+        .io(io)
+        .build(&config)?;
     let tracking_provider = TrajectoryTrackingProvider::new_with_path(
         provider,
         "error-test".to_string(),
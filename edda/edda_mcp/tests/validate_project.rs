@@ -1,11 +1,19 @@
+use edda_mcp::config::DEFAULT_SANDBOX_EXECUTE_SECS;
+use edda_mcp::progress::ProgressReporter;
 use edda_mcp::providers::IOProvider;
 use edda_mcp::providers::io::validation::*;
-use edda_templates::TemplateTRPC;
+use edda_templates::{TemplatePyFastAPI, TemplatePyStreamlit, TemplateTRPC, TemplateVars};
 use std::path::Path;
 use tempfile::TempDir;
 
 fn initiate_project_for_tests(work_dir: &Path, force_rewrite: bool) {
-    IOProvider::initiate_project_impl(work_dir, TemplateTRPC, force_rewrite).unwrap();
+    IOProvider::initiate_project_impl(
+        work_dir,
+        TemplateTRPC,
+        &TemplateVars::default(),
+        force_rewrite,
+    )
+    .unwrap();
 }
 
 #[tokio::test]
@@ -18,9 +26,16 @@ async fn test_validate_after_initiate() {
 
     // validate the initialized project (build + tests)
     let validation_strategy = ValidationTRPC.boxed();
-    let result = IOProvider::validate_project_impl(work_dir, validation_strategy, None)
-        .await
-        .unwrap();
+    let result = IOProvider::validate_project_impl(
+        work_dir,
+        validation_strategy,
+        None,
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
 
     // default template should pass validation (including healthcheck test)
     assert!(
@@ -49,9 +64,16 @@ async fn test_validate_with_typescript_error() {
 
     // validate should detect the error
     let validation_strategy = ValidationTRPC.boxed();
-    let result = IOProvider::validate_project_impl(work_dir, validation_strategy, None)
-        .await
-        .unwrap();
+    let result = IOProvider::validate_project_impl(
+        work_dir,
+        validation_strategy,
+        None,
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
 
     // validation should fail due to type error
     assert!(
@@ -80,11 +102,54 @@ async fn test_validate_with_failing_test() {
 
     // validate should detect the test failure
     let validation_strategy = ValidationTRPC.boxed();
-    let result = IOProvider::validate_project_impl(work_dir, validation_strategy, None)
-        .await
-        .unwrap();
+    let result = IOProvider::validate_project_impl(
+        work_dir,
+        validation_strategy,
+        None,
+        &ProgressReporter::default(),
+        false,
+        Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+    )
+    .await
+    .unwrap();
 
     // validation should fail due to test failure
     assert!(!result.success, "validation should fail when tests fail");
     assert!(result.details.is_some());
 }
+
+#[tokio::test]
+async fn test_validate_python_templates() {
+    for template in ["streamlit", "fastapi"] {
+        let temp_dir = TempDir::new().unwrap();
+        let work_dir = temp_dir.path();
+        let vars = TemplateVars::default();
+        match template {
+            "streamlit" => {
+                IOProvider::initiate_project_impl(work_dir, TemplatePyStreamlit, &vars, false)
+            }
+            _ => IOProvider::initiate_project_impl(work_dir, TemplatePyFastAPI, &vars, false),
+        }
+        .unwrap();
+
+        // picked from the project's files, pip install + compile + pytest
+        let validation_strategy = for_project(work_dir);
+        assert_eq!(validation_strategy.docker_image(), "python:3.12-slim");
+        let result = IOProvider::validate_project_impl(
+            work_dir,
+            validation_strategy,
+            None,
+            &ProgressReporter::default(),
+            false,
+            Some(DEFAULT_SANDBOX_EXECUTE_SECS),
+        )
+        .await
+        .unwrap();
+
+        assert!(
+            result.success,
+            "{} template should pass validation. Details: {:?}",
+            template, result.details
+        );
+    }
+}
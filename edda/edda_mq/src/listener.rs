@@ -143,6 +143,14 @@ impl<A: Aggregate + 'static, ES: EventStore> Listener<A, ES> {
         self
     }
 
+    /// Treat events already in the store as processed, so a listener started over an
+    /// existing stream only reacts to new events instead of replaying them into handlers.
+    pub async fn skip_existing(&mut self) -> Result<()> {
+        let sequences = self.store.load_sequence_nums::<A>().await?;
+        self.offsets.extend(sequences);
+        Ok(())
+    }
+
     pub fn push_callback<C: Callback<A> + 'static>(&mut self, callback: C) {
         self.callbacks.push(Arc::new(Mutex::new(callback)));
     }
@@ -180,3 +180,42 @@ async fn test_single_callback() {
     assert_eq!(ctx.current_sequence, 2);
     assert_eq!(ctx.aggregate.0, 2);
 }
+
+#[tokio::test]
+async fn test_skip_existing() {
+    let store = PollingQueue::new(setup_test_store().await);
+    let aggregate_id = "test-aggregate";
+    let handler = Handler::<TestAggregate, _>::new(store.clone(), ());
+    handler
+        .execute(aggregate_id, TestCommand::Increment(3))
+        .await
+        .expect("Failed to execute command");
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let callback = TestCallback {
+        handler: handler.clone(),
+        tx,
+    };
+    let mut listener = store.listener();
+    listener.push_callback(callback);
+    listener
+        .skip_existing()
+        .await
+        .expect("Failed to load sequence numbers");
+    tokio::spawn(async move {
+        let _ = listener.run().await;
+    });
+
+    // only the new increment reaches the callback
+    handler
+        .execute(aggregate_id, TestCommand::Increment(2))
+        .await
+        .expect("Failed to execute command");
+    let _ = rx.recv().await;
+    let ctx = store
+        .load_aggregate::<TestAggregate>(aggregate_id)
+        .await
+        .expect("Failed to load aggregate");
+    assert_eq!(ctx.current_sequence, 3);
+    assert_eq!(ctx.aggregate.0, 4);
+}
@@ -0,0 +1,435 @@
+import { Browser, BrowserContext, devices, Page, Request } from "@playwright/test";
+import AxeBuilder from "@axe-core/playwright";
+import { mkdir, readFile, rm, writeFile } from "fs/promises";
+import { exec } from "child_process";
+import { promisify } from "util";
+
+const execAsync = promisify(exec);
+
+interface LogEntry {
+  timestamp: string;
+  type: "console" | "pageerror" | "network";
+  level?: "log" | "warn" | "error" | "info" | "debug";
+  message: string;
+}
+
+export interface RouteCapture {
+  route: string;
+  // viewport name, unset for the first viewport
+  viewport?: string;
+  // Playwright device to emulate, unset for the default browser window
+  device?: string;
+  file: string;
+}
+
+interface NetworkRequest {
+  method: string;
+  url: string;
+  resource_type: string;
+  status: number | null;
+  duration_ms: number | null;
+  failure: string | null;
+}
+
+interface A11yViolation {
+  route: string;
+  viewport: string | null;
+  id: string;
+  impact: string | null;
+  description: string;
+  help_url: string;
+  // selectors of the offending elements
+  targets: string[];
+}
+
+export type InteractionStep =
+  | { action: "wait_for_selector"; selector: string }
+  | { action: "click"; selector: string }
+  | { action: "fill"; selector: string; value: string }
+  | { action: "navigate"; url: string };
+
+async function runInteractions(
+  page: Page,
+  steps: InteractionStep[],
+  baseUrl: string,
+  timeout: number
+): Promise<void> {
+  for (const [i, step] of steps.entries()) {
+    console.log(`Interaction ${i + 1}/${steps.length}: ${JSON.stringify(step)}`);
+    try {
+      switch (step.action) {
+        case "wait_for_selector":
+          await page.waitForSelector(step.selector, { state: "visible", timeout });
+          break;
+        case "click":
+          await page.click(step.selector, { timeout });
+          break;
+        case "fill":
+          await page.fill(step.selector, step.value, { timeout });
+          break;
+        case "navigate":
+          await page.goto(`${baseUrl}${step.url}`, { timeout });
+          break;
+      }
+      // let requests started by the step settle before the next one
+      await page.waitForLoadState("networkidle", { timeout });
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      throw new Error(
+        `interaction step ${i + 1} (${step.action}) failed: ${message}`
+      );
+    }
+  }
+}
+
+// lay the route images out in a grid with their routes as captions
+async function writeContactSheet(
+  page: Page,
+  captured: RouteCapture[],
+  outDir: string
+): Promise<void> {
+  const cells = await Promise.all(
+    captured.map(async ({ route, viewport, file }) => {
+      const image = await readFile(`${outDir}/${file}`, "base64");
+      const caption = viewport ? `${route} (${viewport})` : route;
+      return `<figure><img src="data:image/png;base64,${image}"><figcaption>${caption
+        .replace(/&/g, "&amp;")
+        .replace(/</g, "&lt;")}</figcaption></figure>`;
+    })
+  );
+  await page.setViewportSize({ width: 1600, height: 900 });
+  await page.setContent(`<html><body style="margin:0;padding:16px;background:#eee;font-family:sans-serif">
+<div style="display:grid;grid-template-columns:repeat(3,1fr);gap:16px;align-items:start">
+${cells.join("\n")}
+</div>
+<style>figure{margin:0;background:#fff;padding:8px}img{width:100%;max-height:1200px;object-fit:cover;object-position:top}figcaption{padding-top:8px;font-size:14px}</style>
+</body></html>`);
+  await page.screenshot({ path: `${outDir}/contact-sheet.png`, fullPage: true });
+  await execAsync(`oxipng -o 2 --strip all ${outDir}/contact-sheet.png`);
+}
+
+// resolve hostname to IP to avoid SSL protocol errors with service binding
+export async function resolveHost(host: string): Promise<string> {
+  const { stdout } = await execAsync(`getent hosts ${host} | awk '{ print $1 }'`);
+  return stdout.trim();
+}
+
+export interface CaptureConfig {
+  // app origin, e.g. http://10.87.0.2:8000
+  baseUrl: string;
+  // landing page, where the interaction script runs
+  targetUrl: string;
+  // every page to capture with its image file, the first one is the landing page
+  captures: RouteCapture[];
+  interactions: InteractionStep[];
+  // network idle timeout in ms
+  timeout: number;
+  recordNetwork: boolean;
+  auditA11y: boolean;
+  // CSS selector of the element to capture instead of the whole page
+  selector: string | null;
+  // record the first page from its first load: a video or a Playwright trace
+  recording: "video" | "trace" | null;
+  // directory the images, logs and reports are written to
+  outDir: string;
+}
+
+// Capture every page of the app in its own browser contexts, which are closed afterwards
+// so a long-lived browser can capture again
+export async function captureApp(
+  browser: Browser,
+  config: CaptureConfig
+): Promise<void> {
+  const {
+    baseUrl,
+    targetUrl,
+    captures,
+    interactions,
+    timeout,
+    recordNetwork,
+    auditA11y,
+    selector,
+    recording,
+    outDir,
+  } = config;
+  const maxHeight = 10000;
+  const format = "png";
+
+  await mkdir(outDir, { recursive: true });
+
+  // collect browser logs
+  const logs: LogEntry[] = [];
+  // every request of every page, when recording network activity
+  const requests: NetworkRequest[] = [];
+  const recordRequest = (
+    request: Request,
+    status: number | null,
+    failure: string | null
+  ) => {
+    // responseEnd is -1 when the timing is unavailable, e.g. for cached responses
+    const { responseEnd } = request.timing();
+    requests.push({
+      method: request.method(),
+      url: request.url(),
+      resource_type: request.resourceType(),
+      status,
+      duration_ms: responseEnd >= 0 ? Math.round(responseEnd) : null,
+      failure,
+    });
+  };
+
+  // one page per emulated device, all logging to the same list
+  const pages = new Map<string, Page>();
+  const contexts: BrowserContext[] = [];
+  // holds the recorded page once it's opened
+  const recordedPages: Page[] = [];
+  const pageFor = async (device?: string): Promise<Page> => {
+    const existing = pages.get(device ?? "");
+    if (existing) {
+      return existing;
+    }
+    if (device && !devices[device]) {
+      throw new Error(`Unknown Playwright device: ${device}`);
+    }
+    // only the first page is recorded, it loads the app first
+    const recorded = recording !== null && contexts.length === 0;
+    const context = await browser.newContext({
+      ...(device ? devices[device] : {}),
+      ...(recorded && recording === "video"
+        ? { recordVideo: { dir: `${outDir}/video` } }
+        : {}),
+    });
+    if (recorded && recording === "trace") {
+      await context.tracing.start({ screenshots: true, snapshots: true });
+    }
+    contexts.push(context);
+    const page = await context.newPage();
+    if (recorded) {
+      recordedPages.push(page);
+    }
+
+    // capture console messages
+    page.on("console", (msg) => {
+      logs.push({
+        timestamp: new Date().toISOString(),
+        type: "console",
+        level: msg.type() as "log" | "warn" | "error" | "info" | "debug",
+        message: msg.text(),
+      });
+    });
+
+    // capture page errors (JavaScript exceptions)
+    page.on("pageerror", (error) => {
+      logs.push({
+        timestamp: new Date().toISOString(),
+        type: "pageerror",
+        message: error.message,
+      });
+    });
+
+    // capture requests that failed or got an error status, for assets and API calls
+    // that never show up in a screenshot
+    page.on("response", (response) => {
+      if (response.status() >= 400) {
+        logs.push({
+          timestamp: new Date().toISOString(),
+          type: "network",
+          message: `${response.status()} ${response.request().method()} ${response.url()}`,
+        });
+      }
+    });
+    page.on("requestfailed", (request) => {
+      logs.push({
+        timestamp: new Date().toISOString(),
+        type: "network",
+        message: `FAILED ${request.method()} ${request.url()} ${request.failure()?.errorText ?? ""}`.trimEnd(),
+      });
+    });
+
+    if (recordNetwork) {
+      page.on("requestfinished", async (request) => {
+        const response = await request.response().catch(() => null);
+        recordRequest(request, response?.status() ?? null, null);
+      });
+      page.on("requestfailed", (request) => {
+        recordRequest(request, null, request.failure()?.errorText ?? "failed");
+      });
+    }
+
+    pages.set(device ?? "", page);
+    return page;
+  };
+
+  const errors: string[] = [];
+  const captured: RouteCapture[] = [];
+  const a11yViolations: A11yViolation[] = [];
+  // pages the interaction script already ran on
+  const prepared = new Set<Page>();
+
+  try {
+    for (const capture of captures) {
+      const { route, viewport, file } = capture;
+      const label = viewport ? `${route} (${viewport})` : route;
+      const screenshotPath = `${outDir}/${file}`;
+      // use IP instead of hostname to avoid SSL protocol errors
+      // wait for network idle (500ms of no new requests) to ensure data is loaded
+      try {
+        console.log(`Capturing ${label}`);
+        const page = await pageFor(capture.device);
+        if (interactions.length > 0 && !prepared.has(page)) {
+          // the script starts on the landing page, which is then captured as the
+          // script left it
+          prepared.add(page);
+          await page.goto(`${baseUrl}${targetUrl}`, {
+            waitUntil: "networkidle",
+            timeout: timeout,
+          });
+          await runInteractions(page, interactions, baseUrl, timeout);
+        }
+        if (route !== targetUrl || !prepared.has(page)) {
+          await page.goto(`${baseUrl}${route}`, {
+            waitUntil: "networkidle",
+            timeout: timeout,
+          });
+        }
+
+        if (selector) {
+          // only the first element matching the selector, scrolled into view
+          const element = page.locator(selector).first();
+          await element.waitFor({ state: "visible", timeout });
+          await element.screenshot({
+            path: screenshotPath,
+            type: format as "png" | "jpeg",
+            timeout,
+          });
+          console.log(`Captured element ${selector}`);
+        } else {
+          // take full page screenshot with height limit
+          const screenshotOptions: any = {
+            path: screenshotPath,
+            fullPage: true,
+            type: format as "png" | "jpeg",
+          };
+
+          // clip to max height if needed
+          const bodyHandle = await page.$("body");
+          const boundingBox = await bodyHandle?.boundingBox();
+          if (boundingBox && boundingBox.height > maxHeight) {
+            screenshotOptions.clip = {
+              x: 0,
+              y: 0,
+              width: boundingBox.width,
+              height: maxHeight,
+            };
+            screenshotOptions.fullPage = false;
+            console.log(`Height limited: ${boundingBox.height}px → ${maxHeight}px`);
+          }
+
+          await page.screenshot(screenshotOptions);
+        }
+
+        // compress PNG with oxipng (lossless, ~20-40% reduction)
+        console.log(`Compressing ${screenshotPath} with oxipng...`);
+        await execAsync(`oxipng -o 2 --strip all ${screenshotPath}`);
+
+        console.log(`Screenshot saved to ${screenshotPath}`);
+        captured.push(capture);
+
+        if (auditA11y) {
+          // a failing audit leaves the screenshot in place
+          try {
+            const results = await new AxeBuilder({ page }).analyze();
+            for (const violation of results.violations) {
+              a11yViolations.push({
+                route,
+                viewport: viewport ?? null,
+                id: violation.id,
+                impact: violation.impact ?? null,
+                description: violation.help,
+                help_url: violation.helpUrl,
+                targets: violation.nodes.map((node) => node.target.join(" ")),
+              });
+            }
+            console.log(`${results.violations.length} accessibility violations on ${label}`);
+          } catch (error) {
+            console.error(`Accessibility audit of ${label} failed: ${error}`);
+          }
+        }
+      } catch (error) {
+        const errorMessage =
+          error instanceof Error ? error.message : String(error);
+        console.error(`Failed to screenshot ${label}: ${errorMessage}`);
+        errors.push(
+          captures.length > 1 ? `${label}: ${errorMessage}` : errorMessage
+        );
+      }
+    }
+
+    if (errors.length > 0) {
+      // write errors to separate file
+      await writeFile(`${outDir}/error.txt`, errors.join("\n"), "utf-8");
+      console.log(`Error details saved to ${outDir}/error.txt`);
+    }
+
+    if (captures.length > 1 && captured.length > 0) {
+      const context = await browser.newContext();
+      contexts.push(context);
+      try {
+        await writeContactSheet(await context.newPage(), captured, outDir);
+        console.log(`Contact sheet saved to ${outDir}/contact-sheet.png`);
+      } catch (error) {
+        console.error(`Failed to write contact sheet: ${error}`);
+      }
+    }
+
+    // save browser logs as text (always, regardless of screenshot success)
+    const logText = logs
+      .map((log) => {
+        const prefix =
+          log.type === "console"
+            ? `[${log.level?.toUpperCase()}]`
+            : `[${log.type.toUpperCase()}]`;
+        return `${log.timestamp} ${prefix} ${log.message}`;
+      })
+      .join("\n");
+
+    await writeFile(`${outDir}/logs.txt`, logText, "utf-8");
+
+    if (auditA11y) {
+      await writeFile(
+        `${outDir}/a11y.json`,
+        JSON.stringify(a11yViolations, null, 2),
+        "utf-8"
+      );
+    }
+
+    if (recordNetwork) {
+      await writeFile(
+        `${outDir}/network.json`,
+        JSON.stringify(requests, null, 2),
+        "utf-8"
+      );
+      console.log(`Recorded ${requests.length} requests to ${outDir}/network.json`);
+    }
+
+    console.log(`Captured ${logs.length} browser log entries`);
+    console.log(`Logs saved to ${outDir}/logs.txt`);
+  } finally {
+    const recordedPage = recordedPages[0];
+    if (recording === "trace" && recordedPage) {
+      await recordedPage
+        .context()
+        .tracing.stop({ path: `${outDir}/trace.zip` })
+        .catch((error) => console.error(`Failed to save trace: ${error}`));
+    }
+    await Promise.all(contexts.map((context) => context.close()));
+    // the video is complete once its page is closed
+    const video = recordedPage?.video();
+    if (video) {
+      await video
+        .saveAs(`${outDir}/recording.webm`)
+        .catch((error) => console.error(`Failed to save video: ${error}`));
+      await rm(`${outDir}/video`, { recursive: true, force: true });
+    }
+  }
+}
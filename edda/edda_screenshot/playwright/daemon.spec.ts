@@ -0,0 +1,107 @@
+import { test, chromium, Browser } from "@playwright/test";
+import { createServer, IncomingMessage } from "http";
+import { mkdtemp, readdir, readFile, rm } from "fs/promises";
+import {
+  captureApp,
+  InteractionStep,
+  resolveHost,
+  RouteCapture,
+} from "./capture";
+
+// body of POST /capture, mirrors edda_screenshot::daemon::CaptureRequest
+interface CaptureRequest {
+  url: string;
+  port: number;
+  captures: RouteCapture[];
+  interactions: InteractionStep[];
+  wait_time_ms: number;
+  record_network: boolean;
+  accessibility_audit: boolean;
+  selector: string | null;
+  recording: "video" | "trace" | null;
+}
+
+const launch = () =>
+  chromium.launch({ args: ["--no-sandbox", "--disable-setuid-sandbox"] });
+
+async function readBody(req: IncomingMessage): Promise<string> {
+  const chunks: Buffer[] = [];
+  for await (const chunk of req) {
+    chunks.push(chunk as Buffer);
+  }
+  return Buffer.concat(chunks).toString("utf-8");
+}
+
+// capture into a fresh directory and return its files base64 encoded by name
+async function capture(
+  browser: Browser,
+  request: CaptureRequest
+): Promise<Record<string, string>> {
+  const outDir = await mkdtemp("/tmp/capture-");
+  try {
+    const appIp = await resolveHost("app");
+    console.log(`Capturing http://${appIp}:${request.port}${request.url}`);
+    await captureApp(browser, {
+      baseUrl: `http://${appIp}:${request.port}`,
+      targetUrl: request.url,
+      captures: request.captures,
+      interactions: request.interactions,
+      timeout: request.wait_time_ms,
+      recordNetwork: request.record_network,
+      auditA11y: request.accessibility_audit,
+      selector: request.selector,
+      recording: request.recording,
+      outDir,
+    });
+    const files: Record<string, string> = {};
+    for (const name of await readdir(outDir)) {
+      files[name] = await readFile(`${outDir}/${name}`, "base64");
+    }
+    return files;
+  } finally {
+    await rm(outDir, { recursive: true, force: true });
+  }
+}
+
+test("screenshot daemon", async () => {
+  // serve until the container is stopped
+  test.setTimeout(0);
+
+  const port = parseInt(process.env.DAEMON_PORT || "9323");
+  let browser = await launch();
+  // captures share the "app" binding, so they run one at a time
+  let queue: Promise<unknown> = Promise.resolve();
+
+  const server = createServer(async (req, res) => {
+    if (req.method === "GET" && req.url === "/health") {
+      res.end("ok");
+      return;
+    }
+    if (req.method !== "POST" || req.url !== "/capture") {
+      res.statusCode = 404;
+      res.end();
+      return;
+    }
+    try {
+      const request: CaptureRequest = JSON.parse(await readBody(req));
+      const run = queue.then(async () => {
+        if (!browser.isConnected()) {
+          console.log("Browser disconnected, relaunching");
+          browser = await launch();
+        }
+        return capture(browser, request);
+      });
+      queue = run.catch(() => undefined);
+      const files = await run;
+      res.setHeader("Content-Type", "application/json");
+      res.end(JSON.stringify({ files }));
+    } catch (error) {
+      res.statusCode = 500;
+      res.end(error instanceof Error ? error.message : String(error));
+    }
+  });
+
+  await new Promise<void>((resolve) => server.listen(port, resolve));
+  console.log(`Screenshot daemon listening on port ${port}`);
+  await new Promise(() => {});
+});
@@ -0,0 +1,64 @@
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Accessibility violations found with `options.accessibility_audit`
+pub const A11Y_FILE: &str = "a11y.json";
+
+/// An axe-core rule failing on a captured page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct A11yViolation {
+    pub route: String,
+    /// Viewport name, None for the first viewport
+    pub viewport: Option<String>,
+    /// axe-core rule, e.g. "color-contrast"
+    pub id: String,
+    /// "minor", "moderate", "serious" or "critical"
+    pub impact: Option<String>,
+    pub description: String,
+    pub help_url: String,
+    /// Selectors of the offending elements
+    pub targets: Vec<String>,
+}
+
+impl A11yViolation {
+    fn severity(&self) -> u8 {
+        match self.impact.as_deref() {
+            Some("critical") => 3,
+            Some("serious") => 2,
+            Some("moderate") => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Parse the contents of `a11y.json`, most severe violations first
+pub fn parse_a11y(json: &str) -> Result<Vec<A11yViolation>> {
+    let mut violations: Vec<A11yViolation> =
+        serde_json::from_str(json).context("invalid accessibility report")?;
+    violations.sort_by_key(|v| std::cmp::Reverse(v.severity()));
+    Ok(violations)
+}
+
+/// One line per violation, e.g.
+/// `[serious] color-contrast on /: Elements must meet minimum color contrast ratio thresholds (3 elements)`
+pub fn a11y_summary(violations: &[A11yViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| {
+            let page = match &v.viewport {
+                Some(viewport) => format!("{} ({})", v.route, viewport),
+                None => v.route.clone(),
+            };
+            format!(
+                "[{}] {} on {}: {} ({} element{})",
+                v.impact.as_deref().unwrap_or("unknown"),
+                v.id,
+                page,
+                v.description,
+                v.targets.len(),
+                if v.targets.len() == 1 { "" } else { "s" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,227 @@
+//! Long-lived screenshot service: one Playwright container keeps a browser open and
+//! captures apps on request, so a screenshot doesn't pay for a fresh container each time.
+
+use crate::playwright::build_playwright_base;
+use crate::screenshot::serve_app;
+use crate::types::{InteractionStep, Recording, RouteCapture, ScreenshotOptions};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use dagger_sdk::{DaggerConn, Directory, NetworkProtocol, PortForward, ServiceUpOpts};
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Port the daemon listens on inside its container
+pub const DAEMON_PORT: u16 = 9323;
+
+/// How long an app may take to build and start before its capture is given up
+const APP_START_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Body of `POST /capture`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureRequest {
+    pub url: String,
+    /// Port of the app on the daemon's "app" host
+    pub port: u16,
+    pub captures: Vec<RouteCapture>,
+    pub interactions: Vec<InteractionStep>,
+    pub wait_time_ms: u64,
+    pub record_network: bool,
+    pub accessibility_audit: bool,
+    pub selector: Option<String>,
+    pub recording: Option<Recording>,
+}
+
+impl CaptureRequest {
+    pub fn new(options: &ScreenshotOptions, port: u16) -> Self {
+        Self {
+            url: options.url.clone(),
+            port,
+            captures: options.captures(),
+            interactions: options.interactions.clone(),
+            wait_time_ms: options.wait_time_ms,
+            record_network: options.record_network,
+            accessibility_audit: options.accessibility_audit,
+            selector: options.selector.clone(),
+            recording: options.recording,
+        }
+    }
+}
+
+/// Files written by a capture, base64 encoded by name
+#[derive(Debug, Deserialize)]
+struct CaptureResponse {
+    files: BTreeMap<String, String>,
+}
+
+/// Run the daemon container with its control port forwarded to `control_port` on the host.
+/// Apps served on host port `app_port` are reachable from it as "app". Runs until the
+/// dagger session ends.
+pub async fn run_daemon(client: &DaggerConn, control_port: u16, app_port: u16) -> Result<()> {
+    let playwright_base = build_playwright_base(client)
+        .await
+        .context("failed to build playwright container")?;
+
+    let host_app = client.host().service(vec![PortForward {
+        backend: app_port as isize,
+        frontend: app_port as isize,
+        protocol: NetworkProtocol::Tcp,
+    }]);
+
+    tracing::info!("Starting screenshot daemon on host port {}", control_port);
+
+    playwright_base
+        .with_service_binding("app", host_app)
+        .with_env_variable("DAEMON_PORT", DAEMON_PORT.to_string())
+        .with_exposed_port(DAEMON_PORT as isize)
+        .with_default_args(vec![
+            "npx",
+            "playwright",
+            "test",
+            "--config=playwright.daemon.config.ts",
+        ])
+        .as_service()
+        .up_opts(ServiceUpOpts {
+            ports: Some(vec![PortForward {
+                backend: DAEMON_PORT as isize,
+                frontend: control_port as isize,
+                protocol: NetworkProtocol::Tcp,
+            }]),
+            random: None,
+        })
+        .await
+        .context("screenshot daemon stopped")?;
+    Ok(())
+}
+
+/// Talks to a daemon started by `run_daemon`. Clones share the daemon, captures run one
+/// at a time since they share the app port.
+#[derive(Clone)]
+pub struct DaemonClient {
+    http: reqwest::Client,
+    control_port: u16,
+    app_port: u16,
+    capture_lock: Arc<Mutex<()>>,
+}
+
+impl DaemonClient {
+    pub fn new(control_port: u16, app_port: u16) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            control_port,
+            app_port,
+            capture_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.control_port, path)
+    }
+
+    /// Whether the daemon answers its health check
+    pub async fn is_ready(&self) -> bool {
+        match self.http.get(self.url("/health")).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        }
+    }
+
+    /// Wait until the daemon answers, the first start builds the Playwright container
+    pub async fn wait_ready(&self, timeout: Duration) -> Result<()> {
+        let start = Instant::now();
+        while !self.is_ready().await {
+            if start.elapsed() > timeout {
+                eyre::bail!("screenshot daemon not ready after {}s", timeout.as_secs());
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+        Ok(())
+    }
+
+    /// Capture the app served on the app port and write the images, logs and reports to
+    /// `output`
+    async fn capture(&self, options: &ScreenshotOptions, output: &Path) -> Result<()> {
+        let response = self
+            .http
+            .post(self.url("/capture"))
+            .json(&CaptureRequest::new(options, self.app_port))
+            .send()
+            .await
+            .context("screenshot daemon unreachable")?;
+        if !response.status().is_success() {
+            eyre::bail!(
+                "screenshot daemon failed: {}",
+                response.text().await.unwrap_or_default()
+            );
+        }
+        let response: CaptureResponse = response
+            .json()
+            .await
+            .context("invalid screenshot daemon response")?;
+
+        std::fs::create_dir_all(output)?;
+        for (name, data) in response.files {
+            // names of files in the daemon's output directory, never paths
+            if name.contains(['/', '\\']) || name == ".." {
+                tracing::warn!("Ignoring daemon file {}", name);
+                continue;
+            }
+            let bytes = BASE64
+                .decode(data)
+                .with_context(|| format!("invalid contents of {}", name))?;
+            std::fs::write(output.join(&name), bytes)
+                .with_context(|| format!("failed to write {}", name))?;
+        }
+        Ok(())
+    }
+
+    async fn wait_for_app(&self) -> Result<()> {
+        let start = Instant::now();
+        while tokio::net::TcpStream::connect(("127.0.0.1", self.app_port))
+            .await
+            .is_err()
+        {
+            if start.elapsed() > APP_START_TIMEOUT {
+                eyre::bail!("app not reachable on port {}", self.app_port);
+            }
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+        Ok(())
+    }
+}
+
+/// Build an app and screenshot it with the daemon. The files are written to `output`,
+/// which is returned as a directory with the same contents as `screenshot_app` gives.
+pub async fn screenshot_app_with_daemon(
+    client: &DaggerConn,
+    daemon: &DaemonClient,
+    app_source: Directory,
+    options: ScreenshotOptions,
+    output: &Path,
+) -> Result<Directory> {
+    let _capture = daemon.capture_lock.lock().await;
+
+    tracing::info!(
+        "Serving app to the screenshot daemon on port {}",
+        daemon.app_port
+    );
+
+    let serve = serve_app(app_source, options.clone(), daemon.app_port);
+    let capture = async {
+        daemon.wait_for_app().await?;
+        daemon.capture(&options, output).await
+    };
+    tokio::select! {
+        served = serve => {
+            served?;
+            eyre::bail!("app service stopped before the screenshot");
+        }
+        captured = capture => captured?,
+    }
+
+    Ok(client.host().directory(output.display().to_string()))
+}
@@ -0,0 +1,221 @@
+use eyre::{Context, Result};
+use image::{Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffOptions {
+    /// Largest per-channel difference (0.0-1.0) still treated as the same pixel, absorbs
+    /// anti-aliasing and compression noise (default: 0.1)
+    pub pixel_threshold: f64,
+    /// Fraction of changed pixels above which the screenshots count as different
+    /// (default: 0.001)
+    pub max_changed_ratio: f64,
+    /// Side in pixels of the grid cells changed pixels are grouped into (default: 32)
+    pub cell_size: u32,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            pixel_threshold: 0.1,
+            max_changed_ratio: 0.001,
+            cell_size: 32,
+        }
+    }
+}
+
+/// Bounding box of adjacent changed cells
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub changed_pixels: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffReport {
+    /// (width, height) of the before image
+    pub before_size: (u32, u32),
+    /// (width, height) of the after image
+    pub after_size: (u32, u32),
+    /// Pixels that differ, pixels outside the overlap of both images included
+    pub changed_pixels: u64,
+    pub total_pixels: u64,
+    pub changed_ratio: f64,
+    /// Changed areas, largest first
+    pub regions: Vec<DiffRegion>,
+    /// Whether the change exceeds `max_changed_ratio` or the page size changed
+    pub differs: bool,
+}
+
+impl DiffReport {
+    pub fn summary(&self) -> String {
+        if !self.differs {
+            return "no visible changes".to_string();
+        }
+        let mut summary = format!(
+            "{:.2}% of pixels changed in {} region(s)",
+            self.changed_ratio * 100.0,
+            self.regions.len()
+        );
+        if self.before_size != self.after_size {
+            summary.push_str(&format!(
+                ", page size {}x{} -> {}x{}",
+                self.before_size.0, self.before_size.1, self.after_size.0, self.after_size.1
+            ));
+        }
+        for region in self.regions.iter().take(5) {
+            summary.push_str(&format!(
+                "\n  {}x{} at ({}, {})",
+                region.width, region.height, region.x, region.y
+            ));
+        }
+        summary
+    }
+}
+
+/// Compare two PNG screenshots with the default options
+pub fn compare_screenshots(before: &Path, after: &Path) -> Result<DiffReport> {
+    compare_screenshots_with(before, after, &DiffOptions::default())
+}
+
+pub fn compare_screenshots_with(
+    before: &Path,
+    after: &Path,
+    options: &DiffOptions,
+) -> Result<DiffReport> {
+    let before = load(before)?;
+    let after = load(after)?;
+    Ok(diff_images(&before, &after, options))
+}
+
+fn load(path: &Path) -> Result<RgbaImage> {
+    Ok(image::open(path)
+        .with_context(|| format!("failed to read screenshot {}", path.display()))?
+        .to_rgba8())
+}
+
+fn pixel_changed(a: &Rgba<u8>, b: &Rgba<u8>, threshold: u8) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .any(|(x, y)| x.abs_diff(*y) > threshold)
+}
+
+/// Pixel diff of two images. Changed pixels are counted per grid cell and touching
+/// cells are merged into regions.
+pub fn diff_images(before: &RgbaImage, after: &RgbaImage, options: &DiffOptions) -> DiffReport {
+    let (width, height) = (
+        before.width().max(after.width()),
+        before.height().max(after.height()),
+    );
+    let cell = options.cell_size.max(1);
+    let (cols, rows) = (width.div_ceil(cell), height.div_ceil(cell));
+    let threshold = (options.pixel_threshold.clamp(0.0, 1.0) * 255.0) as u8;
+
+    let mut cells = vec![0u64; (cols * rows) as usize];
+    for y in 0..height {
+        for x in 0..width {
+            let changed = match (
+                before.get_pixel_checked(x, y),
+                after.get_pixel_checked(x, y),
+            ) {
+                (Some(a), Some(b)) => pixel_changed(a, b, threshold),
+                (None, None) => false,
+                // outside one of the images
+                _ => true,
+            };
+            if changed {
+                cells[((y / cell) * cols + x / cell) as usize] += 1;
+            }
+        }
+    }
+
+    let mut regions = vec![];
+    let mut seen = vec![false; cells.len()];
+    for start in 0..cells.len() {
+        if cells[start] == 0 || seen[start] {
+            continue;
+        }
+        // flood fill over the 8 neighbours of each changed cell
+        let (mut min_col, mut min_row, mut max_col, mut max_row) = (cols, rows, 0, 0);
+        let mut changed_pixels = 0;
+        let mut stack = vec![start];
+        seen[start] = true;
+        while let Some(index) = stack.pop() {
+            let (col, row) = (index as u32 % cols, index as u32 / cols);
+            changed_pixels += cells[index];
+            (min_col, min_row) = (min_col.min(col), min_row.min(row));
+            (max_col, max_row) = (max_col.max(col), max_row.max(row));
+            for dy in -1i64..=1 {
+                for dx in -1i64..=1 {
+                    let (c, r) = (col as i64 + dx, row as i64 + dy);
+                    if c < 0 || r < 0 || c >= cols as i64 || r >= rows as i64 {
+                        continue;
+                    }
+                    let neighbour = (r * cols as i64 + c) as usize;
+                    if cells[neighbour] > 0 && !seen[neighbour] {
+                        seen[neighbour] = true;
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+        let (x, y) = (min_col * cell, min_row * cell);
+        regions.push(DiffRegion {
+            x,
+            y,
+            width: ((max_col + 1) * cell).min(width) - x,
+            height: ((max_row + 1) * cell).min(height) - y,
+            changed_pixels,
+        });
+    }
+    regions.sort_by_key(|region| std::cmp::Reverse(region.changed_pixels));
+
+    let changed_pixels: u64 = cells.iter().sum();
+    let total_pixels = width as u64 * height as u64;
+    let changed_ratio = match total_pixels {
+        0 => 0.0,
+        total => changed_pixels as f64 / total as f64,
+    };
+    DiffReport {
+        before_size: before.dimensions(),
+        after_size: after.dimensions(),
+        changed_pixels,
+        total_pixels,
+        changed_ratio,
+        regions,
+        differs: before.dimensions() != after.dimensions()
+            || changed_ratio > options.max_changed_ratio,
+    }
+}
+
+/// Copy of `after` with each changed region outlined in red
+pub fn annotate(after: &RgbaImage, report: &DiffReport) -> RgbaImage {
+    const BORDER: u32 = 3;
+    let red = Rgba([255, 0, 0, 255]);
+    let mut annotated = after.clone();
+    for region in &report.regions {
+        for y in region.y..region.y + region.height {
+            for x in region.x..region.x + region.width {
+                let on_border = x < region.x + BORDER
+                    || y < region.y + BORDER
+                    || x + BORDER >= region.x + region.width
+                    || y + BORDER >= region.y + region.height;
+                if on_border && x < annotated.width() && y < annotated.height() {
+                    annotated.put_pixel(x, y, red);
+                }
+            }
+        }
+    }
+    annotated
+}
+
+/// Write `after` with the changed regions of `report` outlined to `output`
+pub fn write_annotated(after: &Path, report: &DiffReport, output: &Path) -> Result<()> {
+    annotate(&load(after)?, report)
+        .save(output)
+        .with_context(|| format!("failed to write diff image {}", output.display()))
+}
@@ -1,7 +1,20 @@
+pub mod a11y;
+pub mod daemon;
+pub mod diff;
+pub mod logs;
+pub mod network;
 pub mod playwright;
 pub mod screenshot;
 pub mod types;
 
+pub use a11y::{A11Y_FILE, A11yViolation, a11y_summary, parse_a11y};
+pub use daemon::{DaemonClient, run_daemon, screenshot_app_with_daemon};
+pub use diff::{DiffOptions, DiffRegion, DiffReport, compare_screenshots};
+pub use logs::{LOGS_FILE, LogEntry, LogLevel, LogPolicy, parse_logs};
+pub use network::{NETWORK_FILE, NetworkRequest, network_summary, parse_network};
 pub use playwright::warmup_playwright;
-pub use screenshot::{screenshot_app, screenshot_apps_batch, screenshot_service};
-pub use types::ScreenshotOptions;
+pub use screenshot::{screenshot_app, screenshot_apps_batch, screenshot_service, serve_app};
+pub use types::{
+    CONTACT_SHEET_FILE, InteractionStep, Recording, RouteCapture, SCREENSHOT_FILE,
+    ScreenshotOptions, Viewport,
+};
@@ -0,0 +1,167 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Browser log file written next to the screenshots
+pub const LOGS_FILE: &str = "logs.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Log,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    /// Uncaught exception in the page
+    PageError,
+    /// Request that failed or got an error status
+    Network,
+}
+
+impl LogLevel {
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag.to_ascii_lowercase().as_str() {
+            "log" => Some(Self::Log),
+            "debug" | "trace" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "assert" => Some(Self::Error),
+            "pageerror" => Some(Self::PageError),
+            "network" => Some(Self::Network),
+            _ => None,
+        }
+    }
+}
+
+/// One entry of `logs.txt`, written by the Playwright script as
+/// `<timestamp> [<LEVEL>] <message>`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+/// Request logged as `[NETWORK] <status> <method> <url>`, or as
+/// `[NETWORK] FAILED <method> <url> <error>` when no response arrived
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedRequest<'a> {
+    pub status: Option<u16>,
+    pub method: &'a str,
+    pub url: &'a str,
+    /// Browser error of a request without a response, e.g. "net::ERR_EMPTY_RESPONSE"
+    pub error: Option<&'a str>,
+}
+
+impl FailedRequest<'_> {
+    /// Cancelled by the page itself, e.g. a fetch aborted on navigation. Not an app error.
+    pub fn aborted(&self) -> bool {
+        self.error == Some("net::ERR_ABORTED")
+    }
+
+    /// Path of the URL, e.g. "/api/users.list" for "http://10.0.0.2:8000/api/users.list?batch=1"
+    pub fn path(&self) -> &str {
+        let rest = match self.url.split_once("://") {
+            Some((_, rest)) => rest.find('/').map_or("/", |i| &rest[i..]),
+            None => self.url,
+        };
+        rest.split(['?', '#']).next().unwrap_or(rest)
+    }
+}
+
+impl LogEntry {
+    pub fn failed_request(&self) -> Option<FailedRequest<'_>> {
+        if self.level != LogLevel::Network {
+            return None;
+        }
+        let mut parts = self.message.split_whitespace();
+        let status = match parts.next()? {
+            "FAILED" => None,
+            status => Some(status.parse().ok()?),
+        };
+        Some(FailedRequest {
+            status,
+            method: parts.next()?,
+            url: parts.next()?,
+            error: parts.next(),
+        })
+    }
+}
+
+/// Split a `<timestamp> [<LEVEL>] ...` line, None for the continuation of a multiline message
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    if !timestamp.starts_with(|c: char| c.is_ascii_digit()) || !timestamp.contains('T') {
+        return None;
+    }
+    let (tag, message) = rest.strip_prefix('[')?.split_once(']')?;
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level: LogLevel::from_tag(tag)?,
+        message: message.strip_prefix(' ').unwrap_or(message).to_string(),
+    })
+}
+
+/// Parse the contents of `logs.txt`. Lines not starting an entry belong to the message of
+/// the entry before them.
+pub fn parse_logs(text: &str) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = vec![];
+    for line in text.lines() {
+        match (parse_line(line), entries.last_mut()) {
+            (Some(entry), _) => entries.push(entry),
+            (None, Some(last)) => {
+                last.message.push('\n');
+                last.message.push_str(line);
+            }
+            (None, None) if line.trim().is_empty() => {}
+            (None, None) => entries.push(LogEntry {
+                timestamp: String::new(),
+                level: LogLevel::Log,
+                message: line.to_string(),
+            }),
+        }
+    }
+    entries
+}
+
+/// Browser log entries that fail validation. A React render crash leaves a blank page that
+/// screenshots fine, but shows up here as an uncaught exception or failing API calls.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LogPolicy {
+    /// Fail on uncaught exceptions (default: true)
+    pub fail_on_page_errors: bool,
+    /// Fail on requests to these URL path prefixes that got no response or a 5xx status
+    /// (default: the tRPC endpoint, "/api")
+    pub failing_request_paths: Vec<String>,
+}
+
+impl Default for LogPolicy {
+    fn default() -> Self {
+        Self {
+            fail_on_page_errors: true,
+            failing_request_paths: vec!["/api".to_string()],
+        }
+    }
+}
+
+impl LogPolicy {
+    /// Entries violating the policy, in log order
+    pub fn violations<'a>(&self, entries: &'a [LogEntry]) -> Vec<&'a LogEntry> {
+        entries
+            .iter()
+            .filter(|entry| match entry.level {
+                LogLevel::PageError => self.fail_on_page_errors,
+                LogLevel::Network => entry.failed_request().is_some_and(|request| {
+                    request.status.is_none_or(|status| status >= 500)
+                        && !request.aborted()
+                        && self
+                            .failing_request_paths
+                            .iter()
+                            .any(|prefix| request.path().starts_with(prefix.as_str()))
+                }),
+                _ => false,
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,8 @@
 use clap::Parser;
 use edda_sandbox::dagger::{ConnectOpts, Logger};
-use edda_screenshot::{screenshot_app, screenshot_apps_batch, ScreenshotOptions};
+use edda_screenshot::{
+    screenshot_app, screenshot_apps_batch, Recording, ScreenshotOptions, Viewport,
+};
 use eyre::Result;
 use tracing_subscriber;
 
@@ -29,6 +31,36 @@ enum Commands {
         #[arg(long)]
         env_vars: Option<String>,
 
+        /// More URL paths to capture after "/", one image each (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        routes: Vec<String>,
+
+        /// Screens to capture on: desktop, tablet, mobile or a Playwright device name
+        /// (comma-separated, default: desktop)
+        #[arg(long, value_delimiter = ',')]
+        viewports: Vec<Viewport>,
+
+        /// Steps to run on the landing page before capturing it, as a JSON array, e.g.
+        /// '[{"action":"fill","selector":"#user","value":"demo"},{"action":"click","selector":"text=Sign in"}]'
+        #[arg(long)]
+        interactions: Option<String>,
+
+        /// Record every request with its status and timing to network.json
+        #[arg(long)]
+        record_network: bool,
+
+        /// Audit every captured page with axe-core and write the violations to a11y.json
+        #[arg(long)]
+        a11y: bool,
+
+        /// CSS selector of the element to capture instead of the whole page
+        #[arg(long)]
+        selector: Option<String>,
+
+        /// Record the landing page while it loads: video (recording.webm) or trace (trace.zip)
+        #[arg(long)]
+        record: Option<Recording>,
+
         /// Port the app listens on
         #[arg(long, default_value = "8000")]
         port: u16,
@@ -52,6 +84,15 @@ enum Commands {
         #[arg(long)]
         env_vars: Option<String>,
 
+        /// More URL paths to capture after "/" in every app (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        routes: Vec<String>,
+
+        /// Screens to capture every app on: desktop, tablet, mobile or a Playwright device
+        /// name (comma-separated, default: desktop)
+        #[arg(long, value_delimiter = ',')]
+        viewports: Vec<Viewport>,
+
         /// Port all apps listen on
         #[arg(long, default_value = "8000")]
         port: u16,
@@ -97,13 +138,32 @@ async fn main() -> Result<()> {
         Commands::App {
             app_source,
             env_vars,
+            routes,
+            viewports,
+            interactions,
+            record_network,
+            a11y,
+            selector,
+            record,
             port,
             wait_time,
             output,
         } => {
             let env_vars = parse_env_vars(env_vars.as_deref().unwrap_or(""));
+            let interactions = match interactions {
+                Some(json) => serde_json::from_str(&json)
+                    .map_err(|e| eyre::eyre!("invalid --interactions: {}", e))?,
+                None => vec![],
+            };
 
             let options = ScreenshotOptions {
+                routes,
+                viewports,
+                interactions,
+                record_network,
+                accessibility_audit: a11y,
+                selector,
+                recording: record,
                 port,
                 wait_time_ms: wait_time,
                 env_vars,
@@ -131,6 +191,8 @@ async fn main() -> Result<()> {
         Commands::Batch {
             app_sources,
             env_vars,
+            routes,
+            viewports,
             port,
             wait_time,
             concurrency,
@@ -139,6 +201,8 @@ async fn main() -> Result<()> {
             let env_vars = parse_env_vars(env_vars.as_deref().unwrap_or(""));
 
             let options = ScreenshotOptions {
+                routes,
+                viewports,
                 port,
                 wait_time_ms: wait_time,
                 env_vars,
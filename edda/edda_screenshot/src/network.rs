@@ -0,0 +1,71 @@
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Requests recorded with `options.record_network`
+pub const NETWORK_FILE: &str = "network.json";
+
+/// Requests slower than this are listed in the summary
+const SLOW_REQUEST_MS: f64 = 1000.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkRequest {
+    pub method: String,
+    pub url: String,
+    /// Playwright resource type, e.g. "document", "script", "fetch"
+    pub resource_type: String,
+    /// None when no response arrived
+    pub status: Option<u16>,
+    /// Time from sending the request to the end of the response, None if unknown
+    pub duration_ms: Option<f64>,
+    /// Why the request failed, e.g. "net::ERR_CONNECTION_REFUSED"
+    pub failure: Option<String>,
+}
+
+impl NetworkRequest {
+    pub fn failed(&self) -> bool {
+        self.failure.is_some() || self.status.is_none_or(|status| status >= 400)
+    }
+}
+
+/// Parse the contents of `network.json`
+pub fn parse_network(json: &str) -> Result<Vec<NetworkRequest>> {
+    serde_json::from_str(json).context("invalid network log")
+}
+
+/// Request counts followed by the failed requests and the slowest ones
+pub fn network_summary(requests: &[NetworkRequest]) -> String {
+    let failed: Vec<_> = requests.iter().filter(|r| r.failed()).collect();
+    let mut slow: Vec<_> = requests
+        .iter()
+        .filter(|r| r.duration_ms.is_some_and(|ms| ms > SLOW_REQUEST_MS))
+        .collect();
+    slow.sort_by(|a, b| b.duration_ms.partial_cmp(&a.duration_ms).unwrap());
+
+    let mut summary = format!(
+        "{} requests, {} failed, {} slower than {}ms",
+        requests.len(),
+        failed.len(),
+        slow.len(),
+        SLOW_REQUEST_MS
+    );
+    for request in failed {
+        let status = match (&request.failure, request.status) {
+            (Some(failure), _) => failure.clone(),
+            (None, Some(status)) => status.to_string(),
+            (None, None) => "no response".to_string(),
+        };
+        summary.push_str(&format!(
+            "\n  {} {} {}",
+            status, request.method, request.url
+        ));
+    }
+    for request in slow.iter().take(5) {
+        summary.push_str(&format!(
+            "\n  {:.0}ms {} {}",
+            request.duration_ms.unwrap_or_default(),
+            request.method,
+            request.url
+        ));
+    }
+    summary
+}
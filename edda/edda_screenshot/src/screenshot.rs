@@ -1,6 +1,8 @@
 use crate::playwright::build_playwright_base;
 use crate::types::ScreenshotOptions;
-use dagger_sdk::{DaggerConn, Directory, Service};
+use dagger_sdk::{
+    Container, DaggerConn, Directory, NetworkProtocol, PortForward, Service, ServiceUpOpts,
+};
 use eyre::{Context, Result};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -31,7 +33,38 @@ async fn build_app_service(
     Ok(app_container.with_exposed_port(port).as_service())
 }
 
-/// Capture a screenshot of a running web service
+/// Run the screenshot spec against the service bound as "app"
+fn capture_container(
+    playwright_base: Container,
+    service: Service,
+    options: &ScreenshotOptions,
+    cache_bust: &str,
+) -> Container {
+    let captures = serde_json::to_string(&options.captures()).expect("routes serialize to JSON");
+    let interactions =
+        serde_json::to_string(&options.interactions).expect("interactions serialize to JSON");
+    playwright_base
+        .with_service_binding("app", service)
+        .with_env_variable("TARGET_URL", &options.url)
+        .with_env_variable("TARGET_ROUTES", captures)
+        .with_env_variable("TARGET_INTERACTIONS", interactions)
+        .with_env_variable("RECORD_NETWORK", options.record_network.to_string())
+        .with_env_variable("A11Y_AUDIT", options.accessibility_audit.to_string())
+        .with_env_variable("TARGET_SELECTOR", options.selector.clone().unwrap_or_default())
+        .with_env_variable("TARGET_RECORDING", options.recording.map_or("", |r| r.name()))
+        .with_env_variable("TARGET_PORT", options.port.to_string())
+        .with_env_variable("WAIT_TIME", options.wait_time_ms.to_string())
+        .with_env_variable("CACHE_BUST", cache_bust)
+        .with_exec(vec![
+            "npx",
+            "playwright",
+            "test",
+            "--config=playwright.single.config.ts",
+        ])
+}
+
+/// Capture a screenshot of a running web service. With `options.routes`, the directory
+/// has one image per route and a contact sheet of all of them.
 pub async fn screenshot_service(
     client: &DaggerConn,
     service: Service,
@@ -44,8 +77,10 @@ pub async fn screenshot_service(
         .context("failed to build playwright container")?;
 
     tracing::debug!(
-        "Configuring Playwright: url={}, port={}, wait_time={}ms",
+        "Configuring Playwright: url={}, routes={:?}, viewports={:?}, port={}, wait_time={}ms",
         options.url,
+        options.routes,
+        options.viewports,
         options.port,
         options.wait_time_ms
     );
@@ -56,18 +91,7 @@ pub async fn screenshot_service(
         .as_secs()
         .to_string();
 
-    let container = playwright_base
-        .with_service_binding("app", service)
-        .with_env_variable("TARGET_URL", &options.url)
-        .with_env_variable("TARGET_PORT", options.port.to_string())
-        .with_env_variable("WAIT_TIME", options.wait_time_ms.to_string())
-        .with_env_variable("CACHE_BUST", cache_bust)
-        .with_exec(vec![
-            "npx",
-            "playwright",
-            "test",
-            "--config=playwright.single.config.ts",
-        ]);
+    let container = capture_container(playwright_base, service, &options, &cache_bust);
 
     tracing::info!("Executing screenshot capture");
 
@@ -94,6 +118,35 @@ pub async fn screenshot_app(
     screenshot_service(client, service, options).await
 }
 
+/// Build an app from a directory with a Dockerfile and forward its port to `host_port`
+/// on the host. Runs until the dagger session ends.
+pub async fn serve_app(
+    app_source: Directory,
+    options: ScreenshotOptions,
+    host_port: u16,
+) -> Result<()> {
+    tracing::info!("Building app from Dockerfile");
+
+    let service = build_app_service(app_source, &options)
+        .await
+        .context("failed to build app service")?;
+
+    tracing::info!("Serving app on host port {}", host_port);
+
+    service
+        .up_opts(ServiceUpOpts {
+            ports: Some(vec![PortForward {
+                backend: options.port as isize,
+                frontend: host_port as isize,
+                protocol: NetworkProtocol::Tcp,
+            }]),
+            random: None,
+        })
+        .await
+        .context("app service stopped")?;
+    Ok(())
+}
+
 /// Screenshot multiple apps in batch with controlled concurrency
 pub async fn screenshot_apps_batch(
     client: &DaggerConn,
@@ -188,18 +241,8 @@ pub async fn screenshot_apps_batch(
             tracing::info!("[app-{}] Screenshotting", i);
 
             // bind service and run playwright for this specific app
-            let container = playwright_clone
-                .with_service_binding("app", service)
-                .with_env_variable("TARGET_URL", &options_clone.url)
-                .with_env_variable("TARGET_PORT", options_clone.port.to_string())
-                .with_env_variable("WAIT_TIME", options_clone.wait_time_ms.to_string())
-                .with_env_variable("CACHE_BUST", &cache_bust_clone)
-                .with_exec(vec![
-                    "npx",
-                    "playwright",
-                    "test",
-                    "--config=playwright.single.config.ts",
-                ]);
+            let container =
+                capture_container(playwright_clone, service, &options_clone, &cache_bust_clone);
 
             // execute and capture result
             match container.sync().await {
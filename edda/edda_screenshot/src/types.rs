@@ -1,9 +1,35 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Image of the page at `url`
+pub const SCREENSHOT_FILE: &str = "screenshot.png";
+/// Grid of every route's image, written when more than one route is captured
+pub const CONTACT_SHEET_FILE: &str = "contact-sheet.png";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScreenshotOptions {
     /// URL path to navigate to (default: "/")
     pub url: String,
+    /// More URL paths to capture after `url`, one image per route (default: none)
+    pub routes: Vec<String>,
+    /// Screens to capture every route on, the first one gives the unsuffixed images
+    /// (default: desktop only)
+    pub viewports: Vec<Viewport>,
+    /// Steps run on the page at `url` before it's captured, e.g. to log in or switch to a
+    /// tab. Other routes are captured afterwards in the same browser session (default: none)
+    pub interactions: Vec<InteractionStep>,
+    /// Record every request with its status and timing to `network.json` (default: false)
+    pub record_network: bool,
+    /// Run axe-core on every captured page and write the violations to `a11y.json`
+    /// (default: false)
+    pub accessibility_audit: bool,
+    /// CSS selector of an element (a chart, a table) to capture instead of the whole page,
+    /// the first match is used (default: none)
+    pub selector: Option<String>,
+    /// Record the first page from the moment it starts loading, to see errors that flash
+    /// and disappear before the screenshot (default: none)
+    pub recording: Option<Recording>,
     /// Port the service listens on (default: 8000)
     pub port: u16,
     /// Maximum timeout to wait for network idle in ms (default: 30000)
@@ -16,9 +42,237 @@ impl Default for ScreenshotOptions {
     fn default() -> Self {
         Self {
             url: "/".to_string(),
+            routes: vec![],
+            viewports: vec![],
+            interactions: vec![],
+            record_network: false,
+            accessibility_audit: false,
+            selector: None,
+            recording: None,
             port: 8000,
             wait_time_ms: 30000,
             env_vars: vec![],
         }
     }
 }
+
+/// One step of a pre-capture interaction script, written as e.g.
+/// `{"action": "click", "selector": "text=Sign in"}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum InteractionStep {
+    /// Wait until an element matching `selector` is visible
+    WaitForSelector {
+        selector: String,
+    },
+    Click {
+        selector: String,
+    },
+    /// Type `value` into the input matching `selector`
+    Fill {
+        selector: String,
+        value: String,
+    },
+    /// Go to a URL path of the app
+    Navigate {
+        url: String,
+    },
+}
+
+/// What `options.recording` captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Recording {
+    /// WebM video of the page
+    Video,
+    /// Playwright trace with DOM snapshots, console and network, opened with
+    /// `npx playwright show-trace`
+    Trace,
+}
+
+impl Recording {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Video => "video",
+            Self::Trace => "trace",
+        }
+    }
+
+    /// File the recording is written to
+    pub fn file(&self) -> &'static str {
+        match self {
+            Self::Video => "recording.webm",
+            Self::Trace => "trace.zip",
+        }
+    }
+}
+
+impl FromStr for Recording {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "video" => Ok(Self::Video),
+            "trace" => Ok(Self::Trace),
+            other => Err(format!(
+                "unknown recording '{}', expected video or trace",
+                other
+            )),
+        }
+    }
+}
+
+/// Screen a page is captured on, written as its name: "desktop", "tablet", "mobile" or a
+/// device name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(from = "String", into = "String")]
+pub enum Viewport {
+    /// The browser's default 1280x720 window
+    Desktop,
+    /// iPad (gen 7), portrait
+    Tablet,
+    /// iPhone 13, with touch and a mobile user agent
+    Mobile,
+    /// Any Playwright device descriptor, e.g. "Pixel 7" or "Galaxy Tab S4 landscape"
+    Device(String),
+}
+
+impl Viewport {
+    /// Playwright device emulated for this viewport, None for the default browser window
+    pub fn device(&self) -> Option<&str> {
+        match self {
+            Self::Desktop => None,
+            Self::Tablet => Some("iPad (gen 7)"),
+            Self::Mobile => Some("iPhone 13"),
+            Self::Device(name) => Some(name),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Desktop => "desktop",
+            Self::Tablet => "tablet",
+            Self::Mobile => "mobile",
+            Self::Device(name) => name,
+        }
+    }
+}
+
+impl FromStr for Viewport {
+    type Err = std::convert::Infallible;
+
+    /// "desktop", "tablet" and "mobile" are presets, anything else is a device name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_lowercase().as_str() {
+            "desktop" => Self::Desktop,
+            "tablet" => Self::Tablet,
+            "mobile" => Self::Mobile,
+            _ => Self::Device(s.trim().to_string()),
+        })
+    }
+}
+
+impl From<String> for Viewport {
+    fn from(name: String) -> Self {
+        match name.parse() {
+            Ok(viewport) => viewport,
+        }
+    }
+}
+
+impl From<Viewport> for String {
+    fn from(viewport: Viewport) -> Self {
+        viewport.name().to_string()
+    }
+}
+
+/// A page to capture and the file its image is saved as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteCapture {
+    pub route: String,
+    /// Name of the viewport, None for the first one
+    pub viewport: Option<String>,
+    /// Playwright device to emulate, None for the default browser window
+    pub device: Option<String>,
+    pub file: String,
+}
+
+impl ScreenshotOptions {
+    /// Every page to capture: `url` first, saved as screenshot.png, then each of `routes`
+    /// not already listed. With more than one viewport, the routes are captured again on
+    /// each of the others, their files suffixed with the viewport name.
+    pub fn captures(&self) -> Vec<RouteCapture> {
+        let mut routes = vec![(self.url.clone(), SCREENSHOT_FILE.to_string())];
+        for route in &self.routes {
+            if routes.iter().any(|(r, _)| r == route) {
+                continue;
+            }
+            // distinct routes can share a slug, e.g. "/a-b" and "/a/b"
+            let files: Vec<&str> = routes.iter().map(|(_, f)| f.as_str()).collect();
+            let file = unique_file(&files, &format!("screenshot-{}", slug(route, "root")));
+            routes.push((route.clone(), file));
+        }
+
+        let mut viewports: Vec<&Viewport> = vec![];
+        for viewport in &self.viewports {
+            if !viewports.contains(&viewport) {
+                viewports.push(viewport);
+            }
+        }
+        if viewports.is_empty() {
+            viewports.push(&Viewport::Desktop);
+        }
+
+        let mut captures: Vec<RouteCapture> = vec![];
+        for (i, viewport) in viewports.into_iter().enumerate() {
+            for (route, file) in &routes {
+                let file = match i {
+                    0 => file.clone(),
+                    _ => {
+                        let files: Vec<&str> = captures.iter().map(|c| c.file.as_str()).collect();
+                        let stem = file.trim_end_matches(".png");
+                        unique_file(
+                            &files,
+                            &format!("{}-{}", stem, slug(viewport.name(), "device")),
+                        )
+                    }
+                };
+                captures.push(RouteCapture {
+                    route: route.clone(),
+                    viewport: (i > 0).then(|| viewport.name().to_string()),
+                    device: viewport.device().map(str::to_string),
+                    file,
+                });
+            }
+        }
+        captures
+    }
+}
+
+/// `stem`.png, or `stem`-2.png, `stem`-3.png... if taken
+fn unique_file(taken: &[&str], stem: &str) -> String {
+    let mut file = format!("{}.png", stem);
+    let mut n = 1;
+    while taken.contains(&file.as_str()) {
+        n += 1;
+        file = format!("{}-{}.png", stem, n);
+    }
+    file
+}
+
+/// Lowercase alphanumeric runs joined by dashes, e.g. "/reports/sales?year=2024" ->
+/// reports-sales-year-2024
+fn slug(text: &str, empty: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    match slug.trim_end_matches('-') {
+        "" => empty.to_string(),
+        slug => slug.to_string(),
+    }
+}
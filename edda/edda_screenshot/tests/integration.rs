@@ -1,4 +1,10 @@
-use edda_screenshot::ScreenshotOptions;
+use edda_screenshot::a11y::{a11y_summary, parse_a11y};
+use edda_screenshot::daemon::CaptureRequest;
+use edda_screenshot::diff::{DiffOptions, annotate, diff_images};
+use edda_screenshot::logs::{LogEntry, LogLevel, LogPolicy, parse_logs};
+use edda_screenshot::network::{network_summary, parse_network};
+use edda_screenshot::{InteractionStep, Recording, RouteCapture, ScreenshotOptions, Viewport};
+use image::{Rgba, RgbaImage};
 
 #[test]
 fn test_screenshot_options_default() {
@@ -15,6 +21,13 @@ fn test_screenshot_options_custom() {
         port: 3000,
         wait_time_ms: 5000,
         url: "/health".to_string(),
+        routes: vec!["/reports".to_string()],
+        viewports: vec![Viewport::Mobile],
+        interactions: vec![],
+        record_network: true,
+        accessibility_audit: true,
+        selector: Some("#revenue-chart".to_string()),
+        recording: Some(Recording::Trace),
         env_vars: vec![("KEY".to_string(), "VALUE".to_string())],
     };
 
@@ -24,6 +37,351 @@ fn test_screenshot_options_custom() {
     assert_eq!(options.env_vars.len(), 1);
 }
 
+fn capture(route: &str, viewport: Option<&str>, device: Option<&str>, file: &str) -> RouteCapture {
+    RouteCapture {
+        route: route.to_string(),
+        viewport: viewport.map(str::to_string),
+        device: device.map(str::to_string),
+        file: file.to_string(),
+    }
+}
+
+#[test]
+fn test_screenshot_options_captures() {
+    let options = ScreenshotOptions {
+        routes: vec![
+            "/".to_string(),
+            "/reports/sales?year=2024".to_string(),
+            "/a-b".to_string(),
+            "/a/b".to_string(),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        options.captures(),
+        vec![
+            capture("/", None, None, "screenshot.png"),
+            capture(
+                "/reports/sales?year=2024",
+                None,
+                None,
+                "screenshot-reports-sales-year-2024.png"
+            ),
+            capture("/a-b", None, None, "screenshot-a-b.png"),
+            capture("/a/b", None, None, "screenshot-a-b-2.png"),
+        ]
+    );
+}
+
+#[test]
+fn test_screenshot_options_viewports() {
+    let options = ScreenshotOptions {
+        routes: vec!["/reports".to_string()],
+        viewports: vec![
+            Viewport::Desktop,
+            "Mobile".parse().unwrap(),
+            "Pixel 7".parse().unwrap(),
+        ],
+        ..Default::default()
+    };
+
+    assert_eq!(
+        options.captures(),
+        vec![
+            capture("/", None, None, "screenshot.png"),
+            capture("/reports", None, None, "screenshot-reports.png"),
+            capture(
+                "/",
+                Some("mobile"),
+                Some("iPhone 13"),
+                "screenshot-mobile.png"
+            ),
+            capture(
+                "/reports",
+                Some("mobile"),
+                Some("iPhone 13"),
+                "screenshot-reports-mobile.png"
+            ),
+            capture(
+                "/",
+                Some("Pixel 7"),
+                Some("Pixel 7"),
+                "screenshot-pixel-7.png"
+            ),
+            capture(
+                "/reports",
+                Some("Pixel 7"),
+                Some("Pixel 7"),
+                "screenshot-reports-pixel-7.png"
+            ),
+        ]
+    );
+
+    let json = serde_json::to_string(&options.viewports).unwrap();
+    assert_eq!(json, r#"["desktop","mobile","Pixel 7"]"#);
+    let parsed: Vec<Viewport> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, options.viewports);
+
+    // the first viewport gives the unsuffixed images
+    let options = ScreenshotOptions {
+        viewports: vec![Viewport::Tablet],
+        ..Default::default()
+    };
+    assert_eq!(
+        options.captures(),
+        vec![capture("/", None, Some("iPad (gen 7)"), "screenshot.png")]
+    );
+}
+
+#[test]
+fn test_interaction_steps_json() {
+    let json = r##"[
+        {"action": "wait_for_selector", "selector": "#login"},
+        {"action": "fill", "selector": "#user", "value": "demo"},
+        {"action": "click", "selector": "text=Sign in"},
+        {"action": "navigate", "url": "/reports"}
+    ]"##;
+    let steps: Vec<InteractionStep> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        steps,
+        vec![
+            InteractionStep::WaitForSelector {
+                selector: "#login".to_string()
+            },
+            InteractionStep::Fill {
+                selector: "#user".to_string(),
+                value: "demo".to_string()
+            },
+            InteractionStep::Click {
+                selector: "text=Sign in".to_string()
+            },
+            InteractionStep::Navigate {
+                url: "/reports".to_string()
+            },
+        ]
+    );
+    assert!(serde_json::from_str::<Vec<InteractionStep>>(r#"[{"action": "hover"}]"#).is_err());
+}
+
+#[test]
+fn test_parse_logs() {
+    let logs = "\
+2025-01-01T00:00:00.000Z [LOG] Loading dashboard
+2025-01-01T00:00:01.000Z [WARNING] Slow query
+2025-01-01T00:00:02.000Z [PAGEERROR] Cannot read properties of undefined (reading 'map')
+    at Dashboard (App.tsx:12)
+2025-01-01T00:00:03.000Z [NETWORK] 404 GET http://10.0.0.2:8000/favicon.ico";
+    let entries = parse_logs(logs);
+    let levels: Vec<LogLevel> = entries.iter().map(|e| e.level).collect();
+    assert_eq!(
+        levels,
+        vec![
+            LogLevel::Log,
+            LogLevel::Warn,
+            LogLevel::PageError,
+            LogLevel::Network
+        ]
+    );
+    assert_eq!(
+        entries[2].message,
+        "Cannot read properties of undefined (reading 'map')\n    at Dashboard (App.tsx:12)"
+    );
+    let request = entries[3].failed_request().unwrap();
+    assert_eq!(request.status, Some(404));
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path(), "/favicon.ico");
+    assert!(entries[0].failed_request().is_none());
+    assert!(parse_logs("").is_empty());
+}
+
+#[test]
+fn test_log_policy() {
+    let entry = |level, message: &str| LogEntry {
+        timestamp: "2025-01-01T00:00:00.000Z".to_string(),
+        level,
+        message: message.to_string(),
+    };
+    let entries = vec![
+        entry(
+            LogLevel::Error,
+            "Warning: Each child in a list should have a unique key",
+        ),
+        entry(LogLevel::Network, "404 GET http://10.0.0.2:8000/logo.svg"),
+        entry(
+            LogLevel::Network,
+            "401 GET http://10.0.0.2:8000/api/auth.me",
+        ),
+        entry(
+            LogLevel::Network,
+            "500 GET http://10.0.0.2:8000/api/users.list?batch=1",
+        ),
+        entry(
+            LogLevel::Network,
+            "FAILED POST http://10.0.0.2:8000/api/users.create net::ERR_EMPTY_RESPONSE",
+        ),
+        entry(LogLevel::PageError, "Cannot read properties of undefined"),
+        // a query cancelled when the page navigates away
+        entry(
+            LogLevel::Network,
+            "FAILED GET http://10.0.0.2:8000/api/users.list net::ERR_ABORTED",
+        ),
+    ];
+
+    let violations = LogPolicy::default().violations(&entries);
+    assert_eq!(violations, vec![&entries[3], &entries[4], &entries[5]]);
+    let aborted = entries[6].failed_request().unwrap();
+    assert!(aborted.aborted());
+    assert_eq!(aborted.error, Some("net::ERR_ABORTED"));
+    assert!(!entries[4].failed_request().unwrap().aborted());
+
+    let lenient = LogPolicy {
+        fail_on_page_errors: false,
+        failing_request_paths: vec![],
+    };
+    assert!(lenient.violations(&entries).is_empty());
+
+    // unset fields keep their defaults
+    let policy: LogPolicy = serde_json::from_str(r#"{"fail_on_page_errors": false}"#).unwrap();
+    assert_eq!(policy.failing_request_paths, vec!["/api"]);
+    assert_eq!(policy.violations(&entries).len(), 2);
+}
+
+#[test]
+fn test_network_summary() {
+    let json = r#"[
+        {"method": "GET", "url": "http://10.0.0.2:8000/", "resource_type": "document", "status": 200, "duration_ms": 35, "failure": null},
+        {"method": "GET", "url": "http://10.0.0.2:8000/logo.svg", "resource_type": "image", "status": 404, "duration_ms": 4, "failure": null},
+        {"method": "GET", "url": "http://10.0.0.2:8000/api/sales.list", "resource_type": "fetch", "status": 200, "duration_ms": 2450.5, "failure": null},
+        {"method": "POST", "url": "http://10.0.0.2:8000/api/sales.create", "resource_type": "fetch", "status": null, "duration_ms": null, "failure": "net::ERR_EMPTY_RESPONSE"}
+    ]"#;
+    let requests = parse_network(json).unwrap();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[2].duration_ms, Some(2450.5));
+    assert!(!requests[0].failed());
+    assert!(requests[1].failed());
+    assert!(requests[3].failed());
+
+    assert_eq!(
+        network_summary(&requests),
+        "4 requests, 2 failed, 1 slower than 1000ms
+  404 GET http://10.0.0.2:8000/logo.svg
+  net::ERR_EMPTY_RESPONSE POST http://10.0.0.2:8000/api/sales.create
+  2450ms GET http://10.0.0.2:8000/api/sales.list"
+    );
+    assert!(parse_network("not json").is_err());
+}
+
+#[test]
+fn test_a11y_report() {
+    let json = r##"[
+        {"route": "/", "viewport": null, "id": "region", "impact": "moderate", "description": "All page content should be contained by landmarks", "help_url": "https://dequeuniversity.com/rules/axe/4.8/region", "targets": ["#root > div"]},
+        {"route": "/reports", "viewport": "mobile", "id": "color-contrast", "impact": "serious", "description": "Elements must meet minimum color contrast ratio thresholds", "help_url": "https://dequeuniversity.com/rules/axe/4.8/color-contrast", "targets": [".badge", ".muted", "th"]}
+    ]"##;
+    let violations = parse_a11y(json).unwrap();
+    assert_eq!(violations[0].id, "color-contrast");
+    assert_eq!(violations[1].id, "region");
+
+    assert_eq!(
+        a11y_summary(&violations),
+        "[serious] color-contrast on /reports (mobile): Elements must meet minimum color contrast ratio thresholds (3 elements)
+[moderate] region on /: All page content should be contained by landmarks (1 element)"
+    );
+    assert_eq!(a11y_summary(&[]), "");
+}
+
+#[test]
+fn test_capture_request_json() {
+    let options = ScreenshotOptions {
+        routes: vec!["/reports".to_string()],
+        interactions: vec![InteractionStep::Click {
+            selector: "text=Revenue".to_string(),
+        }],
+        record_network: true,
+        ..Default::default()
+    };
+    let request = CaptureRequest::new(&options, 41000);
+    assert_eq!(request.port, 41000);
+    assert_eq!(request.captures, options.captures());
+
+    // field names read by daemon.spec.ts
+    let json = serde_json::to_value(&request).unwrap();
+    assert_eq!(json["url"], "/");
+    assert_eq!(json["wait_time_ms"], 30000);
+    assert_eq!(json["record_network"], true);
+    assert_eq!(json["accessibility_audit"], false);
+    assert_eq!(json["selector"], serde_json::Value::Null);
+    assert_eq!(json["recording"], serde_json::Value::Null);
+    assert_eq!(json["captures"][1]["file"], "screenshot-reports.png");
+    assert_eq!(json["interactions"][0]["action"], "click");
+}
+
+#[test]
+fn test_recording() {
+    assert_eq!("video".parse::<Recording>(), Ok(Recording::Video));
+    assert_eq!(" Trace ".parse::<Recording>(), Ok(Recording::Trace));
+    assert!("gif".parse::<Recording>().is_err());
+    assert_eq!(Recording::Video.file(), "recording.webm");
+    assert_eq!(Recording::Trace.file(), "trace.zip");
+
+    // written as its name, as daemon.spec.ts and the config expect
+    assert_eq!(
+        serde_json::to_string(&Recording::Trace).unwrap(),
+        r#""trace""#
+    );
+    let recording: Recording = serde_json::from_str(r#""video""#).unwrap();
+    assert_eq!(recording, Recording::Video);
+}
+
+#[test]
+fn test_diff_images() {
+    let white = Rgba([255, 255, 255, 255]);
+    let before = RgbaImage::from_pixel(200, 100, white);
+    let options = DiffOptions::default();
+
+    // anti-aliasing noise below the threshold is ignored
+    let mut noisy = before.clone();
+    noisy.put_pixel(10, 10, Rgba([250, 250, 250, 255]));
+    let report = diff_images(&before, &noisy, &options);
+    assert_eq!(report.changed_pixels, 0);
+    assert!(!report.differs);
+
+    // two separate changed blocks are two regions
+    let mut after = before.clone();
+    for y in 0..20 {
+        for x in 0..20 {
+            after.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        }
+    }
+    for y in 70..80 {
+        for x in 150..160 {
+            after.put_pixel(x, y, Rgba([0, 0, 255, 255]));
+        }
+    }
+    let report = diff_images(&before, &after, &options);
+    assert!(report.differs);
+    assert_eq!(report.changed_pixels, 500);
+    assert_eq!(report.regions.len(), 2);
+    assert_eq!((report.regions[0].x, report.regions[0].y), (0, 0));
+    assert_eq!(report.regions[0].changed_pixels, 400);
+    assert_eq!((report.regions[1].x, report.regions[1].y), (128, 64));
+    assert_eq!(
+        (report.regions[1].width, report.regions[1].height),
+        (32, 32)
+    );
+
+    let annotated = annotate(&after, &report);
+    assert_eq!(annotated.get_pixel(140, 64), &Rgba([255, 0, 0, 255]));
+    assert_eq!(annotated.get_pixel(140, 80), &white);
+
+    // a taller page differs even where the overlap is identical
+    let taller = RgbaImage::from_pixel(200, 120, white);
+    let report = diff_images(&before, &taller, &options);
+    assert!(report.differs);
+    assert_eq!(report.changed_pixels, 200 * 20);
+    assert_eq!(report.regions.len(), 1);
+}
+
 /// Smoke test using the trpc template from the repo
 /// Run with: cargo test --features dagger test_screenshot_smoke
 #[tokio::test]
@@ -31,26 +389,22 @@ fn test_screenshot_options_custom() {
 async fn test_screenshot_smoke() {
     use edda_sandbox::dagger::ConnectOpts;
     use edda_screenshot::screenshot_app;
-    use std::path::PathBuf;
-
-    // use the trpc template from the repo
-    let template_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .parent()
-        .unwrap()
-        .join("edda_templates")
-        .join("template_trpc");
-
-    if !template_dir.exists() {
-        panic!(
-            "Template not found at {}. This test expects the edda_templates crate.",
-            template_dir.display()
-        );
-    }
+    use edda_templates::{TemplateCore, TemplateTRPC, TemplateVars};
+
+    // scaffold the trpc template, its files have placeholders until extracted
+    let template_dir = tempfile::tempdir().unwrap();
+    let vars = TemplateVars {
+        project_name: Some("smoke-test".to_string()),
+        ..Default::default()
+    };
+    TemplateTRPC
+        .extract(template_dir.path(), &vars)
+        .expect("Template should extract");
 
     let output_dir = std::env::temp_dir().join("screenshot-smoke-test");
     std::fs::create_dir_all(&output_dir).unwrap();
 
-    let template_dir_str = template_dir.to_string_lossy().to_string();
+    let template_dir_str = template_dir.path().to_string_lossy().to_string();
     let output_str = output_dir.to_string_lossy().to_string();
 
     println!("Template directory: {}", template_dir_str);
@@ -60,8 +414,14 @@ async fn test_screenshot_smoke() {
         port: 8000,
         wait_time_ms: 30000,
         env_vars: vec![
-            ("DATABRICKS_HOST".to_string(), "https://example.databricks.com".to_string()),
-            ("DATABRICKS_TOKEN".to_string(), "dummy_token_for_test".to_string()),
+            (
+                "DATABRICKS_HOST".to_string(),
+                "https://example.databricks.com".to_string(),
+            ),
+            (
+                "DATABRICKS_TOKEN".to_string(),
+                "dummy_token_for_test".to_string(),
+            ),
         ],
         ..Default::default()
     };
@@ -91,7 +451,10 @@ async fn test_screenshot_smoke() {
     assert!(logs_path.exists(), "logs.txt should exist");
 
     let screenshot_size = std::fs::metadata(&screenshot_path).unwrap().len();
-    assert!(screenshot_size > 1000, "Screenshot should have reasonable size");
+    assert!(
+        screenshot_size > 1000,
+        "Screenshot should have reasonable size"
+    );
 
     println!("✓ Smoke test passed");
     println!("  Screenshot: {}", screenshot_path.display());
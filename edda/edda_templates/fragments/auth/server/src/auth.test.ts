@@ -0,0 +1,26 @@
+import { test } from "node:test";
+import { strict as assert } from "node:assert";
+import { TRPCError } from "@trpc/server";
+import { requireUser, userFromHeaders } from "./auth";
+
+test("reads the user from Databricks Apps headers", () => {
+  const user = userFromHeaders({
+    "x-forwarded-email": "ada@example.com",
+    "x-forwarded-access-token": "token",
+  });
+  assert.deepEqual(user, { email: "ada@example.com", accessToken: "token" });
+});
+
+test("requests without identity headers are anonymous", () => {
+  const previous = process.env["DEV_USER_EMAIL"];
+  delete process.env["DEV_USER_EMAIL"];
+  try {
+    const user = userFromHeaders({});
+    assert.equal(user, null);
+    assert.throws(() => requireUser({ user }), TRPCError);
+  } finally {
+    if (previous !== undefined) {
+      process.env["DEV_USER_EMAIL"] = previous;
+    }
+  }
+});
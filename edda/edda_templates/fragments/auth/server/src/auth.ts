@@ -0,0 +1,40 @@
+import { TRPCError } from "@trpc/server";
+import type { CreateExpressContextOptions } from "@trpc/server/adapters/express";
+
+export interface User {
+  email: string;
+  // on-behalf-of token, only present when user authorization is enabled for the app
+  accessToken: string | null;
+}
+
+export interface Context {
+  user: User | null;
+}
+
+type Headers = Record<string, string | string[] | undefined>;
+
+function header(headers: Headers, name: string): string | null {
+  const value = headers[name];
+  const first = Array.isArray(value) ? value[0] : value;
+  return first && first.trim() !== "" ? first.trim() : null;
+}
+
+// Databricks Apps forwards the signed-in user in X-Forwarded-* headers
+export function userFromHeaders(headers: Headers): User | null {
+  const email = header(headers, "x-forwarded-email") ?? process.env["DEV_USER_EMAIL"] ?? null;
+  if (!email) {
+    return null;
+  }
+  return { email, accessToken: header(headers, "x-forwarded-access-token") };
+}
+
+export function createContext({ req }: CreateExpressContextOptions): Context {
+  return { user: userFromHeaders(req.headers) };
+}
+
+export function requireUser(ctx: Context): User {
+  if (!ctx.user) {
+    throw new TRPCError({ code: "UNAUTHORIZED", message: "Sign in required" });
+  }
+  return ctx.user;
+}
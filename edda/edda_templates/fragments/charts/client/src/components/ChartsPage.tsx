@@ -0,0 +1,19 @@
+import { CategoryBarChart, TimeSeriesChart } from "@/components/charts";
+
+export interface ChartsPageProps {
+  timeSeries: { date: string; [metric: string]: string | number }[];
+  metrics: string[];
+  categories: { category: string; value: number }[];
+}
+
+export function ChartsPage({ timeSeries, metrics, categories }: ChartsPageProps) {
+  return (
+    <div className="container mx-auto p-4">
+      <h1 className="text-2xl font-bold mb-4">Overview</h1>
+      <div className="grid gap-4 md:grid-cols-2">
+        <TimeSeriesChart title="Trend" data={timeSeries} xKey="date" series={metrics} />
+        <CategoryBarChart title="By category" data={categories} categoryKey="category" valueKey="value" />
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,88 @@
+import type { ReactElement } from "react";
+import {
+  Bar,
+  BarChart,
+  CartesianGrid,
+  Legend,
+  Line,
+  LineChart,
+  ResponsiveContainer,
+  Tooltip,
+  XAxis,
+  YAxis,
+} from "recharts";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+
+const COLORS = ["#2563eb", "#16a34a", "#ea580c", "#9333ea", "#0891b2", "#dc2626"];
+
+type Row = Record<string, string | number | null>;
+
+function ChartCard({ title, empty, children }: { title: string; empty: boolean; children: ReactElement }) {
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle>{title}</CardTitle>
+      </CardHeader>
+      <CardContent className="h-72">
+        {empty ? (
+          <p className="text-sm text-foreground/70">No data for the selected period.</p>
+        ) : (
+          <ResponsiveContainer width="100%" height="100%">
+            {children}
+          </ResponsiveContainer>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
+
+export function TimeSeriesChart({
+  title,
+  data,
+  xKey,
+  series,
+}: {
+  title: string;
+  data: Row[];
+  xKey: string;
+  series: string[];
+}) {
+  return (
+    <ChartCard title={title} empty={data.length === 0}>
+      <LineChart data={data}>
+        <CartesianGrid strokeDasharray="3 3" />
+        <XAxis dataKey={xKey} />
+        <YAxis />
+        <Tooltip />
+        <Legend />
+        {series.map((key, index) => (
+          <Line key={key} type="monotone" dataKey={key} stroke={COLORS[index % COLORS.length]} dot={false} />
+        ))}
+      </LineChart>
+    </ChartCard>
+  );
+}
+
+export function CategoryBarChart({
+  title,
+  data,
+  categoryKey,
+  valueKey,
+}: {
+  title: string;
+  data: Row[];
+  categoryKey: string;
+  valueKey: string;
+}) {
+  return (
+    <ChartCard title={title} empty={data.length === 0}>
+      <BarChart data={data}>
+        <CartesianGrid strokeDasharray="3 3" />
+        <XAxis dataKey={categoryKey} />
+        <YAxis />
+        <Tooltip />
+        <Bar dataKey={valueKey} fill={COLORS[0]} />
+      </BarChart>
+    </ChartCard>
+  );
+}
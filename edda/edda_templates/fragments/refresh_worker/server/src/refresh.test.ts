@@ -0,0 +1,32 @@
+import { test } from "node:test";
+import { strict as assert } from "node:assert";
+import { RefreshWorker } from "./refresh";
+
+test("keeps the last successful result when a refresh fails", async () => {
+  let calls = 0;
+  const worker = new RefreshWorker(async () => {
+    calls += 1;
+    if (calls === 2) {
+      throw new Error("warehouse unavailable");
+    }
+    return calls;
+  }, 60_000);
+
+  await worker.refresh();
+  assert.equal(worker.snapshot().data, 1);
+
+  await worker.refresh();
+  assert.equal(worker.snapshot().data, 1);
+  assert.equal(worker.snapshot().error, "warehouse unavailable");
+
+  await worker.refresh();
+  assert.equal(worker.snapshot().data, 3);
+  assert.equal(worker.snapshot().error, null);
+});
+
+test("concurrent refreshes run the task once", async () => {
+  let calls = 0;
+  const worker = new RefreshWorker(async () => ++calls, 60_000);
+  await Promise.all([worker.refresh(), worker.refresh()]);
+  assert.equal(calls, 1);
+});
@@ -0,0 +1,57 @@
+export interface Snapshot<T> {
+  data: T | null;
+  refreshedAt: Date | null;
+  // message of the last failed refresh, cleared by the next successful one
+  error: string | null;
+}
+
+export class RefreshWorker<T> {
+  private timer: ReturnType<typeof setInterval> | null = null;
+  private running: Promise<void> | null = null;
+  private state: Snapshot<T> = { data: null, refreshedAt: null, error: null };
+
+  constructor(
+    private readonly task: () => Promise<T>,
+    private readonly intervalMs: number,
+  ) {}
+
+  start(): void {
+    if (this.timer) {
+      return;
+    }
+    void this.refresh();
+    this.timer = setInterval(() => void this.refresh(), this.intervalMs);
+    // don't keep the process alive just for refreshes
+    this.timer.unref();
+  }
+
+  stop(): void {
+    if (this.timer) {
+      clearInterval(this.timer);
+      this.timer = null;
+    }
+  }
+
+  // runs the task now; concurrent calls share the in-flight run
+  refresh(): Promise<void> {
+    if (!this.running) {
+      this.running = this.task()
+        .then((data) => {
+          this.state = { data, refreshedAt: new Date(), error: null };
+        })
+        .catch((err: unknown) => {
+          // keep serving the previous data
+          this.state = { ...this.state, error: err instanceof Error ? err.message : String(err) };
+          console.error("Refresh failed:", err);
+        })
+        .finally(() => {
+          this.running = null;
+        });
+    }
+    return this.running;
+  }
+
+  snapshot(): Snapshot<T> {
+    return this.state;
+  }
+}
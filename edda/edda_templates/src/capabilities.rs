@@ -0,0 +1,233 @@
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Component, Path};
+
+/// Manifest at the root of a template, extracted with it so a scaffolded project still
+/// knows how it is validated and deployed
+pub const MANIFEST_FILE: &str = "edda.json";
+
+const DEFAULT_NODE: &str = "20";
+const DEFAULT_PYTHON: &str = "3.12";
+const DEFAULT_RUST: &str = "1.85";
+
+/// What a template needs and how its apps are checked and shipped. Templates without a
+/// manifest get the defaults: tRPC validation on Node 20, deployed as a Databricks App.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateCapabilities {
+    /// Providers the generated apps rely on, by their config name (e.g. "DatabricksRest")
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub required_providers: Vec<String>,
+    pub validation: ValidationSpec,
+    pub deploy: DeployMode,
+    /// Node.js version the template is built for (default: 20)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    /// Python version the template is built for (default: 3.12)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub python: Option<String>,
+    /// Rust toolchain version the template is built for (default: 1.85)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rust: Option<String>,
+    /// Setup commands run after the template is extracted, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<Hook>,
+}
+
+/// Post-scaffold step, e.g. `npm install` or generating an env file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hook {
+    /// Shell command run from the project root
+    pub command: String,
+    #[serde(default)]
+    pub run_in: HookTarget,
+    /// Files produced by a sandbox hook that are copied back into the project
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub export: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookTarget {
+    /// In the template's validation image, with the project mounted at /app
+    #[default]
+    Sandbox,
+    /// On the host with the user's permissions
+    Host,
+}
+
+impl fmt::Display for HookTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookTarget::Sandbox => write!(f, "sandbox"),
+            HookTarget::Host => write!(f, "host"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum ValidationSpec {
+    /// Build, type check and tests of the client/ and server/ npm packages
+    #[default]
+    Trpc,
+    /// pip install, compile check and pytest
+    Python,
+    /// cargo check and cargo test
+    Rust,
+    /// Custom command run in /app of `docker_image`
+    Command {
+        command: String,
+        docker_image: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployMode {
+    #[default]
+    DatabricksApp,
+    /// Runs from its Dockerfile only
+    Docker,
+    None,
+}
+
+impl TemplateCapabilities {
+    pub fn parse(content: &[u8]) -> Result<Self> {
+        let capabilities: Self = serde_json::from_slice(content)
+            .map_err(|e| eyre::eyre!("invalid template manifest {}: {}", MANIFEST_FILE, e))?;
+        // exports are copied into the project, they can't point outside of it
+        for hook in &capabilities.hooks {
+            let outside = hook.export.iter().find(|path| {
+                Path::new(path)
+                    .components()
+                    .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+            });
+            if let Some(path) = outside {
+                return Err(eyre::eyre!(
+                    "invalid template manifest {}: hook `{}` exports {}, exports must be relative paths inside the project",
+                    MANIFEST_FILE,
+                    hook.command,
+                    path
+                ));
+            }
+        }
+        Ok(capabilities)
+    }
+
+    /// Manifest of the project at `work_dir`, `None` if its template has none
+    pub fn load(work_dir: &Path) -> Result<Option<Self>> {
+        match std::fs::read(work_dir.join(MANIFEST_FILE)) {
+            Ok(content) => Self::parse(&content).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn runtime(&self) -> Option<(&'static str, &str)> {
+        match &self.validation {
+            ValidationSpec::Trpc => Some(("node", self.node.as_deref().unwrap_or(DEFAULT_NODE))),
+            ValidationSpec::Python => {
+                Some(("python", self.python.as_deref().unwrap_or(DEFAULT_PYTHON)))
+            }
+            ValidationSpec::Rust => Some(("rust", self.rust.as_deref().unwrap_or(DEFAULT_RUST))),
+            ValidationSpec::Command { .. } => None,
+        }
+    }
+
+    /// Paths, relative to the project root, whose sources make up the validation checksum
+    pub fn source_paths(&self) -> &'static [&'static str] {
+        match &self.validation {
+            ValidationSpec::Trpc => &["client", "server", "package.json"],
+            _ => &["."],
+        }
+    }
+
+    /// Commands run from the project root to build the app before it is deployed
+    pub fn build_commands(&self) -> &'static [&'static [&'static str]] {
+        match &self.validation {
+            ValidationSpec::Trpc => &[&["npm", "install"], &["npm", "run", "build"]],
+            _ => &[],
+        }
+    }
+
+    /// Directory, relative to the project root, that holds `app.yaml` and is synced
+    /// to the workspace on deploy
+    pub fn app_dir(&self) -> &'static str {
+        match &self.validation {
+            ValidationSpec::Trpc => "server",
+            _ => ".",
+        }
+    }
+
+    /// Image the template's validation runs in
+    pub fn sandbox_image(&self) -> String {
+        match &self.validation {
+            ValidationSpec::Trpc => format!(
+                "node:{}-alpine3.22",
+                self.node.as_deref().unwrap_or(DEFAULT_NODE)
+            ),
+            ValidationSpec::Python => format!(
+                "python:{}-slim",
+                self.python.as_deref().unwrap_or(DEFAULT_PYTHON)
+            ),
+            ValidationSpec::Rust => {
+                format!("rust:{}-slim", self.rust.as_deref().unwrap_or(DEFAULT_RUST))
+            }
+            ValidationSpec::Command { docker_image, .. } => docker_image.clone(),
+        }
+    }
+
+    /// Why validation in `image` can't work for this template, if it can't. Only official
+    /// node, python and rust images are recognized, other images are assumed to fit.
+    pub fn image_mismatch(&self, image: &str) -> Option<String> {
+        let (runtime, version) = self.runtime()?;
+        let image_name = image.rsplit('/').next().unwrap_or(image);
+        let (name, tag) = image_name.split_once(':').unwrap_or((image_name, "latest"));
+        if !["node", "python", "rust"].contains(&name) {
+            return None;
+        }
+        if name != runtime {
+            return Some(format!(
+                "needs a {} {} image, {} is configured",
+                runtime, version, image
+            ));
+        }
+        let same_version = tag == version
+            || tag
+                .strip_prefix(version)
+                .is_some_and(|rest| rest.starts_with(['.', '-']));
+        match same_version {
+            true => None,
+            false => Some(format!(
+                "built for {} {}, {} is configured",
+                runtime, version, image
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TemplateCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.validation, self.runtime()) {
+            (ValidationSpec::Command { command, .. }, _) => write!(
+                f,
+                "validated with `{}` in {}",
+                command,
+                self.sandbox_image()
+            )?,
+            (_, Some((runtime, version))) => write!(f, "{} {}", runtime, version)?,
+            (_, None) => {}
+        }
+        match self.deploy {
+            DeployMode::DatabricksApp => write!(f, ", deploys as a Databricks App")?,
+            DeployMode::Docker => write!(f, ", deploys from its Dockerfile")?,
+            DeployMode::None => {}
+        }
+        if !self.required_providers.is_empty() {
+            write!(f, ", needs {}", self.required_providers.join(", "))?;
+        }
+        Ok(())
+    }
+}
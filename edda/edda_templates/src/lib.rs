@@ -1,10 +1,18 @@
+use edda_sandbox::Sandbox;
+use eyre::Result;
 use rust_embed::Embed;
+pub mod capabilities;
 pub mod local;
+pub mod merge;
+pub mod source;
 pub mod template;
+pub use capabilities::{Hook, HookTarget, TemplateCapabilities};
 pub use local::LocalTemplate;
-pub use template::{Template, TemplateCore};
+pub use source::{TemplateCache, TemplateSource};
+pub use template::{Template, TemplateCore, TemplateVars};
+use template::{check_app_starts, run_step};
 
-#[derive(Embed)]
+#[derive(Embed, Clone, Copy)]
 #[folder = "template_trpc"]
 #[exclude = ".git/**"]
 #[exclude = "**/node_modules/**"]
@@ -19,4 +27,102 @@ impl Template for TemplateTRPC {
     fn name(&self) -> String {
         "tRPC TypeScript".to_string()
     }
+
+    async fn smoke_test<S: Sandbox + Send>(&self, sandbox: &mut S) -> Result<()> {
+        run_step(sandbox, "npm run install:all").await?;
+        check_app_starts(
+            sandbox,
+            "cd server && PORT=8000 npm start",
+            "wget -qO- http://localhost:8000/api/healthcheck",
+        )
+        .await
+    }
+}
+
+#[derive(Embed, Clone, Copy)]
+#[folder = "template_py_streamlit"]
+#[exclude = ".git/**"]
+#[exclude = "**/__pycache__/**"]
+#[exclude = ".venv/**"]
+#[exclude = ".pytest_cache/**"]
+#[exclude = "**/.DS_Store"]
+pub struct TemplatePyStreamlit;
+
+impl Template for TemplatePyStreamlit {
+    fn name(&self) -> String {
+        "Streamlit Python".to_string()
+    }
+
+    async fn smoke_test<S: Sandbox + Send>(&self, sandbox: &mut S) -> Result<()> {
+        run_step(sandbox, "pip install --quiet -r requirements.txt").await?;
+        check_app_starts(
+            sandbox,
+            "streamlit run app.py --server.port=8000 --server.headless=true",
+            &python_get("http://localhost:8000/_stcore/health"),
+        )
+        .await
+    }
+}
+
+#[derive(Embed, Clone, Copy)]
+#[folder = "template_py_fastapi"]
+#[exclude = ".git/**"]
+#[exclude = "**/__pycache__/**"]
+#[exclude = ".venv/**"]
+#[exclude = ".pytest_cache/**"]
+#[exclude = "**/.DS_Store"]
+pub struct TemplatePyFastAPI;
+
+impl Template for TemplatePyFastAPI {
+    fn name(&self) -> String {
+        "FastAPI Python".to_string()
+    }
+
+    async fn smoke_test<S: Sandbox + Send>(&self, sandbox: &mut S) -> Result<()> {
+        run_step(sandbox, "pip install --quiet -r requirements.txt").await?;
+        check_app_starts(
+            sandbox,
+            "PORT=8000 python -m app",
+            &python_get("http://localhost:8000/api/health"),
+        )
+        .await
+    }
+}
+
+#[derive(Embed, Clone, Copy)]
+#[folder = "template_rust_axum"]
+#[exclude = ".git/**"]
+#[exclude = "target/**"]
+#[exclude = "Cargo.lock"]
+#[exclude = "**/.DS_Store"]
+pub struct TemplateRustAxum;
+
+impl Template for TemplateRustAxum {
+    fn name(&self) -> String {
+        "Axum Rust".to_string()
+    }
+
+    async fn smoke_test<S: Sandbox + Send>(&self, sandbox: &mut S) -> Result<()> {
+        // the slim image has neither curl nor wget
+        run_step(
+            sandbox,
+            "apt-get update -qq && apt-get install -y -qq --no-install-recommends curl",
+        )
+        .await?;
+        run_step(sandbox, "cargo build --quiet").await?;
+        check_app_starts(
+            sandbox,
+            "PORT=8000 cargo run --quiet",
+            "curl -sf http://localhost:8000/api/health",
+        )
+        .await
+    }
+}
+
+/// Command requesting `url`, the slim Python images have neither curl nor wget
+fn python_get(url: &str) -> String {
+    format!(
+        "python -c \"import urllib.request; urllib.request.urlopen('{}')\"",
+        url
+    )
 }
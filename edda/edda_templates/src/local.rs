@@ -1,7 +1,7 @@
 pub use crate::template::*;
 use eyre::Result;
 use ignore::Walk;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 pub struct LocalTemplate {
     pub name: String,
@@ -42,7 +42,7 @@ impl TemplateCore for LocalTemplate {
         }
     }
 
-    fn extract(&self, work_dir: &Path) -> Result<Vec<PathBuf>> {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>> {
         let mut files = Vec::new();
         for entry in Walk::new(&self.template_dir) {
             if let Ok(entry) = entry {
@@ -54,12 +54,6 @@ impl TemplateCore for LocalTemplate {
             }
         }
         files.sort_by(|a, b| a.0.cmp(&b.0));
-
-        let mut extracted = Vec::new();
-        for (path, content) in files {
-            let written_path = write_file(work_dir, &path, &content)?;
-            extracted.push(written_path);
-        }
-        Ok(extracted)
+        Ok(files)
     }
 }
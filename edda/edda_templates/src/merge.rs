@@ -0,0 +1,289 @@
+use crate::template::{Template, TemplateVars, write_file};
+use eyre::Result;
+use ignore::WalkBuilder;
+use rust_embed::Embed;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// Optional features layered onto an already scaffolded project. Each top-level
+/// directory is one feature; its FEATURE.md describes it and how to wire it up.
+#[derive(Embed)]
+#[folder = "fragments"]
+#[exclude = "**/.DS_Store"]
+struct Fragments;
+
+const FEATURE_DOC: &str = "FEATURE.md";
+
+pub struct Feature {
+    pub name: String,
+    /// First line of FEATURE.md
+    pub summary: String,
+}
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    /// Files that didn't exist in the project
+    pub added: Vec<PathBuf>,
+    /// Files already present with the same content
+    pub unchanged: Vec<PathBuf>,
+    /// Files present with different content, left untouched unless overwriting
+    pub conflicts: Vec<PathBuf>,
+    /// Files replaced because overwriting was requested
+    pub overwritten: Vec<PathBuf>,
+}
+
+fn feature_doc(name: &str) -> Option<String> {
+    Fragments::get(&format!("{}/{}", name, FEATURE_DOC))
+        .map(|file| String::from_utf8_lossy(&file.data).to_string())
+}
+
+pub fn features() -> Vec<Feature> {
+    let mut names: Vec<String> = Fragments::iter()
+        .filter_map(|path| path.split_once('/').map(|(name, _)| name.to_string()))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| Feature {
+            summary: feature_doc(&name)
+                .and_then(|doc| doc.lines().next().map(str::to_string))
+                .unwrap_or_default(),
+            name,
+        })
+        .collect()
+}
+
+/// How to wire the feature into the project after merging (the full FEATURE.md).
+pub fn feature_instructions(name: &str) -> Option<String> {
+    feature_doc(name)
+}
+
+/// Layer `files` (project-relative path, content) onto `work_dir`. Existing files are
+/// never modified unless `overwrite` is set, so agent edits aren't lost.
+pub fn merge_files(
+    work_dir: &Path,
+    files: Vec<(String, Vec<u8>)>,
+    overwrite: bool,
+) -> Result<MergeReport> {
+    let mut report = MergeReport::default();
+    for (path, content) in files {
+        let target = work_dir.join(&path);
+        match std::fs::read(&target) {
+            Ok(existing) if existing == content => report.unchanged.push(target),
+            Ok(_) if !overwrite => report.conflicts.push(target),
+            Ok(_) => report
+                .overwritten
+                .push(write_file(work_dir, &path, &content)?),
+            Err(_) => report.added.push(write_file(work_dir, &path, &content)?),
+        }
+    }
+    Ok(report)
+}
+
+/// Merge the fragment of feature `name` into the project at `work_dir`.
+pub fn merge_feature(work_dir: &Path, name: &str, overwrite: bool) -> Result<MergeReport> {
+    if !work_dir.is_dir() {
+        return Err(eyre::eyre!(
+            "'{}' is not a directory, scaffold the project first",
+            work_dir.display()
+        ));
+    }
+    let prefix = format!("{}/", name);
+    let mut files = Vec::new();
+    for path in Fragments::iter() {
+        let Some(relative) = path.strip_prefix(&prefix) else {
+            continue;
+        };
+        if relative == FEATURE_DOC {
+            continue;
+        }
+        if let Some(file) = Fragments::get(path.as_ref()) {
+            files.push((relative.to_string(), file.data.to_vec()));
+        }
+    }
+    if files.is_empty() {
+        let available: Vec<String> = features().into_iter().map(|f| f.name).collect();
+        return Err(eyre::eyre!(
+            "unknown feature '{}', available features: {}",
+            name,
+            available.join(", ")
+        ));
+    }
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    merge_files(work_dir, files, overwrite)
+}
+
+/// The project's copy of the template files as last scaffolded or upgraded, the common
+/// ancestor when upgrading to a newer template
+pub const TEMPLATE_BASE_DIR: &str = ".edda_template";
+const BASE_FILES_DIR: &str = "files";
+const BASE_VARS_FILE: &str = "vars";
+/// Name of the template the base came from
+const BASE_TEMPLATE_FILE: &str = "template";
+
+#[derive(Debug, Default)]
+pub struct UpgradeReport {
+    /// Files new in the template
+    pub added: Vec<PathBuf>,
+    /// Files changed in the template and not in the project, replaced
+    pub updated: Vec<PathBuf>,
+    /// Files changed in both, the template's changes merged into the project's
+    pub merged: Vec<PathBuf>,
+    /// Files removed from the template and unchanged in the project, deleted
+    pub removed: Vec<PathBuf>,
+    /// Files changed in both where the changes overlap. Text files are written with
+    /// conflict markers, others and files deleted on one side are left as they are.
+    pub conflicts: Vec<PathBuf>,
+}
+
+/// Record the files just extracted from `template_name` to `work_dir` and the vars they
+/// were rendered with as the project's template base
+pub fn save_base(
+    work_dir: &Path,
+    template_name: &str,
+    vars: &TemplateVars,
+    extracted: &[PathBuf],
+) -> Result<()> {
+    let mut files = Vec::new();
+    for path in extracted {
+        let relative = path.strip_prefix(work_dir)?;
+        files.push((relative.to_string_lossy().to_string(), std::fs::read(path)?));
+    }
+    write_base(work_dir, template_name, &vars.values(work_dir), files)
+}
+
+fn write_base(
+    work_dir: &Path,
+    template_name: &str,
+    values: &[(&str, String)],
+    files: Vec<(String, Vec<u8>)>,
+) -> Result<()> {
+    let base_dir = work_dir.join(TEMPLATE_BASE_DIR);
+    if base_dir.exists() {
+        std::fs::remove_dir_all(&base_dir)?;
+    }
+    let files_dir = base_dir.join(BASE_FILES_DIR);
+    for (path, content) in files {
+        write_file(&files_dir, &path, &content)?;
+    }
+    let vars: String = values
+        .iter()
+        .map(|(name, value)| format!("{}={}\n", name, value))
+        .collect();
+    write_file(&base_dir, BASE_VARS_FILE, vars.as_bytes())?;
+    write_file(&base_dir, BASE_TEMPLATE_FILE, template_name.as_bytes())?;
+    Ok(())
+}
+
+/// Vars the project was scaffolded with, None if it has no template base
+pub fn base_vars(work_dir: &Path) -> Result<Option<TemplateVars>> {
+    let path = work_dir.join(TEMPLATE_BASE_DIR).join(BASE_VARS_FILE);
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let mut vars = TemplateVars::default();
+    for (name, value) in content.lines().filter_map(|line| line.split_once('=')) {
+        let value = value.to_string();
+        match name {
+            "project_name" => vars.project_name = Some(value),
+            "description" => vars.description = Some(value),
+            "databricks_catalog" => vars.databricks_catalog = Some(value),
+            "databricks_schema" => vars.databricks_schema = Some(value),
+            "port" => vars.port = Some(value.parse()?),
+            _ => {}
+        }
+    }
+    Ok(Some(vars))
+}
+
+/// Name of the template the project was scaffolded from, None if it has no template base
+/// or the base was saved before the name was recorded
+pub fn base_template(work_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(work_dir.join(TEMPLATE_BASE_DIR).join(BASE_TEMPLATE_FILE)).ok()
+}
+
+/// Upgrade the project at `work_dir` to the current version of `template` with a
+/// three-way merge against its template base: changes made in the project are kept, the
+/// template's changes are applied on top and overlapping ones reported as conflicts.
+pub fn upgrade_project(work_dir: &Path, template: &impl Template) -> Result<UpgradeReport> {
+    let Some(vars) = base_vars(work_dir)? else {
+        return Err(eyre::eyre!(
+            "'{}' has no {} directory, only projects scaffolded with template tracking can be upgraded",
+            work_dir.display(),
+            TEMPLATE_BASE_DIR
+        ));
+    };
+    // bases saved before the template name was recorded can't be checked
+    if let Some(base_template) = base_template(work_dir).filter(|name| *name != template.name()) {
+        return Err(eyre::eyre!(
+            "'{}' was scaffolded from the '{}' template, not '{}'; upgrade it with the template it was scaffolded from",
+            work_dir.display(),
+            base_template,
+            template.name()
+        ));
+    }
+    let base_dir = work_dir.join(TEMPLATE_BASE_DIR).join(BASE_FILES_DIR);
+    let theirs: BTreeMap<String, Vec<u8>> = template
+        .rendered_files(work_dir, &vars)?
+        .into_iter()
+        .collect();
+    let mut base: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    // every file of the base, including dotfiles and ones the template's .gitignore lists
+    let walk = WalkBuilder::new(&base_dir).standard_filters(false).build();
+    for entry in walk.flatten() {
+        if entry.file_type().is_some_and(|ft| ft.is_file()) {
+            let path = entry.path().strip_prefix(&base_dir)?;
+            base.insert(
+                path.to_string_lossy().to_string(),
+                std::fs::read(entry.path())?,
+            );
+        }
+    }
+
+    let mut report = UpgradeReport::default();
+    let paths: BTreeSet<&String> = base.keys().chain(theirs.keys()).collect();
+    for path in paths {
+        let target = work_dir.join(path);
+        let ours = std::fs::read(&target).ok();
+        let (base, theirs) = (base.get(path), theirs.get(path));
+        if theirs == base || ours.as_ref() == theirs {
+            continue;
+        }
+        match (base, ours.as_ref(), theirs) {
+            (None, None, Some(theirs)) => report.added.push(write_file(work_dir, path, theirs)?),
+            (Some(base), Some(ours), None) if ours == base => {
+                std::fs::remove_file(&target)?;
+                report.removed.push(target);
+            }
+            (Some(base), Some(ours), Some(theirs)) if ours == base => {
+                report.updated.push(write_file(work_dir, path, theirs)?)
+            }
+            (base, Some(ours), Some(theirs)) => {
+                let base = base.map(Vec::as_slice).unwrap_or_default();
+                let text = [base, ours, theirs]
+                    .iter()
+                    .all(|content| std::str::from_utf8(content).is_ok());
+                match diffy::merge_bytes(base, ours, theirs) {
+                    Ok(merged) => report.merged.push(write_file(work_dir, path, &merged)?),
+                    Err(conflicted) if text => {
+                        report
+                            .conflicts
+                            .push(write_file(work_dir, path, &conflicted)?)
+                    }
+                    Err(_) => report.conflicts.push(target),
+                }
+            }
+            // deleted in the project but changed in the template, or the other way round
+            _ => report.conflicts.push(target),
+        }
+    }
+
+    write_base(
+        work_dir,
+        &template.name(),
+        &vars.values(work_dir),
+        theirs.into_iter().collect(),
+    )?;
+    Ok(report)
+}
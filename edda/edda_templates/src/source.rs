@@ -0,0 +1,250 @@
+use eyre::Result;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where a template's files come from. Written in config as a string:
+/// `embedded`, `git+<url>[#<rev>]`, `oci://<reference>` or a local directory path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateSource {
+    /// The tRPC template compiled into the binary
+    Embedded,
+    LocalDir(PathBuf),
+    /// Git repository at `rev` (branch, tag or commit), its default branch if none
+    GitRepo {
+        url: String,
+        rev: Option<String>,
+    },
+    /// OCI artifact whose files are the template, pulled with `oras`
+    OciArtifact {
+        reference: String,
+    },
+}
+
+const EMBEDDED: &str = "embedded";
+const GIT_PREFIX: &str = "git+";
+const OCI_PREFIX: &str = "oci://";
+
+impl TemplateSource {
+    /// Whether the files have to be fetched into a cache before use
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::GitRepo { .. } | Self::OciArtifact { .. })
+    }
+}
+
+impl FromStr for TemplateSource {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(eyre::eyre!("empty template source"));
+        }
+        if s == EMBEDDED {
+            return Ok(Self::Embedded);
+        }
+        if let Some(repo) = s.strip_prefix(GIT_PREFIX) {
+            let (url, rev) = match repo.rsplit_once('#') {
+                Some((url, rev)) => (url, Some(rev.to_string())),
+                None => (repo, None),
+            };
+            if url.is_empty() || rev.as_deref() == Some("") {
+                return Err(eyre::eyre!("invalid git template source '{}'", s));
+            }
+            return Ok(Self::GitRepo {
+                url: url.to_string(),
+                rev,
+            });
+        }
+        if let Some(reference) = s.strip_prefix(OCI_PREFIX) {
+            if reference.is_empty() {
+                return Err(eyre::eyre!("invalid OCI template source '{}'", s));
+            }
+            return Ok(Self::OciArtifact {
+                reference: reference.to_string(),
+            });
+        }
+        Ok(Self::LocalDir(PathBuf::from(s)))
+    }
+}
+
+impl fmt::Display for TemplateSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Embedded => write!(f, "{}", EMBEDDED),
+            Self::LocalDir(path) => write!(f, "{}", path.display()),
+            Self::GitRepo { url, rev: None } => write!(f, "{}{}", GIT_PREFIX, url),
+            Self::GitRepo {
+                url,
+                rev: Some(rev),
+            } => write!(f, "{}{}#{}", GIT_PREFIX, url, rev),
+            Self::OciArtifact { reference } => write!(f, "{}{}", OCI_PREFIX, reference),
+        }
+    }
+}
+
+/// Local copies of remote templates, one directory per source. A source is fetched
+/// once and reused until refreshed, so scaffolding works offline afterwards.
+pub struct TemplateCache {
+    dir: PathBuf,
+}
+
+impl TemplateCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory holding the copy of `source`
+    pub fn path(&self, source: &TemplateSource) -> PathBuf {
+        let key: String = source
+            .to_string()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        self.dir.join(key.trim_matches('-'))
+    }
+
+    /// Local directory with the template files of `source`. Local directories are used as
+    /// they are, remote sources are fetched unless cached or `refresh` is set.
+    pub fn fetch(&self, source: &TemplateSource, refresh: bool) -> Result<PathBuf> {
+        match source {
+            TemplateSource::Embedded => {
+                return Err(eyre::eyre!("the embedded template has no directory"));
+            }
+            TemplateSource::LocalDir(path) => return Ok(path.clone()),
+            TemplateSource::GitRepo { .. } | TemplateSource::OciArtifact { .. } => {}
+        }
+
+        let target = self.path(source);
+        if target.is_dir() && !refresh {
+            return Ok(target);
+        }
+
+        // fetched next to the target and moved in place once complete, unique per fetch
+        // so concurrent fetches of the same source don't write into each other
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let mut partial = target.clone().into_os_string();
+        partial.push(format!(".partial-{}-{}", std::process::id(), nanos));
+        let partial = PathBuf::from(partial);
+        std::fs::create_dir_all(&partial).map_err(|e| {
+            eyre::eyre!(
+                "failed to create template cache '{}': {}",
+                partial.display(),
+                e
+            )
+        })?;
+        let fetched = match source {
+            TemplateSource::GitRepo { url, rev } => fetch_git(url, rev.as_deref(), &partial),
+            TemplateSource::OciArtifact { reference } => fetch_oci(reference, &partial),
+            TemplateSource::Embedded | TemplateSource::LocalDir(_) => {
+                unreachable!("only remote sources are fetched")
+            }
+        };
+        if let Err(e) = fetched {
+            let _ = std::fs::remove_dir_all(&partial);
+            return Err(e.wrap_err(format!("failed to fetch template {}", source)));
+        }
+
+        if refresh && target.exists() {
+            std::fs::remove_dir_all(&target)?;
+        }
+        if let Err(e) = std::fs::rename(&partial, &target) {
+            let _ = std::fs::remove_dir_all(&partial);
+            // a concurrent fetch of the same source got there first
+            if !target.is_dir() {
+                return Err(e.into());
+            }
+        }
+        Ok(target)
+    }
+}
+
+fn fetch_git(url: &str, rev: Option<&str>, dir: &Path) -> Result<()> {
+    run(Command::new("git").arg("init").arg("--quiet").arg(dir))?;
+    let git = || {
+        let mut command = Command::new("git");
+        command.arg("-C").arg(dir);
+        command
+    };
+    // fetching the rev directly works for commits as well as branches and tags
+    run(git().args([
+        "fetch",
+        "--quiet",
+        "--depth",
+        "1",
+        url,
+        rev.unwrap_or("HEAD"),
+    ]))?;
+    run(git().args(["checkout", "--quiet", "--detach", "FETCH_HEAD"]))?;
+    // the template is the files, not their history
+    std::fs::remove_dir_all(dir.join(".git"))?;
+    Ok(())
+}
+
+fn fetch_oci(reference: &str, dir: &Path) -> Result<()> {
+    run(Command::new("oras")
+        .args(["pull", reference, "--output"])
+        .arg(dir))
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command
+        .output()
+        .map_err(|e| eyre::eyre!("failed to run {} (is it installed?): {}", program, e))?;
+    if !output.status.success() {
+        return Err(eyre::eyre!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_source_parse() {
+        let parse = |s: &str| s.parse::<TemplateSource>().unwrap();
+        assert_eq!(parse("embedded"), TemplateSource::Embedded);
+        assert_eq!(
+            parse("/opt/templates/dashboard"),
+            TemplateSource::LocalDir("/opt/templates/dashboard".into())
+        );
+        assert_eq!(
+            parse("git+https://github.com/acme/templates.git#v2"),
+            TemplateSource::GitRepo {
+                url: "https://github.com/acme/templates.git".to_string(),
+                rev: Some("v2".to_string()),
+            }
+        );
+        assert_eq!(
+            parse("oci://ghcr.io/acme/dashboard:1.0"),
+            TemplateSource::OciArtifact {
+                reference: "ghcr.io/acme/dashboard:1.0".to_string(),
+            }
+        );
+        for source in [
+            "git+https://github.com/acme/templates.git#v2",
+            "oci://ghcr.io/acme/dashboard:1.0",
+        ] {
+            assert_eq!(parse(source).to_string(), source);
+        }
+        assert!("git+".parse::<TemplateSource>().is_err());
+        assert!("oci://".parse::<TemplateSource>().is_err());
+    }
+}
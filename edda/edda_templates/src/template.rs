@@ -1,14 +1,212 @@
+use crate::capabilities::{MANIFEST_FILE, TemplateCapabilities};
+use edda_sandbox::Sandbox;
 use eyre::Result;
 use rust_embed::RustEmbed;
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 
+/// Port the app listens on unless another is given when scaffolding
+pub const DEFAULT_PORT: u16 = 8000;
+
 pub trait Template: TemplateCore {
     fn name(&self) -> String;
+
+    /// Template specific checks of a scaffold extracted to /app of `sandbox`, on top of
+    /// its validation. Nothing beyond validation by default.
+    fn smoke_test<S: Sandbox + Send>(
+        &self,
+        sandbox: &mut S,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let _ = sandbox;
+        async { Ok(()) }
+    }
+}
+
+/// Start the app of a scaffold at /app with `start` and wait for `check` to succeed, the
+/// smoke test of the embedded templates. Fails with the app's output if it never does.
+pub async fn check_app_starts<S: Sandbox + Send>(
+    sandbox: &mut S,
+    start: &str,
+    check: &str,
+) -> Result<()> {
+    // the app only lives as long as this one shell
+    let command = format!(
+        "cd /app && ({}) > /tmp/app.log 2>&1 & \
+         for _ in $(seq 60); do if {} > /dev/null 2>&1; then exit 0; fi; sleep 1; done; \
+         cat /tmp/app.log; exit 1",
+        start, check
+    );
+    let result = sandbox.exec(&command).await?;
+    if result.exit_code != 0 {
+        eyre::bail!(
+            "app didn't answer `{}` within 60s:\n{}{}",
+            check,
+            result.stdout,
+            result.stderr
+        );
+    }
+    Ok(())
+}
+
+/// Run a setup step of a smoke test, e.g. installing dependencies
+pub async fn run_step<S: Sandbox + Send>(sandbox: &mut S, command: &str) -> Result<()> {
+    let result = sandbox.exec(&format!("cd /app && {}", command)).await?;
+    if result.exit_code != 0 {
+        eyre::bail!(
+            "`{}` failed (exit code {}):\n{}{}",
+            command,
+            result.exit_code,
+            result.stdout,
+            result.stderr
+        );
+    }
+    Ok(())
 }
 
 pub trait TemplateCore {
     fn description(&self) -> Option<String>;
-    fn extract(&self, work_dir: &Path) -> Result<Vec<PathBuf>>;
+    /// Template files as (relative path, content) sorted by path, placeholders unfilled
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>>;
+
+    /// Capability manifest shipped with the template, the defaults if it has none
+    fn capabilities(&self) -> Result<TemplateCapabilities> {
+        match self
+            .files()?
+            .into_iter()
+            .find(|(path, _)| path == MANIFEST_FILE)
+        {
+            Some((_, content)) => TemplateCapabilities::parse(&content),
+            None => Ok(TemplateCapabilities::default()),
+        }
+    }
+
+    /// Template files with `vars` filled into their placeholders for a project at `work_dir`
+    fn rendered_files(
+        &self,
+        work_dir: &Path,
+        vars: &TemplateVars,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        let values = vars.values(work_dir);
+        let files = self.files()?.into_iter().map(|(path, content)| {
+            let content = render(&content, &values).into_owned();
+            (path, content)
+        });
+        Ok(files.collect())
+    }
+
+    /// Write the template files to `work_dir` with `vars` filled into their placeholders
+    fn extract(&self, work_dir: &Path, vars: &TemplateVars) -> Result<Vec<PathBuf>> {
+        let mut extracted = Vec::new();
+        for (path, content) in self.rendered_files(work_dir, vars)? {
+            extracted.push(write_file(work_dir, &path, &content)?);
+        }
+        Ok(extracted)
+    }
+}
+
+/// Values for the `{{name}}` placeholders in template files, filled in when extracting so
+/// a scaffold starts out with the project's names instead of the template's
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    /// Project name, the work directory name if unset. Written as an npm package name.
+    pub project_name: Option<String>,
+    /// One line description of the app
+    pub description: Option<String>,
+    /// Unity Catalog catalog holding the app's data (default: main)
+    pub databricks_catalog: Option<String>,
+    /// Schema within the catalog (default: default)
+    pub databricks_schema: Option<String>,
+    pub port: Option<u16>,
+}
+
+impl TemplateVars {
+    /// Check that catalog and schema are plain identifiers, they're written into SQL
+    /// and config files unquoted
+    pub fn validate(&self) -> Result<()> {
+        let names = [
+            ("databricks_catalog", &self.databricks_catalog),
+            ("databricks_schema", &self.databricks_schema),
+        ];
+        for (name, value) in names {
+            if let Some(value) = value
+                && !is_identifier(value)
+            {
+                eyre::bail!(
+                    "{} must contain only letters, digits and underscores, got '{}'",
+                    name,
+                    value
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Placeholder names with their values for a project extracted to `work_dir`
+    pub fn values(&self, work_dir: &Path) -> Vec<(&'static str, String)> {
+        let project_name = self
+            .project_name
+            .clone()
+            .or_else(|| {
+                work_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .unwrap_or_default();
+        let description = self.description.as_deref().map(plain_text);
+        let catalog = self.databricks_catalog.as_deref().unwrap_or("main");
+        let schema = self.databricks_schema.as_deref().unwrap_or("default");
+        vec![
+            ("project_name", package_name(&project_name)),
+            ("description", description.unwrap_or_default()),
+            ("databricks_catalog", catalog.to_string()),
+            ("databricks_schema", schema.to_string()),
+            ("port", self.port.unwrap_or(DEFAULT_PORT).to_string()),
+        ]
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Lowercase letters, digits and dashes, valid as an npm package name
+fn package_name(name: &str) -> String {
+    let mut package = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            package.push(c.to_ascii_lowercase());
+        } else if !package.is_empty() && !package.ends_with('-') {
+            package.push('-');
+        }
+    }
+    match package.trim_end_matches('-') {
+        "" => "app".to_string(),
+        package => package.to_string(),
+    }
+}
+
+/// One line without characters that would break the JSON and HTML it's written to
+fn plain_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(['"', '\\', '<', '>'], "")
+}
+
+/// Fill `values` into the `{{name}}` placeholders of a text file, other files are
+/// returned unchanged
+pub fn render<'a>(content: &'a [u8], values: &[(&str, String)]) -> Cow<'a, [u8]> {
+    let Ok(text) = std::str::from_utf8(content) else {
+        return Cow::Borrowed(content);
+    };
+    if !text.contains("{{") {
+        return Cow::Borrowed(content);
+    }
+    let mut rendered = text.to_string();
+    for (name, value) in values {
+        rendered = rendered.replace(&format!("{{{{{}}}}}", name), value);
+    }
+    Cow::Owned(rendered.into_bytes())
 }
 
 impl<T: RustEmbed> TemplateCore for T {
@@ -16,21 +214,15 @@ impl<T: RustEmbed> TemplateCore for T {
         Self::get("CLAUDE.md").map(|file| String::from_utf8_lossy(&file.data).to_string())
     }
 
-    fn extract(&self, work_dir: &Path) -> Result<Vec<PathBuf>> {
+    fn files(&self) -> Result<Vec<(String, Vec<u8>)>> {
         let mut files = Vec::new();
         for path in Self::iter().filter(|p| !p.is_empty()) {
             if let Some(file) = Self::get(path.as_ref()) {
-                files.push((path.to_string(), file.data.to_owned()));
+                files.push((path.to_string(), file.data.to_vec()));
             }
         }
         files.sort_by(|a, b| a.0.cmp(&b.0));
-
-        let mut extracted = Vec::new();
-        for (path, content) in files {
-            let written_path = write_file(work_dir, &path, &content)?;
-            extracted.push(written_path);
-        }
-        Ok(extracted)
+        Ok(files)
     }
 }
 
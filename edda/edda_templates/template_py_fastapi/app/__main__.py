@@ -0,0 +1,7 @@
+import os
+
+import uvicorn
+
+# Databricks Apps pass the port in DATABRICKS_APP_PORT
+port = int(os.environ.get("DATABRICKS_APP_PORT") or os.environ.get("PORT") or "{{port}}")
+uvicorn.run("app.main:app", host="0.0.0.0", port=port)
@@ -0,0 +1,31 @@
+from pathlib import Path
+
+from fastapi import FastAPI
+from fastapi.responses import FileResponse
+from fastapi.staticfiles import StaticFiles
+from pydantic import BaseModel
+
+from app import db
+
+STATIC_DIR = Path(__file__).resolve().parent.parent / "static"
+
+app = FastAPI(title="{{project_name}}", description="{{description}}")
+
+
+class Health(BaseModel):
+    status: str
+    catalog: str
+    schema_name: str
+
+
+@app.get("/api/health")
+def health() -> Health:
+    return Health(status="ok", catalog=db.CATALOG, schema_name=db.SCHEMA)
+
+
+@app.get("/", include_in_schema=False)
+def index() -> FileResponse:
+    return FileResponse(STATIC_DIR / "index.html")
+
+
+app.mount("/static", StaticFiles(directory=STATIC_DIR), name="static")
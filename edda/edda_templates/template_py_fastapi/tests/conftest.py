@@ -0,0 +1,5 @@
+import sys
+from pathlib import Path
+
+# tests import the app package the way `python -m app` does, from the project root
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
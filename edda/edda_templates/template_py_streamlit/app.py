@@ -0,0 +1,10 @@
+import streamlit as st
+
+import db
+
+st.set_page_config(page_title="{{project_name}}", layout="wide")
+
+st.title("{{project_name}}")
+st.caption("{{description}}")
+
+st.write(f"Data from `{db.CATALOG}.{db.SCHEMA}`")
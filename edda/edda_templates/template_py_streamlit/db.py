@@ -0,0 +1,46 @@
+"""Databricks SQL access for the app.
+
+Usage:
+
+    from db import table, query
+
+    df = query(f"SELECT * FROM {table('orders')} WHERE region = :region", {"region": "EMEA"})
+
+Credentials come from DATABRICKS_HOST and DATABRICKS_TOKEN locally, and from the app's
+service principal when deployed as a Databricks App.
+"""
+
+import os
+
+import pandas as pd
+from databricks import sql
+from databricks.sdk.core import Config
+
+# Unity Catalog location of the app's tables, e.g. f"{CATALOG}.{SCHEMA}.orders"
+CATALOG = os.environ.get("DATABRICKS_CATALOG") or "{{databricks_catalog}}"
+SCHEMA = os.environ.get("DATABRICKS_SCHEMA") or "{{databricks_schema}}"
+
+
+def table(name: str) -> str:
+    """Fully qualified name of a table in the app's catalog and schema."""
+    return f"{CATALOG}.{SCHEMA}.{name}"
+
+
+def connect():
+    config = Config()
+    warehouse_id = os.environ["DATABRICKS_WAREHOUSE_ID"]
+    return sql.connect(
+        server_hostname=config.host,
+        http_path=f"/sql/1.0/warehouses/{warehouse_id}",
+        credentials_provider=lambda: config.authenticate,
+    )
+
+
+def query(statement: str, parameters: dict | None = None) -> pd.DataFrame:
+    """Run a query and return its rows as a DataFrame.
+
+    Pass values as named parameters (`:name` in the statement), never format them in.
+    """
+    with connect() as connection, connection.cursor() as cursor:
+        cursor.execute(statement, parameters)
+        return cursor.fetchall_arrow().to_pandas()
@@ -0,0 +1,5 @@
+import sys
+from pathlib import Path
+
+# tests import the app's modules the way `streamlit run` does, from the project root
+sys.path.insert(0, str(Path(__file__).resolve().parent.parent))
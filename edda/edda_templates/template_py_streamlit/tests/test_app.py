@@ -0,0 +1,7 @@
+from streamlit.testing.v1 import AppTest
+
+
+def test_app_renders():
+    app = AppTest.from_file("../app.py").run()
+    assert not app.exception
+    assert app.title
@@ -0,0 +1,53 @@
+use std::env;
+
+/// Settings read from the environment, set by app.yaml on Databricks Apps and by .env or
+/// the shell locally
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub port: u16,
+    /// Workspace URL, e.g. https://my-workspace.cloud.databricks.com
+    pub databricks_host: String,
+    pub warehouse_id: String,
+    /// Personal access token for local development
+    pub token: Option<String>,
+    /// Service principal of the deployed app
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// Unity Catalog location of the app's tables
+    pub catalog: String,
+    pub schema: String,
+    pub database_url: Option<String>,
+}
+
+fn var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        let host = var("DATABRICKS_HOST").unwrap_or_default();
+        let host = match host.starts_with("http") || host.is_empty() {
+            true => host,
+            false => format!("https://{}", host),
+        };
+        Self {
+            port: var("DATABRICKS_APP_PORT")
+                .or_else(|| var("PORT"))
+                .and_then(|port| port.parse().ok())
+                .unwrap_or({{port}}),
+            databricks_host: host.trim_end_matches('/').to_string(),
+            warehouse_id: var("DATABRICKS_WAREHOUSE_ID").unwrap_or_default(),
+            token: var("DATABRICKS_TOKEN"),
+            client_id: var("DATABRICKS_CLIENT_ID"),
+            client_secret: var("DATABRICKS_CLIENT_SECRET"),
+            catalog: var("DATABRICKS_CATALOG").unwrap_or_else(|| "{{databricks_catalog}}".into()),
+            schema: var("DATABRICKS_SCHEMA").unwrap_or_else(|| "{{databricks_schema}}".into()),
+            database_url: var("DATABASE_URL"),
+        }
+    }
+
+    /// Fully qualified name of a table in the app's catalog and schema
+    pub fn table(&self, name: &str) -> String {
+        format!("{}.{}.{}", self.catalog, self.schema, name)
+    }
+}
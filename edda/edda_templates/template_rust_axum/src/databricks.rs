@@ -0,0 +1,209 @@
+//! Databricks SQL through the Statement Execution API of the app's warehouse.
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct Order { id: i64, region: String }
+//!
+//! let orders: Vec<Order> = state
+//!     .databricks
+//!     .query(
+//!         &format!("SELECT id, region FROM {} WHERE region = :region", state.config.table("orders")),
+//!         &[("region", "EMEA")],
+//!     )
+//!     .await?;
+//! ```
+
+use crate::{AppError, Config};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Clone)]
+pub struct DatabricksClient {
+    http: reqwest::Client,
+    config: Config,
+    /// OAuth token of the app's service principal with its expiry
+    oauth_token: Arc<Mutex<Option<(String, std::time::Instant)>>>,
+}
+
+#[derive(Deserialize)]
+struct StatementResponse {
+    status: StatementStatus,
+    manifest: Option<Manifest>,
+    result: Option<StatementResult>,
+}
+
+#[derive(Deserialize)]
+struct StatementStatus {
+    state: String,
+    error: Option<StatementError>,
+}
+
+#[derive(Deserialize)]
+struct StatementError {
+    message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    schema: ManifestSchema,
+}
+
+#[derive(Deserialize)]
+struct ManifestSchema {
+    columns: Vec<Column>,
+}
+
+#[derive(Deserialize)]
+struct Column {
+    name: String,
+    type_name: String,
+}
+
+#[derive(Deserialize)]
+struct StatementResult {
+    #[serde(default)]
+    data_array: Vec<Vec<Option<String>>>,
+}
+
+#[derive(Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl DatabricksClient {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config: config.clone(),
+            oauth_token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Run `statement` and deserialize its rows, columns by name. Pass values as named
+    /// parameters (`:name` in the statement), never format them in.
+    pub async fn query<T: DeserializeOwned>(
+        &self,
+        statement: &str,
+        parameters: &[(&str, &str)],
+    ) -> Result<Vec<T>, AppError> {
+        let rows = self.query_rows(statement, parameters).await?;
+        rows.into_iter()
+            .map(|row| serde_json::from_value(Value::Object(row)))
+            .collect::<Result<_, _>>()
+            .map_err(|e| AppError::Databricks(format!("unexpected row shape: {}", e)))
+    }
+
+    /// Rows as JSON objects keyed by column name
+    pub async fn query_rows(
+        &self,
+        statement: &str,
+        parameters: &[(&str, &str)],
+    ) -> Result<Vec<Map<String, Value>>, AppError> {
+        let parameters: Vec<Value> = parameters
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "value": value }))
+            .collect();
+        let body = json!({
+            "warehouse_id": self.config.warehouse_id,
+            "statement": statement,
+            "parameters": parameters,
+            "wait_timeout": "50s",
+            "disposition": "INLINE",
+            "format": "JSON_ARRAY",
+        });
+        let response: StatementResponse = self
+            .http
+            .post(format!(
+                "{}/api/2.0/sql/statements",
+                self.config.databricks_host
+            ))
+            .bearer_auth(self.token().await?)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if response.status.state != "SUCCEEDED" {
+            let message = response
+                .status
+                .error
+                .and_then(|error| error.message)
+                .unwrap_or_else(|| format!("statement {}", response.status.state));
+            return Err(AppError::Databricks(message));
+        }
+        let columns = response
+            .manifest
+            .map(|manifest| manifest.schema.columns)
+            .unwrap_or_default();
+        let data = response
+            .result
+            .map(|result| result.data_array)
+            .unwrap_or_default();
+        Ok(data
+            .into_iter()
+            .map(|values| {
+                columns
+                    .iter()
+                    .zip(values)
+                    .map(|(column, value)| (column.name.clone(), typed(&column.type_name, value)))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Personal access token locally, OAuth machine-to-machine token when deployed
+    async fn token(&self) -> Result<String, AppError> {
+        if let Some(token) = &self.config.token {
+            return Ok(token.clone());
+        }
+        let (Some(client_id), Some(client_secret)) =
+            (&self.config.client_id, &self.config.client_secret)
+        else {
+            return Err(AppError::Databricks(
+                "set DATABRICKS_TOKEN, or DATABRICKS_CLIENT_ID and DATABRICKS_CLIENT_SECRET".into(),
+            ));
+        };
+
+        let mut cached = self.oauth_token.lock().await;
+        if let Some((token, expires)) = cached.as_ref()
+            && *expires > std::time::Instant::now()
+        {
+            return Ok(token.clone());
+        }
+        let token: OAuthToken = self
+            .http
+            .post(format!("{}/oidc/v1/token", self.config.databricks_host))
+            .basic_auth(client_id, Some(client_secret))
+            .form(&[("grant_type", "client_credentials"), ("scope", "all-apis")])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        // refreshed a minute early so a query never starts with an expiring token
+        let expires = std::time::Instant::now()
+            + std::time::Duration::from_secs(token.expires_in.saturating_sub(60));
+        *cached = Some((token.access_token.clone(), expires));
+        Ok(token.access_token)
+    }
+}
+
+/// JSON_ARRAY results are strings, numbers and booleans are turned back into JSON ones
+fn typed(type_name: &str, value: Option<String>) -> Value {
+    let Some(value) = value else {
+        return Value::Null;
+    };
+    let parsed = match type_name {
+        "BOOLEAN" => value.parse().ok().map(Value::Bool),
+        "BYTE" | "SHORT" | "INT" | "LONG" => value.parse::<i64>().ok().map(Value::from),
+        "FLOAT" | "DOUBLE" | "DECIMAL" => value.parse::<f64>().ok().map(Value::from),
+        _ => None,
+    };
+    parsed.unwrap_or(Value::String(value))
+}
@@ -0,0 +1,10 @@
+//! Postgres access with SQLx, e.g. for a Lakebase database holding the app's own state.
+//! Query with `sqlx::query_as` at runtime, the `query!` macros need a database at build
+//! time.
+
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+
+pub async fn connect(url: &str) -> Result<PgPool, sqlx::Error> {
+    PgPoolOptions::new().max_connections(5).connect(url).await
+}
@@ -0,0 +1,47 @@
+//! Data app backend: JSON API under /api, the frontend from static/ everywhere else.
+
+pub mod config;
+pub mod databricks;
+pub mod db;
+pub mod error;
+pub mod routes;
+
+pub use config::Config;
+pub use error::AppError;
+
+use axum::Router;
+use databricks::DatabricksClient;
+use sqlx::PgPool;
+use tower_http::services::{ServeDir, ServeFile};
+use tower_http::trace::TraceLayer;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Config,
+    pub databricks: DatabricksClient,
+    /// Postgres (e.g. Lakebase) pool, only when DATABASE_URL is set
+    pub db: Option<PgPool>,
+}
+
+impl AppState {
+    pub async fn connect(config: Config) -> Result<Self, AppError> {
+        let db = match &config.database_url {
+            Some(url) => Some(db::connect(url).await?),
+            None => None,
+        };
+        Ok(Self {
+            databricks: DatabricksClient::new(&config),
+            config,
+            db,
+        })
+    }
+}
+
+pub fn app(state: AppState) -> Router {
+    let frontend = ServeDir::new("static").not_found_service(ServeFile::new("static/index.html"));
+    Router::new()
+        .nest("/api", routes::router())
+        .fallback_service(frontend)
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
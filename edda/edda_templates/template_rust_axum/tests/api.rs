@@ -0,0 +1,38 @@
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use data_app::{AppState, Config, app};
+use http_body_util::BodyExt;
+use tower::ServiceExt;
+
+async fn test_app() -> axum::Router {
+    // no DATABASE_URL and no Databricks credentials are needed to build the app
+    let config = Config {
+        database_url: None,
+        ..Config::from_env()
+    };
+    app(AppState::connect(config).await.unwrap())
+}
+
+#[tokio::test]
+async fn test_health() {
+    let response = test_app()
+        .await
+        .oneshot(Request::get("/api/health").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let health: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(health["status"], "ok");
+}
+
+#[tokio::test]
+async fn test_frontend() {
+    let response = test_app()
+        .await
+        .oneshot(Request::get("/").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -33,6 +33,13 @@ const clientId: string = process.env["DATABRICKS_CLIENT_ID"] || "";
 const clientSecret: string = process.env["DATABRICKS_CLIENT_SECRET"] || "";
 const httpPath = `/sql/1.0/warehouses/${warehouseId}`;
 
+// Unity Catalog location of the app's tables,
+// e.g. `${databricksCatalog}.${databricksSchema}.orders`
+export const databricksCatalog: string =
+  process.env["DATABRICKS_CATALOG"] || "{{databricks_catalog}}";
+export const databricksSchema: string =
+  process.env["DATABRICKS_SCHEMA"] || "{{databricks_schema}}";
+
 // Default schema for untyped queries - accepts any valid SQL value
 export const sqlValueSchema = z.union([
   z.string(),
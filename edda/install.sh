@@ -273,7 +273,8 @@ download_and_install() {
     echo ""
     echo "${BOLD}Running environment check...${RESET}"
     echo ""
-    if "$_install_path" check; then
+    # --fix --yes pre-pulls the sandbox images, credentials are left for the user
+    if "$_install_path" doctor --fix --yes; then
         echo ""
         success "Environment validation passed"
     else
@@ -289,9 +290,10 @@ download_and_install() {
     echo "  ${CYAN}~/.edda/.env.example${RESET}"
     echo ""
     echo "  To configure credentials:"
-    echo "  1. ${DIM}cp ~/.edda/.env.example ~/.edda/.env${RESET}"
-    echo "  2. Edit ${DIM}~/.edda/.env${RESET} and add your credentials"
-    echo "  3. Run ${DIM}edda_mcp check${RESET} to validate"
+    echo "  1. Edit ${DIM}~/.edda/.env${RESET} and add your credentials"
+    echo "  2. Run ${DIM}edda_mcp doctor${RESET} to validate"
+    echo ""
+    echo "  Or let ${DIM}edda_mcp doctor --fix${RESET} prompt for them."
     echo ""
     echo "  Found a bug? Please report via: ${DIM}edda_mcp yell${RESET}"
     echo ""